@@ -43,6 +43,23 @@ gen_random_ints!(gen_10, 10);
 gen_random_ints!(gen_100, 100);
 gen_random_ints!(gen_1000, 1000);
 
+/// Generates runs of 8 to 64 repeated random values, which are RLE encoded by
+/// dictionary encoder.
+pub fn gen_runs(total: usize) -> (usize, Vec<i32>) {
+  let mut values = Vec::with_capacity(total);
+  let mut rng = thread_rng();
+  while values.len() < total {
+    let value = rng.gen_range::<i32>(0, 1000);
+    let run_length = rng.gen_range::<usize>(8, 65);
+    for _ in 0..run_length {
+      values.push(value);
+    }
+  }
+  values.truncate(total);
+  let bytes = values.len() * ::std::mem::size_of::<i32>();
+  (bytes, values)
+}
+
 pub fn gen_test_strs(total: usize) -> (usize, Vec<ByteArray>) {
   let mut words = Vec::new();
   words.push("aaaaaaaaaa");
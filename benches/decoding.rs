@@ -27,6 +27,7 @@ mod common;
 use common::*;
 
 use std::rc::Rc;
+use std::sync::Arc;
 
 use parquet::basic::*;
 use parquet::data_type::*;
@@ -39,7 +40,7 @@ macro_rules! plain {
    $gen_data_fn:expr) => {
     #[bench]
     fn $fname(bench: &mut Bencher) {
-      let mem_tracker = Rc::new(MemTracker::new());
+      let mem_tracker = Arc::new(MemTracker::new());
       let mut encoder = PlainEncoder::<$ty>::new(
         Rc::new(col_desc(0, $pty)), mem_tracker, vec![]);
 
@@ -58,9 +59,9 @@ macro_rules! dict {
    $gen_data_fn:expr) => {
     #[bench]
     fn $fname(bench: &mut Bencher) {
-      let mem_tracker = Rc::new(MemTracker::new());
+      let mem_tracker = Arc::new(MemTracker::new());
       let mut encoder = DictEncoder::<$ty>::new(
-        Rc::new(col_desc(0, $pty)), mem_tracker);
+        Rc::new(col_desc(0, $pty)), mem_tracker.clone());
 
       let (_, values) = $gen_data_fn($num_values);
       encoder.put(&values[..]).expect("put() should be OK");
@@ -70,7 +71,7 @@ macro_rules! dict {
         encoder.num_entries()).expect("set_data() should be OK");
 
       let buffer = encoder.flush_buffer().expect("flush_buffer() should be OK");
-      let mut decoder = DictDecoder::<$ty>::new();
+      let mut decoder = DictDecoder::<$ty>::new(mem_tracker);
       decoder.set_dict(Box::new(dict_decoder)).expect("set_dict() should be OK");
 
       bench_decoding(bench, $num_values, $batch_size, buffer, Box::new(decoder));
@@ -128,6 +129,9 @@ dict!(dict_i32_1m_32, 1024 * 1024, 32, Int32Type, Type::INT32, gen_1000);
 dict!(dict_i32_1m_64, 1024 * 1024, 64, Int32Type, Type::INT32, gen_1000);
 dict!(dict_i32_1m_128, 1024 * 1024, 128, Int32Type, Type::INT32, gen_1000);
 dict!(dict_str_1m_128, 1024 * 1024, 128, ByteArrayType, Type::BYTE_ARRAY, gen_test_strs);
+dict!(dict_i32_1m_1024, 1024 * 1024, 1024, Int32Type, Type::INT32, gen_1000);
+dict!(dict_i32_runs_1m_128, 1024 * 1024, 128, Int32Type, Type::INT32, gen_runs);
+dict!(dict_i32_runs_1m_1024, 1024 * 1024, 1024, Int32Type, Type::INT32, gen_runs);
 
 delta_bit_pack!(delta_bit_pack_i32_1k_32, 1024, 32, Int32Type, gen_1000);
 delta_bit_pack!(delta_bit_pack_i32_1k_64, 1024, 64, Int32Type, gen_1000);
@@ -27,6 +27,7 @@ mod common;
 use common::*;
 
 use std::rc::Rc;
+use std::sync::Arc;
 
 use parquet::basic::*;
 use parquet::data_type::*;
@@ -37,7 +38,7 @@ macro_rules! plain {
   ($fname:ident, $batch_size:expr, $ty:ident, $pty:expr, $gen_data_fn:expr) => {
     #[bench]
     fn $fname(bench: &mut Bencher) {
-      let mem_tracker = Rc::new(MemTracker::new());
+      let mem_tracker = Arc::new(MemTracker::new());
       let encoder = PlainEncoder::<$ty>::new(
         Rc::new(col_desc(0, $pty)), mem_tracker, vec![]);
       let (bytes, values) = $gen_data_fn($batch_size);
@@ -50,7 +51,7 @@ macro_rules! dict {
   ($fname:ident, $batch_size:expr, $ty:ident, $pty:expr, $gen_data_fn:expr) => {
     #[bench]
     fn $fname(bench: &mut Bencher) {
-      let mem_tracker = Rc::new(MemTracker::new());
+      let mem_tracker = Arc::new(MemTracker::new());
       let encoder = DictEncoder::<$ty>::new(
         Rc::new(col_desc(0, $pty)), mem_tracker);
       let (bytes, values) = $gen_data_fn($batch_size);
@@ -0,0 +1,88 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![feature(test)]
+extern crate parquet;
+extern crate test;
+use test::Bencher;
+
+use parquet::basic::*;
+use parquet::bench_util::*;
+use parquet::data_type::*;
+
+const NUM_VALUES: usize = 64 * 1024;
+const NUM_DISTINCT: usize = 1000;
+const BATCH_SIZE: usize = 1024;
+const SEED: u64 = 42;
+
+macro_rules! page_decoding {
+  ($fname:ident, $ty:ident, $pty:expr, $type_length:expr, $encoding:expr) => {
+    page_decoding!($fname, $ty, $pty, $type_length, $encoding, NUM_VALUES);
+  };
+  ($fname:ident, $ty:ident, $pty:expr, $type_length:expr, $encoding:expr,
+   $num_values:expr) => {
+    #[bench]
+    fn $fname(bench: &mut Bencher) {
+      let descr = column_descr($pty, $type_length);
+      let pages =
+        gen_pages::<$ty>(descr.clone(), $encoding, $num_values, NUM_DISTINCT, SEED)
+          .expect("gen_pages() should be OK");
+      bench.bytes = pages.iter().map(|page| page.buffer().len() as u64).sum();
+      bench.iter(|| {
+        decode_pages::<$ty>(descr.clone(), &pages, BATCH_SIZE)
+          .expect("decode_pages() should be OK")
+      })
+    }
+  }
+}
+
+page_decoding!(plain_bool, BoolType, Type::BOOLEAN, 0, Encoding::PLAIN);
+// RLE encoder of values has a fixed size buffer
+page_decoding!(rle_bool, BoolType, Type::BOOLEAN, 0, Encoding::RLE, 4 * 1024);
+
+page_decoding!(plain_i32, Int32Type, Type::INT32, 0, Encoding::PLAIN);
+page_decoding!(dict_i32, Int32Type, Type::INT32, 0, Encoding::RLE_DICTIONARY);
+page_decoding!(delta_bit_pack_i32, Int32Type, Type::INT32, 0,
+  Encoding::DELTA_BINARY_PACKED);
+
+page_decoding!(plain_i64, Int64Type, Type::INT64, 0, Encoding::PLAIN);
+page_decoding!(dict_i64, Int64Type, Type::INT64, 0, Encoding::RLE_DICTIONARY);
+page_decoding!(delta_bit_pack_i64, Int64Type, Type::INT64, 0,
+  Encoding::DELTA_BINARY_PACKED);
+
+page_decoding!(plain_i96, Int96Type, Type::INT96, 0, Encoding::PLAIN);
+page_decoding!(dict_i96, Int96Type, Type::INT96, 0, Encoding::RLE_DICTIONARY);
+
+page_decoding!(plain_float, FloatType, Type::FLOAT, 0, Encoding::PLAIN);
+page_decoding!(dict_float, FloatType, Type::FLOAT, 0, Encoding::RLE_DICTIONARY);
+
+page_decoding!(plain_double, DoubleType, Type::DOUBLE, 0, Encoding::PLAIN);
+page_decoding!(dict_double, DoubleType, Type::DOUBLE, 0, Encoding::RLE_DICTIONARY);
+
+page_decoding!(plain_str, ByteArrayType, Type::BYTE_ARRAY, 0, Encoding::PLAIN);
+page_decoding!(dict_str, ByteArrayType, Type::BYTE_ARRAY, 0, Encoding::RLE_DICTIONARY);
+page_decoding!(delta_length_str, ByteArrayType, Type::BYTE_ARRAY, 0,
+  Encoding::DELTA_LENGTH_BYTE_ARRAY);
+page_decoding!(delta_str, ByteArrayType, Type::BYTE_ARRAY, 0,
+  Encoding::DELTA_BYTE_ARRAY);
+
+page_decoding!(plain_fixed_len, FixedLenByteArrayType, Type::FIXED_LEN_BYTE_ARRAY, 16,
+  Encoding::PLAIN);
+page_decoding!(dict_fixed_len, FixedLenByteArrayType, Type::FIXED_LEN_BYTE_ARRAY, 16,
+  Encoding::RLE_DICTIONARY);
+page_decoding!(delta_fixed_len, FixedLenByteArrayType, Type::FIXED_LEN_BYTE_ARRAY, 16,
+  Encoding::DELTA_BYTE_ARRAY);
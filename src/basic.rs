@@ -18,6 +18,8 @@
 //! Contains Rust mappings for Thrift definition.
 //! Refer to `parquet.thrift` file to see raw definitions.
 
+use std::cell::RefCell;
+use std::collections::HashSet;
 use std::convert;
 use std::fmt;
 use std::result;
@@ -25,6 +27,8 @@ use std::str;
 
 use errors::ParquetError;
 use parquet_format as parquet;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 // ----------------------------------------------------------------------
 // Types from the Thrift definition
@@ -38,6 +42,7 @@ use parquet_format as parquet;
 /// For example INT16 is not included as a type since a good encoding of INT32
 /// would handle this.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Type {
   BOOLEAN,
   INT32,
@@ -98,6 +103,15 @@ pub enum LogicalType {
   /// physical type.
   TIME_MICROS,
 
+  /// Time of day recorded as the number of `unit`s since midnight, stored as INT32
+  /// physical type for milliseconds and as INT64 physical type otherwise.
+  ///
+  /// Times adjusted to UTC in milliseconds and microseconds are the same as
+  /// `TIME_MILLIS` and `TIME_MICROS`, which are used when reading them, see
+  /// [`time`](#method.time). Other times only exist as a logical type and have no
+  /// converted type, hence older readers see plain integer values.
+  TIME { unit: TimeUnit, is_adjusted_to_utc: bool },
+
   /// Date and time recorded as milliseconds since the Unix epoch.
   /// Recorded as a physical type of INT64.
   TIMESTAMP_MILLIS,
@@ -106,6 +120,16 @@ pub enum LogicalType {
   /// The value is stored as an INT64 physical type.
   TIMESTAMP_MICROS,
 
+  /// Date and time recorded as the number of `unit`s since the Unix epoch, stored as
+  /// INT64 physical type. Timestamps adjusted to UTC are instants, other timestamps are
+  /// local date and time that is not bound to a time zone.
+  ///
+  /// Timestamps adjusted to UTC in milliseconds and microseconds are the same as
+  /// `TIMESTAMP_MILLIS` and `TIMESTAMP_MICROS`, which are used when reading them, see
+  /// [`timestamp`](#method.timestamp). Other timestamps only exist as a logical type
+  /// and have no converted type, hence older readers see plain INT64 values.
+  TIMESTAMP { unit: TimeUnit, is_adjusted_to_utc: bool },
+
   /// An unsigned 8 bit integer value stored as INT32 physical type.
   UINT_8,
 
@@ -145,7 +169,153 @@ pub enum LogicalType {
   /// the number of days associated with the duration and the third identifies
   /// the number of milliseconds associated with the provided duration.
   /// This duration of time is independent of any particular timezone or date.
-  INTERVAL
+  INTERVAL,
+
+  /// A universally unique identifier, stored as FIXED_LEN_BYTE_ARRAY of length 16 in
+  /// big-endian byte order.
+  ///
+  /// UUID only exists as a logical type of parquet-format 2.6.0+ and has no converted
+  /// type, hence older readers see plain FIXED_LEN_BYTE_ARRAY values.
+  UUID,
+
+  /// A half-precision floating point value, stored as FIXED_LEN_BYTE_ARRAY of length 2
+  /// in little-endian byte order.
+  ///
+  /// FLOAT16 only exists as a logical type of parquet-format 2.10.0+ and has no
+  /// converted type, hence older readers see plain FIXED_LEN_BYTE_ARRAY values.
+  FLOAT16,
+
+  /// Converted type written by a newer version of Parquet that is not supported, with
+  /// its Thrift value. Values are read according to the physical type.
+  Unknown(i32),
+
+  /// Logical type written by a newer version of Parquet that is not supported, e.g.
+  /// GEOMETRY, kept as is, so that it is written unchanged when the schema is written
+  /// again. Values are read according to the physical type.
+  Other(RawLogicalType)
+}
+
+/// Member of the Thrift `LogicalType` union that is not supported, identified by its
+/// field id and its fields serialized with the compact protocol.
+///
+/// Serialized fields are interned and never freed, so that `LogicalType` can remain
+/// `Copy`. This is fine, since there are only a few distinct logical types in practice.
+#[derive(Clone, Copy, PartialEq)]
+pub struct RawLogicalType {
+  id: i16,
+  data: &'static [u8]
+}
+
+thread_local! {
+  // Serialized fields of all raw logical types created by the current thread
+  static RAW_LOGICAL_TYPES: RefCell<HashSet<&'static [u8]>> =
+    RefCell::new(HashSet::new());
+}
+
+impl RawLogicalType {
+  /// Creates raw logical type from field id of the union member and its fields
+  /// serialized with the compact protocol, without the field stop.
+  pub fn new(id: i16, data: &[u8]) -> Self {
+    let data = RAW_LOGICAL_TYPES.with(|types| {
+      let mut types = types.borrow_mut();
+      if let Some(interned) = types.get(data) {
+        return *interned;
+      }
+      let interned: &'static [u8] = Box::leak(data.to_vec().into_boxed_slice());
+      types.insert(interned);
+      interned
+    });
+    Self { id: id, data: data }
+  }
+
+  /// Returns field id of the member of the `LogicalType` union.
+  pub fn id(&self) -> i16 {
+    self.id
+  }
+
+  /// Returns fields of the member serialized with the compact protocol.
+  pub fn data(&self) -> &[u8] {
+    self.data
+  }
+}
+
+impl fmt::Debug for RawLogicalType {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.id)?;
+    if !self.data.is_empty() {
+      write!(f, ", {:?}", self.data)?;
+    }
+    Ok(())
+  }
+}
+
+impl LogicalType {
+  /// Returns TIMESTAMP logical type with `unit`, using `TIMESTAMP_MILLIS` and
+  /// `TIMESTAMP_MICROS` for timestamps adjusted to UTC that have a converted type.
+  pub fn timestamp(unit: TimeUnit, is_adjusted_to_utc: bool) -> Self {
+    match (unit, is_adjusted_to_utc) {
+      (TimeUnit::MILLIS, true) => LogicalType::TIMESTAMP_MILLIS,
+      (TimeUnit::MICROS, true) => LogicalType::TIMESTAMP_MICROS,
+      _ => LogicalType::TIMESTAMP { unit: unit, is_adjusted_to_utc: is_adjusted_to_utc }
+    }
+  }
+
+  /// Returns TIME logical type with `unit`, using `TIME_MILLIS` and `TIME_MICROS` for
+  /// times adjusted to UTC that have a converted type.
+  pub fn time(unit: TimeUnit, is_adjusted_to_utc: bool) -> Self {
+    match (unit, is_adjusted_to_utc) {
+      (TimeUnit::MILLIS, true) => LogicalType::TIME_MILLIS,
+      (TimeUnit::MICROS, true) => LogicalType::TIME_MICROS,
+      _ => LogicalType::TIME { unit: unit, is_adjusted_to_utc: is_adjusted_to_utc }
+    }
+  }
+
+  /// Returns unit and UTC adjustment of time logical types, `None` for other logical
+  /// types.
+  pub fn time_unit(&self) -> Option<(TimeUnit, bool)> {
+    match *self {
+      LogicalType::TIME_MILLIS => Some((TimeUnit::MILLIS, true)),
+      LogicalType::TIME_MICROS => Some((TimeUnit::MICROS, true)),
+      LogicalType::TIME { unit, is_adjusted_to_utc } => Some((unit, is_adjusted_to_utc)),
+      _ => None
+    }
+  }
+
+  /// Returns unit and UTC adjustment of timestamp logical types, `None` for other
+  /// logical types.
+  pub fn timestamp_unit(&self) -> Option<(TimeUnit, bool)> {
+    match *self {
+      LogicalType::TIMESTAMP_MILLIS => Some((TimeUnit::MILLIS, true)),
+      LogicalType::TIMESTAMP_MICROS => Some((TimeUnit::MICROS, true)),
+      LogicalType::TIMESTAMP { unit, is_adjusted_to_utc } => {
+        Some((unit, is_adjusted_to_utc))
+      },
+      _ => None
+    }
+  }
+}
+
+// ----------------------------------------------------------------------
+// Mirrors `parquet::TimeUnit`
+
+/// Unit of TIME and TIMESTAMP logical type values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+  MILLIS,
+  MICROS,
+  /// Nanoseconds only exist in parquet-format 2.6.0+.
+  NANOS
+}
+
+impl TimeUnit {
+  /// Returns number of units in a second.
+  pub fn units_per_second(&self) -> i64 {
+    match *self {
+      TimeUnit::MILLIS => 1_000,
+      TimeUnit::MICROS => 1_000_000,
+      TimeUnit::NANOS => 1_000_000_000
+    }
+  }
 }
 
 // ----------------------------------------------------------------------
@@ -153,6 +323,7 @@ pub enum LogicalType {
 
 /// Representation of field types in schema.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Repetition {
   /// Field is required (can not be null) and each record has exactly 1 value.
   REQUIRED,
@@ -169,6 +340,7 @@ pub enum Repetition {
 /// Not all encodings are valid for all types. These enums are also used to specify the
 /// encoding of definition and repetition levels.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Encoding {
   /// Default byte encoding.
   /// - BOOLEAN - 1 bit per value, 0 is false; 1 is true.
@@ -217,7 +389,11 @@ pub enum Encoding {
   /// Dictionary encoding.
   ///
   /// The ids are encoded using the RLE encoding.
-  RLE_DICTIONARY
+  RLE_DICTIONARY,
+
+  /// Encoding written by a newer version of Parquet that is not supported, with its
+  /// Thrift value.
+  Unknown(i32)
 }
 
 // ----------------------------------------------------------------------
@@ -225,6 +401,7 @@ pub enum Encoding {
 
 /// Supported compression algorithms.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Compression {
   UNCOMPRESSED,
   SNAPPY,
@@ -232,7 +409,10 @@ pub enum Compression {
   LZO,
   BROTLI,
   LZ4,
-  ZSTD
+  ZSTD,
+  /// Compression codec written by a newer version of Parquet that is not supported, with
+  /// its Thrift value.
+  Unknown(i32)
 }
 
 // ----------------------------------------------------------------------
@@ -245,7 +425,9 @@ pub enum PageType {
   DATA_PAGE,
   INDEX_PAGE,
   DICTIONARY_PAGE,
-  DATA_PAGE_V2
+  DATA_PAGE_V2,
+  /// Page type written by a newer version of Parquet, such pages are skipped by readers.
+  Unknown(i32)
 }
 
 // ----------------------------------------------------------------------
@@ -312,14 +494,24 @@ impl ColumnOrder {
 
       LogicalType::TIME_MILLIS |
       LogicalType::TIME_MICROS |
+      LogicalType::TIME { .. } |
       LogicalType::TIMESTAMP_MILLIS |
-      LogicalType::TIMESTAMP_MICROS => SortOrder::SIGNED,
+      LogicalType::TIMESTAMP_MICROS |
+      LogicalType::TIMESTAMP { .. } => SortOrder::SIGNED,
 
       LogicalType::INTERVAL => SortOrder::UNSIGNED,
 
+      // Unsigned byte-wise comparison.
+      LogicalType::UUID => SortOrder::UNSIGNED,
+
+      // Signed comparison of the represented value.
+      LogicalType::FLOAT16 => SortOrder::SIGNED,
+
       LogicalType::LIST |
       LogicalType::MAP |
-      LogicalType::MAP_KEY_VALUE => SortOrder::UNDEFINED,
+      LogicalType::MAP_KEY_VALUE |
+      LogicalType::Unknown(_) |
+      LogicalType::Other(_) => SortOrder::UNDEFINED,
 
       // Fall back to physical type.
       LogicalType::NONE => Self::get_default_sort_order(physical_type)
@@ -361,6 +553,20 @@ impl fmt::Display for Type {
 }
 
 impl fmt::Display for LogicalType {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      LogicalType::TIME { unit, is_adjusted_to_utc } => {
+        write!(f, "TIME({},{})", unit, is_adjusted_to_utc)
+      },
+      LogicalType::TIMESTAMP { unit, is_adjusted_to_utc } => {
+        write!(f, "TIMESTAMP({},{})", unit, is_adjusted_to_utc)
+      },
+      _ => write!(f, "{:?}", self)
+    }
+  }
+}
+
+impl fmt::Display for TimeUnit {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     write!(f, "{:?}", self)
   }
@@ -485,8 +691,22 @@ impl convert::From<LogicalType> for Option<parquet::ConvertedType> {
       LogicalType::DATE => Some(parquet::ConvertedType::DATE),
       LogicalType::TIME_MILLIS => Some(parquet::ConvertedType::TIME_MILLIS),
       LogicalType::TIME_MICROS => Some(parquet::ConvertedType::TIME_MICROS),
+      LogicalType::TIME { unit: TimeUnit::MILLIS, is_adjusted_to_utc: true } => {
+        Some(parquet::ConvertedType::TIME_MILLIS)
+      },
+      LogicalType::TIME { unit: TimeUnit::MICROS, is_adjusted_to_utc: true } => {
+        Some(parquet::ConvertedType::TIME_MICROS)
+      },
+      LogicalType::TIME { .. } => None,
       LogicalType::TIMESTAMP_MILLIS => Some(parquet::ConvertedType::TIMESTAMP_MILLIS),
       LogicalType::TIMESTAMP_MICROS => Some(parquet::ConvertedType::TIMESTAMP_MICROS),
+      LogicalType::TIMESTAMP { unit: TimeUnit::MILLIS, is_adjusted_to_utc: true } => {
+        Some(parquet::ConvertedType::TIMESTAMP_MILLIS)
+      },
+      LogicalType::TIMESTAMP { unit: TimeUnit::MICROS, is_adjusted_to_utc: true } => {
+        Some(parquet::ConvertedType::TIMESTAMP_MICROS)
+      },
+      LogicalType::TIMESTAMP { .. } => None,
       LogicalType::UINT_8 => Some(parquet::ConvertedType::UINT_8),
       LogicalType::UINT_16 => Some(parquet::ConvertedType::UINT_16),
       LogicalType::UINT_32 => Some(parquet::ConvertedType::UINT_32),
@@ -497,7 +717,13 @@ impl convert::From<LogicalType> for Option<parquet::ConvertedType> {
       LogicalType::INT_64 => Some(parquet::ConvertedType::INT_64),
       LogicalType::JSON => Some(parquet::ConvertedType::JSON),
       LogicalType::BSON => Some(parquet::ConvertedType::BSON),
-      LogicalType::INTERVAL => Some(parquet::ConvertedType::INTERVAL)
+      LogicalType::INTERVAL => Some(parquet::ConvertedType::INTERVAL),
+      LogicalType::UUID => None,
+      LogicalType::FLOAT16 => None,
+      LogicalType::Other(_) => None,
+      LogicalType::Unknown(value) => {
+        panic!("Cannot write unknown converted type {}", value)
+      }
     }
   }
 }
@@ -553,7 +779,8 @@ impl convert::From<Encoding> for parquet::Encoding {
       Encoding::DELTA_BINARY_PACKED => parquet::Encoding::DELTA_BINARY_PACKED,
       Encoding::DELTA_LENGTH_BYTE_ARRAY => parquet::Encoding::DELTA_LENGTH_BYTE_ARRAY,
       Encoding::DELTA_BYTE_ARRAY => parquet::Encoding::DELTA_BYTE_ARRAY,
-      Encoding::RLE_DICTIONARY => parquet::Encoding::RLE_DICTIONARY
+      Encoding::RLE_DICTIONARY => parquet::Encoding::RLE_DICTIONARY,
+      Encoding::Unknown(value) => panic!("Cannot write unknown encoding {}", value)
     }
   }
 }
@@ -584,7 +811,8 @@ impl convert::From<Compression> for parquet::CompressionCodec {
       Compression::LZO => parquet::CompressionCodec::LZO,
       Compression::BROTLI => parquet::CompressionCodec::BROTLI,
       Compression::LZ4 => parquet::CompressionCodec::LZ4,
-      Compression::ZSTD => parquet::CompressionCodec::ZSTD
+      Compression::ZSTD => parquet::CompressionCodec::ZSTD,
+      Compression::Unknown(value) => panic!("Cannot write unknown compression {}", value)
     }
   }
 }
@@ -609,13 +837,14 @@ impl convert::From<PageType> for parquet::PageType {
       PageType::DATA_PAGE => parquet::PageType::DATA_PAGE,
       PageType::INDEX_PAGE => parquet::PageType::INDEX_PAGE,
       PageType::DICTIONARY_PAGE => parquet::PageType::DICTIONARY_PAGE,
-      PageType::DATA_PAGE_V2 => parquet::PageType::DATA_PAGE_V2
+      PageType::DATA_PAGE_V2 => parquet::PageType::DATA_PAGE_V2,
+      PageType::Unknown(value) => panic!("Cannot write unknown page type {}", value)
     }
   }
 }
 
 // ----------------------------------------------------------------------
-// String conversions for schema parsing.
+// String conversions for schema parsing and configuration of writer properties.
 
 impl str::FromStr for Repetition {
   type Err = ParquetError;
@@ -673,15 +902,64 @@ impl str::FromStr for LogicalType {
       "JSON" => Ok(LogicalType::JSON),
       "BSON" => Ok(LogicalType::BSON),
       "INTERVAL" => Ok(LogicalType::INTERVAL),
+      "UUID" => Ok(LogicalType::UUID),
+      "FLOAT16" => Ok(LogicalType::FLOAT16),
       other => Err(general_err!("Invalid logical type {}", other)),
     }
   }
 }
 
+impl str::FromStr for Encoding {
+  type Err = ParquetError;
+  fn from_str(s: &str) -> result::Result<Self, Self::Err> {
+    match s {
+      "PLAIN" => Ok(Encoding::PLAIN),
+      "PLAIN_DICTIONARY" => Ok(Encoding::PLAIN_DICTIONARY),
+      "RLE" => Ok(Encoding::RLE),
+      "BIT_PACKED" => Ok(Encoding::BIT_PACKED),
+      "DELTA_BINARY_PACKED" => Ok(Encoding::DELTA_BINARY_PACKED),
+      "DELTA_LENGTH_BYTE_ARRAY" => Ok(Encoding::DELTA_LENGTH_BYTE_ARRAY),
+      "DELTA_BYTE_ARRAY" => Ok(Encoding::DELTA_BYTE_ARRAY),
+      "RLE_DICTIONARY" => Ok(Encoding::RLE_DICTIONARY),
+      other => Err(general_err!("Invalid encoding {}", other)),
+    }
+  }
+}
+
+impl str::FromStr for Compression {
+  type Err = ParquetError;
+  fn from_str(s: &str) -> result::Result<Self, Self::Err> {
+    match s {
+      "UNCOMPRESSED" => Ok(Compression::UNCOMPRESSED),
+      "SNAPPY" => Ok(Compression::SNAPPY),
+      "GZIP" => Ok(Compression::GZIP),
+      "LZO" => Ok(Compression::LZO),
+      "BROTLI" => Ok(Compression::BROTLI),
+      "LZ4" => Ok(Compression::LZ4),
+      "ZSTD" => Ok(Compression::ZSTD),
+      other => Err(general_err!("Invalid compression {}", other)),
+    }
+  }
+}
+
+impl str::FromStr for TimeUnit {
+  type Err = ParquetError;
+  fn from_str(s: &str) -> result::Result<Self, Self::Err> {
+    match s {
+      "MILLIS" => Ok(TimeUnit::MILLIS),
+      "MICROS" => Ok(TimeUnit::MICROS),
+      "NANOS" => Ok(TimeUnit::NANOS),
+      other => Err(general_err!("Invalid time unit {}", other)),
+    }
+  }
+}
+
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  #[cfg(all(feature = "serde", feature = "serde_json"))]
+  use serde_json;
 
   #[test]
   fn test_display_type() {
@@ -744,6 +1022,12 @@ mod tests {
     assert_eq!(LogicalType::UTF8.to_string(), "UTF8");
     assert_eq!(LogicalType::MAP.to_string(), "MAP");
     assert_eq!(LogicalType::MAP_KEY_VALUE.to_string(), "MAP_KEY_VALUE");
+    assert_eq!(LogicalType::Unknown(30).to_string(), "Unknown(30)");
+    assert_eq!(LogicalType::Other(RawLogicalType::new(16, &[])).to_string(), "Other(16)");
+    assert_eq!(
+      LogicalType::Other(RawLogicalType::new(16, &[0x18, 0x01, 0x61])).to_string(),
+      "Other(16, [24, 1, 97])"
+    );
     assert_eq!(LogicalType::LIST.to_string(), "LIST");
     assert_eq!(LogicalType::ENUM.to_string(), "ENUM");
     assert_eq!(LogicalType::DECIMAL.to_string(), "DECIMAL");
@@ -751,8 +1035,16 @@ mod tests {
     assert_eq!(LogicalType::TIME_MILLIS.to_string(), "TIME_MILLIS");
     assert_eq!(LogicalType::DATE.to_string(), "DATE");
     assert_eq!(LogicalType::TIME_MICROS.to_string(), "TIME_MICROS");
+    assert_eq!(
+      LogicalType::time(TimeUnit::NANOS, true).to_string(),
+      "TIME(NANOS,true)"
+    );
     assert_eq!(LogicalType::TIMESTAMP_MILLIS.to_string(), "TIMESTAMP_MILLIS");
     assert_eq!(LogicalType::TIMESTAMP_MICROS.to_string(), "TIMESTAMP_MICROS");
+    assert_eq!(
+      LogicalType::timestamp(TimeUnit::NANOS, false).to_string(),
+      "TIMESTAMP(NANOS,false)"
+    );
     assert_eq!(LogicalType::UINT_8.to_string(), "UINT_8");
     assert_eq!(LogicalType::UINT_16.to_string(), "UINT_16");
     assert_eq!(LogicalType::UINT_32.to_string(), "UINT_32");
@@ -764,6 +1056,8 @@ mod tests {
     assert_eq!(LogicalType::JSON.to_string(), "JSON");
     assert_eq!(LogicalType::BSON.to_string(), "BSON");
     assert_eq!(LogicalType::INTERVAL.to_string(), "INTERVAL");
+    assert_eq!(LogicalType::UUID.to_string(), "UUID");
+    assert_eq!(LogicalType::FLOAT16.to_string(), "FLOAT16");
   }
 
     #[test]
@@ -954,6 +1248,91 @@ mod tests {
       Some(parquet::ConvertedType::INTERVAL),
       LogicalType::INTERVAL.into()
     );
+    let converted_type: Option<parquet::ConvertedType> = LogicalType::UUID.into();
+    assert_eq!(converted_type, None);
+    let converted_type: Option<parquet::ConvertedType> = LogicalType::FLOAT16.into();
+    assert_eq!(converted_type, None);
+    let converted_type: Option<parquet::ConvertedType> =
+      LogicalType::TIMESTAMP { unit: TimeUnit::MICROS, is_adjusted_to_utc: true }.into();
+    assert_eq!(converted_type, Some(parquet::ConvertedType::TIMESTAMP_MICROS));
+    let converted_type: Option<parquet::ConvertedType> =
+      LogicalType::timestamp(TimeUnit::MILLIS, false).into();
+    assert_eq!(converted_type, None);
+    let converted_type: Option<parquet::ConvertedType> =
+      LogicalType::timestamp(TimeUnit::NANOS, true).into();
+    assert_eq!(converted_type, None);
+    let converted_type: Option<parquet::ConvertedType> =
+      LogicalType::TIME { unit: TimeUnit::MILLIS, is_adjusted_to_utc: true }.into();
+    assert_eq!(converted_type, Some(parquet::ConvertedType::TIME_MILLIS));
+    let converted_type: Option<parquet::ConvertedType> =
+      LogicalType::time(TimeUnit::MICROS, false).into();
+    assert_eq!(converted_type, None);
+  }
+
+  #[test]
+  fn test_time_logical_type() {
+    assert_eq!(LogicalType::time(TimeUnit::MILLIS, true), LogicalType::TIME_MILLIS);
+    assert_eq!(LogicalType::time(TimeUnit::MICROS, true), LogicalType::TIME_MICROS);
+    assert_eq!(
+      LogicalType::time(TimeUnit::MILLIS, false),
+      LogicalType::TIME { unit: TimeUnit::MILLIS, is_adjusted_to_utc: false }
+    );
+    assert_eq!(
+      LogicalType::time(TimeUnit::NANOS, true),
+      LogicalType::TIME { unit: TimeUnit::NANOS, is_adjusted_to_utc: true }
+    );
+
+    assert_eq!(LogicalType::TIME_MILLIS.time_unit(), Some((TimeUnit::MILLIS, true)));
+    assert_eq!(LogicalType::TIME_MICROS.time_unit(), Some((TimeUnit::MICROS, true)));
+    assert_eq!(
+      LogicalType::time(TimeUnit::NANOS, false).time_unit(),
+      Some((TimeUnit::NANOS, false))
+    );
+    assert_eq!(LogicalType::time(TimeUnit::NANOS, false).timestamp_unit(), None);
+    assert_eq!(LogicalType::INT_32.time_unit(), None);
+  }
+
+  #[test]
+  fn test_timestamp_logical_type() {
+    assert_eq!(
+      LogicalType::timestamp(TimeUnit::MILLIS, true),
+      LogicalType::TIMESTAMP_MILLIS
+    );
+    assert_eq!(
+      LogicalType::timestamp(TimeUnit::MICROS, true),
+      LogicalType::TIMESTAMP_MICROS
+    );
+    assert_eq!(
+      LogicalType::timestamp(TimeUnit::MICROS, false),
+      LogicalType::TIMESTAMP { unit: TimeUnit::MICROS, is_adjusted_to_utc: false }
+    );
+    assert_eq!(
+      LogicalType::timestamp(TimeUnit::NANOS, true),
+      LogicalType::TIMESTAMP { unit: TimeUnit::NANOS, is_adjusted_to_utc: true }
+    );
+
+    assert_eq!(
+      LogicalType::TIMESTAMP_MILLIS.timestamp_unit(),
+      Some((TimeUnit::MILLIS, true))
+    );
+    assert_eq!(
+      LogicalType::TIMESTAMP_MICROS.timestamp_unit(),
+      Some((TimeUnit::MICROS, true))
+    );
+    assert_eq!(
+      LogicalType::timestamp(TimeUnit::NANOS, false).timestamp_unit(),
+      Some((TimeUnit::NANOS, false))
+    );
+    assert_eq!(LogicalType::TIME_MICROS.timestamp_unit(), None);
+    assert_eq!(LogicalType::INT_64.timestamp_unit(), None);
+
+    assert_eq!(LogicalType::TIMESTAMP_MILLIS.time_unit(), None);
+    assert_eq!(TimeUnit::MILLIS.units_per_second(), 1_000);
+    assert_eq!(TimeUnit::MICROS.units_per_second(), 1_000_000);
+    assert_eq!(TimeUnit::NANOS.units_per_second(), 1_000_000_000);
+    assert_eq!(TimeUnit::NANOS.to_string(), "NANOS");
+    assert_eq!("MICROS".parse::<TimeUnit>().unwrap(), TimeUnit::MICROS);
+    assert!("SECONDS".parse::<TimeUnit>().is_err());
   }
 
   #[test]
@@ -1050,6 +1429,14 @@ mod tests {
       LogicalType::INTERVAL.to_string().parse::<LogicalType>().unwrap(),
       LogicalType::INTERVAL
     );
+    assert_eq!(
+      LogicalType::UUID.to_string().parse::<LogicalType>().unwrap(),
+      LogicalType::UUID
+    );
+    assert_eq!(
+      LogicalType::FLOAT16.to_string().parse::<LogicalType>().unwrap(),
+      LogicalType::FLOAT16
+    );
   }
 
   #[test]
@@ -1117,6 +1504,32 @@ mod tests {
     assert_eq!(Encoding::DELTA_LENGTH_BYTE_ARRAY.to_string(), "DELTA_LENGTH_BYTE_ARRAY");
     assert_eq!(Encoding::DELTA_BYTE_ARRAY.to_string(), "DELTA_BYTE_ARRAY");
     assert_eq!(Encoding::RLE_DICTIONARY.to_string(), "RLE_DICTIONARY");
+    assert_eq!(Encoding::Unknown(9).to_string(), "Unknown(9)");
+  }
+
+  #[test]
+  fn test_from_string_into_encoding() {
+    let encodings = vec![
+      Encoding::PLAIN,
+      Encoding::PLAIN_DICTIONARY,
+      Encoding::RLE,
+      Encoding::BIT_PACKED,
+      Encoding::DELTA_BINARY_PACKED,
+      Encoding::DELTA_LENGTH_BYTE_ARRAY,
+      Encoding::DELTA_BYTE_ARRAY,
+      Encoding::RLE_DICTIONARY
+    ];
+    for encoding in encodings {
+      assert_eq!(encoding.to_string().parse::<Encoding>().unwrap(), encoding);
+    }
+    assert_eq!(
+      "Unknown(9)".parse::<Encoding>().unwrap_err(),
+      general_err!("Invalid encoding Unknown(9)")
+    );
+    assert_eq!(
+      "plain".parse::<Encoding>().unwrap_err(),
+      general_err!("Invalid encoding plain")
+    );
   }
 
   #[test]
@@ -1192,6 +1605,62 @@ mod tests {
     assert_eq!(Compression::BROTLI.to_string(), "BROTLI");
     assert_eq!(Compression::LZ4.to_string(), "LZ4");
     assert_eq!(Compression::ZSTD.to_string(), "ZSTD");
+    assert_eq!(Compression::Unknown(7).to_string(), "Unknown(7)");
+  }
+
+  #[test]
+  fn test_from_string_into_compression() {
+    let codecs = vec![
+      Compression::UNCOMPRESSED,
+      Compression::SNAPPY,
+      Compression::GZIP,
+      Compression::LZO,
+      Compression::BROTLI,
+      Compression::LZ4,
+      Compression::ZSTD
+    ];
+    for codec in codecs {
+      assert_eq!(codec.to_string().parse::<Compression>().unwrap(), codec);
+    }
+    assert_eq!(
+      "LZ4_RAW".parse::<Compression>().unwrap_err(),
+      general_err!("Invalid compression LZ4_RAW")
+    );
+  }
+
+  #[test]
+  #[cfg(all(feature = "serde", feature = "serde_json"))]
+  fn test_serde_basic_enums() {
+    assert_eq!(serde_json::to_string(&Type::INT96).unwrap(), "\"INT96\"");
+    assert_eq!(serde_json::to_string(&Repetition::OPTIONAL).unwrap(), "\"OPTIONAL\"");
+    assert_eq!(
+      serde_json::to_string(&Encoding::DELTA_BINARY_PACKED).unwrap(),
+      "\"DELTA_BINARY_PACKED\""
+    );
+    assert_eq!(serde_json::to_string(&Compression::SNAPPY).unwrap(), "\"SNAPPY\"");
+
+    assert_eq!(
+      serde_json::from_str::<Type>("\"FIXED_LEN_BYTE_ARRAY\"").unwrap(),
+      Type::FIXED_LEN_BYTE_ARRAY
+    );
+    assert_eq!(
+      serde_json::from_str::<Repetition>("\"REPEATED\"").unwrap(),
+      Repetition::REPEATED
+    );
+    assert_eq!(
+      serde_json::from_str::<Encoding>("\"RLE_DICTIONARY\"").unwrap(),
+      Encoding::RLE_DICTIONARY
+    );
+    assert_eq!(
+      serde_json::from_str::<Compression>("\"ZSTD\"").unwrap(),
+      Compression::ZSTD
+    );
+    assert!(serde_json::from_str::<Compression>("\"SNAPPY_RAW\"").is_err());
+
+    // Values written by newer versions of Parquet round trip as well
+    let codec = Compression::Unknown(8);
+    let json = serde_json::to_string(&codec).unwrap();
+    assert_eq!(serde_json::from_str::<Compression>(&json).unwrap(), codec);
   }
 
   #[test]
@@ -1264,6 +1733,7 @@ mod tests {
     assert_eq!(PageType::INDEX_PAGE.to_string(), "INDEX_PAGE");
     assert_eq!(PageType::DICTIONARY_PAGE.to_string(), "DICTIONARY_PAGE");
     assert_eq!(PageType::DATA_PAGE_V2.to_string(), "DATA_PAGE_V2");
+    assert_eq!(PageType::Unknown(4).to_string(), "Unknown(4)");
   }
 
   #[test]
@@ -1332,7 +1802,8 @@ mod tests {
       LogicalType::UINT_16,
       LogicalType::UINT_32,
       LogicalType::UINT_64,
-      LogicalType::INTERVAL
+      LogicalType::INTERVAL,
+      LogicalType::UUID
     ];
     check_sort_order(unsigned, SortOrder::UNSIGNED);
 
@@ -1347,7 +1818,11 @@ mod tests {
       LogicalType::TIME_MILLIS,
       LogicalType::TIME_MICROS,
       LogicalType::TIMESTAMP_MILLIS,
-      LogicalType::TIMESTAMP_MICROS
+      LogicalType::TIMESTAMP_MICROS,
+      LogicalType::timestamp(TimeUnit::MILLIS, false),
+      LogicalType::timestamp(TimeUnit::NANOS, true),
+      LogicalType::time(TimeUnit::NANOS, false),
+      LogicalType::FLOAT16
     ];
     check_sort_order(signed, SortOrder::SIGNED);
 
@@ -1355,7 +1830,9 @@ mod tests {
     let undefined = vec![
       LogicalType::LIST,
       LogicalType::MAP,
-      LogicalType::MAP_KEY_VALUE
+      LogicalType::MAP_KEY_VALUE,
+      LogicalType::Unknown(30),
+      LogicalType::Other(RawLogicalType::new(16, &[]))
     ];
     check_sort_order(undefined, SortOrder::UNDEFINED);
 
@@ -1364,6 +1841,18 @@ mod tests {
     check_sort_order(vec![LogicalType::NONE], SortOrder::UNSIGNED);
   }
 
+  #[test]
+  fn test_raw_logical_type() {
+    let raw = RawLogicalType::new(16, &[0x18, 0x01, 0x61]);
+    assert_eq!(raw.id(), 16);
+    assert_eq!(raw.data(), &[0x18, 0x01, 0x61]);
+    // Serialized fields are interned
+    let other = RawLogicalType::new(17, &[0x18, 0x01, 0x61]);
+    assert_eq!(raw.data().as_ptr(), other.data().as_ptr());
+    assert!(raw != other);
+    assert_eq!(raw, RawLogicalType::new(16, &[0x18, 0x01, 0x61]));
+  }
+
   #[test]
   fn test_column_order_get_default_sort_order() {
     // Comparison based on physical type
@@ -0,0 +1,233 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Binary file to convert a CSV file into a Parquet file with a provided schema.
+//!
+//! # Install
+//!
+//! `parquet-fromcsv` can be installed using `cargo`:
+//! ```
+//! cargo install parquet
+//! ```
+//! After this `parquet-fromcsv` should be globally available:
+//! ```
+//! parquet-fromcsv XYZ.csv XYZ.parquet --schema XYZ.schema
+//! ```
+//!
+//! The binary can also be built from the source code and run as follows:
+//! ```
+//! cargo run --bin parquet-fromcsv XYZ.csv XYZ.parquet --schema XYZ.schema
+//! ```
+//!
+//! # Usage
+//!
+//! ```
+//! parquet-fromcsv <csv-path> <output-path> --schema <schema-path> [--delimiter <char>]
+//!   [--no-header] [--null <token>]... [--date-format <format>]
+//!   [--time-format <format>] [--timestamp-format <format>]...
+//!   [--row-group-size <num-rows>] [--compression <codec>]
+//! ```
+//! where `csv-path` is the path to a CSV file to read, `output-path` is the path of the
+//! new Parquet file, and `schema-path` is the path to a file with the message type of
+//! the new file, e.g.
+//! ```
+//! message schema {
+//!   REQUIRED INT64 id;
+//!   OPTIONAL BYTE_ARRAY name (UTF8);
+//!   OPTIONAL INT64 created (TIMESTAMP_MILLIS);
+//! }
+//! ```
+//! Fields of the schema must be primitive and not repeated.
+//!
+//! Options:
+//! - `--delimiter` sets delimiter of fields, default is `,`, use `\t` for TSV files.
+//! - `--no-header` specifies that the first line is not a header, fields are matched to
+//! CSV columns by position instead of by name.
+//! - `--null` adds a value that is parsed as null, can be used multiple times. When not
+//! provided, only empty fields are nulls.
+//! - `--date-format`, `--time-format` and `--timestamp-format` set `chrono` formats of
+//! `DATE`, `TIME_*` and `TIMESTAMP_*` values, timestamp format can be used multiple
+//! times. Defaults are `%Y-%m-%d`, `%H:%M:%S%.f` and ISO 8601 timestamps.
+//! - `--row-group-size` sets number of rows of each row group, default is 65536. Values
+//! of one row group are buffered in memory.
+//! - `--compression` sets compression codec, e.g. `snappy`, `gzip` or `zstd`, default is
+//! `uncompressed`.
+//!
+//! For example,
+//! ```
+//! parquet-fromcsv data.csv data.parquet --schema data.schema --null NULL \
+//!   --compression snappy
+//! ```
+
+extern crate parquet;
+
+use std::env;
+use std::fs::{self, File};
+use std::path::Path;
+use std::process;
+use std::rc::Rc;
+
+use parquet::basic::Compression;
+use parquet::file::csv::{csv_to_parquet, CsvOptions};
+use parquet::file::properties::WriterProperties;
+use parquet::schema::parser::parse_message_type;
+
+const USAGE: &str = "Usage: parquet-fromcsv <csv-path> <output-path> \
+  --schema <schema-path> [--delimiter <char>] [--no-header] [--null <token>]... \
+  [--date-format <format>] [--time-format <format>] [--timestamp-format <format>]... \
+  [--row-group-size <num-rows>] [--compression <codec>]";
+
+const COMPRESSIONS: [Compression; 7] = [
+  Compression::UNCOMPRESSED,
+  Compression::SNAPPY,
+  Compression::GZIP,
+  Compression::LZO,
+  Compression::BROTLI,
+  Compression::LZ4,
+  Compression::ZSTD
+];
+
+/// Options of the binary parsed from command line arguments.
+struct Options {
+  input: String,
+  output: String,
+  schema: String,
+  csv_options: CsvOptions,
+  compression: Option<Compression>
+}
+
+/// Parses command line arguments, returns `None` if arguments are invalid.
+fn parse_args(args: &[String]) -> Option<Options> {
+  let mut paths = Vec::new();
+  let mut schema = None;
+  let mut csv_options = CsvOptions::new();
+  let mut null_tokens = Vec::new();
+  let mut timestamp_formats = Vec::new();
+  let mut compression = None;
+
+  let mut iter = args.iter();
+  while let Some(arg) = iter.next() {
+    match arg.as_str() {
+      "--schema" => schema = Some(iter.next()?.to_owned()),
+      "--delimiter" => {
+        let delimiter = match iter.next()?.as_str() {
+          "\\t" | "tab" => b'\t',
+          value if value.len() == 1 => value.as_bytes()[0],
+          value => panic!("Invalid value {} for --delimiter, expected one byte", value)
+        };
+        csv_options = csv_options.with_delimiter(delimiter);
+      },
+      "--no-header" => csv_options = csv_options.with_header(false),
+      "--null" => null_tokens.push(iter.next()?.to_owned()),
+      "--date-format" => {
+        csv_options = csv_options.with_date_format(iter.next()?.to_owned());
+      },
+      "--time-format" => {
+        csv_options = csv_options.with_time_format(iter.next()?.to_owned());
+      },
+      "--timestamp-format" => timestamp_formats.push(iter.next()?.to_owned()),
+      "--row-group-size" => {
+        let value = iter.next()?;
+        match value.parse() {
+          Ok(num_rows) => csv_options = csv_options.with_row_group_size(num_rows),
+          Err(e) => panic!("Error when reading value for --row-group-size, {}", e)
+        }
+      },
+      "--compression" => {
+        let value = iter.next()?;
+        match COMPRESSIONS.iter().find(|c| c.to_string().eq_ignore_ascii_case(value)) {
+          Some(c) => compression = Some(*c),
+          None => {
+            let names: Vec<_> =
+              COMPRESSIONS.iter().map(|c| c.to_string().to_lowercase()).collect();
+            panic!(
+              "Invalid value {} for --compression, expected one of {}",
+              value,
+              names.join(", ")
+            );
+          }
+        }
+      },
+      arg if arg.starts_with("--") => return None,
+      arg => paths.push(arg.to_owned())
+    }
+  }
+  if paths.len() != 2 {
+    return None;
+  }
+  if !null_tokens.is_empty() {
+    csv_options = csv_options.with_null_tokens(null_tokens);
+  }
+  if !timestamp_formats.is_empty() {
+    csv_options = csv_options.with_timestamp_formats(timestamp_formats);
+  }
+  let output = paths.pop()?;
+  let input = paths.pop()?;
+  Some(Options {
+    input: input,
+    output: output,
+    schema: schema?,
+    csv_options: csv_options,
+    compression: compression
+  })
+}
+
+fn main() {
+  let args: Vec<String> = env::args().skip(1).collect();
+  let options = match parse_args(&args) {
+    Some(options) => options,
+    None => {
+      println!("{}", USAGE);
+      process::exit(1);
+    }
+  };
+
+  let message_type = match fs::read_to_string(&options.schema) {
+    Err(e) => panic!("Error when reading schema file {}: {}", options.schema, e),
+    Ok(message_type) => message_type
+  };
+  let schema = match parse_message_type(&message_type) {
+    Err(e) => panic!("Error when parsing schema: {}", e),
+    Ok(schema) => schema
+  };
+  let mut props = WriterProperties::builder();
+  if let Some(compression) = options.compression {
+    props = props.set_compression(compression);
+  }
+
+  let input_path = Path::new(&options.input);
+  let input = match File::open(&input_path) {
+    Err(e) => panic!("Error when opening file {}: {}", input_path.display(), e),
+    Ok(f) => f
+  };
+  let output_path = Path::new(&options.output);
+  let output = match File::create(&output_path) {
+    Err(e) => panic!("Error when creating file {}: {}", output_path.display(), e),
+    Ok(f) => f
+  };
+  let res = csv_to_parquet(
+    input,
+    output,
+    Rc::new(schema),
+    Rc::new(props.build()),
+    &options.csv_options
+  );
+  match res {
+    Err(e) => panic!("Error when converting CSV file: {}", e),
+    Ok(num_rows) => println!("Wrote {} rows into {}", num_rows, output_path.display())
+  }
+}
@@ -0,0 +1,253 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Binary file to print row group, column chunk and page level metadata of a Parquet
+//! file, similar to `parquet-tools meta` and `parquet-tools column-index`.
+//!
+//! # Install
+//!
+//! `parquet-meta` can be installed using `cargo`:
+//! ```
+//! cargo install parquet
+//! ```
+//! After this `parquet-meta` should be globally available:
+//! ```
+//! parquet-meta XYZ.parquet
+//! ```
+//!
+//! The binary can also be built from the source code and run as follows:
+//! ```
+//! cargo run --bin parquet-meta XYZ.parquet
+//! ```
+//!
+//! # Usage
+//!
+//! ```
+//! parquet-meta <file-path> [--pages]
+//! ```
+//! where `file-path` is the path to a Parquet file and `--pages` is the optional flag
+//! to also read page headers of each column chunk and print page type, number of
+//! values, encoding, size and statistics of every page.
+//!
+//! For each row group the tool prints number of rows and sizes, for each column
+//! chunk it prints type, codec, encodings, sizes and compression ratio, page offsets,
+//! statistics, page encoding stats, and summaries of the offset index, column index and
+//! bloom filter, when the file has them.
+//!
+//! For example,
+//! ```
+//! parquet-meta data/alltypes_plain.snappy.parquet
+//!
+//! parquet-meta data/alltypes_plain.snappy.parquet --pages
+//! ```
+
+extern crate parquet;
+
+use std::env;
+use std::fs::File;
+use std::path::Path;
+use std::process;
+
+use parquet::basic::LogicalType;
+use parquet::column::page::{Page, PageReader};
+use parquet::file::bloom_filter::read_bloom_filter;
+use parquet::file::metadata::{ColumnChunkMetaData, RowGroupMetaData};
+use parquet::file::page_index::{read_column_index, read_offset_index};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  if args.len() != 2 && (args.len() != 3 || args[2] != "--pages") {
+    println!("Usage: parquet-meta <file-path> [--pages]");
+    process::exit(1);
+  }
+  let path = Path::new(&args[1]);
+  let print_pages = args.len() == 3;
+
+  let open_file = || match File::open(&path) {
+    Err(e) => panic!("Error when opening file {}: {}", path.display(), e),
+    Ok(f) => f
+  };
+  let reader = match SerializedFileReader::new(open_file()) {
+    Err(e) => panic!("Error when parsing Parquet file: {}", e),
+    Ok(reader) => reader
+  };
+  // Separate handle to read page indexes and bloom filters, file reader owns the
+  // other one.
+  let mut file = open_file();
+
+  let metadata = reader.metadata();
+  let file_metadata = metadata.file_metadata();
+  println!("file: {}", &args[1]);
+  println!("version: {}", file_metadata.version());
+  println!(
+    "created by: {}",
+    file_metadata.created_by().as_ref().map(|s| s.as_str()).unwrap_or("N/A")
+  );
+  println!("num of rows: {}", file_metadata.num_rows());
+  println!("num of row groups: {}", metadata.num_row_groups());
+
+  for i in 0..metadata.num_row_groups() {
+    let row_group = metadata.row_group(i);
+    println!("");
+    print_row_group(i, &row_group);
+
+    let row_group_reader = if print_pages {
+      match reader.get_row_group(i) {
+        Err(e) => panic!("Error when reading row group {}: {}", i, e),
+        Ok(r) => Some(r)
+      }
+    } else {
+      None
+    };
+
+    for (j, column) in row_group.columns().iter().enumerate() {
+      println!("");
+      print_column_chunk(column);
+
+      match read_offset_index(&mut file, column) {
+        Err(e) => println!("  offset index: error: {}", e),
+        Ok(None) => println!("  offset index: N/A"),
+        Ok(Some(index)) => {
+          println!("  offset index: {} pages", index.num_pages());
+          for (k, loc) in index.page_locations().iter().enumerate() {
+            println!(
+              "    page {}: offset {}, size {}, first row {}",
+              k,
+              loc.offset(),
+              loc.compressed_page_size(),
+              loc.first_row_index()
+            );
+          }
+        }
+      }
+      match read_column_index(&mut file, column) {
+        Err(e) => println!("  column index: error: {}", e),
+        Ok(None) => println!("  column index: N/A"),
+        Ok(Some(index)) => {
+          println!(
+            "  column index: {} pages, boundary order {}",
+            index.num_pages(),
+            index.boundary_order()
+          );
+          for (k, stats) in index.page_statistics().iter().enumerate() {
+            if index.null_pages()[k] {
+              println!("    page {}: null page, {}", k, stats);
+            } else {
+              println!("    page {}: {}", k, stats);
+            }
+          }
+        }
+      }
+      match read_bloom_filter(&mut file, column) {
+        Err(e) => println!("  bloom filter: error: {}", e),
+        Ok(None) => println!("  bloom filter: N/A"),
+        Ok(Some(bloom_filter)) => {
+          println!("  bloom filter: {} bytes", bloom_filter.num_bytes())
+        }
+      }
+
+      if let Some(ref row_group_reader) = row_group_reader {
+        match row_group_reader.get_column_page_reader(j) {
+          Err(e) => println!("  pages: error: {}", e),
+          Ok(mut page_reader) => print_pages_of(&mut *page_reader)
+        }
+      }
+    }
+  }
+}
+
+fn print_row_group(i: usize, row_group: &RowGroupMetaData) {
+  let compressed_size: i64 =
+    row_group.columns().iter().map(|c| c.compressed_size()).sum();
+  println!("row group {}", i);
+  println!("  num of rows: {}", row_group.num_rows());
+  println!("  num of columns: {}", row_group.num_columns());
+  println!("  total byte size: {}", row_group.total_byte_size());
+  println!("  total compressed size: {}", compressed_size);
+}
+
+fn print_column_chunk(column: &ColumnChunkMetaData) {
+  println!("column {}", column.column_path());
+  let logical_type = column.column_descr().logical_type();
+  if logical_type == LogicalType::NONE {
+    println!("  type: {}", column.column_type());
+  } else {
+    println!("  type: {} ({})", column.column_type(), logical_type);
+  }
+  println!("  codec: {}", column.compression());
+  let encodings: Vec<_> = column.encodings().iter().map(|e| e.to_string()).collect();
+  println!("  encodings: {}", encodings.join(" "));
+  println!("  num of values: {}", column.num_values());
+  println!("  compressed size: {}", column.compressed_size());
+  println!("  uncompressed size: {}", column.uncompressed_size());
+  if column.compressed_size() > 0 {
+    println!(
+      "  compression ratio: {:.2}",
+      column.uncompressed_size() as f64 / column.compressed_size() as f64
+    );
+  }
+  println!("  data page offset: {}", column.data_page_offset());
+  match column.dictionary_page_offset() {
+    Some(offset) => println!("  dictionary page offset: {}", offset),
+    None => println!("  dictionary page offset: N/A")
+  }
+  match column.statistics() {
+    Some(stats) => println!("  statistics: {}", stats),
+    None => println!("  statistics: N/A")
+  }
+  match column.encoding_stats() {
+    Some(encoding_stats) => {
+      let stats: Vec<_> = encoding_stats.iter()
+        .map(|s| format!("{}/{}: {}", s.page_type, s.encoding, s.count))
+        .collect();
+      println!("  page encoding stats: {}", stats.join(", "));
+    },
+    None => println!("  page encoding stats: N/A")
+  }
+}
+
+fn print_pages_of(page_reader: &mut PageReader) {
+  println!("  pages:");
+  let mut i = 0;
+  loop {
+    let page = match page_reader.get_next_page() {
+      Err(e) => {
+        println!("    error: {}", e);
+        break;
+      },
+      Ok(None) => break,
+      Ok(Some(page)) => page
+    };
+    let mut line = format!(
+      "    page {}: {}, {} values, encoding {}, uncompressed size {}",
+      i,
+      page.page_type(),
+      page.num_values(),
+      page.encoding(),
+      page.buffer().len()
+    );
+    if let Page::DataPageV2 { num_nulls, num_rows, .. } = page {
+      line.push_str(&format!(", {} nulls, {} rows", num_nulls, num_rows));
+    }
+    if let Some(stats) = page.statistics() {
+      line.push_str(&format!(", statistics {}", stats));
+    }
+    println!("{}", line);
+    i += 1;
+  }
+}
@@ -36,20 +36,30 @@
 //! # Usage
 //!
 //! ```
-//! parquet-read <file-path> [num-records]
+//! parquet-read <file-path> [num-records] [--json] [--columns <names>]
+//!   [--offset <num-records>] [--limit <num-records>]
 //! ```
 //! where `file-path` is the path to a Parquet file and `num-records` is the optional
 //! numeric option that allows to specify number of records to read from a file.
 //! When not provided, all records are read.
 //!
-//! Note that `parquet-read` reads full file schema, no projection or filtering is
-//! applied.
+//! Options:
+//! - `--json` prints each record as a JSON object on a separate line, instead of the
+//! default text representation.
+//! - `--columns` specifies comma separated names of top-level fields to read, when not
+//! provided, full file schema is read.
+//! - `--offset` specifies number of records to skip.
+//! - `--limit` specifies maximum number of records to print, same as `num-records`.
 //!
 //! For example,
 //! ```
 //! parquet-read data/alltypes_plain.snappy.parquet
 //!
 //! parquet-read data/alltypes_plain.snappy.parquet 4
+//!
+//! parquet-read data/alltypes_plain.snappy.parquet --json --columns id,bool_col
+//!
+//! parquet-read data/alltypes_plain.snappy.parquet --offset 2 --limit 4
 //! ```
 
 extern crate parquet;
@@ -58,40 +68,116 @@ use std::env;
 use std::fs::File;
 use std::path::Path;
 use std::process;
+use std::rc::Rc;
 
 use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::schema::types::Type;
 
-fn main() {
-  let args: Vec<String> = env::args().collect();
-  if args.len() != 2 && args.len() != 3 {
-    println!("Usage: parquet-read <file-path> [num-records]");
-    process::exit(1);
+const USAGE: &str = "Usage: parquet-read <file-path> [num-records] [--json] \
+  [--columns <names>] [--offset <num-records>] [--limit <num-records>]";
+
+/// Options of the binary parsed from command line arguments.
+struct Options {
+  path: String,
+  json: bool,
+  columns: Option<String>,
+  offset: usize,
+  limit: Option<usize>
+}
+
+/// Parses command line arguments, returns `None` if arguments are invalid.
+fn parse_args(args: &[String]) -> Option<Options> {
+  let mut path = None;
+  let mut options = Options {
+    path: String::new(),
+    json: false,
+    columns: None,
+    offset: 0,
+    limit: None
+  };
+
+  let mut iter = args.iter();
+  while let Some(arg) = iter.next() {
+    match arg.as_str() {
+      "--json" => options.json = true,
+      "--columns" => options.columns = Some(iter.next()?.clone()),
+      "--offset" => options.offset = parse_num_records("--offset", iter.next()?),
+      "--limit" => options.limit = Some(parse_num_records("--limit", iter.next()?)),
+      arg if arg.starts_with("--") => return None,
+      arg if path.is_none() => path = Some(arg.to_owned()),
+      arg if options.limit.is_none() => {
+        options.limit = Some(parse_num_records("[num-records]", arg))
+      },
+      _ => return None
+    }
   }
+  options.path = path?;
+  Some(options)
+}
 
-  let mut num_records: Option<usize> = None;
-  if args.len() == 3 {
-    match args[2].parse() {
-      Ok(value) => num_records = Some(value),
-      Err(e) => panic!("Error when reading value for [num-records], {}", e)
+fn parse_num_records(name: &str, value: &str) -> usize {
+  match value.parse() {
+    Ok(value) => value,
+    Err(e) => panic!("Error when reading value for {}, {}", name, e)
+  }
+}
+
+/// Returns projection of top-level fields of `schema` with comma separated `names`.
+fn get_projection(schema: &Type, names: &str) -> Type {
+  let mut fields = Vec::new();
+  for name in names.split(',').map(|name| name.trim()).filter(|name| !name.is_empty()) {
+    match schema.get_fields().iter().find(|field| field.name() == name) {
+      Some(field) => fields.push(Rc::clone(field)),
+      None => panic!("Column {} is not found in the file schema", name)
     }
   }
+  match Type::group_type_builder(schema.name()).with_fields(&mut fields).build() {
+    Ok(projection) => projection,
+    Err(e) => panic!("Error when creating projection: {}", e)
+  }
+}
 
-  let path = Path::new(&args[1]);
-  let file = File::open(&path).unwrap();
-  let parquet_reader = SerializedFileReader::new(file).unwrap();
+fn main() {
+  let args: Vec<String> = env::args().skip(1).collect();
+  let options = match parse_args(&args) {
+    Some(options) => options,
+    None => {
+      println!("{}", USAGE);
+      process::exit(1);
+    }
+  };
 
-  // Use full schema as projected schema
-  let mut iter = parquet_reader.get_row_iter(None).unwrap();
+  let path = Path::new(&options.path);
+  let file = match File::open(&path) {
+    Err(e) => panic!("Error when opening file {}: {}", path.display(), e),
+    Ok(f) => f
+  };
+  let parquet_reader = match SerializedFileReader::new(file) {
+    Err(e) => panic!("Error when parsing Parquet file: {}", e),
+    Ok(reader) => reader
+  };
 
-  let mut start = 0;
-  let end = num_records.unwrap_or(0);
-  let all_records = num_records.is_none();
+  // Use full schema as projected schema, unless columns are specified
+  let projection = options.columns.as_ref().map(|names| {
+    get_projection(parquet_reader.metadata().file_metadata().schema(), names)
+  });
+  let mut iter = match parquet_reader.get_row_iter(projection) {
+    Err(e) => panic!("Error when reading records: {}", e),
+    Ok(iter) => iter.with_offset(options.offset)
+  };
+  if let Some(limit) = options.limit {
+    iter = iter.with_limit(limit);
+  }
 
-  while all_records || start < end {
-    match iter.next() {
-      Some(row) => println!("{}", row),
-      None => break
+  for row in iter {
+    let row = match row {
+      Err(e) => panic!("Error when reading record: {}", e),
+      Ok(row) => row
+    };
+    if options.json {
+      println!("{}", row.to_json());
+    } else {
+      println!("{}", row);
     }
-    start += 1;
   }
 }
@@ -0,0 +1,234 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Binary file to rewrite a Parquet file with different compression, encoding or row
+//! group size, optionally dropping some of the columns. Values are preserved exactly.
+//!
+//! # Install
+//!
+//! `parquet-rewrite` can be installed using `cargo`:
+//! ```
+//! cargo install parquet
+//! ```
+//! After this `parquet-rewrite` should be globally available:
+//! ```
+//! parquet-rewrite XYZ.parquet XYZ.zstd.parquet --compression zstd
+//! ```
+//!
+//! The binary can also be built from the source code and run as follows:
+//! ```
+//! cargo run --bin parquet-rewrite XYZ.parquet XYZ.zstd.parquet --compression zstd
+//! ```
+//!
+//! # Usage
+//!
+//! ```
+//! parquet-rewrite <input-path> <output-path> [--compression <codec>]
+//!   [--encoding <encoding>] [--no-dictionary] [--row-group-size <num-rows>]
+//!   [--drop-columns <names>] [--null-columns <names>] [--copy-column-chunks]
+//! ```
+//! where `input-path` is the path to a Parquet file to read and `output-path` is the
+//! path of the new file.
+//!
+//! Options:
+//! - `--compression` sets compression codec of all columns, e.g. `snappy`, `gzip` or
+//! `zstd`, default is `uncompressed`.
+//! - `--encoding` sets encoding of all columns when dictionary encoding is disabled or
+//! falls back, e.g. `plain` or `delta_binary_packed`. Delta encodings are written with
+//! writer version 2.0, i.e. data pages v2.
+//! - `--no-dictionary` disables dictionary encoding.
+//! - `--row-group-size` sets number of rows of each row group, when not provided, row
+//! groups of the input file are kept.
+//! - `--drop-columns` specifies comma separated fields to drop, nested fields are
+//! specified with dots, e.g. `a.b`.
+//! - `--null-columns` specifies comma separated optional leaf columns, whose values are
+//! replaced with nulls, e.g. to redact personal data.
+//! - `--copy-column-chunks` copies column chunks as is without decoding them when
+//! compression codec of the column chunk matches the new one and row groups are kept.
+//!
+//! For example,
+//! ```
+//! parquet-rewrite data/alltypes_plain.parquet out.parquet --compression gzip
+//!
+//! parquet-rewrite data/alltypes_plain.parquet out.parquet --row-group-size 2 \
+//!   --drop-columns id,bool_col
+//! ```
+
+extern crate parquet;
+
+use std::env;
+use std::fs::File;
+use std::path::Path;
+use std::process;
+use std::rc::Rc;
+
+use parquet::basic::{Compression, Encoding};
+use parquet::file::properties::{WriterProperties, WriterVersion};
+use parquet::file::rewrite::{rewrite_file, ColumnMask, RewriteOptions};
+
+const USAGE: &str = "Usage: parquet-rewrite <input-path> <output-path> \
+  [--compression <codec>] [--encoding <encoding>] [--no-dictionary] \
+  [--row-group-size <num-rows>] [--drop-columns <names>] [--null-columns <names>] \
+  [--copy-column-chunks]";
+
+const COMPRESSIONS: [Compression; 7] = [
+  Compression::UNCOMPRESSED,
+  Compression::SNAPPY,
+  Compression::GZIP,
+  Compression::LZO,
+  Compression::BROTLI,
+  Compression::LZ4,
+  Compression::ZSTD
+];
+
+const ENCODINGS: [Encoding; 5] = [
+  Encoding::PLAIN,
+  Encoding::RLE,
+  Encoding::DELTA_BINARY_PACKED,
+  Encoding::DELTA_LENGTH_BYTE_ARRAY,
+  Encoding::DELTA_BYTE_ARRAY
+];
+
+/// Options of the binary parsed from command line arguments.
+struct Options {
+  input: String,
+  output: String,
+  compression: Option<Compression>,
+  encoding: Option<Encoding>,
+  dictionary_enabled: bool,
+  row_group_size: Option<usize>,
+  dropped_columns: Vec<String>,
+  null_columns: Vec<String>,
+  copy_column_chunks: bool
+}
+
+/// Parses command line arguments, returns `None` if arguments are invalid.
+fn parse_args(args: &[String]) -> Option<Options> {
+  let mut paths = Vec::new();
+  let mut options = Options {
+    input: String::new(),
+    output: String::new(),
+    compression: None,
+    encoding: None,
+    dictionary_enabled: true,
+    row_group_size: None,
+    dropped_columns: Vec::new(),
+    null_columns: Vec::new(),
+    copy_column_chunks: false
+  };
+
+  let mut iter = args.iter();
+  while let Some(arg) = iter.next() {
+    match arg.as_str() {
+      "--compression" => {
+        let value = iter.next()?;
+        options.compression = Some(parse_enum("--compression", value, &COMPRESSIONS))
+      },
+      "--encoding" => {
+        let value = iter.next()?;
+        options.encoding = Some(parse_enum("--encoding", value, &ENCODINGS))
+      },
+      "--no-dictionary" => options.dictionary_enabled = false,
+      "--copy-column-chunks" => options.copy_column_chunks = true,
+      "--row-group-size" => {
+        let value = iter.next()?;
+        match value.parse() {
+          Ok(num_rows) => options.row_group_size = Some(num_rows),
+          Err(e) => panic!("Error when reading value for --row-group-size, {}", e)
+        }
+      },
+      "--drop-columns" => options.dropped_columns = parse_names(iter.next()?),
+      "--null-columns" => options.null_columns = parse_names(iter.next()?),
+      arg if arg.starts_with("--") => return None,
+      arg => paths.push(arg.to_owned())
+    }
+  }
+  if paths.len() != 2 {
+    return None;
+  }
+  options.output = paths.pop()?;
+  options.input = paths.pop()?;
+  Some(options)
+}
+
+/// Returns names of comma separated list `value`.
+fn parse_names(value: &str) -> Vec<String> {
+  value.split(',')
+    .map(|name| name.trim())
+    .filter(|name| !name.is_empty())
+    .map(|name| name.to_owned())
+    .collect()
+}
+
+/// Returns value of `values` with case-insensitive name `value`.
+fn parse_enum<T: ToString + Copy>(name: &str, value: &str, values: &[T]) -> T {
+  match values.iter().find(|v| v.to_string().eq_ignore_ascii_case(value)) {
+    Some(v) => *v,
+    None => {
+      let names: Vec<_> = values.iter().map(|v| v.to_string().to_lowercase()).collect();
+      panic!("Invalid value {} for {}, expected one of {}", value, name, names.join(", "))
+    }
+  }
+}
+
+fn main() {
+  let args: Vec<String> = env::args().skip(1).collect();
+  let options = match parse_args(&args) {
+    Some(options) => options,
+    None => {
+      println!("{}", USAGE);
+      process::exit(1);
+    }
+  };
+
+  let mut props = WriterProperties::builder()
+    .set_dictionary_enabled(options.dictionary_enabled);
+  if let Some(compression) = options.compression {
+    props = props.set_compression(compression);
+  }
+  if let Some(encoding) = options.encoding {
+    // Delta encodings are only written in writer version 2.0
+    if !WriterVersion::PARQUET_1_0.supports_encoding(encoding) {
+      props = props.set_writer_version(WriterVersion::PARQUET_2_0);
+    }
+    props = props.set_encoding(encoding);
+  }
+  let mut rewrite_options = RewriteOptions::new(Rc::new(props.build()))
+    .with_dropped_columns(options.dropped_columns)
+    .with_masked_columns(
+      options.null_columns.into_iter().map(|name| (name, ColumnMask::Null)).collect()
+    )
+    .with_copy_column_chunks(options.copy_column_chunks);
+  if let Some(row_group_size) = options.row_group_size {
+    rewrite_options = rewrite_options.with_row_group_size(row_group_size);
+  }
+
+  let input_path = Path::new(&options.input);
+  let input = match File::open(&input_path) {
+    Err(e) => panic!("Error when opening file {}: {}", input_path.display(), e),
+    Ok(f) => f
+  };
+  let output_path = Path::new(&options.output);
+  let output = match File::create(&output_path) {
+    Err(e) => panic!("Error when creating file {}: {}", output_path.display(), e),
+    Ok(f) => f
+  };
+  match rewrite_file(input, output, &rewrite_options) {
+    Err(e) => panic!("Error when rewriting Parquet file: {}", e),
+    Ok(num_rows) => println!("Rewrote {} rows into {}", num_rows, output_path.display())
+  }
+}
@@ -0,0 +1,184 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Binary file to quickly report row counts and sizes of one or many Parquet files.
+//! Only file footers are read, no pages are decoded.
+//!
+//! # Install
+//!
+//! `parquet-rowcount` can be installed using `cargo`:
+//! ```
+//! cargo install parquet
+//! ```
+//! After this `parquet-rowcount` should be globally available:
+//! ```
+//! parquet-rowcount XYZ.parquet
+//! ```
+//!
+//! The binary can also be built from the source code and run as follows:
+//! ```
+//! cargo run --bin parquet-rowcount XYZ.parquet
+//! ```
+//!
+//! # Usage
+//!
+//! ```
+//! parquet-rowcount [--columns] <file-path>...
+//! ```
+//! where `file-path` is the path to a Parquet file. Any number of paths can be
+//! provided, file names can contain `*` and `?` wildcards, e.g. `data/*.parquet`, which
+//! are expanded when the shell does not do it.
+//!
+//! For each file the tool prints number of rows, number of row groups and total
+//! compressed and uncompressed sizes of column chunks, followed by totals of all files.
+//! With `--columns` flag it also prints compressed and uncompressed sizes and
+//! compression ratio of each column, aggregated across all files.
+//!
+//! For example,
+//! ```
+//! parquet-rowcount data/alltypes_plain.parquet data/alltypes_plain.snappy.parquet
+//!
+//! parquet-rowcount --columns "data/*.parquet"
+//! ```
+
+extern crate parquet;
+
+use std::env;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::size_summary::SizeSummary;
+
+const USAGE: &str = "Usage: parquet-rowcount [--columns] <file-path>...";
+
+fn main() {
+  let args: Vec<String> = env::args().skip(1).collect();
+  let print_columns = args.iter().any(|arg| arg == "--columns");
+  let patterns: Vec<&String> = args.iter().filter(|arg| *arg != "--columns").collect();
+  if patterns.is_empty() {
+    println!("{}", USAGE);
+    process::exit(1);
+  }
+
+  let mut paths = Vec::new();
+  for pattern in patterns {
+    let matched = expand_pattern(pattern);
+    if matched.is_empty() {
+      panic!("No files match {}", pattern);
+    }
+    paths.extend(matched);
+  }
+
+  let mut total = SizeSummary::default();
+  for path in &paths {
+    let file = match File::open(path) {
+      Err(e) => panic!("Error when opening file {}: {}", path.display(), e),
+      Ok(f) => f
+    };
+    let reader = match SerializedFileReader::new(file) {
+      Err(e) => panic!("Error when parsing Parquet file {}: {}", path.display(), e),
+      Ok(reader) => reader
+    };
+    let summary = SizeSummary::from_metadata(&reader.metadata());
+    print_summary(&path.display().to_string(), &summary);
+    total.add(&summary);
+  }
+
+  if paths.len() > 1 {
+    print_summary(&format!("total ({} files)", paths.len()), &total);
+  }
+  if print_columns {
+    println!("");
+    for column in total.columns() {
+      println!(
+        "{}: compressed {}, uncompressed {}, ratio {}",
+        column.path().string(),
+        column.compressed_size(),
+        column.uncompressed_size(),
+        format_ratio(column.compression_ratio())
+      );
+    }
+  }
+}
+
+fn print_summary(name: &str, summary: &SizeSummary) {
+  println!(
+    "{}: {} rows, {} row groups, compressed {}, uncompressed {}, ratio {}",
+    name,
+    summary.num_rows(),
+    summary.num_row_groups(),
+    summary.compressed_size(),
+    summary.uncompressed_size(),
+    format_ratio(summary.compression_ratio())
+  );
+}
+
+fn format_ratio(ratio: Option<f64>) -> String {
+  match ratio {
+    Some(ratio) => format!("{:.2}", ratio),
+    None => "N/A".to_owned()
+  }
+}
+
+/// Expands `*` and `?` wildcards in the file name of `pattern`, paths are sorted.
+/// Patterns without wildcards are returned as is.
+fn expand_pattern(pattern: &str) -> Vec<PathBuf> {
+  let path = Path::new(pattern);
+  let file_name = match path.file_name().and_then(|name| name.to_str()) {
+    Some(name) if name.contains('*') || name.contains('?') => name,
+    _ => return vec![path.to_path_buf()]
+  };
+  let dir = match path.parent() {
+    Some(dir) if !dir.as_os_str().is_empty() => dir,
+    _ => Path::new(".")
+  };
+  let entries = match fs::read_dir(dir) {
+    Err(e) => panic!("Error when reading directory {}: {}", dir.display(), e),
+    Ok(entries) => entries
+  };
+
+  let mut paths: Vec<PathBuf> = entries
+    .filter_map(|entry| entry.ok())
+    .filter(|entry| entry.path().is_file())
+    .filter(|entry| {
+      let name = entry.file_name();
+      name.to_str().map_or(false, |name| {
+        wildcard_match(file_name.as_bytes(), name.as_bytes())
+      })
+    })
+    .map(|entry| entry.path())
+    .collect();
+  paths.sort();
+  paths
+}
+
+/// Returns `true` if `name` matches `pattern`, where `*` matches any sequence of
+/// characters and `?` matches any single character.
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+  match (pattern.first(), name.first()) {
+    (None, None) => true,
+    (Some(b'*'), _) => {
+      wildcard_match(&pattern[1..], name) ||
+        (!name.is_empty() && wildcard_match(pattern, &name[1..]))
+    },
+    (Some(b'?'), Some(_)) => wildcard_match(&pattern[1..], &name[1..]),
+    (Some(p), Some(n)) if p == n => wildcard_match(&pattern[1..], &name[1..]),
+    _ => false
+  }
+}
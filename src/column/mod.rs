@@ -67,7 +67,7 @@
 //! let mut row_group_writer = writer.next_row_group().unwrap();
 //! while let Some(mut col_writer) = row_group_writer.next_column().unwrap() {
 //!   match col_writer {
-//!     // You can also use `get_typed_column_writer` method to extract typed writer.
+//!     // You can also use `get_typed` method to extract typed writer.
 //!     ColumnWriter::Int32ColumnWriter(ref mut typed_writer) => {
 //!       typed_writer.write_batch(
 //!         &[1, 2, 3],
@@ -100,7 +100,7 @@
 //!   for j in 0..row_group_metadata.num_columns() {
 //!     let mut column_reader = row_group_reader.get_column_reader(j).unwrap();
 //!     match column_reader {
-//!       // You can also use `get_typed_column_reader` method to extract typed reader.
+//!       // You can also use `get_typed` method to extract typed reader.
 //!       ColumnReader::Int32ColumnReader(ref mut typed_reader) => {
 //!         res = typed_reader.read_batch(
 //!           8, // batch size
@@ -122,4 +122,5 @@
 
 pub mod page;
 pub mod reader;
+pub mod selection;
 pub mod writer;
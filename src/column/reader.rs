@@ -17,18 +17,23 @@
 
 //! Contains column reader API.
 
+use std::any::Any;
 use std::cmp::{max, min};
 use std::collections::HashMap;
 use std::mem;
+use std::sync::Arc;
 
 use super::page::{Page, PageReader};
+use super::selection::RowSelection;
 use basic::*;
 use data_type::*;
-use encodings::decoding::{get_decoder, Decoder, PlainDecoder, DictDecoder};
+use encodings::decoding::{Decoder, DecoderFactory};
 use encodings::levels::LevelDecoder;
-use errors::{Result, ParquetError};
+use errors::{ErrorContext, Result, ParquetError};
+use file::dictionary_cache::DictionaryCachePtr;
+use file::metrics::ReaderMetricsPtr;
 use schema::types::ColumnDescPtr;
-use util::memory::ByteBufferPtr;
+use util::memory::{ByteBufferPtr, MemTracker, MemTrackerPtr};
 
 /// Column reader for a Parquet type.
 pub enum ColumnReader {
@@ -42,6 +47,99 @@ pub enum ColumnReader {
   FixedLenByteArrayColumnReader(ColumnReaderImpl<FixedLenByteArrayType>)
 }
 
+impl ColumnReader {
+  /// Returns physical type of values of this column reader.
+  pub fn physical_type(&self) -> Type {
+    match *self {
+      ColumnReader::BoolColumnReader(_) => Type::BOOLEAN,
+      ColumnReader::Int32ColumnReader(_) => Type::INT32,
+      ColumnReader::Int64ColumnReader(_) => Type::INT64,
+      ColumnReader::Int96ColumnReader(_) => Type::INT96,
+      ColumnReader::FloatColumnReader(_) => Type::FLOAT,
+      ColumnReader::DoubleColumnReader(_) => Type::DOUBLE,
+      ColumnReader::ByteArrayColumnReader(_) => Type::BYTE_ARRAY,
+      ColumnReader::FixedLenByteArrayColumnReader(_) => Type::FIXED_LEN_BYTE_ARRAY
+    }
+  }
+
+  /// Returns typed column reader for the type `T`, or error if `T` does not match
+  /// physical type of this column reader. This is a checked alternative to
+  /// [`get_typed_column_reader`](fn.get_typed_column_reader.html).
+  pub fn get_typed<T: DataType>(&mut self) -> Result<&mut ColumnReaderImpl<T>> {
+    let physical_type = self.physical_type();
+    let reader: &mut Any = match *self {
+      ColumnReader::BoolColumnReader(ref mut r) => r,
+      ColumnReader::Int32ColumnReader(ref mut r) => r,
+      ColumnReader::Int64ColumnReader(ref mut r) => r,
+      ColumnReader::Int96ColumnReader(ref mut r) => r,
+      ColumnReader::FloatColumnReader(ref mut r) => r,
+      ColumnReader::DoubleColumnReader(ref mut r) => r,
+      ColumnReader::ByteArrayColumnReader(ref mut r) => r,
+      ColumnReader::FixedLenByteArrayColumnReader(ref mut r) => r
+    };
+    reader.downcast_mut::<ColumnReaderImpl<T>>().ok_or_else(|| general_err!(
+      "Cannot access column reader of {} type as {}",
+      physical_type,
+      T::get_physical_type()
+    ))
+  }
+
+  /// Converts this column reader into typed column reader for the type `T`, or returns
+  /// error if `T` does not match physical type of this column reader.
+  pub fn into_typed<T: DataType>(mut self) -> Result<ColumnReaderImpl<T>> {
+    self.get_typed::<T>()?;
+    Ok(get_typed_column_reader(self))
+  }
+
+  /// Enables metrics for the underlying column reader, see
+  /// [`ColumnReaderImpl::with_metrics`](struct.ColumnReaderImpl.html).
+  pub fn with_metrics(self, metrics: ReaderMetricsPtr) -> Self {
+    match self {
+      ColumnReader::BoolColumnReader(r) =>
+        ColumnReader::BoolColumnReader(r.with_metrics(metrics)),
+      ColumnReader::Int32ColumnReader(r) =>
+        ColumnReader::Int32ColumnReader(r.with_metrics(metrics)),
+      ColumnReader::Int64ColumnReader(r) =>
+        ColumnReader::Int64ColumnReader(r.with_metrics(metrics)),
+      ColumnReader::Int96ColumnReader(r) =>
+        ColumnReader::Int96ColumnReader(r.with_metrics(metrics)),
+      ColumnReader::FloatColumnReader(r) =>
+        ColumnReader::FloatColumnReader(r.with_metrics(metrics)),
+      ColumnReader::DoubleColumnReader(r) =>
+        ColumnReader::DoubleColumnReader(r.with_metrics(metrics)),
+      ColumnReader::ByteArrayColumnReader(r) =>
+        ColumnReader::ByteArrayColumnReader(r.with_metrics(metrics)),
+      ColumnReader::FixedLenByteArrayColumnReader(r) =>
+        ColumnReader::FixedLenByteArrayColumnReader(r.with_metrics(metrics))
+    }
+  }
+
+  /// Enables dictionary cache for the underlying column reader, see
+  /// [`ColumnReaderImpl::with_dictionary_cache`](struct.ColumnReaderImpl.html).
+  pub fn with_dictionary_cache(self, cache: DictionaryCachePtr, column: usize) -> Self {
+    match self {
+      ColumnReader::BoolColumnReader(r) =>
+        ColumnReader::BoolColumnReader(r.with_dictionary_cache(cache, column)),
+      ColumnReader::Int32ColumnReader(r) =>
+        ColumnReader::Int32ColumnReader(r.with_dictionary_cache(cache, column)),
+      ColumnReader::Int64ColumnReader(r) =>
+        ColumnReader::Int64ColumnReader(r.with_dictionary_cache(cache, column)),
+      ColumnReader::Int96ColumnReader(r) =>
+        ColumnReader::Int96ColumnReader(r.with_dictionary_cache(cache, column)),
+      ColumnReader::FloatColumnReader(r) =>
+        ColumnReader::FloatColumnReader(r.with_dictionary_cache(cache, column)),
+      ColumnReader::DoubleColumnReader(r) =>
+        ColumnReader::DoubleColumnReader(r.with_dictionary_cache(cache, column)),
+      ColumnReader::ByteArrayColumnReader(r) =>
+        ColumnReader::ByteArrayColumnReader(r.with_dictionary_cache(cache, column)),
+      ColumnReader::FixedLenByteArrayColumnReader(r) => {
+        let r = r.with_dictionary_cache(cache, column);
+        ColumnReader::FixedLenByteArrayColumnReader(r)
+      }
+    }
+  }
+}
+
 /// Gets a specific column reader corresponding to column descriptor `col_descr`. The
 /// column reader will read from pages in `col_page_reader`.
 pub fn get_column_reader(
@@ -71,20 +169,30 @@ pub fn get_column_reader(
 /// Gets a typed column reader for the specific type `T`, by "up-casting" `col_reader` of
 /// non-generic type to a generic column reader type `ColumnReaderImpl`.
 ///
-/// NOTE: the caller MUST guarantee that the actual enum value for `col_reader` matches
-/// the type `T`. Otherwise, disastrous consequence could happen.
+/// Panics if the actual enum value for `col_reader` does not match the type `T`, see
+/// [`ColumnReader::into_typed`](enum.ColumnReader.html#method.into_typed) for a
+/// variant that returns error instead.
 pub fn get_typed_column_reader<T: DataType>(
   col_reader: ColumnReader
 ) -> ColumnReaderImpl<T> {
-  match col_reader {
-    ColumnReader::BoolColumnReader(r) => unsafe { mem::transmute(r) },
-    ColumnReader::Int32ColumnReader(r) => unsafe { mem::transmute(r) },
-    ColumnReader::Int64ColumnReader(r) => unsafe { mem::transmute(r) },
-    ColumnReader::Int96ColumnReader(r) => unsafe { mem::transmute(r) },
-    ColumnReader::FloatColumnReader(r) => unsafe { mem::transmute(r) },
-    ColumnReader::DoubleColumnReader(r) => unsafe { mem::transmute(r) },
-    ColumnReader::ByteArrayColumnReader(r) => unsafe { mem::transmute(r) },
-    ColumnReader::FixedLenByteArrayColumnReader(r) => unsafe { mem::transmute(r) }
+  let physical_type = col_reader.physical_type();
+  let reader: Box<Any> = match col_reader {
+    ColumnReader::BoolColumnReader(r) => Box::new(r),
+    ColumnReader::Int32ColumnReader(r) => Box::new(r),
+    ColumnReader::Int64ColumnReader(r) => Box::new(r),
+    ColumnReader::Int96ColumnReader(r) => Box::new(r),
+    ColumnReader::FloatColumnReader(r) => Box::new(r),
+    ColumnReader::DoubleColumnReader(r) => Box::new(r),
+    ColumnReader::ByteArrayColumnReader(r) => Box::new(r),
+    ColumnReader::FixedLenByteArrayColumnReader(r) => Box::new(r)
+  };
+  match reader.downcast::<ColumnReaderImpl<T>>() {
+    Ok(reader) => *reader,
+    Err(_) => panic!(
+      "Cannot convert column reader of {} type into {}",
+      physical_type,
+      T::get_physical_type()
+    )
   }
 }
 
@@ -103,8 +211,27 @@ pub struct ColumnReaderImpl<T: DataType> {
   // so far.
   num_decoded_values: u32,
 
+  // The number of rows stored in the data page, only known for data page v2.
+  num_buffered_rows: Option<u32>,
+
   // Cache of decoders for existing encodings
-  decoders: HashMap<Encoding, Box<Decoder<T>>>
+  decoders: HashMap<Encoding, Box<Decoder<T>>>,
+
+  // Tracks memory allocated by decoders
+  mem_tracker: MemTrackerPtr,
+
+  // Levels and values decoded by `read_records`, that belong to records which have not
+  // been returned yet
+  pending: PendingRecords<T>,
+
+  // Metrics to update, if enabled
+  metrics: Option<ReaderMetricsPtr>,
+
+  // Ordinal of the current page in the column chunk, including dictionary page
+  page_ordinal: Option<usize>,
+
+  // Cache of decoded dictionaries and index of this column in the row group, if enabled
+  dictionary_cache: Option<(DictionaryCachePtr, usize)>
 }
 
 impl<T: DataType> ColumnReaderImpl<T> {
@@ -118,10 +245,52 @@ impl<T: DataType> ColumnReaderImpl<T> {
       current_encoding: None,
       num_buffered_values: 0,
       num_decoded_values: 0,
-      decoders: HashMap::new()
+      num_buffered_rows: None,
+      decoders: HashMap::new(),
+      mem_tracker: Arc::new(MemTracker::new()),
+      pending: PendingRecords::new(),
+      metrics: None,
+      page_ordinal: None,
+      dictionary_cache: None
     }
   }
 
+  /// Sets memory tracker for this column reader, e.g. a tracker with a limit, see
+  /// [`MemTracker::with_limit`](../../memory/struct.MemTracker.html).
+  /// Reads that would exceed the limit return `ParquetError::ResourceExhausted` error
+  /// with context of the column.
+  pub fn with_mem_tracker(mut self, mem_tracker: MemTrackerPtr) -> Self {
+    self.mem_tracker = mem_tracker;
+    self
+  }
+
+  /// Returns memory tracker for this column reader.
+  /// Tracks memory allocated by decoders, such as dictionaries and decoded lengths.
+  pub fn mem_tracker(&self) -> MemTrackerPtr {
+    self.mem_tracker.clone()
+  }
+
+  /// Enables metrics for this column reader: data pages decoded and data pages skipped
+  /// by [`skip_records`](#method.skip_records) are recorded.
+  pub fn with_metrics(mut self, metrics: ReaderMetricsPtr) -> Self {
+    self.metrics = Some(metrics);
+    self
+  }
+
+  /// Enables dictionary cache of the row group for this column reader, where `column`
+  /// is index of the column in the row group. Dictionary of the column chunk is taken
+  /// from `cache` instead of decoding dictionary page, if cached, otherwise decoded
+  /// dictionary is cached, see
+  /// [`DictionaryCache`](../../file/dictionary_cache/struct.DictionaryCache.html).
+  pub fn with_dictionary_cache(
+    mut self,
+    cache: DictionaryCachePtr,
+    column: usize
+  ) -> Self {
+    self.dictionary_cache = Some((cache, column));
+    self
+  }
+
   /// Reads a batch of values of at most `batch_size`.
   ///
   /// This will try to read from the row group, and fills up at most `batch_size` values
@@ -161,6 +330,38 @@ impl<T: DataType> ColumnReaderImpl<T> {
       batch_size = min(batch_size, levels.len());
     }
 
+    // Return levels and values left in the pending buffer by `skip_records` first
+    let num_pending = min(batch_size, self.pending.def_levels.len());
+    if num_pending > 0 {
+      let max_def_level = self.descr.max_def_level();
+      let max_rep_level = self.descr.max_rep_level();
+      let num_values = if max_def_level > 0 {
+        self.pending.def_levels[0..num_pending].iter()
+          .filter(|level| **level == max_def_level)
+          .count()
+      } else {
+        num_pending
+      };
+
+      if let Some(ref mut levels) = def_levels {
+        if max_def_level > 0 {
+          levels[0..num_pending]
+            .copy_from_slice(&self.pending.def_levels[0..num_pending]);
+          levels_read = num_pending;
+        }
+      }
+      if let Some(ref mut levels) = rep_levels {
+        if max_rep_level > 0 {
+          levels[0..num_pending]
+            .copy_from_slice(&self.pending.rep_levels[0..num_pending]);
+          levels_read = num_pending;
+        }
+      }
+      values[0..num_values].clone_from_slice(&self.pending.values[0..num_values]);
+      self.pending.consume(num_pending, num_values);
+      values_read = num_values;
+    }
+
     // Read exhaustively all pages until we read all batch_size values/levels
     // or there are no more values/levels to read.
     while max(values_read, levels_read) < batch_size {
@@ -249,6 +450,325 @@ impl<T: DataType> ColumnReaderImpl<T> {
     Ok((values_read, levels_read))
   }
 
+  /// Reads at most `num_records` complete records.
+  ///
+  /// Unlike [`read_batch`](#method.read_batch), this never stops in the middle of a
+  /// record, i.e. the levels returned always end right before a repetition level of 0.
+  /// Records can span multiple batches and pages; levels and values of a record that
+  /// is not complete yet are buffered internally until the next call.
+  ///
+  /// For non-repeated columns every level is a record. Definition and repetition levels
+  /// are written into `def_levels` and `rep_levels`, if provided; they are always read
+  /// internally to find record boundaries. It will stop when `num_records` records are
+  /// read, the column chunk is depleted, or the next record does not fit into the
+  /// provided slices.
+  ///
+  /// This method should not be mixed with `read_batch` for the same reader.
+  ///
+  /// Returns a tuple of the number of records read, the number of values read and
+  /// the number of levels written into `def_levels`/`rep_levels`. Returns error if a
+  /// single record does not fit into the provided slices.
+  pub fn read_records(
+    &mut self,
+    num_records: usize,
+    mut def_levels: Option<&mut [i16]>,
+    mut rep_levels: Option<&mut [i16]>,
+    values: &mut [T::T]
+  ) -> Result<(usize, usize, usize)> {
+    let mut records_read = 0;
+    let mut values_read = 0;
+    let mut levels_read = 0;
+
+    let has_levels = def_levels.is_some() || rep_levels.is_some();
+    let mut levels_capacity = ::std::usize::MAX;
+    if let Some(ref levels) = def_levels {
+      levels_capacity = min(levels_capacity, levels.len());
+    }
+    if let Some(ref levels) = rep_levels {
+      levels_capacity = min(levels_capacity, levels.len());
+    }
+
+    let mut at_end = false;
+    while records_read < num_records {
+      // Find complete records in pending levels that fit into the output slices
+      let (num_levels, num_values, num_complete, is_full) = self.pending.complete_records(
+        num_records - records_read,
+        if has_levels { levels_capacity - levels_read } else { ::std::usize::MAX },
+        values.len() - values_read,
+        self.descr.max_def_level(),
+        self.descr.max_rep_level(),
+        at_end
+      );
+
+      if num_complete > 0 {
+        if let Some(ref mut levels) = def_levels {
+          if self.descr.max_def_level() > 0 {
+            levels[levels_read..levels_read + num_levels]
+              .copy_from_slice(&self.pending.def_levels[0..num_levels]);
+          }
+        }
+        if let Some(ref mut levels) = rep_levels {
+          if self.descr.max_rep_level() > 0 {
+            levels[levels_read..levels_read + num_levels]
+              .copy_from_slice(&self.pending.rep_levels[0..num_levels]);
+          }
+        }
+        values[values_read..values_read + num_values]
+          .clone_from_slice(&self.pending.values[0..num_values]);
+        self.pending.consume(num_levels, num_values);
+
+        records_read += num_complete;
+        values_read += num_values;
+        if has_levels {
+          levels_read += num_levels;
+        }
+      }
+
+      if is_full {
+        if records_read == 0 {
+          if has_levels {
+            return Err(general_err!(
+              "Record does not fit into provided buffers of {} levels and {} values",
+              levels_capacity, values.len()));
+          }
+          return Err(general_err!(
+            "Record does not fit into provided buffers of {} values", values.len()));
+        }
+        break;
+      }
+      if records_read == num_records || at_end {
+        break;
+      }
+
+      // Buffer more levels and values from the current or next page
+      if !self.has_next()? {
+        at_end = true;
+        continue;
+      }
+      self.read_pending_levels()?;
+    }
+
+    Ok((records_read, values_read, levels_read))
+  }
+
+  /// Skips at most `num_records` records without returning their values.
+  ///
+  /// Pages that only contain skipped records are not decoded, which is based on the
+  /// number of values in page headers for non-repeated columns, and on the number of
+  /// rows of data pages v2 for repeated columns. Otherwise levels are decoded to find
+  /// record boundaries, and values are skipped with `Decoder::skip`.
+  ///
+  /// Can be mixed with `read_records` and `read_batch`. Returns the actual number of
+  /// records skipped, which is less than `num_records` only if the column chunk is
+  /// depleted.
+  pub fn skip_records(&mut self, num_records: usize) -> Result<usize> {
+    // Skip complete records that have already been decoded
+    let (num_levels, num_values, mut records_skipped, _) = self.pending.complete_records(
+      num_records,
+      ::std::usize::MAX,
+      ::std::usize::MAX,
+      self.descr.max_def_level(),
+      self.descr.max_rep_level(),
+      false
+    );
+    self.pending.consume(num_levels, num_values);
+
+    // Levels of the record that is being skipped, when pending levels are non-empty,
+    // they contain the beginning of such record
+    let mut in_record = false;
+    if records_skipped < num_records && !self.pending.def_levels.is_empty() {
+      in_record = true;
+      records_skipped += 1;
+      self.pending.clear();
+    }
+
+    while records_skipped < num_records || in_record {
+      if !self.has_next()? {
+        break;
+      }
+
+      let levels_left = (self.num_buffered_values - self.num_decoded_values) as usize;
+      let records_left = num_records - records_skipped;
+
+      // Skip the rest of the page without decoding, if possible
+      if self.descr.max_rep_level() == 0 && levels_left <= records_left {
+        if self.num_decoded_values == 0 {
+          self.record_page_skipped();
+        }
+        self.num_decoded_values = self.num_buffered_values;
+        records_skipped += levels_left;
+        continue;
+      }
+      if let Some(num_rows) = self.num_buffered_rows {
+        // Data page v2 always starts with a new record
+        if self.num_decoded_values == 0 && num_rows as usize <= records_left {
+          self.record_page_skipped();
+          self.num_decoded_values = self.num_buffered_values;
+          records_skipped += num_rows as usize;
+          in_record = false;
+          continue;
+        }
+      }
+
+      // Decode levels to find the end of skipped records
+      self.read_pending_levels_only()?;
+      let mut end = 0;
+      let num_levels = self.pending.def_levels.len();
+      while end < num_levels {
+        if self.descr.max_rep_level() == 0 || self.pending.rep_levels[end] == 0 {
+          if records_skipped == num_records {
+            in_record = false;
+            break;
+          }
+          records_skipped += 1;
+          in_record = self.descr.max_rep_level() > 0;
+        }
+        end += 1;
+      }
+
+      let max_def_level = self.descr.max_def_level();
+      let values_in_range = |levels: &[i16]| if max_def_level > 0 {
+        levels.iter().filter(|level| **level == max_def_level).count()
+      } else {
+        levels.len()
+      };
+      let values_to_skip = values_in_range(&self.pending.def_levels[0..end]);
+      if self.skip_values(values_to_skip)? != values_to_skip {
+        return Err(eof_err!("Not enough values to skip"));
+      }
+
+      // Decode values of the levels that are not skipped
+      let values_to_read = values_in_range(&self.pending.def_levels[end..]);
+      self.pending.consume(end, 0);
+      self.read_pending_values(values_to_read)?;
+    }
+
+    Ok(records_skipped)
+  }
+
+  /// Reads records selected by `selection`, skipped records are not decoded where
+  /// possible, see [`skip_records`](#method.skip_records).
+  ///
+  /// Selection is applied starting from the current record. Provided slices must be
+  /// large enough to hold all selected records; reading stops early if the column chunk
+  /// is depleted.
+  ///
+  /// Returns a tuple of the number of records read, the number of values read and
+  /// the number of levels written into `def_levels`/`rep_levels`.
+  pub fn read_selected_records(
+    &mut self,
+    selection: &RowSelection,
+    mut def_levels: Option<&mut [i16]>,
+    mut rep_levels: Option<&mut [i16]>,
+    values: &mut [T::T]
+  ) -> Result<(usize, usize, usize)> {
+    let mut records_read = 0;
+    let mut values_read = 0;
+    let mut levels_read = 0;
+
+    for selector in selection.selectors() {
+      if selector.skip {
+        if self.skip_records(selector.row_count)? < selector.row_count {
+          break;
+        }
+        continue;
+      }
+
+      let mut selected_read = 0;
+      while selected_read < selector.row_count {
+        let (records, num_values, num_levels) = self.read_records(
+          selector.row_count - selected_read,
+          def_levels.as_mut().map(|levels| &mut levels[levels_read..]),
+          rep_levels.as_mut().map(|levels| &mut levels[levels_read..]),
+          &mut values[values_read..]
+        )?;
+        if records == 0 {
+          return Ok((records_read + selected_read, values_read, levels_read));
+        }
+        selected_read += records;
+        values_read += num_values;
+        levels_read += num_levels;
+      }
+      records_read += selected_read;
+    }
+
+    Ok((records_read, values_read, levels_read))
+  }
+
+  /// Records data page skipped without decoding, if metrics are enabled.
+  fn record_page_skipped(&self) {
+    if let Some(ref metrics) = self.metrics {
+      metrics.record_page_skipped();
+    }
+  }
+
+  /// Reads levels and values of the current page into pending records buffer.
+  fn read_pending_levels(&mut self) -> Result<()> {
+    let num_values = self.read_pending_levels_only()?;
+    self.read_pending_values(num_values)
+  }
+
+  /// Reads levels of the current page into pending records buffer.
+  /// Returns the number of values that correspond to the levels read.
+  fn read_pending_levels_only(&mut self) -> Result<usize> {
+    let batch_size = min(
+      (self.num_buffered_values - self.num_decoded_values) as usize,
+      RECORDS_BATCH_SIZE
+    );
+    let start = self.pending.def_levels.len();
+    self.pending.def_levels.resize(start + batch_size, 0);
+    self.pending.rep_levels.resize(start + batch_size, 0);
+
+    let mut num_levels = batch_size;
+    let mut num_values = batch_size;
+    if self.descr.max_def_level() > 0 {
+      let def_levels = &mut self.pending.def_levels[start..];
+      num_levels = self.def_level_decoder.as_mut()
+        .expect("def_level_decoder be set")
+        .get(def_levels)?;
+      let max_def_level = self.descr.max_def_level();
+      num_values = def_levels[0..num_levels].iter()
+        .filter(|level| **level == max_def_level)
+        .count();
+    }
+    if self.descr.max_rep_level() > 0 {
+      let num_rep_levels = self.rep_level_decoder.as_mut()
+        .expect("rep_level_decoder be set")
+        .get(&mut self.pending.rep_levels[start..])?;
+      if self.descr.max_def_level() > 0 && num_rep_levels != num_levels {
+        return Err(general_err!(
+          "Number of decoded rep / def levels did not match: {} != {}",
+          num_rep_levels, num_levels));
+      }
+      num_levels = num_rep_levels;
+    }
+    if num_levels == 0 {
+      return Err(eof_err!("Not enough levels to decode"));
+    }
+    self.pending.def_levels.truncate(start + num_levels);
+    self.pending.rep_levels.truncate(start + num_levels);
+    if self.descr.max_def_level() == 0 {
+      num_values = num_levels;
+    }
+
+    self.num_decoded_values += num_levels as u32;
+    Ok(num_values)
+  }
+
+  /// Decodes `num_values` values into pending records buffer.
+  fn read_pending_values(&mut self, num_values: usize) -> Result<()> {
+    let mut values = mem::replace(&mut self.pending.values, vec![]);
+    let values_start = values.len();
+    values.resize(values_start + num_values, T::T::default());
+    let values_read = self.read_values(&mut values[values_start..])?;
+    self.pending.values = values;
+    if values_read != num_values {
+      return Err(eof_err!("Expected to decode {} values, found {}",
+        num_values, values_read));
+    }
+    Ok(())
+  }
+
   /// Reads a new page and set up the decoders for levels, values or dictionary.
   /// Returns false if there's no page left.
   fn read_new_page(&mut self) -> Result<bool> {
@@ -260,6 +780,7 @@ impl<T: DataType> ColumnReaderImpl<T> {
           return Ok(false)
         },
         Some(current_page) => {
+          self.page_ordinal = Some(self.page_ordinal.map(|page| page + 1).unwrap_or(0));
           match current_page {
             // 1. Dictionary page: configure dictionary for this page.
             p @ Page::DictionaryPage { .. } => {
@@ -275,8 +796,13 @@ impl<T: DataType> ColumnReaderImpl<T> {
               rep_level_encoding,
               statistics: _
             } => {
+              trace_span!("decode_page", num_values = num_values);
               self.num_buffered_values = num_values;
               self.num_decoded_values = 0;
+              self.num_buffered_rows = None;
+              if let Some(ref metrics) = self.metrics {
+                metrics.record_data_page_loaded();
+              }
 
               let mut buffer_ptr = buf;
 
@@ -309,14 +835,19 @@ impl<T: DataType> ColumnReaderImpl<T> {
               num_values,
               encoding,
               num_nulls: _,
-              num_rows: _,
+              num_rows,
               def_levels_byte_len,
               rep_levels_byte_len,
               is_compressed: _,
               statistics: _
             } => {
+              trace_span!("decode_page", num_values = num_values, num_rows = num_rows);
               self.num_buffered_values = num_values;
               self.num_decoded_values = 0;
+              self.num_buffered_rows = Some(num_rows);
+              if let Some(ref metrics) = self.metrics {
+                metrics.record_data_page_loaded();
+              }
 
               let mut offset = 0;
 
@@ -371,7 +902,7 @@ impl<T: DataType> ColumnReaderImpl<T> {
         // Search cache for data page decoder
         if !self.decoders.contains_key(&encoding) {
           // Initialize decoder for this page
-          let data_decoder = get_decoder::<T>(self.descr.clone(), encoding)?;
+          let data_decoder = self.decoder_factory().get_decoder(encoding)?;
           self.decoders.insert(encoding, data_decoder);
         }
         self.decoders.get_mut(&encoding).unwrap()
@@ -388,7 +919,7 @@ impl<T: DataType> ColumnReaderImpl<T> {
         self.num_buffered_values == self.num_decoded_values {
         // TODO: should we return false if read_new_page() = true and
         // num_buffered_values = 0?
-      if !self.read_new_page()? {
+      if !self.read_new_page().map_err(|e| e.with_context(self.error_context()))? {
         Ok(false)
       } else {
         Ok(self.num_buffered_values != 0)
@@ -411,53 +942,175 @@ impl<T: DataType> ColumnReaderImpl<T> {
   }
 
   #[inline]
-  fn read_values(&mut self, buffer: &mut [T::T]) -> Result<usize> {
+  fn skip_values(&mut self, num_values: usize) -> Result<usize> {
     let encoding = self.current_encoding.expect("current_encoding should be set");
     let current_decoder = self.decoders
       .get_mut(&encoding)
       .expect(format!("decoder for encoding {} should be set", encoding).as_str());
-    current_decoder.get(buffer)
+    let res = current_decoder.skip(num_values);
+    res.map_err(|e| e.with_context(self.error_context()))
   }
 
   #[inline]
-  fn configure_dictionary(&mut self, page: Page) -> Result<bool> {
-    let mut encoding = page.encoding();
-    if encoding == Encoding::PLAIN || encoding == Encoding::PLAIN_DICTIONARY {
-      encoding = Encoding::RLE_DICTIONARY
+  fn read_values(&mut self, buffer: &mut [T::T]) -> Result<usize> {
+    trace_fine_span!("decode_values", batch_size = buffer.len());
+    let encoding = self.current_encoding.expect("current_encoding should be set");
+    let current_decoder = self.decoders
+      .get_mut(&encoding)
+      .expect(format!("decoder for encoding {} should be set", encoding).as_str());
+    let res = current_decoder.get(buffer);
+    res.map_err(|e| e.with_context(self.error_context()))
+  }
+
+  /// Returns column path and ordinal of the current page to add to errors.
+  fn error_context(&self) -> ErrorContext {
+    let context = ErrorContext::new().with_column_path(self.descr.path().string());
+    match self.page_ordinal {
+      Some(page) => context.with_page(page),
+      None => context
     }
+  }
 
-    if self.decoders.contains_key(&encoding) {
+  /// Returns factory of decoders for values of this column.
+  fn decoder_factory(&self) -> DecoderFactory<T> {
+    DecoderFactory::new(self.descr.clone(), self.mem_tracker.clone())
+  }
+
+  #[inline]
+  fn configure_dictionary(&mut self, page: Page) -> Result<bool> {
+    if self.decoders.contains_key(&Encoding::RLE_DICTIONARY) {
       return Err(general_err!("Column cannot have more than one dictionary"))
     }
 
-    if encoding == Encoding::RLE_DICTIONARY {
-      let mut dictionary = PlainDecoder::<T>::new(self.descr.type_length());
-      let num_values = page.num_values();
-      dictionary.set_data(page.buffer().clone(), num_values as usize)?;
-
-      let mut decoder = DictDecoder::new();
-      decoder.set_dict(Box::new(dictionary))?;
-      self.decoders.insert(encoding, Box::new(decoder));
-      Ok(true)
-    } else {
-      Err(nyi_err!("Invalid/Unsupported encoding type for dictionary: {}", encoding))
-    }
+    let factory = self.decoder_factory();
+    let cached = match self.dictionary_cache {
+      Some((ref cache, column)) => cache.get::<T>(column),
+      None => None
+    };
+    let decoder = match cached {
+      Some(values) => {
+        let mut decoder = factory.new_dict_decoder();
+        decoder.set_dict_values(values);
+        decoder
+      },
+      None => {
+        let num_values = page.num_values();
+        trace_span!("decode_page", num_values = num_values, dictionary = true);
+        let decoder = factory.get_dict_decoder(
+          page.encoding(), page.buffer().clone(), num_values as usize)?;
+
+        if let Some((ref cache, column)) = self.dictionary_cache {
+          // Byte array values are slices of the page, so page is accounted as well
+          let num_bytes =
+            page.buffer().len() + num_values as usize * mem::size_of::<T::T>();
+          let values = decoder.dict_values().expect("dictionary is set").clone();
+          cache.insert::<T>(column, values, num_bytes as i64);
+        }
+        decoder
+      }
+    };
+    self.decoders.insert(Encoding::RLE_DICTIONARY, Box::new(decoder));
+    Ok(true)
   }
 }
 
 
+/// Maximum number of levels buffered at once by `read_records`.
+const RECORDS_BATCH_SIZE: usize = 1024;
+
+/// Levels and values that have been decoded, but not returned by `read_records` yet.
+/// Always starts at the beginning of a record.
+struct PendingRecords<T: DataType> {
+  def_levels: Vec<i16>,
+  rep_levels: Vec<i16>,
+  values: Vec<T::T>
+}
+
+impl<T: DataType> PendingRecords<T> {
+  fn new() -> Self {
+    Self { def_levels: vec![], rep_levels: vec![], values: vec![] }
+  }
+
+  /// Finds at most `max_records` complete records that fit into `levels_capacity` and
+  /// `values_capacity`. If `at_end` is true, the last buffered record is complete.
+  ///
+  /// Returns a tuple of the number of levels, the number of values, the number of
+  /// records and flag whether the next complete record does not fit into capacities.
+  fn complete_records(
+    &self,
+    max_records: usize,
+    levels_capacity: usize,
+    values_capacity: usize,
+    max_def_level: i16,
+    max_rep_level: i16,
+    at_end: bool
+  ) -> (usize, usize, usize, bool) {
+    let num_levels = self.def_levels.len();
+
+    let mut num_records = 0;
+    let mut record_end = 0;
+    let mut record_values = 0;
+    let mut values = 0;
+    let mut i = 0;
+    while i < num_levels && num_records < max_records {
+      // Find the end of the record that starts at `i`
+      let mut end = i + 1;
+      if max_rep_level > 0 {
+        while end < num_levels && self.rep_levels[end] != 0 {
+          end += 1;
+        }
+        if end == num_levels && !at_end {
+          break;
+        }
+      }
+      let num_values = if max_def_level > 0 {
+        self.def_levels[i..end].iter().filter(|level| **level == max_def_level).count()
+      } else {
+        end - i
+      };
+      if end > levels_capacity || values + num_values > values_capacity {
+        return (record_end, record_values, num_records, true);
+      }
+      values += num_values;
+      num_records += 1;
+      record_end = end;
+      record_values = values;
+      i = end;
+    }
+    (record_end, record_values, num_records, false)
+  }
+
+  /// Removes all levels and values.
+  fn clear(&mut self) {
+    self.def_levels.clear();
+    self.rep_levels.clear();
+    self.values.clear();
+  }
+
+  /// Removes first `num_levels` levels and `num_values` values.
+  fn consume(&mut self, num_levels: usize, num_values: usize) {
+    self.def_levels.drain(0..num_levels);
+    self.rep_levels.drain(0..num_levels);
+    self.values.drain(0..num_values);
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
   use rand::distributions::range::SampleRange;
   use std::collections::VecDeque;
   use std::rc::Rc;
+  use std::sync::Arc;
   use std::vec::IntoIter;
 
   use basic::Type as PhysicalType;
   use column::page::Page;
   use encodings::encoding::{get_encoder, DictEncoder, Encoder};
+  use column::selection::RowSelection;
   use encodings::levels::{max_buffer_size, LevelEncoder};
+  use file::dictionary_cache::DictionaryCache;
+  use file::metrics::ReaderMetrics;
   use schema::types::{ColumnDescriptor, ColumnPath, Type as SchemaType};
   use util::memory::{ByteBufferPtr, MemTracker, MemTrackerPtr};
   use util::test_common::random_numbers_range;
@@ -636,6 +1289,345 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_read_batch_mem_tracker_limit() {
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(get_test_int32_type()), None, 0, 0, ColumnPath::new(Vec::new())));
+
+    let read = |limit: i64| {
+      let mut pages = VecDeque::new();
+      make_pages::<Int32Type>(
+        desc.clone(), Encoding::RLE_DICTIONARY, 1, 16, 0, 1000,
+        &mut vec![], &mut vec![], &mut vec![], &mut pages, false);
+      let mut column_reader = ColumnReaderImpl::<Int32Type>::new(
+        desc.clone(), Box::new(TestPageReader::new(Vec::from(pages))))
+        .with_mem_tracker(Arc::new(MemTracker::with_limit(limit)));
+      column_reader.read_batch(16, None, None, &mut vec![0; 16])
+    };
+
+    // Dictionary of at most 16 values requires at most 64 bytes
+    assert_eq!(read(64).unwrap().0, 16);
+    match read(1).as_ref().map_err(|e| e.without_context()) {
+      Err(&ParquetError::ResourceExhausted(_)) => {},
+      res => panic!("Expected ResourceExhausted error, found {:?}", res)
+    }
+  }
+
+  #[test]
+  fn test_read_batch_dictionary_cache() {
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(get_test_int32_type()), None, 0, 0, ColumnPath::new(Vec::new())));
+    let mut values = vec![];
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(
+      desc.clone(), Encoding::RLE_DICTIONARY, 2, 16, 0, 1000,
+      &mut vec![], &mut vec![], &mut values, &mut pages, false);
+    let pages = Vec::from(pages);
+
+    let cache = Rc::new(DictionaryCache::new(Arc::new(MemTracker::new())));
+    let read = |pages: Vec<Page>| {
+      let mut column_reader = ColumnReaderImpl::<Int32Type>::new(
+        desc.clone(), Box::new(TestPageReader::new(pages)))
+        .with_dictionary_cache(cache.clone(), 3);
+      let mut output = vec![0; 32];
+      let mut num_values = 0;
+      while num_values < 32 {
+        let res = column_reader.read_batch(32, None, None, &mut output[num_values..]);
+        match res.unwrap().0 {
+          0 => break,
+          n => num_values += n
+        }
+      }
+      output
+    };
+    assert_eq!(read(pages.clone()), values);
+    assert_eq!((cache.num_entries(), cache.hits(), cache.misses()), (1, 0, 1));
+    assert!(cache.memory_usage() > 0);
+
+    // Cached dictionary is used instead of decoding dictionary page
+    let mut other_pages = pages.clone();
+    let num_entries = other_pages[0].num_values();
+    other_pages[0] = Page::DictionaryPage {
+      buf: ByteBufferPtr::new(vec![0; 4 * num_entries as usize]),
+      num_values: num_entries,
+      encoding: Encoding::PLAIN,
+      is_sorted: false
+    };
+    assert_eq!(read(other_pages.clone()), values);
+    assert_eq!((cache.num_entries(), cache.hits(), cache.misses()), (1, 1, 1));
+
+    cache.clear();
+    assert_eq!(read(other_pages), vec![0; 32]);
+  }
+
+  #[test]
+  fn test_read_batch_error_context() {
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(get_test_int32_type()), None, 0, 0,
+      ColumnPath::new(vec!["a".to_owned(), "b".to_owned()])));
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(
+      desc.clone(), Encoding::RLE_DICTIONARY, 2, 16, 0, 1000,
+      &mut vec![], &mut vec![], &mut vec![], &mut pages, false);
+
+    let mut column_reader = ColumnReaderImpl::<Int32Type>::new(
+      desc.clone(), Box::new(TestPageReader::new(Vec::from(pages))))
+      .with_mem_tracker(Arc::new(MemTracker::with_limit(1)));
+    let err = column_reader.read_batch(16, None, None, &mut vec![0; 16]).unwrap_err();
+    let context = err.context().expect("Error context is set");
+    assert_eq!(context.column_path(), Some("a.b"));
+    // Dictionary page is the first page of the column chunk
+    assert_eq!(context.page(), Some(0));
+    assert_eq!(context.row_group(), None);
+    assert!(err.to_string().ends_with("(column: a.b, page: 0)"));
+  }
+
+  #[test]
+  fn test_read_records_across_pages() {
+    let (desc, pages) = make_repeated_pages(
+      &[(&[0, 1, 0, 1, 1], &[1, 2, 3, 4, 5]), (&[1, 0, 0, 1], &[6, 7, 8, 9])]);
+    let mut reader = ColumnReaderImpl::<Int32Type>::new(
+      desc, Box::new(TestPageReader::new(pages)));
+
+    let mut rep_levels = vec![0; 10];
+    let mut values = vec![0; 10];
+    let res = reader.read_records(2, None, Some(&mut rep_levels), &mut values).unwrap();
+    assert_eq!(res, (2, 6, 6));
+    assert_eq!(&rep_levels[0..6], &[0, 1, 0, 1, 1, 1]);
+    assert_eq!(&values[0..6], &[1, 2, 3, 4, 5, 6]);
+
+    let res = reader.read_records(10, None, Some(&mut rep_levels), &mut values).unwrap();
+    assert_eq!(res, (2, 3, 3));
+    assert_eq!(&rep_levels[0..3], &[0, 0, 1]);
+    assert_eq!(&values[0..3], &[7, 8, 9]);
+
+    let res = reader.read_records(10, None, Some(&mut rep_levels), &mut values).unwrap();
+    assert_eq!(res, (0, 0, 0));
+  }
+
+  #[test]
+  fn test_read_records_does_not_fit() {
+    let (desc, pages) = make_repeated_pages(
+      &[(&[0, 1, 0, 1, 1], &[1, 2, 3, 4, 5]), (&[1, 0, 0, 1], &[6, 7, 8, 9])]);
+    let mut reader = ColumnReaderImpl::<Int32Type>::new(
+      desc, Box::new(TestPageReader::new(pages)));
+
+    let mut values = vec![0; 3];
+    assert_eq!(reader.read_records(10, None, None, &mut values).unwrap(), (1, 2, 0));
+    assert_eq!(&values[0..2], &[1, 2]);
+    assert_eq!(
+      reader.read_records(10, None, None, &mut values),
+      Err(general_err!("Record does not fit into provided buffers of 3 values"))
+    );
+  }
+
+  #[test]
+  fn test_read_records_roundtrip() {
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(get_test_int32_type()), None, 2, 2, ColumnPath::new(Vec::new())));
+    let mut def_levels = vec![];
+    let mut rep_levels = vec![];
+    let mut values = vec![];
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(
+      desc.clone(), Encoding::PLAIN, 4, 128, 0, 1000,
+      &mut def_levels, &mut rep_levels, &mut values, &mut pages, false);
+
+    let mut reader = ColumnReaderImpl::<Int32Type>::new(
+      desc, Box::new(TestPageReader::new(Vec::from(pages))));
+    let mut res_def_levels = vec![];
+    let mut res_rep_levels = vec![];
+    let mut res_values = vec![];
+    loop {
+      let mut def_buffer = vec![0; 64];
+      let mut rep_buffer = vec![0; 64];
+      let mut values_buffer = vec![0; 64];
+      let (records_read, values_read, levels_read) = reader.read_records(
+        3, Some(&mut def_buffer), Some(&mut rep_buffer), &mut values_buffer).unwrap();
+      if records_read == 0 {
+        break;
+      }
+      // Each batch ends at record boundary
+      let next = res_rep_levels.len() + levels_read;
+      assert!(next == rep_levels.len() || rep_levels[next] == 0);
+      let num_records = rep_buffer[1..levels_read].iter().filter(|l| **l == 0).count();
+      assert_eq!(records_read, num_records + 1);
+
+      res_def_levels.extend_from_slice(&def_buffer[0..levels_read]);
+      res_rep_levels.extend_from_slice(&rep_buffer[0..levels_read]);
+      res_values.extend_from_slice(&values_buffer[0..values_read]);
+    }
+    assert_eq!(res_def_levels, def_levels);
+    assert_eq!(res_rep_levels, rep_levels);
+    assert_eq!(res_values, values);
+  }
+
+  #[test]
+  fn test_skip_records_repeated() {
+    let data: &[(&[i16], &[i32])] =
+      &[(&[0, 1, 0, 1, 1], &[1, 2, 3, 4, 5]), (&[1, 0, 0, 1], &[6, 7, 8, 9])];
+    let mut values = vec![0; 10];
+
+    let (desc, pages) = make_repeated_pages(data);
+    let mut reader = ColumnReaderImpl::<Int32Type>::new(
+      desc, Box::new(TestPageReader::new(pages)));
+    assert_eq!(reader.skip_records(1).unwrap(), 1);
+    assert_eq!(reader.read_records(1, None, None, &mut values).unwrap(), (1, 4, 0));
+    assert_eq!(&values[0..4], &[3, 4, 5, 6]);
+    assert_eq!(reader.skip_records(1).unwrap(), 1);
+    assert_eq!(reader.read_records(1, None, None, &mut values).unwrap(), (1, 2, 0));
+    assert_eq!(&values[0..2], &[8, 9]);
+    assert_eq!(reader.skip_records(1).unwrap(), 0);
+
+    // Skip records that have been partially buffered by `read_records`
+    let (desc, pages) = make_repeated_pages(data);
+    let mut reader = ColumnReaderImpl::<Int32Type>::new(
+      desc, Box::new(TestPageReader::new(pages)));
+    assert_eq!(reader.read_records(1, None, None, &mut values).unwrap(), (1, 2, 0));
+    assert_eq!(reader.skip_records(2).unwrap(), 2);
+    assert_eq!(reader.read_records(5, None, None, &mut values).unwrap(), (1, 2, 0));
+    assert_eq!(&values[0..2], &[8, 9]);
+
+    let (desc, pages) = make_repeated_pages(data);
+    let mut reader = ColumnReaderImpl::<Int32Type>::new(
+      desc, Box::new(TestPageReader::new(pages)));
+    assert_eq!(reader.skip_records(10).unwrap(), 4);
+  }
+
+  #[test]
+  fn test_skip_records_data_page_v2() {
+    let data: &[(&[i16], &[i32])] = &[
+      (&[0, 1, 0], &[1, 2, 3]),
+      (&[0, 0, 1, 1], &[4, 5, 6, 7]),
+      (&[0, 1], &[8, 9])
+    ];
+    let (desc, pages) = make_repeated_pages_internal(data, true);
+    let mut reader = ColumnReaderImpl::<Int32Type>::new(
+      desc, Box::new(TestPageReader::new(pages)));
+
+    let mut values = vec![0; 10];
+    assert_eq!(reader.skip_records(3).unwrap(), 3);
+    assert_eq!(reader.read_records(1, None, None, &mut values).unwrap(), (1, 3, 0));
+    assert_eq!(&values[0..3], &[5, 6, 7]);
+    assert_eq!(reader.skip_records(1).unwrap(), 1);
+    assert_eq!(reader.read_records(1, None, None, &mut values).unwrap(), (0, 0, 0));
+  }
+
+  #[test]
+  fn test_skip_records_non_repeated() {
+    for &encoding in &[Encoding::PLAIN, Encoding::RLE_DICTIONARY] {
+      let desc = Rc::new(ColumnDescriptor::new(
+        Rc::new(get_test_int32_type()), None, 0, 0, ColumnPath::new(Vec::new())));
+      let mut values = vec![];
+      let mut pages = VecDeque::new();
+      make_pages::<Int32Type>(
+        desc.clone(), encoding, 4, 32, 0, 1000,
+        &mut vec![], &mut vec![], &mut values, &mut pages, false);
+
+      // Skip whole page and part of the next one, then read the rest
+      let mut reader = ColumnReaderImpl::<Int32Type>::new(
+        desc, Box::new(TestPageReader::new(Vec::from(pages))));
+      assert_eq!(reader.skip_records(40).unwrap(), 40);
+
+      let mut res_values = vec![0; 128];
+      let res = reader.read_records(128, None, None, &mut res_values).unwrap();
+      assert_eq!(res, (88, 88, 0));
+      assert_eq!(&res_values[0..88], &values[40..]);
+      assert_eq!(reader.skip_records(1).unwrap(), 0);
+    }
+  }
+
+  #[test]
+  fn test_skip_records_metrics() {
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(get_test_int32_type()), None, 0, 0, ColumnPath::new(Vec::new())));
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(
+      desc.clone(), Encoding::PLAIN, 4, 32, 0, 1000,
+      &mut vec![], &mut vec![], &mut vec![], &mut pages, false);
+
+    let metrics = Arc::new(ReaderMetrics::new());
+    let mut reader = ColumnReaderImpl::<Int32Type>::new(
+      desc, Box::new(TestPageReader::new(Vec::from(pages))))
+      .with_metrics(metrics.clone());
+    // Skips the first page entirely and the second page partially
+    assert_eq!(reader.skip_records(40).unwrap(), 40);
+    assert_eq!(metrics.pages_skipped(), 1);
+    assert_eq!(metrics.pages_decoded(), 1);
+
+    let mut res_values = vec![0; 128];
+    assert_eq!(reader.read_batch(128, None, None, &mut res_values).unwrap(), (88, 0));
+    assert_eq!(metrics.pages_skipped(), 1);
+    assert_eq!(metrics.pages_decoded(), 3);
+  }
+
+  #[test]
+  fn test_skip_records_then_read_batch() {
+    let data: &[(&[i16], &[i32])] =
+      &[(&[0, 1, 0, 1, 1], &[1, 2, 3, 4, 5]), (&[1, 0, 0, 1], &[6, 7, 8, 9])];
+    let (desc, pages) = make_repeated_pages(data);
+    let mut reader = ColumnReaderImpl::<Int32Type>::new(
+      desc, Box::new(TestPageReader::new(pages)));
+
+    // Levels and values decoded by `skip_records` are returned by `read_batch`
+    assert_eq!(reader.skip_records(1).unwrap(), 1);
+    let mut rep_levels = vec![0; 10];
+    let mut values = vec![0; 10];
+    let res = reader.read_batch(10, None, Some(&mut rep_levels), &mut values).unwrap();
+    assert_eq!(res, (7, 7));
+    assert_eq!(&values[0..7], &[3, 4, 5, 6, 7, 8, 9]);
+    assert_eq!(&rep_levels[0..7], &[0, 1, 1, 1, 0, 0, 1]);
+  }
+
+  #[test]
+  fn test_read_selected_records() {
+    let data: &[(&[i16], &[i32])] = &[
+      (&[0, 1, 0, 1, 1], &[1, 2, 3, 4, 5]),
+      (&[1, 0, 0, 1], &[6, 7, 8, 9]),
+      (&[0, 0], &[10, 11])
+    ];
+    let (desc, pages) = make_repeated_pages(data);
+    let mut reader = ColumnReaderImpl::<Int32Type>::new(
+      desc, Box::new(TestPageReader::new(pages)));
+
+    // Records: [1, 2], [3, 4, 5, 6], [7], [8, 9], [10], [11]
+    let selection = RowSelection::from_ranges(&[1..2, 3..5], 5);
+    let mut rep_levels = vec![0; 10];
+    let mut values = vec![0; 10];
+    let res = reader.read_selected_records(
+      &selection, None, Some(&mut rep_levels), &mut values).unwrap();
+    assert_eq!(res, (3, 7, 7));
+    assert_eq!(&values[0..7], &[3, 4, 5, 6, 8, 9, 10]);
+    assert_eq!(&rep_levels[0..7], &[0, 1, 1, 1, 0, 1, 0]);
+
+    // Selection beyond the end of column chunk
+    let selection = RowSelection::from_ranges(&[0..10], 10);
+    let res = reader.read_selected_records(&selection, None, None, &mut values).unwrap();
+    assert_eq!(res, (1, 1, 0));
+    assert_eq!(values[0], 11);
+  }
+
+  // Creates column descriptor for required repeated INT32 column and PLAIN data pages
+  // v1 for provided repetition levels and values.
+  fn make_repeated_pages(data: &[(&[i16], &[i32])]) -> (ColumnDescPtr, Vec<Page>) {
+    make_repeated_pages_internal(data, false)
+  }
+
+  fn make_repeated_pages_internal(
+    data: &[(&[i16], &[i32])],
+    use_v2: bool
+  ) -> (ColumnDescPtr, Vec<Page>) {
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(get_test_int32_type()), None, 0, 1, ColumnPath::new(Vec::new())));
+    let pages = data.iter().map(|&(rep_levels, values)| {
+      let mut pb = DataPageBuilderImpl::new(desc.clone(), values.len() as u32, use_v2);
+      pb.add_rep_levels(1, rep_levels);
+      pb.add_values::<Int32Type>(Encoding::PLAIN, values);
+      pb.consume()
+    }).collect();
+    (desc, pages)
+  }
+
   // ----------------------------------------------------------------------
   // Helper methods to make pages and test
   //
@@ -679,6 +1671,35 @@ mod tests {
   // reader -> typed column reader, buffer values in `read_batch` method and compare
   // output with generated data.
 
+  #[test]
+  fn test_column_reader_get_typed() {
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(get_test_int64_type()), None, 0, 0, ColumnPath::new(Vec::new())));
+    let page_reader = TestPageReader::new(vec![]);
+    let mut column_reader = get_column_reader(desc, Box::new(page_reader));
+    assert_eq!(column_reader.physical_type(), PhysicalType::INT64);
+    assert_eq!(
+      column_reader.get_typed::<DoubleType>().err().unwrap(),
+      general_err!("Cannot access column reader of INT64 type as DOUBLE")
+    );
+    {
+      let mut values = vec![0; 4];
+      let typed_reader = column_reader.get_typed::<Int64Type>().unwrap();
+      assert_eq!(typed_reader.read_batch(4, None, None, &mut values), Ok((0, 0)));
+    }
+    assert!(column_reader.into_typed::<Int64Type>().is_ok());
+  }
+
+  #[test]
+  #[should_panic(expected = "Cannot convert column reader of INT64 type into DOUBLE")]
+  fn test_get_typed_column_reader_type_mismatch() {
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(get_test_int64_type()), None, 0, 0, ColumnPath::new(Vec::new())));
+    let page_reader = TestPageReader::new(vec![]);
+    let column_reader = get_column_reader(desc, Box::new(page_reader));
+    get_typed_column_reader::<DoubleType>(column_reader);
+  }
+
   // Returns dummy Parquet `Type` for primitive field, because most of our tests use
   // INT32 physical type.
   fn get_test_int32_type() -> SchemaType {
@@ -999,6 +2020,7 @@ mod tests {
     encoding: Option<Encoding>,
     mem_tracker: MemTrackerPtr,
     num_values: u32,
+    num_rows: u32,
     buffer: Vec<u8>,
     rep_levels_byte_len: u32,
     def_levels_byte_len: u32,
@@ -1013,8 +2035,9 @@ mod tests {
       DataPageBuilderImpl {
         desc: desc,
         encoding: None,
-        mem_tracker: Rc::new(MemTracker::new()),
+        mem_tracker: Arc::new(MemTracker::new()),
         num_values: num_values,
+        num_rows: num_values,
         buffer: vec![],
         rep_levels_byte_len: 0,
         def_levels_byte_len: 0,
@@ -1045,6 +2068,7 @@ mod tests {
   impl DataPageBuilder for DataPageBuilderImpl {
     fn add_rep_levels(&mut self, max_levels: i16, rep_levels: &[i16]) {
       self.num_values = rep_levels.len() as u32;
+      self.num_rows = rep_levels.iter().filter(|level| **level == 0).count() as u32;
       self.rep_levels_byte_len = self.add_levels(max_levels, rep_levels);
     }
 
@@ -1086,7 +2110,7 @@ mod tests {
           num_values: self.num_values,
           encoding: self.encoding.unwrap(),
           num_nulls: 0, // set to dummy value - don't need this when reading data page
-          num_rows: self.num_values, // also don't need this when reading data page
+          num_rows: self.num_rows,
           def_levels_byte_len: self.def_levels_byte_len,
           rep_levels_byte_len: self.rep_levels_byte_len,
           is_compressed: false,
@@ -1123,7 +2147,7 @@ mod tests {
     let max_def_level = desc.max_def_level();
     let max_rep_level = desc.max_rep_level();
 
-    let mem_tracker = Rc::new(MemTracker::new());
+    let mem_tracker = Arc::new(MemTracker::new());
     let mut dict_encoder = DictEncoder::<T>::new(desc.clone(), mem_tracker);
 
     for i in 0..num_pages {
@@ -0,0 +1,334 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains API to select a subset of rows (records) of a row group.
+//!
+//! [`RowSelection`] is a sequence of alternating runs of skipped and selected rows,
+//! which can be built from row ranges, e.g. produced by filtering with statistics, and
+//! combined with other selections. Column reader reads only selected records, see
+//! [`ColumnReaderImpl::read_selected_records`](../reader/struct.ColumnReaderImpl.html).
+//!
+//! # Example
+//!
+//! ```rust
+//! use parquet::column::selection::{RowSelection, RowSelector};
+//!
+//! let selection = RowSelection::from_ranges(&[2..5, 8..10], 12);
+//! assert_eq!(selection.selectors(), &[
+//!   RowSelector::skip(2),
+//!   RowSelector::select(3),
+//!   RowSelector::skip(3),
+//!   RowSelector::select(2),
+//!   RowSelector::skip(2)
+//! ]);
+//! assert_eq!(selection.row_count(), 5);
+//!
+//! let other = RowSelection::from_ranges(&[0..3], 12);
+//! assert_eq!(selection.union(&other).to_ranges(), vec![0..5, 8..10]);
+//! assert_eq!(selection.intersection(&other).to_ranges(), vec![2..3]);
+//! assert_eq!(selection.offset(1).limit(3).to_ranges(), vec![3..5, 8..9]);
+//! ```
+
+use std::cmp;
+use std::ops::Range;
+
+/// A run of rows that are either all skipped or all selected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RowSelector {
+  /// Number of rows in this run.
+  pub row_count: usize,
+  /// Whether rows are skipped or selected.
+  pub skip: bool
+}
+
+impl RowSelector {
+  /// Creates run of `row_count` selected rows.
+  pub fn select(row_count: usize) -> Self {
+    Self { row_count: row_count, skip: false }
+  }
+
+  /// Creates run of `row_count` skipped rows.
+  pub fn skip(row_count: usize) -> Self {
+    Self { row_count: row_count, skip: true }
+  }
+}
+
+/// Selection of rows as a sequence of alternating [`RowSelector`]s.
+///
+/// Selection is normalized: it does not contain empty runs and adjacent runs always
+/// differ. Rows after the last run are not selected.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RowSelection {
+  selectors: Vec<RowSelector>
+}
+
+impl RowSelection {
+  /// Creates selection from a sequence of runs.
+  pub fn from_selectors(selectors: Vec<RowSelector>) -> Self {
+    let mut result: Vec<RowSelector> = Vec::with_capacity(selectors.len());
+    for selector in selectors {
+      if selector.row_count == 0 {
+        continue;
+      }
+      match result.last_mut() {
+        Some(ref mut last) if last.skip == selector.skip => {
+          last.row_count += selector.row_count;
+          continue;
+        },
+        _ => {}
+      }
+      result.push(selector);
+    }
+    Self { selectors: result }
+  }
+
+  /// Creates selection that selects all `num_rows` rows.
+  pub fn select_all(num_rows: usize) -> Self {
+    Self::from_selectors(vec![RowSelector::select(num_rows)])
+  }
+
+  /// Creates selection from sorted non-overlapping `ranges` of selected rows out of
+  /// `num_rows` rows.
+  ///
+  /// Panics if ranges are not sorted, overlap, or exceed `num_rows`.
+  pub fn from_ranges(ranges: &[Range<usize>], num_rows: usize) -> Self {
+    let mut selectors = Vec::with_capacity(ranges.len() * 2 + 1);
+    let mut pos = 0;
+    for range in ranges {
+      assert!(range.start >= pos, "Ranges must be sorted and must not overlap");
+      assert!(range.end >= range.start, "Invalid range {:?}", range);
+      selectors.push(RowSelector::skip(range.start - pos));
+      selectors.push(RowSelector::select(range.end - range.start));
+      pos = range.end;
+    }
+    assert!(pos <= num_rows, "Ranges exceed number of rows {}", num_rows);
+    selectors.push(RowSelector::skip(num_rows - pos));
+    Self::from_selectors(selectors)
+  }
+
+  /// Returns runs of this selection.
+  pub fn selectors(&self) -> &[RowSelector] {
+    &self.selectors
+  }
+
+  /// Returns number of selected rows.
+  pub fn row_count(&self) -> usize {
+    self.selectors.iter().filter(|s| !s.skip).map(|s| s.row_count).sum()
+  }
+
+  /// Returns total number of rows covered by this selection, including skipped rows.
+  pub fn total_row_count(&self) -> usize {
+    self.selectors.iter().map(|s| s.row_count).sum()
+  }
+
+  /// Returns `true` if at least one row is selected.
+  pub fn selects_any(&self) -> bool {
+    self.selectors.iter().any(|s| !s.skip)
+  }
+
+  /// Returns ranges of selected rows.
+  pub fn to_ranges(&self) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut pos = 0;
+    for selector in &self.selectors {
+      if !selector.skip {
+        ranges.push(pos..pos + selector.row_count);
+      }
+      pos += selector.row_count;
+    }
+    ranges
+  }
+
+  /// Returns selection of rows that are selected by both this and `other` selections.
+  pub fn intersection(&self, other: &RowSelection) -> Self {
+    self.combine(other, |a, b| a && b)
+  }
+
+  /// Returns selection of rows that are selected by either this or `other` selection.
+  pub fn union(&self, other: &RowSelection) -> Self {
+    self.combine(other, |a, b| a || b)
+  }
+
+  /// Returns selection with the first `offset` selected rows skipped.
+  pub fn offset(&self, offset: usize) -> Self {
+    let mut remaining = offset;
+    let mut selectors = Vec::with_capacity(self.selectors.len() + 1);
+    for selector in &self.selectors {
+      if selector.skip || remaining == 0 {
+        selectors.push(*selector);
+      } else if selector.row_count <= remaining {
+        remaining -= selector.row_count;
+        selectors.push(RowSelector::skip(selector.row_count));
+      } else {
+        selectors.push(RowSelector::skip(remaining));
+        selectors.push(RowSelector::select(selector.row_count - remaining));
+        remaining = 0;
+      }
+    }
+    Self::from_selectors(selectors)
+  }
+
+  /// Returns selection that selects at most `limit` rows, all runs after the limit is
+  /// reached are removed.
+  pub fn limit(&self, limit: usize) -> Self {
+    let mut remaining = limit;
+    let mut selectors = Vec::with_capacity(self.selectors.len());
+    for selector in &self.selectors {
+      if remaining == 0 {
+        break;
+      }
+      if selector.skip {
+        selectors.push(*selector);
+      } else {
+        let row_count = cmp::min(selector.row_count, remaining);
+        selectors.push(RowSelector::select(row_count));
+        remaining -= row_count;
+      }
+    }
+    Self::from_selectors(selectors)
+  }
+
+  /// Combines this and `other` selection row by row with `op`, rows beyond the end of
+  /// either selection are considered skipped.
+  fn combine<F: Fn(bool, bool) -> bool>(&self, other: &RowSelection, op: F) -> Self {
+    let mut selectors = vec![];
+    let mut left = self.selectors.iter().cloned();
+    let mut right = other.selectors.iter().cloned();
+    let mut curr_left = left.next();
+    let mut curr_right = right.next();
+    loop {
+      let (l, r) = match (curr_left, curr_right) {
+        (None, None) => break,
+        (Some(l), None) => (l, RowSelector::skip(l.row_count)),
+        (None, Some(r)) => (RowSelector::skip(r.row_count), r),
+        (Some(l), Some(r)) => (l, r)
+      };
+      let row_count = cmp::min(l.row_count, r.row_count);
+      selectors.push(RowSelector {
+        row_count: row_count,
+        skip: !op(!l.skip, !r.skip)
+      });
+
+      curr_left = if curr_left.is_none() || l.row_count == row_count {
+        left.next()
+      } else {
+        Some(RowSelector { row_count: l.row_count - row_count, skip: l.skip })
+      };
+      curr_right = if curr_right.is_none() || r.row_count == row_count {
+        right.next()
+      } else {
+        Some(RowSelector { row_count: r.row_count - row_count, skip: r.skip })
+      };
+    }
+    // Trailing skipped rows do not affect selection
+    while selectors.last().map(|s| s.skip).unwrap_or(false) {
+      selectors.pop();
+    }
+    Self::from_selectors(selectors)
+  }
+}
+
+impl From<Vec<RowSelector>> for RowSelection {
+  fn from(selectors: Vec<RowSelector>) -> Self {
+    Self::from_selectors(selectors)
+  }
+}
+
+impl From<RowSelection> for Vec<RowSelector> {
+  fn from(selection: RowSelection) -> Self {
+    selection.selectors
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_row_selection_from_selectors() {
+    let selection = RowSelection::from_selectors(vec![
+      RowSelector::skip(0),
+      RowSelector::select(2),
+      RowSelector::select(3),
+      RowSelector::skip(1),
+      RowSelector::select(0),
+      RowSelector::skip(4)
+    ]);
+    assert_eq!(selection.selectors(), &[RowSelector::select(5), RowSelector::skip(5)]);
+    assert_eq!(selection.row_count(), 5);
+    assert_eq!(selection.total_row_count(), 10);
+    assert!(selection.selects_any());
+    assert!(!RowSelection::from_selectors(vec![RowSelector::skip(3)]).selects_any());
+    assert_eq!(RowSelection::select_all(0), RowSelection::default());
+  }
+
+  #[test]
+  fn test_row_selection_from_ranges() {
+    let selection = RowSelection::from_ranges(&[0..2, 2..3, 5..6], 8);
+    assert_eq!(selection.selectors(), &[
+      RowSelector::select(3),
+      RowSelector::skip(2),
+      RowSelector::select(1),
+      RowSelector::skip(2)
+    ]);
+    assert_eq!(selection.to_ranges(), vec![0..3, 5..6]);
+    assert_eq!(RowSelection::from_ranges(&[], 4).to_ranges(), vec![]);
+  }
+
+  #[test]
+  #[should_panic(expected = "Ranges must be sorted and must not overlap")]
+  fn test_row_selection_from_overlapping_ranges() {
+    RowSelection::from_ranges(&[0..3, 2..4], 8);
+  }
+
+  #[test]
+  fn test_row_selection_intersection_union() {
+    let a = RowSelection::from_ranges(&[0..4, 6..10], 10);
+    let b = RowSelection::from_ranges(&[2..7], 8);
+    assert_eq!(a.intersection(&b).to_ranges(), vec![2..4, 6..7]);
+    assert_eq!(b.intersection(&a), a.intersection(&b));
+    assert_eq!(a.union(&b).to_ranges(), vec![0..10]);
+    assert_eq!(b.union(&a), a.union(&b));
+
+    let empty = RowSelection::default();
+    assert_eq!(a.intersection(&empty), empty);
+    assert_eq!(a.union(&empty).to_ranges(), a.to_ranges());
+  }
+
+  #[test]
+  fn test_row_selection_offset_limit() {
+    let selection = RowSelection::from_ranges(&[1..4, 6..9], 10);
+    assert_eq!(selection.offset(0), selection);
+    assert_eq!(selection.offset(2).to_ranges(), vec![3..4, 6..9]);
+    assert_eq!(selection.offset(3).to_ranges(), vec![6..9]);
+    assert_eq!(selection.offset(10).to_ranges(), vec![]);
+
+    assert_eq!(selection.limit(0), RowSelection::default());
+    assert_eq!(selection.limit(4).to_ranges(), vec![1..4, 6..7]);
+    assert_eq!(selection.limit(4).total_row_count(), 7);
+    assert_eq!(selection.limit(100), selection);
+  }
+
+  #[test]
+  fn test_row_selection_conversion() {
+    let selectors = vec![RowSelector::skip(2), RowSelector::select(3)];
+    let selection = RowSelection::from(selectors.clone());
+    let res: Vec<RowSelector> = selection.into();
+    assert_eq!(res, selectors);
+  }
+}
@@ -17,21 +17,30 @@
 
 //! Contains column writer API.
 
-use std::cmp;
+use std::any::Any;
+use std::cmp::{self, Ordering};
 use std::collections::VecDeque;
 use std::mem;
-use std::rc::Rc;
+use std::str;
+use std::sync::Arc;
 
-use basic::{Compression, Encoding, PageType, Type};
+use byteorder::{ByteOrder, LittleEndian};
+
+use basic::{ColumnOrder, Compression, Encoding, LogicalType, PageType, SortOrder, Type};
 use column::page::{CompressedPage, Page, PageWriteSpec, PageWriter};
 use compression::{Codec, create_codec};
 use data_type::*;
 use encodings::encoding::{DictEncoder, Encoder, get_encoder};
 use encodings::levels::{LevelEncoder, max_buffer_size};
 use errors::{ParquetError, Result};
-use file::metadata::ColumnChunkMetaData;
-use file::properties::{WriterPropertiesPtr, WriterVersion};
-use schema::types::ColumnDescPtr;
+use file::metadata::{ColumnChunkMetaData, PageEncodingStats, SortingColumn};
+use file::properties::{EnabledStatistics, WriterPropertiesPtr, WriterVersion};
+use file::size_statistics::{SizeStatistics, level_histogram};
+use file::statistics::{self, Statistics};
+use parquet_format::{Statistics as TStatistics};
+use schema::types::{ColumnDescPtr, ColumnDescriptor};
+use util::bit_util::num_required_bits;
+use util::distinct::DistinctCounter;
 use util::memory::{ByteBufferPtr, MemTracker};
 
 /// Column writer for a Parquet type.
@@ -46,6 +55,53 @@ pub enum ColumnWriter {
   FixedLenByteArrayColumnWriter(ColumnWriterImpl<FixedLenByteArrayType>)
 }
 
+impl ColumnWriter {
+  /// Returns physical type of values of this column writer.
+  pub fn physical_type(&self) -> Type {
+    match *self {
+      ColumnWriter::BoolColumnWriter(_) => Type::BOOLEAN,
+      ColumnWriter::Int32ColumnWriter(_) => Type::INT32,
+      ColumnWriter::Int64ColumnWriter(_) => Type::INT64,
+      ColumnWriter::Int96ColumnWriter(_) => Type::INT96,
+      ColumnWriter::FloatColumnWriter(_) => Type::FLOAT,
+      ColumnWriter::DoubleColumnWriter(_) => Type::DOUBLE,
+      ColumnWriter::ByteArrayColumnWriter(_) => Type::BYTE_ARRAY,
+      ColumnWriter::FixedLenByteArrayColumnWriter(_) => Type::FIXED_LEN_BYTE_ARRAY
+    }
+  }
+
+  /// Returns typed column writer for the type `T`, or error if `T` does not match
+  /// physical type of this column writer. This is a checked alternative to
+  /// [`get_typed_column_writer`](fn.get_typed_column_writer.html) that keeps the
+  /// column writer, so that it can be closed afterwards.
+  pub fn get_typed<T: DataType>(&mut self) -> Result<&mut ColumnWriterImpl<T>> {
+    let physical_type = self.physical_type();
+    let writer: &mut Any = match *self {
+      ColumnWriter::BoolColumnWriter(ref mut w) => w,
+      ColumnWriter::Int32ColumnWriter(ref mut w) => w,
+      ColumnWriter::Int64ColumnWriter(ref mut w) => w,
+      ColumnWriter::Int96ColumnWriter(ref mut w) => w,
+      ColumnWriter::FloatColumnWriter(ref mut w) => w,
+      ColumnWriter::DoubleColumnWriter(ref mut w) => w,
+      ColumnWriter::ByteArrayColumnWriter(ref mut w) => w,
+      ColumnWriter::FixedLenByteArrayColumnWriter(ref mut w) => w
+    };
+    writer.downcast_mut::<ColumnWriterImpl<T>>().ok_or_else(|| general_err!(
+      "Cannot access column writer of {} type as {}",
+      physical_type,
+      T::get_physical_type()
+    ))
+  }
+
+  /// Converts this column writer into typed column writer for the type `T`, or returns
+  /// error if `T` does not match physical type of this column writer.
+  pub fn into_typed<T: DataType>(mut self) -> Result<ColumnWriterImpl<T>> {
+    // Checks type, so that conversion does not panic
+    self.get_typed::<T>()?;
+    Ok(get_typed_column_writer(self))
+  }
+}
+
 /// Gets a specific column writer corresponding to column descriptor `descr`.
 pub fn get_column_writer(
   descr: ColumnDescPtr,
@@ -75,20 +131,30 @@ pub fn get_column_writer(
 /// Gets a typed column writer for the specific type `T`, by "up-casting" `col_writer` of
 /// non-generic type to a generic column writer type `ColumnWriterImpl`.
 ///
-/// NOTE: the caller MUST guarantee that the actual enum value for `col_writer` matches
-/// the type `T`. Otherwise, disastrous consequence could happen.
+/// Panics if the actual enum value for `col_writer` does not match the type `T`, see
+/// [`ColumnWriter::into_typed`](enum.ColumnWriter.html#method.into_typed) for a
+/// variant that returns error instead.
 pub fn get_typed_column_writer<T: DataType>(
   col_writer: ColumnWriter
 ) -> ColumnWriterImpl<T> {
-  match col_writer {
-    ColumnWriter::BoolColumnWriter(r) => unsafe { mem::transmute(r) },
-    ColumnWriter::Int32ColumnWriter(r) => unsafe { mem::transmute(r) },
-    ColumnWriter::Int64ColumnWriter(r) => unsafe { mem::transmute(r) },
-    ColumnWriter::Int96ColumnWriter(r) => unsafe { mem::transmute(r) },
-    ColumnWriter::FloatColumnWriter(r) => unsafe { mem::transmute(r) },
-    ColumnWriter::DoubleColumnWriter(r) => unsafe { mem::transmute(r) },
-    ColumnWriter::ByteArrayColumnWriter(r) => unsafe { mem::transmute(r) },
-    ColumnWriter::FixedLenByteArrayColumnWriter(r) => unsafe { mem::transmute(r) }
+  let physical_type = col_writer.physical_type();
+  let writer: Box<Any> = match col_writer {
+    ColumnWriter::BoolColumnWriter(w) => Box::new(w),
+    ColumnWriter::Int32ColumnWriter(w) => Box::new(w),
+    ColumnWriter::Int64ColumnWriter(w) => Box::new(w),
+    ColumnWriter::Int96ColumnWriter(w) => Box::new(w),
+    ColumnWriter::FloatColumnWriter(w) => Box::new(w),
+    ColumnWriter::DoubleColumnWriter(w) => Box::new(w),
+    ColumnWriter::ByteArrayColumnWriter(w) => Box::new(w),
+    ColumnWriter::FixedLenByteArrayColumnWriter(w) => Box::new(w)
+  };
+  match writer.downcast::<ColumnWriterImpl<T>>() {
+    Ok(writer) => *writer,
+    Err(_) => panic!(
+      "Cannot convert column writer of {} type into {}",
+      physical_type,
+      T::get_physical_type()
+    )
   }
 }
 
@@ -103,10 +169,15 @@ pub struct ColumnWriterImpl<T: DataType> {
   encoder: Box<Encoder<T>>,
   codec: Compression,
   compressor: Option<Box<Codec>>,
+  statistics_enabled: EnabledStatistics,
   // Metrics per page
   num_buffered_values: u32,
   num_buffered_encoded_values: u32,
   num_buffered_rows: u32,
+  min_page_value: Option<T::T>,
+  max_page_value: Option<T::T>,
+  num_page_nulls: u64,
+  page_byte_array_data_bytes: i64,
   // Metrics per column writer
   total_bytes_written: u64,
   total_rows_written: u64,
@@ -115,6 +186,17 @@ pub struct ColumnWriterImpl<T: DataType> {
   total_num_values: u64,
   dictionary_page_offset: Option<u64>,
   data_page_offset: Option<u64>,
+  encoding_stats: Vec<PageEncodingStats>,
+  min_column_value: Option<T::T>,
+  max_column_value: Option<T::T>,
+  num_column_nulls: u64,
+  distinct_counter: Option<DistinctCounter>,
+  page_size_statistics: Vec<SizeStatistics>,
+  row_order_tracker: Option<RowOrderTracker>,
+  // Values of the first data page and their size in bytes, while adaptive encoding is
+  // not chosen yet
+  encoding_sample: Option<Vec<T::T>>,
+  encoding_sample_size: usize,
   // Reused buffers
   def_levels_sink: Vec<i16>,
   rep_levels_sink: Vec<i16>,
@@ -130,9 +212,24 @@ impl<T: DataType> ColumnWriterImpl<T> {
     let codec = props.compression(descr.path());
     let compressor = create_codec(codec).unwrap();
 
+    // Adaptive encoding starts with dictionary encoding and chooses encoding once values
+    // of the first data page are sampled, boolean values are always plain encoded.
+    let adaptive_encoding = props.adaptive_encoding(descr.path());
+    let sample_encoding = adaptive_encoding && descr.physical_type() != Type::BOOLEAN;
+    let dictionary_enabled = if adaptive_encoding {
+      sample_encoding
+    } else {
+      props.dictionary_enabled(descr.path())
+    };
+    let encoding = if adaptive_encoding {
+      Encoding::PLAIN
+    } else {
+      props.encoding(descr.path())
+    };
+
     // Optionally set dictionary encoder.
-    let dict_encoder = if props.dictionary_enabled(descr.path()) {
-      Some(DictEncoder::new(descr.clone(), Rc::new(MemTracker::new())))
+    let dict_encoder = if dictionary_enabled {
+      Some(DictEncoder::new(descr.clone(), Arc::new(MemTracker::new())))
     } else {
       None
     };
@@ -143,10 +240,18 @@ impl<T: DataType> ColumnWriterImpl<T> {
     // Set either main encoder or fallback encoder.
     let fallback_encoder = get_encoder(
       descr.clone(),
-      props.encoding(descr.path()),
-      Rc::new(MemTracker::new())
+      encoding,
+      Arc::new(MemTracker::new())
     ).unwrap();
 
+    let statistics_enabled = props.statistics_enabled(descr.path());
+    let distinct_counter = if statistics_enabled != EnabledStatistics::NONE &&
+        props.distinct_count_enabled(descr.path()) {
+      Some(DistinctCounter::new())
+    } else {
+      None
+    };
+
     Self {
       descr: descr,
       props: props,
@@ -156,9 +261,14 @@ impl<T: DataType> ColumnWriterImpl<T> {
       encoder: fallback_encoder,
       codec: codec,
       compressor: compressor,
+      statistics_enabled: statistics_enabled,
       num_buffered_values: 0,
       num_buffered_encoded_values: 0,
       num_buffered_rows: 0,
+      min_page_value: None,
+      max_page_value: None,
+      num_page_nulls: 0,
+      page_byte_array_data_bytes: 0,
       total_bytes_written: 0,
       total_rows_written: 0,
       total_uncompressed_size: 0,
@@ -166,6 +276,15 @@ impl<T: DataType> ColumnWriterImpl<T> {
       total_num_values: 0,
       dictionary_page_offset: None,
       data_page_offset: None,
+      encoding_stats: vec![],
+      min_column_value: None,
+      max_column_value: None,
+      num_column_nulls: 0,
+      distinct_counter: distinct_counter,
+      page_size_statistics: vec![],
+      row_order_tracker: None,
+      encoding_sample: if sample_encoding { Some(vec![]) } else { None },
+      encoding_sample_size: 0,
       def_levels_sink: vec![],
       rep_levels_sink: vec![],
       data_pages: VecDeque::new()
@@ -246,9 +365,38 @@ impl<T: DataType> ColumnWriterImpl<T> {
     self.total_rows_written
   }
 
+  /// Returns size statistics of each data page added by this column writer so far, in
+  /// the order of pages, or an empty slice if statistics are disabled. Buffered values
+  /// are added as the last page when column writer is closed, statistics of the column
+  /// chunk are available in its metadata.
+  pub fn get_page_size_statistics(&self) -> &[SizeStatistics] {
+    &self.page_size_statistics
+  }
+
+  /// Enables tracking of the order of each written row relative to the previous row,
+  /// according to `sorting_column`; tracked orders are returned by
+  /// [`take_row_orders`](#method.take_row_orders). Tracking is only supported for
+  /// non-repeated columns and is ignored otherwise.
+  pub fn set_sorting_column(&mut self, sorting_column: SortingColumn) {
+    if self.descr.max_rep_level() == 0 {
+      self.row_order_tracker = Some(RowOrderTracker::new(sorting_column));
+    }
+  }
+
+  /// Returns orders of rows written so far, where element `i` is the order of row `i`
+  /// relative to row `i + 1`, so `Ordering::Greater` means that rows are not sorted.
+  /// Returns `None` if tracking is not enabled, see
+  /// [`set_sorting_column`](#method.set_sorting_column).
+  pub fn take_row_orders(&mut self) -> Option<Vec<Ordering>> {
+    self.row_order_tracker
+      .as_mut()
+      .map(|tracker| mem::replace(&mut tracker.orders, vec![]))
+  }
+
   /// Finalises writes and closes the column writer.
   /// Returns total bytes written, total rows written and column chunk metadata.
   pub fn close(mut self) -> Result<(u64, u64, ColumnChunkMetaData)> {
+    self.choose_encoding()?;
     if self.dict_encoder.is_some() {
       self.write_dictionary_page()?;
     }
@@ -338,13 +486,43 @@ impl<T: DataType> ColumnWriterImpl<T> {
       ));
     }
 
-    // TODO: update page statistics
+    if self.statistics_enabled != EnabledStatistics::NONE {
+      self.update_page_min_max(&values[0..values_to_write]);
+      self.num_page_nulls += (num_values - values_to_write) as u64;
+      if self.descr.physical_type() == Type::BYTE_ARRAY {
+        for value in &values[0..values_to_write] {
+          self.page_byte_array_data_bytes += value.as_bytes().len() as i64;
+        }
+      }
+    }
+    if let Some(ref mut counter) = self.distinct_counter {
+      for value in &values[0..values_to_write] {
+        counter.insert(value);
+      }
+    }
+    if let Some(ref mut tracker) = self.row_order_tracker {
+      let max_def_level = self.descr.max_def_level();
+      let mut value_index = 0;
+      for i in 0..num_values {
+        let is_null = def_levels.map_or(false, |levels| levels[i] < max_def_level);
+        if is_null {
+          tracker.push(&self.descr, None);
+        } else {
+          tracker.push(&self.descr, Some(values[value_index].as_bytes()));
+          value_index += 1;
+        }
+      }
+    }
 
     self.write_values(&values[0..values_to_write])?;
 
     self.num_buffered_values += num_values as u32;
     self.num_buffered_encoded_values += values_to_write as u32;
 
+    if self.should_choose_encoding() {
+      self.choose_encoding()?;
+    }
+
     if self.should_add_data_page() {
       self.add_data_page()?;
     }
@@ -368,12 +546,135 @@ impl<T: DataType> ColumnWriterImpl<T> {
 
   #[inline]
   fn write_values(&mut self, values: &[T::T]) -> Result<()> {
+    if let Some(ref mut sample) = self.encoding_sample {
+      sample.extend_from_slice(values);
+      for value in values {
+        self.encoding_sample_size += value.as_bytes().len();
+      }
+    }
     match self.dict_encoder {
       Some(ref mut encoder) => encoder.put(values),
       None => self.encoder.put(values)
     }
   }
 
+  /// Updates min and max values of the current page with `values`.
+  /// NaN values are ignored, since they can not be compared with other values. Min and
+  /// max values are not tracked for columns with undefined sort order, e.g. INT96.
+  fn update_page_min_max(&mut self, values: &[T::T]) {
+    if self.sort_order() == SortOrder::UNDEFINED {
+      return;
+    }
+    for value in values {
+      if statistics::is_nan(&self.descr, value.as_bytes()) {
+        continue;
+      }
+      if self.min_page_value.as_ref().map_or(true, |v| self.compare_greater(v, value)) {
+        self.min_page_value = Some(value.clone());
+      }
+      if self.max_page_value.as_ref().map_or(true, |v| self.compare_greater(value, v)) {
+        self.max_page_value = Some(value.clone());
+      }
+    }
+  }
+
+  /// Updates min and max values and null count of the column chunk with statistics of
+  /// the current page.
+  fn update_column_statistics(&mut self) {
+    if let Some(ref min) = self.min_page_value {
+      if self.min_column_value.as_ref().map_or(true, |v| self.compare_greater(v, min)) {
+        self.min_column_value = Some(min.clone());
+      }
+    }
+    if let Some(ref max) = self.max_page_value {
+      if self.max_column_value.as_ref().map_or(true, |v| self.compare_greater(max, v)) {
+        self.max_column_value = Some(max.clone());
+      }
+    }
+    self.num_column_nulls += self.num_page_nulls;
+  }
+
+  /// Returns `true` if value `a` is greater than value `b` in the sort order of the
+  /// column.
+  #[inline]
+  fn compare_greater(&self, a: &T::T, b: &T::T) -> bool {
+    statistics::compare_greater(&self.descr, a.as_bytes(), b.as_bytes())
+  }
+
+  /// Creates statistics from min and max values, null count and distinct count. Min and
+  /// max values are truncated if statistics truncate length is set, and omitted when
+  /// they exceed max statistics size.
+  fn make_statistics(
+    &self,
+    min: Option<&T::T>,
+    max: Option<&T::T>,
+    null_count: u64,
+    distinct_count: Option<u64>
+  ) -> Statistics {
+    let max_size = self.props.max_statistics_size(self.descr.path());
+    let (mut is_min_exact, mut is_max_exact) = (true, true);
+    let (min, max) = match (min, max) {
+      (Some(min), Some(max)) => {
+        let mut min = min.as_bytes().to_vec();
+        let mut max = max.as_bytes().to_vec();
+        if let Some(length) = self.statistics_truncate_length() {
+          if min.len() > length {
+            min = truncate_min_value(&min, length, self.is_utf8());
+            is_min_exact = false;
+          }
+          if max.len() > length {
+            if let Some(value) = truncate_max_value(&max, length, self.is_utf8()) {
+              max = value;
+              is_max_exact = false;
+            }
+          }
+        }
+        if min.len() <= max_size && max.len() <= max_size {
+          (Some(min), Some(max))
+        } else {
+          (None, None)
+        }
+      },
+      _ => (None, None)
+    };
+    let thrift_stats = TStatistics {
+      max: None,
+      min: None,
+      null_count: Some(null_count as i64),
+      distinct_count: distinct_count.map(|count| count as i64),
+      max_value: max,
+      min_value: min
+    };
+    // Conversion also adjusts min and max values of floating point statistics
+    statistics::from_thrift(self.descr.physical_type(), Some(thrift_stats))
+      .expect("Statistics are set")
+      .with_min_max_exact(is_min_exact, is_max_exact)
+  }
+
+  /// Returns truncate length of min and max values, if values of this column can be
+  /// truncated, i.e. BYTE_ARRAY values compared as unsigned bytes.
+  fn statistics_truncate_length(&self) -> Option<usize> {
+    if self.descr.physical_type() == Type::BYTE_ARRAY &&
+        self.sort_order() == SortOrder::UNSIGNED {
+      self.props.statistics_truncate_length()
+    } else {
+      None
+    }
+  }
+
+  /// Returns sort order of values of this column.
+  #[inline]
+  fn sort_order(&self) -> SortOrder {
+    ColumnOrder::get_sort_order(self.descr.logical_type(), self.descr.physical_type())
+  }
+
+  /// Returns `true` if values of this column are UTF-8 strings.
+  #[inline]
+  fn is_utf8(&self) -> bool {
+    self.descr.logical_type() == LogicalType::UTF8 ||
+      self.descr.logical_type() == LogicalType::JSON
+  }
+
   /// Returns true if we need to fall back to non-dictionary encoding.
   ///
   /// We can only fall back if dictionary encoder is set and we have exceeded dictionary
@@ -388,6 +689,45 @@ impl<T: DataType> ColumnWriterImpl<T> {
     }
   }
 
+  /// Returns true if adaptive encoding has sampled enough values to choose encoding,
+  /// i.e. values of a data page, or before dictionary fallback.
+  #[inline]
+  fn should_choose_encoding(&self) -> bool {
+    self.encoding_sample.is_some() && (
+      self.encoding_sample_size >= self.props.data_pagesize_limit() ||
+        self.should_dict_fallback()
+    )
+  }
+
+  /// Chooses encoding based on sampled values, if adaptive encoding is enabled and
+  /// encoding is not chosen yet. Dictionary encoding is kept if values repeat at least
+  /// twice on average, otherwise sampled values are encoded again with the chosen
+  /// encoding, which is also the fallback encoding of dictionary encoding.
+  ///
+  /// This is called before the first data page or dictionary page is added, so that
+  /// dictionary encoder can be discarded.
+  fn choose_encoding(&mut self) -> Result<()> {
+    let sample = match self.encoding_sample.take() {
+      Some(sample) => sample,
+      None => return Ok(())
+    };
+    let num_distinct =
+      self.dict_encoder.as_ref().map_or(sample.len(), |e| e.num_entries());
+    let encoding = adaptive_value_encoding::<T>(
+      self.props.writer_version(),
+      self.descr.physical_type(),
+      &sample
+    );
+    self.encoder =
+      get_encoder(self.descr.clone(), encoding, Arc::new(MemTracker::new()))?;
+    if num_distinct * 2 > sample.len() {
+      self.dict_encoder = None;
+      self.has_dictionary = false;
+      self.encoder.put(&sample)?;
+    }
+    Ok(())
+  }
+
   /// Returns true if there is enough data for a data page, false otherwise.
   #[inline]
   fn should_add_data_page(&self) -> bool {
@@ -407,6 +747,8 @@ impl<T: DataType> ColumnWriterImpl<T> {
   /// Adds data page.
   /// Data page is either buffered in case of dictionary encoding or written directly.
   fn add_data_page(&mut self) -> Result<()> {
+    self.choose_encoding()?;
+
     // Extract encoded values
     let value_bytes = match self.dict_encoder {
       Some(ref mut encoder) => encoder.write_indices()?,
@@ -423,6 +765,22 @@ impl<T: DataType> ColumnWriterImpl<T> {
     let max_def_level = self.descr.max_def_level();
     let max_rep_level = self.descr.max_rep_level();
 
+    if self.statistics_enabled != EnabledStatistics::NONE {
+      self.update_column_statistics();
+      let size_statistics = self.make_page_size_statistics();
+      self.page_size_statistics.push(size_statistics);
+    }
+    let page_statistics = if self.statistics_enabled == EnabledStatistics::PAGE {
+      Some(self.make_statistics(
+        self.min_page_value.as_ref(),
+        self.max_page_value.as_ref(),
+        self.num_page_nulls,
+        None
+      ))
+    } else {
+      None
+    };
+
     let compressed_page = match self.props.writer_version() {
       WriterVersion::PARQUET_1_0 => {
         let mut buffer = vec![];
@@ -445,6 +803,7 @@ impl<T: DataType> ColumnWriterImpl<T> {
         let uncompressed_size = buffer.len();
 
         if let Some(ref mut cmpr) = self.compressor {
+          trace_span!("compress_page", uncompressed_size = uncompressed_size);
           let mut compressed_buf = Vec::with_capacity(value_bytes.data().len());
           cmpr.compress(&buffer[..], &mut compressed_buf)?;
           buffer = compressed_buf;
@@ -456,8 +815,7 @@ impl<T: DataType> ColumnWriterImpl<T> {
           encoding: encoding,
           def_level_encoding: Encoding::RLE,
           rep_level_encoding: Encoding::RLE,
-          // TODO: process statistics
-          statistics: None
+          statistics: page_statistics
         };
 
         CompressedPage::new(data_page, uncompressed_size)
@@ -485,6 +843,7 @@ impl<T: DataType> ColumnWriterImpl<T> {
         // Data Page v2 compresses values only.
         match self.compressor {
           Some(ref mut cmpr) => {
+            trace_span!("compress_page", uncompressed_size = value_bytes.len());
             let mut compressed_buf = Vec::with_capacity(value_bytes.data().len());
             cmpr.compress(value_bytes.data(), &mut compressed_buf)?;
             buffer.extend_from_slice(&compressed_buf[..]);
@@ -503,8 +862,7 @@ impl<T: DataType> ColumnWriterImpl<T> {
           def_levels_byte_len: def_levels_byte_len as u32,
           rep_levels_byte_len: rep_levels_byte_len as u32,
           is_compressed: self.compressor.is_some(),
-          // TODO: process statistics
-          statistics: None
+          statistics: page_statistics
         };
 
         CompressedPage::new(data_page, uncompressed_size)
@@ -527,6 +885,10 @@ impl<T: DataType> ColumnWriterImpl<T> {
     self.num_buffered_values = 0;
     self.num_buffered_encoded_values = 0;
     self.num_buffered_rows = 0;
+    self.min_page_value = None;
+    self.max_page_value = None;
+    self.num_page_nulls = 0;
+    self.page_byte_array_data_bytes = 0;
 
     Ok(())
   }
@@ -576,7 +938,7 @@ impl<T: DataType> ColumnWriterImpl<T> {
     // We use only RLE level encoding for data page v1 and data page v2.
     encodings.push(Encoding::RLE);
 
-    let metadata = ColumnChunkMetaData::builder(self.descr.clone())
+    let mut builder = ColumnChunkMetaData::builder(self.descr.clone())
       .set_compression(self.codec)
       .set_encodings(encodings)
       .set_file_offset(file_offset)
@@ -585,13 +947,58 @@ impl<T: DataType> ColumnWriterImpl<T> {
       .set_num_values(num_values)
       .set_data_page_offset(data_page_offset)
       .set_dictionary_page_offset(dict_page_offset)
-      .build()?;
+      .set_encoding_stats(self.encoding_stats.clone());
+    if self.statistics_enabled != EnabledStatistics::NONE {
+      let statistics = self.make_statistics(
+        self.min_column_value.as_ref(),
+        self.max_column_value.as_ref(),
+        self.num_column_nulls,
+        self.distinct_counter.as_ref().map(|counter| counter.count())
+      );
+      builder = builder.set_statistics(statistics);
+
+      let size_statistics = self.page_size_statistics.split_first().map(|(first, rest)| {
+        let mut size_statistics = first.clone();
+        for page in rest {
+          size_statistics.update(page);
+        }
+        size_statistics
+      });
+      builder = builder.set_size_statistics(size_statistics);
+    }
+    let metadata = builder.build()?;
 
     self.page_writer.write_metadata(&metadata)?;
 
     Ok(metadata)
   }
 
+  /// Returns size statistics of the current page, level histograms are computed only
+  /// for levels that are written, i.e. when max level is greater than 0.
+  fn make_page_size_statistics(&self) -> SizeStatistics {
+    let max_def_level = self.descr.max_def_level();
+    let max_rep_level = self.descr.max_rep_level();
+    let unencoded_byte_array_data_bytes = match self.descr.physical_type() {
+      Type::BYTE_ARRAY => Some(self.page_byte_array_data_bytes),
+      _ => None
+    };
+    let repetition_level_histogram = if max_rep_level > 0 {
+      Some(level_histogram(&self.rep_levels_sink[..], max_rep_level))
+    } else {
+      None
+    };
+    let definition_level_histogram = if max_def_level > 0 {
+      Some(level_histogram(&self.def_levels_sink[..], max_def_level))
+    } else {
+      None
+    };
+    SizeStatistics::new(
+      unencoded_byte_array_data_bytes,
+      repetition_level_histogram,
+      definition_level_histogram
+    )
+  }
+
   /// Encodes definition or repetition levels for Data Page v1.
   #[inline]
   fn encode_levels_v1(
@@ -619,8 +1026,10 @@ impl<T: DataType> ColumnWriterImpl<T> {
   /// Writes compressed data page into underlying sink and updates global metrics.
   #[inline]
   fn write_data_page(&mut self, page: CompressedPage) -> Result<()> {
+    let (page_type, encoding) = (page.page_type(), page.encoding());
     let page_spec = self.page_writer.write_page(page)?;
     self.update_metrics_for_page(page_spec);
+    self.update_encoding_stats(page_type, encoding);
     Ok(())
   }
 
@@ -639,6 +1048,7 @@ impl<T: DataType> ColumnWriterImpl<T> {
       let uncompressed_size = values_buf.len();
 
       if let Some(ref mut cmpr) = self.compressor {
+        trace_span!("compress_page", uncompressed_size = uncompressed_size);
         let mut output_buf = Vec::with_capacity(uncompressed_size);
         cmpr.compress(values_buf.data(), &mut output_buf)?;
         values_buf = ByteBufferPtr::new(output_buf);
@@ -655,9 +1065,24 @@ impl<T: DataType> ColumnWriterImpl<T> {
 
     let page_spec = self.page_writer.write_page(compressed_page)?;
     self.update_metrics_for_page(page_spec);
+    self.update_encoding_stats(
+      PageType::DICTIONARY_PAGE, self.props.dictionary_page_encoding());
     Ok(())
   }
 
+  /// Increments number of written pages with `page_type` and `encoding`.
+  #[inline]
+  fn update_encoding_stats(&mut self, page_type: PageType, encoding: Encoding) {
+    for stats in self.encoding_stats.iter_mut() {
+      if stats.page_type == page_type && stats.encoding == encoding {
+        stats.count += 1;
+        return;
+      }
+    }
+    self.encoding_stats.push(
+      PageEncodingStats { page_type: page_type, encoding: encoding, count: 1 });
+  }
+
   /// Updates column writer metrics with each page metadata.
   #[inline]
   fn update_metrics_for_page(&mut self, page_spec: PageWriteSpec) {
@@ -690,6 +1115,144 @@ impl<T: DataType> ColumnWriterImpl<T> {
   }
 }
 
+/// Returns encoding of values that are not dictionary encoded, chosen by adaptive
+/// encoding based on sampled `values`: `DELTA_BINARY_PACKED` for integers, if deltas
+/// between consecutive values need at most half of the bits of plain encoded values and
+/// writer version supports it, `PLAIN` otherwise.
+fn adaptive_value_encoding<T: DataType>(
+  writer_version: WriterVersion,
+  physical_type: Type,
+  values: &[T::T]
+) -> Encoding {
+  if !writer_version.supports_encoding(Encoding::DELTA_BINARY_PACKED) {
+    return Encoding::PLAIN;
+  }
+  let plain_bits = match physical_type {
+    Type::INT32 => 32,
+    Type::INT64 => 64,
+    _ => return Encoding::PLAIN
+  };
+  let mut min_delta = i64::max_value();
+  let mut max_delta = i64::min_value();
+  for pair in values.windows(2) {
+    let (previous, value) = if physical_type == Type::INT32 {
+      (
+        LittleEndian::read_i32(pair[0].as_bytes()) as i64,
+        LittleEndian::read_i32(pair[1].as_bytes()) as i64
+      )
+    } else {
+      (
+        LittleEndian::read_i64(pair[0].as_bytes()),
+        LittleEndian::read_i64(pair[1].as_bytes())
+      )
+    };
+    match value.checked_sub(previous) {
+      Some(delta) => {
+        min_delta = cmp::min(min_delta, delta);
+        max_delta = cmp::max(max_delta, delta);
+      },
+      None => return Encoding::PLAIN
+    }
+  }
+  match max_delta.checked_sub(min_delta) {
+    Some(range) if num_required_bits(range as u64) * 2 <= plain_bits => {
+      Encoding::DELTA_BINARY_PACKED
+    },
+    _ => Encoding::PLAIN
+  }
+}
+
+/// Tracks order of consecutive rows of a non-repeated column according to a sorting
+/// column, comparing plain-encoded values.
+struct RowOrderTracker {
+  sorting_column: SortingColumn,
+  // `None` before the first row, `Some(None)` if the previous row is null
+  previous: Option<Option<Vec<u8>>>,
+  orders: Vec<Ordering>
+}
+
+impl RowOrderTracker {
+  fn new(sorting_column: SortingColumn) -> Self {
+    Self { sorting_column: sorting_column, previous: None, orders: vec![] }
+  }
+
+  fn push(&mut self, descr: &ColumnDescriptor, value: Option<&[u8]>) {
+    if let Some(ref previous) = self.previous {
+      let nulls_first = self.sorting_column.nulls_first;
+      let order = match (previous.as_ref(), value) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) if nulls_first => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) if nulls_first => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => {
+          let order = if statistics::compare_greater(descr, a, b) {
+            Ordering::Greater
+          } else if statistics::compare_greater(descr, b, a) {
+            Ordering::Less
+          } else {
+            Ordering::Equal
+          };
+          if self.sorting_column.descending { order.reverse() } else { order }
+        }
+      };
+      self.orders.push(order);
+    }
+    self.previous = Some(value.map(|v| v.to_vec()));
+  }
+}
+
+/// Truncates min value to at most `length` bytes. UTF-8 strings are truncated at
+/// character boundary.
+fn truncate_min_value(value: &[u8], length: usize, is_utf8: bool) -> Vec<u8> {
+  let length = if is_utf8 { utf8_prefix_length(value, length) } else { length };
+  value[..length].to_vec()
+}
+
+/// Truncates max value to at most `length` bytes and increments the last byte, or the
+/// last character of UTF-8 strings, so that result is greater than the original value.
+/// Trailing bytes or characters that can not be incremented are removed.
+///
+/// Returns `None` if there is no such value, e.g. all bytes are `0xFF`.
+fn truncate_max_value(value: &[u8], length: usize, is_utf8: bool) -> Option<Vec<u8>> {
+  if is_utf8 {
+    if let Ok(string) = str::from_utf8(value) {
+      let prefix = &string[..utf8_prefix_length(value, length)];
+      let mut chars: Vec<char> = prefix.chars().collect();
+      while let Some(c) = chars.pop() {
+        // Next valid character, skipping surrogates
+        let next = (c as u32 + 1..=char::MAX as u32).filter_map(char::from_u32).next();
+        if let Some(next) = next {
+          chars.push(next);
+          let result: String = chars.iter().collect();
+          if result.len() <= length {
+            return Some(result.into_bytes());
+          }
+          chars.pop();
+        }
+      }
+      return None;
+    }
+  }
+  let mut result = value[..length].to_vec();
+  while let Some(byte) = result.pop() {
+    if byte < 0xFF {
+      result.push(byte + 1);
+      return Some(result);
+    }
+  }
+  None
+}
+
+/// Returns length of the longest prefix of UTF-8 string `value` of at most `length`
+/// bytes that ends at character boundary. If value is not a valid UTF-8 string,
+/// returns `length`.
+fn utf8_prefix_length(value: &[u8], length: usize) -> usize {
+  match str::from_utf8(value) {
+    Ok(string) => (0..=length).rev().find(|&i| string.is_char_boundary(i)).unwrap_or(0),
+    Err(_) => length
+  }
+}
 
 #[cfg(test)]
 mod tests {
@@ -697,6 +1260,7 @@ mod tests {
   use rand::distributions::range::SampleRange;
 
   use std::error::Error;
+  use std::rc::Rc;
   use column::page::PageReader;
   use column::reader::{ColumnReaderImpl, get_column_reader, get_typed_column_reader};
   use file::properties::WriterProperties;
@@ -706,6 +1270,33 @@ mod tests {
   use util::io::{FileSink, FileSource};
   use util::test_common::{get_temp_file, random_numbers_range};
 
+  #[test]
+  fn test_column_writer_get_typed() {
+    let props = Rc::new(WriterProperties::builder().build());
+    let descr = Rc::new(get_test_column_descr::<ByteArrayType>(0, 0));
+    let mut writer = get_column_writer(descr, props, get_test_page_writer());
+    assert_eq!(writer.physical_type(), Type::BYTE_ARRAY);
+    assert_eq!(
+      writer.get_typed::<FixedLenByteArrayType>().err().unwrap(),
+      general_err!(
+        "Cannot access column writer of BYTE_ARRAY type as FIXED_LEN_BYTE_ARRAY"
+      )
+    );
+    {
+      let values = vec![ByteArray::from("a"), ByteArray::from("b")];
+      let typed_writer = writer.get_typed::<ByteArrayType>().unwrap();
+      typed_writer.write_batch(&values, None, None).unwrap();
+    }
+    assert!(writer.into_typed::<Int32Type>().is_err());
+
+    let props = Rc::new(WriterProperties::builder().build());
+    let descr = Rc::new(get_test_column_descr::<ByteArrayType>(0, 0));
+    let writer = get_column_writer(descr, props, get_test_page_writer());
+    let typed_writer = writer.into_typed::<ByteArrayType>().unwrap();
+    let (_, rows_written, _) = typed_writer.close().unwrap();
+    assert_eq!(rows_written, 0);
+  }
+
   #[test]
   fn test_column_writer_inconsistent_def_rep_length() {
     let page_writer = get_test_page_writer();
@@ -736,6 +1327,29 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_column_writer_row_orders() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, 1, 0, props);
+    assert_eq!(writer.take_row_orders(), None);
+
+    writer.set_sorting_column(SortingColumn::new(0, false, true));
+    writer.write_batch(&[1, 3], Some(&[0, 1, 1]), None).unwrap();
+    writer.write_batch(&[3, 2], Some(&[1, 1, 0]), None).unwrap();
+    assert_eq!(
+      writer.take_row_orders(),
+      Some(vec![
+        Ordering::Less,
+        Ordering::Less,
+        Ordering::Equal,
+        Ordering::Greater,
+        Ordering::Greater
+      ])
+    );
+    assert_eq!(writer.take_row_orders(), Some(vec![]));
+  }
+
   #[test]
   fn test_column_writer_invalid_rep_levels() {
     let page_writer = get_test_page_writer();
@@ -809,6 +1423,304 @@ mod tests {
     assert_eq!(metadata.uncompressed_size(), 20);
     assert_eq!(metadata.data_page_offset(), 0);
     assert_eq!(metadata.dictionary_page_offset(), Some(0));
+    assert_eq!(metadata.encoding_stats(), Some(&vec![
+      PageEncodingStats {
+        page_type: PageType::DICTIONARY_PAGE,
+        encoding: Encoding::PLAIN,
+        count: 1
+      },
+      PageEncodingStats {
+        page_type: PageType::DATA_PAGE,
+        encoding: Encoding::RLE_DICTIONARY,
+        count: 1
+      }
+    ]));
+    assert!(metadata.is_fully_dictionary_encoded());
+  }
+
+  #[test]
+  fn test_column_writer_encoding_stats_dictionary_fallback() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(
+      WriterProperties::builder()
+        .set_dictionary_pagesize_limit(1)
+        .set_data_pagesize_limit(1)
+        .set_write_batch_size(1)
+        .build()
+    );
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, 0, 0, props);
+    writer.write_batch(&[1, 2, 3, 4], None, None).unwrap();
+
+    let (_, _, metadata) = writer.close().unwrap();
+    let encoding_stats = metadata.encoding_stats().unwrap();
+    assert_eq!(encoding_stats[0], PageEncodingStats {
+      page_type: PageType::DICTIONARY_PAGE,
+      encoding: Encoding::PLAIN,
+      count: 1
+    });
+    let num_data_pages = encoding_stats.iter()
+      .filter(|stats| stats.page_type == PageType::DATA_PAGE)
+      .map(|stats| stats.count)
+      .sum::<i32>();
+    assert_eq!(num_data_pages, 4);
+    assert!(encoding_stats.iter().any(|stats| stats.encoding == Encoding::PLAIN &&
+      stats.page_type == PageType::DATA_PAGE));
+    assert!(!metadata.is_fully_dictionary_encoded());
+  }
+
+  #[test]
+  fn test_column_writer_float_statistics() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<FloatType>(page_writer, 1, 0, props);
+    let values = [::std::f32::NAN, 0.0, -0.0, 0.0, ::std::f32::NAN];
+    writer.write_batch(&values, Some(&[1, 1, 0, 1, 1, 0, 1]), None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+
+    match metadata.statistics() {
+      Some(&Statistics::Float(ref stats)) => {
+        assert!(stats.min().is_sign_negative() && *stats.min() == 0.0);
+        assert!(stats.max().is_sign_positive() && *stats.max() == 0.0);
+      },
+      other => panic!("Unexpected statistics: {:?}", other)
+    }
+    assert_eq!(metadata.statistics().unwrap().null_count(), 2);
+
+    // Statistics of only NaN values do not have min and max values
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<DoubleType>(page_writer, 0, 0, props);
+    writer.write_batch(&[::std::f64::NAN], None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    let stats = metadata.statistics().unwrap();
+    assert!(!stats.has_min_max_set());
+    assert_eq!(stats.null_count(), 0);
+  }
+
+  #[test]
+  fn test_column_writer_undefined_sort_order_statistics() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int96Type>(page_writer, 1, 0, props);
+    let values = [Int96::from(vec![3, 0, 0]), Int96::from(vec![1, 0, 0])];
+    writer.write_batch(&values, Some(&[1, 0, 1]), None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    let stats = metadata.statistics().unwrap();
+    assert!(!stats.has_min_max_set());
+    assert_eq!(stats.null_count(), 1);
+  }
+
+  #[test]
+  fn test_column_writer_adaptive_encoding() {
+    let encodings = |values: &[i64], props: WriterProperties| {
+      let page_writer = get_test_page_writer();
+      let mut writer =
+        get_test_column_writer::<Int64Type>(page_writer, 0, 0, Rc::new(props));
+      writer.write_batch(values, None, None).unwrap();
+      let (_, _, metadata) = writer.close().unwrap();
+      metadata.encodings().clone()
+    };
+    let props = || {
+      WriterProperties::builder()
+        .set_writer_version(WriterVersion::PARQUET_2_0)
+        .set_adaptive_encoding(true)
+    };
+
+    // Repeated values keep dictionary encoding
+    let values: Vec<i64> = (0..100).map(|i| i % 3).collect();
+    assert_eq!(
+      encodings(&values, props().build()),
+      vec![Encoding::PLAIN, Encoding::RLE_DICTIONARY, Encoding::RLE]
+    );
+
+    // Unique values with small deltas are delta encoded
+    let values: Vec<i64> = (0..100).map(|i| 1_000_000_000 + i * 7).collect();
+    assert_eq!(
+      encodings(&values, props().build()),
+      vec![Encoding::DELTA_BINARY_PACKED, Encoding::RLE]
+    );
+
+    // Writer version 1.0 does not use delta encoding
+    let props_v1 = props().set_writer_version(WriterVersion::PARQUET_1_0).build();
+    assert_eq!(encodings(&values, props_v1), vec![Encoding::PLAIN, Encoding::RLE]);
+
+    // Unique values with large deltas are plain encoded
+    let values: Vec<i64> = (0..100).map(|i| (i % 2) << 60 | i).collect();
+    assert_eq!(encodings(&values, props().build()), vec![Encoding::PLAIN, Encoding::RLE]);
+
+    // Encoding set for a column is not overridden
+    let col = ColumnPath::from("col");
+    let values: Vec<i64> = (0..100).collect();
+    assert_eq!(
+      encodings(&values, props().set_column_encoding(col, Encoding::PLAIN).build()),
+      vec![Encoding::PLAIN, Encoding::RLE_DICTIONARY, Encoding::RLE]
+    );
+
+    // Boolean values are plain encoded
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(props().build());
+    let mut writer = get_test_column_writer::<BoolType>(page_writer, 0, 0, props);
+    writer.write_batch(&[true, false, true], None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(metadata.encodings(), &vec![Encoding::PLAIN, Encoding::RLE]);
+  }
+
+  #[test]
+  fn test_column_writer_statistics_disabled() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(
+      WriterProperties::builder().set_statistics_enabled(EnabledStatistics::NONE).build()
+    );
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, 0, 0, props);
+    writer.write_batch(&[1, 2, 3], None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert!(metadata.statistics().is_none());
+    assert!(metadata.size_statistics().is_none());
+  }
+
+  #[test]
+  fn test_column_writer_statistics_levels() {
+    for &level in &[EnabledStatistics::CHUNK, EnabledStatistics::PAGE] {
+      let file = get_temp_file("test_column_writer_statistics_levels", &[]);
+      let page_writer = Box::new(SerializedPageWriter::new(FileSink::new(&file)));
+      let props =
+        Rc::new(WriterProperties::builder().set_statistics_enabled(level).build());
+      let mut writer = get_test_column_writer::<Int32Type>(page_writer, 0, 0, props);
+      writer.write_batch(&[3, 1, 2], None, None).unwrap();
+      let (bytes_written, _, metadata) = writer.close().unwrap();
+      assert_eq!(
+        metadata.statistics(),
+        Some(&Statistics::int32(Some(1), Some(3), None, 0, false))
+      );
+
+      let source = FileSource::new(&file, 0, bytes_written as usize);
+      let mut page_reader = SerializedPageReader::new(
+        source,
+        metadata.num_values(),
+        metadata.compression(),
+        Type::INT32
+      ).unwrap();
+      while let Some(page) = page_reader.get_next_page().unwrap() {
+        if page.page_type() != PageType::DICTIONARY_PAGE {
+          assert_eq!(page.statistics().is_some(), level == EnabledStatistics::PAGE);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_column_writer_distinct_count() {
+    let page_writer = get_test_page_writer();
+    let props =
+      Rc::new(WriterProperties::builder().set_distinct_count_enabled(true).build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, 1, 0, props);
+    writer.write_batch(&[1, 2, 1, 3, 2], Some(&[1, 1, 0, 1, 1, 1]), None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(metadata.statistics().unwrap().distinct_count(), Some(3));
+    assert_eq!(metadata.statistics().unwrap().null_count(), 1);
+
+    // Distinct count is disabled by default
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, 0, 0, props);
+    writer.write_batch(&[1, 2, 1], None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(metadata.statistics().unwrap().distinct_count(), None);
+  }
+
+  #[test]
+  fn test_column_writer_truncate_statistics() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(
+      WriterProperties::builder().set_statistics_truncate_length(Some(2)).build()
+    );
+    let mut writer = get_test_column_writer::<ByteArrayType>(page_writer, 0, 0, props);
+    let values = vec![
+      ByteArray::from(vec![1, 2, 3]),
+      ByteArray::from(vec![1, 0xFF, 0xFF]),
+      ByteArray::from(vec![1, 1])
+    ];
+    writer.write_batch(&values, None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    let stats = metadata.statistics().unwrap();
+    assert_eq!(stats.min_bytes(), &[1, 1]);
+    assert!(stats.is_min_exact());
+    assert_eq!(stats.max_bytes(), &[2]);
+    assert!(!stats.is_max_exact());
+  }
+
+  #[test]
+  fn test_column_writer_size_statistics() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(
+      WriterProperties::builder()
+        .set_dictionary_enabled(false)
+        .set_data_pagesize_limit(1)
+        .set_write_batch_size(2)
+        .build()
+    );
+    let mut writer = get_test_column_writer::<ByteArrayType>(page_writer, 2, 1, props);
+    let values = vec![
+      ByteArray::from("a"),
+      ByteArray::from("bc"),
+      ByteArray::from(""),
+      ByteArray::from("def")
+    ];
+    let def_levels = [2, 2, 1, 2, 2, 0];
+    let rep_levels = [0, 1, 0, 0, 1, 0];
+    writer.write_batch(&values, Some(&def_levels), Some(&rep_levels)).unwrap();
+    // Each batch of 2 levels is written as a separate page
+    assert_eq!(
+      writer.get_page_size_statistics(),
+      &[
+        SizeStatistics::new(Some(3), Some(vec![1, 1]), Some(vec![0, 0, 2])),
+        SizeStatistics::new(Some(0), Some(vec![2, 0]), Some(vec![0, 1, 1])),
+        SizeStatistics::new(Some(3), Some(vec![1, 1]), Some(vec![1, 0, 1]))
+      ]
+    );
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(
+      metadata.size_statistics(),
+      Some(&SizeStatistics::new(Some(6), Some(vec![4, 2]), Some(vec![1, 1, 4])))
+    );
+
+    // Sizes of values are counted before dictionary encoding
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<ByteArrayType>(page_writer, 0, 0, props);
+    let values = vec![ByteArray::from("ab"), ByteArray::from("ab"), ByteArray::from("c")];
+    writer.write_batch(&values, None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(
+      metadata.size_statistics().and_then(|s| s.unencoded_byte_array_data_bytes()),
+      Some(5)
+    );
+
+    // Level histograms are not computed when max levels are 0
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, 0, 0, props);
+    writer.write_batch(&[1, 2, 3], None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(metadata.size_statistics(), Some(&SizeStatistics::default()));
+  }
+
+  #[test]
+  fn test_truncate_min_max_value() {
+    assert_eq!(truncate_min_value(&[1, 2, 3], 2, false), vec![1, 2]);
+    assert_eq!(truncate_max_value(&[1, 2, 3], 2, false), Some(vec![1, 3]));
+    assert_eq!(truncate_max_value(&[1, 0xFF, 3], 2, false), Some(vec![2]));
+    assert_eq!(truncate_max_value(&[0xFF, 0xFF, 3], 2, false), None);
+
+    // "aé" is 3 bytes and can not be truncated to 2 bytes at character boundary
+    assert_eq!(truncate_min_value("aéb".as_bytes(), 2, true), b"a".to_vec());
+    assert_eq!(truncate_max_value("aéb".as_bytes(), 2, true), Some(b"b".to_vec()));
+    assert_eq!(truncate_max_value("aéb".as_bytes(), 3, true), Some("aê".into()));
+    // Incremented character does not fit into 1 byte
+    assert_eq!(truncate_max_value("\u{7f}a".as_bytes(), 1, true), None);
+    // Invalid UTF-8 strings are truncated as bytes
+    assert_eq!(truncate_min_value(&[0xC3, 0x28, 1], 2, true), vec![0xC3, 0x28]);
+    assert_eq!(truncate_max_value(&[0xC3, 0x28, 1], 2, true), Some(vec![0xC3, 0x29]));
   }
 
   #[test]
@@ -901,6 +1813,26 @@ mod tests {
       ::std::i32::MIN, ::std::i32::MAX, 10, 10);
   }
 
+  #[test]
+  fn test_column_writer_adaptive_encoding_roundtrip() {
+    let props = WriterProperties::builder()
+      .set_writer_version(WriterVersion::PARQUET_2_0)
+      .set_adaptive_encoding(true)
+      .set_data_pagesize_limit(32)
+      .set_write_batch_size(10)
+      .build();
+    let values: Vec<i32> = (0..1024).map(|i| i * 3).collect();
+    column_roundtrip::<Int32Type>("test_col_writer_rnd_10", props, &values, None, None);
+
+    let props = WriterProperties::builder().set_adaptive_encoding(true).build();
+    column_roundtrip_random::<Int32Type>("test_col_writer_rnd_11", props, 1024,
+      ::std::i32::MIN, ::std::i32::MAX, 10, 10);
+
+    let props = WriterProperties::builder().set_adaptive_encoding(true).build();
+    column_roundtrip_random::<Int32Type>("test_col_writer_rnd_12", props, 1024,
+      0, 10, 10, 0);
+  }
+
   /// Performs write-read roundtrip with randomly generated values and levels.
   /// `max_size` is maximum number of values or levels (if `max_def_level` > 0) to write
   /// for a column.
@@ -0,0 +1,289 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Conformance tests against files of the
+//! [parquet-testing](https://github.com/apache/parquet-testing) corpus.
+//!
+//! Tests run only when `PARQUET_TESTING_DATA` environment variable is set to `data`
+//! directory of a checkout of the corpus, e.g.
+//!
+//! ```text
+//! PARQUET_TESTING_DATA=/path/to/parquet-testing/data cargo test conformance
+//! ```
+//!
+//! Every Parquet file in the directory is read row by row, and number of rows must
+//! match file metadata. Rows are checked against expected values when available:
+//! - `<file name>.json` in `data/conformance` directory, with a JSON object per line in
+//! the format of [`Row::to_json`](../record/struct.Row.html).
+//! - `<file stem>_expect.csv` next to the file in the corpus, with header of column
+//! names and a line per row, where empty value is null. Only flat rows without quoted
+//! values are supported.
+//!
+//! Files with Parquet modular encryption (`.parquet.encrypted`) are decrypted with the
+//! keys of the corpus, except for files with keys wrapped by a KMS, which must fail.
+//! Malformed files of the corpus must fail without panic.
+
+use std::env;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use encryption::decrypt::FileDecryptionProperties;
+use errors::{ParquetError, Result};
+use file::reader::{FileReader, SerializedFileReader};
+use record::Row;
+use schema::types::ColumnPath;
+use serde_json::{self, Value};
+use util::test_common::get_test_path;
+
+/// Environment variable with path to `data` directory of parquet-testing corpus.
+const TESTING_DATA_ENV: &str = "PARQUET_TESTING_DATA";
+
+const PARQUET_SUFFIX: &str = ".parquet";
+
+const ENCRYPTED_SUFFIX: &str = ".parquet.encrypted";
+
+/// Footer key of encrypted files of the corpus.
+const FOOTER_KEY: &[u8] = b"0123456789012345";
+
+/// Keys of encrypted columns of the corpus.
+const COLUMN_KEYS: &[(&str, &[u8])] = &[
+  ("double_field", b"1234567890123450"),
+  ("float_field", b"1234567890123451")
+];
+
+/// AAD prefix of encrypted files of the corpus that use one.
+const AAD_PREFIX: &[u8] = b"tester";
+
+/// Encrypted files of the corpus with keys wrapped by a KMS, which is not available.
+const KMS_FILES: &[&str] = &["external_key_material_java.parquet.encrypted"];
+
+/// Files of the corpus that are malformed on purpose.
+const MALFORMED_FILES: &[&str] = &[
+  "nation.dict-malformed.parquet",
+  "PARQUET-1481.parquet"
+];
+
+#[test]
+fn test_parquet_testing_corpus() {
+  let dir = match env::var_os(TESTING_DATA_ENV) {
+    Some(dir) => PathBuf::from(dir),
+    None => return
+  };
+
+  let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+    .unwrap()
+    .map(|entry| entry.unwrap().path())
+    .filter(|path| {
+      let name = file_name(path);
+      name.ends_with(PARQUET_SUFFIX) || name.ends_with(ENCRYPTED_SUFFIX)
+    })
+    .collect();
+  paths.sort();
+  assert!(!paths.is_empty(), "No Parquet files found in {:?}", dir);
+
+  // Check all files before failing, so that a single run reports all failures
+  let mut failures = vec![];
+  for path in paths {
+    let failure = match panic::catch_unwind(AssertUnwindSafe(|| check_file(&path))) {
+      Ok(Ok(())) => continue,
+      Ok(Err(error)) => error.to_string(),
+      Err(_) => "panicked".to_string()
+    };
+    failures.push(format!("{}: {}", file_name(&path), failure));
+  }
+  assert!(failures.is_empty(), "Conformance failures:\n{}", failures.join("\n"));
+}
+
+/// Reads file at `path` and checks rows against expected values.
+fn check_file(path: &Path) -> Result<()> {
+  let name = file_name(path);
+  if name.ends_with(ENCRYPTED_SUFFIX) {
+    let result = read_rows(path, Some(decryption_properties(&name)));
+    return match result {
+      Err(_) if KMS_FILES.contains(&name.as_str()) => Ok(()),
+      Ok(_) if KMS_FILES.contains(&name.as_str()) => {
+        Err(general_err!("Expected error for file with KMS keys"))
+      },
+      result => result.map(|_| ())
+    };
+  }
+
+  let result = read_rows(path, None);
+  if MALFORMED_FILES.contains(&name.as_str()) {
+    return match result {
+      Err(_) => Ok(()),
+      Ok(_) => Err(general_err!("Expected error for malformed file"))
+    };
+  }
+
+  let rows = result?;
+  let expected_json = get_test_path("conformance").join(format!("{}.json", name));
+  if expected_json.exists() {
+    check_json_rows(&rows, &fs::read_to_string(expected_json)?)?;
+  }
+  let stem = &name[..name.len() - PARQUET_SUFFIX.len()];
+  let expected_csv = path.with_file_name(format!("{}_expect.csv", stem));
+  if expected_csv.exists() {
+    check_csv_rows(&rows, &fs::read_to_string(expected_csv)?)?;
+  }
+  Ok(())
+}
+
+/// Returns properties to decrypt encrypted file `name` of the corpus.
+fn decryption_properties(name: &str) -> FileDecryptionProperties {
+  let mut builder =
+    FileDecryptionProperties::builder().set_footer_key(FOOTER_KEY.to_vec());
+  for &(column, key) in COLUMN_KEYS {
+    builder = builder.set_column_key(ColumnPath::from(column), key.to_vec());
+  }
+  // Prefix is either stored in file and checked, or must be supplied
+  if name.contains("aad") {
+    builder = builder.set_aad_prefix(AAD_PREFIX.to_vec());
+  }
+  builder.build().unwrap()
+}
+
+/// Reads all rows of file at `path`, decrypted with `decryption` properties if set,
+/// checks that number of rows matches metadata. Errors and panics of row iterator on
+/// invalid data are returned as error.
+fn read_rows(
+  path: &Path,
+  decryption: Option<FileDecryptionProperties>
+) -> Result<Vec<Row>> {
+  let file = fs::File::open(path)?;
+  let reader = match decryption {
+    Some(properties) => SerializedFileReader::new_with_decryption(file, properties)?,
+    None => SerializedFileReader::new(file)?
+  };
+  let num_rows = reader.metadata().file_metadata().num_rows();
+  let iter = reader.get_row_iter(None)?;
+  let rows = panic::catch_unwind(AssertUnwindSafe(|| iter.collect::<Result<Vec<Row>>>()))
+    .map_err(|_| general_err!("Reading rows panicked"))??;
+  if rows.len() as i64 != num_rows {
+    return Err(general_err!("Expected {} rows, found {}", num_rows, rows.len()));
+  }
+  Ok(rows)
+}
+
+/// Checks rows against JSON lines of `expected`, a JSON object per row.
+fn check_json_rows(rows: &[Row], expected: &str) -> Result<()> {
+  let lines: Vec<&str> =
+    expected.lines().filter(|line| !line.trim().is_empty()).collect();
+  if rows.len() != lines.len() {
+    return Err(general_err!(
+      "Expected {} JSON rows, found {}", lines.len(), rows.len()
+    ));
+  }
+
+  for (i, (row, line)) in rows.iter().zip(lines).enumerate() {
+    let json = row.to_json();
+    if parse_json(&json)? != parse_json(line)? {
+      return Err(general_err!("Row {}: expected {}, found {}", i, line, json));
+    }
+  }
+  Ok(())
+}
+
+/// Checks flat rows against CSV `expected`, with header of column names.
+fn check_csv_rows(rows: &[Row], expected: &str) -> Result<()> {
+  let mut lines = expected.lines().map(|line| line.trim_end_matches('\r'));
+  let columns: Vec<&str> = match lines.next() {
+    Some(header) => header.split(',').collect(),
+    None => return Err(general_err!("Expected CSV has no header"))
+  };
+  let lines: Vec<&str> = lines.filter(|line| !line.is_empty()).collect();
+  if rows.len() != lines.len() {
+    return Err(general_err!(
+      "Expected {} CSV rows, found {}", lines.len(), rows.len()
+    ));
+  }
+
+  for (i, (row, line)) in rows.iter().zip(lines).enumerate() {
+    let row = parse_json(&row.to_json())?;
+    let values: Vec<&str> = line.split(',').collect();
+    if values.len() != columns.len() {
+      return Err(general_err!(
+        "Row {}: expected {} CSV values, found {}", i, columns.len(), values.len()
+      ));
+    }
+    for (column, value) in columns.iter().zip(values) {
+      let field = row.get(column)
+        .ok_or_else(|| general_err!("Row {}: missing column '{}'", i, column))?;
+      if !csv_value_matches(field, value) {
+        return Err(general_err!(
+          "Row {}, column '{}': expected '{}', found {:?}", i, column, value, field
+        ));
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Returns true if JSON value of a field is equal to CSV `value`.
+fn csv_value_matches(field: &Value, value: &str) -> bool {
+  match *field {
+    Value::Null => value.is_empty(),
+    Value::Bool(field) => value.parse::<bool>().ok() == Some(field),
+    Value::Number(ref field) => value.parse::<f64>().ok() == field.as_f64(),
+    Value::String(ref field) => field == value,
+    Value::Array(ref bytes) => {
+      bytes.len() == value.len() &&
+        bytes.iter().zip(value.bytes()).all(|(b, v)| b.as_u64() == Some(v as u64))
+    },
+    Value::Object(_) => false
+  }
+}
+
+/// Parses JSON document `json`, returns error if it is not valid JSON.
+fn parse_json(json: &str) -> Result<Value> {
+  serde_json::from_str(json).map_err(|e| general_err!("Invalid JSON {}: {}", json, e))
+}
+
+fn file_name(path: &Path) -> String {
+  path.file_name().unwrap().to_string_lossy().into_owned()
+}
+
+#[test]
+fn test_check_rows_of_test_files() {
+  let path = get_test_path("nonnullable.impala.parquet");
+  check_file(&path).unwrap();
+  assert!(check_file(&get_test_path("nation.dict-malformed.parquet")).is_ok());
+
+  let rows = read_rows(&get_test_path("nulls.snappy.parquet"), None).unwrap();
+  let expected = fs::read_to_string(
+    get_test_path("conformance").join("nulls.snappy.parquet.json")
+  ).unwrap();
+  check_json_rows(&rows, &expected).unwrap();
+  assert!(check_json_rows(&rows[1..], &expected).is_err());
+
+  // Groups are not flat values
+  let expected = "b_struct\n1\n1\n1\n1\n1\n1\n1\n1\n";
+  assert!(check_csv_rows(&rows, expected).is_err());
+}
+
+#[test]
+fn test_csv_value_matches() {
+  assert!(csv_value_matches(&Value::Null, ""));
+  assert!(!csv_value_matches(&Value::Null, "0"));
+  assert!(csv_value_matches(&Value::Bool(true), "true"));
+  assert!(csv_value_matches(&Value::from(-12), "-12"));
+  assert!(csv_value_matches(&Value::from(1.5), "1.5"));
+  assert!(!csv_value_matches(&Value::from(1.5), "abc"));
+  assert!(csv_value_matches(&Value::from("abc"), "abc"));
+  assert!(csv_value_matches(&Value::from(vec![97, 98]), "ab"));
+}
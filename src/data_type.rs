@@ -18,11 +18,18 @@
 //! Data types that connect Parquet physical types with their Rust-specific
 //! representations.
 
+use std::cmp::Ordering;
+use std::fmt;
 use std::mem;
+use std::ops::Deref;
 
-use basic::Type;
-use byteorder::{BigEndian, ByteOrder};
+use basic::{TimeUnit, Type};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use chrono::{Datelike, NaiveDate};
+use errors::{ParquetError, Result};
+use util::float16::{f16_to_f32, f32_to_f16};
 use util::memory::{ByteBuffer, ByteBufferPtr};
+use util::utf8;
 
 /// Rust representation for logical type INT96, value is backed by an array of `u32`.
 /// The type only takes 12 bytes, without extra padding.
@@ -105,6 +112,46 @@ impl ByteArray {
     assert!(self.data.is_some());
     Self::from(self.data.as_ref().unwrap().range(start, len))
   }
+
+  /// Returns data as string slice, or error if data is not valid UTF-8.
+  pub fn as_utf8(&self) -> Result<&str> {
+    utf8::from_utf8(self.data())
+      .map_err(|e| general_err!("Byte array is not valid UTF-8: {}", e))
+  }
+
+  /// Creates value of FLOAT16 logical type, i.e. 2 bytes in little-endian byte order,
+  /// from bits of a half-precision float.
+  pub fn from_f16_bits(bits: u16) -> Self {
+    let mut buf = vec![0; 2];
+    LittleEndian::write_u16(&mut buf, bits);
+    Self::from(buf)
+  }
+
+  /// Creates value of FLOAT16 logical type from `value` rounded to the nearest
+  /// half-precision float.
+  pub fn from_f32_as_f16(value: f32) -> Self {
+    Self::from_f16_bits(f32_to_f16(value))
+  }
+
+  /// Returns value of FLOAT16 logical type as `f32`, or error if data is not 2 bytes.
+  pub fn f16_to_f32(&self) -> Result<f32> {
+    match self.data() {
+      bytes if bytes.len() == 2 => Ok(f16_to_f32(LittleEndian::read_u16(bytes))),
+      bytes => Err(general_err!("Invalid FLOAT16 value length: {}", bytes.len()))
+    }
+  }
+}
+
+impl<'a> From<&'a [u8]> for ByteArray {
+  fn from(buf: &'a [u8]) -> ByteArray {
+    Self::from(buf.to_vec())
+  }
+}
+
+impl From<String> for ByteArray {
+  fn from(s: String) -> ByteArray {
+    Self::from(s.into_bytes())
+  }
 }
 
 impl From<Vec<u8>> for ByteArray {
@@ -145,6 +192,63 @@ impl PartialEq for ByteArray {
   }
 }
 
+impl Eq for ByteArray {}
+
+/// Byte arrays are ordered lexicographically by content, as unsigned bytes.
+impl PartialOrd for ByteArray {
+  fn partial_cmp(&self, other: &ByteArray) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for ByteArray {
+  fn cmp(&self, other: &ByteArray) -> Ordering {
+    self.data().cmp(other.data())
+  }
+}
+
+/// Byte array with fixed length, i.e. value of FIXED_LEN_BYTE_ARRAY Parquet physical
+/// type. Length of the value is checked when it is created, so it always matches type
+/// length of a column.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FixedLenByteArray {
+  value: ByteArray
+}
+
+impl FixedLenByteArray {
+  /// Creates new fixed length byte array from `value`, returns error if length of
+  /// `value` is not `type_length`.
+  pub fn try_new(value: ByteArray, type_length: i32) -> Result<Self> {
+    if type_length < 0 || value.len() != type_length as usize {
+      return Err(general_err!(
+        "Expected byte array of length {}, found: {}",
+        type_length,
+        value.len()
+      ));
+    }
+    Ok(Self { value: value })
+  }
+
+  /// Returns underlying byte array.
+  pub fn into_inner(self) -> ByteArray {
+    self.value
+  }
+}
+
+impl Deref for FixedLenByteArray {
+  type Target = ByteArray;
+
+  fn deref(&self) -> &ByteArray {
+    &self.value
+  }
+}
+
+impl From<FixedLenByteArray> for ByteArray {
+  fn from(value: FixedLenByteArray) -> ByteArray {
+    value.value
+  }
+}
+
 /// Rust representation for Decimal values.
 ///
 /// This is not a representation of Parquet physical type, but rather a wrapper for
@@ -180,6 +284,35 @@ impl Decimal {
     Decimal::Bytes { value: value, precision: precision, scale: scale }
   }
 
+  /// Creates new decimal value from `i128`, unscaled value is stored as 16 bytes.
+  pub fn from_i128(value: i128, precision: i32, scale: i32) -> Self {
+    let mut bytes = vec![0; 16];
+    BigEndian::write_i128(&mut bytes, value);
+    Self::from_bytes(ByteArray::from(bytes), precision, scale)
+  }
+
+  /// Returns unscaled value as `i128`.
+  ///
+  /// Bytes of unscaled value are interpreted as big-endian two's complement number,
+  /// returns error if value does not fit into `i128`.
+  pub fn to_i128(&self) -> Result<i128> {
+    let data = self.data();
+    if data.is_empty() {
+      return Err(general_err!("Decimal value is empty"));
+    }
+    // Leading bytes over 16 bytes can only be sign extension
+    let sign_byte = if data[0] & 0x80 != 0 { 0xff } else { 0 };
+    let num_extra_bytes = data.len().saturating_sub(16);
+    let (extra, data) = data.split_at(num_extra_bytes);
+    if extra.iter().any(|b| *b != sign_byte) ||
+        (num_extra_bytes > 0 && (data[0] ^ sign_byte) & 0x80 != 0) {
+      return Err(general_err!("Decimal value does not fit into i128"));
+    }
+    let mut bytes = [sign_byte; 16];
+    bytes[16 - data.len()..].copy_from_slice(data);
+    Ok(BigEndian::read_i128(&bytes))
+  }
+
   /// Returns bytes of unscaled value.
   pub fn data(&self) -> &[u8] {
     match *self {
@@ -221,6 +354,241 @@ impl PartialEq for Decimal {
   }
 }
 
+/// Rust representation for INTERVAL values.
+///
+/// This is not a representation of Parquet physical type, but rather a wrapper for
+/// INTERVAL logical type, stored as FIXED_LEN_BYTE_ARRAY of length 12 that contains
+/// three little-endian unsigned integers: months, days and milliseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Interval {
+  months: u32,
+  days: u32,
+  millis: u32
+}
+
+impl Interval {
+  /// Creates new interval value from its components.
+  pub fn new(months: u32, days: u32, millis: u32) -> Self {
+    Interval { months: months, days: days, millis: millis }
+  }
+
+  /// Creates new interval value from 12 bytes of INTERVAL logical type, returns error
+  /// if length of `bytes` is not 12.
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+    if bytes.len() != 12 {
+      return Err(general_err!("Invalid INTERVAL value length: {}", bytes.len()));
+    }
+    Ok(Self::new(
+      LittleEndian::read_u32(&bytes[0..4]),
+      LittleEndian::read_u32(&bytes[4..8]),
+      LittleEndian::read_u32(&bytes[8..12])
+    ))
+  }
+
+  /// Returns number of months.
+  pub fn months(&self) -> u32 {
+    self.months
+  }
+
+  /// Returns number of days.
+  pub fn days(&self) -> u32 {
+    self.days
+  }
+
+  /// Returns number of milliseconds.
+  pub fn millis(&self) -> u32 {
+    self.millis
+  }
+
+  /// Returns 12 bytes of INTERVAL logical type, that can be written as
+  /// FIXED_LEN_BYTE_ARRAY value.
+  pub fn to_bytes(&self) -> ByteArray {
+    let mut buf = vec![0; 12];
+    LittleEndian::write_u32(&mut buf[0..4], self.months);
+    LittleEndian::write_u32(&mut buf[4..8], self.days);
+    LittleEndian::write_u32(&mut buf[8..12], self.millis);
+    ByteArray::from(buf)
+  }
+}
+
+impl fmt::Display for Interval {
+  /// Formats interval as ISO 8601 duration, e.g. `P1M2DT0.003S`.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "P{}M{}DT{}.{:03}S",
+      self.months,
+      self.days,
+      self.millis / 1000,
+      self.millis % 1000
+    )
+  }
+}
+
+
+/// Rust representation for TIME values.
+///
+/// This is not a representation of Parquet physical type, but rather a wrapper for
+/// TIME logical types: number of units since midnight and whether it is adjusted to UTC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Time {
+  value: i64,
+  unit: TimeUnit,
+  is_adjusted_to_utc: bool
+}
+
+impl Time {
+  /// Creates new time value from number of `unit`s since midnight.
+  pub fn new(value: i64, unit: TimeUnit, is_adjusted_to_utc: bool) -> Self {
+    Time { value: value, unit: unit, is_adjusted_to_utc: is_adjusted_to_utc }
+  }
+
+  /// Returns number of units since midnight.
+  pub fn value(&self) -> i64 {
+    self.value
+  }
+
+  /// Returns unit of the value.
+  pub fn unit(&self) -> TimeUnit {
+    self.unit
+  }
+
+  /// Returns `true` if time is adjusted to UTC.
+  pub fn is_adjusted_to_utc(&self) -> bool {
+    self.is_adjusted_to_utc
+  }
+
+  /// Returns number of nanoseconds since midnight.
+  pub fn nanos(&self) -> i64 {
+    self.value * (1_000_000_000 / self.unit.units_per_second())
+  }
+}
+
+impl Ord for Time {
+  /// Compares times regardless of their units, ties are broken by UTC adjustment and
+  /// unit, so that the order is consistent with equality.
+  fn cmp(&self, other: &Time) -> Ordering {
+    let key = |t: &Time| (t.nanos(), t.is_adjusted_to_utc, t.unit as u8);
+    key(self).cmp(&key(other))
+  }
+}
+
+impl PartialOrd for Time {
+  fn partial_cmp(&self, other: &Time) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl fmt::Display for Time {
+  /// Formats time as `HH:MM:SS.fff`, with as many fractional digits as the unit has,
+  /// e.g. `12:30:05.000250` for microseconds.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let units_per_second = self.unit.units_per_second();
+    let seconds = self.value / units_per_second;
+    let digits = match self.unit {
+      TimeUnit::MILLIS => 3,
+      TimeUnit::MICROS => 6,
+      TimeUnit::NANOS => 9
+    };
+    write!(
+      f,
+      "{:02}:{:02}:{:02}.{:0width$}",
+      seconds / 3600,
+      seconds / 60 % 60,
+      seconds % 60,
+      self.value % units_per_second,
+      width = digits
+    )
+  }
+}
+
+/// Rust representation for TIMESTAMP values.
+///
+/// This is not a representation of Parquet physical type, but rather a wrapper for
+/// TIMESTAMP logical types and INT96 timestamps: number of units since the Unix epoch
+/// and whether it is adjusted to UTC. Timestamps that are not adjusted to UTC are local
+/// date and time, i.e. the epoch is 1970-01-01 00:00:00 in an unknown time zone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timestamp {
+  value: i64,
+  unit: TimeUnit,
+  is_adjusted_to_utc: bool
+}
+
+impl Timestamp {
+  /// Creates new timestamp value from number of `unit`s since the Unix epoch.
+  pub fn new(value: i64, unit: TimeUnit, is_adjusted_to_utc: bool) -> Self {
+    Timestamp { value: value, unit: unit, is_adjusted_to_utc: is_adjusted_to_utc }
+  }
+
+  /// Returns number of units since the Unix epoch.
+  pub fn value(&self) -> i64 {
+    self.value
+  }
+
+  /// Returns unit of the value.
+  pub fn unit(&self) -> TimeUnit {
+    self.unit
+  }
+
+  /// Returns `true` if timestamp is adjusted to UTC.
+  pub fn is_adjusted_to_utc(&self) -> bool {
+    self.is_adjusted_to_utc
+  }
+
+  /// Returns number of whole seconds since the Unix epoch and nanoseconds within the
+  /// second, which are always non-negative.
+  pub fn seconds_and_nanos(&self) -> (i64, u32) {
+    let units_per_second = self.unit.units_per_second();
+    let nanos_per_unit = 1_000_000_000 / units_per_second;
+    let mut seconds = self.value / units_per_second;
+    let mut units = self.value % units_per_second;
+    if units < 0 {
+      seconds -= 1;
+      units += units_per_second;
+    }
+    (seconds, (units * nanos_per_unit) as u32)
+  }
+
+  /// Returns number of milliseconds since the Unix epoch, rounded towards negative
+  /// infinity.
+  pub fn millis(&self) -> i64 {
+    let (seconds, nanos) = self.seconds_and_nanos();
+    seconds * 1000 + (nanos / 1_000_000) as i64
+  }
+}
+
+impl Ord for Timestamp {
+  /// Compares timestamps as points in time regardless of their units, ties are broken
+  /// by UTC adjustment and unit, so that the order is consistent with equality.
+  fn cmp(&self, other: &Timestamp) -> Ordering {
+    let key = |t: &Timestamp| (t.seconds_and_nanos(), t.is_adjusted_to_utc, t.unit as u8);
+    key(self).cmp(&key(other))
+  }
+}
+
+impl PartialOrd for Timestamp {
+  fn partial_cmp(&self, other: &Timestamp) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+/// Number of days from 1 January of year 1 (CE) to the Unix epoch, 1 January 1970.
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+/// Converts DATE value, i.e. number of days since the Unix epoch, into a date.
+/// Returns `None` if the date is out of range of `NaiveDate`.
+pub fn date_from_days(days: i32) -> Option<NaiveDate> {
+  days
+    .checked_add(UNIX_EPOCH_DAYS_FROM_CE)
+    .and_then(NaiveDate::from_num_days_from_ce_opt)
+}
+
+/// Converts date into DATE value, i.e. number of days since the Unix epoch, that can be
+/// written into INT32 column.
+pub fn date_to_days(date: NaiveDate) -> i32 {
+  date.num_days_from_ce() - UNIX_EPOCH_DAYS_FROM_CE
+}
 
 /// Converts an instance of data type to a slice of bytes as `u8`.
 pub trait AsBytes {
@@ -265,6 +633,12 @@ impl AsBytes for ByteArray {
   }
 }
 
+impl AsBytes for FixedLenByteArray {
+  fn as_bytes(&self) -> &[u8] {
+    self.value.data()
+  }
+}
+
 impl AsBytes for Decimal {
   fn as_bytes(&self) -> &[u8] {
     self.data()
@@ -291,9 +665,9 @@ impl AsBytes for str {
 
 /// Contains the Parquet physical type information as well as the Rust primitive type
 /// presentation.
-pub trait DataType: 'static {
+pub trait DataType: 'static + Send + Sync {
   type T: ::std::cmp::PartialEq + ::std::fmt::Debug + ::std::default::Default
-    + ::std::clone::Clone + AsBytes;
+    + ::std::clone::Clone + AsBytes + Send + Sync;
 
   /// Returns Parquet physical type.
   fn get_physical_type() -> Type;
@@ -398,6 +772,158 @@ mod tests {
     assert_eq!(ByteArray::from(buf).data(), &[6u8, 7u8, 8u8, 9u8, 10u8]);
   }
 
+  #[test]
+  fn test_byte_array_as_utf8() {
+    assert_eq!(ByteArray::from("abc").as_utf8().unwrap(), "abc");
+    assert_eq!(ByteArray::from(String::from("\u{e9}")).as_utf8().unwrap(), "\u{e9}");
+    assert!(ByteArray::from(vec![b'a', 0xff]).as_utf8().is_err());
+  }
+
+  #[test]
+  fn test_byte_array_f16() {
+    assert_eq!(ByteArray::from_f16_bits(0x3c00).data(), &[0x00, 0x3c]);
+    assert_eq!(ByteArray::from_f32_as_f16(-2.0).data(), &[0x00, 0xc0]);
+    assert_eq!(ByteArray::from_f32_as_f16(1.5).f16_to_f32().unwrap(), 1.5);
+    assert!(ByteArray::from_f32_as_f16(::std::f32::NAN).f16_to_f32().unwrap().is_nan());
+    assert!(ByteArray::from(vec![1, 2, 3]).f16_to_f32().is_err());
+  }
+
+  #[test]
+  fn test_interval() {
+    let bytes = [1, 0, 0, 0, 2, 0, 0, 0, 0xd2, 0x04, 0, 0];
+    let interval = Interval::from_bytes(&bytes).unwrap();
+    assert_eq!(interval, Interval::new(1, 2, 1234));
+    assert_eq!((interval.months(), interval.days(), interval.millis()), (1, 2, 1234));
+    assert_eq!(interval.to_bytes().data(), &bytes);
+    assert_eq!(interval.to_string(), "P1M2DT1.234S");
+    assert_eq!(Interval::new(0, 0, 5).to_string(), "P0M0DT0.005S");
+    assert!(Interval::from_bytes(&bytes[1..]).is_err());
+  }
+
+  #[test]
+  fn test_time() {
+    let time = Time::new(45_005_001, TimeUnit::MILLIS, true);
+    assert_eq!(time.value(), 45_005_001);
+    assert_eq!(time.unit(), TimeUnit::MILLIS);
+    assert!(time.is_adjusted_to_utc());
+    assert_eq!(time.nanos(), 45_005_001_000_000);
+    assert_eq!(time.to_string(), "12:30:05.001");
+
+    let time = Time::new(45_005_000_250, TimeUnit::MICROS, false);
+    assert_eq!(time.to_string(), "12:30:05.000250");
+    let time = Time::new(1, TimeUnit::NANOS, false);
+    assert_eq!(time.to_string(), "00:00:00.000000001");
+    assert_eq!(Time::new(0, TimeUnit::MILLIS, false).to_string(), "00:00:00.000");
+
+    let millis = Time::new(1, TimeUnit::MILLIS, true);
+    let micros = Time::new(1_000, TimeUnit::MICROS, true);
+    assert!(millis != micros);
+    assert!(millis < micros);
+    assert!(Time::new(999, TimeUnit::MICROS, true) < millis);
+  }
+
+  #[test]
+  fn test_timestamp() {
+    let ts = Timestamp::new(1_500, TimeUnit::MILLIS, true);
+    assert_eq!(ts.value(), 1_500);
+    assert_eq!(ts.unit(), TimeUnit::MILLIS);
+    assert!(ts.is_adjusted_to_utc());
+    assert_eq!(ts.seconds_and_nanos(), (1, 500_000_000));
+    assert_eq!(ts.millis(), 1_500);
+
+    let ts = Timestamp::new(-1_500_001, TimeUnit::MICROS, false);
+    assert_eq!(ts.seconds_and_nanos(), (-2, 499_999_000));
+    assert_eq!(ts.millis(), -1_501);
+
+    let ts = Timestamp::new(1_000_000_001, TimeUnit::NANOS, true);
+    assert_eq!(ts.seconds_and_nanos(), (1, 1));
+    assert_eq!(ts.millis(), 1_000);
+
+    let millis = Timestamp::new(1_000, TimeUnit::MILLIS, true);
+    let nanos = Timestamp::new(1_000_000_000, TimeUnit::NANOS, true);
+    assert!(millis != nanos);
+    assert!(millis < nanos);
+    assert!(Timestamp::new(999_999_999, TimeUnit::NANOS, true) < millis);
+    assert!(Timestamp::new(1_001, TimeUnit::MILLIS, true) > nanos);
+  }
+
+  #[test]
+  fn test_date_days_conversion() {
+    let epoch = NaiveDate::from_ymd(1970, 1, 1);
+    assert_eq!(date_from_days(0), Some(epoch));
+    assert_eq!(date_to_days(epoch), 0);
+
+    let date = NaiveDate::from_ymd(2010, 1, 2);
+    assert_eq!(date_from_days(14611), Some(date));
+    assert_eq!(date_to_days(date), 14611);
+
+    let date = NaiveDate::from_ymd(1969, 12, 31);
+    assert_eq!(date_from_days(-1), Some(date));
+    assert_eq!(date_to_days(date), -1);
+
+    assert_eq!(date_from_days(::std::i32::MAX), None);
+    assert_eq!(date_from_days(::std::i32::MIN), None);
+  }
+
+  #[test]
+  fn test_byte_array_ord() {
+    assert!(ByteArray::from("abc") < ByteArray::from("abd"));
+    assert!(ByteArray::from("ab") < ByteArray::from("abc"));
+    assert!(ByteArray::from(&[0x7f][..]) < ByteArray::from(&[0x80][..]));
+    assert_eq!(ByteArray::from("abc").cmp(&ByteArray::from("abc")), Ordering::Equal);
+
+    let mut values: Vec<ByteArray> =
+      vec!["b", "c", "a"].into_iter().map(From::from).collect();
+    values.sort();
+    assert_eq!(values, vec![ByteArray::from("a"), ByteArray::from("b"), "c".into()]);
+  }
+
+  #[test]
+  fn test_fixed_len_byte_array() {
+    let value = FixedLenByteArray::try_new(ByteArray::from(vec![1, 2, 3]), 3).unwrap();
+    assert_eq!(value.len(), 3);
+    assert_eq!(value.as_bytes(), &[1, 2, 3]);
+    assert_eq!(ByteArray::from(value), ByteArray::from(vec![1, 2, 3]));
+
+    assert!(FixedLenByteArray::try_new(ByteArray::from(vec![1, 2, 3]), 4).is_err());
+    assert!(FixedLenByteArray::try_new(ByteArray::from(vec![]), -1).is_err());
+  }
+
+  #[test]
+  fn test_decimal_i128() {
+    assert_eq!(Decimal::from_i32(-123, 5, 2).to_i128().unwrap(), -123);
+    let decimal = Decimal::from_i64(i64::max_value(), 19, 0);
+    assert_eq!(decimal.to_i128().unwrap(), i64::max_value() as i128);
+    let decimal = Decimal::from_bytes(ByteArray::from(vec![0xff, 0x38]), 5, 2);
+    assert_eq!(decimal.to_i128().unwrap(), -200);
+    let decimal = Decimal::from_bytes(ByteArray::from(vec![0x00, 0x80]), 5, 2);
+    assert_eq!(decimal.to_i128().unwrap(), 128);
+
+    let decimal = Decimal::from_i128(i128::min_value(), 38, 0);
+    assert_eq!(decimal.data().len(), 16);
+    assert_eq!(decimal.to_i128().unwrap(), i128::min_value());
+    let mut bytes = vec![0xff; 15];
+    bytes.push(0x85);
+    let decimal = Decimal::from_bytes(ByteArray::from(bytes), 5, 2);
+    assert_eq!(Decimal::from_i128(-123, 5, 2), decimal);
+
+    // Sign extension beyond 16 bytes
+    let mut bytes = vec![0xff; 4];
+    bytes.extend_from_slice(Decimal::from_i128(-5, 38, 0).data());
+    let decimal = Decimal::from_bytes(ByteArray::from(bytes), 38, 0);
+    assert_eq!(decimal.to_i128().unwrap(), -5);
+
+    let mut bytes = vec![0x01];
+    bytes.extend_from_slice(&[0; 16]);
+    let decimal = Decimal::from_bytes(ByteArray::from(bytes), 38, 0);
+    assert!(decimal.to_i128().is_err());
+    let mut bytes = vec![0x00];
+    bytes.extend_from_slice(&[0xff; 16]);
+    let decimal = Decimal::from_bytes(ByteArray::from(bytes), 38, 0);
+    assert!(decimal.to_i128().is_err());
+    assert!(Decimal::from_bytes(ByteArray::from(vec![]), 1, 0).to_i128().is_err());
+  }
+
   #[test]
   fn test_decimal_partial_eq() {
     assert_eq!(Decimal::default(), Decimal::from_i32(0, 0, 0));
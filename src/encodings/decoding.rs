@@ -20,7 +20,6 @@
 use std::cmp;
 use std::marker::PhantomData;
 use std::mem;
-use std::slice::from_raw_parts_mut;
 
 use super::rle::RleDecoder;
 use basic::*;
@@ -29,13 +28,15 @@ use data_type::*;
 use errors::{ParquetError, Result};
 use schema::types::ColumnDescPtr;
 use util::bit_util::BitReader;
-use util::memory::{ByteBuffer, ByteBufferPtr};
+use util::memory::{Buffer, BufferPtr, ByteBuffer, ByteBufferPtr, MemTrackerPtr};
 
 // ----------------------------------------------------------------------
 // Decoders
 
 /// A Parquet decoder for the data type `T`.
-pub trait Decoder<T: DataType> {
+///
+/// Decoders are `Send`, so boxed decoders can be moved to other threads.
+pub trait Decoder<T: DataType>: Send {
   /// Sets the data to decode to be `data`, which should contain `num_values` of values
   /// to decode.
   fn set_data(&mut self, data: ByteBufferPtr, num_values: usize) -> Result<()>;
@@ -47,6 +48,15 @@ pub trait Decoder<T: DataType> {
   /// unless the remaining number of values is less than `buffer.len()`.
   fn get(&mut self, buffer: &mut [T::T]) -> Result<usize>;
 
+  /// Skips at most `num_values` values without returning them.
+  ///
+  /// Returns the actual number of values skipped, which should be equal to
+  /// `num_values` unless the remaining number of values is less than `num_values`.
+  /// By default values are decoded into a temporary buffer and discarded.
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    skip_by_decoding(self, num_values)
+  }
+
   /// Returns the number of values left in this decoder stream.
   fn values_left(&self) -> usize;
 
@@ -54,36 +64,144 @@ pub trait Decoder<T: DataType> {
   fn encoding(&self) -> Encoding;
 }
 
+/// Maximum number of values decoded at once when skipping values by decoding them.
+const SKIP_BATCH_SIZE: usize = 1024;
+
+/// Skips at most `num_values` values of `decoder` by decoding them into a temporary
+/// buffer. Returns the actual number of values skipped.
+fn skip_by_decoding<T: DataType, D: Decoder<T> + ?Sized>(
+  decoder: &mut D,
+  num_values: usize
+) -> Result<usize> {
+  let mut buffer = vec![T::T::default(); cmp::min(num_values, SKIP_BATCH_SIZE)];
+  let mut values_skipped = 0;
+  while values_skipped < num_values {
+    let batch_size = cmp::min(buffer.len(), num_values - values_skipped);
+    let values_read = decoder.get(&mut buffer[0..batch_size])?;
+    if values_read == 0 {
+      break;
+    }
+    values_skipped += values_read;
+  }
+  Ok(values_skipped)
+}
+
 /// Gets a decoder for the column descriptor `descr` and encoding type `encoding`.
+/// Memory allocated by decoder internally is tracked by `mem_tracker`.
+///
+/// Dictionary encodings are not supported, use
+/// [`DecoderFactory::get_dict_decoder`](struct.DecoderFactory.html) instead.
 ///
 /// NOTE: the primitive type in `descr` MUST match the data type `T`, otherwise
 /// disastrous consequence could occur.
 pub fn get_decoder<T: DataType>(
   descr: ColumnDescPtr,
-  encoding: Encoding
+  encoding: Encoding,
+  mem_tracker: MemTrackerPtr
 ) -> Result<Box<Decoder<T>>> {
-  let decoder: Box<Decoder<T>> = match encoding {
-    Encoding::PLAIN => {
-      Box::new(PlainDecoder::new(descr.type_length()))
-    },
-    Encoding::RLE_DICTIONARY | Encoding::PLAIN_DICTIONARY => {
-      return Err(general_err!("Cannot initialize this encoding through this function"))
-    },
-    Encoding::RLE => {
-      Box::new(RleValueDecoder::new())
-    },
-    Encoding::DELTA_BINARY_PACKED => {
-      Box::new(DeltaBitPackDecoder::new())
-    },
-    Encoding::DELTA_LENGTH_BYTE_ARRAY => {
-      Box::new(DeltaLengthByteArrayDecoder::new())
-    },
-    Encoding::DELTA_BYTE_ARRAY => {
-      Box::new(DeltaByteArrayDecoder::new())
-    },
-    e => return Err(nyi_err!("Encoding {} is not supported", e))
-  };
-  Ok(decoder)
+  DecoderFactory::new(descr, mem_tracker).get_decoder(encoding)
+}
+
+/// Factory of decoders for values of a single column.
+///
+/// Creates decoders for data page encodings, checking that the encoding is valid for
+/// the physical type of the column, and bootstraps dictionary decoders from the values
+/// of dictionary pages.
+pub struct DecoderFactory<T: DataType> {
+  descr: ColumnDescPtr,
+  mem_tracker: MemTrackerPtr,
+  _phantom: PhantomData<T>
+}
+
+impl<T: DataType> DecoderFactory<T> {
+  /// Creates new factory for column descriptor `descr`.
+  /// Memory allocated by created decoders internally is tracked by `mem_tracker`.
+  ///
+  /// NOTE: the primitive type in `descr` MUST match the data type `T`.
+  pub fn new(descr: ColumnDescPtr, mem_tracker: MemTrackerPtr) -> Self {
+    Self { descr, mem_tracker, _phantom: PhantomData }
+  }
+
+  /// Returns column descriptor of this factory.
+  pub fn column_descr(&self) -> &ColumnDescPtr {
+    &self.descr
+  }
+
+  /// Creates decoder for data page values encoded with `encoding`.
+  ///
+  /// Returns error if `encoding` is a dictionary encoding, which requires decoder
+  /// created by `get_dict_decoder`, or if it is invalid for the physical type of the
+  /// column.
+  pub fn get_decoder(&self, encoding: Encoding) -> Result<Box<Decoder<T>>> {
+    let physical_type = self.descr.physical_type();
+    let decoder: Box<Decoder<T>> = match encoding {
+      Encoding::PLAIN => {
+        Box::new(PlainDecoder::new(self.descr.type_length()))
+      },
+      Encoding::RLE_DICTIONARY | Encoding::PLAIN_DICTIONARY => {
+        return Err(general_err!("Cannot initialize this encoding through this function"))
+      },
+      Encoding::RLE if physical_type == Type::BOOLEAN => {
+        Box::new(RleValueDecoder::new())
+      },
+      Encoding::DELTA_BINARY_PACKED
+          if physical_type == Type::INT32 || physical_type == Type::INT64 => {
+        Box::new(DeltaBitPackDecoder::new().with_mem_tracker(self.mem_tracker.clone()))
+      },
+      Encoding::DELTA_LENGTH_BYTE_ARRAY if physical_type == Type::BYTE_ARRAY => {
+        Box::new(DeltaLengthByteArrayDecoder::new(self.mem_tracker.clone()))
+      },
+      Encoding::DELTA_BYTE_ARRAY
+          if physical_type == Type::BYTE_ARRAY ||
+            physical_type == Type::FIXED_LEN_BYTE_ARRAY => {
+        Box::new(DeltaByteArrayDecoder::new(self.mem_tracker.clone()))
+      },
+      Encoding::RLE |
+          Encoding::DELTA_BINARY_PACKED |
+          Encoding::DELTA_LENGTH_BYTE_ARRAY |
+          Encoding::DELTA_BYTE_ARRAY => {
+        return Err(general_err!(
+          "Encoding {} is invalid for physical type {}",
+          encoding,
+          physical_type
+        ))
+      },
+      e => return Err(nyi_err!("Encoding {} is not supported", e))
+    };
+    Ok(decoder)
+  }
+
+  /// Creates dictionary decoder from `num_values` values of a dictionary page stored in
+  /// `buf` with `encoding`.
+  ///
+  /// Dictionary values are always PLAIN encoded, `encoding` is `PLAIN`, deprecated
+  /// `PLAIN_DICTIONARY`, or `RLE_DICTIONARY` written by some writers. Returned decoder
+  /// is used for data pages with `RLE_DICTIONARY` or `PLAIN_DICTIONARY` encodings.
+  pub fn get_dict_decoder(
+    &self,
+    encoding: Encoding,
+    buf: ByteBufferPtr,
+    num_values: usize
+  ) -> Result<DictDecoder<T>> {
+    if encoding != Encoding::PLAIN && encoding != Encoding::PLAIN_DICTIONARY &&
+        encoding != Encoding::RLE_DICTIONARY {
+      return Err(nyi_err!(
+        "Invalid/Unsupported encoding type for dictionary: {}",
+        encoding
+      ))
+    }
+    let mut dictionary = PlainDecoder::<T>::new(self.descr.type_length());
+    dictionary.set_data(buf, num_values)?;
+    let mut decoder = self.new_dict_decoder();
+    decoder.set_dict(Box::new(dictionary))?;
+    Ok(decoder)
+  }
+
+  /// Creates dictionary decoder without dictionary, which should be set with
+  /// `DictDecoder::set_dict_values`, e.g. with dictionary values of another decoder.
+  pub fn new_dict_decoder(&self) -> DictDecoder<T> {
+    DictDecoder::new(self.mem_tracker.clone())
+  }
 }
 
 // ----------------------------------------------------------------------
@@ -147,32 +265,71 @@ impl<T: DataType> Decoder<T> for PlainDecoder<T> {
   }
 
   #[inline]
-  default fn get(&mut self, buffer: &mut [T::T]) -> Result<usize> {
-    assert!(self.data.is_some());
+  default fn get(&mut self, _: &mut [T::T]) -> Result<usize> {
+    Err(general_err!("PlainDecoder does not support {}", T::get_physical_type()))
+  }
 
-    let data = self.data.as_mut().unwrap();
-    let num_values = cmp::min(buffer.len(), self.num_values);
-    let bytes_left = data.len() - self.start;
-    let bytes_to_decode = mem::size_of::<T::T>() * num_values;
-    if bytes_left < bytes_to_decode {
-      return Err(eof_err!("Not enough bytes to decode"));
+  #[inline]
+  default fn skip(&mut self, num_values: usize) -> Result<usize> {
+    self.skip_fixed_width(num_values, mem::size_of::<T::T>())
+  }
+}
+
+impl<T: DataType> PlainDecoder<T> {
+  /// Skips values of `type_size` bytes each by advancing the current position.
+  #[inline]
+  fn skip_fixed_width(&mut self, num_values: usize, type_size: usize) -> Result<usize> {
+    let data = self.data.as_ref()
+      .ok_or(general_err!("Data is not set, call set_data() first"))?;
+    let num_values = cmp::min(num_values, self.num_values);
+    let bytes_to_skip = type_size * num_values;
+    if data.len() - self.start < bytes_to_skip {
+      return Err(eof_err!("Not enough bytes to skip"));
     }
-    let raw_buffer: &mut [u8] = unsafe {
-      from_raw_parts_mut(buffer.as_ptr() as *mut u8, bytes_to_decode)
-    };
-    raw_buffer.copy_from_slice(data.range(self.start, bytes_to_decode).as_ref());
-    self.start += bytes_to_decode;
+    self.start += bytes_to_skip;
     self.num_values -= num_values;
-
     Ok(num_values)
   }
 }
 
+/// Generates PLAIN decoding of fixed width numeric types. Values are copied from little
+/// endian bytes in bulk with `read_into`, which does not require `buffer` to be
+/// reinterpreted as bytes.
+macro_rules! plain_decode_numeric {
+  ($data_ty:ty, $native_ty:ty, $read_into:ident) => {
+    impl Decoder<$data_ty> for PlainDecoder<$data_ty> {
+      #[inline]
+      fn get(&mut self, buffer: &mut [$native_ty]) -> Result<usize> {
+        let data = self.data.as_ref()
+          .ok_or(general_err!("Data is not set, call set_data() first"))?;
+        let num_values = cmp::min(buffer.len(), self.num_values);
+        let bytes_left = data.len() - self.start;
+        let bytes_to_decode = mem::size_of::<$native_ty>() * num_values;
+        if bytes_left < bytes_to_decode {
+          return Err(eof_err!("Not enough bytes to decode"));
+        }
+        LittleEndian::$read_into(
+          &data.as_ref()[self.start..self.start + bytes_to_decode],
+          &mut buffer[..num_values]
+        );
+        self.start += bytes_to_decode;
+        self.num_values -= num_values;
+
+        Ok(num_values)
+      }
+    }
+  };
+}
+
+plain_decode_numeric!(Int32Type, i32, read_i32_into);
+plain_decode_numeric!(Int64Type, i64, read_i64_into);
+plain_decode_numeric!(FloatType, f32, read_f32_into);
+plain_decode_numeric!(DoubleType, f64, read_f64_into);
+
 impl Decoder<Int96Type> for PlainDecoder<Int96Type> {
   fn get(&mut self, buffer: &mut [Int96]) -> Result<usize> {
-    assert!(self.data.is_some());
-
-    let data = self.data.as_ref().unwrap();
+    let data = self.data.as_ref()
+      .ok_or(general_err!("Data is not set, call set_data() first"))?;
     let num_values = cmp::min(buffer.len(), self.num_values);
     let bytes_left = data.len() - self.start;
     let bytes_to_decode = 12 * num_values;
@@ -196,6 +353,10 @@ impl Decoder<Int96Type> for PlainDecoder<Int96Type> {
 
     Ok(num_values)
   }
+
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    self.skip_fixed_width(num_values, 12)
+  }
 }
 
 impl Decoder<BoolType> for PlainDecoder<BoolType> {
@@ -206,23 +367,29 @@ impl Decoder<BoolType> for PlainDecoder<BoolType> {
   }
 
   fn get(&mut self, buffer: &mut [bool]) -> Result<usize> {
-    assert!(self.bit_reader.is_some());
-
-    let bit_reader = self.bit_reader.as_mut().unwrap();
-    let values_read = bit_reader.get_batch::<bool>(buffer, 1);
+    let bit_reader = self.bit_reader.as_mut()
+      .ok_or(general_err!("Data is not set, call set_data() first"))?;
+    let num_values = cmp::min(buffer.len(), self.num_values);
+    let values_read = bit_reader.get_batch::<bool>(&mut buffer[..num_values], 1);
     self.num_values -= values_read;
 
     Ok(values_read)
   }
+
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    skip_by_decoding(self, num_values)
+  }
 }
 
 impl Decoder<ByteArrayType> for PlainDecoder<ByteArrayType> {
   fn get(&mut self, buffer: &mut [ByteArray]) -> Result<usize> {
-    assert!(self.data.is_some());
-
-    let data = self.data.as_mut().unwrap();
+    let data = self.data.as_mut()
+      .ok_or(general_err!("Data is not set, call set_data() first"))?;
     let num_values = cmp::min(buffer.len(), self.num_values);
     for i in 0..num_values {
+      if data.len() < self.start + mem::size_of::<u32>() {
+        return Err(eof_err!("Not enough bytes to decode"));
+      }
       let len: usize = read_num_bytes!(
         u32, 4, data.start_from(self.start).as_ref()) as usize;
       self.start += mem::size_of::<u32>();
@@ -236,14 +403,36 @@ impl Decoder<ByteArrayType> for PlainDecoder<ByteArrayType> {
 
     Ok(num_values)
   }
+
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    let data = self.data.as_ref()
+      .ok_or(general_err!("Data is not set, call set_data() first"))?;
+    let num_values = cmp::min(num_values, self.num_values);
+    for _ in 0..num_values {
+      if data.len() < self.start + mem::size_of::<u32>() {
+        return Err(eof_err!("Not enough bytes to skip"));
+      }
+      let len: usize = read_num_bytes!(
+        u32, 4, data.start_from(self.start).as_ref()) as usize;
+      self.start += mem::size_of::<u32>();
+      if data.len() < self.start + len {
+        return Err(eof_err!("Not enough bytes to skip"));
+      }
+      self.start += len;
+    }
+    self.num_values -= num_values;
+
+    Ok(num_values)
+  }
 }
 
 impl Decoder<FixedLenByteArrayType> for PlainDecoder<FixedLenByteArrayType> {
   fn get(&mut self, buffer: &mut [ByteArray]) -> Result<usize> {
-    assert!(self.data.is_some());
-    assert!(self.type_length > 0);
-
-    let data = self.data.as_mut().unwrap();
+    if self.type_length <= 0 {
+      return Err(general_err!("Invalid type length: {}", self.type_length));
+    }
+    let data = self.data.as_mut()
+      .ok_or(general_err!("Data is not set, call set_data() first"))?;
     let type_length = self.type_length as usize;
     let num_values = cmp::min(buffer.len(), self.num_values);
     for i in 0..num_values {
@@ -257,6 +446,14 @@ impl Decoder<FixedLenByteArrayType> for PlainDecoder<FixedLenByteArrayType> {
 
     Ok(num_values)
   }
+
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    if self.type_length <= 0 {
+      return Err(general_err!("Invalid type length: {}", self.type_length));
+    }
+    let type_length = self.type_length as usize;
+    self.skip_fixed_width(num_values, type_length)
+  }
 }
 
 // ----------------------------------------------------------------------
@@ -267,11 +464,12 @@ impl Decoder<FixedLenByteArrayType> for PlainDecoder<FixedLenByteArrayType> {
 /// The dictionary is be stored in a dictionary page per column chunk.
 /// See [`DictEncoder`](`::encoding::DictEncoder`) for more information.
 pub struct DictDecoder<T: DataType> {
-  // The dictionary, which maps ids to the values
-  dictionary: Vec<T::T>,
+  // The dictionary, which maps ids to the values, if initialized. Dictionary can be
+  // shared with other decoders.
+  dictionary: Option<BufferPtr<T::T>>,
 
-  // Whether `dictionary` has been initialized
-  has_dictionary: bool,
+  // Tracks memory of decoded dictionary values
+  mem_tracker: MemTrackerPtr,
 
   // The decoder for the value ids
   rle_decoder: Option<RleDecoder>,
@@ -282,10 +480,11 @@ pub struct DictDecoder<T: DataType> {
 
 impl<T: DataType> DictDecoder<T> {
   /// Creates new dictionary decoder.
-  pub fn new() -> Self {
+  /// Memory allocated for dictionary values is tracked by `mem_tracker`.
+  pub fn new(mem_tracker: MemTrackerPtr) -> Self {
     Self {
-      dictionary: vec![],
-      has_dictionary: false,
+      dictionary: None,
+      mem_tracker: mem_tracker,
       rle_decoder: None,
       num_values: 0
     }
@@ -294,17 +493,34 @@ impl<T: DataType> DictDecoder<T> {
   /// Decodes and sets values for dictionary using `decoder` decoder.
   pub fn set_dict(&mut self, mut decoder: Box<Decoder<T>>) -> Result<()> {
     let num_values = decoder.values_left();
-    self.dictionary.resize(num_values, T::T::default());
-    let _ = decoder.get(&mut self.dictionary)?;
-    self.has_dictionary = true;
+    let mut dictionary = Buffer::new().with_mem_tracker(self.mem_tracker.clone());
+    dictionary.try_resize(num_values, T::T::default())?;
+    let _ = decoder.get(dictionary.data_mut())?;
+    self.dictionary = Some(dictionary.consume());
     Ok(())
   }
+
+  /// Sets already decoded values for dictionary, e.g. values of another decoder.
+  pub fn set_dict_values(&mut self, values: BufferPtr<T::T>) {
+    self.dictionary = Some(values);
+  }
+
+  /// Returns decoded values of dictionary, if set.
+  pub fn dict_values(&self) -> Option<&BufferPtr<T::T>> {
+    self.dictionary.as_ref()
+  }
 }
 
 impl<T: DataType> Decoder<T> for DictDecoder<T> {
   fn set_data(&mut self, data: ByteBufferPtr, num_values: usize) -> Result<()> {
     // First byte in `data` is bit width
+    if data.len() < 1 {
+      return Err(eof_err!("Not enough bytes to decode bit width"));
+    }
     let bit_width = data.as_ref()[0];
+    if bit_width > 32 {
+      return Err(general_err!("Invalid bit width of dictionary indices: {}", bit_width));
+    }
     let mut rle_decoder = RleDecoder::new(bit_width);
     rle_decoder.set_data(data.start_from(1));
     self.num_values = num_values;
@@ -313,12 +529,17 @@ impl<T: DataType> Decoder<T> for DictDecoder<T> {
   }
 
   fn get(&mut self, buffer: &mut [T::T]) -> Result<usize> {
-    assert!(self.rle_decoder.is_some());
-    assert!(self.has_dictionary, "Must call set_dict() first!");
-
-    let rle = self.rle_decoder.as_mut().unwrap();
+    let dictionary = match self.dictionary {
+      Some(ref dictionary) => dictionary,
+      None => return Err(general_err!("Must call set_dict() first!"))
+    };
+    let rle = self.rle_decoder.as_mut()
+      .ok_or(general_err!("Data is not set, call set_data() first"))?;
     let num_values = cmp::min(buffer.len(), self.num_values);
-    rle.get_batch_with_dict(&self.dictionary[..], buffer, num_values)
+    let values_read =
+      rle.get_batch_with_dict(dictionary.data(), buffer, num_values)?;
+    self.num_values -= values_read;
+    Ok(values_read)
   }
 
   /// Number of values left in this decoder stream
@@ -359,8 +580,15 @@ impl<T: DataType> RleValueDecoder<T> {
   ) -> Result<()> {
     // We still need to remove prefix of i32 from the stream.
     let i32_size = mem::size_of::<i32>();
+    if data.len() < i32_size {
+      return Err(eof_err!("Not enough bytes to decode data size"));
+    }
     let data_size = read_num_bytes!(i32, i32_size, data.as_ref()) as usize;
-    let rle_decoder = self.decoder.as_mut().expect("RLE decoder is not initialized");
+    if data.len() - i32_size < data_size {
+      return Err(eof_err!("Not enough bytes to decode"));
+    }
+    let rle_decoder = self.decoder.as_mut()
+      .ok_or(general_err!("RLE decoder is not initialized"))?;
     rle_decoder.set_data(data.range(i32_size, data_size));
     self.values_left = num_values;
     Ok(())
@@ -374,7 +602,7 @@ impl<T: DataType> Decoder<T> for RleValueDecoder<T> {
     _data: ByteBufferPtr,
     _num_values: usize
   ) -> Result<()> {
-    panic!("RleValueDecoder only supports BoolType");
+    Err(general_err!("RleValueDecoder only supports BoolType"))
   }
 
   #[inline]
@@ -389,8 +617,11 @@ impl<T: DataType> Decoder<T> for RleValueDecoder<T> {
 
   #[inline]
   fn get(&mut self, buffer: &mut [T::T]) -> Result<usize> {
-    let rle_decoder = self.decoder.as_mut().expect("RLE decoder is not initialized");
-    let values_read = rle_decoder.get_batch(buffer)?;
+    let rle_decoder = self.decoder.as_mut()
+      .ok_or(general_err!("RLE decoder is not initialized"))?;
+    // Bit-packed runs are padded, do not read values past the end of the stream
+    let num_values = cmp::min(buffer.len(), self.values_left);
+    let values_read = rle_decoder.get_batch(&mut buffer[..num_values])?;
     self.values_left -= values_read;
     Ok(values_read)
   }
@@ -419,17 +650,18 @@ pub struct DeltaBitPackDecoder<T: DataType> {
   num_values: usize,
   num_mini_blocks: i64,
   values_per_mini_block: usize,
-  values_current_mini_block: usize,
   first_value: i64,
   first_value_read: bool,
 
   // Per block info
   min_delta: i64,
+  mini_block_bit_widths: Vec<u8>,
+
+  // Per mini block info
   mini_block_idx: usize,
-  delta_bit_width: u8,
-  delta_bit_widths: ByteBuffer,
-  deltas_in_mini_block: Vec<T::T>, // eagerly loaded deltas for a mini block
-  use_batch: bool,
+  mini_block_remaining: usize,
+  mini_block_bit_width: usize,
+  deltas_in_mini_block: Buffer<T::T>, // eagerly loaded deltas for a mini block
 
   current_value: i64,
 
@@ -445,68 +677,95 @@ impl<T: DataType> DeltaBitPackDecoder<T> {
       num_values: 0,
       num_mini_blocks: 0,
       values_per_mini_block: 0,
-      values_current_mini_block: 0,
       first_value: 0,
       first_value_read: false,
       min_delta: 0,
+      mini_block_bit_widths: vec![],
       mini_block_idx: 0,
-      delta_bit_width: 0,
-      delta_bit_widths: ByteBuffer::new(),
-      deltas_in_mini_block: vec![],
-      use_batch: mem::size_of::<T::T>() == 4,
+      mini_block_remaining: 0,
+      mini_block_bit_width: 0,
+      deltas_in_mini_block: Buffer::new(),
       current_value: 0,
       _phantom: PhantomData
     }
   }
 
+  /// Adds memory tracker for deltas of a mini block, which are loaded eagerly.
+  pub fn with_mem_tracker(mut self, mem_tracker: MemTrackerPtr) -> Self {
+    self.deltas_in_mini_block = Buffer::new().with_mem_tracker(mem_tracker);
+    self
+  }
+
   /// Returns underlying bit reader offset.
-  pub fn get_offset(&self) -> usize {
-    assert!(self.initialized, "Bit reader is not initialized");
-    self.bit_reader.get_byte_offset()
+  pub fn get_offset(&self) -> Result<usize> {
+    if !self.initialized {
+      return Err(general_err!("Bit reader is not initialized"));
+    }
+    Ok(self.bit_reader.get_byte_offset())
   }
 
-  /// Initializes new mini block.
+  /// Reads header of the next block, i.e. min delta and bit widths of mini blocks.
   #[inline]
   fn init_block(&mut self) -> Result<()> {
     self.min_delta = self.bit_reader
       .get_zigzag_vlq_int()
       .ok_or(eof_err!("Not enough data to decode 'min_delta'"))?;
 
-    let mut widths = vec![];
+    self.mini_block_bit_widths.clear();
     for _ in 0..self.num_mini_blocks {
       let w = self.bit_reader
         .get_aligned::<u8>(1)
         .ok_or(eof_err!("Not enough data to decode 'width'"))?;
-      widths.push(w);
+      self.mini_block_bit_widths.push(w);
     }
-
-    self.delta_bit_widths.set_data(widths);
     self.mini_block_idx = 0;
-    self.delta_bit_width = self.delta_bit_widths.data()[0];
-    self.values_current_mini_block = self.values_per_mini_block;
     Ok(())
   }
 
-  /// Loads delta into mini block.
+  /// Moves to the next mini block, which is the first mini block of the next block
+  /// after the last mini block of the current block, and loads its deltas.
+  ///
+  /// Bit width of a mini block is validated only when the mini block is read, since
+  /// bit widths of mini blocks after the last value of a page can be arbitrary.
   #[inline]
-  fn load_deltas_in_mini_block(&mut self) -> Result<()> {
-    self.deltas_in_mini_block.clear();
-    if self.use_batch {
-      self.deltas_in_mini_block.resize(self.values_current_mini_block, T::T::default());
-      let loaded = self.bit_reader.get_batch::<T::T>(
-        &mut self.deltas_in_mini_block[..], self.delta_bit_width as usize
-      );
-      assert!(loaded == self.values_current_mini_block);
+  fn next_mini_block(&mut self) -> Result<()> {
+    if self.mini_block_idx + 1 < self.mini_block_bit_widths.len() {
+      self.mini_block_idx += 1;
     } else {
-      for _ in 0..self.values_current_mini_block {
-        // TODO: load one batch at a time similar to int32
-        let delta = self.bit_reader
-          .get_value::<T::T>(self.delta_bit_width as usize)
-          .ok_or(eof_err!("Not enough data to decode 'delta'"))?;
-        self.deltas_in_mini_block.push(delta);
-      }
+      self.init_block()?;
     }
 
+    let bit_width = self.mini_block_bit_widths[self.mini_block_idx] as usize;
+    if bit_width > mem::size_of::<T::T>() * 8 {
+      return Err(general_err!("Invalid delta bit width: {}", bit_width));
+    }
+    self.load_deltas_in_mini_block(bit_width)?;
+    self.mini_block_bit_width = bit_width;
+    self.mini_block_remaining = self.values_per_mini_block;
+    Ok(())
+  }
+
+  /// Loads all deltas of a mini block, including padding of the last mini block.
+  ///
+  /// Mini block with bit width of 0 has no data and all its deltas are 0, so nothing
+  /// is loaded. Otherwise the mini block must fit into the remaining data, which bounds
+  /// memory allocated for deltas by the size of the page.
+  #[inline]
+  fn load_deltas_in_mini_block(&mut self, bit_width: usize) -> Result<()> {
+    self.deltas_in_mini_block.clear();
+    if bit_width == 0 {
+      return Ok(());
+    }
+    if self.values_per_mini_block > self.bit_reader.bytes_left() * 8 / bit_width {
+      return Err(eof_err!("Not enough data to decode 'delta'"));
+    }
+    self.deltas_in_mini_block.try_resize(self.values_per_mini_block, T::T::default())?;
+    let loaded = self.bit_reader.get_batch::<T::T>(
+      self.deltas_in_mini_block.data_mut(), bit_width
+    );
+    if loaded != self.values_per_mini_block {
+      return Err(eof_err!("Not enough data to decode 'delta'"));
+    }
     Ok(())
   }
 }
@@ -515,6 +774,11 @@ impl<T: DataType> Decoder<T> for DeltaBitPackDecoder<T> {
   // # of total values is derived from encoding
   #[inline]
   default fn set_data(&mut self, data: ByteBufferPtr, _: usize) -> Result<()> {
+    match T::get_physical_type() {
+      Type::INT32 | Type::INT64 => {},
+      _ => return Err(general_err!(
+        "DeltaBitPackDecoder only supports Int32Type and Int64Type"))
+    }
     self.bit_reader = BitReader::new(data);
     self.initialized = true;
 
@@ -533,50 +797,76 @@ impl<T: DataType> Decoder<T> for DeltaBitPackDecoder<T> {
 
     // Reset decoding state
     self.first_value_read = false;
+    self.mini_block_bit_widths.clear();
     self.mini_block_idx = 0;
-    self.delta_bit_widths.clear();
-    self.values_current_mini_block = 0;
-
-    self.values_per_mini_block = (block_size / self.num_mini_blocks) as usize;
-    assert!(self.values_per_mini_block % 8 == 0);
+    self.mini_block_remaining = 0;
+
+    // Block size is a multiple of 128 and number of values per mini block is a non-zero
+    // multiple of 32
+    if block_size <= 0 || block_size % 128 != 0 {
+      return Err(general_err!(
+        "Block size must be a positive multiple of 128, found: {}",
+        block_size
+      ));
+    }
+    if self.num_mini_blocks <= 0 || block_size % self.num_mini_blocks != 0 {
+      return Err(general_err!(
+        "Invalid number of mini blocks {} for block size {}",
+        self.num_mini_blocks,
+        block_size
+      ));
+    }
+    let values_per_mini_block = block_size / self.num_mini_blocks;
+    if values_per_mini_block % 32 != 0 {
+      return Err(general_err!(
+        "Number of values per mini block must be a multiple of 32, found: {}",
+        values_per_mini_block
+      ));
+    }
+    // Every block after the first value stores a bit width for each mini block
+    if self.num_values > 1 && self.num_mini_blocks as usize > self.bit_reader.bytes_left()
+    {
+      return Err(eof_err!(
+        "Not enough data to decode bit widths of {} mini blocks",
+        self.num_mini_blocks
+      ));
+    }
+    self.values_per_mini_block = values_per_mini_block as usize;
 
     Ok(())
   }
 
   default fn get(&mut self, buffer: &mut [T::T]) -> Result<usize> {
-    assert!(self.initialized, "Bit reader is not initialized");
+    if !self.initialized {
+      return Err(general_err!("Bit reader is not initialized"));
+    }
 
     let num_values = cmp::min(buffer.len(), self.num_values);
     for i in 0..num_values {
       if !self.first_value_read {
-        self.set_decoded_value(buffer, i, self.first_value);
+        self.set_decoded_value(buffer, i, self.first_value)?;
         self.current_value = self.first_value;
         self.first_value_read = true;
         continue;
       }
 
-      if self.values_current_mini_block == 0 {
-        self.mini_block_idx += 1;
-        if self.mini_block_idx < self.delta_bit_widths.size() {
-          self.delta_bit_width = self.delta_bit_widths.data()[self.mini_block_idx];
-          self.values_current_mini_block = self.values_per_mini_block;
-        } else {
-          self.init_block()?;
-        }
-        self.load_deltas_in_mini_block()?;
+      if self.mini_block_remaining == 0 {
+        self.next_mini_block()?;
       }
 
       // we decrement values in current mini block, so we need to invert index for delta
-      let delta = self.get_delta(
-        self.deltas_in_mini_block.len() - self.values_current_mini_block
-      );
+      let delta = if self.mini_block_bit_width == 0 {
+        0
+      } else {
+        self.get_delta(self.values_per_mini_block - self.mini_block_remaining)?
+      };
       // It is OK for deltas to contain "overflowed" values after encoding,
       // e.g. i64::MAX - i64::MIN, so we use `wrapping_add` to "overflow" again and
       // restore original value.
       self.current_value = self.current_value.wrapping_add(self.min_delta);
       self.current_value = self.current_value.wrapping_add(delta as i64);
-      self.set_decoded_value(buffer, i, self.current_value);
-      self.values_current_mini_block -= 1;
+      self.set_decoded_value(buffer, i, self.current_value)?;
+      self.mini_block_remaining -= 1;
     }
 
     self.num_values -= num_values;
@@ -594,47 +884,53 @@ impl<T: DataType> Decoder<T> for DeltaBitPackDecoder<T> {
 
 /// Helper trait to define specific conversions when decoding values
 trait DeltaBitPackDecoderConversion<T: DataType> {
-  /// Sets decoded value based on type `T`.
+  /// Gets delta at `index` in the current mini block.
   #[inline]
-  fn get_delta(&self, index: usize) -> i64;
+  fn get_delta(&self, index: usize) -> Result<i64>;
 
+  /// Sets decoded value based on type `T`.
   #[inline]
-  fn set_decoded_value(&self, buffer: &mut [T::T], index: usize, value: i64);
+  fn set_decoded_value(&self, buffer: &mut [T::T], index: usize, value: i64)
+    -> Result<()>;
 }
 
 impl<T: DataType> DeltaBitPackDecoderConversion<T> for DeltaBitPackDecoder<T> {
   #[inline]
-  default fn get_delta(&self, _: usize) -> i64 {
-    panic!("DeltaBitPackDecoder only supports Int32Type and Int64Type")
+  default fn get_delta(&self, _: usize) -> Result<i64> {
+    Err(general_err!("DeltaBitPackDecoder only supports Int32Type and Int64Type"))
   }
 
   #[inline]
-  default fn set_decoded_value(&self, _: &mut [T::T], _: usize, _: i64) {
-    panic!("DeltaBitPackDecoder only supports Int32Type and Int64Type")
+  default fn set_decoded_value(&self, _: &mut [T::T], _: usize, _: i64) -> Result<()> {
+    Err(general_err!("DeltaBitPackDecoder only supports Int32Type and Int64Type"))
   }
 }
 
 impl DeltaBitPackDecoderConversion<Int32Type> for DeltaBitPackDecoder<Int32Type> {
   #[inline]
-  fn get_delta(&self, index: usize) -> i64 {
-    self.deltas_in_mini_block[index] as i64
+  fn get_delta(&self, index: usize) -> Result<i64> {
+    Ok(self.deltas_in_mini_block[index] as i64)
   }
 
   #[inline]
-  fn set_decoded_value(&self, buffer: &mut [i32], index: usize, value: i64) {
+  fn set_decoded_value(&self, buffer: &mut [i32], index: usize, value: i64)
+    -> Result<()> {
     buffer[index] = value as i32;
+    Ok(())
   }
 }
 
 impl DeltaBitPackDecoderConversion<Int64Type> for DeltaBitPackDecoder<Int64Type> {
   #[inline]
-  fn get_delta(&self, index: usize) -> i64 {
-    self.deltas_in_mini_block[index]
+  fn get_delta(&self, index: usize) -> Result<i64> {
+    Ok(self.deltas_in_mini_block[index])
   }
 
   #[inline]
-  fn set_decoded_value(&self, buffer: &mut [i64], index: usize, value: i64) {
+  fn set_decoded_value(&self, buffer: &mut [i64], index: usize, value: i64)
+    -> Result<()> {
     buffer[index] = value;
+    Ok(())
   }
 }
 
@@ -649,8 +945,7 @@ impl DeltaBitPackDecoderConversion<Int64Type> for DeltaBitPackDecoder<Int64Type>
 /// for more information.
 pub struct DeltaLengthByteArrayDecoder<T: DataType> {
   // Lengths for each byte array in `data`
-  // TODO: add memory tracker to this
-  lengths: Vec<i32>,
+  lengths: Buffer<i32>,
 
   // Current index into `lengths`
   current_idx: usize,
@@ -670,9 +965,10 @@ pub struct DeltaLengthByteArrayDecoder<T: DataType> {
 
 impl<T: DataType> DeltaLengthByteArrayDecoder<T> {
   /// Creates new delta length byte array decoder.
-  pub fn new() -> Self {
+  /// Memory allocated for lengths is tracked by `mem_tracker`.
+  pub fn new(mem_tracker: MemTrackerPtr) -> Self {
     Self {
-      lengths: vec![],
+      lengths: Buffer::new().with_mem_tracker(mem_tracker),
       current_idx: 0,
       data: None,
       offset: 0,
@@ -702,13 +998,14 @@ impl<T: DataType> Decoder<T> for DeltaLengthByteArrayDecoder<T> {
 
 impl Decoder<ByteArrayType> for DeltaLengthByteArrayDecoder<ByteArrayType> {
   fn set_data(&mut self, data: ByteBufferPtr, num_values: usize) -> Result<()> {
-    let mut len_decoder = DeltaBitPackDecoder::<Int32Type>::new();
+    let mut len_decoder = DeltaBitPackDecoder::<Int32Type>::new()
+      .with_mem_tracker(self.lengths.mem_tracker().clone());
     len_decoder.set_data(data.all(), num_values)?;
     let num_lengths = len_decoder.values_left();
-    self.lengths.resize(num_lengths, 0);
-    len_decoder.get(&mut self.lengths[..])?;
+    self.lengths.try_resize(num_lengths, 0)?;
+    len_decoder.get(self.lengths.data_mut())?;
 
-    self.data = Some(data.start_from(len_decoder.get_offset()));
+    self.data = Some(data.start_from(len_decoder.get_offset()?));
     self.offset = 0;
     self.current_idx = 0;
     self.num_values = num_lengths;
@@ -716,12 +1013,18 @@ impl Decoder<ByteArrayType> for DeltaLengthByteArrayDecoder<ByteArrayType> {
   }
 
   fn get(&mut self, buffer: &mut [ByteArray]) -> Result<usize> {
-    assert!(self.data.is_some());
-
-    let data = self.data.as_ref().unwrap();
+    let data = self.data.as_ref()
+      .ok_or(general_err!("Data is not set, call set_data() first"))?;
     let num_values = cmp::min(buffer.len(), self.num_values);
     for i in 0..num_values {
-      let len = self.lengths[self.current_idx] as usize;
+      let len = self.lengths[self.current_idx];
+      if len < 0 {
+        return Err(general_err!("Invalid byte array length: {}", len));
+      }
+      let len = len as usize;
+      if data.len() < self.offset + len {
+        return Err(eof_err!("Not enough bytes to decode"));
+      }
       buffer[i].set_data(data.range(self.offset, len));
       self.offset += len;
       self.current_idx += 1;
@@ -742,8 +1045,7 @@ impl Decoder<ByteArrayType> for DeltaLengthByteArrayDecoder<ByteArrayType> {
 /// information.
 pub struct DeltaByteArrayDecoder<T: DataType> {
   // Prefix lengths for each byte array
-  // TODO: add memory tracker to this
-  prefix_lengths: Vec<i32>,
+  prefix_lengths: Buffer<i32>,
 
   // The current index into `prefix_lengths`,
   current_idx: usize,
@@ -757,25 +1059,31 @@ pub struct DeltaByteArrayDecoder<T: DataType> {
   // Number of values left
   num_values: usize,
 
+  // Tracks memory of prefix lengths, suffixes and decoded values
+  mem_tracker: MemTrackerPtr,
+
   // Placeholder to allow `T` as generic parameter
   _phantom: PhantomData<T>
 }
 
 impl<T: DataType> DeltaByteArrayDecoder<T> {
   /// Creates new delta byte array decoder.
-  pub fn new() -> Self {
+  /// Memory allocated for prefix lengths, suffixes and decoded values is tracked by
+  /// `mem_tracker`.
+  pub fn new(mem_tracker: MemTrackerPtr) -> Self {
     Self {
-      prefix_lengths: vec![],
+      prefix_lengths: Buffer::new().with_mem_tracker(mem_tracker.clone()),
       current_idx: 0,
       suffix_decoder: None,
       previous_value: vec![],
       num_values: 0,
+      mem_tracker: mem_tracker,
       _phantom: PhantomData
     }
   }
 }
 
-impl<'m, T: DataType> Decoder<T> for DeltaByteArrayDecoder<T> {
+impl<T: DataType> Decoder<T> for DeltaByteArrayDecoder<T> {
   default fn set_data(&mut self, _: ByteBufferPtr, _: usize) -> Result<()> {
     Err(general_err!(
       "DeltaByteArrayDecoder only supports ByteArrayType and FixedLenByteArrayType"
@@ -799,15 +1107,16 @@ impl<'m, T: DataType> Decoder<T> for DeltaByteArrayDecoder<T> {
 
 impl<> Decoder<ByteArrayType> for DeltaByteArrayDecoder<ByteArrayType> {
   fn set_data(&mut self, data: ByteBufferPtr, num_values: usize) -> Result<()> {
-    let mut prefix_len_decoder = DeltaBitPackDecoder::<Int32Type>::new();
+    let mut prefix_len_decoder = DeltaBitPackDecoder::<Int32Type>::new()
+      .with_mem_tracker(self.mem_tracker.clone());
     prefix_len_decoder.set_data(data.all(), num_values)?;
     let num_prefixes = prefix_len_decoder.values_left();
-    self.prefix_lengths.resize(num_prefixes, 0);
-    prefix_len_decoder.get(&mut self.prefix_lengths[..])?;
+    self.prefix_lengths.try_resize(num_prefixes, 0)?;
+    prefix_len_decoder.get(self.prefix_lengths.data_mut())?;
 
-    let mut suffix_decoder = DeltaLengthByteArrayDecoder::new();
+    let mut suffix_decoder = DeltaLengthByteArrayDecoder::new(self.mem_tracker.clone());
     suffix_decoder.set_data(
-      data.start_from(prefix_len_decoder.get_offset()), num_values)?;
+      data.start_from(prefix_len_decoder.get_offset()?), num_values)?;
     self.suffix_decoder = Some(suffix_decoder);
     self.num_values = num_prefixes;
     self.current_idx = 0;
@@ -816,27 +1125,33 @@ impl<> Decoder<ByteArrayType> for DeltaByteArrayDecoder<ByteArrayType> {
   }
 
   fn get(&mut self, buffer: &mut [ByteArray]) -> Result<usize> {
-    assert!(self.suffix_decoder.is_some());
-
     let num_values = cmp::min(buffer.len(), self.num_values);
     let mut v: [ByteArray; 1] = [ByteArray::new(); 1];
     for i in 0..num_values {
       // Process suffix
       // TODO: this is awkward - maybe we should add a non-vectorized API?
-      let suffix_decoder = self.suffix_decoder.as_mut().unwrap();
-      suffix_decoder.get(&mut v[..])?;
+      let suffix_decoder = self.suffix_decoder.as_mut()
+        .ok_or(general_err!("Data is not set, call set_data() first"))?;
+      if suffix_decoder.get(&mut v[..])? == 0 {
+        return Err(eof_err!("Not enough suffixes to decode"));
+      }
       let suffix = v[0].data();
 
       // Extract current prefix length, can be 0
-      let prefix_len = self.prefix_lengths[self.current_idx] as usize;
+      let prefix_len = self.prefix_lengths[self.current_idx];
+      if prefix_len < 0 || prefix_len as usize > self.previous_value.len() {
+        return Err(general_err!("Invalid prefix length: {}", prefix_len));
+      }
+      let prefix_len = prefix_len as usize;
 
       // Concatenate prefix with suffix
       let mut result = Vec::new();
       result.extend_from_slice(&self.previous_value[0..prefix_len]);
       result.extend_from_slice(suffix);
 
-      let data = ByteBufferPtr::new(result.clone());
-      buffer[i].set_data(data);
+      let mut data = ByteBuffer::new().with_mem_tracker(self.mem_tracker.clone());
+      data.try_set_data(result.clone())?;
+      buffer[i].set_data(data.consume());
       self.previous_value = result;
       self.current_idx += 1;
     }
@@ -858,6 +1173,144 @@ impl<> Decoder<FixedLenByteArrayType> for DeltaByteArrayDecoder<FixedLenByteArra
   }
 }
 
+// ----------------------------------------------------------------------
+// Iterator adapters
+
+/// Default number of values decoded at once by decoder iterators.
+const DEFAULT_ITER_BATCH_SIZE: usize = 1024;
+
+/// Iterator over values of a decoder.
+///
+/// Values are decoded lazily in batches and yielded one by one, iteration stops after
+/// all values are consumed, or after the first error.
+///
+/// # Example
+///
+/// ```rust
+/// use parquet::data_type::Int32Type;
+/// use parquet::decoding::{Decoder, PlainDecoder};
+/// use parquet::memory::ByteBufferPtr;
+///
+/// let data = vec![1u8, 0, 0, 0, 2, 0, 0, 0];
+/// let mut decoder = PlainDecoder::<Int32Type>::new(-1);
+/// decoder.set_data(ByteBufferPtr::new(data), 2).unwrap();
+///
+/// let decoder: Box<Decoder<Int32Type>> = Box::new(decoder);
+/// let mut values = vec![];
+/// for value in decoder {
+///   values.push(value.unwrap());
+/// }
+/// assert_eq!(values, vec![1, 2]);
+/// ```
+pub struct DecoderIter<T: DataType> {
+  chunks: DecoderChunks<T>,
+  buffer: Vec<T::T>,
+  pos: usize
+}
+
+impl<T: DataType> DecoderIter<T> {
+  /// Creates new iterator that decodes values with default batch size.
+  pub fn new(decoder: Box<Decoder<T>>) -> Self {
+    Self::with_batch_size(decoder, DEFAULT_ITER_BATCH_SIZE)
+  }
+
+  /// Creates new iterator that decodes `batch_size` values at once.
+  pub fn with_batch_size(decoder: Box<Decoder<T>>, batch_size: usize) -> Self {
+    Self {
+      chunks: DecoderChunks::new(decoder, batch_size),
+      buffer: vec![],
+      pos: 0
+    }
+  }
+}
+
+impl<T: DataType> Iterator for DecoderIter<T> {
+  type Item = Result<T::T>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.pos == self.buffer.len() {
+      match self.chunks.next() {
+        Some(Ok(chunk)) => {
+          self.buffer = chunk;
+          self.pos = 0;
+        },
+        Some(Err(e)) => return Some(Err(e)),
+        None => return None
+      }
+    }
+    let value = mem::replace(&mut self.buffer[self.pos], T::T::default());
+    self.pos += 1;
+    Some(Ok(value))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let num_values = self.buffer.len() - self.pos + self.chunks.values_left();
+    (num_values, Some(num_values))
+  }
+}
+
+impl<T: DataType> IntoIterator for Box<Decoder<T>> {
+  type Item = Result<T::T>;
+  type IntoIter = DecoderIter<T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    DecoderIter::new(self)
+  }
+}
+
+/// Iterator over chunks of values of a decoder.
+///
+/// Each chunk contains at most `batch_size` values, only the last chunk can be smaller.
+/// Iteration stops after all values are consumed, or after the first error.
+pub struct DecoderChunks<T: DataType> {
+  decoder: Box<Decoder<T>>,
+  batch_size: usize,
+  done: bool
+}
+
+impl<T: DataType> DecoderChunks<T> {
+  /// Creates new iterator that yields chunks of at most `batch_size` values.
+  pub fn new(decoder: Box<Decoder<T>>, batch_size: usize) -> Self {
+    assert!(batch_size > 0, "Batch size must be positive");
+    Self { decoder: decoder, batch_size: batch_size, done: false }
+  }
+
+  /// Returns the underlying decoder.
+  pub fn into_inner(self) -> Box<Decoder<T>> {
+    self.decoder
+  }
+
+  fn values_left(&self) -> usize {
+    if self.done { 0 } else { self.decoder.values_left() }
+  }
+}
+
+impl<T: DataType> Iterator for DecoderChunks<T> {
+  type Item = Result<Vec<T::T>>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.done || self.decoder.values_left() == 0 {
+      return None;
+    }
+    let num_values = cmp::min(self.batch_size, self.decoder.values_left());
+    let mut chunk = vec![T::T::default(); num_values];
+    match self.decoder.get(&mut chunk[..]) {
+      Ok(0) => {
+        self.done = true;
+        None
+      },
+      Ok(values_read) => {
+        chunk.truncate(values_read);
+        Some(Ok(chunk))
+      },
+      Err(e) => {
+        self.done = true;
+        Some(Err(e))
+      }
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::super::encoding::*;
@@ -865,6 +1318,8 @@ mod tests {
   use schema::types::{ColumnDescriptor, ColumnDescPtr, ColumnPath, Type as SchemaType};
   use std::mem;
   use std::rc::Rc;
+  use std::sync::Arc;
+  use std::thread;
   use util::bit_util::set_array_bit;
   use util::memory::MemTracker;
   use util::test_common::RandGen;
@@ -874,8 +1329,10 @@ mod tests {
     // supported encodings
     create_and_check_decoder::<Int32Type>(Encoding::PLAIN, None);
     create_and_check_decoder::<Int32Type>(Encoding::DELTA_BINARY_PACKED, None);
-    create_and_check_decoder::<Int32Type>(Encoding::DELTA_LENGTH_BYTE_ARRAY, None);
-    create_and_check_decoder::<Int32Type>(Encoding::DELTA_BYTE_ARRAY, None);
+    create_and_check_decoder::<Int64Type>(Encoding::DELTA_BINARY_PACKED, None);
+    create_and_check_decoder::<ByteArrayType>(Encoding::DELTA_LENGTH_BYTE_ARRAY, None);
+    create_and_check_decoder::<ByteArrayType>(Encoding::DELTA_BYTE_ARRAY, None);
+    create_and_check_decoder::<FixedLenByteArrayType>(Encoding::DELTA_BYTE_ARRAY, None);
     create_and_check_decoder::<BoolType>(Encoding::RLE, None);
 
     // error when initializing
@@ -888,6 +1345,26 @@ mod tests {
       Some(general_err!("Cannot initialize this encoding through this function"))
     );
 
+    // invalid for physical type
+    create_and_check_decoder::<FloatType>(
+      Encoding::DELTA_BINARY_PACKED,
+      Some(general_err!(
+        "Encoding DELTA_BINARY_PACKED is invalid for physical type FLOAT"))
+    );
+    create_and_check_decoder::<DoubleType>(
+      Encoding::DELTA_BYTE_ARRAY,
+      Some(general_err!("Encoding DELTA_BYTE_ARRAY is invalid for physical type DOUBLE"))
+    );
+    create_and_check_decoder::<Int32Type>(
+      Encoding::DELTA_LENGTH_BYTE_ARRAY,
+      Some(general_err!(
+        "Encoding DELTA_LENGTH_BYTE_ARRAY is invalid for physical type INT32"))
+    );
+    create_and_check_decoder::<Int64Type>(
+      Encoding::RLE,
+      Some(general_err!("Encoding RLE is invalid for physical type INT64"))
+    );
+
     // unsupported
     create_and_check_decoder::<Int32Type>(
       Encoding::BIT_PACKED,
@@ -895,6 +1372,39 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_get_dict_decoder() {
+    let descr = create_test_col_desc_ptr(-1, Type::BYTE_ARRAY);
+    let factory =
+      DecoderFactory::<ByteArrayType>::new(descr, Arc::new(MemTracker::new()));
+    let dict = vec![ByteArray::from("abc"), ByteArray::from("de")];
+    let dict_bytes = ByteArrayType::to_byte_array(&dict[..]);
+
+    let encodings =
+      [Encoding::PLAIN, Encoding::PLAIN_DICTIONARY, Encoding::RLE_DICTIONARY];
+    for &encoding in &encodings {
+      let mut decoder = factory
+        .get_dict_decoder(encoding, ByteBufferPtr::new(dict_bytes.clone()), 2)
+        .unwrap();
+      assert_eq!(decoder.dict_values().unwrap().data(), &dict[..]);
+      assert_eq!(decoder.encoding(), Encoding::RLE_DICTIONARY);
+
+      // Bit width 1, single bit-packed group of 8 indices: 1, 0, 1, 1, 0, 0, 0, 0
+      decoder.set_data(ByteBufferPtr::new(vec![1, 3, 0b0000_1101]), 4).unwrap();
+      let mut buffer = vec![ByteArray::new(); 4];
+      assert_eq!(decoder.get(&mut buffer).unwrap(), 4);
+      assert_eq!(buffer, vec![dict[1].clone(), dict[0].clone(), dict[1].clone(),
+        dict[1].clone()]);
+    }
+
+    let res = factory.get_dict_decoder(
+      Encoding::DELTA_BYTE_ARRAY, ByteBufferPtr::new(dict_bytes), 2);
+    assert_eq!(
+      res.err().unwrap(),
+      nyi_err!("Invalid/Unsupported encoding type for dictionary: DELTA_BYTE_ARRAY")
+    );
+  }
+
   #[test]
   fn test_plain_decode_int32() {
     let data = vec![42, 18, 52];
@@ -915,6 +1425,18 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_plain_decode_int64_unaligned() {
+    // Values start at an odd offset, so they are not aligned in the data buffer
+    let data = vec![42, -18, i64::max_value()];
+    let mut data_bytes = vec![0u8];
+    data_bytes.extend_from_slice(&Int64Type::to_byte_array(&data[..]));
+    let mut buffer = vec![0; 3];
+    test_plain_decode::<Int64Type>(
+      ByteBufferPtr::new(data_bytes).start_from(1), 3, -1, &mut buffer[..], &data[..]
+    );
+  }
+
   #[test]
   fn test_plain_decode_float() {
     let data = vec![3.14, 2.414, 12.51];
@@ -992,10 +1514,27 @@ mod tests {
   }
 
   #[test]
-  #[should_panic(expected = "RleValueDecoder only supports BoolType")]
   fn test_rle_value_decode_int32_not_supported() {
     let mut decoder = RleValueDecoder::<Int32Type>::new();
-    decoder.set_data(ByteBufferPtr::new(vec![5, 0, 0, 0]), 1).unwrap();
+    let res = decoder.set_data(ByteBufferPtr::new(vec![5, 0, 0, 0]), 1);
+    assert_eq!(
+      res.unwrap_err(),
+      general_err!("RleValueDecoder only supports BoolType")
+    );
+  }
+
+  #[test]
+  fn test_rle_value_decode_bool_larger_buffer() {
+    // Bit-packed run is padded to 8 values, padding is not returned as values
+    let mut encoder = RleValueEncoder::<BoolType>::new();
+    encoder.put(&[true, false, true]).unwrap();
+    let data = encoder.flush_buffer().unwrap();
+    let mut decoder = RleValueDecoder::<BoolType>::new();
+    decoder.set_data(data, 3).unwrap();
+    let mut buffer = vec![false; 16];
+    assert_eq!(decoder.get(&mut buffer).unwrap(), 3);
+    assert_eq!(&buffer[..3], &[true, false, true]);
+    assert_eq!(decoder.values_left(), 0);
   }
 
   #[test]
@@ -1010,20 +1549,24 @@ mod tests {
   }
 
   #[test]
-  #[should_panic(expected = "Bit reader is not initialized")]
   fn test_delta_bit_packed_not_initialized_offset() {
     // Fail if set_data() is not called before get_offset()
     let decoder = DeltaBitPackDecoder::<Int32Type>::new();
-    decoder.get_offset();
+    assert_eq!(
+      decoder.get_offset().unwrap_err(),
+      general_err!("Bit reader is not initialized")
+    );
   }
 
   #[test]
-  #[should_panic(expected = "Bit reader is not initialized")]
   fn test_delta_bit_packed_not_initialized_get() {
     // Fail if set_data() is not called before get()
     let mut decoder = DeltaBitPackDecoder::<Int32Type>::new();
     let mut buffer = vec![];
-    decoder.get(&mut buffer).unwrap();
+    assert_eq!(
+      decoder.get(&mut buffer).unwrap_err(),
+      general_err!("Bit reader is not initialized")
+    );
   }
 
   #[test]
@@ -1148,10 +1691,10 @@ mod tests {
     decoder.set_data(buffer, 3).unwrap();
     // check exact offsets, because when reading partial values we end up with
     // some data not being read from bit reader
-    assert_eq!(decoder.get_offset(), 5);
+    assert_eq!(decoder.get_offset().unwrap(), 5);
     let mut result = vec![0, 0, 0];
     decoder.get(&mut result).unwrap();
-    assert_eq!(decoder.get_offset(), 34);
+    assert_eq!(decoder.get_offset().unwrap(), 34);
     assert_eq!(result, vec![29, 43, 89]);
   }
 
@@ -1189,6 +1732,326 @@ mod tests {
     test_delta_byte_array_decode(data);
   }
 
+  #[test]
+  fn test_delta_byte_array_decoder_mem_tracker() {
+    let data = vec![
+      ByteArray::from("parquet"),
+      ByteArray::from("parquet-rs"),
+      ByteArray::from("part"),
+      ByteArray::from("")
+    ];
+    let col_descr = create_test_col_desc_ptr(-1, Type::BYTE_ARRAY);
+    let mut encoder = get_encoder::<ByteArrayType>(
+      col_descr.clone(),
+      Encoding::DELTA_BYTE_ARRAY,
+      Arc::new(MemTracker::new())
+    ).unwrap();
+    encoder.put(&data[..]).unwrap();
+    let bytes = encoder.flush_buffer().unwrap();
+
+    let mem_tracker = Arc::new(MemTracker::new());
+    {
+      let mut decoder = get_decoder::<ByteArrayType>(
+        col_descr, Encoding::DELTA_BYTE_ARRAY, mem_tracker.clone()).unwrap();
+      decoder.set_data(bytes, data.len()).unwrap();
+      // Prefix lengths and suffix lengths
+      let lengths_size = (2 * data.len() * mem::size_of::<i32>()) as i64;
+      assert!(mem_tracker.memory_usage() >= lengths_size);
+
+      let mut result = vec![ByteArray::new(); data.len()];
+      decoder.get(&mut result[..]).unwrap();
+      assert_eq!(result, data);
+      assert!(mem_tracker.memory_usage() >= lengths_size + 21);
+    }
+    assert_eq!(mem_tracker.memory_usage(), 0);
+    assert!(mem_tracker.max_memory_usage() > 0);
+  }
+
+  #[test]
+  fn test_decoders_are_send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<Box<Decoder<BoolType>>>();
+    assert_send::<Box<Decoder<Int32Type>>>();
+    assert_send::<Box<Decoder<Int96Type>>>();
+    assert_send::<Box<Decoder<ByteArrayType>>>();
+    assert_send::<Box<Decoder<FixedLenByteArrayType>>>();
+    assert_send::<DictDecoder<DoubleType>>();
+    assert_send::<DeltaByteArrayDecoder<ByteArrayType>>();
+
+    // Decode values in another thread
+    let data = vec![ByteArray::from("a"), ByteArray::from("bc")];
+    let mut decoder = get_decoder::<ByteArrayType>(
+      create_test_col_desc_ptr(-1, Type::BYTE_ARRAY),
+      Encoding::PLAIN,
+      Arc::new(MemTracker::new())
+    ).unwrap();
+    decoder.set_data(
+      ByteBufferPtr::new(ByteArrayType::to_byte_array(&data[..])), data.len()).unwrap();
+    let result = thread::spawn(move || {
+      let mut buffer = vec![ByteArray::new(); 2];
+      decoder.get(&mut buffer[..]).unwrap();
+      buffer
+    }).join().unwrap();
+    assert_eq!(result, data);
+  }
+
+  #[test]
+  fn test_decoder_iter() {
+    let data = (0..100).collect::<Vec<i64>>();
+    let decoder = create_plain_decoder::<Int64Type>(&data[..]);
+    let iter = DecoderIter::with_batch_size(decoder, 7);
+    assert_eq!(iter.size_hint(), (100, Some(100)));
+    let result = iter.collect::<Result<Vec<i64>>>().unwrap();
+    assert_eq!(result, data);
+
+    let decoder = create_plain_decoder::<Int64Type>(&data[..]);
+    let mut sum = 0;
+    for value in decoder {
+      sum += value.unwrap();
+    }
+    assert_eq!(sum, 4950);
+
+    let data = vec![ByteArray::from("parquet"), ByteArray::from("rs")];
+    let decoder = create_plain_decoder::<ByteArrayType>(&data[..]);
+    let mut iter = decoder.into_iter();
+    assert_eq!(iter.next(), Some(Ok(ByteArray::from("parquet"))));
+    assert_eq!(iter.size_hint(), (1, Some(1)));
+    assert_eq!(iter.next(), Some(Ok(ByteArray::from("rs"))));
+    assert_eq!(iter.next(), None);
+  }
+
+  #[test]
+  fn test_decoder_chunks() {
+    let data = (0..10).collect::<Vec<i32>>();
+    let decoder = create_plain_decoder::<Int32Type>(&data[..]);
+    let chunks = DecoderChunks::new(decoder, 4).collect::<Result<Vec<_>>>().unwrap();
+    assert_eq!(chunks, vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]);
+  }
+
+  #[test]
+  fn test_decoder_iter_error() {
+    // Decoder expects more values than the data contains
+    let mut decoder = PlainDecoder::<Int32Type>::new(-1);
+    decoder.set_data(ByteBufferPtr::new(vec![1, 0, 0, 0, 2, 0]), 2).unwrap();
+    let mut iter = DecoderIter::new(Box::new(decoder));
+    assert!(iter.next().unwrap().is_err());
+    assert_eq!(iter.next(), None);
+  }
+
+  #[test]
+  fn test_plain_skip() {
+    let data = (0..10).collect::<Vec<i64>>();
+    let mut decoder = create_plain_decoder::<Int64Type>(&data[..]);
+    assert_eq!(decoder.skip(3).unwrap(), 3);
+    assert_eq!(decoder.values_left(), 7);
+    let mut buffer = vec![0; 2];
+    decoder.get(&mut buffer[..]).unwrap();
+    assert_eq!(buffer, vec![3, 4]);
+    assert_eq!(decoder.skip(10).unwrap(), 5);
+    assert_eq!(decoder.values_left(), 0);
+
+    let data = vec![true, false, false, true, true];
+    let mut decoder = create_plain_decoder::<BoolType>(&data[..]);
+    assert_eq!(decoder.skip(3).unwrap(), 3);
+    let mut buffer = vec![false; 2];
+    decoder.get(&mut buffer[..]).unwrap();
+    assert_eq!(buffer, vec![true, true]);
+
+    let data = vec![Int96::from(vec![1, 2, 3]), Int96::from(vec![4, 5, 6])];
+    let mut decoder = create_plain_decoder::<Int96Type>(&data[..]);
+    assert_eq!(decoder.skip(1).unwrap(), 1);
+    let mut buffer = vec![Int96::new(); 1];
+    decoder.get(&mut buffer[..]).unwrap();
+    assert_eq!(buffer, vec![Int96::from(vec![4, 5, 6])]);
+
+    let data = vec![ByteArray::from("a"), ByteArray::from("bcd"), ByteArray::from("ef")];
+    let mut decoder = create_plain_decoder::<ByteArrayType>(&data[..]);
+    assert_eq!(decoder.skip(2).unwrap(), 2);
+    let mut buffer = vec![ByteArray::new(); 1];
+    decoder.get(&mut buffer[..]).unwrap();
+    assert_eq!(buffer, vec![ByteArray::from("ef")]);
+  }
+
+  #[test]
+  fn test_dict_skip() {
+    let new_decoder = || {
+      let dict = vec![10i32, 20, 30];
+      let mut dict_decoder = PlainDecoder::<Int32Type>::new(-1);
+      dict_decoder.set_data(
+        ByteBufferPtr::new(Int32Type::to_byte_array(&dict[..])), dict.len()).unwrap();
+      let mut decoder = DictDecoder::<Int32Type>::new(Arc::new(MemTracker::new()));
+      decoder.set_dict(Box::new(dict_decoder)).unwrap();
+
+      // Bit width 2, RLE run of four 1s followed by bit-packed run of 8 values
+      let indices = vec![2u8, 8, 1, 3, 0b10_01_00_10, 0b00_01_10_01];
+      decoder.set_data(ByteBufferPtr::new(indices), 12).unwrap();
+      decoder
+    };
+
+    let mut expected = vec![0; 12];
+    assert_eq!(new_decoder().get(&mut expected[..]).unwrap(), 12);
+
+    let mut decoder = new_decoder();
+    assert_eq!(decoder.skip(5).unwrap(), 5);
+    let mut buffer = vec![0; 7];
+    assert_eq!(decoder.get(&mut buffer[..]).unwrap(), 7);
+    assert_eq!(&buffer[..], &expected[5..]);
+    assert_eq!(decoder.values_left(), 0);
+  }
+
+  fn create_plain_decoder<T: DataType>(data: &[T::T]) -> Box<Decoder<T>> {
+    let mut decoder = PlainDecoder::<T>::new(-1);
+    decoder.set_data(ByteBufferPtr::new(T::to_byte_array(data)), data.len()).unwrap();
+    Box::new(decoder)
+  }
+
+  #[test]
+  fn test_dict_decoder_mem_tracker() {
+    let dict = vec![1i64, 2, 3, 4];
+    let mut dict_decoder = PlainDecoder::<Int64Type>::new(-1);
+    dict_decoder.set_data(
+      ByteBufferPtr::new(Int64Type::to_byte_array(&dict[..])), dict.len()).unwrap();
+
+    let mem_tracker = Arc::new(MemTracker::new());
+    {
+      let mut decoder = DictDecoder::<Int64Type>::new(mem_tracker.clone());
+      decoder.set_dict(Box::new(dict_decoder)).unwrap();
+      assert!(mem_tracker.memory_usage() >= (dict.len() * mem::size_of::<i64>()) as i64);
+    }
+    assert_eq!(mem_tracker.memory_usage(), 0);
+  }
+
+  #[test]
+  fn test_dict_decoder_invalid_index() {
+    let dict = vec![1i32, 2];
+    let mut dict_decoder = PlainDecoder::<Int32Type>::new(-1);
+    dict_decoder.set_data(
+      ByteBufferPtr::new(Int32Type::to_byte_array(&dict[..])), dict.len()).unwrap();
+    let mut decoder = DictDecoder::<Int32Type>::new(Arc::new(MemTracker::new()));
+    decoder.set_dict(Box::new(dict_decoder)).unwrap();
+
+    // Bit width of 2, followed by RLE run of index 3
+    decoder.set_data(ByteBufferPtr::new(vec![2, 2, 3]), 1).unwrap();
+    let mut buffer = vec![0; 1];
+    assert_eq!(
+      decoder.get(&mut buffer).unwrap_err(),
+      corrupt_err!("Invalid dictionary index: 3, dictionary has 2 values")
+    );
+
+    assert!(decoder.set_data(ByteBufferPtr::new(vec![]), 1).is_err());
+    assert!(decoder.set_data(ByteBufferPtr::new(vec![33]), 1).is_err());
+  }
+
+  #[test]
+  fn test_plain_decode_byte_array_truncated() {
+    let mut decoder = PlainDecoder::<ByteArrayType>::new(-1);
+    decoder.set_data(ByteBufferPtr::new(vec![4, 0]), 1).unwrap();
+    let mut buffer = vec![ByteArray::new(); 1];
+    assert_eq!(
+      decoder.get(&mut buffer).unwrap_err(),
+      eof_err!("Not enough bytes to decode")
+    );
+  }
+
+  #[test]
+  fn test_delta_bit_packed_invalid_header() {
+    let mut decoder = DeltaBitPackDecoder::<Int32Type>::new();
+    // Block size of 0
+    assert!(decoder.set_data(ByteBufferPtr::new(vec![0, 4, 1, 0]), 1).is_err());
+    // Block size of 64, which is not a multiple of 128
+    assert!(decoder.set_data(ByteBufferPtr::new(vec![64, 2, 1, 0]), 1).is_err());
+    // Block size of 4 with 8 mini blocks, i.e. 0 values per mini block
+    let data = vec![4, 8, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    assert!(decoder.set_data(ByteBufferPtr::new(data), 2).is_err());
+    // 16 values per mini block
+    assert!(decoder.set_data(ByteBufferPtr::new(vec![128, 1, 8, 1, 0]), 1).is_err());
+    // 3 mini blocks do not divide block size
+    assert!(decoder.set_data(ByteBufferPtr::new(vec![128, 1, 3, 1, 0]), 1).is_err());
+    // Bit widths of 4 mini blocks do not fit into remaining 2 bytes
+    let data = vec![128, 1, 4, 2, 0, 0, 0];
+    assert_eq!(
+      decoder.set_data(ByteBufferPtr::new(data), 2).unwrap_err(),
+      eof_err!("Not enough data to decode bit widths of 4 mini blocks")
+    );
+
+    let mut decoder = DeltaBitPackDecoder::<FloatType>::new();
+    assert!(decoder.set_data(ByteBufferPtr::new(vec![128, 1, 4, 1, 0]), 1).is_err());
+  }
+
+  #[test]
+  fn test_delta_bit_packed_int32_multiple_blocks_parquet_mr_layout() {
+    // Page laid out the way parquet-mr writes it: 139 values span two blocks, mini
+    // blocks with bit width 0 have no data, and bit widths of mini blocks after the
+    // last value are not initialized, so they must not be validated.
+    let data = vec![
+      // block size 128, 4 mini blocks, 139 values, first value 7
+      0x80, 0x01, 0x04, 0x8B, 0x01, 0x0E,
+      // min delta 5, bit widths [0, 1, 0, 0], deltas of the second mini block
+      0x0A, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+      // min delta -3, bit widths [0, 255, 32, 65]
+      0x05, 0x00, 0xFF, 0x20, 0x41
+    ];
+
+    let mut expected = vec![7];
+    for k in 1..139 {
+      let delta = if k == 41 { 6 } else if k <= 128 { 5 } else { -3 };
+      let prev = expected[k - 1];
+      expected.push(prev + delta);
+    }
+
+    let mut decoder = DeltaBitPackDecoder::<Int32Type>::new();
+    decoder.set_data(ByteBufferPtr::new(data), 139).unwrap();
+    let mut buffer = vec![0; 139];
+    assert_eq!(decoder.get(&mut buffer).unwrap(), 139);
+    assert_eq!(buffer, expected);
+    assert_eq!(decoder.values_left(), 0);
+    assert_eq!(decoder.get_offset().unwrap(), 20);
+
+    // Bit width of a mini block that is read must fit into the type
+    let data = vec![0x80, 0x01, 0x04, 0x03, 0x00, 0x00, 0x21, 0x00, 0x00, 0x00];
+    decoder.set_data(ByteBufferPtr::new(data), 3).unwrap();
+    let mut buffer = vec![0; 3];
+    assert!(decoder.get(&mut buffer).is_err());
+  }
+
+  #[test]
+  fn test_delta_bit_packed_large_mini_block() {
+    // Block size of 2^40 in a single mini block, bit width of 1 requires 2^37 bytes of
+    // deltas, which the page does not have
+    let data = vec![
+      0x80, 0x80, 0x80, 0x80, 0x80, 0x20, 0x01, 0x02, 0x00, 0x00, 0x01, 0xFF, 0xFF
+    ];
+    let mut decoder = DeltaBitPackDecoder::<Int32Type>::new();
+    decoder.set_data(ByteBufferPtr::new(data), 2).unwrap();
+    let mut buffer = vec![0; 2];
+    assert_eq!(
+      decoder.get(&mut buffer).unwrap_err(),
+      eof_err!("Not enough data to decode 'delta'")
+    );
+
+    // Mini block with bit width of 0 has no data, so nothing is allocated
+    let data = vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x20, 0x01, 0x03, 0x02, 0x02, 0x00];
+    let mem_tracker = Arc::new(MemTracker::new());
+    let mut decoder = DeltaBitPackDecoder::<Int64Type>::new()
+      .with_mem_tracker(mem_tracker.clone());
+    decoder.set_data(ByteBufferPtr::new(data), 3).unwrap();
+    let mut buffer = vec![0; 3];
+    assert_eq!(decoder.get(&mut buffer).unwrap(), 3);
+    assert_eq!(buffer, vec![1, 2, 3]);
+    assert_eq!(mem_tracker.max_memory_usage(), 0);
+
+    // Memory of deltas is charged to memory tracker
+    let mut data = vec![0x80, 0x01, 0x01, 0x03, 0x00, 0x02, 0x01];
+    data.extend_from_slice(&[0xFF; 16]);
+    let mem_tracker = Arc::new(MemTracker::with_limit(100));
+    let mut decoder = DeltaBitPackDecoder::<Int32Type>::new()
+      .with_mem_tracker(mem_tracker.clone());
+    decoder.set_data(ByteBufferPtr::new(data), 3).unwrap();
+    let mut buffer = vec![0; 3];
+    assert!(decoder.get(&mut buffer).is_err());
+    assert_eq!(mem_tracker.memory_usage(), 0);
+  }
+
   fn test_plain_decode<T: DataType>(
     data: ByteBufferPtr,
     num_values: usize,
@@ -1231,7 +2094,7 @@ mod tests {
 
     // Encode data
     let mut encoder = get_encoder::<T>(col_descr.clone(), encoding,
-      Rc::new(MemTracker::new())).expect("get encoder");
+      Arc::new(MemTracker::new())).expect("get encoder");
 
     for v in &data[..] {
       encoder.put(&v[..]).expect("ok to encode");
@@ -1242,8 +2105,8 @@ mod tests {
     let expected: Vec<T::T> = data.iter().flat_map(|s| s.clone()).collect();
 
     // Decode data and compare with original
-    let mut decoder = get_decoder::<T>(col_descr.clone(), encoding)
-      .expect("get decoder");
+    let mut decoder = get_decoder::<T>(col_descr.clone(), encoding,
+      Arc::new(MemTracker::new())).expect("get decoder");
 
     let mut result = vec![T::T::default(); expected.len()];
     decoder.set_data(bytes, expected.len()).expect("ok to set data");
@@ -1259,8 +2122,11 @@ mod tests {
   fn create_and_check_decoder<T: DataType>(
     encoding: Encoding, err: Option<ParquetError>
   ) {
-    let descr = create_test_col_desc_ptr(-1, T::get_physical_type());
-    let decoder = get_decoder::<T>(descr, encoding);
+    let physical_type = T::get_physical_type();
+    // Fixed length byte arrays must have a valid length
+    let type_len = if physical_type == Type::FIXED_LEN_BYTE_ARRAY { 4 } else { -1 };
+    let descr = create_test_col_desc_ptr(type_len, physical_type);
+    let decoder = get_decoder::<T>(descr, encoding, Arc::new(MemTracker::new()));
     match err {
       Some(parquet_error) => {
         assert!(decoder.is_err());
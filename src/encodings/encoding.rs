@@ -28,7 +28,7 @@ use data_type::*;
 use encodings::rle::RleEncoder;
 use errors::{ParquetError, Result};
 use schema::types::ColumnDescPtr;
-use util::bit_util::{log2, num_required_bits, BitWriter};
+use util::bit_util::{get_array_bit, log2, num_required_bits, BitWriter};
 use util::memory::{Buffer, ByteBuffer, ByteBufferPtr, MemTrackerPtr};
 use util::hash_util;
 
@@ -43,6 +43,30 @@ pub trait Encoder<T: DataType> {
   /// Encodes data from `values`.
   fn put(&mut self, values: &[T::T]) -> Result<()>;
 
+  /// Encodes non-null values from `values`, which has a slot for every value including
+  /// nulls. Slot `i` is non-null if bit `i` of `valid_bits` is set, bits are in
+  /// least-significant bit order. Returns number of encoded values.
+  ///
+  /// Runs of non-null values are encoded with `put` directly, so values do not need to
+  /// be compacted into a dense buffer first.
+  fn put_spaced(&mut self, values: &[T::T], valid_bits: &[u8]) -> Result<usize> {
+    let mut num_encoded = 0;
+    let mut i = 0;
+    while i < values.len() {
+      if !get_array_bit(valid_bits, i) {
+        i += 1;
+        continue;
+      }
+      let start = i;
+      while i < values.len() && get_array_bit(valid_bits, i) {
+        i += 1;
+      }
+      self.put(&values[start..i])?;
+      num_encoded += i - start;
+    }
+    Ok(num_encoded)
+  }
+
   /// Returns the encoding type of this encoder.
   fn encoding(&self) -> Encoding;
 
@@ -982,6 +1006,7 @@ mod tests {
   use super::*;
   use schema::types::{ColumnDescriptor, ColumnDescPtr, ColumnPath, Type as SchemaType};
   use std::rc::Rc;
+  use std::sync::Arc;
   use util::memory::MemTracker;
   use util::test_common::RandGen;
 
@@ -1178,6 +1203,44 @@ mod tests {
     assert_eq!(output, input);
   }
 
+  #[test]
+  fn test_put_spaced() {
+    // Nulls at the start, in the middle and at the end, across byte boundary
+    let valid_bits = [0b1101_1100, 0b0000_0011];
+    let num_values = 11;
+    let expected_slots = [2, 3, 4, 6, 7, 8, 9];
+
+    for &encoding in &[Encoding::PLAIN, Encoding::DELTA_BINARY_PACKED] {
+      let mut encoder = create_test_encoder::<Int32Type>(0, encoding);
+      let mut decoder = create_test_decoder::<Int32Type>(0, encoding);
+      let values = (0..num_values).map(|v| v * 10).collect::<Vec<i32>>();
+      assert_eq!(encoder.put_spaced(&values, &valid_bits).unwrap(), 7);
+      decoder.set_data(encoder.flush_buffer().unwrap(), 7).unwrap();
+      let mut output = vec![0; 7];
+      assert_eq!(decoder.get(&mut output).unwrap(), 7);
+      let expected = expected_slots.iter().map(|&i| values[i]).collect::<Vec<i32>>();
+      assert_eq!(output, expected);
+    }
+
+    let mut encoder = create_test_encoder::<ByteArrayType>(0, Encoding::PLAIN);
+    let mut decoder = create_test_decoder::<ByteArrayType>(0, Encoding::PLAIN);
+    let values = (0..num_values)
+      .map(|v| ByteArray::from(v.to_string().as_str()))
+      .collect::<Vec<_>>();
+    assert_eq!(encoder.put_spaced(&values, &valid_bits).unwrap(), 7);
+    decoder.set_data(encoder.flush_buffer().unwrap(), 7).unwrap();
+    let mut output = vec![ByteArray::default(); 7];
+    assert_eq!(decoder.get(&mut output).unwrap(), 7);
+    let expected = expected_slots.iter().map(|&i| values[i].clone()).collect::<Vec<_>>();
+    assert_eq!(output, expected);
+
+    let mut encoder = create_test_encoder::<BoolType>(0, Encoding::PLAIN);
+    let values = vec![true; 8];
+    assert_eq!(encoder.put_spaced(&values, &[0]).unwrap(), 0);
+    assert_eq!(encoder.put_spaced(&values, &[0xFF]).unwrap(), 8);
+    assert_eq!(encoder.flush_buffer().unwrap().data(), &[0xFF]);
+  }
+
   trait EncodingTester<T: DataType> {
     fn test(enc: Encoding, total: usize, type_length: i32) {
       let result = match enc {
@@ -1272,7 +1335,7 @@ mod tests {
     encoding: Encoding, err: Option<ParquetError>
   ) {
     let descr = create_test_col_desc_ptr(-1, T::get_physical_type());
-    let mem_tracker = Rc::new(MemTracker::new());
+    let mem_tracker = Arc::new(MemTracker::new());
     let encoder = get_encoder::<T>(descr, encoding, mem_tracker);
     match err {
       Some(parquet_error) => {
@@ -1299,7 +1362,7 @@ mod tests {
     type_len: i32, enc: Encoding
   ) -> Box<Encoder<T>> {
     let desc = create_test_col_desc_ptr(type_len, T::get_physical_type());
-    let mem_tracker = Rc::new(MemTracker::new());
+    let mem_tracker = Arc::new(MemTracker::new());
     get_encoder(desc, enc, mem_tracker).unwrap()
   }
 
@@ -1307,16 +1370,18 @@ mod tests {
     type_len: i32, enc: Encoding
   ) -> Box<Decoder<T>> {
     let desc = create_test_col_desc_ptr(type_len, T::get_physical_type());
-    get_decoder(desc, enc).unwrap()
+    let mem_tracker = Arc::new(MemTracker::new());
+    get_decoder(desc, enc, mem_tracker).unwrap()
   }
 
   fn create_test_dict_encoder<T: DataType>(type_len: i32) -> DictEncoder<T> {
     let desc = create_test_col_desc_ptr(type_len, T::get_physical_type());
-    let mem_tracker = Rc::new(MemTracker::new());
+    let mem_tracker = Arc::new(MemTracker::new());
     DictEncoder::<T>::new(desc, mem_tracker)
   }
 
   fn create_test_dict_decoder<T: DataType>() -> DictDecoder<T> {
-    DictDecoder::<T>::new()
+    let mem_tracker = Arc::new(MemTracker::new());
+    DictDecoder::<T>::new(mem_tracker)
   }
 }
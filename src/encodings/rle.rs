@@ -338,6 +338,8 @@ impl RleDecoder {
     }
   }
 
+  /// Sets data to decode. Runs are read lazily, so errors in `data` are returned by
+  /// `get`, `get_batch` and `get_batch_with_dict`.
   pub fn set_data(&mut self, data: ByteBufferPtr) {
     if let Some(ref mut bit_reader) = self.bit_reader {
       bit_reader.reset(data);
@@ -346,29 +348,29 @@ impl RleDecoder {
       self.index_buf = Some([0; 1024]);
     }
 
-    let _ = self.reload();
+    self.rle_left = 0;
+    self.bit_packed_left = 0;
+    self.current_value = None;
   }
 
   #[inline]
   pub fn get<T: Default>(&mut self) -> Result<Option<T>> {
-    assert!(size_of::<T>() <= 8);
+    check_value_size::<T>()?;
 
     while self.rle_left <= 0 && self.bit_packed_left <= 0 {
-      if !self.reload() {
+      if !self.reload()? {
         return Ok(None);
       }
     }
 
     let value =
       if self.rle_left > 0 {
-        let rle_value = unsafe {
-          transmute_copy::<u64, T>(
-            self.current_value.as_mut().expect("current_value should be Some"))
-        };
+        let current_value = self.current_value.ok_or_else(no_rle_value)?;
+        let rle_value = unsafe { transmute_copy::<u64, T>(&current_value) };
         self.rle_left -= 1;
         rle_value
       } else { // self.bit_packed_left > 0
-        let bit_reader = self.bit_reader.as_mut().expect("bit_reader should be Some");
+        let bit_reader = self.bit_reader.as_mut().ok_or_else(data_not_set)?;
         let bit_packed_value = bit_reader
           .get_value(self.bit_width as usize)
           .ok_or(eof_err!("Not enough data for 'bit_packed_value'"))?;
@@ -381,36 +383,37 @@ impl RleDecoder {
 
   #[inline]
   pub fn get_batch<T: Default>(&mut self, buffer: &mut [T]) -> Result<usize> {
-    assert!(self.bit_reader.is_some());
-    assert!(size_of::<T>() <= 8);
+    if self.bit_reader.is_none() {
+      return Err(data_not_set());
+    }
+    check_value_size::<T>()?;
 
     let mut values_read = 0;
     while values_read < buffer.len() {
       if self.rle_left > 0 {
-        assert!(self.current_value.is_some());
+        let current_value = self.current_value.ok_or_else(no_rle_value)?;
         let num_values = cmp::min(buffer.len() - values_read, self.rle_left as usize);
         for i in 0..num_values {
-          let repeated_value = unsafe {
-            transmute_copy::<u64, T>(self.current_value.as_mut().unwrap())
-          };
+          let repeated_value = unsafe { transmute_copy::<u64, T>(&current_value) };
           buffer[values_read + i] = repeated_value;
         }
         self.rle_left -= num_values as u32;
         values_read += num_values;
       } else if self.bit_packed_left > 0 {
-        assert!(self.bit_reader.is_some());
-        let mut num_values = cmp::min(
+        let num_values = cmp::min(
           buffer.len() - values_read, self.bit_packed_left as usize
         );
-        if let Some(ref mut bit_reader) = self.bit_reader {
-          num_values = bit_reader.get_batch::<T>(
-            &mut buffer[values_read..values_read + num_values], self.bit_width as usize
-          );
-          self.bit_packed_left -= num_values as u32;
-          values_read += num_values;
+        let bit_reader = self.bit_reader.as_mut().ok_or_else(data_not_set)?;
+        let num_values = bit_reader.get_batch::<T>(
+          &mut buffer[values_read..values_read + num_values], self.bit_width as usize
+        );
+        if num_values == 0 {
+          return Err(eof_err!("Not enough data for bit-packed run"));
         }
+        self.bit_packed_left -= num_values as u32;
+        values_read += num_values;
       } else {
-        if !self.reload() {
+        if !self.reload()? {
           break;
         }
       }
@@ -419,6 +422,13 @@ impl RleDecoder {
     Ok(values_read)
   }
 
+  /// Reads at most `max_values` dictionary indices and writes corresponding values of
+  /// `dict` into `buffer`. Returns number of values read, which is less than
+  /// `max_values` only when there is no more data.
+  ///
+  /// Values of a RLE run are filled with a single lookup, indices of a bit-packed run are
+  /// unpacked in batches, checked against the size of the dictionary once per batch and
+  /// then gathered without further bounds checks.
   #[inline]
   pub fn get_batch_with_dict<T>(
     &mut self,
@@ -426,43 +436,44 @@ impl RleDecoder {
     buffer: &mut [T],
     max_values: usize
   ) -> Result<usize> where T: Default + Clone {
-    assert!(buffer.len() >= max_values);
+    if buffer.len() < max_values {
+      return Err(general_err!(
+        "Buffer of {} values is too small to read {} values", buffer.len(), max_values));
+    }
 
     let mut values_read = 0;
     while values_read < max_values {
       if self.rle_left > 0 {
-        assert!(self.current_value.is_some());
+        let current_value = self.current_value.ok_or_else(no_rle_value)?;
         let num_values = cmp::min(max_values - values_read, self.rle_left as usize);
-        let dict_idx = self.current_value.unwrap() as usize;
-        for i in 0..num_values {
-          buffer[values_read + i] = dict[dict_idx].clone();
+        let dict_idx = current_value as usize;
+        if dict_idx >= dict.len() {
+          return Err(invalid_dict_index(dict_idx, dict.len()));
+        }
+        let value = &dict[dict_idx];
+        for v in &mut buffer[values_read..values_read + num_values] {
+          *v = value.clone();
         }
         self.rle_left -= num_values as u32;
         values_read += num_values;
       } else if self.bit_packed_left > 0 {
-        assert!(self.bit_reader.is_some());
-        let mut num_values = cmp::min(
+        let num_values = cmp::min(
           max_values - values_read, self.bit_packed_left as usize
         );
-        if let Some(ref mut bit_reader) = self.bit_reader {
-          let mut index_buf = self.index_buf.unwrap();
-          num_values = cmp::min(num_values, index_buf.len());
-          loop {
-            num_values = bit_reader.get_batch::<i32>(
-              &mut index_buf[..num_values], self.bit_width as usize
-            );
-            for i in 0..num_values {
-              buffer[values_read + i] = dict[index_buf[i] as usize].clone();
-            }
-            self.bit_packed_left -= num_values as u32;
-            values_read += num_values;
-            if num_values < index_buf.len() {
-              break;
-            }
-          }
+        let num_values = Self::get_bit_packed_with_dict(
+          self.bit_reader.as_mut().ok_or_else(data_not_set)?,
+          self.index_buf.as_mut().ok_or_else(data_not_set)?,
+          self.bit_width as usize,
+          dict,
+          &mut buffer[values_read..values_read + num_values]
+        )?;
+        if num_values == 0 {
+          return Err(eof_err!("Not enough data for bit-packed run"));
         }
+        self.bit_packed_left -= num_values as u32;
+        values_read += num_values;
       } else {
-        if !self.reload() {
+        if !self.reload()? {
           break;
         }
       }
@@ -471,28 +482,93 @@ impl RleDecoder {
     Ok(values_read)
   }
 
+  /// Unpacks at most `buffer.len()` indices of a bit-packed run from `bit_reader` in
+  /// batches of `index_buf` size and writes corresponding values of `dict` into
+  /// `buffer`. Returns number of values read.
   #[inline]
-  fn reload(&mut self) -> bool {
-    assert!(self.bit_reader.is_some());
-    if let Some(ref mut bit_reader) = self.bit_reader {
-      if let Some(indicator_value) = bit_reader.get_vlq_int() {
-        if indicator_value & 1 == 1 {
-          self.bit_packed_left = ((indicator_value >> 1) * 8) as u32;
-        } else {
-          self.rle_left = (indicator_value >> 1) as u32;
-          let value_width = bit_util::ceil(self.bit_width as i64, 8);
-          self.current_value = bit_reader.get_aligned::<u64>(value_width as usize);
-          assert!(self.current_value.is_some());
-        }
-        return true;
+  fn get_bit_packed_with_dict<T: Clone>(
+    bit_reader: &mut BitReader,
+    index_buf: &mut [i32],
+    bit_width: usize,
+    dict: &[T],
+    buffer: &mut [T]
+  ) -> Result<usize> {
+    let mut values_read = 0;
+    while values_read < buffer.len() {
+      let batch_size = cmp::min(buffer.len() - values_read, index_buf.len());
+      let num_values =
+        bit_reader.get_batch::<i32>(&mut index_buf[..batch_size], bit_width);
+      let indices = &index_buf[..num_values];
+
+      // Indices are unsigned, so a single comparison of the maximum index validates the
+      // whole batch
+      let max_idx = indices.iter().fold(0, |max, &idx| cmp::max(max, idx as u32));
+      if num_values > 0 && max_idx as usize >= dict.len() {
+        return Err(invalid_dict_index(max_idx as usize, dict.len()));
+      }
+      let values = &mut buffer[values_read..values_read + num_values];
+      for (v, &idx) in values.iter_mut().zip(indices) {
+        *v = unsafe { dict.get_unchecked(idx as u32 as usize) }.clone();
+      }
+
+      values_read += num_values;
+      if num_values < batch_size {
+        break;
+      }
+    }
+    Ok(values_read)
+  }
+
+  /// Reads header of the next run. Returns `false` if there is no more data, and error
+  /// if value of a RLE run is truncated.
+  #[inline]
+  fn reload(&mut self) -> Result<bool> {
+    let bit_reader = self.bit_reader.as_mut().ok_or_else(data_not_set)?;
+    if let Some(indicator_value) = bit_reader.get_vlq_int() {
+      if indicator_value & 1 == 1 {
+        self.bit_packed_left = ((indicator_value >> 1) * 8) as u32;
       } else {
-        return false;
+        let value_width = bit_util::ceil(self.bit_width as i64, 8);
+        self.current_value = bit_reader.get_aligned::<u64>(value_width as usize);
+        if self.current_value.is_none() {
+          return Err(eof_err!("Not enough data for RLE run value"));
+        }
+        self.rle_left = (indicator_value >> 1) as u32;
       }
+      Ok(true)
+    } else {
+      Ok(false)
     }
-    return false;
   }
 }
 
+/// Returns error if values of type `T` can not be decoded, i.e. `T` is wider than 64
+/// bits.
+#[inline]
+fn check_value_size<T>() -> Result<()> {
+  if size_of::<T>() > 8 {
+    return Err(general_err!(
+      "RleDecoder can not decode values of {} bytes", size_of::<T>()));
+  }
+  Ok(())
+}
+
+/// Returns error for a decoder without data.
+fn data_not_set() -> ParquetError {
+  general_err!("Data is not set, call set_data() first")
+}
+
+/// Returns error for a RLE run without value.
+fn no_rle_value() -> ParquetError {
+  general_err!("RLE run has no value")
+}
+
+/// Returns error for dictionary index `index` that is out of range of dictionary of
+/// `dict_len` values.
+fn invalid_dict_index(index: usize, dict_len: usize) -> ParquetError {
+  corrupt_err!(
+    "Invalid dictionary index: {}, dictionary has {} values", index, dict_len)
+}
 
 #[cfg(test)]
 mod tests {
@@ -609,6 +685,114 @@ mod tests {
     assert_eq!(buffer, expected);
   }
 
+  #[test]
+  fn test_rle_decode_with_dict_runs() {
+    // Several bit-packed runs followed by RLE runs
+    let mut indices = Vec::new();
+    for i in 0..3000 {
+      indices.push((i * 7 % 100) as u64);
+    }
+    for i in 0..1500 {
+      indices.push((i / 500) as u64);
+    }
+    let mut encoder = RleEncoder::new(7, 64 * 1024);
+    for &idx in &indices {
+      encoder.put(idx).unwrap();
+    }
+    let data = ByteBufferPtr::new(encoder.consume().unwrap());
+    let dict: Vec<i64> = (0..100).map(|v| v * 10).collect();
+    let expected: Vec<i64> = indices.iter().map(|&idx| dict[idx as usize]).collect();
+
+    for &batch_size in &[1, 37, 1024, 5000] {
+      let mut decoder = RleDecoder::new(7);
+      decoder.set_data(data.all());
+      let mut values = Vec::new();
+      let mut buffer = vec![0; batch_size];
+      loop {
+        let num_values =
+          decoder.get_batch_with_dict(&dict, &mut buffer, batch_size).unwrap();
+        values.extend_from_slice(&buffer[..num_values]);
+        if num_values < batch_size {
+          break;
+        }
+      }
+      assert_eq!(values, expected);
+    }
+
+    // Index beyond the dictionary in a bit-packed run
+    let mut decoder = RleDecoder::new(7);
+    decoder.set_data(data.all());
+    let mut buffer = vec![0; 100];
+    let result = decoder.get_batch_with_dict(&dict[..50], &mut buffer, 100);
+    assert_eq!(
+      result.unwrap_err(),
+      corrupt_err!("Invalid dictionary index: 99, dictionary has 50 values")
+    );
+
+    // Index beyond the dictionary in a RLE run
+    let mut decoder = RleDecoder::new(7);
+    decoder.set_data(data);
+    let mut buffer = vec![0; 3000];
+    assert_eq!(decoder.get_batch_with_dict(&dict, &mut buffer, 3000).unwrap(), 3000);
+    let result = decoder.get_batch_with_dict(&dict[..2], &mut buffer, 1500);
+    assert_eq!(
+      result.unwrap_err(),
+      corrupt_err!("Invalid dictionary index: 2, dictionary has 2 values")
+    );
+  }
+
+  #[test]
+  fn test_rle_decode_truncated() {
+    // RLE run of 4 values without the value
+    let mut decoder = RleDecoder::new(8);
+    decoder.set_data(ByteBufferPtr::new(vec![0x08]));
+    assert_eq!(
+      decoder.get::<i32>().unwrap_err(),
+      eof_err!("Not enough data for RLE run value")
+    );
+    decoder.set_data(ByteBufferPtr::new(vec![0x08]));
+    let mut buffer = vec![0; 4];
+    assert_eq!(
+      decoder.get_batch(&mut buffer).unwrap_err(),
+      eof_err!("Not enough data for RLE run value")
+    );
+    decoder.set_data(ByteBufferPtr::new(vec![0x08]));
+    assert_eq!(
+      decoder.get_batch_with_dict(&[1, 2], &mut buffer, 4).unwrap_err(),
+      eof_err!("Not enough data for RLE run value")
+    );
+
+    // Bit-packed run of 8 values with data for 2 values only
+    let mut decoder = RleDecoder::new(3);
+    decoder.set_data(ByteBufferPtr::new(vec![0x03, 0x88]));
+    let mut buffer = vec![0; 8];
+    assert_eq!(
+      decoder.get_batch(&mut buffer).unwrap_err(),
+      eof_err!("Not enough data for bit-packed run")
+    );
+    decoder.set_data(ByteBufferPtr::new(vec![0x03, 0x88]));
+    assert_eq!(decoder.get::<i32>().unwrap(), Some(0));
+    assert_eq!(decoder.get::<i32>().unwrap(), Some(1));
+    assert_eq!(
+      decoder.get::<i32>().unwrap_err(),
+      eof_err!("Not enough data for 'bit_packed_value'")
+    );
+
+    // Decoder without data, and buffer smaller than number of values to read
+    let mut decoder = RleDecoder::new(3);
+    assert!(decoder.get_batch(&mut buffer).is_err());
+    decoder.set_data(ByteBufferPtr::new(vec![0x08, 0x01]));
+    assert_eq!(
+      decoder.get_batch_with_dict(&[1, 2], &mut buffer, 9).unwrap_err(),
+      general_err!("Buffer of 8 values is too small to read 9 values")
+    );
+
+    // State of the previous data is reset
+    decoder.set_data(ByteBufferPtr::new(vec![0x02, 0x05]));
+    assert_eq!(decoder.get_batch(&mut buffer).unwrap(), 1);
+    assert_eq!(buffer[0], 5);
+  }
+
   fn validate_rle(
     values: &[i64],
     bit_width: u8,
@@ -721,6 +905,18 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_values_wide_bit_widths() {
+    for width in MAX_WIDTH + 1..65 {
+      let high_bit = 1u64 << (width - 1);
+      // Values with the highest bit set are bit packed, repeated values are RLE encoded
+      let mut values: Vec<i64> = (0..100).map(|i| (high_bit | i) as i64).collect();
+      values.extend(vec![(high_bit | 7) as i64; 20]);
+      validate_rle(&values, width as u8, None, -1);
+      test_rle_values(width, 1024, -1);
+    }
+  }
+
   #[test]
   fn test_rle_specific_roundtrip() {
     let bit_width = 1;
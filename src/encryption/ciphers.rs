@@ -0,0 +1,524 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains AES ciphers of encrypted modules and construction of their additional
+//! authenticated data (AAD).
+//!
+//! Each encrypted module is stored as 4 bytes of little-endian length of the rest of
+//! the module, followed by 12 bytes of nonce and ciphertext. Modules encrypted with
+//! AES-GCM end with 16 bytes of authentication tag, modules encrypted with AES-CTR are
+//! not authenticated and use nonce followed by counter `1` as initialization vector.
+
+use std::io::Read;
+
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::aead::consts::U12;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{AeadInPlace, KeyInit, OsRng};
+use aes_gcm::{AesGcm, Nonce, Tag};
+use byteorder::{ByteOrder, LittleEndian};
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use ctr::Ctr32BE;
+
+use encryption::metadata::EncryptionAlgorithm;
+use errors::{ParquetError, Result};
+
+/// Length of the module length prefix in bytes.
+pub(crate) const SIZE_LEN: usize = 4;
+
+/// Length of nonce in bytes.
+pub(crate) const NONCE_LEN: usize = 12;
+
+/// Length of AES-GCM authentication tag in bytes.
+pub(crate) const TAG_LEN: usize = 16;
+
+/// Initial value of the counter of AES-CTR, appended to nonce.
+const CTR_INITIAL_COUNTER: [u8; 4] = [0, 0, 0, 1];
+
+/// Type of an encrypted module, which is part of its AAD.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ModuleType {
+  FOOTER = 0,
+  COLUMN_META_DATA = 1,
+  DATA_PAGE = 2,
+  DICTIONARY_PAGE = 3,
+  DATA_PAGE_HEADER = 4,
+  DICTIONARY_PAGE_HEADER = 5,
+  COLUMN_INDEX = 6,
+  OFFSET_INDEX = 7,
+  BLOOM_FILTER_HEADER = 8,
+  BLOOM_FILTER_BITSET = 9
+}
+
+impl ModuleType {
+  /// Returns `true` if module contains page data, which is encrypted with AES-CTR in
+  /// `AES_GCM_CTR_V1` algorithm.
+  pub(crate) fn is_page_data(&self) -> bool {
+    *self == ModuleType::DATA_PAGE || *self == ModuleType::DICTIONARY_PAGE
+  }
+}
+
+/// Returns AAD of footer of a file with `file_aad`.
+pub(crate) fn footer_aad(file_aad: &[u8]) -> Vec<u8> {
+  let mut aad = file_aad.to_vec();
+  aad.push(ModuleType::FOOTER as u8);
+  aad
+}
+
+/// Returns AAD of a module of column `column` of row group `row_group`. Page ordinal,
+/// counting data pages only, is added to AAD of data pages and their headers.
+pub(crate) fn module_aad(
+  file_aad: &[u8],
+  module_type: ModuleType,
+  row_group: usize,
+  column: usize,
+  page: usize
+) -> Result<Vec<u8>> {
+  let mut aad = footer_aad(file_aad);
+  aad[file_aad.len()] = module_type as u8;
+  push_ordinal(&mut aad, "row group", row_group)?;
+  push_ordinal(&mut aad, "column", column)?;
+  if module_type == ModuleType::DATA_PAGE || module_type == ModuleType::DATA_PAGE_HEADER {
+    push_ordinal(&mut aad, "page", page)?;
+  }
+  Ok(aad)
+}
+
+/// Appends `ordinal` as 2 bytes in little-endian order, ordinals are limited to 32767.
+fn push_ordinal(aad: &mut Vec<u8>, name: &str, ordinal: usize) -> Result<()> {
+  if ordinal > i16::max_value() as usize {
+    return Err(general_err!(
+      "Encrypted files cannot have more than {} {}s",
+      i16::max_value() as usize + 1,
+      name
+    ));
+  }
+  let mut buf = [0; 2];
+  LittleEndian::write_i16(&mut buf, ordinal as i16);
+  aad.extend_from_slice(&buf);
+  Ok(())
+}
+
+/// Returns `len` random bytes, e.g. nonce or unique part of file AAD.
+pub(crate) fn random_bytes(len: usize) -> Vec<u8> {
+  let mut buf = vec![0; len];
+  OsRng.fill_bytes(&mut buf);
+  buf
+}
+
+/// Checks that `key` is a valid AES key, i.e. 16, 24 or 32 bytes long.
+pub(crate) fn check_key(key: &[u8]) -> Result<()> {
+  match key.len() {
+    16 | 24 | 32 => Ok(()),
+    len => Err(general_err!("Invalid key length {}, expected 16, 24 or 32 bytes", len))
+  }
+}
+
+/// Reads an encrypted module, including its length, from `reader`. Length is checked
+/// against `max_len` before the module is allocated.
+pub(crate) fn read_module<R: Read>(reader: &mut R, max_len: usize) -> Result<Vec<u8>> {
+  let mut module = vec![0; SIZE_LEN];
+  reader.read_exact(&mut module)?;
+  let len = LittleEndian::read_u32(&module) as usize;
+  if len > max_len {
+    return Err(corrupt_err!(
+      "Length of encrypted module {} exceeds limit {}", len, max_len));
+  }
+  module.resize(SIZE_LEN + len, 0);
+  reader.read_exact(&mut module[SIZE_LEN..])?;
+  Ok(module)
+}
+
+/// AES cipher of encrypted modules with a data key.
+#[derive(Clone)]
+pub(crate) struct AesCipher {
+  key: Vec<u8>
+}
+
+impl AesCipher {
+  /// Creates cipher with `key` of 16, 24 or 32 bytes.
+  pub(crate) fn new(key: &[u8]) -> Result<Self> {
+    check_key(key)?;
+    Ok(Self { key: key.to_vec() })
+  }
+
+  /// Encrypts `plaintext` with AES-GCM and a random nonce, returns encrypted module.
+  pub(crate) fn encrypt_gcm(&self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    self.encrypt_gcm_with_nonce(&random_bytes(NONCE_LEN), aad, plaintext)
+  }
+
+  /// Encrypts `plaintext` with AES-GCM and `nonce`, returns encrypted module.
+  pub(crate) fn encrypt_gcm_with_nonce(
+    &self,
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8]
+  ) -> Result<Vec<u8>> {
+    let len = NONCE_LEN + plaintext.len() + TAG_LEN;
+    let mut module = Vec::with_capacity(SIZE_LEN + len);
+    module.extend_from_slice(&module_len(len)?);
+    module.extend_from_slice(nonce);
+    module.extend_from_slice(plaintext);
+    let tag = {
+      let buf = &mut module[SIZE_LEN + NONCE_LEN..];
+      match self.key.len() {
+        16 => gcm_encrypt::<AesGcm<Aes128, U12>>(&self.key, nonce, aad, buf),
+        24 => gcm_encrypt::<AesGcm<Aes192, U12>>(&self.key, nonce, aad, buf),
+        _ => gcm_encrypt::<AesGcm<Aes256, U12>>(&self.key, nonce, aad, buf)
+      }?
+    };
+    module.extend_from_slice(&tag);
+    Ok(module)
+  }
+
+  /// Decrypts AES-GCM encrypted `module` and verifies its authentication tag, returns
+  /// plaintext. Fails if key or AAD is wrong, or module is corrupt.
+  pub(crate) fn decrypt_gcm(&self, aad: &[u8], module: &[u8]) -> Result<Vec<u8>> {
+    check_module_len(module, NONCE_LEN + TAG_LEN)?;
+    let nonce = &module[SIZE_LEN..SIZE_LEN + NONCE_LEN];
+    let tag = &module[module.len() - TAG_LEN..];
+    let mut buf = module[SIZE_LEN + NONCE_LEN..module.len() - TAG_LEN].to_vec();
+    match self.key.len() {
+      16 => gcm_decrypt::<AesGcm<Aes128, U12>>(&self.key, nonce, aad, &mut buf, tag),
+      24 => gcm_decrypt::<AesGcm<Aes192, U12>>(&self.key, nonce, aad, &mut buf, tag),
+      _ => gcm_decrypt::<AesGcm<Aes256, U12>>(&self.key, nonce, aad, &mut buf, tag)
+    }?;
+    Ok(buf)
+  }
+
+  /// Encrypts `plaintext` with AES-CTR and a random nonce, returns encrypted module.
+  pub(crate) fn encrypt_ctr(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let len = NONCE_LEN + plaintext.len();
+    let mut module = Vec::with_capacity(SIZE_LEN + len);
+    module.extend_from_slice(&module_len(len)?);
+    module.extend_from_slice(&random_bytes(NONCE_LEN));
+    module.extend_from_slice(plaintext);
+    let (header, buf) = module.split_at_mut(SIZE_LEN + NONCE_LEN);
+    self.apply_ctr(&header[SIZE_LEN..], buf)?;
+    Ok(module)
+  }
+
+  /// Decrypts AES-CTR encrypted `module`, returns plaintext.
+  pub(crate) fn decrypt_ctr(&self, module: &[u8]) -> Result<Vec<u8>> {
+    check_module_len(module, NONCE_LEN)?;
+    let mut buf = module[SIZE_LEN + NONCE_LEN..].to_vec();
+    self.apply_ctr(&module[SIZE_LEN..SIZE_LEN + NONCE_LEN], &mut buf)?;
+    Ok(buf)
+  }
+
+  /// Applies AES-CTR keystream of `nonce` to `buf`.
+  fn apply_ctr(&self, nonce: &[u8], buf: &mut [u8]) -> Result<()> {
+    let mut iv = [0; 16];
+    iv[..NONCE_LEN].copy_from_slice(nonce);
+    iv[NONCE_LEN..].copy_from_slice(&CTR_INITIAL_COUNTER);
+    match self.key.len() {
+      16 => ctr_apply::<Ctr32BE<Aes128>>(&self.key, &iv, buf),
+      24 => ctr_apply::<Ctr32BE<Aes192>>(&self.key, &iv, buf),
+      _ => ctr_apply::<Ctr32BE<Aes256>>(&self.key, &iv, buf)
+    }
+  }
+}
+
+/// Cipher of modules of a column chunk: pages, page headers, column metadata, page
+/// indexes and bloom filter.
+#[derive(Clone)]
+pub(crate) struct ColumnCipher {
+  cipher: AesCipher,
+  algorithm: EncryptionAlgorithm,
+  file_aad: Vec<u8>,
+  row_group: usize,
+  column: usize
+}
+
+impl ColumnCipher {
+  /// Creates cipher of column `column` of row group `row_group` with column `key`.
+  pub(crate) fn new(
+    key: &[u8],
+    algorithm: EncryptionAlgorithm,
+    file_aad: &[u8],
+    row_group: usize,
+    column: usize
+  ) -> Result<Self> {
+    Ok(Self {
+      cipher: AesCipher::new(key)?,
+      algorithm: algorithm,
+      file_aad: file_aad.to_vec(),
+      row_group: row_group,
+      column: column
+    })
+  }
+
+  /// Encrypts `plaintext` of a module, `page` is ordinal of a data page and is only
+  /// used for data pages and their headers.
+  pub(crate) fn encrypt(
+    &self,
+    module_type: ModuleType,
+    page: usize,
+    plaintext: &[u8]
+  ) -> Result<Vec<u8>> {
+    let aad = module_aad(&self.file_aad, module_type, self.row_group, self.column, page)?;
+    if self.is_ctr(module_type) {
+      self.cipher.encrypt_ctr(plaintext)
+    } else {
+      self.cipher.encrypt_gcm(&aad, plaintext)
+    }
+  }
+
+  /// Decrypts encrypted `module`, see [`encrypt`](#method.encrypt).
+  pub(crate) fn decrypt(
+    &self,
+    module_type: ModuleType,
+    page: usize,
+    module: &[u8]
+  ) -> Result<Vec<u8>> {
+    let aad = module_aad(&self.file_aad, module_type, self.row_group, self.column, page)?;
+    if self.is_ctr(module_type) {
+      self.cipher.decrypt_ctr(module)
+    } else {
+      self.cipher.decrypt_gcm(&aad, module)
+    }
+  }
+
+  /// Returns `true` if module is encrypted with AES-CTR.
+  fn is_ctr(&self, module_type: ModuleType) -> bool {
+    self.algorithm == EncryptionAlgorithm::AES_GCM_CTR_V1 && module_type.is_page_data()
+  }
+}
+
+/// Returns length prefix of a module, whose length without prefix is `len`.
+fn module_len(len: usize) -> Result<[u8; SIZE_LEN]> {
+  if len > i32::max_value() as usize {
+    return Err(general_err!("Encrypted module of {} bytes is too large", len));
+  }
+  let mut buf = [0; SIZE_LEN];
+  LittleEndian::write_u32(&mut buf, len as u32);
+  Ok(buf)
+}
+
+/// Checks that length prefix of `module` matches its size, and that module has at least
+/// `min_len` bytes after the prefix.
+fn check_module_len(module: &[u8], min_len: usize) -> Result<()> {
+  if module.len() < SIZE_LEN + min_len {
+    return Err(corrupt_err!("Encrypted module of {} bytes is too short", module.len()));
+  }
+  let len = LittleEndian::read_u32(module) as usize;
+  if len != module.len() - SIZE_LEN {
+    return Err(corrupt_err!(
+      "Encrypted module length {} does not match its size {}",
+      len,
+      module.len() - SIZE_LEN
+    ));
+  }
+  Ok(())
+}
+
+fn gcm_encrypt<C: KeyInit + AeadInPlace>(
+  key: &[u8],
+  nonce: &[u8],
+  aad: &[u8],
+  buf: &mut [u8]
+) -> Result<Vec<u8>> {
+  let cipher = C::new_from_slice(key).map_err(|_| general_err!("Invalid key"))?;
+  let tag = cipher.encrypt_in_place_detached(Nonce::from_slice(nonce), aad, buf)
+    .map_err(|_| general_err!("Failed to encrypt module"))?;
+  Ok(tag.to_vec())
+}
+
+fn gcm_decrypt<C: KeyInit + AeadInPlace>(
+  key: &[u8],
+  nonce: &[u8],
+  aad: &[u8],
+  buf: &mut [u8],
+  tag: &[u8]
+) -> Result<()> {
+  let cipher = C::new_from_slice(key).map_err(|_| general_err!("Invalid key"))?;
+  let nonce = Nonce::from_slice(nonce);
+  cipher.decrypt_in_place_detached(nonce, aad, buf, Tag::from_slice(tag))
+    .map_err(|_| {
+      general_err!("Failed to decrypt module, key is wrong or module is corrupt")
+    })
+}
+
+fn ctr_apply<C: KeyIvInit + StreamCipher>(
+  key: &[u8],
+  iv: &[u8],
+  buf: &mut [u8]
+) -> Result<()> {
+  let mut cipher = C::new_from_slices(key, iv).map_err(|_| general_err!("Invalid key"))?;
+  cipher.apply_keystream(buf);
+  Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::io::Cursor;
+
+  fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len() / 2).map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap())
+      .collect()
+  }
+
+  #[test]
+  fn test_gcm_test_vectors() {
+    // Test cases 2 and 4 of the GCM specification
+    let cipher = AesCipher::new(&[0; 16]).unwrap();
+    let module = cipher.encrypt_gcm_with_nonce(&[0; 12], &[], &[0; 16]).unwrap();
+    assert_eq!(&module[..4], &[44, 0, 0, 0]);
+    assert_eq!(&module[4..16], &[0; 12]);
+    assert_eq!(&module[16..32], &from_hex("0388dace60b6a392f328c2b971b2fe78")[..]);
+    assert_eq!(&module[32..], &from_hex("ab6e47d42cec13bdf53a67b21257bddf")[..]);
+
+    let cipher = AesCipher::new(&from_hex("feffe9928665731c6d6a8f9467308308")).unwrap();
+    let nonce = from_hex("cafebabefacedbaddecaf888");
+    let aad = from_hex("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+    let plaintext = from_hex(
+      "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+       1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39");
+    let module = cipher.encrypt_gcm_with_nonce(&nonce, &aad, &plaintext).unwrap();
+    let ciphertext = from_hex(
+      "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+       21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091");
+    assert_eq!(&module[16..16 + 60], &ciphertext[..]);
+    assert_eq!(&module[16 + 60..], &from_hex("5bc94fbc3221a5db94fae95ae7121a47")[..]);
+    assert_eq!(cipher.decrypt_gcm(&aad, &module).unwrap(), plaintext);
+  }
+
+  #[test]
+  fn test_ctr_test_vector() {
+    // Keystream of zero nonce and counter 1 is the tag of test case 1 of the GCM
+    // specification, which encrypts empty plaintext with zero key and nonce
+    let cipher = AesCipher::new(&[0; 16]).unwrap();
+    let mut module = vec![28, 0, 0, 0];
+    module.extend_from_slice(&[0; 28]);
+    assert_eq!(
+      cipher.decrypt_ctr(&module).unwrap(),
+      from_hex("58e2fccefa7e3061367f1d57a4e7455a")
+    );
+
+    let cipher = AesCipher::new(&from_hex("2b7e151628aed2a6abf7158809cf4f3c")).unwrap();
+    let plaintext = b"plaintext of more than one block of AES".to_vec();
+    let module = cipher.encrypt_ctr(&plaintext).unwrap();
+    assert_eq!(module.len(), 4 + 12 + plaintext.len());
+    assert_ne!(&module[16..], &plaintext[..]);
+    assert_eq!(cipher.decrypt_ctr(&module).unwrap(), plaintext);
+  }
+
+  #[test]
+  fn test_gcm_key_sizes() {
+    for &len in &[16, 24, 32] {
+      let cipher = AesCipher::new(&vec![1; len]).unwrap();
+      let module = cipher.encrypt_gcm(b"aad", b"value").unwrap();
+      assert_eq!(module.len(), 4 + 12 + 5 + 16);
+      assert_eq!(cipher.decrypt_gcm(b"aad", &module).unwrap(), b"value");
+      assert!(cipher.decrypt_gcm(b"other aad", &module).is_err());
+      let other = AesCipher::new(&vec![2; len]).unwrap();
+      assert!(other.decrypt_gcm(b"aad", &module).is_err());
+    }
+    assert_eq!(
+      AesCipher::new(&[0; 10]).err().unwrap(),
+      general_err!("Invalid key length 10, expected 16, 24 or 32 bytes")
+    );
+  }
+
+  #[test]
+  fn test_decrypt_corrupt_module() {
+    let cipher = AesCipher::new(&[0; 16]).unwrap();
+    let mut module = cipher.encrypt_gcm(&[], b"value").unwrap();
+    assert_eq!(
+      cipher.decrypt_gcm(&[], &module[..20]).err().unwrap(),
+      corrupt_err!("Encrypted module of 20 bytes is too short")
+    );
+    module.push(0);
+    assert_eq!(
+      cipher.decrypt_gcm(&[], &module).err().unwrap(),
+      corrupt_err!("Encrypted module length 33 does not match its size 34")
+    );
+    module.pop();
+    module[20] ^= 1;
+    assert_eq!(
+      cipher.decrypt_gcm(&[], &module).err().unwrap(),
+      general_err!("Failed to decrypt module, key is wrong or module is corrupt")
+    );
+  }
+
+  #[test]
+  fn test_module_aad() {
+    let file_aad = [1, 2];
+    assert_eq!(footer_aad(&file_aad), vec![1, 2, 0]);
+    assert_eq!(
+      module_aad(&file_aad, ModuleType::COLUMN_META_DATA, 3, 258, 5).unwrap(),
+      vec![1, 2, 1, 3, 0, 2, 1]
+    );
+    assert_eq!(
+      module_aad(&file_aad, ModuleType::DATA_PAGE_HEADER, 0, 1, 7).unwrap(),
+      vec![1, 2, 4, 0, 0, 1, 0, 7, 0]
+    );
+    assert_eq!(
+      module_aad(&file_aad, ModuleType::DICTIONARY_PAGE, 0, 1, 7).unwrap(),
+      vec![1, 2, 3, 0, 0, 1, 0]
+    );
+    assert_eq!(
+      module_aad(&file_aad, ModuleType::DATA_PAGE, 0, 0, 32768).err().unwrap(),
+      general_err!("Encrypted files cannot have more than 32768 pages")
+    );
+  }
+
+  #[test]
+  fn test_column_cipher() {
+    let file_aad = b"file aad";
+    let gcm = ColumnCipher::new(&[1; 16], EncryptionAlgorithm::AES_GCM_V1, file_aad, 1, 2)
+      .unwrap();
+    let module = gcm.encrypt(ModuleType::DATA_PAGE, 3, b"page").unwrap();
+    assert_eq!(module.len(), 4 + 12 + 4 + 16);
+    assert_eq!(gcm.decrypt(ModuleType::DATA_PAGE, 3, &module).unwrap(), b"page");
+    // AAD of a different page, module type or column
+    assert!(gcm.decrypt(ModuleType::DATA_PAGE, 4, &module).is_err());
+    assert!(gcm.decrypt(ModuleType::DICTIONARY_PAGE, 3, &module).is_err());
+    let other =
+      ColumnCipher::new(&[1; 16], EncryptionAlgorithm::AES_GCM_V1, file_aad, 1, 3)
+        .unwrap();
+    assert!(other.decrypt(ModuleType::DATA_PAGE, 3, &module).is_err());
+
+    let ctr =
+      ColumnCipher::new(&[1; 16], EncryptionAlgorithm::AES_GCM_CTR_V1, file_aad, 1, 2)
+        .unwrap();
+    let module = ctr.encrypt(ModuleType::DICTIONARY_PAGE, 0, b"page").unwrap();
+    assert_eq!(module.len(), 4 + 12 + 4);
+    assert_eq!(ctr.decrypt(ModuleType::DICTIONARY_PAGE, 0, &module).unwrap(), b"page");
+    let module = ctr.encrypt(ModuleType::DATA_PAGE_HEADER, 0, b"header").unwrap();
+    assert_eq!(module.len(), 4 + 12 + 6 + 16);
+    assert_eq!(gcm.decrypt(ModuleType::DATA_PAGE_HEADER, 0, &module).unwrap(), b"header");
+  }
+
+  #[test]
+  fn test_read_module() {
+    let cipher = AesCipher::new(&[0; 16]).unwrap();
+    let module = cipher.encrypt_gcm(&[], b"value").unwrap();
+    let mut data = module.clone();
+    data.extend_from_slice(b"rest");
+    let mut cursor = Cursor::new(&data[..]);
+    assert_eq!(read_module(&mut cursor, 100).unwrap(), module);
+    assert_eq!(cursor.position(), module.len() as u64);
+    assert_eq!(
+      read_module(&mut Cursor::new(&data[..]), 10).err().unwrap(),
+      corrupt_err!("Length of encrypted module 33 exceeds limit 10")
+    );
+  }
+}
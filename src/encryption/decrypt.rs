@@ -0,0 +1,489 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains properties to read encrypted files, see
+//! [`SerializedFileReader::new_with_decryption`](
+//! ../../file/reader/struct.SerializedFileReader.html#method.new_with_decryption).
+//!
+//! Keys are either set explicitly with [`FileDecryptionPropertiesBuilder`], or
+//! retrieved by [`KeyRetriever`] from key metadata stored in the file, e.g. with
+//! [`kms`](../kms/index.html) key tools. Explicit keys take precedence.
+//!
+//! [`FileDecryptionPropertiesBuilder`]: struct.FileDecryptionPropertiesBuilder.html
+//! [`KeyRetriever`]: trait.KeyRetriever.html
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use encryption::ciphers::{
+  check_key, footer_aad, AesCipher, ColumnCipher, NONCE_LEN, SIZE_LEN, TAG_LEN
+};
+use encryption::metadata::{AlgorithmMetaData, ColumnCryptoMetaData};
+use errors::{ParquetError, Result};
+use file::metadata::ColumnChunkMetaData;
+use schema::types::ColumnPath;
+
+/// Retrieves data keys of an encrypted file from their key metadata, e.g. by unwrapping
+/// key material with master keys of a KMS.
+pub trait KeyRetriever {
+  /// Returns key of 16, 24 or 32 bytes for `key_metadata` of the footer or of a column.
+  fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Properties to decrypt a file written with Parquet modular encryption.
+#[derive(Clone)]
+pub struct FileDecryptionProperties {
+  footer_key: Option<Vec<u8>>,
+  column_keys: HashMap<ColumnPath, Vec<u8>>,
+  key_retriever: Option<Rc<KeyRetriever>>,
+  aad_prefix: Option<Vec<u8>>,
+  footer_signature_verification: bool
+}
+
+impl FileDecryptionProperties {
+  /// Returns builder for decryption properties.
+  pub fn builder() -> FileDecryptionPropertiesBuilder {
+    FileDecryptionPropertiesBuilder {
+      footer_key: None,
+      column_keys: HashMap::new(),
+      key_retriever: None,
+      aad_prefix: None,
+      footer_signature_verification: true
+    }
+  }
+
+  /// Returns AAD prefix supplied by the reader, if any.
+  pub fn aad_prefix(&self) -> Option<&[u8]> {
+    self.aad_prefix.as_ref().map(|prefix| &prefix[..])
+  }
+
+  /// Returns `true` if signature of plaintext footer is verified.
+  pub fn footer_signature_verification(&self) -> bool {
+    self.footer_signature_verification
+  }
+}
+
+impl fmt::Debug for FileDecryptionProperties {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    // Keys are never printed
+    f.debug_struct("FileDecryptionProperties")
+      .field("footer_key", &self.footer_key.as_ref().map(|_| "<redacted>"))
+      .field("columns", &self.column_keys.keys().collect::<Vec<_>>())
+      .field("key_retriever", &self.key_retriever.is_some())
+      .field("aad_prefix", &self.aad_prefix)
+      .field("footer_signature_verification", &self.footer_signature_verification)
+      .finish()
+  }
+}
+
+/// Builder of [`FileDecryptionProperties`](struct.FileDecryptionProperties.html).
+///
+/// # Example
+///
+/// ```rust
+/// use parquet::encryption::decrypt::FileDecryptionProperties;
+/// use parquet::schema::types::ColumnPath;
+///
+/// let properties = FileDecryptionProperties::builder()
+///   .set_footer_key(b"0123456789012345".to_vec())
+///   .set_column_key(ColumnPath::from("double_field"), b"1234567890123450".to_vec())
+///   .build()
+///   .unwrap();
+/// assert!(properties.footer_signature_verification());
+/// ```
+pub struct FileDecryptionPropertiesBuilder {
+  footer_key: Option<Vec<u8>>,
+  column_keys: HashMap<ColumnPath, Vec<u8>>,
+  key_retriever: Option<Rc<KeyRetriever>>,
+  aad_prefix: Option<Vec<u8>>,
+  footer_signature_verification: bool
+}
+
+impl FileDecryptionPropertiesBuilder {
+  /// Sets key to decrypt the footer and columns encrypted with the footer key.
+  pub fn set_footer_key(mut self, key: Vec<u8>) -> Self {
+    self.footer_key = Some(key);
+    self
+  }
+
+  /// Sets key to decrypt column `path` encrypted with its own key.
+  pub fn set_column_key(mut self, path: ColumnPath, key: Vec<u8>) -> Self {
+    self.column_keys.insert(path, key);
+    self
+  }
+
+  /// Sets retriever of keys that are not set explicitly.
+  pub fn set_key_retriever(mut self, retriever: Rc<KeyRetriever>) -> Self {
+    self.key_retriever = Some(retriever);
+    self
+  }
+
+  /// Sets AAD prefix, which is required for files written without storing the prefix,
+  /// and is checked against the stored prefix otherwise.
+  pub fn set_aad_prefix(mut self, aad_prefix: Vec<u8>) -> Self {
+    self.aad_prefix = Some(aad_prefix);
+    self
+  }
+
+  /// Sets whether or not signature of plaintext footer is verified, `true` by default.
+  /// Verification requires the footer key.
+  pub fn set_footer_signature_verification(mut self, value: bool) -> Self {
+    self.footer_signature_verification = value;
+    self
+  }
+
+  /// Finalizes the configuration and returns decryption properties. Returns error if
+  /// length of a key is invalid.
+  pub fn build(self) -> Result<FileDecryptionProperties> {
+    if let Some(ref key) = self.footer_key {
+      check_key(key)?;
+    }
+    for (path, key) in &self.column_keys {
+      check_key(key).map_err(|_| {
+        general_err!(
+          "Invalid key length {} of column {}, expected 16, 24 or 32 bytes",
+          key.len(),
+          path.string()
+        )
+      })?;
+    }
+    Ok(FileDecryptionProperties {
+      footer_key: self.footer_key,
+      column_keys: self.column_keys,
+      key_retriever: self.key_retriever,
+      aad_prefix: self.aad_prefix,
+      footer_signature_verification: self.footer_signature_verification
+    })
+  }
+}
+
+/// Decryptor of a file, created from decryption properties and encryption algorithm
+/// of the file.
+pub(crate) struct FileDecryptor {
+  properties: FileDecryptionProperties,
+  algorithm: AlgorithmMetaData,
+  file_aad: Vec<u8>,
+  footer_key_metadata: Option<Vec<u8>>,
+  // Keys returned by key retriever, keyed by key metadata
+  retrieved_keys: RefCell<HashMap<Vec<u8>, Vec<u8>>>
+}
+
+impl FileDecryptor {
+  /// Creates decryptor of a file with `algorithm`, where footer key is identified by
+  /// `footer_key_metadata`, if any. Returns error if AAD prefix is required but not
+  /// supplied, or does not match the prefix stored in the file.
+  pub(crate) fn new(
+    properties: &FileDecryptionProperties,
+    algorithm: AlgorithmMetaData,
+    footer_key_metadata: Option<Vec<u8>>
+  ) -> Result<Self> {
+    let aad_prefix = match (&properties.aad_prefix, &algorithm.aad_prefix) {
+      (Some(supplied), Some(stored)) if supplied != stored => {
+        return Err(general_err!("AAD prefix does not match the prefix stored in file"));
+      },
+      (Some(prefix), _) | (None, Some(prefix)) => prefix.clone(),
+      (None, None) if algorithm.supply_aad_prefix => {
+        return Err(general_err!("AAD prefix is not stored in file and must be supplied"));
+      },
+      (None, None) => vec![]
+    };
+    let mut file_aad = aad_prefix;
+    if let Some(ref aad_file_unique) = algorithm.aad_file_unique {
+      file_aad.extend_from_slice(aad_file_unique);
+    }
+    Ok(Self {
+      properties: properties.clone(),
+      algorithm: algorithm,
+      file_aad: file_aad,
+      footer_key_metadata: footer_key_metadata,
+      retrieved_keys: RefCell::new(HashMap::new())
+    })
+  }
+
+  /// Returns key of the footer.
+  pub(crate) fn footer_key(&self) -> Result<Vec<u8>> {
+    if let Some(ref key) = self.properties.footer_key {
+      return Ok(key.clone());
+    }
+    match self.footer_key_metadata {
+      Some(ref key_metadata) => self.retrieve_key(key_metadata),
+      None => Err(general_err!("Footer key is not set"))
+    }
+  }
+
+  /// Decrypts encrypted footer `module`.
+  pub(crate) fn decrypt_footer(&self, module: &[u8]) -> Result<Vec<u8>> {
+    let cipher = AesCipher::new(&self.footer_key()?)?;
+    cipher.decrypt_gcm(&footer_aad(&self.file_aad), module)
+      .map_err(|e| general_err!("Could not decrypt footer: {}", e.message()))
+  }
+
+  /// Verifies `signature` of plaintext `footer`, i.e. nonce and tag of the footer
+  /// encrypted with the footer key, if verification is enabled.
+  pub(crate) fn verify_footer_signature(
+    &self,
+    footer: &[u8],
+    signature: &[u8]
+  ) -> Result<()> {
+    if !self.properties.footer_signature_verification {
+      return Ok(());
+    }
+    if signature.len() != NONCE_LEN + TAG_LEN {
+      return Err(corrupt_err!("Invalid length of footer signature {}", signature.len()));
+    }
+    let cipher = AesCipher::new(&self.footer_key()?)?;
+    let module = cipher.encrypt_gcm_with_nonce(
+      &signature[..NONCE_LEN], &footer_aad(&self.file_aad), footer)?;
+    if module[module.len() - TAG_LEN..] != signature[NONCE_LEN..] ||
+        module[SIZE_LEN..SIZE_LEN + NONCE_LEN] != signature[..NONCE_LEN] {
+      return Err(general_err!("Footer signature verification failed"));
+    }
+    Ok(())
+  }
+
+  /// Returns cipher of column `column` of row group `row_group` encrypted as described
+  /// by `crypto_metadata`.
+  pub(crate) fn column_cipher(
+    &self,
+    crypto_metadata: &ColumnCryptoMetaData,
+    row_group: usize,
+    column: usize
+  ) -> Result<ColumnCipher> {
+    let key = match *crypto_metadata {
+      ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY => self.footer_key()?,
+      ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(ref path, ref key_metadata) => {
+        match (self.properties.column_keys.get(path), key_metadata) {
+          (Some(key), _) => key.clone(),
+          (None, Some(key_metadata)) => self.retrieve_key(key_metadata)?,
+          (None, None) => {
+            let path = path.string();
+            return Err(general_err!("Key of encrypted column {} is not set", path));
+          }
+        }
+      }
+    };
+    ColumnCipher::new(&key, self.algorithm.algorithm, &self.file_aad, row_group, column)
+  }
+
+  /// Retrieves key of `key_metadata` with key retriever, keys are retrieved once.
+  fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>> {
+    if let Some(key) = self.retrieved_keys.borrow().get(key_metadata) {
+      return Ok(key.clone());
+    }
+    let key = match self.properties.key_retriever {
+      Some(ref retriever) => retriever.retrieve_key(key_metadata)?,
+      None => return Err(general_err!("Key is not set and key retriever is missing"))
+    };
+    check_key(&key)?;
+    self.retrieved_keys.borrow_mut().insert(key_metadata.to_vec(), key.clone());
+    Ok(key)
+  }
+}
+
+/// Returns cipher of `column` with ordinal `j` in row group `i`, or `None` if column
+/// chunk is not encrypted. Returns error if column chunk is encrypted, but `decryptor`
+/// is not available.
+pub(crate) fn column_cipher(
+  decryptor: Option<&FileDecryptor>,
+  column: &ColumnChunkMetaData,
+  i: usize,
+  j: usize
+) -> Result<Option<ColumnCipher>> {
+  match (column.crypto_metadata(), decryptor) {
+    (Some(crypto_metadata), Some(decryptor)) => {
+      decryptor.column_cipher(crypto_metadata, i, j).map(Some)
+    },
+    (Some(_), None) => Err(general_err!(
+      "Column {} is encrypted, decryption properties are required to read it",
+      column.column_path().string()
+    )),
+    (None, _) => Ok(None)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::cell::Cell;
+
+  use encryption::ciphers::ModuleType;
+  use encryption::metadata::EncryptionAlgorithm;
+
+  struct MockRetriever {
+    calls: Cell<usize>
+  }
+
+  impl KeyRetriever for MockRetriever {
+    fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>> {
+      self.calls.set(self.calls.get() + 1);
+      match key_metadata {
+        b"footer" => Ok(vec![1; 16]),
+        b"column" => Ok(vec![2; 16]),
+        b"short" => Ok(vec![3; 10]),
+        _ => Err(general_err!("Unknown key"))
+      }
+    }
+  }
+
+  fn algorithm(aad_prefix: Option<&[u8]>, supply_aad_prefix: bool) -> AlgorithmMetaData {
+    AlgorithmMetaData {
+      algorithm: EncryptionAlgorithm::AES_GCM_V1,
+      aad_prefix: aad_prefix.map(|prefix| prefix.to_vec()),
+      aad_file_unique: Some(b"unique".to_vec()),
+      supply_aad_prefix: supply_aad_prefix
+    }
+  }
+
+  #[test]
+  fn test_decryption_properties_invalid_key() {
+    let res = FileDecryptionProperties::builder().set_footer_key(vec![0; 15]).build();
+    assert_eq!(
+      res.err().unwrap(),
+      general_err!("Invalid key length 15, expected 16, 24 or 32 bytes")
+    );
+    let res = FileDecryptionProperties::builder()
+      .set_column_key(ColumnPath::from("a"), vec![0; 8])
+      .build();
+    assert_eq!(
+      res.err().unwrap(),
+      general_err!(
+        "Invalid key length 8 of column a, expected 16, 24 or 32 bytes")
+    );
+  }
+
+  #[test]
+  fn test_decryption_properties_debug_redacts_keys() {
+    let properties = FileDecryptionProperties::builder()
+      .set_footer_key(b"0123456789012345".to_vec())
+      .build()
+      .unwrap();
+    let debug = format!("{:?}", properties);
+    assert!(debug.contains("<redacted>"));
+    assert!(!debug.contains("48, 49"));
+  }
+
+  #[test]
+  fn test_file_decryptor_aad_prefix() {
+    let supplied = FileDecryptionProperties::builder()
+      .set_footer_key(vec![1; 16])
+      .set_aad_prefix(b"prefix".to_vec())
+      .build()
+      .unwrap();
+    let missing = FileDecryptionProperties::builder()
+      .set_footer_key(vec![1; 16])
+      .build()
+      .unwrap();
+
+    let decryptor = FileDecryptor::new(&supplied, algorithm(None, true), None).unwrap();
+    assert_eq!(decryptor.file_aad, b"prefixunique");
+    let decryptor =
+      FileDecryptor::new(&missing, algorithm(Some(b"prefix"), false), None).unwrap();
+    assert_eq!(decryptor.file_aad, b"prefixunique");
+    let decryptor =
+      FileDecryptor::new(&supplied, algorithm(Some(b"prefix"), false), None);
+    assert!(decryptor.is_ok());
+
+    assert_eq!(
+      FileDecryptor::new(&missing, algorithm(None, true), None).err().unwrap(),
+      general_err!("AAD prefix is not stored in file and must be supplied")
+    );
+    assert_eq!(
+      FileDecryptor::new(&supplied, algorithm(Some(b"other"), false), None)
+        .err()
+        .unwrap(),
+      general_err!("AAD prefix does not match the prefix stored in file")
+    );
+  }
+
+  #[test]
+  fn test_file_decryptor_keys() {
+    let retriever = Rc::new(MockRetriever { calls: Cell::new(0) });
+    let properties = FileDecryptionProperties::builder()
+      .set_column_key(ColumnPath::from("a"), vec![4; 16])
+      .set_key_retriever(retriever.clone())
+      .build()
+      .unwrap();
+    let decryptor =
+      FileDecryptor::new(&properties, algorithm(None, false), Some(b"footer".to_vec()))
+        .unwrap();
+    assert_eq!(decryptor.footer_key().unwrap(), vec![1; 16]);
+    assert_eq!(decryptor.footer_key().unwrap(), vec![1; 16]);
+    assert_eq!(retriever.calls.get(), 1);
+
+    // Explicit key takes precedence over key metadata
+    let with_key = ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(
+      ColumnPath::from("a"), Some(b"column".to_vec()));
+    let expected = ColumnCipher::new(&[4; 16], EncryptionAlgorithm::AES_GCM_V1,
+      b"unique", 0, 1).unwrap();
+    let module = expected.encrypt(ModuleType::COLUMN_META_DATA, 0, b"metadata").unwrap();
+    let cipher = decryptor.column_cipher(&with_key, 0, 1).unwrap();
+    assert_eq!(cipher.decrypt(ModuleType::COLUMN_META_DATA, 0, &module).unwrap(),
+      b"metadata");
+    assert_eq!(retriever.calls.get(), 1);
+
+    let retrieved = ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(
+      ColumnPath::from("b"), Some(b"column".to_vec()));
+    assert!(decryptor.column_cipher(&retrieved, 0, 1).is_ok());
+    assert_eq!(retriever.calls.get(), 2);
+
+    let missing = ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(
+      ColumnPath::from("b"), None);
+    assert_eq!(
+      decryptor.column_cipher(&missing, 0, 1).err().unwrap(),
+      general_err!("Key of encrypted column b is not set")
+    );
+    let short = ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(
+      ColumnPath::from("b"), Some(b"short".to_vec()));
+    assert_eq!(
+      decryptor.column_cipher(&short, 0, 1).err().unwrap(),
+      general_err!("Invalid key length 10, expected 16, 24 or 32 bytes")
+    );
+  }
+
+  #[test]
+  fn test_file_decryptor_footer_signature() {
+    let properties = FileDecryptionProperties::builder()
+      .set_footer_key(vec![1; 16])
+      .build()
+      .unwrap();
+    let decryptor =
+      FileDecryptor::new(&properties, algorithm(None, false), None).unwrap();
+    let module = AesCipher::new(&[1; 16]).unwrap()
+      .encrypt_gcm(&footer_aad(b"unique"), b"footer")
+      .unwrap();
+    let mut signature = module[4..16].to_vec();
+    signature.extend_from_slice(&module[module.len() - 16..]);
+    assert!(decryptor.verify_footer_signature(b"footer", &signature).is_ok());
+    assert_eq!(
+      decryptor.verify_footer_signature(b"footes", &signature).err().unwrap(),
+      general_err!("Footer signature verification failed")
+    );
+    assert_eq!(decryptor.decrypt_footer(&module).unwrap(), b"footer");
+
+    let properties = FileDecryptionProperties::builder()
+      .set_footer_signature_verification(false)
+      .build()
+      .unwrap();
+    let decryptor =
+      FileDecryptor::new(&properties, algorithm(None, false), None).unwrap();
+    assert!(decryptor.verify_footer_signature(b"footes", &signature).is_ok());
+  }
+}
@@ -0,0 +1,469 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains properties to write encrypted files, which are set with
+//! `set_file_encryption_properties` of [`WriterPropertiesBuilder`](
+//! ../../file/properties/struct.WriterPropertiesBuilder.html).
+//!
+//! Either all columns are encrypted with the footer key, or only columns with keys set
+//! with [`FileEncryptionPropertiesBuilder::set_column_key`] are encrypted, each with its
+//! own key, while other columns are written in plaintext.
+//!
+//! [`FileEncryptionPropertiesBuilder::set_column_key`]:
+//! struct.FileEncryptionPropertiesBuilder.html#method.set_column_key
+
+use std::collections::HashMap;
+use std::fmt;
+
+use encryption::ciphers::{
+  check_key, footer_aad, random_bytes, AesCipher, ColumnCipher, NONCE_LEN, SIZE_LEN,
+  TAG_LEN
+};
+use encryption::metadata::{
+  AlgorithmMetaData, ColumnCryptoMetaData, EncryptionAlgorithm, FileCryptoMetaData
+};
+use errors::{ParquetError, Result};
+use schema::types::{ColumnPath, SchemaDescriptor};
+
+/// Length of the unique part of file AAD in bytes, generated for each file.
+const AAD_FILE_UNIQUE_LEN: usize = 8;
+
+/// Properties to write a file with Parquet modular encryption.
+#[derive(Clone)]
+pub struct FileEncryptionProperties {
+  footer_key: Vec<u8>,
+  footer_key_metadata: Option<Vec<u8>>,
+  // Keys of columns encrypted with their own keys and metadata to retrieve them
+  column_keys: HashMap<ColumnPath, (Vec<u8>, Option<Vec<u8>>)>,
+  algorithm: EncryptionAlgorithm,
+  aad_prefix: Option<Vec<u8>>,
+  store_aad_prefix: bool,
+  plaintext_footer: bool
+}
+
+impl FileEncryptionProperties {
+  /// Returns builder for encryption properties with `footer_key` of 16, 24 or 32
+  /// bytes, which encrypts the footer and, unless column keys are set, all columns.
+  pub fn builder(footer_key: Vec<u8>) -> FileEncryptionPropertiesBuilder {
+    FileEncryptionPropertiesBuilder {
+      footer_key: footer_key,
+      footer_key_metadata: None,
+      column_keys: HashMap::new(),
+      algorithm: EncryptionAlgorithm::AES_GCM_V1,
+      aad_prefix: None,
+      store_aad_prefix: true,
+      plaintext_footer: false
+    }
+  }
+
+  /// Returns encryption algorithm.
+  pub fn algorithm(&self) -> EncryptionAlgorithm {
+    self.algorithm
+  }
+
+  /// Returns `true` if footer is written in plaintext and signed with the footer key,
+  /// so that readers without keys can read metadata and plaintext columns.
+  pub fn plaintext_footer(&self) -> bool {
+    self.plaintext_footer
+  }
+
+  /// Returns AAD prefix, if any.
+  pub fn aad_prefix(&self) -> Option<&[u8]> {
+    self.aad_prefix.as_ref().map(|prefix| &prefix[..])
+  }
+
+  /// Returns `true` if AAD prefix is stored in the file, otherwise readers must supply
+  /// it.
+  pub fn store_aad_prefix(&self) -> bool {
+    self.store_aad_prefix
+  }
+
+  /// Returns `true` if column `path` is encrypted.
+  pub fn is_column_encrypted(&self, path: &ColumnPath) -> bool {
+    self.column_keys.is_empty() || self.column_keys.contains_key(path)
+  }
+}
+
+impl fmt::Debug for FileEncryptionProperties {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    // Keys are never printed
+    f.debug_struct("FileEncryptionProperties")
+      .field("footer_key", &"<redacted>")
+      .field("columns", &self.column_keys.keys().collect::<Vec<_>>())
+      .field("algorithm", &self.algorithm)
+      .field("aad_prefix", &self.aad_prefix)
+      .field("store_aad_prefix", &self.store_aad_prefix)
+      .field("plaintext_footer", &self.plaintext_footer)
+      .finish()
+  }
+}
+
+/// Builder of [`FileEncryptionProperties`](struct.FileEncryptionProperties.html).
+///
+/// # Example
+///
+/// ```rust
+/// use parquet::encryption::encrypt::FileEncryptionProperties;
+/// use parquet::encryption::metadata::EncryptionAlgorithm;
+/// use parquet::schema::types::ColumnPath;
+///
+/// let properties = FileEncryptionProperties::builder(b"0123456789012345".to_vec())
+///   .set_column_key(ColumnPath::from("double_field"), b"1234567890123450".to_vec())
+///   .set_algorithm(EncryptionAlgorithm::AES_GCM_CTR_V1)
+///   .build()
+///   .unwrap();
+/// assert!(properties.is_column_encrypted(&ColumnPath::from("double_field")));
+/// assert!(!properties.is_column_encrypted(&ColumnPath::from("int32_field")));
+/// ```
+pub struct FileEncryptionPropertiesBuilder {
+  footer_key: Vec<u8>,
+  footer_key_metadata: Option<Vec<u8>>,
+  column_keys: HashMap<ColumnPath, (Vec<u8>, Option<Vec<u8>>)>,
+  algorithm: EncryptionAlgorithm,
+  aad_prefix: Option<Vec<u8>>,
+  store_aad_prefix: bool,
+  plaintext_footer: bool
+}
+
+impl FileEncryptionPropertiesBuilder {
+  /// Sets metadata stored in the file to retrieve the footer key when the file is
+  /// read, e.g. key material of [`kms`](../kms/index.html) key tools.
+  pub fn set_footer_key_metadata(mut self, key_metadata: Vec<u8>) -> Self {
+    self.footer_key_metadata = Some(key_metadata);
+    self
+  }
+
+  /// Sets key to encrypt column `path` with. Once any column key is set, columns
+  /// without keys are not encrypted.
+  pub fn set_column_key(mut self, path: ColumnPath, key: Vec<u8>) -> Self {
+    self.column_keys.insert(path, (key, None));
+    self
+  }
+
+  /// Sets key to encrypt column `path` with, and metadata stored in the file to
+  /// retrieve the key when the file is read.
+  pub fn set_column_key_with_metadata(
+    mut self,
+    path: ColumnPath,
+    key: Vec<u8>,
+    key_metadata: Vec<u8>
+  ) -> Self {
+    self.column_keys.insert(path, (key, Some(key_metadata)));
+    self
+  }
+
+  /// Sets encryption algorithm, `AES_GCM_V1` by default.
+  pub fn set_algorithm(mut self, algorithm: EncryptionAlgorithm) -> Self {
+    self.algorithm = algorithm;
+    self
+  }
+
+  /// Sets AAD prefix, e.g. name of the file, that protects against swapping of files.
+  pub fn set_aad_prefix(mut self, aad_prefix: Vec<u8>) -> Self {
+    self.aad_prefix = Some(aad_prefix);
+    self
+  }
+
+  /// Sets whether or not AAD prefix is stored in the file, `true` by default. If it is
+  /// not stored, readers must supply it.
+  pub fn set_store_aad_prefix(mut self, value: bool) -> Self {
+    self.store_aad_prefix = value;
+    self
+  }
+
+  /// Sets whether or not footer is written in plaintext, `false` by default. Plaintext
+  /// footer is signed with the footer key, and metadata of encrypted columns is
+  /// encrypted separately.
+  pub fn set_plaintext_footer(mut self, value: bool) -> Self {
+    self.plaintext_footer = value;
+    self
+  }
+
+  /// Finalizes the configuration and returns encryption properties. Returns error if
+  /// length of a key is invalid.
+  pub fn build(self) -> Result<FileEncryptionProperties> {
+    check_key(&self.footer_key)?;
+    for (path, &(ref key, _)) in &self.column_keys {
+      check_key(key).map_err(|_| {
+        general_err!(
+          "Invalid key length {} of column {}, expected 16, 24 or 32 bytes",
+          key.len(),
+          path.string()
+        )
+      })?;
+    }
+    Ok(FileEncryptionProperties {
+      footer_key: self.footer_key,
+      footer_key_metadata: self.footer_key_metadata,
+      column_keys: self.column_keys,
+      algorithm: self.algorithm,
+      aad_prefix: self.aad_prefix,
+      store_aad_prefix: self.store_aad_prefix,
+      plaintext_footer: self.plaintext_footer
+    })
+  }
+}
+
+/// Encryptor of a file, created from encryption properties with a unique file AAD.
+pub(crate) struct FileEncryptor {
+  properties: FileEncryptionProperties,
+  algorithm: AlgorithmMetaData,
+  file_aad: Vec<u8>
+}
+
+impl FileEncryptor {
+  /// Creates encryptor of a file with `schema`. Returns error if a column key is set
+  /// for a column that is not in the schema.
+  pub(crate) fn new(
+    properties: &FileEncryptionProperties,
+    schema: &SchemaDescriptor
+  ) -> Result<Self> {
+    for path in properties.column_keys.keys() {
+      if !schema.columns().iter().any(|column| column.path() == path) {
+        return Err(general_err!("Encrypted column {} is not in schema", path.string()));
+      }
+    }
+    let aad_file_unique = random_bytes(AAD_FILE_UNIQUE_LEN);
+    let aad_prefix = properties.aad_prefix.clone();
+    let mut file_aad = aad_prefix.clone().unwrap_or_default();
+    file_aad.extend_from_slice(&aad_file_unique);
+    let algorithm = AlgorithmMetaData {
+      algorithm: properties.algorithm,
+      supply_aad_prefix: aad_prefix.is_some() && !properties.store_aad_prefix,
+      aad_prefix: aad_prefix.filter(|_| properties.store_aad_prefix),
+      aad_file_unique: Some(aad_file_unique)
+    };
+    Ok(Self { properties: properties.clone(), algorithm: algorithm, file_aad: file_aad })
+  }
+
+  /// Returns `true` if footer is encrypted.
+  pub(crate) fn is_footer_encrypted(&self) -> bool {
+    !self.properties.plaintext_footer
+  }
+
+  /// Returns encryption algorithm of the file, stored in the footer.
+  pub(crate) fn algorithm(&self) -> &AlgorithmMetaData {
+    &self.algorithm
+  }
+
+  /// Returns metadata to retrieve the footer key, if any.
+  pub(crate) fn footer_key_metadata(&self) -> Option<&[u8]> {
+    self.properties.footer_key_metadata.as_ref().map(|metadata| &metadata[..])
+  }
+
+  /// Returns crypto metadata stored before encrypted footer.
+  pub(crate) fn file_crypto_metadata(&self) -> FileCryptoMetaData {
+    FileCryptoMetaData {
+      algorithm: self.algorithm.clone(),
+      key_metadata: self.properties.footer_key_metadata.clone()
+    }
+  }
+
+  /// Returns crypto metadata of column `path`, or `None` if column is not encrypted.
+  pub(crate) fn column_crypto_metadata(
+    &self,
+    path: &ColumnPath
+  ) -> Option<ColumnCryptoMetaData> {
+    if self.properties.column_keys.is_empty() {
+      return Some(ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY);
+    }
+    self.properties.column_keys.get(path).map(|&(_, ref key_metadata)| {
+      ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(path.clone(), key_metadata.clone())
+    })
+  }
+
+  /// Returns cipher of column `path` with ordinal `column` in row group `row_group`, or
+  /// `None` if column is not encrypted.
+  pub(crate) fn column_cipher(
+    &self,
+    path: &ColumnPath,
+    row_group: usize,
+    column: usize
+  ) -> Result<Option<ColumnCipher>> {
+    let key = if self.properties.column_keys.is_empty() {
+      &self.properties.footer_key
+    } else {
+      match self.properties.column_keys.get(path) {
+        Some(&(ref key, _)) => key,
+        None => return Ok(None)
+      }
+    };
+    ColumnCipher::new(key, self.properties.algorithm, &self.file_aad, row_group, column)
+      .map(Some)
+  }
+
+  /// Encrypts serialized `footer`, returns encrypted module.
+  pub(crate) fn encrypt_footer(&self, footer: &[u8]) -> Result<Vec<u8>> {
+    let cipher = AesCipher::new(&self.properties.footer_key)?;
+    cipher.encrypt_gcm(&footer_aad(&self.file_aad), footer)
+  }
+
+  /// Returns signature of serialized plaintext `footer`, i.e. nonce and tag of the
+  /// footer encrypted with the footer key.
+  pub(crate) fn sign_footer(&self, footer: &[u8]) -> Result<Vec<u8>> {
+    let module = self.encrypt_footer(footer)?;
+    let mut signature = module[SIZE_LEN..SIZE_LEN + NONCE_LEN].to_vec();
+    signature.extend_from_slice(&module[module.len() - TAG_LEN..]);
+    Ok(signature)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::rc::Rc;
+
+  use encryption::ciphers::ModuleType;
+  use schema::parser::parse_message_type;
+
+  fn schema() -> SchemaDescriptor {
+    let schema = "message schema { REQUIRED INT32 a; REQUIRED DOUBLE b; }";
+    SchemaDescriptor::new(Rc::new(parse_message_type(schema).unwrap()))
+  }
+
+  #[test]
+  fn test_encryption_properties_invalid_keys() {
+    let result = FileEncryptionProperties::builder(vec![0; 15]).build();
+    assert!(result.is_err());
+
+    let result = FileEncryptionProperties::builder(vec![0; 16])
+      .set_column_key(ColumnPath::from("a"), vec![0; 20])
+      .build();
+    assert_eq!(
+      result.unwrap_err().to_string(),
+      "Parquet error: Invalid key length 20 of column a, expected 16, 24 or 32 bytes"
+    );
+  }
+
+  #[test]
+  fn test_encryption_properties_debug_redacts_keys() {
+    let properties = FileEncryptionProperties::builder(b"0123456789012345".to_vec())
+      .set_column_key(ColumnPath::from("a"), b"1234567890123450".to_vec())
+      .build()
+      .unwrap();
+    let debug = format!("{:?}", properties);
+    assert!(!debug.contains("0123456789012345"));
+    assert!(!debug.contains("1234567890123450"));
+  }
+
+  #[test]
+  fn test_file_encryptor_column_not_in_schema() {
+    let properties = FileEncryptionProperties::builder(vec![0; 16])
+      .set_column_key(ColumnPath::from("c"), vec![1; 16])
+      .build()
+      .unwrap();
+    let result = FileEncryptor::new(&properties, &schema());
+    assert_eq!(
+      result.err().unwrap().to_string(),
+      "Parquet error: Encrypted column c is not in schema"
+    );
+  }
+
+  #[test]
+  fn test_file_encryptor_uniform_encryption() {
+    let properties = FileEncryptionProperties::builder(vec![0; 16]).build().unwrap();
+    let encryptor = FileEncryptor::new(&properties, &schema()).unwrap();
+    let path = ColumnPath::from("a");
+    assert!(properties.is_column_encrypted(&path));
+    assert_eq!(
+      encryptor.column_crypto_metadata(&path),
+      Some(ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY)
+    );
+
+    // Column modules are encrypted with the footer key
+    let cipher = encryptor.column_cipher(&path, 1, 0).unwrap().unwrap();
+    let module = cipher.encrypt(ModuleType::COLUMN_INDEX, 0, b"index").unwrap();
+    let algorithm = EncryptionAlgorithm::AES_GCM_V1;
+    let footer_cipher =
+      ColumnCipher::new(&[0; 16], algorithm, &encryptor.file_aad, 1, 0).unwrap();
+    assert_eq!(
+      footer_cipher.decrypt(ModuleType::COLUMN_INDEX, 0, &module).unwrap(),
+      b"index".to_vec()
+    );
+  }
+
+  #[test]
+  fn test_file_encryptor_column_keys() {
+    let properties = FileEncryptionProperties::builder(vec![0; 16])
+      .set_column_key_with_metadata(ColumnPath::from("b"), vec![1; 16], b"kb".to_vec())
+      .build()
+      .unwrap();
+    let encryptor = FileEncryptor::new(&properties, &schema()).unwrap();
+    let a = ColumnPath::from("a");
+    let b = ColumnPath::from("b");
+    assert!(encryptor.column_crypto_metadata(&a).is_none());
+    assert!(encryptor.column_cipher(&a, 0, 0).unwrap().is_none());
+    assert_eq!(
+      encryptor.column_crypto_metadata(&b),
+      Some(
+        ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(b.clone(), Some(b"kb".to_vec()))
+      )
+    );
+    assert!(encryptor.column_cipher(&b, 0, 1).unwrap().is_some());
+  }
+
+  #[test]
+  fn test_file_encryptor_aad_prefix() {
+    let builder = FileEncryptionProperties::builder(vec![0; 16])
+      .set_aad_prefix(b"prefix".to_vec());
+    let properties = builder.build().unwrap();
+    let encryptor = FileEncryptor::new(&properties, &schema()).unwrap();
+    assert_eq!(encryptor.algorithm().aad_prefix, Some(b"prefix".to_vec()));
+    assert!(!encryptor.algorithm().supply_aad_prefix);
+    assert!(encryptor.file_aad.starts_with(b"prefix"));
+    assert_eq!(encryptor.file_aad.len(), 6 + AAD_FILE_UNIQUE_LEN);
+
+    let properties = FileEncryptionProperties::builder(vec![0; 16])
+      .set_aad_prefix(b"prefix".to_vec())
+      .set_store_aad_prefix(false)
+      .build()
+      .unwrap();
+    let encryptor = FileEncryptor::new(&properties, &schema()).unwrap();
+    assert_eq!(encryptor.algorithm().aad_prefix, None);
+    assert!(encryptor.algorithm().supply_aad_prefix);
+
+    // Each file has its own unique AAD
+    let other = FileEncryptor::new(&properties, &schema()).unwrap();
+    assert!(encryptor.file_aad != other.file_aad);
+  }
+
+  #[test]
+  fn test_file_encryptor_footer() {
+    let properties = FileEncryptionProperties::builder(vec![0; 16])
+      .set_footer_key_metadata(b"kf".to_vec())
+      .build()
+      .unwrap();
+    let encryptor = FileEncryptor::new(&properties, &schema()).unwrap();
+    assert!(encryptor.is_footer_encrypted());
+    assert_eq!(encryptor.footer_key_metadata(), Some(&b"kf"[..]));
+    assert_eq!(encryptor.file_crypto_metadata().key_metadata, Some(b"kf".to_vec()));
+
+    let cipher = AesCipher::new(&[0; 16]).unwrap();
+    let aad = footer_aad(&encryptor.file_aad);
+    let module = encryptor.encrypt_footer(b"footer").unwrap();
+    assert_eq!(cipher.decrypt_gcm(&aad, &module).unwrap(), b"footer".to_vec());
+
+    // Signature is nonce and tag of the footer encrypted with the same nonce
+    let signature = encryptor.sign_footer(b"footer").unwrap();
+    assert_eq!(signature.len(), NONCE_LEN + TAG_LEN);
+    let module =
+      cipher.encrypt_gcm_with_nonce(&signature[..NONCE_LEN], &aad, b"footer").unwrap();
+    assert_eq!(&module[module.len() - TAG_LEN..], &signature[NONCE_LEN..]);
+  }
+}
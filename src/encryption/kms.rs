@@ -0,0 +1,612 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains envelope encryption of data encryption keys (DEK) with master keys stored
+//! in an external key management service (KMS), compatible with key tools of
+//! parquet-mr.
+//!
+//! Data keys are never stored in a file as is. Instead each key is wrapped with a master
+//! key and stored as serialized [`KeyMaterial`] in key metadata of the footer or of a
+//! column. Master keys never leave the KMS, which is accessed with [`KmsClient`].
+//!
+//! Two wrapping schemes are supported:
+//! - Single wrapping, where the KMS wraps data keys with a master key, see [`wrap_key`].
+//! - Double wrapping, where data keys are wrapped locally with a key encryption key
+//! (KEK), and only the KEK is wrapped by the KMS, see [`KeyEncryptionKey`]. This
+//! reduces number of calls to the KMS, since one KEK can be shared by all keys of a
+//! file that use the same master key. Local wrapping is done with [`LocalKeyWrapper`],
+//! usually AES-GCM with KEK identifier as additional authenticated data.
+//!
+//! Master keys can be rotated with [`rotate_master_key`], which rewraps data keys or
+//! KEKs without decrypting any data.
+//!
+//! # Example
+//!
+//! ```rust
+//! use parquet::encryption::kms::{unwrap_key, wrap_key, KeyMaterial, KmsClient};
+//! use parquet::errors::Result;
+//!
+//! /// KMS client that "wraps" keys by reversing them, for demonstration only.
+//! struct ReversingKms;
+//!
+//! impl KmsClient for ReversingKms {
+//!   fn wrap_key(&self, key: &[u8], master_key_id: &str) -> Result<String> {
+//!     let bytes: Vec<String> = key.iter().rev().map(|b| b.to_string()).collect();
+//!     Ok(format!("{}:{}", master_key_id, bytes.join(",")))
+//!   }
+//!
+//!   fn unwrap_key(&self, wrapped_key: &str, _master_key_id: &str) -> Result<Vec<u8>> {
+//!     let bytes = wrapped_key.splitn(2, ':').nth(1).unwrap();
+//!     Ok(bytes.split(',').rev().map(|b| b.parse().unwrap()).collect())
+//!   }
+//! }
+//!
+//! let dek = vec![1, 2, 3, 4];
+//! let material = wrap_key(&ReversingKms, &dek, "footer_master_key", true).unwrap();
+//! let key_metadata = material.serialize();
+//!
+//! let material = KeyMaterial::parse(&key_metadata).unwrap();
+//! assert_eq!(material.master_key_id(), "footer_master_key");
+//! assert_eq!(unwrap_key(&ReversingKms, None, &material).unwrap(), dek);
+//! ```
+//!
+//! [`KeyMaterial`]: struct.KeyMaterial.html
+//! [`KmsClient`]: trait.KmsClient.html
+//! [`wrap_key`]: fn.wrap_key.html
+//! [`KeyEncryptionKey`]: struct.KeyEncryptionKey.html
+//! [`LocalKeyWrapper`]: trait.LocalKeyWrapper.html
+//! [`rotate_master_key`]: fn.rotate_master_key.html
+
+use errors::{ParquetError, Result};
+use serde_json::{self, Map, Value};
+
+/// Type of key material written by this implementation.
+const KEY_MATERIAL_TYPE: &str = "PKMT1";
+
+/// Length in bytes of generated key encryption keys and their identifiers.
+const KEK_LENGTH: usize = 16;
+
+// ----------------------------------------------------------------------
+// KMS client and local key wrapper
+
+/// Client of a key management service that stores master keys.
+///
+/// Implementations wrap and unwrap keys with the master key `master_key_id` on the KMS
+/// side, such that the master key is never exposed to the client.
+pub trait KmsClient {
+  /// Wraps (encrypts) `key` with master key `master_key_id`, returns wrapped key in the
+  /// format of the KMS.
+  fn wrap_key(&self, key: &[u8], master_key_id: &str) -> Result<String>;
+
+  /// Unwraps (decrypts) `wrapped_key` previously returned by `wrap_key` with master
+  /// key `master_key_id`.
+  fn unwrap_key(&self, wrapped_key: &str, master_key_id: &str) -> Result<Vec<u8>>;
+}
+
+/// Local key wrapping used for double wrapping of keys.
+///
+/// Parquet-mr wraps keys with AES-GCM, where `aad` is used as additional authenticated
+/// data, implementations should do the same to read and write compatible files.
+pub trait LocalKeyWrapper {
+  /// Returns `len` random bytes from a cryptographically secure random generator.
+  fn generate_key(&self, len: usize) -> Result<Vec<u8>>;
+
+  /// Wraps `key` with key encryption key `kek`.
+  fn wrap_key(&self, key: &[u8], kek: &[u8], aad: &[u8]) -> Result<Vec<u8>>;
+
+  /// Unwraps `wrapped_key` with key encryption key `kek`.
+  fn unwrap_key(&self, wrapped_key: &[u8], kek: &[u8], aad: &[u8]) -> Result<Vec<u8>>;
+}
+
+// ----------------------------------------------------------------------
+// Key material
+
+/// Key material of a data encryption key, which is stored in key metadata of the
+/// footer or of a column chunk as JSON.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyMaterial {
+  is_footer_key: bool,
+  kms_instance_id: Option<String>,
+  kms_instance_url: Option<String>,
+  master_key_id: String,
+  wrapped_dek: String,
+  double_wrapping: bool,
+  kek_id: Option<String>,
+  wrapped_kek: Option<String>
+}
+
+impl KeyMaterial {
+  /// Sets identifier and URL of the KMS instance that stores the master key. These are
+  /// only serialized for footer keys, since all keys of a file share the same KMS.
+  pub fn with_kms_instance(mut self, id: String, url: String) -> Self {
+    self.kms_instance_id = Some(id);
+    self.kms_instance_url = Some(url);
+    self
+  }
+
+  /// Returns `true` if this is material of the footer key, `false` for column keys.
+  pub fn is_footer_key(&self) -> bool {
+    self.is_footer_key
+  }
+
+  /// Returns identifier of the KMS instance, if known.
+  pub fn kms_instance_id(&self) -> Option<&String> {
+    self.kms_instance_id.as_ref()
+  }
+
+  /// Returns URL of the KMS instance, if known.
+  pub fn kms_instance_url(&self) -> Option<&String> {
+    self.kms_instance_url.as_ref()
+  }
+
+  /// Returns identifier of the master key.
+  pub fn master_key_id(&self) -> &str {
+    &self.master_key_id
+  }
+
+  /// Returns wrapped data encryption key. With double wrapping this is base64 encoded
+  /// key wrapped with the KEK, otherwise this is the key wrapped by the KMS.
+  pub fn wrapped_dek(&self) -> &str {
+    &self.wrapped_dek
+  }
+
+  /// Returns `true` if data key is wrapped with a KEK.
+  pub fn double_wrapping(&self) -> bool {
+    self.double_wrapping
+  }
+
+  /// Returns base64 encoded identifier of the KEK, if double wrapping is used.
+  pub fn kek_id(&self) -> Option<&String> {
+    self.kek_id.as_ref()
+  }
+
+  /// Returns KEK wrapped by the KMS, if double wrapping is used.
+  pub fn wrapped_kek(&self) -> Option<&String> {
+    self.wrapped_kek.as_ref()
+  }
+
+  /// Serializes key material into JSON.
+  pub fn serialize(&self) -> String {
+    let mut json = Map::new();
+    json.insert("keyMaterialType".to_owned(), Value::from(KEY_MATERIAL_TYPE));
+    json.insert("internalStorage".to_owned(), Value::from(true));
+    json.insert("isFooterKey".to_owned(), Value::from(self.is_footer_key));
+    if self.is_footer_key {
+      if let Some(ref id) = self.kms_instance_id {
+        json.insert("kmsInstanceID".to_owned(), Value::from(id.as_str()));
+      }
+      if let Some(ref url) = self.kms_instance_url {
+        json.insert("kmsInstanceURL".to_owned(), Value::from(url.as_str()));
+      }
+    }
+    json.insert("masterKeyID".to_owned(), Value::from(self.master_key_id.as_str()));
+    json.insert("wrappedDEK".to_owned(), Value::from(self.wrapped_dek.as_str()));
+    json.insert("doubleWrapping".to_owned(), Value::from(self.double_wrapping));
+    if let (Some(kek_id), Some(wrapped_kek)) = (&self.kek_id, &self.wrapped_kek) {
+      json.insert("keyEncryptionKeyID".to_owned(), Value::from(kek_id.as_str()));
+      json.insert("wrappedKEK".to_owned(), Value::from(wrapped_kek.as_str()));
+    }
+    Value::Object(json).to_string()
+  }
+
+  /// Parses key material from JSON, returns error if JSON is invalid or required fields
+  /// are missing.
+  pub fn parse(json: &str) -> Result<Self> {
+    let value: Value = serde_json::from_str(json)
+      .map_err(|e| general_err!("Invalid key material JSON: {}", e))?;
+    match get_str(&value, "keyMaterialType")? {
+      KEY_MATERIAL_TYPE => {},
+      other => return Err(general_err!("Unsupported key material type {}", other))
+    }
+    if !get_bool(&value, "internalStorage")? {
+      return Err(nyi_err!("Key material in external storage is not supported"));
+    }
+    let is_footer_key = get_bool(&value, "isFooterKey")?;
+    let double_wrapping = get_bool(&value, "doubleWrapping")?;
+    let (kek_id, wrapped_kek) = if double_wrapping {
+      (
+        Some(get_str(&value, "keyEncryptionKeyID")?.to_owned()),
+        Some(get_str(&value, "wrappedKEK")?.to_owned())
+      )
+    } else {
+      (None, None)
+    };
+    let get_opt_str =
+      |key: &str| value.get(key).and_then(|v| v.as_str()).map(String::from);
+    Ok(KeyMaterial {
+      is_footer_key: is_footer_key,
+      kms_instance_id: get_opt_str("kmsInstanceID"),
+      kms_instance_url: get_opt_str("kmsInstanceURL"),
+      master_key_id: get_str(&value, "masterKeyID")?.to_owned(),
+      wrapped_dek: get_str(&value, "wrappedDEK")?.to_owned(),
+      double_wrapping: double_wrapping,
+      kek_id: kek_id,
+      wrapped_kek: wrapped_kek
+    })
+  }
+}
+
+/// Returns string field `key` of key material `value`.
+fn get_str<'a>(value: &'a Value, key: &str) -> Result<&'a str> {
+  value
+    .get(key)
+    .and_then(|v| v.as_str())
+    .ok_or_else(|| general_err!("Key material does not have string field {}", key))
+}
+
+/// Returns boolean field `key` of key material `value`.
+fn get_bool(value: &Value, key: &str) -> Result<bool> {
+  value
+    .get(key)
+    .and_then(|v| v.as_bool())
+    .ok_or_else(|| general_err!("Key material does not have boolean field {}", key))
+}
+
+// ----------------------------------------------------------------------
+// Key wrapping
+
+/// Key encryption key used for double wrapping of data keys with the same master key.
+///
+/// KEK is generated locally and wrapped by the KMS once, when it is created. It should
+/// be reused for all data keys of a file with the same master key.
+pub struct KeyEncryptionKey {
+  id: Vec<u8>,
+  key: Vec<u8>,
+  master_key_id: String,
+  wrapped_key: String
+}
+
+impl KeyEncryptionKey {
+  /// Generates a new KEK with `key_wrapper` and wraps it with master key
+  /// `master_key_id` using `kms`.
+  pub fn new(
+    kms: &KmsClient,
+    key_wrapper: &LocalKeyWrapper,
+    master_key_id: &str
+  ) -> Result<Self> {
+    let id = key_wrapper.generate_key(KEK_LENGTH)?;
+    let key = key_wrapper.generate_key(KEK_LENGTH)?;
+    let wrapped_key = kms.wrap_key(&key, master_key_id)?;
+    Ok(KeyEncryptionKey {
+      id: id,
+      key: key,
+      master_key_id: master_key_id.to_owned(),
+      wrapped_key: wrapped_key
+    })
+  }
+
+  /// Returns identifier of the master key that wraps this KEK.
+  pub fn master_key_id(&self) -> &str {
+    &self.master_key_id
+  }
+
+  /// Wraps data key `dek` with this KEK using `key_wrapper`, returns key material with
+  /// double wrapping.
+  pub fn wrap_key(
+    &self,
+    key_wrapper: &LocalKeyWrapper,
+    dek: &[u8],
+    is_footer_key: bool
+  ) -> Result<KeyMaterial> {
+    let wrapped_dek = key_wrapper.wrap_key(dek, &self.key, &self.id)?;
+    Ok(KeyMaterial {
+      is_footer_key: is_footer_key,
+      kms_instance_id: None,
+      kms_instance_url: None,
+      master_key_id: self.master_key_id.clone(),
+      wrapped_dek: encode_base64(&wrapped_dek),
+      double_wrapping: true,
+      kek_id: Some(encode_base64(&self.id)),
+      wrapped_kek: Some(self.wrapped_key.clone())
+    })
+  }
+}
+
+/// Wraps data key `dek` with master key `master_key_id` using `kms`, returns key
+/// material with single wrapping.
+pub fn wrap_key(
+  kms: &KmsClient,
+  dek: &[u8],
+  master_key_id: &str,
+  is_footer_key: bool
+) -> Result<KeyMaterial> {
+  Ok(KeyMaterial {
+    is_footer_key: is_footer_key,
+    kms_instance_id: None,
+    kms_instance_url: None,
+    master_key_id: master_key_id.to_owned(),
+    wrapped_dek: kms.wrap_key(dek, master_key_id)?,
+    double_wrapping: false,
+    kek_id: None,
+    wrapped_kek: None
+  })
+}
+
+/// Unwraps data key of `material` using `kms`. Key wrapper is required to unwrap keys
+/// with double wrapping.
+pub fn unwrap_key(
+  kms: &KmsClient,
+  key_wrapper: Option<&LocalKeyWrapper>,
+  material: &KeyMaterial
+) -> Result<Vec<u8>> {
+  match (&material.kek_id, &material.wrapped_kek) {
+    (Some(kek_id), Some(wrapped_kek)) => {
+      let key_wrapper = match key_wrapper {
+        Some(key_wrapper) => key_wrapper,
+        None => return Err(general_err!("Key wrapper is required for double wrapping"))
+      };
+      let kek = kms.unwrap_key(wrapped_kek, &material.master_key_id)?;
+      let wrapped_dek = decode_base64(&material.wrapped_dek)?;
+      key_wrapper.unwrap_key(&wrapped_dek, &kek, &decode_base64(kek_id)?)
+    },
+    _ => kms.unwrap_key(&material.wrapped_dek, &material.master_key_id)
+  }
+}
+
+/// Rotates master key of `material` to `new_master_key_id` using `kms`, returns key
+/// material that should replace the original one in the file.
+///
+/// With single wrapping data key is unwrapped and wrapped again with the new master
+/// key. With double wrapping only KEK is rewrapped, wrapped data key stays the same.
+pub fn rotate_master_key(
+  kms: &KmsClient,
+  material: &KeyMaterial,
+  new_master_key_id: &str
+) -> Result<KeyMaterial> {
+  let mut result = material.clone();
+  result.master_key_id = new_master_key_id.to_owned();
+  match material.wrapped_kek {
+    Some(ref wrapped_kek) => {
+      let kek = kms.unwrap_key(wrapped_kek, &material.master_key_id)?;
+      result.wrapped_kek = Some(kms.wrap_key(&kek, new_master_key_id)?);
+    },
+    None => {
+      let dek = kms.unwrap_key(&material.wrapped_dek, &material.master_key_id)?;
+      result.wrapped_dek = kms.wrap_key(&dek, new_master_key_id)?;
+    }
+  }
+  Ok(result)
+}
+
+// ----------------------------------------------------------------------
+// Base64 encoding
+
+const BASE64_CHARS: &[u8] =
+  b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes `data` with standard base64 alphabet and padding.
+fn encode_base64(data: &[u8]) -> String {
+  let mut result = String::with_capacity(data.len() * 4 / 3 + 4);
+  for chunk in data.chunks(3) {
+    let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+    let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+    for i in 0..4 {
+      if i <= chunk.len() {
+        result.push(BASE64_CHARS[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+      } else {
+        result.push('=');
+      }
+    }
+  }
+  result
+}
+
+/// Decodes base64 string `data`, padding is optional.
+fn decode_base64(data: &str) -> Result<Vec<u8>> {
+  let mut result = Vec::with_capacity(data.len() / 4 * 3);
+  let mut n: u32 = 0;
+  let mut bits = 0;
+  for c in data.trim_end_matches('=').bytes() {
+    let value = match BASE64_CHARS.iter().position(|&b| b == c) {
+      Some(value) => value as u32,
+      None => return Err(general_err!("Invalid base64 character {}", c as char))
+    };
+    n = n << 6 | value;
+    bits += 6;
+    if bits >= 8 {
+      bits -= 8;
+      result.push((n >> bits) as u8);
+      n &= (1 << bits) - 1;
+    }
+  }
+  Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::cell::Cell;
+
+  /// KMS that "wraps" keys by prefixing hex value of key with master key id.
+  struct MockKms {
+    num_calls: Cell<usize>
+  }
+
+  impl MockKms {
+    fn new() -> Self {
+      MockKms { num_calls: Cell::new(0) }
+    }
+  }
+
+  impl KmsClient for MockKms {
+    fn wrap_key(&self, key: &[u8], master_key_id: &str) -> Result<String> {
+      self.num_calls.set(self.num_calls.get() + 1);
+      let hex: Vec<String> = key.iter().map(|b| format!("{:02x}", b)).collect();
+      Ok(format!("{}:{}", master_key_id, hex.concat()))
+    }
+
+    fn unwrap_key(&self, wrapped_key: &str, master_key_id: &str) -> Result<Vec<u8>> {
+      self.num_calls.set(self.num_calls.get() + 1);
+      let prefix = format!("{}:", master_key_id);
+      if !wrapped_key.starts_with(&prefix) {
+        return Err(general_err!("Key is not wrapped with {}", master_key_id));
+      }
+      let hex = &wrapped_key[prefix.len()..];
+      Ok(
+        (0..hex.len() / 2)
+          .map(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap())
+          .collect()
+      )
+    }
+  }
+
+  /// Key wrapper that XORs key with KEK and prepends AAD.
+  struct MockKeyWrapper;
+
+  impl LocalKeyWrapper for MockKeyWrapper {
+    fn generate_key(&self, len: usize) -> Result<Vec<u8>> {
+      Ok((0..len).map(|i| (i * 7 + 3) as u8).collect())
+    }
+
+    fn wrap_key(&self, key: &[u8], kek: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+      let mut result = aad.to_vec();
+      result.extend(key.iter().zip(kek.iter().cycle()).map(|(k, e)| k ^ e));
+      Ok(result)
+    }
+
+    fn unwrap_key(&self, wrapped_key: &[u8], kek: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+      if !wrapped_key.starts_with(aad) {
+        return Err(general_err!("Invalid AAD"));
+      }
+      let key = &wrapped_key[aad.len()..];
+      Ok(key.iter().zip(kek.iter().cycle()).map(|(k, e)| k ^ e).collect())
+    }
+  }
+
+  #[test]
+  fn test_key_material_serialize_and_parse() {
+    let kms = MockKms::new();
+    let material = wrap_key(&kms, &[1, 2, 255], "mk", true)
+      .unwrap()
+      .with_kms_instance("kms-1".to_owned(), "https://kms".to_owned());
+    let json = material.serialize();
+    assert_eq!(
+      json,
+      "{\"doubleWrapping\":false,\"internalStorage\":true,\"isFooterKey\":true,\
+       \"keyMaterialType\":\"PKMT1\",\"kmsInstanceID\":\"kms-1\",\
+       \"kmsInstanceURL\":\"https://kms\",\"masterKeyID\":\"mk\",\
+       \"wrappedDEK\":\"mk:0102ff\"}"
+    );
+    assert_eq!(KeyMaterial::parse(&json).unwrap(), material);
+
+    let material = KeyEncryptionKey::new(&kms, &MockKeyWrapper, "mk2")
+      .unwrap()
+      .wrap_key(&MockKeyWrapper, &[9, 8, 7], false)
+      .unwrap();
+    let parsed = KeyMaterial::parse(&material.serialize()).unwrap();
+    assert_eq!(parsed, material);
+    assert!(!parsed.is_footer_key());
+    assert!(parsed.double_wrapping());
+    assert_eq!(parsed.master_key_id(), "mk2");
+    assert!(parsed.kek_id().is_some());
+    assert!(parsed.wrapped_kek().unwrap().starts_with("mk2:"));
+  }
+
+  #[test]
+  fn test_key_material_parse_invalid() {
+    let check_error = |json: &str, message: &str| {
+      assert_eq!(KeyMaterial::parse(json).unwrap_err().message(), message);
+    };
+    check_error("{\"keyMaterialType\":\"PKMT2\"}", "Unsupported key material type PKMT2");
+    check_error(
+      "{\"keyMaterialType\":\"PKMT1\",\"internalStorage\":false}",
+      "Key material in external storage is not supported"
+    );
+    check_error(
+      "{\"keyMaterialType\":\"PKMT1\",\"internalStorage\":true,\"isFooterKey\":false,\
+       \"masterKeyID\":\"mk\",\"wrappedDEK\":\"abc\",\"doubleWrapping\":true}",
+      "Key material does not have string field keyEncryptionKeyID"
+    );
+    check_error("{\"keyMaterialType\":1}", "Key material does not have string field \
+      keyMaterialType");
+    check_error(
+      "[", "Invalid key material JSON: EOF while parsing a list at line 1 column 1");
+  }
+
+  #[test]
+  fn test_wrap_and_unwrap_key() {
+    let kms = MockKms::new();
+    let dek = vec![10, 20, 30, 40];
+    let material = wrap_key(&kms, &dek, "mk", false).unwrap();
+    assert!(!material.double_wrapping());
+    assert_eq!(unwrap_key(&kms, None, &material).unwrap(), dek);
+  }
+
+  #[test]
+  fn test_wrap_and_unwrap_key_double_wrapping() {
+    let kms = MockKms::new();
+    let kek = KeyEncryptionKey::new(&kms, &MockKeyWrapper, "mk").unwrap();
+    assert_eq!(kek.master_key_id(), "mk");
+    let deks = vec![vec![1, 2, 3], vec![4, 5, 6, 7, 8]];
+    let materials: Vec<_> = deks
+      .iter()
+      .map(|dek| kek.wrap_key(&MockKeyWrapper, dek, false).unwrap())
+      .collect();
+    // Only KEK is wrapped by KMS
+    assert_eq!(kms.num_calls.get(), 1);
+
+    for (dek, material) in deks.iter().zip(materials.iter()) {
+      let material = KeyMaterial::parse(&material.serialize()).unwrap();
+      assert_eq!(&unwrap_key(&kms, Some(&MockKeyWrapper), &material).unwrap(), dek);
+    }
+    assert_eq!(
+      unwrap_key(&kms, None, &materials[0]).unwrap_err(),
+      general_err!("Key wrapper is required for double wrapping")
+    );
+  }
+
+  #[test]
+  fn test_rotate_master_key() {
+    let kms = MockKms::new();
+    let dek = vec![1, 2, 3];
+    let material = wrap_key(&kms, &dek, "mk1", true).unwrap();
+    let rotated = rotate_master_key(&kms, &material, "mk2").unwrap();
+    assert_eq!(rotated.master_key_id(), "mk2");
+    assert_eq!(rotated.wrapped_dek(), "mk2:010203");
+    assert_eq!(unwrap_key(&kms, None, &rotated).unwrap(), dek);
+    assert!(rotate_master_key(&kms, &rotated, "mk3").is_ok());
+    assert!(rotate_master_key(&kms, &material, "mk3").is_ok());
+
+    let kek = KeyEncryptionKey::new(&kms, &MockKeyWrapper, "mk1").unwrap();
+    let material = kek.wrap_key(&MockKeyWrapper, &dek, false).unwrap();
+    let rotated = rotate_master_key(&kms, &material, "mk2").unwrap();
+    assert_eq!(rotated.master_key_id(), "mk2");
+    assert_eq!(rotated.wrapped_dek(), material.wrapped_dek());
+    assert_eq!(rotated.kek_id(), material.kek_id());
+    assert!(rotated.wrapped_kek().unwrap().starts_with("mk2:"));
+    assert_eq!(unwrap_key(&kms, Some(&MockKeyWrapper), &rotated).unwrap(), dek);
+  }
+
+  #[test]
+  fn test_base64() {
+    let values: Vec<(&[u8], &str)> = vec![
+      (b"", ""),
+      (b"f", "Zg=="),
+      (b"fo", "Zm8="),
+      (b"foo", "Zm9v"),
+      (b"foob", "Zm9vYg=="),
+      (&[0xff, 0xfe, 0x00, 0x10], "//4AEA==")
+    ];
+    for (data, encoded) in values {
+      assert_eq!(encode_base64(data), encoded);
+      assert_eq!(decode_base64(encoded).unwrap(), data);
+    }
+    assert_eq!(decode_base64("Zm8").unwrap(), b"fo");
+    assert!(decode_base64("Zm!v").is_err());
+  }
+}
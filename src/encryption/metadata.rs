@@ -0,0 +1,432 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains crypto metadata of encrypted files: encryption algorithm of a file and
+//! how each column is encrypted.
+//!
+//! These Thrift structures are not in `parquet_format`, so they are read and written
+//! with the compact protocol directly. In files with plaintext footer they are stored
+//! as unknown fields of `FileMetaData` and `ColumnChunk`.
+
+use thrift::protocol::{
+  TCompactInputProtocol, TCompactOutputProtocol, TFieldIdentifier, TInputProtocol,
+  TListIdentifier, TOutputProtocol, TStructIdentifier, TType
+};
+
+use errors::{ParquetError, Result};
+use schema::types::ColumnPath;
+
+/// Encryption algorithm of a file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EncryptionAlgorithm {
+  /// All modules are encrypted with AES-GCM.
+  AES_GCM_V1,
+  /// Pages are encrypted with AES-CTR, which is faster but does not verify integrity
+  /// of pages, other modules are encrypted with AES-GCM.
+  AES_GCM_CTR_V1
+}
+
+impl EncryptionAlgorithm {
+  /// Returns field id of the algorithm in `EncryptionAlgorithm` union.
+  fn id(&self) -> i16 {
+    match *self {
+      EncryptionAlgorithm::AES_GCM_V1 => 1,
+      EncryptionAlgorithm::AES_GCM_CTR_V1 => 2
+    }
+  }
+}
+
+/// Encryption algorithm of a file with its parameters, the value of
+/// `encryption_algorithm` field of `FileMetaData` and `FileCryptoMetaData`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct AlgorithmMetaData {
+  pub(crate) algorithm: EncryptionAlgorithm,
+  // Prefix of file AAD, if stored in the file
+  pub(crate) aad_prefix: Option<Vec<u8>>,
+  // Unique part of file AAD, generated for each file
+  pub(crate) aad_file_unique: Option<Vec<u8>>,
+  // Whether or not AAD prefix must be supplied by the reader
+  pub(crate) supply_aad_prefix: bool
+}
+
+impl AlgorithmMetaData {
+  /// Reads `EncryptionAlgorithm` union.
+  pub(crate) fn read(prot: &mut TInputProtocol) -> Result<Self> {
+    let mut result = None;
+    prot.read_struct_begin()?;
+    loop {
+      let field = prot.read_field_begin()?;
+      if field.field_type == TType::Stop {
+        break;
+      }
+      match (field.id, field.field_type) {
+        (Some(1), TType::Struct) if result.is_none() => {
+          result = Some(Self::read_parameters(prot, EncryptionAlgorithm::AES_GCM_V1)?);
+        },
+        (Some(2), TType::Struct) if result.is_none() => {
+          result =
+            Some(Self::read_parameters(prot, EncryptionAlgorithm::AES_GCM_CTR_V1)?);
+        },
+        (Some(id), _) if result.is_none() => {
+          return Err(nyi_err!("Encryption algorithm {} is not supported", id));
+        },
+        (_, field_type) => prot.skip(field_type)?
+      }
+      prot.read_field_end()?;
+    }
+    prot.read_struct_end()?;
+    result.ok_or_else(|| corrupt_err!("Encryption algorithm is missing"))
+  }
+
+  /// Reads `AesGcmV1` or `AesGcmCtrV1` structure, which have the same fields.
+  fn read_parameters(
+    prot: &mut TInputProtocol,
+    algorithm: EncryptionAlgorithm
+  ) -> Result<Self> {
+    let mut result = AlgorithmMetaData {
+      algorithm: algorithm,
+      aad_prefix: None,
+      aad_file_unique: None,
+      supply_aad_prefix: false
+    };
+    prot.read_struct_begin()?;
+    loop {
+      let field = prot.read_field_begin()?;
+      if field.field_type == TType::Stop {
+        break;
+      }
+      match (field.id, field.field_type) {
+        (Some(1), TType::String) => result.aad_prefix = Some(prot.read_bytes()?),
+        (Some(2), TType::String) => result.aad_file_unique = Some(prot.read_bytes()?),
+        (Some(3), TType::Bool) => result.supply_aad_prefix = prot.read_bool()?,
+        (_, field_type) => prot.skip(field_type)?
+      }
+      prot.read_field_end()?;
+    }
+    prot.read_struct_end()?;
+    Ok(result)
+  }
+
+  /// Writes `EncryptionAlgorithm` union.
+  pub(crate) fn write(&self, prot: &mut TOutputProtocol) -> Result<()> {
+    prot.write_struct_begin(&TStructIdentifier::new("EncryptionAlgorithm"))?;
+    prot.write_field_begin(&field_identifier(TType::Struct, self.algorithm.id()))?;
+    prot.write_struct_begin(&TStructIdentifier::new("AesGcmV1"))?;
+    if let Some(ref aad_prefix) = self.aad_prefix {
+      prot.write_field_begin(&field_identifier(TType::String, 1))?;
+      prot.write_bytes(aad_prefix)?;
+      prot.write_field_end()?;
+    }
+    if let Some(ref aad_file_unique) = self.aad_file_unique {
+      prot.write_field_begin(&field_identifier(TType::String, 2))?;
+      prot.write_bytes(aad_file_unique)?;
+      prot.write_field_end()?;
+    }
+    if self.supply_aad_prefix {
+      prot.write_field_begin(&field_identifier(TType::Bool, 3))?;
+      prot.write_bool(true)?;
+      prot.write_field_end()?;
+    }
+    prot.write_field_stop()?;
+    prot.write_struct_end()?;
+    prot.write_field_end()?;
+    prot.write_field_stop()?;
+    prot.write_struct_end()?;
+    Ok(())
+  }
+
+  /// Decodes algorithm from the value of `encryption_algorithm` field serialized with
+  /// the compact protocol.
+  pub(crate) fn from_compact_bytes(data: &[u8]) -> Result<Self> {
+    Self::read(&mut TCompactInputProtocol::new(data))
+  }
+
+  /// Serializes algorithm as the value of `encryption_algorithm` field with the
+  /// compact protocol.
+  pub(crate) fn to_compact_bytes(&self) -> Result<Vec<u8>> {
+    to_compact_bytes(|prot| self.write(prot))
+  }
+}
+
+/// Crypto metadata of a file with encrypted footer, stored before the footer.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct FileCryptoMetaData {
+  pub(crate) algorithm: AlgorithmMetaData,
+  // Metadata to retrieve the footer key, if any
+  pub(crate) key_metadata: Option<Vec<u8>>
+}
+
+impl FileCryptoMetaData {
+  /// Reads `FileCryptoMetaData` structure.
+  pub(crate) fn read(prot: &mut TInputProtocol) -> Result<Self> {
+    let mut algorithm = None;
+    let mut key_metadata = None;
+    prot.read_struct_begin()?;
+    loop {
+      let field = prot.read_field_begin()?;
+      if field.field_type == TType::Stop {
+        break;
+      }
+      match (field.id, field.field_type) {
+        (Some(1), TType::Struct) => algorithm = Some(AlgorithmMetaData::read(prot)?),
+        (Some(2), TType::String) => key_metadata = Some(prot.read_bytes()?),
+        (_, field_type) => prot.skip(field_type)?
+      }
+      prot.read_field_end()?;
+    }
+    prot.read_struct_end()?;
+    match algorithm {
+      Some(algorithm) => Ok(Self { algorithm: algorithm, key_metadata: key_metadata }),
+      None => Err(corrupt_err!("File crypto metadata is missing encryption algorithm"))
+    }
+  }
+
+  /// Writes `FileCryptoMetaData` structure.
+  pub(crate) fn write(&self, prot: &mut TOutputProtocol) -> Result<()> {
+    prot.write_struct_begin(&TStructIdentifier::new("FileCryptoMetaData"))?;
+    prot.write_field_begin(&field_identifier(TType::Struct, 1))?;
+    self.algorithm.write(prot)?;
+    prot.write_field_end()?;
+    if let Some(ref key_metadata) = self.key_metadata {
+      prot.write_field_begin(&field_identifier(TType::String, 2))?;
+      prot.write_bytes(key_metadata)?;
+      prot.write_field_end()?;
+    }
+    prot.write_field_stop()?;
+    prot.write_struct_end()?;
+    Ok(())
+  }
+}
+
+/// Describes how a column chunk is encrypted.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnCryptoMetaData {
+  /// Column is encrypted with the footer key.
+  ENCRYPTION_WITH_FOOTER_KEY,
+  /// Column is encrypted with its own key, with path of the column and metadata to
+  /// retrieve the key, if any.
+  ENCRYPTION_WITH_COLUMN_KEY(ColumnPath, Option<Vec<u8>>)
+}
+
+impl ColumnCryptoMetaData {
+  /// Decodes crypto metadata from the value of `crypto_metadata` field of `ColumnChunk`
+  /// serialized with the compact protocol.
+  pub(crate) fn from_compact_bytes(data: &[u8]) -> Result<Self> {
+    let mut prot = TCompactInputProtocol::new(data);
+    let mut result = None;
+    prot.read_struct_begin()?;
+    loop {
+      let field = prot.read_field_begin()?;
+      if field.field_type == TType::Stop {
+        break;
+      }
+      match (field.id, field.field_type) {
+        (Some(1), TType::Struct) if result.is_none() => {
+          prot.skip(TType::Struct)?;
+          result = Some(ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY);
+        },
+        (Some(2), TType::Struct) if result.is_none() => {
+          result = Some(read_column_key(&mut prot)?);
+        },
+        (_, field_type) => prot.skip(field_type)?
+      }
+      prot.read_field_end()?;
+    }
+    prot.read_struct_end()?;
+    result.ok_or_else(|| corrupt_err!("Column crypto metadata is missing"))
+  }
+
+  /// Serializes crypto metadata as the value of `crypto_metadata` field of
+  /// `ColumnChunk` with the compact protocol.
+  pub(crate) fn to_compact_bytes(&self) -> Result<Vec<u8>> {
+    to_compact_bytes(|prot| {
+      prot.write_struct_begin(&TStructIdentifier::new("ColumnCryptoMetaData"))?;
+      match *self {
+        ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY => {
+          prot.write_field_begin(&field_identifier(TType::Struct, 1))?;
+          prot.write_struct_begin(&TStructIdentifier::new("EncryptionWithFooterKey"))?;
+        },
+        ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(ref path, ref key_metadata) => {
+          prot.write_field_begin(&field_identifier(TType::Struct, 2))?;
+          prot.write_struct_begin(&TStructIdentifier::new("EncryptionWithColumnKey"))?;
+          let parts: &[String] = path.as_ref();
+          prot.write_field_begin(&field_identifier(TType::List, 1))?;
+          let list = TListIdentifier::new(TType::String, parts.len() as i32);
+          prot.write_list_begin(&list)?;
+          for part in parts {
+            prot.write_string(part)?;
+          }
+          prot.write_list_end()?;
+          prot.write_field_end()?;
+          if let Some(ref key_metadata) = *key_metadata {
+            prot.write_field_begin(&field_identifier(TType::String, 2))?;
+            prot.write_bytes(key_metadata)?;
+            prot.write_field_end()?;
+          }
+        }
+      }
+      prot.write_field_stop()?;
+      prot.write_struct_end()?;
+      prot.write_field_end()?;
+      prot.write_field_stop()?;
+      prot.write_struct_end()?;
+      Ok(())
+    })
+  }
+}
+
+/// Reads `EncryptionWithColumnKey` structure.
+fn read_column_key(prot: &mut TInputProtocol) -> Result<ColumnCryptoMetaData> {
+  let mut path = None;
+  let mut key_metadata = None;
+  prot.read_struct_begin()?;
+  loop {
+    let field = prot.read_field_begin()?;
+    if field.field_type == TType::Stop {
+      break;
+    }
+    match (field.id, field.field_type) {
+      (Some(1), TType::List) => {
+        let list = prot.read_list_begin()?;
+        if list.size < 0 || list.element_type != TType::String {
+          return Err(corrupt_err!("Invalid path of encrypted column"));
+        }
+        let mut parts = Vec::new();
+        for _ in 0..list.size {
+          parts.push(prot.read_string()?);
+        }
+        prot.read_list_end()?;
+        path = Some(ColumnPath::new(parts));
+      },
+      (Some(2), TType::String) => key_metadata = Some(prot.read_bytes()?),
+      (_, field_type) => prot.skip(field_type)?
+    }
+    prot.read_field_end()?;
+  }
+  prot.read_struct_end()?;
+  match path {
+    Some(path) => {
+      Ok(ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(path, key_metadata))
+    },
+    None => Err(corrupt_err!("Path of encrypted column is missing"))
+  }
+}
+
+/// Decodes the value of a binary field serialized with the compact protocol.
+pub(crate) fn binary_from_compact_bytes(data: &[u8]) -> Result<Vec<u8>> {
+  Ok(TCompactInputProtocol::new(data).read_bytes()?)
+}
+
+/// Serializes `value` as the value of a binary field with the compact protocol.
+pub(crate) fn binary_to_compact_bytes(value: &[u8]) -> Result<Vec<u8>> {
+  to_compact_bytes(|prot| Ok(prot.write_bytes(value)?))
+}
+
+/// Returns bytes written with the compact protocol by `write`.
+fn to_compact_bytes<F>(write: F) -> Result<Vec<u8>>
+  where F: FnOnce(&mut TOutputProtocol) -> Result<()> {
+  let mut buf = Vec::new();
+  {
+    let mut prot = TCompactOutputProtocol::new(&mut buf);
+    write(&mut prot)?;
+    prot.flush()?;
+  }
+  Ok(buf)
+}
+
+fn field_identifier(field_type: TType, id: i16) -> TFieldIdentifier {
+  TFieldIdentifier { name: None, field_type: field_type, id: Some(id) }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::io::Cursor;
+
+  #[test]
+  fn test_algorithm_metadata_compact_bytes() {
+    let algorithms = vec![
+      AlgorithmMetaData {
+        algorithm: EncryptionAlgorithm::AES_GCM_V1,
+        aad_prefix: None,
+        aad_file_unique: Some(vec![1, 2, 3, 4, 5, 6, 7, 8]),
+        supply_aad_prefix: false
+      },
+      AlgorithmMetaData {
+        algorithm: EncryptionAlgorithm::AES_GCM_CTR_V1,
+        aad_prefix: Some(b"prefix".to_vec()),
+        aad_file_unique: None,
+        supply_aad_prefix: true
+      }
+    ];
+    for algorithm in algorithms {
+      let data = algorithm.to_compact_bytes().unwrap();
+      assert_eq!(AlgorithmMetaData::from_compact_bytes(&data).unwrap(), algorithm);
+    }
+
+    // Union with unknown algorithm 3
+    let data = [0x3C, 0x00, 0x00];
+    assert_eq!(
+      AlgorithmMetaData::from_compact_bytes(&data).err().unwrap(),
+      nyi_err!("Encryption algorithm 3 is not supported")
+    );
+    assert_eq!(
+      AlgorithmMetaData::from_compact_bytes(&[0x00]).err().unwrap(),
+      corrupt_err!("Encryption algorithm is missing")
+    );
+  }
+
+  #[test]
+  fn test_file_crypto_metadata_read_write() {
+    let metadata = FileCryptoMetaData {
+      algorithm: AlgorithmMetaData {
+        algorithm: EncryptionAlgorithm::AES_GCM_V1,
+        aad_prefix: None,
+        aad_file_unique: Some(vec![8; 8]),
+        supply_aad_prefix: false
+      },
+      key_metadata: Some(b"footer key".to_vec())
+    };
+    let mut data = to_compact_bytes(|prot| metadata.write(prot)).unwrap();
+    let len = data.len();
+    data.extend_from_slice(b"rest");
+
+    let mut cursor = Cursor::new(&data[..]);
+    let result = FileCryptoMetaData::read(&mut TCompactInputProtocol::new(&mut cursor));
+    assert_eq!(result.unwrap(), metadata);
+    assert_eq!(cursor.position(), len as u64);
+  }
+
+  #[test]
+  fn test_column_crypto_metadata_compact_bytes() {
+    let path = ColumnPath::new(vec!["a".to_string(), "b".to_string()]);
+    let values = vec![
+      ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY,
+      ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(path.clone(), None),
+      ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(path, Some(b"key".to_vec()))
+    ];
+    for value in values {
+      let data = value.to_compact_bytes().unwrap();
+      assert_eq!(ColumnCryptoMetaData::from_compact_bytes(&data).unwrap(), value);
+    }
+
+    let data = binary_to_compact_bytes(b"value").unwrap();
+    assert_eq!(binary_from_compact_bytes(&data).unwrap(), b"value");
+  }
+}
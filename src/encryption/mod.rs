@@ -0,0 +1,36 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains API of Parquet modular encryption, which encrypts data and metadata of
+//! files with AES-GCM and AES-CTR.
+//!
+//! Encrypted files are written with [`FileEncryptionProperties`] and read with
+//! [`FileDecryptionProperties`], either with encrypted footer or with plaintext footer,
+//! where only columns are encrypted. See [`kms`] for envelope encryption of data keys
+//! with master keys of an external key management service (KMS), which requires
+//! `serde_json` feature.
+//!
+//! [`FileEncryptionProperties`]: encrypt/struct.FileEncryptionProperties.html
+//! [`FileDecryptionProperties`]: decrypt/struct.FileDecryptionProperties.html
+//! [`kms`]: kms/index.html
+
+pub(crate) mod ciphers;
+pub mod decrypt;
+pub mod encrypt;
+#[cfg(feature = "serde_json")]
+pub mod kms;
+pub mod metadata;
@@ -16,48 +16,277 @@
 // under the License.
 
 //! Common Parquet errors and macros.
+//!
+//! Errors can carry [`ErrorContext`] with location in a file where they occurred, e.g.
+//! column path, row group, page ordinal and byte offset, see
+//! [`ParquetError::with_context`](enum.ParquetError.html#method.with_context). Errors
+//! of IO, Thrift and compression libraries are available through
+//! [`Error::source`](https://doc.rust-lang.org/std/error/trait.Error.html).
 
 use std::cell;
 use std::convert;
+use std::error::Error;
+use std::fmt;
 use std::io;
 use std::result;
 
 use snap;
 use thrift;
 
-quick_error! {
-  /// Set of errors that can be produced during different operations in Parquet.
-  #[derive(Debug, PartialEq)]
-  pub enum ParquetError {
-    /// General Parquet error.
-    /// Returned when code violates normal workflow of working with Parquet files.
-    General(message: String) {
-      display("Parquet error: {}", message)
-      description(message)
-      from(e: io::Error) -> (format!("underlying IO error: {}", e))
-      from(e: snap::Error) -> (format!("underlying snap error: {}", e))
-      from(e: thrift::Error) -> (format!("underlying Thrift error: {}", e))
-      from(e: cell::BorrowMutError) -> (format!("underlying borrow error: {}", e))
+/// Set of errors that can be produced during different operations in Parquet.
+#[derive(Debug, PartialEq)]
+pub enum ParquetError {
+  /// General Parquet error.
+  /// Returned when code violates normal workflow of working with Parquet files.
+  General(String),
+  /// "Not yet implemented" Parquet error.
+  /// Returned when functionality is not yet available.
+  NYI(String),
+  /// "End of file" Parquet error.
+  /// Returned when IO related failures occur, e.g. when there are not enough bytes to
+  /// decode.
+  EOF(String),
+  /// "Resource exhausted" Parquet error.
+  /// Returned when an allocation would exceed the limit of a memory tracker.
+  ResourceExhausted(String),
+  /// "Corrupt data" Parquet error.
+  /// Returned when decoded data is inconsistent, e.g. dictionary index is out of range
+  /// of the dictionary.
+  Corrupt(String),
+  /// Error of an underlying library, such as IO, Thrift or compression codec.
+  /// Original error is returned as a source of this error.
+  External(String, ErrorSource),
+  /// Error with location in a file where it occurred.
+  /// Original error is returned as a source of this error.
+  Context(ErrorContext, Box<ParquetError>)
+}
+
+impl ParquetError {
+  /// Adds location to this error. If error already has context, fields that are not
+  /// set yet are filled from `context`, so context can be added at different levels,
+  /// e.g. page ordinal in page reader and row group index in row group reader.
+  pub fn with_context(self, context: ErrorContext) -> Self {
+    match self {
+      ParquetError::Context(existing, cause) => {
+        ParquetError::Context(existing.merge(context), cause)
+      },
+      cause => ParquetError::Context(context, Box::new(cause))
+    }
+  }
+
+  /// Returns location of this error, if known.
+  pub fn context(&self) -> Option<&ErrorContext> {
+    match *self {
+      ParquetError::Context(ref context, _) => Some(context),
+      _ => None
     }
-    /// "Not yet implemented" Parquet error.
-    /// Returned when functionality is not yet available.
-    NYI(message: String) {
-      display("NYI: {}", message)
-      description(message)
+  }
+
+  /// Returns this error without context.
+  pub fn without_context(&self) -> &ParquetError {
+    match *self {
+      ParquetError::Context(_, ref cause) => cause.without_context(),
+      ref err => err
     }
-    /// "End of file" Parquet error.
-    /// Returned when IO related failures occur, e.g. when there are not enough bytes to
-    /// decode.
-    EOF(message: String) {
-      display("EOF: {}", message)
-      description(message)
+  }
+
+  /// Returns error message without context.
+  pub fn message(&self) -> &str {
+    match *self {
+      ParquetError::General(ref message) |
+      ParquetError::NYI(ref message) |
+      ParquetError::EOF(ref message) |
+      ParquetError::ResourceExhausted(ref message) |
+      ParquetError::Corrupt(ref message) |
+      ParquetError::External(ref message, _) => message,
+      ParquetError::Context(_, ref cause) => cause.message()
     }
   }
 }
 
+impl fmt::Display for ParquetError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      ParquetError::General(ref message) => write!(f, "Parquet error: {}", message),
+      ParquetError::NYI(ref message) => write!(f, "NYI: {}", message),
+      ParquetError::EOF(ref message) => write!(f, "EOF: {}", message),
+      ParquetError::ResourceExhausted(ref message) => {
+        write!(f, "Resource exhausted: {}", message)
+      },
+      ParquetError::Corrupt(ref message) => write!(f, "Corrupt data: {}", message),
+      ParquetError::External(ref message, _) => write!(f, "Parquet error: {}", message),
+      ParquetError::Context(ref context, ref cause) => {
+        write!(f, "{} ({})", cause, context)
+      }
+    }
+  }
+}
+
+impl Error for ParquetError {
+  fn description(&self) -> &str {
+    self.message()
+  }
+
+  fn source(&self) -> Option<&(Error + 'static)> {
+    match *self {
+      ParquetError::External(_, ref source) => Some(source.inner.as_ref()),
+      ParquetError::Context(_, ref cause) => Some(cause.as_ref()),
+      _ => None
+    }
+  }
+}
+
+/// Location in a file where an error occurred. All fields are optional, only known
+/// fields are set.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ErrorContext {
+  column_path: Option<String>,
+  row_group: Option<usize>,
+  page: Option<usize>,
+  offset: Option<u64>
+}
+
+impl ErrorContext {
+  /// Creates new empty context.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets column path, e.g. `a.b.c`.
+  pub fn with_column_path(mut self, column_path: String) -> Self {
+    self.column_path = Some(column_path);
+    self
+  }
+
+  /// Sets index of a row group in a file.
+  pub fn with_row_group(mut self, row_group: usize) -> Self {
+    self.row_group = Some(row_group);
+    self
+  }
+
+  /// Sets ordinal of a page in a column chunk, including dictionary page.
+  pub fn with_page(mut self, page: usize) -> Self {
+    self.page = Some(page);
+    self
+  }
+
+  /// Sets byte offset in a file.
+  pub fn with_offset(mut self, offset: u64) -> Self {
+    self.offset = Some(offset);
+    self
+  }
+
+  /// Returns column path, if known.
+  pub fn column_path(&self) -> Option<&str> {
+    self.column_path.as_deref()
+  }
+
+  /// Returns index of a row group, if known.
+  pub fn row_group(&self) -> Option<usize> {
+    self.row_group
+  }
+
+  /// Returns ordinal of a page in a column chunk, if known.
+  pub fn page(&self) -> Option<usize> {
+    self.page
+  }
+
+  /// Returns byte offset in a file, if known.
+  pub fn offset(&self) -> Option<u64> {
+    self.offset
+  }
+
+  /// Returns context with fields of this context, fields that are not set are taken
+  /// from `other`.
+  fn merge(self, other: ErrorContext) -> Self {
+    Self {
+      column_path: self.column_path.or(other.column_path),
+      row_group: self.row_group.or(other.row_group),
+      page: self.page.or(other.page),
+      offset: self.offset.or(other.offset)
+    }
+  }
+}
+
+impl fmt::Display for ErrorContext {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut parts = Vec::new();
+    if let Some(ref column_path) = self.column_path {
+      parts.push(format!("column: {}", column_path));
+    }
+    if let Some(row_group) = self.row_group {
+      parts.push(format!("row group: {}", row_group));
+    }
+    if let Some(page) = self.page {
+      parts.push(format!("page: {}", page));
+    }
+    if let Some(offset) = self.offset {
+      parts.push(format!("offset: {}", offset));
+    }
+    write!(f, "{}", parts.join(", "))
+  }
+}
+
+/// Original error of an underlying library.
+///
+/// Errors are compared by their string representation, since errors of underlying
+/// libraries do not implement `PartialEq`.
+#[derive(Debug)]
+pub struct ErrorSource {
+  inner: Box<Error + Send + Sync>
+}
+
+impl ErrorSource {
+  /// Creates new source from an error.
+  pub fn new<E: 'static + Error + Send + Sync>(err: E) -> Self {
+    Self { inner: Box::new(err) }
+  }
+
+  /// Returns original error.
+  pub fn inner(&self) -> &(Error + Send + Sync + 'static) {
+    self.inner.as_ref()
+  }
+}
+
+impl PartialEq for ErrorSource {
+  fn eq(&self, other: &ErrorSource) -> bool {
+    self.inner.to_string() == other.inner.to_string()
+  }
+}
+
 /// A specialized `Result` for Parquet errors.
 pub type Result<T> = result::Result<T, ParquetError>;
 
+// ----------------------------------------------------------------------
+// Conversion from other types of `Error`s to `ParquetError`
+
+impl convert::From<io::Error> for ParquetError {
+  fn from(e: io::Error) -> Self {
+    ParquetError::External(format!("underlying IO error: {}", e), ErrorSource::new(e))
+  }
+}
+
+impl convert::From<snap::Error> for ParquetError {
+  fn from(e: snap::Error) -> Self {
+    ParquetError::External(format!("underlying snap error: {}", e), ErrorSource::new(e))
+  }
+}
+
+impl convert::From<thrift::Error> for ParquetError {
+  fn from(e: thrift::Error) -> Self {
+    ParquetError::External(
+      format!("underlying Thrift error: {}", e),
+      ErrorSource::new(e)
+    )
+  }
+}
+
+impl convert::From<cell::BorrowMutError> for ParquetError {
+  fn from(e: cell::BorrowMutError) -> Self {
+    ParquetError::General(format!("underlying borrow error: {}", e))
+  }
+}
+
 // ----------------------------------------------------------------------
 // Conversion from `ParquetError` to other types of `Error`s
 
@@ -73,9 +302,6 @@ impl convert::From<ParquetError> for io::Error {
 macro_rules! general_err {
   ($fmt:expr) => (ParquetError::General($fmt.to_owned()));
   ($fmt:expr, $($args:expr),*) => (ParquetError::General(format!($fmt, $($args),*)));
-  ($e:expr, $fmt:expr) => (ParquetError::General($fmt.to_owned(), $e));
-  ($e:ident, $fmt:expr, $($args:tt),*) => (
-    ParquetError::General(&format!($fmt, $($args),*), $e));
 }
 
 macro_rules! nyi_err {
@@ -87,3 +313,71 @@ macro_rules! eof_err {
   ($fmt:expr) => (ParquetError::EOF($fmt.to_owned()));
   ($fmt:expr, $($args:expr),*) => (ParquetError::EOF(format!($fmt, $($args),*)));
 }
+
+macro_rules! resource_exhausted_err {
+  ($fmt:expr) => (ParquetError::ResourceExhausted($fmt.to_owned()));
+  ($fmt:expr, $($args:expr),*) => (
+    ParquetError::ResourceExhausted(format!($fmt, $($args),*)));
+}
+
+macro_rules! corrupt_err {
+  ($fmt:expr) => (ParquetError::Corrupt($fmt.to_owned()));
+  ($fmt:expr, $($args:expr),*) => (ParquetError::Corrupt(format!($fmt, $($args),*)));
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_error_context() {
+    let err = general_err!("Test error")
+      .with_context(ErrorContext::new().with_page(2).with_offset(120))
+      .with_context(
+        ErrorContext::new()
+          .with_column_path("a.b".to_owned())
+          .with_row_group(1)
+          .with_page(0)
+      );
+    let context = err.context().unwrap();
+    assert_eq!(context.column_path(), Some("a.b"));
+    assert_eq!(context.row_group(), Some(1));
+    assert_eq!(context.page(), Some(2));
+    assert_eq!(context.offset(), Some(120));
+    assert_eq!(
+      err.to_string(),
+      "Parquet error: Test error (column: a.b, row group: 1, page: 2, offset: 120)"
+    );
+    assert_eq!(err.without_context(), &general_err!("Test error"));
+    assert_eq!(err.description(), "Test error");
+    assert_eq!(err.source().unwrap().to_string(), "Parquet error: Test error");
+
+    assert_eq!(general_err!("Test error").context(), None);
+    assert_eq!(ErrorContext::new().to_string(), "");
+  }
+
+  #[test]
+  fn test_error_source() {
+    let err = ParquetError::from(io::Error::new(io::ErrorKind::Other, "IO failure"));
+    assert_eq!(err.to_string(), "Parquet error: underlying IO error: IO failure");
+    assert_eq!(err.source().unwrap().to_string(), "IO failure");
+    assert_eq!(
+      err,
+      ParquetError::from(io::Error::new(io::ErrorKind::Other, "IO failure"))
+    );
+
+    let err = err.with_context(ErrorContext::new().with_offset(4));
+    assert_eq!(err.source().unwrap().source().unwrap().to_string(), "IO failure");
+    assert!(general_err!("Test error").source().is_none());
+  }
+
+  #[test]
+  fn test_error_corrupt() {
+    let err = corrupt_err!("Invalid dictionary index: {}", 3)
+      .with_context(ErrorContext::new().with_page(1));
+    assert_eq!(err.to_string(), "Corrupt data: Invalid dictionary index: 3 (page: 1)");
+    assert_eq!(err.without_context(), &corrupt_err!("Invalid dictionary index: 3"));
+    assert_eq!(err.message(), "Invalid dictionary index: 3");
+  }
+}
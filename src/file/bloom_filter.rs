@@ -0,0 +1,602 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains split block bloom filters of column chunks and pruning of row groups by
+//! values.
+//!
+//! A bloom filter of a column chunk is stored between row groups and file metadata,
+//! its location is available with [`ColumnChunkMetaData::bloom_filter_offset`]. Only
+//! split block bloom filters ([`Sbbf`]) with xxHash and without compression are
+//! defined by the Parquet format and supported. Values are hashed in plain encoding,
+//! `BYTE_ARRAY` values without length.
+//!
+//! Use [`read_bloom_filter`] to load a bloom filter of a column chunk, or
+//! [`prune_row_groups_by_values`] to find row groups that may contain any of the
+//! values, based on statistics and bloom filters.
+//!
+//! [`ColumnChunkMetaData::bloom_filter_offset`]:
+//! ../metadata/struct.ColumnChunkMetaData.html#method.bloom_filter_offset
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use parquet::data_type::Int32Type;
+//! use parquet::file::bloom_filter::prune_row_groups_by_values;
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//!
+//! let mut file = File::open("data/alltypes_plain.parquet").unwrap();
+//! let reader = SerializedFileReader::new(file.try_clone().unwrap()).unwrap();
+//! let metadata = reader.metadata();
+//! // File does not have bloom filters, the only row group may contain the values
+//! let row_groups =
+//!   prune_row_groups_by_values::<Int32Type, _>(&mut file, &metadata, 0, &[4, 5]);
+//! assert_eq!(row_groups.unwrap(), vec![0]);
+//! ```
+
+use std::io::{Read, Seek, SeekFrom, Take, Write};
+
+use byteorder::{ByteOrder, LittleEndian};
+use thrift::protocol::{
+  TCompactInputProtocol, TCompactOutputProtocol, TFieldIdentifier, TInputProtocol,
+  TOutputProtocol, TStructIdentifier, TType
+};
+
+use data_type::{AsBytes, DataType};
+use encryption::ciphers::{read_module, ColumnCipher, ModuleType};
+use encryption::decrypt::column_cipher;
+use errors::{ErrorContext, ParquetError, Result};
+use file::metadata::{ColumnChunkMetaData, ParquetMetaData};
+use file::reader::Length;
+use util::hash_util::xxhash64;
+
+/// Number of 32-bit words in a block.
+const BLOCK_WORDS: usize = 8;
+
+/// Size of a block in bytes.
+const BLOCK_SIZE: usize = BLOCK_WORDS * 4;
+
+/// Max size of a bloom filter in bytes.
+const MAX_NUM_BYTES: usize = 128 * 1024 * 1024;
+
+/// Salt values to compute bit masks of blocks, defined by the Parquet format.
+const SALT: [u32; BLOCK_WORDS] = [
+  0x47b6137b, 0x44974d91, 0x8824ad5b, 0xa2b7289d,
+  0x705495c7, 0x2df1424b, 0x9efc4947, 0x5c6bfb31
+];
+
+/// Field id of the split block member of the `BloomFilterAlgorithm` union.
+const SPLIT_BLOCK_ALGORITHM_ID: i16 = 1;
+
+/// Field id of the xxHash member of the `BloomFilterHash` union.
+const XXHASH_ID: i16 = 1;
+
+/// Field id of the uncompressed member of the `BloomFilterCompression` union.
+const UNCOMPRESSED_ID: i16 = 1;
+
+/// Split block bloom filter, consisting of blocks of 256 bits. Each value sets or
+/// checks 8 bits of a single block, selected by its 64-bit xxHash value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sbbf {
+  blocks: Vec<[u32; BLOCK_WORDS]>
+}
+
+impl Sbbf {
+  /// Creates an empty bloom filter of at least `num_bytes` bytes. Size is rounded up
+  /// to a power of two between 32 bytes and 128 MB.
+  pub fn new(num_bytes: usize) -> Self {
+    let num_bytes = num_bytes.max(BLOCK_SIZE).min(MAX_NUM_BYTES).next_power_of_two();
+    Self { blocks: vec![[0; BLOCK_WORDS]; num_bytes / BLOCK_SIZE] }
+  }
+
+  /// Creates a bloom filter from its bitset, as stored in a file after the header.
+  pub fn from_bytes(bitset: &[u8]) -> Result<Self> {
+    if bitset.is_empty() || bitset.len() % BLOCK_SIZE != 0 {
+      return Err(corrupt_err!("Invalid bloom filter size {}", bitset.len()));
+    }
+    let blocks = bitset.chunks(BLOCK_SIZE).map(|chunk| {
+      let mut block = [0; BLOCK_WORDS];
+      for (i, word) in block.iter_mut().enumerate() {
+        *word = LittleEndian::read_u32(&chunk[i * 4..]);
+      }
+      block
+    }).collect();
+    Ok(Self { blocks: blocks })
+  }
+
+  /// Returns size of the bitset of this bloom filter in bytes.
+  pub fn num_bytes(&self) -> usize {
+    self.blocks.len() * BLOCK_SIZE
+  }
+
+  /// Adds `value` to this bloom filter.
+  pub fn insert<T: AsBytes>(&mut self, value: &T) {
+    self.insert_hash(xxhash64(value, 0));
+  }
+
+  /// Returns `false` if `value` is definitely not in this bloom filter, `true` if it
+  /// may be.
+  pub fn check<T: AsBytes>(&self, value: &T) -> bool {
+    self.check_hash(xxhash64(value, 0))
+  }
+
+  /// Adds value with xxHash value `hash` to this bloom filter.
+  pub fn insert_hash(&mut self, hash: u64) {
+    let index = self.block_index(hash);
+    let mask = block_mask(hash as u32);
+    for (word, bits) in self.blocks[index].iter_mut().zip(mask.iter()) {
+      *word |= *bits;
+    }
+  }
+
+  /// Returns `false` if value with xxHash value `hash` is definitely not in this bloom
+  /// filter, `true` if it may be.
+  pub fn check_hash(&self, hash: u64) -> bool {
+    let index = self.block_index(hash);
+    let mask = block_mask(hash as u32);
+    self.blocks[index].iter().zip(mask.iter()).all(|(word, bits)| word & bits == *bits)
+  }
+
+  /// Writes header and bitset of this bloom filter, as stored in a file.
+  pub fn write<W: Write>(&self, sink: &mut W) -> Result<()> {
+    self.write_with_cipher(sink, None)
+  }
+
+  /// Writes header and bitset of this bloom filter, which are encrypted as separate
+  /// modules with `cipher` if set.
+  pub(crate) fn write_with_cipher<W: Write>(
+    &self,
+    sink: &mut W,
+    cipher: Option<&ColumnCipher>
+  ) -> Result<()> {
+    let mut header = Vec::new();
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut header);
+      prot.write_struct_begin(&TStructIdentifier::new("BloomFilterHeader"))?;
+      prot.write_field_begin(&field_identifier(TType::I32, 1))?;
+      prot.write_i32(self.num_bytes() as i32)?;
+      prot.write_field_end()?;
+      write_union_member(&mut prot, 2, SPLIT_BLOCK_ALGORITHM_ID)?;
+      write_union_member(&mut prot, 3, XXHASH_ID)?;
+      write_union_member(&mut prot, 4, UNCOMPRESSED_ID)?;
+      prot.write_field_stop()?;
+      prot.write_struct_end()?;
+      prot.flush()?;
+    }
+    let mut bitset = vec![0; self.num_bytes()];
+    for (block, buf) in self.blocks.iter().zip(bitset.chunks_mut(BLOCK_SIZE)) {
+      for (i, word) in block.iter().enumerate() {
+        LittleEndian::write_u32(&mut buf[i * 4..], *word);
+      }
+    }
+    if let Some(cipher) = cipher {
+      header = cipher.encrypt(ModuleType::BLOOM_FILTER_HEADER, 0, &header)?;
+      bitset = cipher.encrypt(ModuleType::BLOOM_FILTER_BITSET, 0, &bitset)?;
+    }
+    sink.write_all(&header)?;
+    sink.write_all(&bitset)?;
+    Ok(())
+  }
+
+  /// Returns index of the block for xxHash value `hash`.
+  #[inline]
+  fn block_index(&self, hash: u64) -> usize {
+    (((hash >> 32) * self.blocks.len() as u64) >> 32) as usize
+  }
+}
+
+/// Returns bits to set or check in a block for the lower 32 bits of xxHash value.
+#[inline]
+fn block_mask(x: u32) -> [u32; BLOCK_WORDS] {
+  let mut mask = [0; BLOCK_WORDS];
+  for (bits, salt) in mask.iter_mut().zip(SALT.iter()) {
+    *bits = 1 << (x.wrapping_mul(*salt) >> 27);
+  }
+  mask
+}
+
+/// Reads bloom filter of a column chunk, returns `None` if column chunk does not have
+/// a bloom filter. Bloom filters of encrypted column chunks are read with
+/// [`SerializedFileReader::read_bloom_filter`](
+/// ../reader/struct.SerializedFileReader.html#method.read_bloom_filter).
+pub fn read_bloom_filter<R: Read + Seek + Length>(
+  reader: &mut R,
+  column: &ColumnChunkMetaData
+) -> Result<Option<Sbbf>> {
+  let cipher = column_cipher(None, column, 0, 0)?;
+  read_bloom_filter_with_cipher(reader, column, cipher.as_ref())
+}
+
+/// Reads bloom filter of a column chunk, whose header and bitset are decrypted with
+/// `cipher` if set.
+pub(crate) fn read_bloom_filter_with_cipher<R: Read + Seek + Length>(
+  reader: &mut R,
+  column: &ColumnChunkMetaData,
+  cipher: Option<&ColumnCipher>
+) -> Result<Option<Sbbf>> {
+  let offset = match column.bloom_filter_offset() {
+    Some(offset) => offset,
+    None => return Ok(None)
+  };
+  // Length is optional, the bloom filter is then bounded by the end of the file
+  let length = column.bloom_filter_length()
+    .map(|length| length as i64)
+    .unwrap_or_else(|| reader.len() as i64 - offset);
+  let in_bounds = match offset.checked_add(length) {
+    Some(end) => offset >= 0 && length >= 0 && end as u64 <= reader.len(),
+    None => false
+  };
+  if !in_bounds {
+    return Err(general_err!(
+      "Bloom filter is out of bounds: offset {}, size {}",
+      offset,
+      length
+    ));
+  }
+
+  reader.seek(SeekFrom::Start(offset as u64))?;
+  let mut input = (&mut *reader).take(length as u64);
+  let context = || ErrorContext::new().with_offset(offset as u64);
+  if let Some(cipher) = cipher {
+    let bitset = read_encrypted_bitset(&mut input, cipher)
+      .map_err(|e| e.with_context(context()))?;
+    return Sbbf::from_bytes(&bitset).map(Some).map_err(|e| e.with_context(context()));
+  }
+  let num_bytes = {
+    let mut prot = TCompactInputProtocol::new(&mut input);
+    read_header(&mut prot).map_err(|e| e.with_context(context()))?
+  };
+  if num_bytes as u64 > input.limit() {
+    return Err(
+      corrupt_err!("Bloom filter size {} exceeds its bounds", num_bytes)
+        .with_context(context())
+    );
+  }
+  let mut bitset = vec![0; num_bytes];
+  input.read_exact(&mut bitset)?;
+  Sbbf::from_bytes(&bitset).map(Some).map_err(|e| e.with_context(context()))
+}
+
+/// Reads header and bitset of a bloom filter, which are encrypted as separate modules,
+/// and returns the decrypted bitset.
+fn read_encrypted_bitset<R: Read>(
+  input: &mut Take<R>,
+  cipher: &ColumnCipher
+) -> Result<Vec<u8>> {
+  let limit = input.limit() as usize;
+  let header = read_module(input, limit)?;
+  let header = cipher.decrypt(ModuleType::BLOOM_FILTER_HEADER, 0, &header)?;
+  let num_bytes = read_header(&mut TCompactInputProtocol::new(&header[..]))?;
+  let limit = input.limit() as usize;
+  let bitset = read_module(input, limit)?;
+  let bitset = cipher.decrypt(ModuleType::BLOOM_FILTER_BITSET, 0, &bitset)?;
+  if bitset.len() != num_bytes {
+    return Err(corrupt_err!(
+      "Bloom filter size {} does not match size {} in its header",
+      bitset.len(),
+      num_bytes
+    ));
+  }
+  Ok(bitset)
+}
+
+/// Returns indexes of row groups that may contain any of `values` in `column`, i.e.
+/// row groups where statistics or bloom filter of the column chunk do not rule out all
+/// of the values. Row groups without statistics and bloom filter are always returned.
+///
+/// Bloom filters are read from `reader` with file `metadata`, only for row groups with
+/// statistics that do not rule out all of the values.
+pub fn prune_row_groups_by_values<T: DataType, R: Read + Seek + Length>(
+  reader: &mut R,
+  metadata: &ParquetMetaData,
+  column: usize,
+  values: &[T::T]
+) -> Result<Vec<usize>> {
+  let file_metadata = metadata.file_metadata();
+  let schema_descr = file_metadata.schema_descr();
+  if column >= schema_descr.num_columns() {
+    return Err(general_err!(
+      "Invalid column index {}, file has {} columns",
+      column,
+      schema_descr.num_columns()
+    ));
+  }
+  let descr = schema_descr.column(column);
+  if descr.physical_type() != T::get_physical_type() {
+    return Err(general_err!(
+      "Cannot prune row groups by values of type {}, column {} has type {}",
+      T::get_physical_type(),
+      descr.path(),
+      descr.physical_type()
+    ));
+  }
+  let column_order = file_metadata.column_order(column);
+
+  let mut row_groups = vec![];
+  for (i, row_group) in metadata.row_groups().iter().enumerate() {
+    let column_chunk = row_group.column(column);
+    let mut candidates = values.iter()
+      .filter(|value| {
+        column_chunk.statistics().map_or(true, |stats| {
+          stats.may_contain(&descr, column_order, value.as_bytes())
+        })
+      })
+      .collect::<Vec<_>>();
+    if !candidates.is_empty() {
+      if let Some(bloom_filter) = read_bloom_filter(reader, column_chunk)? {
+        candidates.retain(|value| bloom_filter.check(*value));
+      }
+    }
+    if !candidates.is_empty() {
+      row_groups.push(i);
+    }
+  }
+  Ok(row_groups)
+}
+
+/// Reads header of a bloom filter and returns size of its bitset in bytes.
+fn read_header(prot: &mut TInputProtocol) -> Result<usize> {
+  let mut num_bytes = None;
+  let mut algorithm = None;
+  let mut hash = None;
+  let mut compression = None;
+  prot.read_struct_begin()?;
+  loop {
+    let field = prot.read_field_begin()?;
+    if field.field_type == TType::Stop {
+      break;
+    }
+    match (field.id, field.field_type) {
+      (Some(1), TType::I32) => num_bytes = Some(prot.read_i32()?),
+      (Some(2), TType::Struct) => algorithm = Some(read_union_member(prot)?),
+      (Some(3), TType::Struct) => hash = Some(read_union_member(prot)?),
+      (Some(4), TType::Struct) => compression = Some(read_union_member(prot)?),
+      (_, field_type) => prot.skip(field_type)?
+    }
+    prot.read_field_end()?;
+  }
+  prot.read_struct_end()?;
+
+  match (num_bytes, algorithm, hash, compression) {
+    (Some(num_bytes), Some(algorithm), Some(hash), Some(compression)) => {
+      if algorithm != SPLIT_BLOCK_ALGORITHM_ID || hash != XXHASH_ID ||
+          compression != UNCOMPRESSED_ID {
+        return Err(nyi_err!(
+          "Bloom filter with algorithm {}, hash {} and compression {} is not supported",
+          algorithm,
+          hash,
+          compression
+        ));
+      }
+      if num_bytes <= 0 || num_bytes as usize % BLOCK_SIZE != 0 {
+        return Err(corrupt_err!("Invalid bloom filter size {}", num_bytes));
+      }
+      Ok(num_bytes as usize)
+    },
+    _ => Err(corrupt_err!("Bloom filter header is missing required fields"))
+  }
+}
+
+/// Reads the value of a union field and returns field id of its member.
+fn read_union_member(prot: &mut TInputProtocol) -> Result<i16> {
+  let mut member = None;
+  prot.read_struct_begin()?;
+  loop {
+    let field = prot.read_field_begin()?;
+    if field.field_type == TType::Stop {
+      break;
+    }
+    if member.is_none() {
+      member = field.id;
+    }
+    prot.skip(field.field_type)?;
+    prot.read_field_end()?;
+  }
+  prot.read_struct_end()?;
+  member.ok_or_else(|| corrupt_err!("Bloom filter header has a union without members"))
+}
+
+/// Writes union field `id` with an empty structure as member `member`.
+fn write_union_member(prot: &mut TOutputProtocol, id: i16, member: i16) -> Result<()> {
+  prot.write_field_begin(&field_identifier(TType::Struct, id))?;
+  prot.write_struct_begin(&TStructIdentifier::new("Union"))?;
+  prot.write_field_begin(&field_identifier(TType::Struct, member))?;
+  prot.write_struct_begin(&TStructIdentifier::new("Member"))?;
+  prot.write_field_stop()?;
+  prot.write_struct_end()?;
+  prot.write_field_end()?;
+  prot.write_field_stop()?;
+  prot.write_struct_end()?;
+  prot.write_field_end()?;
+  Ok(())
+}
+
+fn field_identifier(field_type: TType, id: i16) -> TFieldIdentifier {
+  TFieldIdentifier { name: None, field_type: field_type, id: Some(id) }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::error::Error;
+  use std::io::Cursor;
+  use std::rc::Rc;
+
+  use basic::Type;
+  use data_type::{Int32Type, Int64Type};
+  use file::metadata::{FileMetaData, RowGroupMetaData};
+  use file::statistics::Statistics;
+  use schema::types::{SchemaDescriptor, Type as SchemaType};
+
+  #[test]
+  fn test_sbbf_insert_check() {
+    let mut bloom_filter = Sbbf::new(1000);
+    assert_eq!(bloom_filter.num_bytes(), 1024);
+    for i in 0..100 {
+      bloom_filter.insert(&i);
+    }
+    for i in 0..100 {
+      assert!(bloom_filter.check(&i));
+    }
+    let false_positives = (100..1100).filter(|i| bloom_filter.check(i)).count();
+    assert!(false_positives < 20, "{} false positives", false_positives);
+
+    assert_eq!(Sbbf::new(0).num_bytes(), 32);
+    assert_eq!(Sbbf::new(usize::max_value()).num_bytes(), MAX_NUM_BYTES);
+  }
+
+  #[test]
+  fn test_sbbf_from_bytes_invalid_size() {
+    let res = Sbbf::from_bytes(&[0; 20]);
+    assert_eq!(res.unwrap_err().description(), "Invalid bloom filter size 20");
+  }
+
+  #[test]
+  fn test_read_bloom_filter() {
+    let mut bloom_filter = Sbbf::new(64);
+    bloom_filter.insert(&"parquet");
+    let mut buf = vec![1, 2, 3];
+    bloom_filter.write(&mut buf).unwrap();
+    let length = buf.len() as i32 - 3;
+
+    let column = get_column_chunk(Some(3), Some(length), None);
+    let res = read_bloom_filter(&mut Cursor::new(&buf[..]), &column).unwrap();
+    assert_eq!(res, Some(bloom_filter.clone()));
+    assert!(res.unwrap().check(&"parquet"));
+
+    let column = get_column_chunk(Some(3), None, None);
+    let res = read_bloom_filter(&mut Cursor::new(&buf[..]), &column).unwrap();
+    assert_eq!(res, Some(bloom_filter));
+
+    let column = get_column_chunk(None, None, None);
+    let res = read_bloom_filter(&mut Cursor::new(&buf[..]), &column).unwrap();
+    assert_eq!(res, None);
+
+    let column = get_column_chunk(Some(3), Some(length + 1), None);
+    let res = read_bloom_filter(&mut Cursor::new(&buf[..]), &column);
+    assert_eq!(
+      res.unwrap_err().description(),
+      format!("Bloom filter is out of bounds: offset 3, size {}", length + 1)
+    );
+
+    let column = get_column_chunk(Some(3), Some(length - 1), None);
+    let res = read_bloom_filter(&mut Cursor::new(&buf[..]), &column);
+    assert_eq!(
+      res.unwrap_err().description(),
+      "Bloom filter size 64 exceeds its bounds"
+    );
+  }
+
+  #[test]
+  fn test_prune_row_groups_by_values() {
+    let mut buf = vec![];
+    let mut bloom_filter_offsets = vec![];
+    for values in &[vec![1, 3, 5], vec![10, 20, 30]] {
+      let mut bloom_filter = Sbbf::new(1024);
+      for value in values {
+        bloom_filter.insert(value);
+      }
+      bloom_filter_offsets.push(buf.len() as i64);
+      bloom_filter.write(&mut buf).unwrap();
+    }
+
+    let schema_descr = get_schema_descr();
+    let columns = vec![
+      // Statistics and bloom filter
+      get_column_chunk(
+        Some(bloom_filter_offsets[0]),
+        None,
+        Some(Statistics::int32(Some(1), Some(5), None, 0, false))
+      ),
+      get_column_chunk(
+        Some(bloom_filter_offsets[1]),
+        None,
+        Some(Statistics::int32(Some(10), Some(30), None, 0, false))
+      ),
+      // Neither statistics nor bloom filter
+      get_column_chunk(None, None, None),
+      // Statistics only
+      get_column_chunk(
+        None,
+        None,
+        Some(Statistics::int32(Some(0), Some(100), None, 0, false))
+      )
+    ];
+    let row_groups = columns.into_iter().map(|column| {
+      Rc::new(RowGroupMetaData::builder(schema_descr.clone())
+        .set_column_metadata(vec![Rc::new(column)])
+        .build()
+        .unwrap())
+    }).collect();
+    let file_metadata = FileMetaData::new(
+      1, 0, None, schema_descr.root_schema_ptr(), schema_descr, None);
+    let metadata = ParquetMetaData::new(file_metadata, row_groups);
+
+    let prune = |values: &[i32]| {
+      let mut reader = Cursor::new(&buf[..]);
+      prune_row_groups_by_values::<Int32Type, _>(&mut reader, &metadata, 0, values)
+        .unwrap()
+    };
+    assert_eq!(prune(&[3]), vec![0, 2, 3]);
+    assert_eq!(prune(&[20]), vec![1, 2, 3]);
+    assert_eq!(prune(&[3, 20]), vec![0, 1, 2, 3]);
+    // Ruled out by bloom filter
+    assert_eq!(prune(&[2, 4]), vec![2, 3]);
+    // Ruled out by statistics
+    assert_eq!(prune(&[7]), vec![2, 3]);
+    assert_eq!(prune(&[200]), vec![2]);
+    assert_eq!(prune(&[]), Vec::<usize>::new());
+
+    let mut reader = Cursor::new(&buf[..]);
+    let res = prune_row_groups_by_values::<Int64Type, _>(&mut reader, &metadata, 0, &[1]);
+    assert_eq!(
+      res.unwrap_err().description(),
+      "Cannot prune row groups by values of type INT64, column \"a\" has type INT32"
+    );
+    let res = prune_row_groups_by_values::<Int32Type, _>(&mut reader, &metadata, 1, &[1]);
+    assert_eq!(
+      res.unwrap_err().description(),
+      "Invalid column index 1, file has 1 columns"
+    );
+  }
+
+  fn get_schema_descr() -> Rc<SchemaDescriptor> {
+    let schema = SchemaType::group_type_builder("schema")
+      .with_fields(&mut vec![
+        Rc::new(SchemaType::primitive_type_builder("a", Type::INT32).build().unwrap())
+      ])
+      .build()
+      .unwrap();
+    Rc::new(SchemaDescriptor::new(Rc::new(schema)))
+  }
+
+  fn get_column_chunk(
+    bloom_filter_offset: Option<i64>,
+    bloom_filter_length: Option<i32>,
+    statistics: Option<Statistics>
+  ) -> ColumnChunkMetaData {
+    let mut builder = ColumnChunkMetaData::builder(get_schema_descr().column(0))
+      .set_bloom_filter_offset(bloom_filter_offset)
+      .set_bloom_filter_length(bloom_filter_length);
+    if let Some(statistics) = statistics {
+      builder = builder.set_statistics(statistics);
+    }
+    builder.build().unwrap()
+  }
+}
@@ -0,0 +1,920 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains API to convert CSV data into a Parquet file with a provided schema.
+//!
+//! Schema must be a flat message type, where each field is a primitive `REQUIRED` or
+//! `OPTIONAL` field. Values are parsed according to physical and logical type of the
+//! field:
+//!
+//! - `BOOLEAN` fields accept `true`, `false`, `1` and `0`, case-insensitive.
+//! - `INT32` and `INT64` fields accept integers, unsigned logical types accept values up
+//! to the max unsigned value of the type.
+//! - `DATE` fields are parsed with the date format, `TIME` fields of any unit with the
+//! time format, and `TIMESTAMP` fields of any unit accept either an integer number of
+//! units since the Unix epoch or a value in one of the timestamp formats, see
+//! [`CsvOptions`]. Timestamps without time zone are in UTC.
+//! - `FLOAT` and `DOUBLE` fields accept floating point numbers.
+//! - `BYTE_ARRAY` fields accept any value, including `ENUM`, `JSON` and `BSON` fields,
+//! which are written as they are. `JSON` values must be valid JSON documents with
+//! `serde_json` feature. `FIXED_LEN_BYTE_ARRAY` fields accept values of the type length
+//! in bytes.
+//!
+//! `INT96` and `DECIMAL` fields are not supported.
+//!
+//! Fields are matched to CSV columns by name when CSV data has a header, otherwise by
+//! position. Only one row group of parsed values is kept in memory, see
+//! [`CsvOptions::with_row_group_size`].
+//!
+//! CSV data is parsed according to RFC 4180: fields can be quoted with `"`, quotes in
+//! quoted fields are escaped by doubling them, and quoted fields can contain
+//! delimiters and line breaks. Lines can end with either `\n` or `\r\n`, empty lines are
+//! skipped.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use std::rc::Rc;
+//! use parquet::file::csv::{csv_to_parquet, CsvOptions};
+//! use parquet::file::properties::WriterProperties;
+//! use parquet::schema::parser::parse_message_type;
+//! # use std::fs;
+//! # let path = std::env::temp_dir().join("csv_example.parquet");
+//!
+//! let data = "id,name,created\n1,alice,2019-01-01 10:00:00\n2,,2019-01-02 12:30:00\n";
+//! let schema = parse_message_type("
+//!   message schema {
+//!     REQUIRED INT64 id;
+//!     OPTIONAL BYTE_ARRAY name (UTF8);
+//!     OPTIONAL INT64 created (TIMESTAMP_MILLIS);
+//!   }
+//! ").unwrap();
+//! let props = Rc::new(WriterProperties::builder().build());
+//! let options = CsvOptions::new();
+//!
+//! let output = File::create(&path).unwrap();
+//! let num_rows =
+//!   csv_to_parquet(data.as_bytes(), output, Rc::new(schema), props, &options).unwrap();
+//! assert_eq!(num_rows, 2);
+//! # fs::remove_file(&path).unwrap();
+//! ```
+
+use std::io::{BufRead, BufReader, Read, Write};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+
+use basic::{LogicalType, Repetition, TimeUnit, Type as PhysicalType};
+use column::writer::ColumnWriter;
+use data_type::{date_to_days, ByteArray};
+use errors::{ParquetError, Result};
+use file::properties::WriterPropertiesPtr;
+use file::writer::{FileWriter, SerializedFileWriter};
+use schema::types::{ColumnDescPtr, SchemaDescriptor, TypePtr};
+#[cfg(feature = "serde_json")]
+use serde_json;
+
+/// Default number of rows of each row group.
+const DEFAULT_ROW_GROUP_SIZE: usize = 64 * 1024;
+/// Default format of `DATE` values.
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+/// Default format of `TIME` values.
+const DEFAULT_TIME_FORMAT: &str = "%H:%M:%S%.f";
+/// Default formats of `TIMESTAMP` values.
+const DEFAULT_TIMESTAMP_FORMATS: [&str; 4] = [
+  "%Y-%m-%d %H:%M:%S%.f",
+  "%Y-%m-%dT%H:%M:%S%.f",
+  "%Y-%m-%d %H:%M:%S%.f%:z",
+  "%Y-%m-%dT%H:%M:%S%.f%:z"
+];
+
+/// Options of [`csv_to_parquet`].
+pub struct CsvOptions {
+  delimiter: u8,
+  has_header: bool,
+  null_tokens: Vec<String>,
+  date_format: String,
+  time_format: String,
+  timestamp_formats: Vec<String>,
+  row_group_size: usize
+}
+
+impl CsvOptions {
+  /// Creates default options: fields are delimited by `,`, first line is a header,
+  /// empty fields are nulls, and row groups have 65536 rows.
+  pub fn new() -> Self {
+    Self {
+      delimiter: b',',
+      has_header: true,
+      null_tokens: vec![String::new()],
+      date_format: DEFAULT_DATE_FORMAT.to_owned(),
+      time_format: DEFAULT_TIME_FORMAT.to_owned(),
+      timestamp_formats:
+        DEFAULT_TIMESTAMP_FORMATS.iter().map(|f| f.to_string()).collect(),
+      row_group_size: DEFAULT_ROW_GROUP_SIZE
+    }
+  }
+
+  /// Sets delimiter of fields, e.g. `b'\t'` for TSV data.
+  pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+    self.delimiter = delimiter;
+    self
+  }
+
+  /// Sets whether or not the first line of CSV data is a header with column names.
+  pub fn with_header(mut self, has_header: bool) -> Self {
+    self.has_header = has_header;
+    self
+  }
+
+  /// Sets values that are parsed as nulls, e.g. `NULL` or `\N`. By default only empty
+  /// fields are nulls, use an empty list to parse empty fields as empty strings.
+  pub fn with_null_tokens(mut self, tokens: Vec<String>) -> Self {
+    self.null_tokens = tokens;
+    self
+  }
+
+  /// Sets `chrono` format of `DATE` values, default is `%Y-%m-%d`.
+  pub fn with_date_format(mut self, format: String) -> Self {
+    self.date_format = format;
+    self
+  }
+
+  /// Sets `chrono` format of `TIME` values of any unit, default is `%H:%M:%S%.f`.
+  pub fn with_time_format(mut self, format: String) -> Self {
+    self.time_format = format;
+    self
+  }
+
+  /// Sets `chrono` formats of `TIMESTAMP` values of any unit, which are tried in order.
+  /// Formats with `%z` or `%:z` parse timestamps with time zone offset. Default formats
+  /// are `%Y-%m-%d %H:%M:%S%.f` and `%Y-%m-%dT%H:%M:%S%.f`, with and without `%:z`.
+  pub fn with_timestamp_formats(mut self, formats: Vec<String>) -> Self {
+    self.timestamp_formats = formats;
+    self
+  }
+
+  /// Sets number of rows of each row group, the last row group can be smaller. Parsed
+  /// values of a row group are buffered in memory before they are written.
+  pub fn with_row_group_size(mut self, num_rows: usize) -> Self {
+    self.row_group_size = num_rows;
+    self
+  }
+}
+
+impl Default for CsvOptions {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Reads CSV data from `input`, and writes it to `sink` as Parquet file with `schema`
+/// and writer properties `props`. Returns number of rows written.
+///
+/// Returns error if schema is not supported, if CSV data does not have a column of the
+/// schema or has a line with a different number of fields, or if a value cannot be
+/// parsed. Errors of CSV data contain line number.
+pub fn csv_to_parquet<R, W>(
+  input: R,
+  sink: W,
+  schema: TypePtr,
+  props: WriterPropertiesPtr,
+  options: &CsvOptions
+) -> Result<i64>
+  where R: Read,
+        W: 'static + Write {
+  if options.row_group_size == 0 {
+    return Err(general_err!("Row group size must be positive"));
+  }
+  let schema_descr = SchemaDescriptor::new(schema.clone());
+  for field in schema.get_fields() {
+    let info = field.get_basic_info();
+    if !field.is_primitive() || info.repetition() == Repetition::REPEATED {
+      return Err(nyi_err!(
+        "Field {} is not supported, only primitive non-repeated fields can be converted \
+         from CSV",
+        field.name()
+      ));
+    }
+  }
+  let mut columns = Vec::with_capacity(schema_descr.num_columns());
+  for (i, descr) in schema_descr.columns().iter().enumerate() {
+    columns.push(CsvColumn::new(descr.clone(), i)?);
+  }
+
+  let mut reader = CsvReader::new(BufReader::new(input), options.delimiter);
+  let mut fields = Vec::new();
+  let num_fields = if options.has_header {
+    if reader.read_record(&mut fields)?.is_none() {
+      return Err(general_err!("CSV data does not have a header"));
+    }
+    for column in columns.iter_mut() {
+      match fields.iter().position(|name| name.trim() == column.descr.name()) {
+        Some(pos) => column.field_index = pos,
+        None => {
+          return Err(general_err!(
+            "Column {} is not found in CSV header",
+            column.descr.name()
+          ));
+        }
+      }
+    }
+    Some(fields.len())
+  } else {
+    None
+  };
+
+  let mut writer = SerializedFileWriter::new(sink, schema, props)?;
+  let mut num_rows = 0;
+  let mut row_group_rows = 0;
+  while let Some(line) = reader.read_record(&mut fields)? {
+    let expected = num_fields.unwrap_or_else(|| columns.len());
+    if fields.len() != expected {
+      return Err(general_err!(
+        "Line {} has {} fields, expected {}",
+        line,
+        fields.len(),
+        expected
+      ));
+    }
+    for column in columns.iter_mut() {
+      let value = &fields[column.field_index];
+      if options.null_tokens.iter().any(|token| token == value) {
+        column.push_null(line)?;
+      } else {
+        column.push_value(value, options, line)?;
+      }
+    }
+    row_group_rows += 1;
+    if row_group_rows == options.row_group_size {
+      write_row_group(&mut writer, &mut columns)?;
+      num_rows += row_group_rows as i64;
+      row_group_rows = 0;
+    }
+  }
+  if row_group_rows > 0 {
+    write_row_group(&mut writer, &mut columns)?;
+    num_rows += row_group_rows as i64;
+  }
+  writer.close()?;
+  Ok(num_rows)
+}
+
+/// Writes buffered values of `columns` as a row group, buffers are cleared.
+fn write_row_group<W: 'static + Write>(
+  writer: &mut SerializedFileWriter<W>,
+  columns: &mut [CsvColumn]
+) -> Result<()> {
+  let mut row_group_writer = writer.next_row_group()?;
+  for column in columns.iter_mut() {
+    let mut column_writer = match row_group_writer.next_column()? {
+      Some(column_writer) => column_writer,
+      None => return Err(general_err!("Missing column writer"))
+    };
+    column.write(&mut column_writer)?;
+    row_group_writer.close_column(column_writer)?;
+  }
+  writer.close_row_group(row_group_writer)
+}
+
+// ----------------------------------------------------------------------
+// Parsing and buffering of values
+
+/// Values of a column buffered before they are written.
+enum ColumnValues {
+  Bool(Vec<bool>),
+  Int32(Vec<i32>),
+  Int64(Vec<i64>),
+  Float(Vec<f32>),
+  Double(Vec<f64>),
+  ByteArray(Vec<ByteArray>),
+  FixedLenByteArray(Vec<ByteArray>)
+}
+
+/// Column of the schema with values parsed from a CSV column.
+struct CsvColumn {
+  descr: ColumnDescPtr,
+  field_index: usize,
+  values: ColumnValues,
+  def_levels: Vec<i16>
+}
+
+impl CsvColumn {
+  /// Creates column with `descr`, which is read from CSV column `field_index`.
+  /// Returns error if type of the column is not supported.
+  fn new(descr: ColumnDescPtr, field_index: usize) -> Result<Self> {
+    if descr.logical_type() == LogicalType::DECIMAL {
+      return Err(nyi_err!(
+        "Column {} of DECIMAL type is not supported for CSV data",
+        descr.name()
+      ));
+    }
+    let values = match descr.physical_type() {
+      PhysicalType::BOOLEAN => ColumnValues::Bool(Vec::new()),
+      PhysicalType::INT32 => ColumnValues::Int32(Vec::new()),
+      PhysicalType::INT64 => ColumnValues::Int64(Vec::new()),
+      PhysicalType::FLOAT => ColumnValues::Float(Vec::new()),
+      PhysicalType::DOUBLE => ColumnValues::Double(Vec::new()),
+      PhysicalType::BYTE_ARRAY => ColumnValues::ByteArray(Vec::new()),
+      PhysicalType::FIXED_LEN_BYTE_ARRAY => ColumnValues::FixedLenByteArray(Vec::new()),
+      PhysicalType::INT96 => {
+        return Err(nyi_err!(
+          "Column {} of INT96 type is not supported for CSV data",
+          descr.name()
+        ));
+      }
+    };
+    Ok(Self {
+      descr: descr,
+      field_index: field_index,
+      values: values,
+      def_levels: Vec::new()
+    })
+  }
+
+  /// Appends null value, returns error if column is required.
+  fn push_null(&mut self, line: usize) -> Result<()> {
+    if self.descr.max_def_level() == 0 {
+      return Err(general_err!(
+        "Value of required column {} is null at line {}",
+        self.descr.name(),
+        line
+      ));
+    }
+    self.def_levels.push(0);
+    Ok(())
+  }
+
+  /// Parses and appends non-null `value` of CSV data at `line`.
+  fn push_value(&mut self, value: &str, options: &CsvOptions, line: usize) -> Result<()> {
+    let logical_type = self.descr.logical_type();
+    let type_length = self.descr.type_length();
+    let res = match self.values {
+      ColumnValues::Bool(ref mut values) => {
+        parse_bool(value).map(|v| values.push(v))
+      },
+      ColumnValues::Int32(ref mut values) => {
+        parse_int32(value, logical_type, options).map(|v| values.push(v))
+      },
+      ColumnValues::Int64(ref mut values) => {
+        parse_int64(value, logical_type, options).map(|v| values.push(v))
+      },
+      ColumnValues::Float(ref mut values) => {
+        value.trim().parse().map(|v| values.push(v)).map_err(|e| e.to_string())
+      },
+      ColumnValues::Double(ref mut values) => {
+        value.trim().parse().map(|v| values.push(v)).map_err(|e| e.to_string())
+      },
+      ColumnValues::ByteArray(ref mut values) => {
+        validate_byte_array(value, logical_type)
+          .map(|_| values.push(ByteArray::from(value.as_bytes().to_vec())))
+      },
+      ColumnValues::FixedLenByteArray(ref mut values) => {
+        if value.len() == type_length as usize {
+          values.push(ByteArray::from(value.as_bytes().to_vec()));
+          Ok(())
+        } else {
+          Err(format!("length is {}, expected {}", value.len(), type_length))
+        }
+      }
+    };
+    if let Err(message) = res {
+      return Err(general_err!(
+        "Could not parse value {:?} of column {} at line {}: {}",
+        value,
+        self.descr.name(),
+        line,
+        message
+      ));
+    }
+    if self.descr.max_def_level() > 0 {
+      self.def_levels.push(1);
+    }
+    Ok(())
+  }
+
+  /// Writes buffered values with `column_writer` and clears buffers.
+  fn write(&mut self, column_writer: &mut ColumnWriter) -> Result<()> {
+    let def_levels = if self.descr.max_def_level() > 0 {
+      Some(&self.def_levels[..])
+    } else {
+      None
+    };
+    match (&mut self.values, column_writer) {
+      (&mut ColumnValues::Bool(ref mut values),
+          &mut ColumnWriter::BoolColumnWriter(ref mut typed)) => {
+        typed.write_batch(values, def_levels, None)?;
+        values.clear();
+      },
+      (&mut ColumnValues::Int32(ref mut values),
+          &mut ColumnWriter::Int32ColumnWriter(ref mut typed)) => {
+        typed.write_batch(values, def_levels, None)?;
+        values.clear();
+      },
+      (&mut ColumnValues::Int64(ref mut values),
+          &mut ColumnWriter::Int64ColumnWriter(ref mut typed)) => {
+        typed.write_batch(values, def_levels, None)?;
+        values.clear();
+      },
+      (&mut ColumnValues::Float(ref mut values),
+          &mut ColumnWriter::FloatColumnWriter(ref mut typed)) => {
+        typed.write_batch(values, def_levels, None)?;
+        values.clear();
+      },
+      (&mut ColumnValues::Double(ref mut values),
+          &mut ColumnWriter::DoubleColumnWriter(ref mut typed)) => {
+        typed.write_batch(values, def_levels, None)?;
+        values.clear();
+      },
+      (&mut ColumnValues::ByteArray(ref mut values),
+          &mut ColumnWriter::ByteArrayColumnWriter(ref mut typed)) => {
+        typed.write_batch(values, def_levels, None)?;
+        values.clear();
+      },
+      (&mut ColumnValues::FixedLenByteArray(ref mut values),
+          &mut ColumnWriter::FixedLenByteArrayColumnWriter(ref mut typed)) => {
+        typed.write_batch(values, def_levels, None)?;
+        values.clear();
+      },
+      _ => return Err(general_err!("Column writer does not match column values"))
+    }
+    self.def_levels.clear();
+    Ok(())
+  }
+}
+
+/// Checks that `value` of JSON field is a valid JSON document.
+#[cfg(feature = "serde_json")]
+fn validate_byte_array(
+  value: &str,
+  logical_type: LogicalType
+) -> ::std::result::Result<(), String> {
+  if logical_type != LogicalType::JSON {
+    return Ok(());
+  }
+  serde_json::from_str::<serde_json::Value>(value).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Values of BYTE_ARRAY fields are not validated without `serde_json` feature.
+#[cfg(not(feature = "serde_json"))]
+fn validate_byte_array(
+  _value: &str,
+  _logical_type: LogicalType
+) -> ::std::result::Result<(), String> {
+  Ok(())
+}
+
+fn parse_bool(value: &str) -> ::std::result::Result<bool, String> {
+  let value = value.trim();
+  if value.eq_ignore_ascii_case("true") || value == "1" {
+    Ok(true)
+  } else if value.eq_ignore_ascii_case("false") || value == "0" {
+    Ok(false)
+  } else {
+    Err("expected true or false".to_owned())
+  }
+}
+
+fn parse_int32(
+  value: &str,
+  logical_type: LogicalType,
+  options: &CsvOptions
+) -> ::std::result::Result<i32, String> {
+  let value = value.trim();
+  if let Some((unit, _)) = logical_type.time_unit() {
+    return parse_time(value, unit, options).map(|v| v as i32);
+  }
+  match logical_type {
+    LogicalType::DATE => {
+      let date = NaiveDate::parse_from_str(value, &options.date_format)
+        .map_err(|e| e.to_string())?;
+      Ok(date_to_days(date))
+    },
+    LogicalType::UINT_8 | LogicalType::UINT_16 | LogicalType::UINT_32 => {
+      value.parse::<u32>().map(|v| v as i32).map_err(|e| e.to_string())
+    },
+    _ => value.parse::<i32>().map_err(|e| e.to_string())
+  }
+}
+
+fn parse_int64(
+  value: &str,
+  logical_type: LogicalType,
+  options: &CsvOptions
+) -> ::std::result::Result<i64, String> {
+  let value = value.trim();
+  if let Some((unit, _)) = logical_type.timestamp_unit() {
+    if let Ok(v) = value.parse() {
+      return Ok(v);
+    }
+    let datetime = parse_timestamp(value, &options.timestamp_formats)?;
+    let duration = datetime.signed_duration_since(unix_epoch());
+    let units = match unit {
+      TimeUnit::MILLIS => Some(duration.num_milliseconds()),
+      TimeUnit::MICROS => duration.num_microseconds(),
+      TimeUnit::NANOS => duration.num_nanoseconds()
+    };
+    return units.ok_or_else(|| "timestamp is out of range".to_owned());
+  }
+  if let Some((unit, _)) = logical_type.time_unit() {
+    return parse_time(value, unit, options);
+  }
+  match logical_type {
+    LogicalType::UINT_64 => {
+      value.parse::<u64>().map(|v| v as i64).map_err(|e| e.to_string())
+    },
+    _ => value.parse::<i64>().map_err(|e| e.to_string())
+  }
+}
+
+/// Parses time with the time format, returns number of `unit`s since midnight.
+fn parse_time(
+  value: &str,
+  unit: TimeUnit,
+  options: &CsvOptions
+) -> ::std::result::Result<i64, String> {
+  let time = NaiveTime::parse_from_str(value, &options.time_format)
+    .map_err(|e| e.to_string())?;
+  let duration = time.signed_duration_since(unix_epoch().time());
+  let units = match unit {
+    TimeUnit::MILLIS => Some(duration.num_milliseconds()),
+    TimeUnit::MICROS => duration.num_microseconds(),
+    TimeUnit::NANOS => duration.num_nanoseconds()
+  };
+  units.ok_or_else(|| "time is out of range".to_owned())
+}
+
+/// Parses timestamp with the first matching format, returns timestamp in UTC.
+fn parse_timestamp(
+  value: &str,
+  formats: &[String]
+) -> ::std::result::Result<NaiveDateTime, String> {
+  for format in formats {
+    let res = if format.contains("%z") || format.contains("%:z") {
+      DateTime::parse_from_str(value, format).map(|v| v.naive_utc())
+    } else {
+      NaiveDateTime::parse_from_str(value, format)
+    };
+    if let Ok(datetime) = res {
+      return Ok(datetime);
+    }
+  }
+  Err(format!("expected timestamp in one of formats {}", formats.join(", ")))
+}
+
+fn unix_epoch() -> NaiveDateTime {
+  NaiveDate::from_ymd_opt(1970, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap()
+}
+
+// ----------------------------------------------------------------------
+// CSV records
+
+/// Reader of CSV records, see module documentation for the supported format.
+struct CsvReader<R: BufRead> {
+  input: R,
+  delimiter: u8,
+  line: usize,
+  buf: Vec<u8>,
+  field: Vec<u8>
+}
+
+impl<R: BufRead> CsvReader<R> {
+  fn new(input: R, delimiter: u8) -> Self {
+    Self { input: input, delimiter: delimiter, line: 0, buf: vec![], field: vec![] }
+  }
+
+  /// Reads next non-empty record into `fields`, and returns line number where the
+  /// record starts, or `None` when there are no records left.
+  fn read_record(&mut self, fields: &mut Vec<String>) -> Result<Option<usize>> {
+    let line = loop {
+      self.buf.clear();
+      let line = self.line + 1;
+      // Line breaks in quoted fields continue the record
+      let mut num_quotes = 0;
+      loop {
+        let start = self.buf.len();
+        if self.input.read_until(b'\n', &mut self.buf)? == 0 {
+          if self.buf.is_empty() {
+            return Ok(None);
+          }
+          return Err(eof_err!("Quoted field is not terminated at line {}", line));
+        }
+        self.line += 1;
+        num_quotes += self.buf[start..].iter().filter(|b| **b == b'"').count();
+        if num_quotes % 2 == 0 {
+          break;
+        }
+      }
+      if self.buf.ends_with(b"\n") {
+        self.buf.pop();
+        if self.buf.ends_with(b"\r") {
+          self.buf.pop();
+        }
+      }
+      if !self.buf.is_empty() {
+        break line;
+      }
+    };
+
+    fields.clear();
+    let mut in_quotes = false;
+    let mut i = 0;
+    while i < self.buf.len() {
+      let b = self.buf[i];
+      if in_quotes {
+        if b != b'"' {
+          self.field.push(b);
+        } else if self.buf.get(i + 1) == Some(&b'"') {
+          self.field.push(b'"');
+          i += 1;
+        } else {
+          in_quotes = false;
+        }
+      } else if b == b'"' {
+        in_quotes = true;
+      } else if b == self.delimiter {
+        fields.push(self.take_field(line)?);
+      } else {
+        self.field.push(b);
+      }
+      i += 1;
+    }
+    fields.push(self.take_field(line)?);
+    Ok(Some(line))
+  }
+
+  /// Returns current field as string and clears it.
+  fn take_field(&mut self, line: usize) -> Result<String> {
+    let field = String::from_utf8(self.field.clone())
+      .map_err(|_| general_err!("Invalid UTF-8 data at line {}", line))?;
+    self.field.clear();
+    Ok(field)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::fs::File;
+  use std::rc::Rc;
+
+  use column::reader::get_typed_column_reader;
+  use data_type::*;
+  use file::properties::WriterProperties;
+  use file::reader::{FileReader, SerializedFileReader};
+  use schema::parser::parse_message_type;
+  use util::test_common::get_temp_file;
+
+  /// Converts `data` with `message_type` schema into temp file `file_name`, returns
+  /// reader of the file.
+  fn convert(
+    data: &str,
+    message_type: &str,
+    file_name: &str,
+    options: &CsvOptions
+  ) -> Result<SerializedFileReader<File>> {
+    let schema = Rc::new(parse_message_type(message_type).unwrap());
+    let props = Rc::new(WriterProperties::builder().build());
+    let file = get_temp_file(file_name, &[]);
+    csv_to_parquet(data.as_bytes(), file.try_clone().unwrap(), schema, props, options)?;
+    SerializedFileReader::new(file)
+  }
+
+  /// Returns values and definition levels of column `i` of the first row group,
+  /// definition levels are empty for required columns.
+  fn read_column<T: DataType>(reader: &FileReader, i: usize) -> (Vec<T::T>, Vec<i16>) {
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    let num_rows = row_group_reader.metadata().num_rows() as usize;
+    let max_def_level =
+      row_group_reader.metadata().column(i).column_descr().max_def_level();
+    let mut column_reader =
+      get_typed_column_reader::<T>(row_group_reader.get_column_reader(i).unwrap());
+    let mut values = vec![T::T::default(); num_rows];
+    let mut def_levels = vec![0; num_rows];
+    let (values_read, levels_read) = if max_def_level > 0 {
+      column_reader.read_batch(num_rows, Some(&mut def_levels), None, &mut values)
+    } else {
+      column_reader.read_batch(num_rows, None, None, &mut values)
+    }.unwrap();
+    values.truncate(values_read);
+    def_levels.truncate(levels_read);
+    (values, def_levels)
+  }
+
+  #[test]
+  fn test_csv_to_parquet_types() {
+    let data = "\
+      flag,int,uint,long,float,double,name,code,day,time,ts_millis,ts_micros\n\
+      true,1,4294967295,-1,1.5,2.25,a,AB,2019-01-02,00:00:01.5,1970-01-01 00:00:01,10\n\
+      0,,1,9000000000,-0.5,,\"b,\"\"c\"\"\",CD,,12:00:00,\
+      2019-01-01T00:00:00+01:00,1970-01-01 00:00:00.000002\n";
+    let message_type = "
+      message schema {
+        REQUIRED BOOLEAN flag;
+        OPTIONAL INT32 int;
+        REQUIRED INT32 uint (UINT_32);
+        REQUIRED INT64 long;
+        REQUIRED FLOAT float;
+        OPTIONAL DOUBLE double;
+        REQUIRED BYTE_ARRAY name (UTF8);
+        REQUIRED FIXED_LEN_BYTE_ARRAY (2) code;
+        OPTIONAL INT32 day (DATE);
+        REQUIRED INT32 time (TIME_MILLIS);
+        REQUIRED INT64 ts_millis (TIMESTAMP_MILLIS);
+        REQUIRED INT64 ts_micros (TIMESTAMP_MICROS);
+      }
+    ";
+    let reader = convert(data, message_type, "csv_types", &CsvOptions::new()).unwrap();
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+
+    assert_eq!(read_column::<BoolType>(&reader, 0).0, vec![true, false]);
+    assert_eq!(read_column::<Int32Type>(&reader, 1), (vec![1], vec![1, 0]));
+    assert_eq!(read_column::<Int32Type>(&reader, 2).0, vec![-1, 1]);
+    assert_eq!(read_column::<Int64Type>(&reader, 3).0, vec![-1, 9000000000]);
+    assert_eq!(read_column::<FloatType>(&reader, 4).0, vec![1.5, -0.5]);
+    assert_eq!(read_column::<DoubleType>(&reader, 5), (vec![2.25], vec![1, 0]));
+    assert_eq!(
+      read_column::<ByteArrayType>(&reader, 6).0,
+      vec![ByteArray::from("a"), ByteArray::from("b,\"c\"")]
+    );
+    assert_eq!(
+      read_column::<FixedLenByteArrayType>(&reader, 7).0,
+      vec![ByteArray::from("AB"), ByteArray::from("CD")]
+    );
+    assert_eq!(read_column::<Int32Type>(&reader, 8), (vec![17898], vec![1, 0]));
+    assert_eq!(read_column::<Int32Type>(&reader, 9).0, vec![1500, 43200000]);
+    assert_eq!(read_column::<Int64Type>(&reader, 10).0, vec![1000, 1546297200000]);
+    assert_eq!(read_column::<Int64Type>(&reader, 11).0, vec![10, 2]);
+  }
+
+  #[test]
+  fn test_csv_to_parquet_options() {
+    // Columns are matched by position, quoted field contains line break
+    let data = "1\tNULL\r\n\r\n2\t\"x\ny\"\r\n3\t\r\n";
+    let message_type = "
+      message schema {
+        REQUIRED INT32 a;
+        OPTIONAL BYTE_ARRAY b;
+      }
+    ";
+    let options = CsvOptions::new()
+      .with_delimiter(b'\t')
+      .with_header(false)
+      .with_null_tokens(vec!["NULL".to_owned()]);
+    let reader = convert(data, message_type, "csv_options", &options).unwrap();
+    assert_eq!(read_column::<Int32Type>(&reader, 0).0, vec![1, 2, 3]);
+    assert_eq!(
+      read_column::<ByteArrayType>(&reader, 1),
+      (vec![ByteArray::from("x\ny"), ByteArray::from("")], vec![0, 1, 1])
+    );
+
+    // Header columns are matched by name, in any order
+    let data = "c,b,a\n1,2,3\n4,5,6\n7,8,9\n";
+    let message_type = "
+      message schema {
+        REQUIRED INT32 a;
+        REQUIRED INT64 c;
+      }
+    ";
+    let options = CsvOptions::new().with_row_group_size(2);
+    let reader = convert(data, message_type, "csv_options", &options).unwrap();
+    assert_eq!(reader.num_row_groups(), 2);
+    assert_eq!(reader.metadata().row_group(0).num_rows(), 2);
+    assert_eq!(reader.metadata().row_group(1).num_rows(), 1);
+    assert_eq!(read_column::<Int32Type>(&reader, 0).0, vec![3, 6]);
+    assert_eq!(read_column::<Int64Type>(&reader, 1).0, vec![1, 4]);
+
+    let data = "day,ts,local_ts,time\n\
+      02/01/2019,2019.01.01 10:00,2019.01.01 10:00,10:00:01.5\n";
+    let message_type = "
+      message schema {
+        REQUIRED INT32 day (DATE);
+        REQUIRED INT64 ts (TIMESTAMP_MILLIS);
+        REQUIRED INT64 local_ts (TIMESTAMP(NANOS,false));
+        REQUIRED INT64 time (TIME(NANOS,false));
+      }
+    ";
+    let options = CsvOptions::new()
+      .with_date_format("%d/%m/%Y".to_owned())
+      .with_timestamp_formats(vec!["%Y.%m.%d %H:%M".to_owned()]);
+    let reader = convert(data, message_type, "csv_options", &options).unwrap();
+    assert_eq!(read_column::<Int32Type>(&reader, 0).0, vec![17898]);
+    assert_eq!(read_column::<Int64Type>(&reader, 1).0, vec![1546336800000]);
+    assert_eq!(read_column::<Int64Type>(&reader, 2).0, vec![1546336800000000000]);
+    assert_eq!(read_column::<Int64Type>(&reader, 3).0, vec![36001500000000]);
+  }
+
+  #[test]
+  fn test_csv_to_parquet_documents() {
+    let data = "kind,doc,raw\nsmall,\"{\"\"a\"\": 1}\",AAE=\n";
+    let message_type = "
+      message schema {
+        REQUIRED BYTE_ARRAY kind (ENUM);
+        REQUIRED BYTE_ARRAY doc (JSON);
+        REQUIRED BYTE_ARRAY raw (BSON);
+      }
+    ";
+    let options = CsvOptions::new();
+    let reader = convert(data, message_type, "csv_documents", &options).unwrap();
+    let file_metadata = reader.metadata().file_metadata();
+    let schema = file_metadata.schema_descr();
+    assert!(schema.column(0).is_string());
+    assert!(schema.column(1).is_document());
+    assert_eq!(schema.column(2).logical_type(), LogicalType::BSON);
+    let values = (0..3)
+      .map(|i| read_column::<ByteArrayType>(&reader, i).0.remove(0))
+      .collect::<Vec<_>>();
+    let expected = vec!["small", "{\"a\": 1}", "AAE="];
+    assert_eq!(values, expected.into_iter().map(ByteArray::from).collect::<Vec<_>>());
+  }
+
+  #[cfg(feature = "serde_json")]
+  #[test]
+  fn test_csv_to_parquet_invalid_json() {
+    let message_type = "
+      message schema {
+        REQUIRED BYTE_ARRAY doc (JSON);
+      }
+    ";
+    let options = CsvOptions::new();
+    assert_eq!(
+      convert("doc\n{a}\n", message_type, "csv_invalid_json", &options).err().unwrap(),
+      general_err!(
+        "Could not parse value \"{a}\" of column doc at line 2: key must be a string at \
+         line 1 column 2"
+      )
+    );
+  }
+
+  #[test]
+  fn test_csv_to_parquet_errors() {
+    let message_type = "
+      message schema {
+        REQUIRED INT32 a;
+        OPTIONAL BOOLEAN b;
+      }
+    ";
+    let options = CsvOptions::new();
+    let check_error = |data: &str, message: &str| {
+      assert_eq!(
+        convert(data, message_type, "csv_errors", &options).err().unwrap(),
+        general_err!(message)
+      );
+    };
+    check_error("", "CSV data does not have a header");
+    check_error("a,c\n", "Column b is not found in CSV header");
+    check_error("a,b\n1,true\n2\n", "Line 3 has 1 fields, expected 2");
+    check_error("a,b\n1,true\n,false\n", "Value of required column a is null at line 3");
+    check_error(
+      "a,b\n1,yes\n",
+      "Could not parse value \"yes\" of column b at line 2: expected true or false"
+    );
+    check_error(
+      "a,b\n1,true\n\n\n1.5,true\n",
+      "Could not parse value \"1.5\" of column a at line 5: invalid digit found in string"
+    );
+    assert_eq!(
+      convert("a,b\n1,\"true\n", message_type, "csv_errors", &options).err().unwrap(),
+      eof_err!("Quoted field is not terminated at line 2")
+    );
+
+    let message_type = "
+      message schema {
+        REQUIRED INT96 a;
+      }
+    ";
+    assert_eq!(
+      convert("a\n", message_type, "csv_errors", &options).err().unwrap(),
+      nyi_err!("Column a of INT96 type is not supported for CSV data")
+    );
+    let message_type = "
+      message schema {
+        REQUIRED group a {
+          REQUIRED INT32 b;
+        }
+      }
+    ";
+    assert_eq!(
+      convert("a\n", message_type, "csv_errors", &options).err().unwrap(),
+      nyi_err!(
+        "Field a is not supported, only primitive non-repeated fields can be converted \
+         from CSV"
+      )
+    );
+  }
+}
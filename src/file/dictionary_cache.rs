@@ -0,0 +1,198 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains cache of decoded dictionaries of column chunks.
+//!
+//! When the same column chunk is read several times by a row group reader, e.g. by a
+//! [`RowFilter`](../../record/filter/struct.RowFilter.html) that evaluates predicate on
+//! a few columns followed by the pass that reads matching rows, dictionary page of the
+//! column chunk is decoded by each column reader. [`DictionaryCache`] keeps decoded
+//! dictionaries of a row group, so that later column readers of the same column chunk
+//! reuse them.
+//!
+//! Cache is enabled with
+//! [`SerializedFileReader::with_dictionary_cache`](../reader/struct.SerializedFileReader.html),
+//! every row group reader then has its own cache, which is released together with the
+//! row group reader. Cached dictionaries are accounted in a memory tracker shared by all
+//! row group readers, dictionaries that do not fit into its limit are not cached.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use std::sync::Arc;
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//! use parquet::memory::MemTracker;
+//!
+//! let mem_tracker = Arc::new(MemTracker::with_limit(1024 * 1024));
+//! let file = File::open("data/alltypes_dictionary.parquet").unwrap();
+//! let reader = SerializedFileReader::new(file)
+//!   .unwrap()
+//!   .with_dictionary_cache(mem_tracker.clone());
+//!
+//! // Second pass over the row group reuses dictionaries decoded by the first one
+//! let row_group_reader = reader.get_row_group(0).unwrap();
+//! assert_eq!(row_group_reader.get_row_iter(None).unwrap().count(), 2);
+//! assert_eq!(row_group_reader.get_row_iter(None).unwrap().count(), 2);
+//! assert!(mem_tracker.memory_usage() > 0);
+//! ```
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use data_type::DataType;
+use util::memory::{BufferPtr, MemTrackerPtr};
+
+/// Reference counted pointer for [`DictionaryCache`].
+pub type DictionaryCachePtr = Rc<DictionaryCache>;
+
+/// Cache of decoded dictionaries of column chunks of a row group, identified by column
+/// index.
+pub struct DictionaryCache {
+  mem_tracker: MemTrackerPtr,
+  entries: RefCell<HashMap<usize, CacheEntry>>,
+  hits: Cell<u64>,
+  misses: Cell<u64>
+}
+
+/// Decoded dictionary of one column chunk.
+struct CacheEntry {
+  // Values of type `BufferPtr<T::T>`, where `T` is data type of the column
+  values: Box<Any>,
+  num_bytes: i64
+}
+
+impl DictionaryCache {
+  /// Creates dictionary cache that accounts cached dictionaries in `mem_tracker`.
+  /// Cache is bounded by the limit of `mem_tracker`, if set, and is unbounded
+  /// otherwise.
+  pub fn new(mem_tracker: MemTrackerPtr) -> Self {
+    Self {
+      mem_tracker: mem_tracker,
+      entries: RefCell::new(HashMap::new()),
+      hits: Cell::new(0),
+      misses: Cell::new(0)
+    }
+  }
+
+  /// Returns memory tracker of this cache.
+  pub fn mem_tracker(&self) -> &MemTrackerPtr {
+    &self.mem_tracker
+  }
+
+  /// Returns number of bytes of cached dictionaries.
+  pub fn memory_usage(&self) -> i64 {
+    self.entries.borrow().values().map(|entry| entry.num_bytes).sum()
+  }
+
+  /// Returns number of cached dictionaries.
+  pub fn num_entries(&self) -> usize {
+    self.entries.borrow().len()
+  }
+
+  /// Returns number of dictionaries served from the cache.
+  pub fn hits(&self) -> u64 {
+    self.hits.get()
+  }
+
+  /// Returns number of dictionaries that were not cached.
+  pub fn misses(&self) -> u64 {
+    self.misses.get()
+  }
+
+  /// Removes all cached dictionaries.
+  pub fn clear(&self) {
+    let mut entries = self.entries.borrow_mut();
+    for (_, entry) in entries.drain() {
+      self.mem_tracker.alloc(-entry.num_bytes);
+    }
+  }
+
+  /// Returns decoded dictionary of column `column`, if cached with the same data type.
+  pub fn get<T: DataType>(&self, column: usize) -> Option<BufferPtr<T::T>> {
+    let values = self.entries.borrow()
+      .get(&column)
+      .and_then(|entry| entry.values.downcast_ref::<BufferPtr<T::T>>())
+      .cloned();
+    match values {
+      Some(_) => self.hits.set(self.hits.get() + 1),
+      None => self.misses.set(self.misses.get() + 1)
+    }
+    values
+  }
+
+  /// Caches decoded dictionary `values` of column `column`, accounted as `num_bytes`
+  /// bytes. Dictionary is not cached if it does not fit into the limit of memory
+  /// tracker, or dictionary of the column is already cached.
+  pub fn insert<T: DataType>(
+    &self,
+    column: usize,
+    values: BufferPtr<T::T>,
+    num_bytes: i64
+  ) {
+    let mut entries = self.entries.borrow_mut();
+    if entries.contains_key(&column) || self.mem_tracker.try_alloc(num_bytes).is_err() {
+      return;
+    }
+    entries.insert(column, CacheEntry { values: Box::new(values), num_bytes: num_bytes });
+  }
+}
+
+impl Drop for DictionaryCache {
+  fn drop(&mut self) {
+    self.clear();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::sync::Arc;
+
+  use data_type::{Int32Type, Int64Type};
+  use util::memory::MemTracker;
+
+  #[test]
+  fn test_dictionary_cache() {
+    let mem_tracker = Arc::new(MemTracker::with_limit(100));
+    let cache = DictionaryCache::new(mem_tracker.clone());
+    assert!(cache.get::<Int32Type>(0).is_none());
+
+    cache.insert::<Int32Type>(0, BufferPtr::new(vec![1, 2, 3]), 60);
+    assert_eq!(cache.get::<Int32Type>(0).unwrap().data(), &[1, 2, 3]);
+    // Dictionary of a different data type is not returned
+    assert!(cache.get::<Int64Type>(0).is_none());
+    assert_eq!((cache.hits(), cache.misses()), (1, 2));
+
+    // Dictionaries that do not fit are not cached, cached ones are not replaced
+    cache.insert::<Int32Type>(1, BufferPtr::new(vec![4]), 50);
+    cache.insert::<Int32Type>(0, BufferPtr::new(vec![5]), 10);
+    assert!(cache.get::<Int32Type>(1).is_none());
+    assert_eq!(cache.get::<Int32Type>(0).unwrap().data(), &[1, 2, 3]);
+    assert_eq!(cache.num_entries(), 1);
+    assert_eq!(cache.memory_usage(), 60);
+    assert_eq!(mem_tracker.memory_usage(), 60);
+
+    cache.insert::<Int32Type>(1, BufferPtr::new(vec![4]), 40);
+    assert_eq!(cache.num_entries(), 2);
+    drop(cache);
+    assert_eq!(mem_tracker.memory_usage(), 0);
+  }
+}
@@ -24,7 +24,9 @@
 //! [`RowGroupMetaData`](struct.RowGroupMetaData.html) for each row group.
 //!
 //! [`FileMetaData`](struct.FileMetaData.html) includes file version, application specific
-//! metadata.
+//! metadata. Application that wrote the file is available as
+//! [`ApplicationVersion`](struct.ApplicationVersion.html), which is used to work around
+//! known issues of older writers.
 //!
 //! Each [`RowGroupMetaData`](struct.RowGroupMetaData.html) contains information about row
 //! group and one or more [`ColumnChunkMetaData`](struct.ColumnChunkMetaData.html) for
@@ -32,15 +34,44 @@
 //!
 //! [`ColumnChunkMetaData`](struct.ColumnChunkMetaData.html) has information about column
 //! chunk (primitive leaf column), including encoding/compression, number of values, etc.
+//!
+//! Metadata can be serialized with [`ParquetMetaData::to_bytes`] and restored with
+//! [`ParquetMetaData::from_bytes`], e.g. to cache footers of files in a catalog.
+//!
+//! [`ParquetMetaData::to_bytes`]: struct.ParquetMetaData.html#method.to_bytes
+//! [`ParquetMetaData::from_bytes`]: struct.ParquetMetaData.html#method.from_bytes
 
+use std::fs::File;
 use std::rc::Rc;
 
+use super::reader::{MetadataLimits, SerializedFileReader};
+use super::size_statistics::SizeStatistics;
 use super::statistics::{self, Statistics};
-use basic::{ColumnOrder, Compression, Encoding, Type};
+use basic::{ColumnOrder, Compression, Encoding, PageType, SortOrder, Type};
+use encryption::metadata::ColumnCryptoMetaData;
 use errors::{ParquetError, Result};
-use schema::types::{ColumnDescriptor, ColumnDescPtr, ColumnPath};
+use schema::types::{self, ColumnDescriptor, ColumnDescPtr, ColumnPath};
 use schema::types::{SchemaDescriptor, SchemaDescPtr, Type as SchemaType, TypePtr};
-use parquet_format::{ColumnChunk, ColumnMetaData, RowGroup};
+use parquet_format::{self, ColumnChunk, ColumnMetaData, RowGroup, TypeDefinedOrder};
+use thrift::protocol::{TCompactInputProtocol, TCompactOutputProtocol, TInputProtocol};
+use thrift::protocol::{TOutputProtocol, TType};
+use util::thrift::{TolerantOutputProtocol, UnknownEnums};
+
+/// Key-value pair of application specific metadata of a file, see
+/// [`FileMetaData::key_value_metadata`].
+///
+/// [`FileMetaData::key_value_metadata`]:
+/// struct.FileMetaData.html#method.key_value_metadata
+pub type KeyValue = parquet_format::KeyValue;
+
+/// Column that rows of a row group are sorted by, see
+/// [`RowGroupMetaData::sorting_columns`]. Contains index of the leaf column in the row
+/// group, and whether values are in descending order and nulls come before non-null
+/// values.
+///
+/// [`RowGroupMetaData::sorting_columns`]:
+/// struct.RowGroupMetaData.html#method.sorting_columns
+pub type SortingColumn = parquet_format::SortingColumn;
 
 /// Reference counted pointer for [`ParquetMetaData`].
 pub type ParquetMetaDataPtr = Rc<ParquetMetaData>;
@@ -84,6 +115,52 @@ impl ParquetMetaData {
   pub fn row_groups(&self) -> &[RowGroupMetaDataPtr] {
     &self.row_groups.as_slice()
   }
+
+  /// Converts metadata into Thrift definition of file metadata.
+  pub fn to_thrift(&self) -> Result<parquet_format::FileMetaData> {
+    let file_metadata = &self.file_metadata;
+    Ok(parquet_format::FileMetaData {
+      version: file_metadata.version(),
+      schema: types::to_thrift(file_metadata.schema())?,
+      num_rows: file_metadata.num_rows(),
+      row_groups: self.row_groups.iter().map(|v| v.to_thrift()).collect(),
+      key_value_metadata: file_metadata.key_value_metadata().cloned(),
+      created_by: file_metadata.created_by().clone(),
+      column_orders: file_metadata.column_orders()
+        .and_then(|orders| column_orders_to_thrift(orders))
+    })
+  }
+
+  /// Serializes metadata into Thrift compact encoding, the same encoding as in the
+  /// footer of a file. Metadata can be restored with [`from_bytes`](#method.from_bytes).
+  ///
+  /// Page indexes are stored outside of file metadata and are not serialized, offsets
+  /// of page indexes of column chunks are kept, so they can still be read from the file.
+  /// Fields unknown to this version of the crate are written as they were read.
+  pub fn to_bytes(&self) -> Result<Vec<u8>> {
+    let t_file_metadata = self.to_thrift()?;
+    let mut buf = Vec::new();
+    {
+      let mut unknown_enums =
+        types::to_thrift_unknown_enums(self.file_metadata.schema()).nest(&[2]);
+      unknown_enums.extend(&row_groups_to_thrift_unknown_fields(&self.row_groups)?);
+      unknown_enums.extend(self.file_metadata.unknown_fields());
+      let mut protocol = TolerantOutputProtocol::for_file_metadata(
+        TCompactOutputProtocol::new(&mut buf), unknown_enums);
+      t_file_metadata.write_to_out_protocol(&mut protocol)?;
+      protocol.flush()?;
+    }
+    Ok(buf)
+  }
+
+  /// Deserializes metadata from Thrift compact encoding, e.g. bytes returned by
+  /// [`to_bytes`](#method.to_bytes) or file metadata of a footer without the length
+  /// and magic bytes. Metadata is validated the same way as when it is read from a
+  /// file.
+  pub fn from_bytes(buf: &[u8]) -> Result<Self> {
+    SerializedFileReader::<File>::decode_metadata(
+      buf, None, false, &MetadataLimits::default())
+  }
 }
 
 /// Reference counted pointer for [`FileMetaData`].
@@ -96,7 +173,9 @@ pub struct FileMetaData {
   created_by: Option<String>,
   schema: TypePtr,
   schema_descr: SchemaDescPtr,
-  column_orders: Option<Vec<ColumnOrder>>
+  column_orders: Option<Vec<ColumnOrder>>,
+  key_value_metadata: Option<Vec<KeyValue>>,
+  unknown_fields: UnknownEnums
 }
 
 impl FileMetaData {
@@ -115,10 +194,24 @@ impl FileMetaData {
       created_by,
       schema,
       schema_descr,
-      column_orders
+      column_orders,
+      key_value_metadata: None,
+      unknown_fields: UnknownEnums::default()
     }
   }
 
+  /// Sets key-value metadata of the file.
+  pub fn with_key_value_metadata(mut self, value: Option<Vec<KeyValue>>) -> Self {
+    self.key_value_metadata = value;
+    self
+  }
+
+  /// Sets fields of the file metadata that are unknown to this version of the crate.
+  pub(crate) fn with_unknown_fields(mut self, value: UnknownEnums) -> Self {
+    self.unknown_fields = value;
+    self
+  }
+
   /// Returns version of this file.
   pub fn version(&self) -> i32 {
     self.version
@@ -141,6 +234,12 @@ impl FileMetaData {
     &self.created_by
   }
 
+  /// Returns application that wrote this file, parsed from
+  /// [`created_by`](#method.created_by). If it is not set, application is `unknown`.
+  pub fn writer_version(&self) -> ApplicationVersion {
+    ApplicationVersion::parse(self.created_by.as_ref().map(|s| s.as_str()).unwrap_or(""))
+  }
+
   /// Returns Parquet ['Type`] that describes schema in this file.
   pub fn schema(&self) -> &SchemaType {
     self.schema.as_ref()
@@ -172,6 +271,173 @@ impl FileMetaData {
   pub fn column_order(&self, i: usize) -> ColumnOrder {
     self.column_orders.as_ref().map(|data| data[i]).unwrap_or(ColumnOrder::UNDEFINED)
   }
+
+  /// Returns application specific key-value metadata of the file, if any.
+  pub fn key_value_metadata(&self) -> Option<&Vec<KeyValue>> {
+    self.key_value_metadata.as_ref()
+  }
+
+  /// Returns fields of the file metadata that are not in the Thrift definition of this
+  /// version of the crate, e.g. fields added by newer versions of Parquet format. They
+  /// are kept, so that they are written unchanged when the metadata is written again.
+  pub(crate) fn unknown_fields(&self) -> &UnknownEnums {
+    &self.unknown_fields
+  }
+}
+
+/// Converts column orders into Thrift definition. Column orders can only be written
+/// when all of them are type defined, otherwise returns `None`.
+pub(crate) fn column_orders_to_thrift(
+  orders: &[ColumnOrder]
+) -> Option<Vec<parquet_format::ColumnOrder>> {
+  orders.iter()
+    .map(|order| match *order {
+      ColumnOrder::TYPE_DEFINED_ORDER(_) => {
+        Some(parquet_format::ColumnOrder::TYPEORDER(TypeDefinedOrder::new()))
+      },
+      ColumnOrder::UNDEFINED => None
+    })
+    .collect()
+}
+
+/// Returns fields of row groups that are not in the Thrift definition, with paths
+/// relative to file metadata.
+pub(crate) fn row_groups_to_thrift_unknown_fields(
+  row_groups: &[RowGroupMetaDataPtr]
+) -> Result<UnknownEnums> {
+  let mut unknown_fields = UnknownEnums::default();
+  for (i, row_group) in row_groups.iter().enumerate() {
+    unknown_fields.extend(&row_group.to_thrift_unknown_fields()?.nest(&[4, i]));
+  }
+  Ok(unknown_fields)
+}
+
+/// Application and its version that wrote a file, see
+/// [`FileMetaData::writer_version`](struct.FileMetaData.html#method.writer_version).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApplicationVersion {
+  application: String,
+  version: Option<String>,
+  // Major, minor and patch components of the version, missing components are 0
+  version_number: (u32, u32, u32),
+  build: Option<String>
+}
+
+impl ApplicationVersion {
+  /// Parses `created_by` string of the following format:
+  /// `<application> version <version> (build <build hash>)`, where version and build are
+  /// optional. Application name is converted to lower case, empty name is replaced with
+  /// `unknown`.
+  pub fn parse(created_by: &str) -> Self {
+    let created_by = created_by.trim();
+    let (application, rest) = match created_by.find(" version") {
+      Some(pos) => (&created_by[..pos], created_by[pos + 8..].trim()),
+      None => (created_by, "")
+    };
+    let (version, build) = match rest.find('(') {
+      Some(pos) => {
+        let build = rest[pos + 1..].trim_end_matches(')').trim();
+        let build = if build.starts_with("build") { build[5..].trim() } else { build };
+        (rest[..pos].trim(), build)
+      },
+      None => (rest, "")
+    };
+
+    let mut version_number = [0; 3];
+    for (i, component) in version.split('.').take(3).enumerate() {
+      let digits = component.chars().take_while(|c| c.is_ascii_digit()).collect::<String>();
+      match digits.parse() {
+        Ok(value) => version_number[i] = value,
+        Err(_) => break
+      }
+      if digits.len() < component.len() {
+        break;
+      }
+    }
+
+    let non_empty =
+      |value: &str| if value.is_empty() { None } else { Some(value.to_owned()) };
+    Self {
+      application: non_empty(application)
+        .map(|name| name.to_lowercase())
+        .unwrap_or("unknown".to_owned()),
+      version: non_empty(version),
+      version_number: (version_number[0], version_number[1], version_number[2]),
+      build: non_empty(build)
+    }
+  }
+
+  /// Returns application name in lower case, e.g. `parquet-mr`.
+  pub fn application(&self) -> &str {
+    &self.application
+  }
+
+  /// Returns version string, e.g. `1.8.0` or `1.3.0-INTERNAL`.
+  pub fn version(&self) -> Option<&str> {
+    self.version.as_ref().map(|s| s.as_str())
+  }
+
+  /// Returns major, minor and patch components of the version. Components that are not
+  /// set are 0.
+  pub fn version_number(&self) -> (u32, u32, u32) {
+    self.version_number
+  }
+
+  /// Returns build hash of the application.
+  pub fn build(&self) -> Option<&str> {
+    self.build.as_ref().map(|s| s.as_str())
+  }
+
+  /// Returns `true` if min and max statistics of a column chunk written by this
+  /// application are correct for a column of `physical_type` with `sort_order`.
+  /// `min_equals_max` should be set when min value is equal to max value, since sort
+  /// order does not matter in this case.
+  ///
+  /// - parquet-mr before 1.10.0 and parquet-cpp before 1.3.0 compared all values as
+  /// signed (PARQUET-1025), only statistics of columns with signed sort order are
+  /// correct.
+  /// - parquet-mr before 1.8.0 wrote incorrect statistics of binary columns
+  /// (PARQUET-251).
+  /// - Statistics of columns with undefined sort order, e.g. INT96, are ignored.
+  pub fn has_correct_statistics(
+    &self,
+    physical_type: Type,
+    sort_order: SortOrder,
+    min_equals_max: bool
+  ) -> bool {
+    if self.is_older_than("parquet-cpp", (1, 3, 0)) ||
+        self.is_older_than("parquet-mr", (1, 10, 0)) {
+      if sort_order != SortOrder::SIGNED && !min_equals_max {
+        return false;
+      }
+      let is_binary =
+        physical_type == Type::BYTE_ARRAY || physical_type == Type::FIXED_LEN_BYTE_ARRAY;
+      if !is_binary {
+        return true;
+      }
+    }
+    // Missing application could be parquet-mr at the time of PARQUET-251, however its
+    // statistics are considered correct, see PARQUET-297
+    if self.application == "unknown" {
+      return true;
+    }
+    if sort_order == SortOrder::UNDEFINED {
+      return false;
+    }
+    !self.is_older_than("parquet-mr", (1, 8, 0))
+  }
+
+  /// Returns `true` if sizes of column chunks written by this application do not
+  /// include dictionary page header, which is the case for parquet-mr before 1.2.9
+  /// (PARQUET-816).
+  pub fn excludes_dictionary_page_header(&self) -> bool {
+    self.is_older_than("parquet-mr", (1, 2, 9))
+  }
+
+  /// Returns `true` if this is `application` of version older than `version`.
+  fn is_older_than(&self, application: &str, version: (u32, u32, u32)) -> bool {
+    self.application == application && self.version_number < version
+  }
 }
 
 /// Reference counted pointer for [`RowGroupMetaData`].
@@ -182,6 +448,7 @@ pub struct RowGroupMetaData {
   columns: Vec<ColumnChunkMetaDataPtr>,
   num_rows: i64,
   total_byte_size: i64,
+  sorting_columns: Option<Vec<SortingColumn>>,
   schema_descr: SchemaDescPtr
 }
 
@@ -216,6 +483,18 @@ impl RowGroupMetaData {
     self.total_byte_size
   }
 
+  /// Returns columns that rows of this row group are sorted by, in order of priority,
+  /// if declared by the writer.
+  pub fn sorting_columns(&self) -> Option<&Vec<SortingColumn>> {
+    self.sorting_columns.as_ref()
+  }
+
+  /// Returns path of the file that contains column chunks of this row group, if it is
+  /// not the file with this metadata, e.g. for row groups of summary `_metadata` files.
+  pub fn file_path(&self) -> Option<&String> {
+    self.columns.first().and_then(|column| column.file_path())
+  }
+
   /// Returns reference to a schema descriptor.
   pub fn schema_descr(&self) -> &SchemaDescriptor {
     self.schema_descr.as_ref()
@@ -229,20 +508,34 @@ impl RowGroupMetaData {
   /// Method to convert from Thrift.
   pub fn from_thrift(
     schema_descr: SchemaDescPtr,
-    mut rg: RowGroup
+    rg: RowGroup
+  ) -> Result<RowGroupMetaData> {
+    Self::from_thrift_with_unknown_enums(schema_descr, rg, &UnknownEnums::default())
+  }
+
+  /// Method to convert from Thrift, where `unknown_enums` contains enum values of the
+  /// row group that are not known to the Thrift definition, with paths relative to the
+  /// row group.
+  pub fn from_thrift_with_unknown_enums(
+    schema_descr: SchemaDescPtr,
+    mut rg: RowGroup,
+    unknown_enums: &UnknownEnums
   ) -> Result<RowGroupMetaData> {
     assert_eq!(schema_descr.num_columns(), rg.columns.len());
     let total_byte_size = rg.total_byte_size;
     let num_rows = rg.num_rows;
+    let sorting_columns = rg.sorting_columns.take();
     let mut columns = vec![];
-    for (c, d) in rg.columns.drain(0..).zip(schema_descr.columns()) {
-      let cc = ColumnChunkMetaData::from_thrift(d.clone(), c)?;
+    for (i, (c, d)) in rg.columns.drain(0..).zip(schema_descr.columns()).enumerate() {
+      let cc = ColumnChunkMetaData::from_thrift_with_unknown_enums(
+        d.clone(), c, &unknown_enums.scope(&[1, i]))?;
       columns.push(Rc::new(cc));
     }
     Ok(RowGroupMetaData {
       columns,
       num_rows,
       total_byte_size,
+      sorting_columns,
       schema_descr
     })
   }
@@ -253,9 +546,22 @@ impl RowGroupMetaData {
       columns: self.columns().into_iter().map(|v| v.to_thrift()).collect(),
       total_byte_size: self.total_byte_size,
       num_rows: self.num_rows,
-      sorting_columns: None
+      sorting_columns: self.sorting_columns.clone()
     }
   }
+
+  /// Returns fields of column chunks that are not in the Thrift definition, e.g. size
+  /// statistics, with paths relative to the row group. These are written together with
+  /// [`to_thrift`](#method.to_thrift) by [`ParquetMetaData::to_bytes`] and file writer.
+  ///
+  /// [`ParquetMetaData::to_bytes`]: struct.ParquetMetaData.html#method.to_bytes
+  pub(crate) fn to_thrift_unknown_fields(&self) -> Result<UnknownEnums> {
+    let mut unknown_fields = UnknownEnums::default();
+    for (i, column) in self.columns.iter().enumerate() {
+      unknown_fields.extend(&column.to_thrift_unknown_fields()?.nest(&[1, i]));
+    }
+    Ok(unknown_fields)
+  }
 }
 
 /// Builder for row group metadata.
@@ -263,7 +569,8 @@ pub struct RowGroupMetaDataBuilder {
   columns: Vec<ColumnChunkMetaDataPtr>,
   schema_descr: SchemaDescPtr,
   num_rows: i64,
-  total_byte_size: i64
+  total_byte_size: i64,
+  sorting_columns: Option<Vec<SortingColumn>>
 }
 
 impl RowGroupMetaDataBuilder {
@@ -273,7 +580,8 @@ impl RowGroupMetaDataBuilder {
       columns: Vec::with_capacity(schema_descr.num_columns()),
       schema_descr: schema_descr,
       num_rows: 0,
-      total_byte_size: 0
+      total_byte_size: 0,
+      sorting_columns: None
     }
   }
 
@@ -295,6 +603,12 @@ impl RowGroupMetaDataBuilder {
     self
   }
 
+  /// Sets columns that rows of this row group are sorted by.
+  pub fn set_sorting_columns(mut self, value: Option<Vec<SortingColumn>>) -> Self {
+    self.sorting_columns = value;
+    self
+  }
+
   /// Builds row group metadata.
   pub fn build(self) -> Result<RowGroupMetaData> {
     if self.schema_descr.num_columns() != self.columns.len() {
@@ -309,11 +623,42 @@ impl RowGroupMetaDataBuilder {
       columns: self.columns,
       num_rows: self.num_rows,
       total_byte_size: self.total_byte_size,
+      sorting_columns: self.sorting_columns,
       schema_descr: self.schema_descr
     })
   }
 }
 
+/// Id of the `bloom_filter_offset` field of `ColumnMetaData`, not in `parquet_format`.
+pub(crate) const BLOOM_FILTER_OFFSET_FIELD_ID: usize = 14;
+
+/// Id of the `bloom_filter_length` field of `ColumnMetaData`, not in `parquet_format`.
+pub(crate) const BLOOM_FILTER_LENGTH_FIELD_ID: usize = 15;
+
+/// Id of the `size_statistics` field of `ColumnMetaData`, not in `parquet_format`.
+pub(crate) const SIZE_STATISTICS_FIELD_ID: usize = 16;
+
+/// Id of the `is_max_value_exact` field of `Statistics`, not in `parquet_format`.
+pub(crate) const IS_MAX_VALUE_EXACT_FIELD_ID: usize = 7;
+
+/// Id of the `is_min_value_exact` field of `Statistics`, not in `parquet_format`.
+pub(crate) const IS_MIN_VALUE_EXACT_FIELD_ID: usize = 8;
+
+/// Id of the `crypto_metadata` field of `ColumnChunk`, not in `parquet_format`.
+pub(crate) const CRYPTO_METADATA_FIELD_ID: usize = 8;
+
+/// Id of the `encrypted_column_metadata` field of `ColumnChunk`, not in
+/// `parquet_format`.
+pub(crate) const ENCRYPTED_COLUMN_METADATA_FIELD_ID: usize = 9;
+
+/// Id of the `encryption_algorithm` field of `FileMetaData`, not in `parquet_format`.
+/// Only set in files with encrypted columns and plaintext footer.
+pub(crate) const ENCRYPTION_ALGORITHM_FIELD_ID: usize = 8;
+
+/// Id of the `footer_signing_key_metadata` field of `FileMetaData`, not in
+/// `parquet_format`.
+pub(crate) const FOOTER_SIGNING_KEY_METADATA_FIELD_ID: usize = 9;
+
 /// Reference counted pointer for [`ColumnChunkMetaData`].
 pub type ColumnChunkMetaDataPtr = Rc<ColumnChunkMetaData>;
 
@@ -332,7 +677,16 @@ pub struct ColumnChunkMetaData {
   data_page_offset: i64,
   index_page_offset: Option<i64>,
   dictionary_page_offset: Option<i64>,
-  statistics: Option<Statistics>
+  statistics: Option<Statistics>,
+  encoding_stats: Option<Vec<PageEncodingStats>>,
+  offset_index_offset: Option<i64>,
+  offset_index_length: Option<i32>,
+  column_index_offset: Option<i64>,
+  column_index_length: Option<i32>,
+  bloom_filter_offset: Option<i64>,
+  bloom_filter_length: Option<i32>,
+  size_statistics: Option<SizeStatistics>,
+  crypto_metadata: Option<ColumnCryptoMetaData>
 }
 
 /// Represents common operations for a column chunk.
@@ -431,25 +785,189 @@ impl ColumnChunkMetaData {
     self.statistics.as_ref()
   }
 
+  /// Returns number of pages per page type and encoding for this column chunk,
+  /// or `None` if page encoding stats are not available.
+  pub fn encoding_stats(&self) -> Option<&Vec<PageEncodingStats>> {
+    self.encoding_stats.as_ref()
+  }
+
+  /// Returns the offset of the bloom filter of this column chunk, or `None` if the
+  /// column chunk does not have a bloom filter, see
+  /// [`read_bloom_filter`](../bloom_filter/fn.read_bloom_filter.html).
+  pub fn bloom_filter_offset(&self) -> Option<i64> {
+    self.bloom_filter_offset
+  }
+
+  /// Returns the size of the bloom filter of this column chunk in bytes, or `None` if
+  /// not available. Older writers only write the offset.
+  pub fn bloom_filter_length(&self) -> Option<i32> {
+    self.bloom_filter_length
+  }
+
+  /// Returns size statistics of this column chunk, i.e. definition and repetition level
+  /// histograms and size of unencoded `BYTE_ARRAY` values, or `None` if not available.
+  pub fn size_statistics(&self) -> Option<&SizeStatistics> {
+    self.size_statistics.as_ref()
+  }
+
+  /// Returns how this column chunk is encrypted, or `None` if it is not encrypted.
+  pub fn crypto_metadata(&self) -> Option<&ColumnCryptoMetaData> {
+    self.crypto_metadata.as_ref()
+  }
+
+  /// Sets crypto metadata of this column chunk, e.g. when it is encrypted by the file
+  /// writer.
+  pub(crate) fn with_crypto_metadata(
+    mut self,
+    value: Option<ColumnCryptoMetaData>
+  ) -> Self {
+    self.crypto_metadata = value;
+    self
+  }
+
+  /// Returns `true` if this column chunk has an offset index, `false` otherwise.
+  pub fn has_offset_index(&self) -> bool {
+    self.offset_index_offset.is_some() && self.offset_index_length.is_some()
+  }
+
+  /// Returns the offset for the offset index, if any.
+  pub fn offset_index_offset(&self) -> Option<i64> {
+    self.offset_index_offset
+  }
+
+  /// Returns the size of the offset index in bytes, if any.
+  pub fn offset_index_length(&self) -> Option<i32> {
+    self.offset_index_length
+  }
+
+  /// Returns `true` if this column chunk has a column index, `false` otherwise.
+  pub fn has_column_index(&self) -> bool {
+    self.column_index_offset.is_some() && self.column_index_length.is_some()
+  }
+
+  /// Returns the offset for the column index, if any.
+  pub fn column_index_offset(&self) -> Option<i64> {
+    self.column_index_offset
+  }
+
+  /// Returns the size of the column index in bytes, if any.
+  pub fn column_index_length(&self) -> Option<i32> {
+    self.column_index_length
+  }
+
+  /// Returns `true` if all data pages of this column chunk are dictionary encoded,
+  /// `false` otherwise.
+  ///
+  /// Page encoding stats are used when available. Otherwise the result is based on the
+  /// list of encodings, which does not distinguish the encoding of dictionary page from
+  /// encodings of data pages, so `false` is returned when it is not known whether some
+  /// data pages fell back to a non-dictionary encoding.
+  pub fn is_fully_dictionary_encoded(&self) -> bool {
+    if !self.has_dictionary_page() {
+      return false;
+    }
+
+    match self.encoding_stats {
+      Some(ref encoding_stats) => {
+        encoding_stats.iter()
+          .filter(|stats| {
+            stats.page_type == PageType::DATA_PAGE ||
+              stats.page_type == PageType::DATA_PAGE_V2
+          })
+          .all(|stats| stats.count == 0 || is_dictionary_encoding(stats.encoding))
+      },
+      None => {
+        // RLE and BIT_PACKED are only used for definition and repetition levels,
+        // PLAIN can be either dictionary page or data page encoding.
+        self.encodings.iter().any(|encoding| is_dictionary_encoding(*encoding)) &&
+          self.encodings.iter().all(|encoding| match *encoding {
+            Encoding::PLAIN_DICTIONARY | Encoding::RLE_DICTIONARY => true,
+            Encoding::RLE | Encoding::BIT_PACKED => true,
+            _ => false
+          })
+      }
+    }
+  }
+
   /// Method to convert from Thrift.
   pub fn from_thrift(column_descr: ColumnDescPtr, cc: ColumnChunk) -> Result<Self> {
+    Self::from_thrift_with_unknown_enums(column_descr, cc, &UnknownEnums::default())
+  }
+
+  /// Method to convert from Thrift, where `unknown_enums` contains enum values of the
+  /// column chunk that are not known to the Thrift definition, with paths relative to
+  /// the column chunk.
+  pub fn from_thrift_with_unknown_enums(
+    column_descr: ColumnDescPtr,
+    cc: ColumnChunk,
+    unknown_enums: &UnknownEnums
+  ) -> Result<Self> {
     if cc.meta_data.is_none() {
       return Err(general_err!("Expected to have column metadata"));
     }
     let mut col_metadata: ColumnMetaData = cc.meta_data.unwrap();
+    let crypto_metadata = match unknown_enums.field(&[CRYPTO_METADATA_FIELD_ID]) {
+      Some((TType::Struct, data)) => {
+        Some(ColumnCryptoMetaData::from_compact_bytes(data)?)
+      },
+      _ => None
+    };
+    let unknown_enums = unknown_enums.scope(&[3]);
     let column_type = Type::from(col_metadata.type_);
     let column_path = ColumnPath::new(col_metadata.path_in_schema);
-    let encodings = col_metadata.encodings.drain(0..).map(Encoding::from).collect();
-    let compression = Compression::from(col_metadata.codec);
+    let encodings = col_metadata.encodings.drain(0..).enumerate()
+      .map(|(i, encoding)| unknown_enums.encoding(&[2, i], encoding))
+      .collect();
+    let compression = unknown_enums.compression(&[4], col_metadata.codec);
     let file_path = cc.file_path;
     let file_offset = cc.file_offset;
+    let offset_index_offset = cc.offset_index_offset;
+    let offset_index_length = cc.offset_index_length;
+    let column_index_offset = cc.column_index_offset;
+    let column_index_length = cc.column_index_length;
     let num_values = col_metadata.num_values;
     let total_compressed_size = col_metadata.total_compressed_size;
     let total_uncompressed_size = col_metadata.total_uncompressed_size;
     let data_page_offset = col_metadata.data_page_offset;
     let index_page_offset = col_metadata.index_page_offset;
     let dictionary_page_offset = col_metadata.dictionary_page_offset;
-    let statistics = statistics::from_thrift(column_type, col_metadata.statistics);
+    // Exactness of min and max values is unknown if it is not set, so they are treated
+    // as bounds
+    let is_max_exact = bool_field(&unknown_enums, &[12, IS_MAX_VALUE_EXACT_FIELD_ID])?;
+    let is_min_exact = bool_field(&unknown_enums, &[12, IS_MIN_VALUE_EXACT_FIELD_ID])?;
+    let statistics = statistics::from_thrift(column_type, col_metadata.statistics)
+      .map(|stats| {
+        if stats.has_min_max_set() {
+          stats.with_min_max_exact(
+            is_min_exact.unwrap_or(false),
+            is_max_exact.unwrap_or(false)
+          )
+        } else {
+          stats
+        }
+      });
+    let encoding_stats = col_metadata.encoding_stats.map(|stats| {
+      stats.into_iter().enumerate().map(|(i, stats)| {
+        let unknown_enums = unknown_enums.scope(&[13, i]);
+        PageEncodingStats {
+          page_type: unknown_enums.page_type(&[1], stats.page_type),
+          encoding: unknown_enums.encoding(&[2], stats.encoding),
+          count: stats.count
+        }
+      }).collect()
+    });
+    let bloom_filter_offset = match unknown_enums.field(&[BLOOM_FILTER_OFFSET_FIELD_ID]) {
+      Some((TType::I64, data)) => Some(TCompactInputProtocol::new(data).read_i64()?),
+      _ => None
+    };
+    let bloom_filter_length = match unknown_enums.field(&[BLOOM_FILTER_LENGTH_FIELD_ID]) {
+      Some((TType::I32, data)) => Some(TCompactInputProtocol::new(data).read_i32()?),
+      _ => None
+    };
+    let size_statistics = match unknown_enums.field(&[SIZE_STATISTICS_FIELD_ID]) {
+      Some((TType::Struct, data)) => Some(SizeStatistics::from_compact_bytes(data)?),
+      _ => None
+    };
     let result = ColumnChunkMetaData {
       column_type,
       column_path,
@@ -464,7 +982,16 @@ impl ColumnChunkMetaData {
       data_page_offset,
       index_page_offset,
       dictionary_page_offset,
-      statistics
+      statistics,
+      encoding_stats,
+      offset_index_offset,
+      offset_index_length,
+      column_index_offset,
+      column_index_length,
+      bloom_filter_offset,
+      bloom_filter_length,
+      size_statistics,
+      crypto_metadata
     };
     Ok(result)
   }
@@ -484,18 +1011,55 @@ impl ColumnChunkMetaData {
       index_page_offset: self.index_page_offset,
       dictionary_page_offset: self.dictionary_page_offset,
       statistics: statistics::to_thrift(self.statistics.as_ref()),
-      encoding_stats: None
+      encoding_stats: self.encoding_stats.as_ref().map(|stats| {
+        stats.iter().map(|s| s.to_thrift()).collect()
+      })
     };
 
     ColumnChunk {
       file_path: self.file_path().map(|v| v.clone()),
       file_offset: self.file_offset,
       meta_data: Some(column_metadata),
-      offset_index_offset: None,
-      offset_index_length: None,
-      column_index_offset: None,
-      column_index_length: None
+      offset_index_offset: self.offset_index_offset,
+      offset_index_length: self.offset_index_length,
+      column_index_offset: self.column_index_offset,
+      column_index_length: self.column_index_length
+    }
+  }
+
+  /// Returns fields that are not in the Thrift definition, e.g. size statistics, with
+  /// paths relative to the column chunk.
+  pub(crate) fn to_thrift_unknown_fields(&self) -> Result<UnknownEnums> {
+    let mut unknown_fields = UnknownEnums::default();
+    if let Some(offset) = self.bloom_filter_offset {
+      let data = compact_bytes(|prot| prot.write_i64(offset))?;
+      let path = vec![3, BLOOM_FILTER_OFFSET_FIELD_ID];
+      unknown_fields.insert_field(path, TType::I64, data);
+    }
+    if let Some(length) = self.bloom_filter_length {
+      let data = compact_bytes(|prot| prot.write_i32(length))?;
+      let path = vec![3, BLOOM_FILTER_LENGTH_FIELD_ID];
+      unknown_fields.insert_field(path, TType::I32, data);
+    }
+    if let Some(ref statistics) = self.statistics {
+      if statistics.has_min_max_set() {
+        let data = compact_bytes(|prot| prot.write_bool(statistics.is_max_exact()))?;
+        let path = vec![3, 12, IS_MAX_VALUE_EXACT_FIELD_ID];
+        unknown_fields.insert_field(path, TType::Bool, data);
+        let data = compact_bytes(|prot| prot.write_bool(statistics.is_min_exact()))?;
+        let path = vec![3, 12, IS_MIN_VALUE_EXACT_FIELD_ID];
+        unknown_fields.insert_field(path, TType::Bool, data);
+      }
+    }
+    if let Some(ref size_statistics) = self.size_statistics {
+      let data = size_statistics.to_compact_bytes()?;
+      unknown_fields.insert_field(vec![3, SIZE_STATISTICS_FIELD_ID], TType::Struct, data);
     }
+    if let Some(ref crypto_metadata) = self.crypto_metadata {
+      let data = crypto_metadata.to_compact_bytes()?;
+      unknown_fields.insert_field(vec![CRYPTO_METADATA_FIELD_ID], TType::Struct, data);
+    }
+    Ok(unknown_fields)
   }
 }
 
@@ -512,7 +1076,16 @@ pub struct ColumnChunkMetaDataBuilder {
   data_page_offset: i64,
   index_page_offset: Option<i64>,
   dictionary_page_offset: Option<i64>,
-  statistics: Option<Statistics>
+  statistics: Option<Statistics>,
+  encoding_stats: Option<Vec<PageEncodingStats>>,
+  offset_index_offset: Option<i64>,
+  offset_index_length: Option<i32>,
+  column_index_offset: Option<i64>,
+  column_index_length: Option<i32>,
+  bloom_filter_offset: Option<i64>,
+  bloom_filter_length: Option<i32>,
+  size_statistics: Option<SizeStatistics>,
+  crypto_metadata: Option<ColumnCryptoMetaData>
 }
 
 impl ColumnChunkMetaDataBuilder {
@@ -530,7 +1103,16 @@ impl ColumnChunkMetaDataBuilder {
       data_page_offset: 0,
       index_page_offset: None,
       dictionary_page_offset: None,
-      statistics: None
+      statistics: None,
+      encoding_stats: None,
+      offset_index_offset: None,
+      offset_index_length: None,
+      column_index_offset: None,
+      column_index_length: None,
+      bloom_filter_offset: None,
+      bloom_filter_length: None,
+      size_statistics: None,
+      crypto_metadata: None
     }
   }
 
@@ -600,6 +1182,60 @@ impl ColumnChunkMetaDataBuilder {
     self
   }
 
+  /// Sets page encoding stats for this column chunk.
+  pub fn set_encoding_stats(mut self, value: Vec<PageEncodingStats>) -> Self {
+    self.encoding_stats = Some(value);
+    self
+  }
+
+  /// Sets optional size statistics for this column chunk.
+  pub fn set_size_statistics(mut self, value: Option<SizeStatistics>) -> Self {
+    self.size_statistics = value;
+    self
+  }
+
+  /// Sets optional offset index offset in bytes.
+  pub fn set_offset_index_offset(mut self, value: Option<i64>) -> Self {
+    self.offset_index_offset = value;
+    self
+  }
+
+  /// Sets optional offset index size in bytes.
+  pub fn set_offset_index_length(mut self, value: Option<i32>) -> Self {
+    self.offset_index_length = value;
+    self
+  }
+
+  /// Sets optional column index offset in bytes.
+  pub fn set_column_index_offset(mut self, value: Option<i64>) -> Self {
+    self.column_index_offset = value;
+    self
+  }
+
+  /// Sets optional column index size in bytes.
+  pub fn set_column_index_length(mut self, value: Option<i32>) -> Self {
+    self.column_index_length = value;
+    self
+  }
+
+  /// Sets optional bloom filter offset in bytes.
+  pub fn set_bloom_filter_offset(mut self, value: Option<i64>) -> Self {
+    self.bloom_filter_offset = value;
+    self
+  }
+
+  /// Sets optional bloom filter size in bytes.
+  pub fn set_bloom_filter_length(mut self, value: Option<i32>) -> Self {
+    self.bloom_filter_length = value;
+    self
+  }
+
+  /// Sets optional crypto metadata of an encrypted column chunk.
+  pub fn set_crypto_metadata(mut self, value: Option<ColumnCryptoMetaData>) -> Self {
+    self.crypto_metadata = value;
+    self
+  }
+
   /// Builds column chunk metadata.
   pub fn build(self) -> Result<ColumnChunkMetaData> {
     Ok(ColumnChunkMetaData {
@@ -616,16 +1252,90 @@ impl ColumnChunkMetaDataBuilder {
       data_page_offset: self.data_page_offset,
       index_page_offset: self.index_page_offset,
       dictionary_page_offset: self.dictionary_page_offset,
-      statistics: self.statistics
+      statistics: self.statistics,
+      encoding_stats: self.encoding_stats,
+      offset_index_offset: self.offset_index_offset,
+      offset_index_length: self.offset_index_length,
+      column_index_offset: self.column_index_offset,
+      column_index_length: self.column_index_length,
+      bloom_filter_offset: self.bloom_filter_offset,
+      bloom_filter_length: self.bloom_filter_length,
+      size_statistics: self.size_statistics,
+      crypto_metadata: self.crypto_metadata
     })
   }
 }
 
+/// Number of pages of a column chunk with a particular page type and encoding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageEncodingStats {
+  /// Type of the pages.
+  pub page_type: PageType,
+  /// Encoding of the pages.
+  pub encoding: Encoding,
+  /// Number of pages of this type with this encoding.
+  pub count: i32
+}
+
+impl PageEncodingStats {
+  /// Method to convert from Thrift.
+  pub fn from_thrift(stats: parquet_format::PageEncodingStats) -> Self {
+    Self {
+      page_type: PageType::from(stats.page_type),
+      encoding: Encoding::from(stats.encoding),
+      count: stats.count
+    }
+  }
+
+  /// Method to convert to Thrift.
+  pub fn to_thrift(&self) -> parquet_format::PageEncodingStats {
+    parquet_format::PageEncodingStats {
+      page_type: self.page_type.into(),
+      encoding: self.encoding.into(),
+      count: self.count
+    }
+  }
+}
+
+/// Returns `true` if data pages with `encoding` are dictionary encoded.
+#[inline]
+fn is_dictionary_encoding(encoding: Encoding) -> bool {
+  encoding == Encoding::PLAIN_DICTIONARY || encoding == Encoding::RLE_DICTIONARY
+}
+
+/// Returns value of unknown boolean field at `path`, if any.
+fn bool_field(unknown_fields: &UnknownEnums, path: &[usize]) -> Result<Option<bool>> {
+  match unknown_fields.field(path) {
+    Some((TType::Bool, data)) => Ok(Some(TCompactInputProtocol::new(data).read_bool()?)),
+    _ => Ok(None)
+  }
+}
+
+/// Serializes a value written by `f` with the compact protocol.
+pub(crate) fn compact_bytes<F>(f: F) -> Result<Vec<u8>>
+  where F: FnOnce(&mut TOutputProtocol) -> ::thrift::Result<()> {
+  let mut buf = Vec::new();
+  {
+    let mut prot = TCompactOutputProtocol::new(&mut buf);
+    f(&mut prot)?;
+    prot.flush()?;
+  }
+  Ok(buf)
+}
+
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  use std::fs;
+
+  use byteorder::{ByteOrder, LittleEndian};
+
+  use file::reader::FileReader;
+  use thrift::protocol::TType;
+  use util::test_common::{get_test_file, get_test_path};
+
   #[test]
   fn test_row_group_metadata_thrift_conversion() {
     let schema_descr = get_test_schema_descr();
@@ -639,6 +1349,7 @@ mod tests {
       .set_num_rows(1000)
       .set_total_byte_size(2000)
       .set_column_metadata(columns)
+      .set_sorting_columns(Some(vec![SortingColumn::new(1, true, false)]))
       .build()
       .unwrap();
 
@@ -677,10 +1388,30 @@ mod tests {
       .set_total_uncompressed_size(3000)
       .set_data_page_offset(4000)
       .set_dictionary_page_offset(Some(5000))
+      .set_offset_index_offset(Some(6000))
+      .set_offset_index_length(Some(25))
+      .set_column_index_offset(Some(7000))
+      .set_column_index_length(Some(30))
+      .set_encoding_stats(vec![
+        PageEncodingStats {
+          page_type: PageType::DICTIONARY_PAGE,
+          encoding: Encoding::PLAIN,
+          count: 1
+        },
+        PageEncodingStats {
+          page_type: PageType::DATA_PAGE,
+          encoding: Encoding::RLE_DICTIONARY,
+          count: 10
+        }
+      ])
       .build()
       .unwrap();
 
     let col_chunk_exp = col_metadata.to_thrift();
+    assert_eq!(
+      col_chunk_exp.meta_data.as_ref().unwrap().encoding_stats.as_ref().map(|s| s.len()),
+      Some(2)
+    );
 
     let col_chunk_res = ColumnChunkMetaData::from_thrift(
       column_descr.clone(), col_chunk_exp.clone()
@@ -700,9 +1431,300 @@ mod tests {
     let col_chunk_exp = col_metadata.to_thrift();
     let col_chunk_res = ColumnChunkMetaData::from_thrift(
       column_descr.clone(), col_chunk_exp.clone()
-    ).unwrap().to_thrift();
+    ).unwrap();
+    assert_eq!(col_chunk_res.encoding_stats(), None);
 
-    assert_eq!(col_chunk_res, col_chunk_exp);
+    assert_eq!(col_chunk_res.to_thrift(), col_chunk_exp);
+  }
+
+  #[test]
+  fn test_column_chunk_metadata_fully_dictionary_encoded() {
+    let column_descr = get_test_schema_descr().column(0);
+    let build = |encodings: Vec<Encoding>, dictionary_page_offset: Option<i64>| {
+      ColumnChunkMetaData::builder(column_descr.clone())
+        .set_encodings(encodings)
+        .set_dictionary_page_offset(dictionary_page_offset)
+        .build()
+        .unwrap()
+    };
+
+    // Fall back to list of encodings
+    let dict_v1 = vec![Encoding::PLAIN_DICTIONARY, Encoding::RLE, Encoding::BIT_PACKED];
+    assert!(build(dict_v1.clone(), Some(0)).is_fully_dictionary_encoded());
+    assert!(!build(dict_v1, None).is_fully_dictionary_encoded());
+    let dict_v2 = vec![Encoding::PLAIN, Encoding::RLE_DICTIONARY, Encoding::RLE];
+    assert!(!build(dict_v2.clone(), Some(0)).is_fully_dictionary_encoded());
+    let fallback = vec![Encoding::PLAIN_DICTIONARY, Encoding::PLAIN, Encoding::RLE];
+    assert!(!build(fallback, Some(0)).is_fully_dictionary_encoded());
+    let plain = vec![Encoding::PLAIN, Encoding::RLE];
+    assert!(!build(plain, None).is_fully_dictionary_encoded());
+
+    // Use encoding stats when available
+    let with_stats = |stats: Vec<(PageType, Encoding, i32)>| {
+      let mut col_chunk = build(dict_v2.clone(), Some(0)).to_thrift();
+      col_chunk.meta_data.as_mut().unwrap().encoding_stats = Some(
+        stats.into_iter()
+          .map(|(page_type, encoding, count)| parquet_format::PageEncodingStats::new(
+            page_type.into(), encoding.into(), count))
+          .collect()
+      );
+      ColumnChunkMetaData::from_thrift(column_descr.clone(), col_chunk).unwrap()
+    };
+    assert!(with_stats(vec![
+      (PageType::DICTIONARY_PAGE, Encoding::PLAIN, 1),
+      (PageType::DATA_PAGE, Encoding::RLE_DICTIONARY, 4)
+    ]).is_fully_dictionary_encoded());
+    assert!(with_stats(vec![
+      (PageType::DICTIONARY_PAGE, Encoding::PLAIN, 1),
+      (PageType::DATA_PAGE_V2, Encoding::RLE_DICTIONARY, 2),
+      (PageType::DATA_PAGE_V2, Encoding::PLAIN, 0)
+    ]).is_fully_dictionary_encoded());
+    assert!(!with_stats(vec![
+      (PageType::DICTIONARY_PAGE, Encoding::PLAIN, 1),
+      (PageType::DATA_PAGE, Encoding::RLE_DICTIONARY, 4),
+      (PageType::DATA_PAGE, Encoding::PLAIN, 1)
+    ]).is_fully_dictionary_encoded());
+  }
+
+  #[test]
+  fn test_application_version_parse() {
+    let version = ApplicationVersion::parse(
+      "parquet-mr version 1.8.0 (build 0fda28af84b9746396014ad6a415b90592a98b3b)");
+    assert_eq!(version.application(), "parquet-mr");
+    assert_eq!(version.version(), Some("1.8.0"));
+    assert_eq!(version.version_number(), (1, 8, 0));
+    assert_eq!(version.build(), Some("0fda28af84b9746396014ad6a415b90592a98b3b"));
+
+    let version =
+      ApplicationVersion::parse("impala version 1.3.0-INTERNAL (build 8a48d)");
+    assert_eq!(version.application(), "impala");
+    assert_eq!(version.version(), Some("1.3.0-INTERNAL"));
+    assert_eq!(version.version_number(), (1, 3, 0));
+    assert_eq!(version.build(), Some("8a48d"));
+
+    let version = ApplicationVersion::parse("parquet-cpp version 1.4.1-SNAPSHOT");
+    assert_eq!(version.application(), "parquet-cpp");
+    assert_eq!(version.version_number(), (1, 4, 1));
+    assert_eq!(version.build(), None);
+
+    let version = ApplicationVersion::parse("Parquet-RS version 0.3");
+    assert_eq!(version.application(), "parquet-rs");
+    assert_eq!(version.version_number(), (0, 3, 0));
+
+    let version = ApplicationVersion::parse("parquet-mr");
+    assert_eq!(version.application(), "parquet-mr");
+    assert_eq!(version.version(), None);
+    assert_eq!(version.version_number(), (0, 0, 0));
+
+    let version = ApplicationVersion::parse("");
+    assert_eq!(version.application(), "unknown");
+    assert_eq!(version.version(), None);
+    assert_eq!(version.build(), None);
+  }
+
+  #[test]
+  fn test_application_version_has_correct_statistics() {
+    let parquet_mr_170 = ApplicationVersion::parse("parquet-mr version 1.7.0");
+    let parquet_mr_181 = ApplicationVersion::parse("parquet-mr version 1.8.1");
+    let parquet_mr_1100 = ApplicationVersion::parse("parquet-mr version 1.10.0");
+    let parquet_cpp_120 = ApplicationVersion::parse("parquet-cpp version 1.2.0");
+    let parquet_cpp_141 = ApplicationVersion::parse("parquet-cpp version 1.4.1");
+    let unknown = ApplicationVersion::parse("");
+
+    // Signed sort order of primitive types
+    for version in &[&parquet_mr_170, &parquet_mr_1100, &parquet_cpp_120, &unknown] {
+      assert!(version.has_correct_statistics(Type::INT32, SortOrder::SIGNED, false));
+    }
+
+    // Unsigned sort order, e.g. UINT_32 or UTF8
+    assert!(!parquet_mr_181.has_correct_statistics(
+      Type::INT32, SortOrder::UNSIGNED, false));
+    assert!(parquet_mr_181.has_correct_statistics(
+      Type::INT32, SortOrder::UNSIGNED, true));
+    assert!(!parquet_cpp_120.has_correct_statistics(
+      Type::BYTE_ARRAY, SortOrder::UNSIGNED, false));
+    assert!(parquet_mr_1100.has_correct_statistics(
+      Type::BYTE_ARRAY, SortOrder::UNSIGNED, false));
+    assert!(parquet_cpp_141.has_correct_statistics(
+      Type::BYTE_ARRAY, SortOrder::UNSIGNED, false));
+
+    // PARQUET-251
+    assert!(!parquet_mr_170.has_correct_statistics(
+      Type::BYTE_ARRAY, SortOrder::SIGNED, true));
+    assert!(parquet_mr_181.has_correct_statistics(
+      Type::BYTE_ARRAY, SortOrder::SIGNED, false));
+    assert!(unknown.has_correct_statistics(Type::BYTE_ARRAY, SortOrder::SIGNED, false));
+
+    // Undefined sort order
+    assert!(!parquet_mr_1100.has_correct_statistics(
+      Type::INT96, SortOrder::UNDEFINED, false));
+    assert!(unknown.has_correct_statistics(Type::INT96, SortOrder::UNDEFINED, false));
+  }
+
+  #[test]
+  fn test_application_version_excludes_dictionary_page_header() {
+    assert!(ApplicationVersion::parse("parquet-mr version 1.2.8")
+      .excludes_dictionary_page_header());
+    assert!(!ApplicationVersion::parse("parquet-mr version 1.2.9")
+      .excludes_dictionary_page_header());
+    assert!(!ApplicationVersion::parse("parquet-cpp version 1.0.0")
+      .excludes_dictionary_page_header());
+    assert!(!ApplicationVersion::parse("").excludes_dictionary_page_header());
+  }
+
+  #[test]
+  fn test_parquet_metadata_to_bytes_and_from_bytes() {
+    for file_name in &["alltypes_plain.parquet", "nested_maps.snappy.parquet"] {
+      let reader = SerializedFileReader::new(get_test_file(file_name)).unwrap();
+      let metadata = reader.metadata();
+      let bytes = metadata.to_bytes().unwrap();
+      let result = ParquetMetaData::from_bytes(&bytes).unwrap();
+
+      let file_metadata = result.file_metadata();
+      let expected_file_metadata = metadata.file_metadata();
+      assert_eq!(file_metadata.version(), expected_file_metadata.version());
+      assert_eq!(file_metadata.num_rows(), expected_file_metadata.num_rows());
+      assert_eq!(file_metadata.created_by(), expected_file_metadata.created_by());
+      assert_eq!(file_metadata.schema(), expected_file_metadata.schema());
+      assert_eq!(file_metadata.column_orders(), expected_file_metadata.column_orders());
+      assert_eq!(
+        file_metadata.key_value_metadata(),
+        expected_file_metadata.key_value_metadata()
+      );
+      assert_eq!(result.num_row_groups(), metadata.num_row_groups());
+      for i in 0..result.num_row_groups() {
+        assert_eq!(result.row_group(i).to_thrift(), metadata.row_group(i).to_thrift());
+      }
+      assert_eq!(result.to_bytes().unwrap(), bytes);
+    }
+  }
+
+  #[test]
+  fn test_parquet_metadata_to_bytes_footer_fields() {
+    let schema = Rc::new(
+      SchemaType::group_type_builder("schema")
+        .with_fields(&mut vec![
+          Rc::new(SchemaType::primitive_type_builder("a", Type::INT32).build().unwrap())
+        ])
+        .build()
+        .unwrap()
+    );
+    let schema_descr = Rc::new(SchemaDescriptor::new(schema.clone()));
+    // Field 9 of structure type with field 1 of type i32 with value 2
+    let mut unknown_fields = UnknownEnums::default();
+    unknown_fields.insert_field(vec![9], TType::Struct, vec![0x15, 0x04, 0x00]);
+    let file_metadata = FileMetaData::new(
+      1,
+      0,
+      Some("test".to_owned()),
+      schema,
+      schema_descr,
+      Some(vec![ColumnOrder::TYPE_DEFINED_ORDER(SortOrder::SIGNED)])
+    )
+    .with_key_value_metadata(Some(vec![KeyValue::new("a".to_owned(), "1".to_owned())]))
+    .with_unknown_fields(unknown_fields);
+    let metadata = ParquetMetaData::new(file_metadata, vec![]);
+
+    let bytes = metadata.to_bytes().unwrap();
+    let result = ParquetMetaData::from_bytes(&bytes).unwrap();
+    let file_metadata = result.file_metadata();
+    let expected_file_metadata = metadata.file_metadata();
+    assert_eq!(
+      file_metadata.key_value_metadata(),
+      expected_file_metadata.key_value_metadata()
+    );
+    assert_eq!(file_metadata.column_orders(), expected_file_metadata.column_orders());
+    assert_eq!(file_metadata.unknown_fields(), expected_file_metadata.unknown_fields());
+    assert_eq!(result.to_bytes().unwrap(), bytes);
+  }
+
+  #[test]
+  fn test_parquet_metadata_to_bytes_size_statistics() {
+    let schema = Rc::new(
+      SchemaType::group_type_builder("schema")
+        .with_fields(&mut vec![
+          Rc::new(SchemaType::primitive_type_builder("a", Type::INT32).build().unwrap())
+        ])
+        .build()
+        .unwrap()
+    );
+    let schema_descr = Rc::new(SchemaDescriptor::new(schema.clone()));
+    let size_statistics = SizeStatistics::new(None, Some(vec![3, 1]), Some(vec![0, 4]));
+    let column = ColumnChunkMetaData::builder(schema_descr.column(0))
+      .set_size_statistics(Some(size_statistics.clone()))
+      .set_bloom_filter_offset(Some(1234))
+      .set_bloom_filter_length(Some(56))
+      .build()
+      .unwrap();
+    let row_group = RowGroupMetaData::builder(schema_descr.clone())
+      .set_column_metadata(vec![Rc::new(column)])
+      .build()
+      .unwrap();
+    let file_metadata = FileMetaData::new(1, 0, None, schema, schema_descr, None);
+    let metadata = ParquetMetaData::new(file_metadata, vec![Rc::new(row_group)]);
+
+    let bytes = metadata.to_bytes().unwrap();
+    let result = ParquetMetaData::from_bytes(&bytes).unwrap();
+    assert_eq!(result.row_group(0).column(0).size_statistics(), Some(&size_statistics));
+    assert_eq!(result.row_group(0).column(0).bloom_filter_offset(), Some(1234));
+    assert_eq!(result.row_group(0).column(0).bloom_filter_length(), Some(56));
+    assert!(result.file_metadata().unknown_fields().is_empty());
+    assert_eq!(result.to_bytes().unwrap(), bytes);
+  }
+
+  #[test]
+  fn test_parquet_metadata_to_bytes_statistics_exact() {
+    let schema = Rc::new(
+      SchemaType::group_type_builder("schema")
+        .with_fields(&mut vec![
+          Rc::new(SchemaType::primitive_type_builder("a", Type::INT32).build().unwrap())
+        ])
+        .build()
+        .unwrap()
+    );
+    let schema_descr = Rc::new(SchemaDescriptor::new(schema.clone()));
+    let statistics = Statistics::int32(Some(1), Some(5), None, 0, false)
+      .with_min_max_exact(true, false);
+    let column = ColumnChunkMetaData::builder(schema_descr.column(0))
+      .set_statistics(statistics)
+      .build()
+      .unwrap();
+
+    // Exactness is unknown without unknown fields of the footer
+    let column_descr = schema_descr.column(0);
+    let result = ColumnChunkMetaData::from_thrift(column_descr, column.to_thrift());
+    let result = result.unwrap();
+    let result_statistics = result.statistics().unwrap();
+    assert!(!result_statistics.is_min_exact());
+    assert!(!result_statistics.is_max_exact());
+
+    let row_group = RowGroupMetaData::builder(schema_descr.clone())
+      .set_column_metadata(vec![Rc::new(column)])
+      .build()
+      .unwrap();
+    let file_metadata = FileMetaData::new(1, 0, None, schema, schema_descr, None);
+    let metadata = ParquetMetaData::new(file_metadata, vec![Rc::new(row_group)]);
+
+    let bytes = metadata.to_bytes().unwrap();
+    let result = ParquetMetaData::from_bytes(&bytes).unwrap();
+    let result_row_group = result.row_group(0);
+    let result_statistics = result_row_group.column(0).statistics().unwrap();
+    assert!(result_statistics.is_min_exact());
+    assert!(!result_statistics.is_max_exact());
+    assert_eq!(result.to_bytes().unwrap(), bytes);
+  }
+
+  #[test]
+  fn test_parquet_metadata_from_bytes_of_footer() {
+    let data = fs::read(get_test_path("alltypes_plain.parquet")).unwrap();
+    let metadata_len = LittleEndian::read_i32(&data[data.len() - 8..]) as usize;
+    let footer = &data[data.len() - 8 - metadata_len..data.len() - 8];
+    let metadata = ParquetMetaData::from_bytes(footer).unwrap();
+    assert_eq!(metadata.file_metadata().num_rows(), 8);
+    assert_eq!(metadata.row_group(0).num_columns(), 11);
+
+    let err = ParquetMetaData::from_bytes(&footer[..10]).err().unwrap();
+    assert!(err.to_string().contains("Could not parse metadata"));
   }
 
   /// Returns sample schema descriptor so we can create column metadata.
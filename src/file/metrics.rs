@@ -0,0 +1,209 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains reader metrics, such as number of bytes read and decompressed, number of
+//! pages decoded or skipped and time spent reading and decompressing pages.
+//!
+//! Metrics are opt-in: [`ReaderMetrics`] is shared between file, row group, page and
+//! column readers, created from a file reader with
+//! [`SerializedFileReader::with_metrics`](../reader/struct.SerializedFileReader.html),
+//! and can be inspected at any time, e.g. after a scan.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use std::sync::Arc;
+//! use parquet::file::metrics::ReaderMetrics;
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//!
+//! let metrics = Arc::new(ReaderMetrics::new());
+//! let file = File::open("data/alltypes_plain.parquet").unwrap();
+//! let reader = SerializedFileReader::new(file).unwrap().with_metrics(metrics.clone());
+//!
+//! let iter = reader.get_row_iter(None).unwrap().with_offset(2);
+//! assert_eq!(iter.count(), 6);
+//! assert!(metrics.bytes_read() > 0);
+//! assert_eq!(metrics.rows_pruned(), 2);
+//! ```
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Atomically reference counted pointer for [`ReaderMetrics`].
+pub type ReaderMetricsPtr = Arc<ReaderMetrics>;
+
+/// Counters and timings collected by readers.
+///
+/// All counters are updated atomically, therefore the same instance can be shared by
+/// readers in different threads.
+#[derive(Debug, Default)]
+pub struct ReaderMetrics {
+  // Number of bytes of page headers and pages read from the source
+  bytes_read: AtomicU64,
+  // Number of bytes of pages after decompression
+  bytes_decompressed: AtomicU64,
+  // Number of pages, including dictionary pages, read by page readers
+  pages_read: AtomicU64,
+  // Number of data pages loaded by column readers
+  data_pages_loaded: AtomicU64,
+  // Number of data pages skipped by column readers without decoding
+  pages_skipped: AtomicU64,
+  // Number of corrupt pages skipped or replaced with nulls in lenient mode
+  pages_corrupt: AtomicU64,
+  // Number of rows skipped by record readers
+  rows_pruned: AtomicU64,
+  // Time spent reading pages from the source, in nanoseconds
+  read_time_nanos: AtomicU64,
+  // Time spent decompressing pages, in nanoseconds
+  decompression_time_nanos: AtomicU64
+}
+
+impl ReaderMetrics {
+  /// Creates new metrics with all counters set to 0.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Returns number of bytes of page headers and pages read from the source.
+  pub fn bytes_read(&self) -> u64 {
+    self.bytes_read.load(Ordering::Acquire)
+  }
+
+  /// Returns number of bytes produced by decompressing pages.
+  /// Pages of uncompressed column chunks are not included.
+  pub fn bytes_decompressed(&self) -> u64 {
+    self.bytes_decompressed.load(Ordering::Acquire)
+  }
+
+  /// Returns number of pages, including dictionary pages, read by page readers.
+  pub fn pages_read(&self) -> u64 {
+    self.pages_read.load(Ordering::Acquire)
+  }
+
+  /// Returns number of data pages decoded by column readers, i.e. data pages that were
+  /// loaded and not skipped entirely.
+  pub fn pages_decoded(&self) -> u64 {
+    self.data_pages_loaded.load(Ordering::Acquire).saturating_sub(self.pages_skipped())
+  }
+
+  /// Returns number of data pages skipped by column readers without decoding.
+  pub fn pages_skipped(&self) -> u64 {
+    self.pages_skipped.load(Ordering::Acquire)
+  }
+
+  /// Returns number of corrupt pages skipped or replaced with nulls by page readers in
+  /// lenient mode, see
+  /// [`SerializedFileReader::new_lenient`](../reader/struct.SerializedFileReader.html).
+  pub fn pages_corrupt(&self) -> u64 {
+    self.pages_corrupt.load(Ordering::Acquire)
+  }
+
+  /// Returns number of rows skipped by record readers, e.g. rows before offset or rows
+  /// that do not match a row filter.
+  pub fn rows_pruned(&self) -> u64 {
+    self.rows_pruned.load(Ordering::Acquire)
+  }
+
+  /// Returns total time spent reading page headers and pages from the source.
+  pub fn read_time(&self) -> Duration {
+    Duration::from_nanos(self.read_time_nanos.load(Ordering::Acquire))
+  }
+
+  /// Returns total time spent decompressing pages.
+  pub fn decompression_time(&self) -> Duration {
+    Duration::from_nanos(self.decompression_time_nanos.load(Ordering::Acquire))
+  }
+
+  /// Records `num_bytes` read from the source since `start`.
+  pub fn record_read(&self, num_bytes: usize, start: Instant) {
+    self.bytes_read.fetch_add(num_bytes as u64, Ordering::AcqRel);
+    Self::add_elapsed(&self.read_time_nanos, start);
+  }
+
+  /// Records a page read by a page reader.
+  pub fn record_page_read(&self) {
+    self.pages_read.fetch_add(1, Ordering::AcqRel);
+  }
+
+  /// Records `num_bytes` produced by decompression since `start`.
+  pub fn record_decompression(&self, num_bytes: usize, start: Instant) {
+    self.bytes_decompressed.fetch_add(num_bytes as u64, Ordering::AcqRel);
+    Self::add_elapsed(&self.decompression_time_nanos, start);
+  }
+
+  /// Records a data page loaded by a column reader.
+  pub fn record_data_page_loaded(&self) {
+    self.data_pages_loaded.fetch_add(1, Ordering::AcqRel);
+  }
+
+  /// Records a data page skipped by a column reader without decoding.
+  pub fn record_page_skipped(&self) {
+    self.pages_skipped.fetch_add(1, Ordering::AcqRel);
+  }
+
+  /// Records a corrupt page skipped or replaced with nulls by a page reader.
+  pub fn record_page_corrupt(&self) {
+    self.pages_corrupt.fetch_add(1, Ordering::AcqRel);
+  }
+
+  /// Records `num_rows` rows skipped by a record reader.
+  pub fn record_rows_pruned(&self, num_rows: usize) {
+    self.rows_pruned.fetch_add(num_rows as u64, Ordering::AcqRel);
+  }
+
+  fn add_elapsed(counter: &AtomicU64, start: Instant) {
+    let elapsed = start.elapsed();
+    let nanos = elapsed.as_secs() * 1_000_000_000 + elapsed.subsec_nanos() as u64;
+    counter.fetch_add(nanos, Ordering::AcqRel);
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_reader_metrics() {
+    let metrics = ReaderMetrics::new();
+    assert_eq!(metrics.bytes_read(), 0);
+    assert_eq!(metrics.read_time(), Duration::from_nanos(0));
+
+    let start = Instant::now();
+    metrics.record_read(10, start);
+    metrics.record_read(5, start);
+    metrics.record_page_read();
+    metrics.record_decompression(30, start);
+    metrics.record_data_page_loaded();
+    metrics.record_data_page_loaded();
+    metrics.record_page_skipped();
+    metrics.record_page_corrupt();
+    metrics.record_rows_pruned(7);
+
+    assert_eq!(metrics.bytes_read(), 15);
+    assert_eq!(metrics.bytes_decompressed(), 30);
+    assert_eq!(metrics.pages_read(), 1);
+    assert_eq!(metrics.pages_decoded(), 1);
+    assert_eq!(metrics.pages_skipped(), 1);
+    assert_eq!(metrics.pages_corrupt(), 1);
+    assert_eq!(metrics.rows_pruned(), 7);
+    assert!(metrics.read_time() <= start.elapsed());
+    assert!(metrics.decompression_time() <= start.elapsed());
+  }
+}
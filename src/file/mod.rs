@@ -76,11 +76,30 @@
 //! assert_eq!(&bytes[0..4], &[b'P', b'A', b'R', b'1']);
 //! ```
 
+pub mod bloom_filter;
+pub mod csv;
+pub mod dictionary_cache;
 pub mod metadata;
+pub mod metrics;
+pub mod object;
+pub mod page_cache;
+pub mod page_index;
+pub mod page_metadata;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod prefetch;
 pub mod properties;
 pub mod reader;
+pub mod rewrite;
+pub mod salvage;
+pub mod size_summary;
+pub mod size_statistics;
+pub mod summary;
+pub mod validate;
 pub mod writer;
 pub mod statistics;
 
 const FOOTER_SIZE: usize = 8;
 const PARQUET_MAGIC: [u8; 4] = [b'P', b'A', b'R', b'1'];
+// Magic bytes of files with encrypted footer, see Parquet modular encryption
+const PARQUET_MAGIC_ENCRYPTED_FOOTER: [u8; 4] = [b'P', b'A', b'R', b'E'];
@@ -0,0 +1,387 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains API to read Parquet files stored in remote object stores.
+//!
+//! Object stores, such as S3 or GCS, usually expose two operations that are relevant
+//! for reading Parquet files: fetching the size of an object and fetching a byte range
+//! of an object. [`ObjectReader`](trait.ObjectReader.html) captures exactly these two
+//! operations, and [`ObjectSource`](struct.ObjectSource.html) adapts it into a
+//! [`ParquetReader`](../reader/trait.ParquetReader.html), so it can be passed directly
+//! into `SerializedFileReader`.
+//!
+//! Requests can be retried with [`RetryPolicy`](trait.RetryPolicy.html), see
+//! [`Backoff`](struct.Backoff.html) for exponential backoff between attempts.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs;
+//! use parquet::errors::Result;
+//! use parquet::file::object::{Backoff, ObjectReader, ObjectSource};
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//!
+//! // Object reader that serves bytes from memory; a real implementation would issue
+//! // HEAD and ranged GET requests.
+//! struct InMemoryObject {
+//!   data: Vec<u8>
+//! }
+//!
+//! impl ObjectReader for InMemoryObject {
+//!   fn head(&self) -> Result<u64> {
+//!     Ok(self.data.len() as u64)
+//!   }
+//!
+//!   fn get_range(&self, start: u64, length: usize) -> Result<Vec<u8>> {
+//!     let start = start as usize;
+//!     Ok(self.data[start..start + length].to_vec())
+//!   }
+//! }
+//!
+//! let object = InMemoryObject { data: fs::read("data/alltypes_plain.parquet").unwrap() };
+//! let source = ObjectSource::with_retry_policy(object, Backoff::default()).unwrap();
+//! let reader = SerializedFileReader::new(source).unwrap();
+//! assert_eq!(reader.metadata().num_row_groups(), 1);
+//! ```
+
+use std::cmp;
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use errors::{ParquetError, Result};
+use file::reader::{Length, TryClone};
+
+// ----------------------------------------------------------------------
+// Object reader API
+
+/// Reader of a single object (blob) in an object store.
+///
+/// Implementations are expected to be stateless with regard to the read position, each
+/// call fetches the requested range independently.
+pub trait ObjectReader {
+  /// Returns total size of the object in bytes.
+  fn head(&self) -> Result<u64>;
+
+  /// Returns `length` bytes of the object starting at offset `start`.
+  /// Returned buffer may be shorter than `length` only if the end of the object has
+  /// been reached.
+  fn get_range(&self, start: u64, length: usize) -> Result<Vec<u8>>;
+}
+
+/// Policy that decides whether or not a failed object store request should be retried.
+pub trait RetryPolicy {
+  /// Returns delay before the next attempt, or `None` if request should not be
+  /// retried anymore. `attempt` is the number of failed attempts so far, starting
+  /// with 1.
+  fn retry_delay(&self, attempt: usize, err: &ParquetError) -> Option<Duration>;
+}
+
+/// Retry policy that never retries failed requests.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+  fn retry_delay(&self, _attempt: usize, _err: &ParquetError) -> Option<Duration> {
+    None
+  }
+}
+
+/// Retry policy with exponential backoff.
+/// Delay is doubled after each failed attempt and capped by `max_delay`.
+#[derive(Clone, Copy, Debug)]
+pub struct Backoff {
+  max_retries: usize,
+  base_delay: Duration,
+  max_delay: Duration
+}
+
+impl Backoff {
+  /// Creates new backoff policy.
+  pub fn new(max_retries: usize, base_delay: Duration, max_delay: Duration) -> Self {
+    Self { max_retries: max_retries, base_delay: base_delay, max_delay: max_delay }
+  }
+}
+
+impl Default for Backoff {
+  fn default() -> Self {
+    Self::new(3, Duration::from_millis(100), Duration::from_secs(10))
+  }
+}
+
+impl RetryPolicy for Backoff {
+  fn retry_delay(&self, attempt: usize, _err: &ParquetError) -> Option<Duration> {
+    if attempt > self.max_retries {
+      return None;
+    }
+    let factor = 1u32 << cmp::min(attempt - 1, 31) as u32;
+    let delay = self.base_delay.checked_mul(factor).unwrap_or(self.max_delay);
+    Some(cmp::min(delay, self.max_delay))
+  }
+}
+
+// ----------------------------------------------------------------------
+// Object source
+
+/// Adapter of an [`ObjectReader`] into a seekable stream that can be used as a
+/// `ParquetReader`.
+///
+/// Each `read` call results in a single ranged request, it is recommended to wrap
+/// source with a buffered reader, which is done by `SerializedFileReader` already.
+/// Cloning the source shares the underlying object reader, but keeps independent
+/// position.
+pub struct ObjectSource<O: ObjectReader> {
+  object: Rc<O>,
+  retry_policy: Rc<RetryPolicy>,
+  pos: u64,
+  len: u64
+}
+
+impl<O: ObjectReader> ObjectSource<O> {
+  /// Creates new object source that does not retry failed requests.
+  pub fn new(object: O) -> Result<Self> {
+    Self::with_retry_policy(object, NoRetry)
+  }
+
+  /// Creates new object source with provided retry policy.
+  /// Size of the object is requested eagerly.
+  pub fn with_retry_policy<P: 'static + RetryPolicy>(
+    object: O,
+    retry_policy: P
+  ) -> Result<Self> {
+    let retry_policy: Rc<RetryPolicy> = Rc::new(retry_policy);
+    let len = with_retries(retry_policy.as_ref(), || object.head())?;
+    Ok(Self { object: Rc::new(object), retry_policy: retry_policy, pos: 0, len: len })
+  }
+}
+
+impl<O: ObjectReader> fmt::Debug for ObjectSource<O> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("ObjectSource")
+      .field("pos", &self.pos)
+      .field("len", &self.len)
+      .finish()
+  }
+}
+
+impl<O: ObjectReader> Read for ObjectSource<O> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let bytes_to_read = cmp::min(buf.len() as u64, self.len.saturating_sub(self.pos));
+    if bytes_to_read == 0 {
+      return Ok(0);
+    }
+
+    let (object, pos) = (&self.object, self.pos);
+    let bytes = with_retries(self.retry_policy.as_ref(), || {
+      object.get_range(pos, bytes_to_read as usize)
+    })?;
+
+    let bytes_read = cmp::min(bytes.len(), bytes_to_read as usize);
+    buf[..bytes_read].copy_from_slice(&bytes[..bytes_read]);
+    self.pos += bytes_read as u64;
+    Ok(bytes_read)
+  }
+}
+
+impl<O: ObjectReader> Seek for ObjectSource<O> {
+  fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+    let new_pos = match pos {
+      SeekFrom::Start(offset) => offset as i64,
+      SeekFrom::End(offset) => self.len as i64 + offset,
+      SeekFrom::Current(offset) => self.pos as i64 + offset
+    };
+    if new_pos < 0 {
+      return Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "Invalid seek to a negative position"
+      ));
+    }
+    self.pos = new_pos as u64;
+    Ok(self.pos)
+  }
+}
+
+impl<O: ObjectReader> Length for ObjectSource<O> {
+  fn len(&self) -> u64 {
+    self.len
+  }
+}
+
+impl<O: ObjectReader> TryClone for ObjectSource<O> {
+  fn try_clone(&self) -> Result<Self> {
+    Ok(Self {
+      object: self.object.clone(),
+      retry_policy: self.retry_policy.clone(),
+      pos: self.pos,
+      len: self.len
+    })
+  }
+}
+
+/// Invokes `request` until it succeeds or retry policy gives up.
+fn with_retries<T, F: Fn() -> Result<T>>(policy: &RetryPolicy, request: F) -> Result<T> {
+  let mut attempt = 0;
+  loop {
+    match request() {
+      Ok(value) => return Ok(value),
+      Err(err) => {
+        attempt += 1;
+        match policy.retry_delay(attempt, &err) {
+          Some(delay) => thread::sleep(delay),
+          None => return Err(err)
+        }
+      }
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::cell::Cell;
+
+  use file::reader::{FileReader, SerializedFileReader};
+  use util::test_common::get_test_file;
+
+  struct TestObject {
+    data: Vec<u8>,
+    // Number of requests that should fail before requests start succeeding
+    num_failures: Cell<usize>,
+    num_requests: Cell<usize>
+  }
+
+  impl TestObject {
+    fn new(data: Vec<u8>, num_failures: usize) -> Self {
+      Self { data: data, num_failures: Cell::new(num_failures), num_requests: Cell::new(0) }
+    }
+
+    fn maybe_fail(&self) -> Result<()> {
+      self.num_requests.set(self.num_requests.get() + 1);
+      if self.num_failures.get() > 0 {
+        self.num_failures.set(self.num_failures.get() - 1);
+        return Err(general_err!("Request failed"));
+      }
+      Ok(())
+    }
+  }
+
+  impl ObjectReader for TestObject {
+    fn head(&self) -> Result<u64> {
+      self.maybe_fail()?;
+      Ok(self.data.len() as u64)
+    }
+
+    fn get_range(&self, start: u64, length: usize) -> Result<Vec<u8>> {
+      self.maybe_fail()?;
+      let start = start as usize;
+      Ok(self.data[start..start + length].to_vec())
+    }
+  }
+
+  fn test_file_bytes(name: &str) -> Vec<u8> {
+    let mut buf = vec![];
+    get_test_file(name).read_to_end(&mut buf).unwrap();
+    buf
+  }
+
+  fn zero_backoff(max_retries: usize) -> Backoff {
+    Backoff::new(max_retries, Duration::from_millis(0), Duration::from_millis(0))
+  }
+
+  #[test]
+  fn test_object_source_read_and_seek() {
+    let mut source = ObjectSource::new(TestObject::new(vec![1, 2, 3, 4, 5], 0)).unwrap();
+    assert_eq!(source.len(), 5);
+
+    let mut buf = vec![0; 3];
+    assert_eq!(source.read(&mut buf).unwrap(), 3);
+    assert_eq!(buf, vec![1, 2, 3]);
+    assert_eq!(source.read(&mut buf).unwrap(), 2);
+    assert_eq!(&buf[..2], &[4, 5]);
+    assert_eq!(source.read(&mut buf).unwrap(), 0);
+
+    assert_eq!(source.seek(SeekFrom::End(-2)).unwrap(), 3);
+    assert_eq!(source.read(&mut buf).unwrap(), 2);
+    assert_eq!(&buf[..2], &[4, 5]);
+
+    assert_eq!(source.seek(SeekFrom::Start(1)).unwrap(), 1);
+    assert_eq!(source.seek(SeekFrom::Current(1)).unwrap(), 2);
+    assert!(source.seek(SeekFrom::Current(-3)).is_err());
+  }
+
+  #[test]
+  fn test_object_source_clone_independent_position() {
+    let mut source = ObjectSource::new(TestObject::new(vec![1, 2, 3, 4, 5], 0)).unwrap();
+    source.seek(SeekFrom::Start(2)).unwrap();
+    let mut cloned = source.try_clone().unwrap();
+
+    let mut buf = vec![0; 1];
+    source.read(&mut buf).unwrap();
+    assert_eq!(buf, vec![3]);
+    source.read(&mut buf).unwrap();
+    assert_eq!(buf, vec![4]);
+    cloned.read(&mut buf).unwrap();
+    assert_eq!(buf, vec![3]);
+  }
+
+  #[test]
+  fn test_object_source_no_retry() {
+    let res = ObjectSource::new(TestObject::new(vec![1, 2, 3], 1));
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(err, general_err!("Request failed"));
+    }
+  }
+
+  #[test]
+  fn test_object_source_retry() {
+    let object = TestObject::new(vec![1, 2, 3], 2);
+    let mut source = ObjectSource::with_retry_policy(object, zero_backoff(2)).unwrap();
+    assert_eq!(source.object.num_requests.get(), 3);
+
+    source.object.num_failures.set(3);
+    let res = source.read(&mut vec![0; 3]);
+    assert!(res.is_err());
+    assert_eq!(source.object.num_requests.get(), 6);
+    assert_eq!(source.pos, 0);
+  }
+
+  #[test]
+  fn test_backoff_delay() {
+    let err = general_err!("error");
+    let backoff = Backoff::new(4, Duration::from_millis(100), Duration::from_millis(500));
+    assert_eq!(backoff.retry_delay(1, &err), Some(Duration::from_millis(100)));
+    assert_eq!(backoff.retry_delay(2, &err), Some(Duration::from_millis(200)));
+    assert_eq!(backoff.retry_delay(3, &err), Some(Duration::from_millis(400)));
+    assert_eq!(backoff.retry_delay(4, &err), Some(Duration::from_millis(500)));
+    assert_eq!(backoff.retry_delay(5, &err), None);
+    assert_eq!(NoRetry.retry_delay(1, &err), None);
+  }
+
+  #[test]
+  fn test_object_source_file_reader() {
+    let object = TestObject::new(test_file_bytes("alltypes_plain.parquet"), 0);
+    let source = ObjectSource::new(object).unwrap();
+    let reader = SerializedFileReader::new(source).unwrap();
+    assert_eq!(reader.metadata().num_row_groups(), 1);
+    assert_eq!(reader.get_row_iter(None).unwrap().count(), 8);
+  }
+}
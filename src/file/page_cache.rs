@@ -0,0 +1,388 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains cache of decompressed pages of column chunks.
+//!
+//! Every read of a column chunk reads page headers and decompresses pages. When the
+//! same column chunks are read several times, e.g. a predicate pass over a few columns
+//! followed by a pass that materializes rows, or random access to row groups,
+//! [`PageCache`] keeps pages of recently read column chunks in memory so that later
+//! reads do not repeat this work. Decryption of pages, once supported, is cached the
+//! same way.
+//!
+//! Cache is attached to a file reader with
+//! [`SerializedFileReader::with_page_cache`](../reader/struct.SerializedFileReader.html)
+//! and is bounded by the limit of its memory tracker: least recently used column chunks
+//! are evicted to make room for new ones, and column chunks that do not fit are not
+//! cached. Memory tracker can be shared with other components to account cached pages
+//! in a common memory pool.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use parquet::file::page_cache::PageCache;
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//!
+//! let file = File::open("data/alltypes_plain.parquet").unwrap();
+//! let reader = SerializedFileReader::new(file)
+//!   .unwrap()
+//!   .with_page_cache(PageCache::with_capacity(1024 * 1024));
+//!
+//! // First pass reads and caches pages, second pass only uses cached pages
+//! assert_eq!(reader.get_row_iter(None).unwrap().count(), 8);
+//! assert_eq!(reader.get_row_iter(None).unwrap().count(), 8);
+//! let cache = reader.page_cache().unwrap();
+//! assert_eq!(cache.num_entries(), 11);
+//! assert_eq!(cache.hits(), 11);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::mem;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use column::page::{Page, PageReader};
+use errors::Result;
+use util::memory::{MemTracker, MemTrackerPtr};
+
+/// Reference counted pointer for [`PageCache`].
+pub type PageCachePtr = Rc<PageCache>;
+
+/// Cache of pages of column chunks of a file, identified by row group and column
+/// indexes, with least recently used eviction.
+pub struct PageCache {
+  mem_tracker: MemTrackerPtr,
+  // Cached column chunks, ordered from least to most recently used
+  entries: RefCell<Vec<CacheEntry>>,
+  hits: Cell<u64>,
+  misses: Cell<u64>
+}
+
+/// Pages of one column chunk.
+struct CacheEntry {
+  row_group: usize,
+  column: usize,
+  pages: Rc<Vec<Page>>,
+  num_bytes: i64
+}
+
+impl PageCache {
+  /// Creates page cache that accounts cached pages in `mem_tracker`. Cache is bounded
+  /// by the limit of `mem_tracker`, if set, and is unbounded otherwise.
+  pub fn new(mem_tracker: MemTrackerPtr) -> Self {
+    Self {
+      mem_tracker: mem_tracker,
+      entries: RefCell::new(Vec::new()),
+      hits: Cell::new(0),
+      misses: Cell::new(0)
+    }
+  }
+
+  /// Creates page cache with its own memory tracker that allows at most `num_bytes`
+  /// bytes to be cached.
+  pub fn with_capacity(num_bytes: i64) -> Self {
+    Self::new(Arc::new(MemTracker::with_limit(num_bytes)))
+  }
+
+  /// Returns memory tracker of this cache.
+  pub fn mem_tracker(&self) -> &MemTrackerPtr {
+    &self.mem_tracker
+  }
+
+  /// Returns number of bytes of cached pages.
+  pub fn memory_usage(&self) -> i64 {
+    self.entries.borrow().iter().map(|entry| entry.num_bytes).sum()
+  }
+
+  /// Returns number of cached column chunks.
+  pub fn num_entries(&self) -> usize {
+    self.entries.borrow().len()
+  }
+
+  /// Returns number of reads of column chunks served from the cache.
+  pub fn hits(&self) -> u64 {
+    self.hits.get()
+  }
+
+  /// Returns number of reads of column chunks that were not cached.
+  pub fn misses(&self) -> u64 {
+    self.misses.get()
+  }
+
+  /// Removes all cached pages.
+  pub fn clear(&self) {
+    let mut entries = self.entries.borrow_mut();
+    for entry in entries.drain(..) {
+      self.mem_tracker.alloc(-entry.num_bytes);
+    }
+  }
+
+  /// Returns cached pages of column `column` of row group `row_group`, if any.
+  fn get(&self, row_group: usize, column: usize) -> Option<Rc<Vec<Page>>> {
+    let mut entries = self.entries.borrow_mut();
+    let pos = entries.iter().position(|e| e.row_group == row_group && e.column == column);
+    match pos {
+      Some(pos) => {
+        let entry = entries.remove(pos);
+        let pages = entry.pages.clone();
+        entries.push(entry);
+        self.hits.set(self.hits.get() + 1);
+        Some(pages)
+      },
+      None => {
+        self.misses.set(self.misses.get() + 1);
+        None
+      }
+    }
+  }
+
+  /// Returns `true` if column chunk of `num_bytes` bytes can be cached.
+  fn fits(&self, num_bytes: i64) -> bool {
+    self.mem_tracker.limit().map_or(true, |limit| num_bytes <= limit)
+  }
+
+  /// Caches `pages` of `num_bytes` bytes, evicting least recently used column chunks
+  /// as needed. Pages are not cached if there is not enough memory after eviction.
+  fn insert(&self, row_group: usize, column: usize, pages: Vec<Page>, num_bytes: i64) {
+    let mut entries = self.entries.borrow_mut();
+    if entries.iter().any(|e| e.row_group == row_group && e.column == column) {
+      return;
+    }
+    while self.mem_tracker.try_alloc(num_bytes).is_err() {
+      if entries.is_empty() {
+        return;
+      }
+      let entry = entries.remove(0);
+      self.mem_tracker.alloc(-entry.num_bytes);
+    }
+    entries.push(CacheEntry {
+      row_group: row_group,
+      column: column,
+      pages: Rc::new(pages),
+      num_bytes: num_bytes
+    });
+  }
+}
+
+impl Drop for PageCache {
+  fn drop(&mut self) {
+    self.clear();
+  }
+}
+
+/// Returns number of bytes accounted for `page` in the cache.
+fn page_size(page: &Page) -> i64 {
+  (page.buffer().len() + mem::size_of::<Page>()) as i64
+}
+
+/// Returns page reader of column `column` of row group `row_group`, which reads pages
+/// from `cache`, or from page reader created by `create_reader` if pages are not
+/// cached, in which case pages are cached once all of them are read.
+pub fn get_cached_page_reader<F>(
+  cache: &PageCachePtr,
+  row_group: usize,
+  column: usize,
+  create_reader: F
+) -> Result<Box<PageReader>>
+  where F: FnOnce() -> Result<Box<PageReader>> {
+  if let Some(pages) = cache.get(row_group, column) {
+    return Ok(Box::new(CachedPageReader { pages: pages, pos: 0 }));
+  }
+  Ok(Box::new(CachingPageReader {
+    inner: create_reader()?,
+    cache: cache.clone(),
+    row_group: row_group,
+    column: column,
+    pages: Some(Vec::new()),
+    num_bytes: 0
+  }))
+}
+
+/// Page reader over cached pages of a column chunk.
+struct CachedPageReader {
+  pages: Rc<Vec<Page>>,
+  pos: usize
+}
+
+impl PageReader for CachedPageReader {
+  fn get_next_page(&mut self) -> Result<Option<Page>> {
+    let page = self.pages.get(self.pos).cloned();
+    self.pos += 1;
+    Ok(page)
+  }
+}
+
+/// Page reader that collects pages read from the inner reader and caches them when
+/// the whole column chunk is read.
+struct CachingPageReader {
+  inner: Box<PageReader>,
+  cache: PageCachePtr,
+  row_group: usize,
+  column: usize,
+  // Pages read so far, `None` if column chunk is not going to be cached
+  pages: Option<Vec<Page>>,
+  num_bytes: i64
+}
+
+impl PageReader for CachingPageReader {
+  fn get_next_page(&mut self) -> Result<Option<Page>> {
+    let page = match self.inner.get_next_page() {
+      Ok(page) => page,
+      Err(e) => {
+        self.pages = None;
+        return Err(e);
+      }
+    };
+    match page {
+      Some(page) => {
+        if self.pages.is_some() {
+          self.num_bytes += page_size(&page);
+          if self.cache.fits(self.num_bytes) {
+            self.pages.as_mut().unwrap().push(page.clone());
+          } else {
+            self.pages = None;
+          }
+        }
+        Ok(Some(page))
+      },
+      None => {
+        if let Some(pages) = self.pages.take() {
+          self.cache.insert(self.row_group, self.column, pages, self.num_bytes);
+        }
+        Ok(None)
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use basic::Encoding;
+  use util::memory::ByteBufferPtr;
+
+  fn make_page(len: usize) -> Page {
+    Page::DataPage {
+      buf: ByteBufferPtr::new(vec![0; len]),
+      num_values: len as u32,
+      encoding: Encoding::PLAIN,
+      def_level_encoding: Encoding::RLE,
+      rep_level_encoding: Encoding::RLE,
+      statistics: None
+    }
+  }
+
+  struct TestPageReader {
+    pages: Vec<Page>,
+    num_reads: Rc<Cell<usize>>
+  }
+
+  impl PageReader for TestPageReader {
+    fn get_next_page(&mut self) -> Result<Option<Page>> {
+      self.num_reads.set(self.num_reads.get() + 1);
+      if self.pages.is_empty() {
+        Ok(None)
+      } else {
+        Ok(Some(self.pages.remove(0)))
+      }
+    }
+  }
+
+  /// Reads all pages of column chunk `(row_group, column)` of `page_lens` through
+  /// `cache`, returns lengths of pages read and number of reads of the source.
+  fn read_pages(
+    cache: &PageCachePtr,
+    row_group: usize,
+    column: usize,
+    page_lens: &[usize]
+  ) -> (Vec<usize>, usize) {
+    let num_reads = Rc::new(Cell::new(0));
+    let source = TestPageReader {
+      pages: page_lens.iter().map(|len| make_page(*len)).collect(),
+      num_reads: num_reads.clone()
+    };
+    let mut reader = get_cached_page_reader(cache, row_group, column, || {
+      Ok(Box::new(source))
+    }).unwrap();
+    let mut lens = Vec::new();
+    while let Some(page) = reader.get_next_page().unwrap() {
+      lens.push(page.buffer().len());
+    }
+    (lens, num_reads.get())
+  }
+
+  #[test]
+  fn test_page_cache_hit() {
+    let cache = Rc::new(PageCache::new(Arc::new(MemTracker::new())));
+    assert_eq!(read_pages(&cache, 0, 1, &[10, 20]), (vec![10, 20], 3));
+    assert_eq!(cache.num_entries(), 1);
+    assert_eq!(cache.misses(), 1);
+    let expected_size = 30 + 2 * mem::size_of::<Page>() as i64;
+    assert_eq!(cache.memory_usage(), expected_size);
+    assert_eq!(cache.mem_tracker().memory_usage(), expected_size);
+
+    // Source is not read again
+    assert_eq!(read_pages(&cache, 0, 1, &[10, 20]), (vec![10, 20], 0));
+    assert_eq!(cache.hits(), 1);
+    assert_eq!(read_pages(&cache, 1, 1, &[5]), (vec![5], 2));
+    assert_eq!(cache.num_entries(), 2);
+
+    cache.clear();
+    assert_eq!(cache.num_entries(), 0);
+    assert_eq!(cache.mem_tracker().memory_usage(), 0);
+  }
+
+  #[test]
+  fn test_page_cache_eviction() {
+    let page_size = 100 + mem::size_of::<Page>() as i64;
+    let cache = Rc::new(PageCache::with_capacity(2 * page_size));
+    read_pages(&cache, 0, 0, &[100]);
+    read_pages(&cache, 0, 1, &[100]);
+    // Column 0 becomes the most recently used one, column 1 is evicted
+    assert_eq!(read_pages(&cache, 0, 0, &[100]).1, 0);
+    read_pages(&cache, 0, 2, &[100]);
+    assert_eq!(cache.num_entries(), 2);
+    assert_eq!(cache.mem_tracker().memory_usage(), 2 * page_size);
+    assert_eq!(read_pages(&cache, 0, 0, &[100]).1, 0);
+    assert_eq!(read_pages(&cache, 0, 2, &[100]).1, 0);
+    assert_eq!(read_pages(&cache, 0, 1, &[100]).1, 2);
+
+    // Column chunk larger than the cache is not cached
+    read_pages(&cache, 1, 0, &[100, 100, 100]);
+    assert!(cache.get(1, 0).is_none());
+    assert_eq!(cache.num_entries(), 2);
+  }
+
+  #[test]
+  fn test_page_cache_partial_read() {
+    let cache = Rc::new(PageCache::with_capacity(1024 * 1024));
+    let source = TestPageReader {
+      pages: vec![make_page(10), make_page(20)],
+      num_reads: Rc::new(Cell::new(0))
+    };
+    {
+      let mut reader =
+        get_cached_page_reader(&cache, 0, 0, || Ok(Box::new(source))).unwrap();
+      assert!(reader.get_next_page().unwrap().is_some());
+    }
+    // Column chunk is only cached when all pages are read
+    assert_eq!(cache.num_entries(), 0);
+    assert_eq!(cache.mem_tracker().memory_usage(), 0);
+  }
+}
@@ -0,0 +1,698 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains API to read page indexes of a column chunk.
+//!
+//! Page indexes are stored between row groups and file metadata, and consist of an
+//! offset index ([`OffsetIndex`]) with location and first row and, in files written by
+//! newer writers, size of unencoded `BYTE_ARRAY` values of each data page, and
+//! a column index ([`ColumnIndex`]) with min/max values, null counts and, in files
+//! written by newer writers, definition and repetition level histograms of each data
+//! page. Both are optional, use [`read_offset_index`] and [`read_column_index`] to
+//! load them for a column chunk.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use parquet::file::page_index::read_offset_index;
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//!
+//! let mut file = File::open("data/alltypes_plain.parquet").unwrap();
+//! let reader = SerializedFileReader::new(file.try_clone().unwrap()).unwrap();
+//! let metadata = reader.metadata();
+//! let row_group = metadata.row_group(0);
+//! // File does not have page indexes
+//! assert!(read_offset_index(&mut file, row_group.column(0)).unwrap().is_none());
+//! ```
+
+use std::fmt;
+use std::io::{Read, Seek, SeekFrom};
+
+use parquet_format::{self, ColumnIndex as TColumnIndex, OffsetIndex as TOffsetIndex};
+use parquet_format::{Statistics as TStatistics};
+use thrift::protocol::{TCompactInputProtocol, TType};
+
+use basic::Type;
+use encryption::ciphers::{ColumnCipher, ModuleType};
+use encryption::decrypt::column_cipher;
+use errors::{ErrorContext, ParquetError, Result};
+use file::metadata::ColumnChunkMetaData;
+use file::reader::Length;
+use file::size_statistics;
+use file::statistics::{self, Statistics};
+use util::thrift::{TolerantInputProtocol, UnknownEnums};
+
+/// Location of a data page in a column chunk.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageLocation {
+  offset: i64,
+  compressed_page_size: i32,
+  first_row_index: i64
+}
+
+impl PageLocation {
+  /// Creates location of a data page at `offset` of `compressed_page_size` bytes,
+  /// including page header, that starts at row `first_row_index` of the row group.
+  pub fn new(offset: i64, compressed_page_size: i32, first_row_index: i64) -> Self {
+    Self { offset, compressed_page_size, first_row_index }
+  }
+
+  /// Returns offset of the page header in the file.
+  pub fn offset(&self) -> i64 {
+    self.offset
+  }
+
+  /// Returns size of the page in bytes, including page header.
+  pub fn compressed_page_size(&self) -> i32 {
+    self.compressed_page_size
+  }
+
+  /// Returns index of the first row of the page within the row group.
+  pub fn first_row_index(&self) -> i64 {
+    self.first_row_index
+  }
+}
+
+/// Offset index of a column chunk, contains location of each data page.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OffsetIndex {
+  page_locations: Vec<PageLocation>,
+  unencoded_byte_array_data_bytes: Option<Vec<i64>>
+}
+
+impl OffsetIndex {
+  /// Creates offset index from locations of data pages.
+  pub fn new(page_locations: Vec<PageLocation>) -> Self {
+    Self { page_locations, unencoded_byte_array_data_bytes: None }
+  }
+
+  /// Converts Thrift definition into offset index.
+  pub fn from_thrift(index: TOffsetIndex) -> Self {
+    let page_locations = index.page_locations.into_iter()
+      .map(|loc| PageLocation {
+        offset: loc.offset,
+        compressed_page_size: loc.compressed_page_size,
+        first_row_index: loc.first_row_index
+      })
+      .collect();
+    Self { page_locations, unencoded_byte_array_data_bytes: None }
+  }
+
+  /// Sets number of bytes of unencoded `BYTE_ARRAY` values of each page.
+  pub fn with_unencoded_byte_array_data_bytes(
+    mut self,
+    unencoded_byte_array_data_bytes: Option<Vec<i64>>
+  ) -> Result<Self> {
+    if let Some(ref bytes) = unencoded_byte_array_data_bytes {
+      if bytes.len() != self.num_pages() {
+        return Err(general_err!(
+          "Offset index has {} page locations, but {} unencoded byte array sizes",
+          self.num_pages(),
+          bytes.len()
+        ));
+      }
+    }
+    self.unencoded_byte_array_data_bytes = unencoded_byte_array_data_bytes;
+    Ok(self)
+  }
+
+  /// Returns number of data pages.
+  pub fn num_pages(&self) -> usize {
+    self.page_locations.len()
+  }
+
+  /// Returns locations of data pages in the order they are stored.
+  pub fn page_locations(&self) -> &[PageLocation] {
+    &self.page_locations
+  }
+
+  /// Returns number of bytes of `BYTE_ARRAY` values, excluding their lengths, before
+  /// encoding, of each data page, or `None` if not available, e.g. for other types.
+  pub fn unencoded_byte_array_data_bytes(&self) -> Option<&[i64]> {
+    self.unencoded_byte_array_data_bytes.as_ref().map(|v| &v[..])
+  }
+
+  /// Converts offset index into Thrift definition. Sizes of unencoded `BYTE_ARRAY`
+  /// values are not in the Thrift definition and are not converted.
+  pub fn to_thrift(&self) -> TOffsetIndex {
+    let page_locations = self.page_locations.iter()
+      .map(|loc| {
+        parquet_format::PageLocation::new(
+          loc.offset, loc.compressed_page_size, loc.first_row_index)
+      })
+      .collect();
+    TOffsetIndex::new(page_locations)
+  }
+}
+
+/// Ordering of min/max values of pages in a column index.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BoundaryOrder {
+  UNORDERED,
+  ASCENDING,
+  DESCENDING
+}
+
+impl fmt::Display for BoundaryOrder {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+impl From<BoundaryOrder> for parquet_format::BoundaryOrder {
+  fn from(value: BoundaryOrder) -> Self {
+    match value {
+      BoundaryOrder::UNORDERED => parquet_format::BoundaryOrder::UNORDERED,
+      BoundaryOrder::ASCENDING => parquet_format::BoundaryOrder::ASCENDING,
+      BoundaryOrder::DESCENDING => parquet_format::BoundaryOrder::DESCENDING
+    }
+  }
+}
+
+impl From<parquet_format::BoundaryOrder> for BoundaryOrder {
+  fn from(value: parquet_format::BoundaryOrder) -> Self {
+    match value {
+      parquet_format::BoundaryOrder::UNORDERED => BoundaryOrder::UNORDERED,
+      parquet_format::BoundaryOrder::ASCENDING => BoundaryOrder::ASCENDING,
+      parquet_format::BoundaryOrder::DESCENDING => BoundaryOrder::DESCENDING
+    }
+  }
+}
+
+/// Column index of a column chunk, contains statistics of each data page.
+#[derive(Debug, PartialEq)]
+pub struct ColumnIndex {
+  boundary_order: BoundaryOrder,
+  null_pages: Vec<bool>,
+  page_statistics: Vec<Statistics>,
+  // Histograms of all pages concatenated, each page has the same number of levels
+  repetition_level_histograms: Option<Vec<i64>>,
+  definition_level_histograms: Option<Vec<i64>>
+}
+
+impl ColumnIndex {
+  /// Creates column index from statistics of each data page. Pages that contain only
+  /// null values are marked in `null_pages`, statistics of other pages must have
+  /// min/max values set.
+  pub fn new(
+    boundary_order: BoundaryOrder,
+    null_pages: Vec<bool>,
+    page_statistics: Vec<Statistics>
+  ) -> Result<Self> {
+    if null_pages.len() != page_statistics.len() {
+      return Err(general_err!(
+        "Column index has {} null page flags, but {} page statistics",
+        null_pages.len(),
+        page_statistics.len()
+      ));
+    }
+    let missing = null_pages.iter().zip(page_statistics.iter())
+      .position(|(is_null, stats)| !is_null && !stats.has_min_max_set());
+    if let Some(i) = missing {
+      return Err(general_err!("Statistics of page {} do not have min/max values", i));
+    }
+    Ok(Self {
+      boundary_order,
+      null_pages,
+      page_statistics,
+      repetition_level_histograms: None,
+      definition_level_histograms: None
+    })
+  }
+
+  /// Converts Thrift definition into column index, min/max values are decoded
+  /// according to `physical_type` of the column.
+  pub fn from_thrift(physical_type: Type, index: TColumnIndex) -> Result<Self> {
+    let num_pages = index.null_pages.len();
+    if index.min_values.len() != num_pages || index.max_values.len() != num_pages {
+      return Err(general_err!(
+        "Column index has {} null page flags, but {} min values and {} max values",
+        num_pages,
+        index.min_values.len(),
+        index.max_values.len()
+      ));
+    }
+    if let Some(ref null_counts) = index.null_counts {
+      if null_counts.len() != num_pages {
+        return Err(general_err!(
+          "Column index has {} null page flags, but {} null counts",
+          num_pages,
+          null_counts.len()
+        ));
+      }
+    }
+
+    let mut page_statistics = Vec::with_capacity(num_pages);
+    let values = index.min_values.into_iter().zip(index.max_values);
+    for (i, (min, max)) in values.enumerate() {
+      // Min/max values of null pages are empty and not decoded.
+      let (min, max) = if index.null_pages[i] {
+        (None, None)
+      } else {
+        (Some(min), Some(max))
+      };
+      let thrift_stats = TStatistics {
+        max: None,
+        min: None,
+        null_count: index.null_counts.as_ref().map(|counts| counts[i]),
+        distinct_count: None,
+        max_value: max,
+        min_value: min
+      };
+      let stats = match statistics::from_thrift(physical_type, Some(thrift_stats)) {
+        Some(stats) => stats,
+        None => return Err(general_err!("Could not decode statistics of page {}", i))
+      };
+      page_statistics.push(stats);
+    }
+
+    Ok(Self {
+      boundary_order: index.boundary_order.into(),
+      null_pages: index.null_pages,
+      page_statistics,
+      repetition_level_histograms: None,
+      definition_level_histograms: None
+    })
+  }
+
+  /// Sets repetition and definition level histograms of all pages, concatenated in the
+  /// order of pages. Each histogram has the same length, which is max level + 1.
+  pub fn with_level_histograms(
+    mut self,
+    repetition_level_histograms: Option<Vec<i64>>,
+    definition_level_histograms: Option<Vec<i64>>
+  ) -> Result<Self> {
+    let num_pages = self.num_pages();
+    for histograms in
+        repetition_level_histograms.iter().chain(definition_level_histograms.iter()) {
+      if num_pages == 0 || histograms.is_empty() || histograms.len() % num_pages != 0 {
+        return Err(general_err!(
+          "Column index has {} pages, but {} level histogram values",
+          num_pages,
+          histograms.len()
+        ));
+      }
+    }
+    self.repetition_level_histograms = repetition_level_histograms;
+    self.definition_level_histograms = definition_level_histograms;
+    Ok(self)
+  }
+
+  /// Returns number of data pages.
+  pub fn num_pages(&self) -> usize {
+    self.null_pages.len()
+  }
+
+  /// Returns ordering of min/max values of pages.
+  pub fn boundary_order(&self) -> BoundaryOrder {
+    self.boundary_order
+  }
+
+  /// Returns flags, for each page, whether or not page contains only null values.
+  pub fn null_pages(&self) -> &[bool] {
+    &self.null_pages
+  }
+
+  /// Returns statistics of each page. Null pages do not have min/max values.
+  pub fn page_statistics(&self) -> &[Statistics] {
+    &self.page_statistics
+  }
+
+  /// Returns number of values for each repetition level of page `i`, or `None` if
+  /// histograms are not available.
+  pub fn repetition_level_histogram(&self, i: usize) -> Option<&[i64]> {
+    self.page_histogram(&self.repetition_level_histograms, i)
+  }
+
+  /// Returns number of values for each definition level of page `i`, or `None` if
+  /// histograms are not available.
+  pub fn definition_level_histogram(&self, i: usize) -> Option<&[i64]> {
+    self.page_histogram(&self.definition_level_histograms, i)
+  }
+
+  /// Converts column index into Thrift definition. Level histograms are not in the
+  /// Thrift definition and are not converted.
+  pub fn to_thrift(&self) -> TColumnIndex {
+    let mut min_values = Vec::with_capacity(self.num_pages());
+    let mut max_values = Vec::with_capacity(self.num_pages());
+    for (is_null, stats) in self.null_pages.iter().zip(self.page_statistics.iter()) {
+      if *is_null {
+        min_values.push(Vec::new());
+        max_values.push(Vec::new());
+      } else {
+        min_values.push(stats.min_bytes().to_vec());
+        max_values.push(stats.max_bytes().to_vec());
+      }
+    }
+    let null_counts =
+      self.page_statistics.iter().map(|stats| stats.null_count() as i64).collect();
+    TColumnIndex::new(
+      self.null_pages.clone(),
+      min_values,
+      max_values,
+      self.boundary_order.into(),
+      Some(null_counts)
+    )
+  }
+
+  fn page_histogram<'a>(
+    &self,
+    histograms: &'a Option<Vec<i64>>,
+    i: usize
+  ) -> Option<&'a [i64]> {
+    histograms.as_ref().map(|histograms| {
+      let num_levels = histograms.len() / self.num_pages();
+      &histograms[i * num_levels..(i + 1) * num_levels]
+    })
+  }
+}
+
+/// Reads offset index of a column chunk, returns `None` if column chunk does not have
+/// an offset index. Indexes of encrypted column chunks are read with
+/// [`SerializedFileReader::read_offset_index`](
+/// ../reader/struct.SerializedFileReader.html#method.read_offset_index).
+pub fn read_offset_index<R: Read + Seek + Length>(
+  reader: &mut R,
+  column: &ColumnChunkMetaData
+) -> Result<Option<OffsetIndex>> {
+  let cipher = column_cipher(None, column, 0, 0)?;
+  read_offset_index_with_cipher(reader, column, cipher.as_ref())
+}
+
+/// Reads offset index of a column chunk, which is decrypted with `cipher` if set.
+pub(crate) fn read_offset_index_with_cipher<R: Read + Seek + Length>(
+  reader: &mut R,
+  column: &ColumnChunkMetaData,
+  cipher: Option<&ColumnCipher>
+) -> Result<Option<OffsetIndex>> {
+  match (column.offset_index_offset(), column.offset_index_length()) {
+    (Some(offset), Some(length)) => {
+      let buf =
+        read_index_bytes(reader, offset, length, cipher, ModuleType::OFFSET_INDEX)?;
+      let mut prot = TolerantInputProtocol::for_offset_index(
+        TCompactInputProtocol::new(&buf[..]))
+        .with_max_collection_size(buf.len())
+        .with_max_binary_size(buf.len());
+      let index = TOffsetIndex::read_from_in_protocol(&mut prot)
+        .map_err(|e| index_err(offset, e))?;
+      let unknown_fields = prot.into_unknown_enums();
+      let bytes_field_id = UNENCODED_BYTE_ARRAY_DATA_BYTES_FIELD_ID;
+      let index = i64_list_field(&unknown_fields, bytes_field_id)
+        .and_then(|bytes| {
+          OffsetIndex::from_thrift(index).with_unencoded_byte_array_data_bytes(bytes)
+        })
+        .map_err(|e| e.with_context(ErrorContext::new().with_offset(offset as u64)))?;
+      Ok(Some(index))
+    },
+    _ => Ok(None)
+  }
+}
+
+/// Reads column index of a column chunk, returns `None` if column chunk does not have
+/// a column index. Indexes of encrypted column chunks are read with
+/// [`SerializedFileReader::read_column_index`](
+/// ../reader/struct.SerializedFileReader.html#method.read_column_index).
+pub fn read_column_index<R: Read + Seek + Length>(
+  reader: &mut R,
+  column: &ColumnChunkMetaData
+) -> Result<Option<ColumnIndex>> {
+  let cipher = column_cipher(None, column, 0, 0)?;
+  read_column_index_with_cipher(reader, column, cipher.as_ref())
+}
+
+/// Reads column index of a column chunk, which is decrypted with `cipher` if set.
+pub(crate) fn read_column_index_with_cipher<R: Read + Seek + Length>(
+  reader: &mut R,
+  column: &ColumnChunkMetaData,
+  cipher: Option<&ColumnCipher>
+) -> Result<Option<ColumnIndex>> {
+  match (column.column_index_offset(), column.column_index_length()) {
+    (Some(offset), Some(length)) => {
+      let buf =
+        read_index_bytes(reader, offset, length, cipher, ModuleType::COLUMN_INDEX)?;
+      let mut prot = TolerantInputProtocol::for_column_index(
+        TCompactInputProtocol::new(&buf[..]))
+        .with_max_collection_size(buf.len())
+        .with_max_binary_size(buf.len());
+      let index = TColumnIndex::read_from_in_protocol(&mut prot)
+        .map_err(|e| index_err(offset, e))?;
+      let unknown_fields = prot.into_unknown_enums();
+      let index = ColumnIndex::from_thrift(column.column_type(), index)
+        .and_then(|index| {
+          index.with_level_histograms(
+            i64_list_field(&unknown_fields, REPETITION_LEVEL_HISTOGRAMS_FIELD_ID)?,
+            i64_list_field(&unknown_fields, DEFINITION_LEVEL_HISTOGRAMS_FIELD_ID)?
+          )
+        })
+        .map_err(|e| e.with_context(ErrorContext::new().with_offset(offset as u64)))?;
+      Ok(Some(index))
+    },
+    _ => Ok(None)
+  }
+}
+
+/// Id of the `unencoded_byte_array_data_bytes` field of `OffsetIndex`, which is not in
+/// `parquet_format`.
+const UNENCODED_BYTE_ARRAY_DATA_BYTES_FIELD_ID: usize = 2;
+
+/// Id of the `repetition_level_histograms` field of `ColumnIndex`, which is not in
+/// `parquet_format`.
+const REPETITION_LEVEL_HISTOGRAMS_FIELD_ID: usize = 6;
+
+/// Id of the `definition_level_histograms` field of `ColumnIndex`, which is not in
+/// `parquet_format`.
+const DEFINITION_LEVEL_HISTOGRAMS_FIELD_ID: usize = 7;
+
+/// Decodes `list<i64>` value of an index kept as unknown field `id`, if any.
+fn i64_list_field(
+  unknown_fields: &UnknownEnums,
+  id: usize
+) -> Result<Option<Vec<i64>>> {
+  match unknown_fields.field(&[id]) {
+    Some((TType::List, data)) => {
+      let mut prot = TCompactInputProtocol::new(data);
+      Ok(Some(size_statistics::read_i64_list(&mut prot)?))
+    },
+    _ => Ok(None)
+  }
+}
+
+/// Reads `length` bytes of an index located at `offset`, and decrypts them as module
+/// `module_type` if `cipher` is set.
+fn read_index_bytes<R: Read + Seek + Length>(
+  reader: &mut R,
+  offset: i64,
+  length: i32,
+  cipher: Option<&ColumnCipher>,
+  module_type: ModuleType
+) -> Result<Vec<u8>> {
+  let in_bounds = match offset.checked_add(length as i64) {
+    Some(end) => offset >= 0 && length >= 0 && end as u64 <= reader.len(),
+    None => false
+  };
+  if !in_bounds {
+    return Err(general_err!(
+      "Index is out of bounds: offset {}, size {}",
+      offset,
+      length
+    ));
+  }
+  let mut buf = vec![0; length as usize];
+  reader.seek(SeekFrom::Start(offset as u64))?;
+  reader.read_exact(&mut buf)?;
+  match cipher {
+    Some(cipher) => cipher.decrypt(module_type, 0, &buf)
+      .map_err(|e| e.with_context(ErrorContext::new().with_offset(offset as u64))),
+    None => Ok(buf)
+  }
+}
+
+fn index_err(offset: i64, e: ::thrift::Error) -> ParquetError {
+  general_err!("Could not parse index: {}", e)
+    .with_context(ErrorContext::new().with_offset(offset as u64))
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::io::Cursor;
+  use std::rc::Rc;
+
+  use parquet_format::PageLocation as TPageLocation;
+  use thrift::protocol::TCompactOutputProtocol;
+
+  use schema::types::{ColumnDescriptor, ColumnPath, Type as SchemaType};
+
+  fn get_column(
+    offset_index: Option<(i64, i32)>,
+    column_index: Option<(i64, i32)>
+  ) -> ColumnChunkMetaData {
+    let tpe = SchemaType::primitive_type_builder("a", Type::INT32).build().unwrap();
+    let descr = ColumnDescriptor::new(Rc::new(tpe), None, 0, 0, ColumnPath::from("a"));
+    ColumnChunkMetaData::builder(Rc::new(descr))
+      .set_offset_index_offset(offset_index.map(|i| i.0))
+      .set_offset_index_length(offset_index.map(|i| i.1))
+      .set_column_index_offset(column_index.map(|i| i.0))
+      .set_column_index_length(column_index.map(|i| i.1))
+      .build()
+      .unwrap()
+  }
+
+  #[test]
+  fn test_read_page_indexes() {
+    let offset_index = TOffsetIndex::new(vec![
+      TPageLocation::new(4, 20, 0),
+      TPageLocation::new(24, 30, 10)
+    ]);
+    let column_index = TColumnIndex::new(
+      vec![false, true],
+      vec![vec![1, 0, 0, 0], vec![]],
+      vec![vec![9, 0, 0, 0], vec![]],
+      parquet_format::BoundaryOrder::ASCENDING,
+      Some(vec![0, 5])
+    );
+
+    let mut buf = vec![0; 4];
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut buf);
+      offset_index.write_to_out_protocol(&mut prot).unwrap();
+    }
+    let offset_index_len = buf.len() - 4;
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut buf);
+      column_index.write_to_out_protocol(&mut prot).unwrap();
+    }
+    let column_index_len = buf.len() - 4 - offset_index_len;
+
+    let column = get_column(
+      Some((4, offset_index_len as i32)),
+      Some((4 + offset_index_len as i64, column_index_len as i32))
+    );
+    let mut reader = Cursor::new(&buf[..]);
+
+    let index = read_offset_index(&mut reader, &column).unwrap().unwrap();
+    assert_eq!(index.num_pages(), 2);
+    assert_eq!(index.page_locations()[1].offset(), 24);
+    assert_eq!(index.page_locations()[1].compressed_page_size(), 30);
+    assert_eq!(index.page_locations()[1].first_row_index(), 10);
+
+    let index = read_column_index(&mut reader, &column).unwrap().unwrap();
+    assert_eq!(index.num_pages(), 2);
+    assert_eq!(index.boundary_order(), BoundaryOrder::ASCENDING);
+    assert_eq!(index.null_pages(), &[false, true]);
+    assert_eq!(
+      index.page_statistics(),
+      &[
+        Statistics::int32(Some(1), Some(9), None, 0, false),
+        // Statistics without min/max values are treated as deprecated
+        Statistics::int32(None, None, None, 5, true)
+      ]
+    );
+  }
+
+  #[test]
+  fn test_read_offset_index_unencoded_byte_array_data_bytes() {
+    let offset_index = TOffsetIndex::new(vec![
+      TPageLocation::new(4, 20, 0),
+      TPageLocation::new(24, 30, 10)
+    ]);
+    let mut buf = Vec::new();
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut buf);
+      offset_index.write_to_out_protocol(&mut prot).unwrap();
+    }
+    // Replace stop field with list field 2 with sizes 10 and 0, followed by stop field
+    assert_eq!(buf.pop(), Some(0));
+    buf.extend_from_slice(&[0x19, 0x26, 0x14, 0x00, 0x00]);
+
+    let column = get_column(Some((0, buf.len() as i32)), None);
+    let mut reader = Cursor::new(&buf[..]);
+    let index = read_offset_index(&mut reader, &column).unwrap().unwrap();
+    assert_eq!(index.num_pages(), 2);
+    assert_eq!(index.unencoded_byte_array_data_bytes(), Some(&[10, 0][..]));
+
+    let res = index.with_unencoded_byte_array_data_bytes(Some(vec![1]));
+    assert_eq!(
+      res.err().unwrap(),
+      general_err!("Offset index has 2 page locations, but 1 unencoded byte array sizes")
+    );
+  }
+
+  #[test]
+  fn test_read_column_index_level_histograms() {
+    let column_index = TColumnIndex::new(
+      vec![false, true],
+      vec![vec![1, 0, 0, 0], vec![]],
+      vec![vec![9, 0, 0, 0], vec![]],
+      parquet_format::BoundaryOrder::ASCENDING,
+      Some(vec![0, 5])
+    );
+    let mut buf = Vec::new();
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut buf);
+      column_index.write_to_out_protocol(&mut prot).unwrap();
+    }
+    // Replace stop field with list field 7 with definition level histograms [1, 2] and
+    // [0, 5], followed by stop field
+    assert_eq!(buf.pop(), Some(0));
+    buf.extend_from_slice(&[0x29, 0x46, 0x02, 0x04, 0x00, 0x0A, 0x00]);
+
+    let column = get_column(None, Some((0, buf.len() as i32)));
+    let mut reader = Cursor::new(&buf[..]);
+    let index = read_column_index(&mut reader, &column).unwrap().unwrap();
+    assert_eq!(index.num_pages(), 2);
+    assert_eq!(index.repetition_level_histogram(0), None);
+    assert_eq!(index.definition_level_histogram(0), Some(&[1, 2][..]));
+    assert_eq!(index.definition_level_histogram(1), Some(&[0, 5][..]));
+
+    let index = index.with_level_histograms(None, Some(vec![1, 2, 3])).err().unwrap();
+    assert_eq!(
+      index,
+      general_err!("Column index has 2 pages, but 3 level histogram values")
+    );
+  }
+
+  #[test]
+  fn test_read_page_indexes_missing() {
+    let column = get_column(None, None);
+    let buf = vec![0; 16];
+    let mut reader = Cursor::new(&buf[..]);
+    assert!(read_offset_index(&mut reader, &column).unwrap().is_none());
+    assert!(read_column_index(&mut reader, &column).unwrap().is_none());
+  }
+
+  #[test]
+  fn test_read_page_indexes_invalid() {
+    let buf = vec![0; 16];
+    let mut reader = Cursor::new(&buf[..]);
+
+    let column = get_column(Some((10, 20)), None);
+    assert_eq!(
+      read_offset_index(&mut reader, &column).err().unwrap(),
+      general_err!("Index is out of bounds: offset 10, size 20")
+    );
+
+    let column = get_column(None, Some((4, 4)));
+    assert!(
+      read_column_index(&mut reader, &column).err().unwrap()
+        .to_string().contains("Could not parse index")
+    );
+  }
+}
@@ -0,0 +1,422 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains API to iterate over page headers of a column chunk without reading and
+//! decompressing page data.
+//!
+//! [`PageMetadataIterator`] reads only page headers and skips page data using page
+//! sizes, so it can report number of pages, values and rows and sizes of pages of
+//! large column chunks cheaply, e.g. for tooling or to build an offset index for
+//! files written without one, see [`build_offset_index`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use parquet::basic::PageType;
+//! use parquet::file::page_metadata::PageMetadataIterator;
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//!
+//! let file = File::open("data/alltypes_plain.parquet").unwrap();
+//! let reader = SerializedFileReader::new(file.try_clone().unwrap()).unwrap();
+//! let metadata = reader.metadata();
+//! let column = metadata.row_group(0).column(0);
+//!
+//! let pages: Vec<_> = PageMetadataIterator::new(file, column)
+//!   .collect::<Result<_, _>>()
+//!   .unwrap();
+//! assert_eq!(pages.len(), 2);
+//! assert_eq!(pages[0].page_type(), PageType::DICTIONARY_PAGE);
+//! assert_eq!(pages[1].page_type(), PageType::DATA_PAGE);
+//! assert_eq!(pages[1].num_rows(), Some(8));
+//! ```
+
+use std::io::{BufReader, Read, Seek, SeekFrom};
+
+use parquet_format::PageHeader;
+use thrift::protocol::TCompactInputProtocol;
+
+use basic::{PageType, Type};
+use errors::{ErrorContext, ParquetError, Result};
+use file::metadata::ColumnChunkMetaData;
+use file::page_index::{OffsetIndex, PageLocation};
+use file::reader::check_page_header;
+use file::statistics::{self, Statistics};
+use util::thrift::TolerantInputProtocol;
+
+/// Metadata of a page of a column chunk, read from its page header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageMetadata {
+  page_type: PageType,
+  offset: i64,
+  header_size: i32,
+  compressed_page_size: i32,
+  uncompressed_page_size: i32,
+  num_values: i32,
+  num_rows: Option<i64>,
+  first_row_index: Option<i64>,
+  statistics: Option<Statistics>
+}
+
+impl PageMetadata {
+  /// Returns type of the page. Page types of newer versions of Parquet are read as
+  /// `INDEX_PAGE`.
+  pub fn page_type(&self) -> PageType {
+    self.page_type
+  }
+
+  /// Returns `true` if this is a data page of either version.
+  pub fn is_data_page(&self) -> bool {
+    self.page_type == PageType::DATA_PAGE || self.page_type == PageType::DATA_PAGE_V2
+  }
+
+  /// Returns offset of the page header in the file.
+  pub fn offset(&self) -> i64 {
+    self.offset
+  }
+
+  /// Returns size of the page header in bytes.
+  pub fn header_size(&self) -> i32 {
+    self.header_size
+  }
+
+  /// Returns size of the page data in bytes, excluding page header.
+  pub fn compressed_page_size(&self) -> i32 {
+    self.compressed_page_size
+  }
+
+  /// Returns size of the page data in bytes after decompression.
+  pub fn uncompressed_page_size(&self) -> i32 {
+    self.uncompressed_page_size
+  }
+
+  /// Returns number of values of a data page, including nulls, or number of values of
+  /// a dictionary page. Returns 0 for other pages.
+  pub fn num_values(&self) -> i32 {
+    self.num_values
+  }
+
+  /// Returns number of rows of a data page, or `None` if it is not known without
+  /// decoding repetition levels, i.e. for data pages v1 of repeated columns, or if
+  /// this is not a data page.
+  pub fn num_rows(&self) -> Option<i64> {
+    self.num_rows
+  }
+
+  /// Returns index of the first row of a data page within the row group, or `None` if
+  /// number of rows of any of the previous data pages is not known, or if this is not
+  /// a data page.
+  pub fn first_row_index(&self) -> Option<i64> {
+    self.first_row_index
+  }
+
+  /// Returns statistics of a data page, if written to its page header.
+  pub fn statistics(&self) -> Option<&Statistics> {
+    self.statistics.as_ref()
+  }
+
+  /// Returns location of a data page for an offset index, or `None` if this is not a
+  /// data page or its first row is not known.
+  pub fn page_location(&self) -> Option<PageLocation> {
+    if !self.is_data_page() {
+      return None;
+    }
+    self.first_row_index.map(|first_row_index| {
+      PageLocation::new(
+        self.offset,
+        self.header_size + self.compressed_page_size,
+        first_row_index
+      )
+    })
+  }
+}
+
+/// Iterator over metadata of pages of a column chunk, which reads page headers and
+/// skips page data. Iteration stops after all values of the column chunk are seen or
+/// after the first error.
+pub struct PageMetadataIterator<R: Read + Seek> {
+  reader: BufReader<R>,
+  column_path: String,
+  // Offset and ordinal of the next page header
+  offset: i64,
+  page_ordinal: usize,
+  total_num_values: i64,
+  seen_num_values: i64,
+  // Number of rows in data pages seen so far, `None` if not known
+  seen_num_rows: Option<i64>,
+  max_rep_level: i16,
+  physical_type: Type,
+  done: bool
+}
+
+impl<R: Read + Seek> PageMetadataIterator<R> {
+  /// Creates iterator over pages of column chunk `column`, which reads page headers
+  /// from `reader`.
+  pub fn new(reader: R, column: &ColumnChunkMetaData) -> Self {
+    let offset = column.dictionary_page_offset().unwrap_or(column.data_page_offset());
+    Self {
+      reader: BufReader::new(reader),
+      column_path: column.column_path().string(),
+      offset: offset,
+      page_ordinal: 0,
+      total_num_values: column.num_values(),
+      seen_num_values: 0,
+      seen_num_rows: Some(0),
+      max_rep_level: column.column_descr().max_rep_level(),
+      physical_type: column.column_type(),
+      done: false
+    }
+  }
+
+  /// Reads header of the page at the current offset and moves to the next page.
+  fn read_next(&mut self) -> Result<PageMetadata> {
+    if self.offset < 0 {
+      return Err(general_err!("Invalid page offset: {}", self.offset));
+    }
+    self.reader.seek(SeekFrom::Start(self.offset as u64))?;
+    let page_header = {
+      let mut prot = TolerantInputProtocol::for_page_header(
+        TCompactInputProtocol::new(&mut self.reader));
+      PageHeader::read_from_in_protocol(&mut prot)?
+    };
+    check_page_header(&page_header)?;
+    let header_size = self.reader.seek(SeekFrom::Current(0))? as i64 - self.offset;
+
+    let page_type = PageType::from(page_header.type_);
+    let (num_values, num_rows, page_statistics) = match page_type {
+      PageType::DICTIONARY_PAGE => {
+        let header = page_header.dictionary_page_header.as_ref()
+          .ok_or(general_err!("Missing dictionary page header"))?;
+        (header.num_values, None, None)
+      },
+      PageType::DATA_PAGE => {
+        let header = page_header.data_page_header.as_ref()
+          .ok_or(general_err!("Missing data page header"))?;
+        // Every value of a non-repeated column is a row
+        let num_rows = if self.max_rep_level == 0 {
+          Some(header.num_values as i64)
+        } else {
+          None
+        };
+        (header.num_values, num_rows, header.statistics.clone())
+      },
+      PageType::DATA_PAGE_V2 => {
+        let header = page_header.data_page_header_v2.as_ref()
+          .ok_or(general_err!("Missing data page v2 header"))?;
+        (header.num_values, Some(header.num_rows as i64), header.statistics.clone())
+      },
+      PageType::INDEX_PAGE | PageType::Unknown(_) => (0, None, None)
+    };
+
+    let mut first_row_index = None;
+    if page_type == PageType::DATA_PAGE || page_type == PageType::DATA_PAGE_V2 {
+      first_row_index = self.seen_num_rows;
+      self.seen_num_values += num_values as i64;
+      self.seen_num_rows = match (self.seen_num_rows, num_rows) {
+        (Some(seen), Some(rows)) => Some(seen + rows),
+        _ => None
+      };
+    }
+
+    let metadata = PageMetadata {
+      page_type: page_type,
+      offset: self.offset,
+      header_size: header_size as i32,
+      compressed_page_size: page_header.compressed_page_size,
+      uncompressed_page_size: page_header.uncompressed_page_size,
+      num_values: num_values,
+      num_rows: num_rows,
+      first_row_index: first_row_index,
+      statistics: statistics::from_thrift(self.physical_type, page_statistics)
+    };
+    self.offset += header_size + page_header.compressed_page_size as i64;
+    self.page_ordinal += 1;
+    Ok(metadata)
+  }
+}
+
+impl<R: Read + Seek> Iterator for PageMetadataIterator<R> {
+  type Item = Result<PageMetadata>;
+
+  fn next(&mut self) -> Option<Result<PageMetadata>> {
+    if self.done || self.seen_num_values >= self.total_num_values {
+      return None;
+    }
+    let context = ErrorContext::new()
+      .with_column_path(self.column_path.clone())
+      .with_page(self.page_ordinal)
+      .with_offset(self.offset as u64);
+    let res = self.read_next().map_err(|e| e.with_context(context));
+    self.done = res.is_err();
+    Some(res)
+  }
+}
+
+/// Builds offset index of column chunk `column` from its page headers, e.g. for files
+/// written without page indexes. Returns `None` if first row of any data page is not
+/// known, i.e. for data pages v1 of repeated columns after the first data page.
+pub fn build_offset_index<R: Read + Seek>(
+  reader: R,
+  column: &ColumnChunkMetaData
+) -> Result<Option<OffsetIndex>> {
+  let mut page_locations = Vec::new();
+  for page in PageMetadataIterator::new(reader, column) {
+    let page = page?;
+    if page.is_data_page() {
+      match page.page_location() {
+        Some(location) => page_locations.push(location),
+        None => return Ok(None)
+      }
+    }
+  }
+  Ok(Some(OffsetIndex::new(page_locations)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::io::Cursor;
+  use std::rc::Rc;
+
+  use column::writer::ColumnWriter;
+  use file::properties::WriterProperties;
+  use file::reader::{FileReader, SerializedFileReader};
+  use file::writer::{write_to_bytes, FileWriter};
+  use schema::parser::parse_message_type;
+  use util::test_common::{get_temp_file, get_test_file};
+
+  fn read_pages(file_name: &str, column: usize) -> Vec<PageMetadata> {
+    let reader = SerializedFileReader::new(get_test_file(file_name)).unwrap();
+    let metadata = reader.metadata();
+    let row_group = metadata.row_group(0);
+    let column = row_group.column(column);
+    read_column_pages(get_test_file(file_name), column)
+  }
+
+  fn read_column_pages<R: Read + Seek>(
+    reader: R,
+    column: &ColumnChunkMetaData
+  ) -> Vec<PageMetadata> {
+    PageMetadataIterator::new(reader, column).collect::<Result<_>>().unwrap()
+  }
+
+  #[test]
+  fn test_page_metadata_iterator() {
+    let pages = read_pages("alltypes_plain.parquet", 0);
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[0].page_type(), PageType::DICTIONARY_PAGE);
+    assert_eq!(pages[0].num_rows(), None);
+    assert_eq!(pages[0].first_row_index(), None);
+    assert_eq!(pages[0].page_location(), None);
+    assert_eq!(pages[1].page_type(), PageType::DATA_PAGE);
+    assert_eq!(pages[1].offset(), pages[0].offset() +
+      (pages[0].header_size() + pages[0].compressed_page_size()) as i64);
+    assert_eq!(pages[1].num_values(), 8);
+    assert_eq!(pages[1].num_rows(), Some(8));
+    assert_eq!(pages[1].first_row_index(), Some(0));
+  }
+
+  #[test]
+  fn test_page_metadata_iterator_repeated() {
+    // Rows of data pages v1 of repeated columns are not known
+    let pages = read_pages("nested_lists.snappy.parquet", 0);
+    let data_pages: Vec<_> = pages.iter().filter(|p| p.is_data_page()).collect();
+    assert!(!data_pages.is_empty());
+    assert!(data_pages.iter().all(|p| p.num_rows().is_none()));
+
+    // Data pages v2 contain number of rows
+    let pages = read_pages("test_datapage_v2.snappy.parquet", 4);
+    let data_pages: Vec<_> = pages.iter().filter(|p| p.is_data_page()).collect();
+    assert_eq!(data_pages[0].page_type(), PageType::DATA_PAGE_V2);
+    assert_eq!(data_pages[0].first_row_index(), Some(0));
+    let num_rows: i64 = data_pages.iter().map(|p| p.num_rows().unwrap()).sum();
+    assert_eq!(num_rows, 5);
+  }
+
+  #[test]
+  fn test_build_offset_index() {
+    let reader = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap();
+    let metadata = reader.metadata();
+    let row_group = metadata.row_group(0);
+    let column = row_group.column(0);
+    let index = build_offset_index(get_test_file("alltypes_plain.parquet"), column)
+      .unwrap()
+      .unwrap();
+    let pages = read_pages("alltypes_plain.parquet", 0);
+    assert_eq!(index.num_pages(), 1);
+    assert_eq!(index.page_locations()[0], pages[1].page_location().unwrap());
+    assert_eq!(index.page_locations()[0].offset(), pages[1].offset());
+
+    // First row of the only data page of a repeated column is known
+    let reader = SerializedFileReader::new(get_test_file("nested_lists.snappy.parquet"))
+      .unwrap();
+    let metadata = reader.metadata();
+    let row_group = metadata.row_group(0);
+    let column = row_group.column(0);
+    let file = get_test_file("nested_lists.snappy.parquet");
+    let index = build_offset_index(file, column).unwrap().unwrap();
+    assert_eq!(index.num_pages(), 1);
+    assert_eq!(index.page_locations()[0].first_row_index(), 0);
+
+    // First rows of the following data pages v1 of a repeated column are not known
+    let schema =
+      Rc::new(parse_message_type("message schema { REPEATED INT32 a; }").unwrap());
+    let props = Rc::new(
+      WriterProperties::builder()
+        .set_dictionary_enabled(false)
+        .set_data_pagesize_limit(64)
+        .set_write_batch_size(16)
+        .build()
+    );
+    let bytes = write_to_bytes(schema, props, |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      while let Some(mut col_writer) = row_group_writer.next_column()? {
+        if let ColumnWriter::Int32ColumnWriter(ref mut typed) = col_writer {
+          let values = (0..100).collect::<Vec<i32>>();
+          let rep_levels = (0..100).map(|v| v % 2).collect::<Vec<i16>>();
+          typed.write_batch(&values, Some(&[1; 100]), Some(&rep_levels))?;
+        }
+        row_group_writer.close_column(col_writer)?;
+      }
+      writer.close_row_group(row_group_writer)
+    }).unwrap();
+    let reader = SerializedFileReader::new(
+      get_temp_file("build_offset_index_repeated.parquet", &bytes)).unwrap();
+    let metadata = reader.metadata();
+    let row_group = metadata.row_group(0);
+    let column = row_group.column(0);
+    assert!(read_column_pages(Cursor::new(&bytes[..]), column).len() > 1);
+    assert_eq!(build_offset_index(Cursor::new(&bytes[..]), column).unwrap(), None);
+  }
+
+  #[test]
+  fn test_page_metadata_iterator_invalid() {
+    let reader = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap();
+    let metadata = reader.metadata();
+    let row_group = metadata.row_group(0);
+    let column = row_group.column(0);
+    let data = vec![255; 64];
+    let mut iter = PageMetadataIterator::new(Cursor::new(&data[..]), column);
+    let err = iter.next().unwrap().unwrap_err();
+    assert_eq!(err.context().unwrap().column_path(), Some("id"));
+    assert_eq!(err.context().unwrap().page(), Some(0));
+    assert!(iter.next().is_none());
+  }
+}
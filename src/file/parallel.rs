@@ -0,0 +1,181 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains API to decode row groups of a file in parallel using `rayon`.
+//!
+//! Available with `rayon` feature enabled.
+//!
+//! Readers and decoded values are not thread-safe, therefore each worker opens its own
+//! file reader with provided `open` function and decodes row groups independently.
+//! Results of each row group must be `Send`; they are returned in row group order.
+//!
+//! Columns of a row group can be encoded in parallel with
+//! [`RowGroupWriter::write_columns_parallel`](../writer/trait.RowGroupWriter.html).
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use parquet::errors::Result;
+//! use parquet::file::parallel::map_row_groups;
+//!
+//! let open = || -> Result<File> { Ok(File::open("data/alltypes_plain.parquet")?) };
+//! let num_rows = map_row_groups(open, |row_group_reader| {
+//!   Ok(row_group_reader.get_row_iter(None)?.count())
+//! }).unwrap();
+//! assert_eq!(num_rows, vec![8]);
+//! ```
+
+use rayon::ThreadPool;
+use rayon::prelude::*;
+
+use errors::Result;
+use file::reader::{FileReader, ParquetReader, RowGroupReader, SerializedFileReader};
+
+/// Applies `f` to each row group of a file in parallel on the global `rayon` thread
+/// pool and returns results in row group order.
+///
+/// `open` is called to create an independent handle to the same file, at most once
+/// per worker task. Handles must not share read position, e.g. file should be reopened
+/// instead of using `File::try_clone`. Returns the first error that has occurred, if
+/// any.
+pub fn map_row_groups<R, O, T, F>(open: O, f: F) -> Result<Vec<T>>
+  where R: 'static + ParquetReader,
+        O: Fn() -> Result<R> + Sync + Send,
+        T: Send,
+        F: Fn(&RowGroupReader) -> Result<T> + Sync + Send {
+  let num_row_groups = SerializedFileReader::new(open()?)?.num_row_groups();
+
+  (0..num_row_groups).into_par_iter()
+    .map_init(
+      || None,
+      |slot: &mut Option<SerializedFileReader<R>>, i| {
+        if slot.is_none() {
+          *slot = Some(SerializedFileReader::new(open()?)?);
+        }
+        let row_group_reader = slot.as_ref().unwrap().get_row_group(i)?;
+        f(row_group_reader.as_ref())
+      })
+    .collect()
+}
+
+/// Same as [`map_row_groups`], but runs on the provided thread pool instead of the
+/// global one.
+pub fn map_row_groups_in_pool<R, O, T, F>(
+  pool: &ThreadPool,
+  open: O,
+  f: F
+) -> Result<Vec<T>>
+  where R: 'static + ParquetReader,
+        O: Fn() -> Result<R> + Sync + Send,
+        T: Send,
+        F: Fn(&RowGroupReader) -> Result<T> + Sync + Send {
+  pool.install(|| map_row_groups(open, f))
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::fs::File;
+  use std::path::PathBuf;
+  use std::rc::Rc;
+
+  use rayon::ThreadPoolBuilder;
+
+  use column::writer::ColumnWriter;
+  use errors::ParquetError;
+  use file::properties::WriterProperties;
+  use file::writer::{FileWriter, write_to_bytes};
+  use record::RowAccessor;
+  use schema::parser::parse_message_type;
+  use util::test_common::{get_temp_file, get_temp_path, get_test_file};
+
+  /// Writes file with one INT32 column, each vector is a separate row group.
+  /// Returns path to the written file.
+  fn write_test_file(file_name: &str, data: &[Vec<i32>]) -> PathBuf {
+    let schema = Rc::new(
+      parse_message_type("message schema { REQUIRED INT32 a; }").unwrap()
+    );
+    let props = Rc::new(WriterProperties::builder().build());
+    let bytes = write_to_bytes(schema, props, |writer| {
+      for values in data {
+        let mut row_group_writer = writer.next_row_group()?;
+        let mut col_writer = row_group_writer.next_column()?.unwrap();
+        if let ColumnWriter::Int32ColumnWriter(ref mut typed) = col_writer {
+          typed.write_batch(&values[..], None, None)?;
+        }
+        row_group_writer.close_column(col_writer)?;
+        writer.close_row_group(row_group_writer)?;
+      }
+      Ok(())
+    }).unwrap();
+    get_temp_file(file_name, &bytes[..]);
+    get_temp_path(file_name)
+  }
+
+  fn sum_row_group(row_group_reader: &RowGroupReader) -> Result<i64> {
+    let mut sum = 0;
+    for row in row_group_reader.get_row_iter(None)? {
+      sum += row?.get_int(0)? as i64;
+    }
+    Ok(sum)
+  }
+
+  #[test]
+  fn test_map_row_groups() {
+    let data = (0..16).map(|i| (0..i * 10).collect()).collect::<Vec<Vec<i32>>>();
+    let path = write_test_file("test_map_row_groups", &data[..]);
+
+    let res = map_row_groups(|| Ok(File::open(&path)?), sum_row_group).unwrap();
+    let expected = data.iter()
+      .map(|values| values.iter().map(|v| *v as i64).sum())
+      .collect::<Vec<i64>>();
+    assert_eq!(res, expected);
+  }
+
+  #[test]
+  fn test_map_row_groups_in_pool() {
+    let pool = ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+    let res = map_row_groups_in_pool(
+      &pool,
+      || Ok(get_test_file("alltypes_plain.parquet")),
+      |row_group_reader| Ok(row_group_reader.metadata().num_rows())
+    ).unwrap();
+    assert_eq!(res, vec![8]);
+  }
+
+  #[test]
+  fn test_map_row_groups_error() {
+    let data = vec![vec![1], vec![2], vec![3]];
+    let path = write_test_file("test_map_row_groups_error", &data[..]);
+
+    let res = map_row_groups(|| Ok(File::open(&path)?), |row_group_reader| {
+      if row_group_reader.metadata().num_rows() > 0 {
+        Err(general_err!("Test error"))
+      } else {
+        Ok(())
+      }
+    });
+    assert_eq!(res, Err(general_err!("Test error")));
+
+    let res: Result<Vec<()>> =
+      map_row_groups(|| -> Result<File> { Err(general_err!("Open error")) }, |_| Ok(()));
+    assert_eq!(res, Err(general_err!("Open error")));
+  }
+}
@@ -0,0 +1,256 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains background prefetcher of column chunks for synchronous readers.
+//!
+//! File readers read a column chunk only when its column reader is created, so a
+//! sequential scan waits for IO of every column chunk before decoding it. On spinning
+//! disks and network file systems this latency dominates the scan. [`Prefetcher`]
+//! reads the next column chunks on a background thread while the current ones are
+//! decoded, and page readers use prefetched bytes instead of reading the file.
+//!
+//! Prefetcher is attached to a file reader with
+//! [`SerializedFileReader::with_prefetcher`](../reader/struct.SerializedFileReader.html)
+//! and reads from its own handle of the same file, which must not share read position
+//! with the handle of the file reader, e.g. the file should be opened again instead of
+//! using `File::try_clone`. Memory of prefetched column chunks is bounded by the
+//! number of bytes in flight; column chunks are prefetched in file order, so scans of
+//! all columns benefit the most.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use parquet::file::prefetch::Prefetcher;
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//!
+//! let path = "data/alltypes_plain.parquet";
+//! let prefetcher = Prefetcher::new(File::open(path).unwrap(), 1024 * 1024);
+//! let reader = SerializedFileReader::new(File::open(path).unwrap())
+//!   .unwrap()
+//!   .with_prefetcher(prefetcher);
+//!
+//! assert_eq!(reader.get_row_iter(None).unwrap().count(), 8);
+//! assert_eq!(reader.prefetcher().unwrap().hits(), 11);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::io::{Result as IoResult, SeekFrom};
+use std::rc::Rc;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+use file::reader::ParquetReader;
+
+/// Reference counted pointer for [`Prefetcher`].
+pub type PrefetcherPtr = Rc<Prefetcher>;
+
+/// Background reader of byte ranges of a file, see module documentation.
+pub struct Prefetcher {
+  // Sender of read requests to the background thread, `None` once dropped
+  requests: Option<Sender<Request>>,
+  handle: Option<JoinHandle<()>>,
+  // Requested ranges that have not been taken yet, in request order
+  pending: RefCell<VecDeque<PendingRange>>,
+  pending_bytes: Cell<usize>,
+  max_bytes: usize,
+  hits: Cell<u64>,
+  misses: Cell<u64>
+}
+
+/// Request to read `length` bytes at `start`, result is sent to `reply`.
+struct Request {
+  start: u64,
+  length: usize,
+  reply: Sender<IoResult<Vec<u8>>>
+}
+
+/// Range that is read or has been read by the background thread.
+struct PendingRange {
+  start: u64,
+  length: usize,
+  result: Receiver<IoResult<Vec<u8>>>
+}
+
+impl Prefetcher {
+  /// Creates prefetcher that reads from `reader` on a background thread and keeps at
+  /// most `max_bytes` bytes requested ahead of the reader. A single range larger than
+  /// `max_bytes` is still prefetched when no other range is pending.
+  pub fn new<R: 'static + ParquetReader + Send>(reader: R, max_bytes: usize) -> Self {
+    let (sender, receiver) = channel::<Request>();
+    let handle = thread::spawn(move || {
+      let mut reader = reader;
+      for request in receiver {
+        let mut buf = vec![0; request.length];
+        let result = reader.seek(SeekFrom::Start(request.start))
+          .and_then(|_| reader.read_exact(&mut buf))
+          .map(|_| buf);
+        // Range is no longer needed if its receiver has been dropped
+        let _ = request.reply.send(result);
+      }
+    });
+    Self {
+      requests: Some(sender),
+      handle: Some(handle),
+      pending: RefCell::new(VecDeque::new()),
+      pending_bytes: Cell::new(0),
+      max_bytes: max_bytes,
+      hits: Cell::new(0),
+      misses: Cell::new(0)
+    }
+  }
+
+  /// Returns maximum number of bytes requested ahead of the reader.
+  pub fn max_bytes(&self) -> usize {
+    self.max_bytes
+  }
+
+  /// Returns number of bytes of ranges that have been requested but not taken yet.
+  pub fn pending_bytes(&self) -> usize {
+    self.pending_bytes.get()
+  }
+
+  /// Returns number of reads of column chunks served from prefetched bytes.
+  pub fn hits(&self) -> u64 {
+    self.hits.get()
+  }
+
+  /// Returns number of reads of column chunks that were not prefetched.
+  pub fn misses(&self) -> u64 {
+    self.misses.get()
+  }
+
+  /// Requests `length` bytes at `start` to be read in background. Returns `false` if
+  /// the range does not fit into the limit of bytes in flight, in which case the
+  /// range is not requested.
+  pub(crate) fn prefetch(&self, start: u64, length: usize) -> bool {
+    let mut pending = self.pending.borrow_mut();
+    if pending.iter().any(|p| p.start == start && p.length == length) {
+      return true;
+    }
+    let pending_bytes = self.pending_bytes.get() + length;
+    if pending_bytes > self.max_bytes && !pending.is_empty() {
+      return false;
+    }
+    let (reply, result) = channel();
+    let request = Request { start: start, length: length, reply: reply };
+    match self.requests {
+      Some(ref requests) if requests.send(request).is_ok() => {
+        pending.push_back(PendingRange { start: start, length: length, result: result });
+        self.pending_bytes.set(pending_bytes);
+        true
+      },
+      _ => false
+    }
+  }
+
+  /// Returns prefetched bytes of range `length` bytes at `start`, waiting for the
+  /// background thread to read them if necessary. Ranges requested before this one
+  /// are discarded, since reads are expected to be sequential.
+  ///
+  /// Returns `None` if range has not been requested or could not be read, in which
+  /// case the caller reads range itself and reports errors as usual.
+  pub(crate) fn take(&self, start: u64, length: usize) -> Option<Vec<u8>> {
+    let mut pending = self.pending.borrow_mut();
+    let pos = pending.iter().position(|p| p.start == start && p.length == length);
+    let pos = match pos {
+      Some(pos) => pos,
+      None => {
+        self.misses.set(self.misses.get() + 1);
+        return None;
+      }
+    };
+    let mut range = None;
+    for _ in 0..=pos {
+      let p = pending.pop_front().unwrap();
+      self.pending_bytes.set(self.pending_bytes.get() - p.length);
+      range = Some(p);
+    }
+    match range.unwrap().result.recv() {
+      Ok(Ok(buf)) => {
+        self.hits.set(self.hits.get() + 1);
+        Some(buf)
+      },
+      _ => {
+        self.misses.set(self.misses.get() + 1);
+        None
+      }
+    }
+  }
+}
+
+impl Drop for Prefetcher {
+  fn drop(&mut self) {
+    // Pending reads are finished by the background thread before it exits
+    self.pending.borrow_mut().clear();
+    self.requests = None;
+    if let Some(handle) = self.handle.take() {
+      let _ = handle.join();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::fs::File;
+  use std::io::{Read, Seek};
+
+  use util::test_common::get_test_file;
+
+  fn read_range(file: &mut File, start: u64, length: usize) -> Vec<u8> {
+    let mut buf = vec![0; length];
+    file.seek(SeekFrom::Start(start)).unwrap();
+    file.read_exact(&mut buf).unwrap();
+    buf
+  }
+
+  #[test]
+  fn test_prefetcher() {
+    let mut file = get_test_file("alltypes_plain.parquet");
+    let prefetcher = Prefetcher::new(get_test_file("alltypes_plain.parquet"), 100);
+
+    assert!(prefetcher.prefetch(4, 60));
+    assert!(prefetcher.prefetch(4, 60));
+    assert!(prefetcher.prefetch(64, 40));
+    // Exceeds the limit of bytes in flight
+    assert!(!prefetcher.prefetch(104, 10));
+    assert_eq!(prefetcher.pending_bytes(), 100);
+
+    assert_eq!(prefetcher.take(4, 60), Some(read_range(&mut file, 4, 60)));
+    assert_eq!(prefetcher.pending_bytes(), 40);
+    assert_eq!(prefetcher.take(4, 60), None);
+    assert!(prefetcher.prefetch(104, 10));
+
+    // Skips range at 64, which is discarded
+    assert_eq!(prefetcher.take(104, 10), Some(read_range(&mut file, 104, 10)));
+    assert_eq!(prefetcher.pending_bytes(), 0);
+    assert_eq!(prefetcher.take(64, 40), None);
+    assert_eq!(prefetcher.hits(), 2);
+    assert_eq!(prefetcher.misses(), 2);
+
+    // Single range larger than the limit and range beyond the end of file
+    assert!(prefetcher.prefetch(0, 200));
+    assert_eq!(prefetcher.take(0, 200), Some(read_range(&mut file, 0, 200)));
+    let len = file.metadata().unwrap().len();
+    assert!(prefetcher.prefetch(len - 4, 10));
+    assert_eq!(prefetcher.take(len - 4, 10), None);
+    assert_eq!(prefetcher.misses(), 3);
+  }
+}
@@ -29,13 +29,13 @@
 //!
 //! // Use properties builder to set certain options and assemble the configuration.
 //! let props = WriterProperties::builder()
-//!   .set_writer_version(WriterVersion::PARQUET_1_0)
+//!   .set_writer_version(WriterVersion::PARQUET_2_0)
 //!   .set_encoding(Encoding::PLAIN)
 //!   .set_column_encoding(ColumnPath::from("col1"), Encoding::DELTA_BINARY_PACKED)
 //!   .set_compression(Compression::SNAPPY)
 //!   .build();
 //!
-//! assert_eq!(props.writer_version(), WriterVersion::PARQUET_1_0);
+//! assert_eq!(props.writer_version(), WriterVersion::PARQUET_2_0);
 //! assert_eq!(props.encoding(&ColumnPath::from("col1")), Encoding::DELTA_BINARY_PACKED);
 //! assert_eq!(props.encoding(&ColumnPath::from("col2")), Encoding::PLAIN);
 //! ```
@@ -44,7 +44,17 @@ use std::collections::HashMap;
 use std::rc::Rc;
 
 use basic::{Compression, Encoding};
+use encryption::encrypt::FileEncryptionProperties;
+#[cfg(all(feature = "serde", any(feature = "serde_json", feature = "toml")))]
+use errors::{ParquetError, Result};
+use file::metadata::{KeyValue, SortingColumn};
 use schema::types::ColumnPath;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(all(feature = "serde", feature = "serde_json"))]
+use serde_json;
+#[cfg(all(feature = "serde", feature = "toml"))]
+use toml;
 
 const DEFAULT_PAGE_SIZE: usize = 1024 * 1024;
 const DEFAULT_WRITE_BATCH_SIZE: usize = 1024;
@@ -52,18 +62,29 @@ const DEFAULT_WRITER_VERSION: WriterVersion = WriterVersion::PARQUET_1_0;
 const DEFAULT_ENCODING: Encoding = Encoding::PLAIN;
 const DEFAULT_COMPRESSION: Compression = Compression::UNCOMPRESSED;
 const DEFAULT_DICTIONARY_ENABLED: bool = true;
+const DEFAULT_ADAPTIVE_ENCODING: bool = false;
 const DEFAULT_DICTIONARY_PAGE_SIZE_LIMIT: usize = DEFAULT_PAGE_SIZE;
-const DEFAULT_STATISTICS_ENABLED: bool = true;
+const DEFAULT_STATISTICS_ENABLED: EnabledStatistics = EnabledStatistics::PAGE;
 const DEFAULT_MAX_STATISTICS_SIZE: usize = 4096;
+const DEFAULT_DISTINCT_COUNT_ENABLED: bool = false;
+const DEFAULT_STATISTICS_TRUNCATE_LENGTH: Option<usize> = None;
+const DEFAULT_SORTING_VERIFICATION: SortingVerification = SortingVerification::NONE;
+const DEFAULT_SCHEMA_VALIDATION_ENABLED: bool = false;
 const DEFAULT_MAX_ROW_GROUP_SIZE: usize = 128 * 1024 * 1024;
 const DEFAULT_CREATED_BY: &str = env!("PARQUET_CREATED_BY");
+const CRATE_VERSION: &str = env!("PARQUET_VERSION");
 
 /// Parquet writer version.
 ///
-/// Basic constant, which is not part of the Thrift definition.
+/// Basic constant, which is not part of the Thrift definition. Writer version controls
+/// page formats and encodings used for values, version 1.0 writes files that can be
+/// read by older readers, e.g. Hive 1.x.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum WriterVersion {
+  /// Data pages v1 and no `DELTA_*` encodings.
   PARQUET_1_0,
+  /// Data pages v2 and all encodings.
   PARQUET_2_0
 }
 
@@ -75,6 +96,40 @@ impl WriterVersion {
       WriterVersion::PARQUET_2_0 => 2
     }
   }
+
+  /// Returns `true` if values can be written with `encoding` in this writer version.
+  pub fn supports_encoding(&self, encoding: Encoding) -> bool {
+    match encoding {
+      Encoding::DELTA_BINARY_PACKED |
+      Encoding::DELTA_LENGTH_BYTE_ARRAY |
+      Encoding::DELTA_BYTE_ARRAY => *self == WriterVersion::PARQUET_2_0,
+      _ => true
+    }
+  }
+}
+
+/// Level of statistics written for a column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EnabledStatistics {
+  /// No statistics are written.
+  NONE,
+  /// Statistics are written for column chunks only.
+  CHUNK,
+  /// Statistics are written for column chunks and data pages.
+  PAGE
+}
+
+/// Verification of rows against sorting columns declared in row group metadata.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SortingVerification {
+  /// Rows are not verified, sorting columns are always declared.
+  NONE,
+  /// Closing a row group fails if its rows are not sorted.
+  ERROR,
+  /// Sorting columns are not declared for row groups whose rows are not sorted.
+  CLEAR
 }
 
 /// Reference counted writer properties.
@@ -92,6 +147,12 @@ pub struct WriterProperties {
   max_row_group_size: usize,
   writer_version: WriterVersion,
   created_by: String,
+  statistics_truncate_length: Option<usize>,
+  key_value_metadata: Option<Vec<KeyValue>>,
+  sorting_columns: Option<Vec<SortingColumn>>,
+  sorting_verification: SortingVerification,
+  schema_validation_enabled: bool,
+  file_encryption_properties: Option<FileEncryptionProperties>,
   default_column_properties: ColumnProperties,
   column_properties: HashMap<ColumnPath, ColumnProperties>
 }
@@ -102,6 +163,49 @@ impl WriterProperties {
     WriterPropertiesBuilder::with_defaults()
   }
 
+  /// Creates writer properties from JSON configuration, see
+  /// [`from_toml`](#method.from_toml) for available settings.
+  ///
+  /// Requires `serde` and `serde_json` features.
+  #[cfg(all(feature = "serde", feature = "serde_json"))]
+  pub fn from_json(config: &str) -> Result<Self> {
+    let config: PropertiesConfig = serde_json::from_str(config)
+      .map_err(|e| general_err!("Invalid writer properties: {}", e))?;
+    config.into_properties()
+  }
+
+  /// Creates writer properties from TOML configuration.
+  ///
+  /// Top-level keys set properties of a file: `writer_version`, `data_pagesize_limit`,
+  /// `dictionary_pagesize_limit`, `write_batch_size`, `max_row_group_size`,
+  /// `created_by`, `statistics_truncate_length`, `sorting_verification` and
+  /// `schema_validation_enabled`. Table `defaults` sets properties of any column and
+  /// tables in `columns` set properties of a column, keyed by dot-separated column
+  /// path: `encoding`, `compression`, `dictionary_enabled`, `adaptive_encoding`,
+  /// `statistics_enabled`, `max_statistics_size` and `distinct_count_enabled`.
+  /// Enum values are names of the variants, e.g. `"SNAPPY"`. Settings that are not
+  /// specified keep default values, unknown settings are rejected.
+  ///
+  /// ```text
+  /// writer_version = "PARQUET_2_0"
+  /// max_row_group_size = 1048576
+  ///
+  /// [defaults]
+  /// compression = "SNAPPY"
+  ///
+  /// [columns."a.b"]
+  /// encoding = "DELTA_BINARY_PACKED"
+  /// dictionary_enabled = false
+  /// ```
+  ///
+  /// Requires `serde` and `toml` features.
+  #[cfg(all(feature = "serde", feature = "toml"))]
+  pub fn from_toml(config: &str) -> Result<Self> {
+    let config: PropertiesConfig = toml::from_str(config)
+      .map_err(|e| general_err!("Invalid writer properties: {}", e))?;
+    config.into_properties()
+  }
+
   /// Returns data page size limit.
   pub fn data_pagesize_limit(&self) -> usize {
     self.data_pagesize_limit
@@ -136,6 +240,53 @@ impl WriterProperties {
     &self.created_by
   }
 
+  /// Returns max length in bytes of min and max statistics of BYTE_ARRAY columns with
+  /// unsigned sort order, e.g. UTF8 columns, or `None` if values are not truncated.
+  ///
+  /// Min value is truncated to a prefix of this length. Max value is truncated to
+  /// a prefix, which is then incremented, so that it is still greater than or equal to
+  /// all values; if this is not possible, e.g. all bytes are `0xFF`, max value is not
+  /// truncated. Truncated values are marked as not exact in statistics. For UTF8
+  /// columns values are truncated at character boundaries.
+  pub fn statistics_truncate_length(&self) -> Option<usize> {
+    self.statistics_truncate_length
+  }
+
+  /// Returns key-value metadata to write in the footer of the file, if any.
+  pub fn key_value_metadata(&self) -> Option<&Vec<KeyValue>> {
+    self.key_value_metadata.as_ref()
+  }
+
+  /// Returns columns that rows are sorted by, declared in metadata of each row group,
+  /// if any. Writer does not sort rows, see
+  /// [`sorting_verification`](#method.sorting_verification) for checking that they
+  /// are sorted.
+  pub fn sorting_columns(&self) -> Option<&Vec<SortingColumn>> {
+    self.sorting_columns.as_ref()
+  }
+
+  /// Returns how rows of each row group are verified against sorting columns.
+  ///
+  /// Only rows written through column writers of non-repeated sorting columns are
+  /// verified, comparing sorting columns in order up to the first column that cannot be
+  /// verified, e.g. a repeated column, a column chunk appended as is or column chunks
+  /// written in parallel.
+  pub fn sorting_verification(&self) -> SortingVerification {
+    self.sorting_verification
+  }
+
+  /// Returns `true` if schema is validated when file writer is created, so that files
+  /// with invalid schemas, which cannot be read, are not written, see
+  /// [`validate_schema`](../../schema/types/fn.validate_schema.html).
+  pub fn schema_validation_enabled(&self) -> bool {
+    self.schema_validation_enabled
+  }
+
+  /// Returns properties to encrypt the file with, if any.
+  pub fn file_encryption_properties(&self) -> Option<&FileEncryptionProperties> {
+    self.file_encryption_properties.as_ref()
+  }
+
   /// Returns encoding for a data page, when dictionary encoding is enabled.
   /// This is not configurable.
   #[inline]
@@ -156,11 +307,19 @@ impl WriterProperties {
 
   /// Returns encoding for a column.
   /// In case when dictionary is enabled, returns fallback encoding.
+  ///
+  /// Encoding that is not supported by writer version, e.g. `DELTA_BINARY_PACKED` in
+  /// version 1.0, is replaced with the default `PLAIN` encoding.
   pub fn encoding(&self, col: &ColumnPath) -> Encoding {
-    self.column_properties.get(col)
+    let encoding = self.column_properties.get(col)
       .and_then(|c| c.encoding())
       .or_else(|| self.default_column_properties.encoding())
-      .unwrap_or(DEFAULT_ENCODING)
+      .unwrap_or(DEFAULT_ENCODING);
+    if self.writer_version.supports_encoding(encoding) {
+      encoding
+    } else {
+      DEFAULT_ENCODING
+    }
   }
 
   /// Returns compression codec for a column.
@@ -179,22 +338,52 @@ impl WriterProperties {
       .unwrap_or(DEFAULT_DICTIONARY_ENABLED)
   }
 
-  /// Returns `true` if statistics are enabled for a column.
-  pub fn statistics_enabled(&self, col: &ColumnPath) -> bool {
+  /// Returns `true` if encoding of a column is chosen by the writer, based on values of
+  /// the first data page of each column chunk.
+  ///
+  /// Dictionary encoding is kept if values repeat, otherwise, and as the fallback
+  /// encoding, `DELTA_BINARY_PACKED` is chosen for integers with small deltas between
+  /// consecutive values in writer version 2.0 and `PLAIN` for other values, including
+  /// floating point values, as `BYTE_STREAM_SPLIT` encoding is not supported. Always
+  /// `false` if encoding or dictionary encoding is set for the column itself, which
+  /// overrides the choice.
+  pub fn adaptive_encoding(&self, col: &ColumnPath) -> bool {
+    let column_properties = self.column_properties.get(col);
+    let is_overridden = column_properties.map_or(false, |c| {
+      c.encoding().is_some() || c.dictionary_enabled().is_some()
+    });
+    !is_overridden && column_properties
+      .and_then(|c| c.adaptive_encoding())
+      .or_else(|| self.default_column_properties.adaptive_encoding())
+      .unwrap_or(DEFAULT_ADAPTIVE_ENCODING)
+  }
+
+  /// Returns level of statistics written for a column.
+  pub fn statistics_enabled(&self, col: &ColumnPath) -> EnabledStatistics {
     self.column_properties.get(col)
       .and_then(|c| c.statistics_enabled())
       .or_else(|| self.default_column_properties.statistics_enabled())
       .unwrap_or(DEFAULT_STATISTICS_ENABLED)
   }
 
-  /// Returns max size for statistics.
-  /// Only applicable if statistics are enabled.
+  /// Returns max size for statistics, min and max values larger than this size are
+  /// not written. Only applicable if statistics are enabled.
   pub fn max_statistics_size(&self, col: &ColumnPath) -> usize {
     self.column_properties.get(col)
       .and_then(|c| c.max_statistics_size())
       .or_else(|| self.default_column_properties.max_statistics_size())
       .unwrap_or(DEFAULT_MAX_STATISTICS_SIZE)
   }
+
+  /// Returns `true` if distinct count of column chunk statistics is written for a
+  /// column. Distinct count is exact for a small number of values, otherwise it is an
+  /// estimate. Only applicable if statistics are enabled.
+  pub fn distinct_count_enabled(&self, col: &ColumnPath) -> bool {
+    self.column_properties.get(col)
+      .and_then(|c| c.distinct_count_enabled())
+      .or_else(|| self.default_column_properties.distinct_count_enabled())
+      .unwrap_or(DEFAULT_DISTINCT_COUNT_ENABLED)
+  }
 }
 
 /// Writer properties builder.
@@ -205,6 +394,12 @@ pub struct WriterPropertiesBuilder {
   max_row_group_size: usize,
   writer_version: WriterVersion,
   created_by: String,
+  statistics_truncate_length: Option<usize>,
+  key_value_metadata: Option<Vec<KeyValue>>,
+  sorting_columns: Option<Vec<SortingColumn>>,
+  sorting_verification: SortingVerification,
+  schema_validation_enabled: bool,
+  file_encryption_properties: Option<FileEncryptionProperties>,
   default_column_properties: ColumnProperties,
   column_properties: HashMap<ColumnPath, ColumnProperties>
 }
@@ -219,6 +414,12 @@ impl WriterPropertiesBuilder {
       max_row_group_size: DEFAULT_MAX_ROW_GROUP_SIZE,
       writer_version: DEFAULT_WRITER_VERSION,
       created_by: DEFAULT_CREATED_BY.to_string(),
+      statistics_truncate_length: DEFAULT_STATISTICS_TRUNCATE_LENGTH,
+      key_value_metadata: None,
+      sorting_columns: None,
+      sorting_verification: DEFAULT_SORTING_VERIFICATION,
+      schema_validation_enabled: DEFAULT_SCHEMA_VALIDATION_ENABLED,
+      file_encryption_properties: None,
       default_column_properties: ColumnProperties::new(),
       column_properties: HashMap::new()
     }
@@ -233,6 +434,12 @@ impl WriterPropertiesBuilder {
       max_row_group_size: self.max_row_group_size,
       writer_version: self.writer_version,
       created_by: self.created_by,
+      statistics_truncate_length: self.statistics_truncate_length,
+      key_value_metadata: self.key_value_metadata,
+      sorting_columns: self.sorting_columns,
+      sorting_verification: self.sorting_verification,
+      schema_validation_enabled: self.schema_validation_enabled,
+      file_encryption_properties: self.file_encryption_properties,
       default_column_properties: self.default_column_properties,
       column_properties: self.column_properties
     }
@@ -277,6 +484,59 @@ impl WriterPropertiesBuilder {
     self
   }
 
+  /// Sets "created by" property to name and version of the application that writes
+  /// the file, with version of this crate appended, i.e.
+  /// `<name> version <version> (parquet-rs version <crate version>)`, so that readers
+  /// can parse it with
+  /// [`ApplicationVersion`](../metadata/struct.ApplicationVersion.html).
+  pub fn set_application(self, name: &str, version: &str) -> Self {
+    self.set_created_by(
+      format!("{} version {} (parquet-rs version {})", name, version, CRATE_VERSION))
+  }
+
+  /// Sets max length of min and max statistics of BYTE_ARRAY columns, see
+  /// [`WriterProperties::statistics_truncate_length`](
+  /// struct.WriterProperties.html#method.statistics_truncate_length).
+  /// `None` disables truncation.
+  pub fn set_statistics_truncate_length(mut self, value: Option<usize>) -> Self {
+    self.statistics_truncate_length = value;
+    self
+  }
+
+  /// Sets key-value metadata to write in the footer of the file.
+  pub fn set_key_value_metadata(mut self, value: Option<Vec<KeyValue>>) -> Self {
+    self.key_value_metadata = value;
+    self
+  }
+
+  /// Sets columns that rows are sorted by, declared in metadata of each row group.
+  pub fn set_sorting_columns(mut self, value: Option<Vec<SortingColumn>>) -> Self {
+    self.sorting_columns = value;
+    self
+  }
+
+  /// Sets how rows of each row group are verified against sorting columns.
+  pub fn set_sorting_verification(mut self, value: SortingVerification) -> Self {
+    self.sorting_verification = value;
+    self
+  }
+
+  /// Sets flag to enable/disable validation of schema when file writer is created.
+  pub fn set_schema_validation_enabled(mut self, value: bool) -> Self {
+    self.schema_validation_enabled = value;
+    self
+  }
+
+  /// Sets properties to encrypt the file with Parquet modular encryption, `None`
+  /// writes a plaintext file.
+  pub fn set_file_encryption_properties(
+    mut self,
+    value: Option<FileEncryptionProperties>
+  ) -> Self {
+    self.file_encryption_properties = value;
+    self
+  }
+
   // ----------------------------------------------------------------------
   // Setters for any column (global)
 
@@ -308,8 +568,16 @@ impl WriterPropertiesBuilder {
     self
   }
 
-  /// Sets flag to enable/disable statistics for any column.
-  pub fn set_statistics_enabled(mut self, value: bool) -> Self {
+  /// Sets flag to enable/disable adaptive encoding for any column, see
+  /// [`WriterProperties::adaptive_encoding`]
+  /// (struct.WriterProperties.html#method.adaptive_encoding).
+  pub fn set_adaptive_encoding(mut self, value: bool) -> Self {
+    self.default_column_properties.set_adaptive_encoding(value);
+    self
+  }
+
+  /// Sets level of statistics for any column.
+  pub fn set_statistics_enabled(mut self, value: EnabledStatistics) -> Self {
     self.default_column_properties.set_statistics_enabled(value);
     self
   }
@@ -321,6 +589,13 @@ impl WriterPropertiesBuilder {
     self
   }
 
+  /// Sets flag to enable/disable distinct count of statistics for any column.
+  /// Applicable only if statistics are enabled.
+  pub fn set_distinct_count_enabled(mut self, value: bool) -> Self {
+    self.default_column_properties.set_distinct_count_enabled(value);
+    self
+  }
+
   // ----------------------------------------------------------------------
   // Setters for a specific column
 
@@ -358,9 +633,20 @@ impl WriterPropertiesBuilder {
     self
   }
 
-  /// Sets flag to enable/disable statistics for a column.
+  /// Sets flag to enable/disable adaptive encoding for a column.
   /// Takes precedence over globally defined settings.
-  pub fn set_column_statistics_enabled(mut self, col: ColumnPath, value: bool) -> Self {
+  pub fn set_column_adaptive_encoding(mut self, col: ColumnPath, value: bool) -> Self {
+    self.get_mut_props(col).set_adaptive_encoding(value);
+    self
+  }
+
+  /// Sets level of statistics for a column, e.g. to disable statistics of columns with
+  /// large binary values. Takes precedence over globally defined settings.
+  pub fn set_column_statistics_enabled(
+    mut self,
+    col: ColumnPath,
+    value: EnabledStatistics
+  ) -> Self {
     self.get_mut_props(col).set_statistics_enabled(value);
     self
   }
@@ -371,6 +657,17 @@ impl WriterPropertiesBuilder {
     self.get_mut_props(col).set_max_statistics_size(value);
     self
   }
+
+  /// Sets flag to enable/disable distinct count of statistics for a column.
+  /// Takes precedence over globally defined settings.
+  pub fn set_column_distinct_count_enabled(
+    mut self,
+    col: ColumnPath,
+    value: bool
+  ) -> Self {
+    self.get_mut_props(col).set_distinct_count_enabled(value);
+    self
+  }
 }
 
 /// Container for column properties that can be changed as part of writer.
@@ -382,8 +679,10 @@ struct ColumnProperties {
   encoding: Option<Encoding>,
   codec: Option<Compression>,
   dictionary_enabled: Option<bool>,
-  statistics_enabled: Option<bool>,
-  max_statistics_size: Option<usize>
+  adaptive_encoding: Option<bool>,
+  statistics_enabled: Option<EnabledStatistics>,
+  max_statistics_size: Option<usize>,
+  distinct_count_enabled: Option<bool>
 }
 
 impl ColumnProperties {
@@ -393,8 +692,10 @@ impl ColumnProperties {
       encoding: None,
       codec: None,
       dictionary_enabled: None,
+      adaptive_encoding: None,
       statistics_enabled: None,
-      max_statistics_size: None
+      max_statistics_size: None,
+      distinct_count_enabled: None
     }
   }
 
@@ -424,8 +725,13 @@ impl ColumnProperties {
     self.dictionary_enabled = Some(enabled);
   }
 
-  /// Sets whether or not statistics are enabled for this column.
-  fn set_statistics_enabled(&mut self, enabled: bool) {
+  /// Sets whether or not adaptive encoding is enabled for this column.
+  fn set_adaptive_encoding(&mut self, enabled: bool) {
+    self.adaptive_encoding = Some(enabled);
+  }
+
+  /// Sets level of statistics for this column.
+  fn set_statistics_enabled(&mut self, enabled: EnabledStatistics) {
     self.statistics_enabled = Some(enabled);
   }
 
@@ -434,6 +740,11 @@ impl ColumnProperties {
     self.max_statistics_size = Some(value);
   }
 
+  /// Sets whether or not distinct count of statistics is enabled for this column.
+  fn set_distinct_count_enabled(&mut self, enabled: bool) {
+    self.distinct_count_enabled = Some(enabled);
+  }
+
   /// Returns optional encoding for this column.
   fn encoding(&self) -> Option<Encoding> {
     self.encoding
@@ -450,9 +761,14 @@ impl ColumnProperties {
     self.dictionary_enabled
   }
 
-  /// Returns `Some(true)` if statistics are enabled for this column, if disabled then
-  /// returns `Some(false)`. If result is `None`, then no setting has been provided.
-  fn statistics_enabled(&self) -> Option<bool> {
+  /// Returns `Some(true)` if adaptive encoding is enabled for this column, if disabled
+  /// then returns `Some(false)`. If result is `None`, then no setting has been provided.
+  fn adaptive_encoding(&self) -> Option<bool> {
+    self.adaptive_encoding
+  }
+
+  /// Returns optional level of statistics for this column.
+  fn statistics_enabled(&self) -> Option<EnabledStatistics> {
     self.statistics_enabled
   }
 
@@ -460,12 +776,135 @@ impl ColumnProperties {
   fn max_statistics_size(&self) -> Option<usize> {
     self.max_statistics_size
   }
+
+  /// Returns `Some(true)` if distinct count is enabled for this column, if disabled then
+  /// returns `Some(false)`. If result is `None`, then no setting has been provided.
+  fn distinct_count_enabled(&self) -> Option<bool> {
+    self.distinct_count_enabled
+  }
+}
+
+// ----------------------------------------------------------------------
+// Configuration of writer properties
+
+/// Writer properties deserialized from a configuration file.
+/// Fields that are not set keep default values of the builder.
+#[cfg(all(feature = "serde", any(feature = "serde_json", feature = "toml")))]
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PropertiesConfig {
+  writer_version: Option<WriterVersion>,
+  data_pagesize_limit: Option<usize>,
+  dictionary_pagesize_limit: Option<usize>,
+  write_batch_size: Option<usize>,
+  max_row_group_size: Option<usize>,
+  created_by: Option<String>,
+  statistics_truncate_length: Option<usize>,
+  sorting_verification: Option<SortingVerification>,
+  schema_validation_enabled: Option<bool>,
+  #[serde(default)]
+  defaults: ColumnConfig,
+  #[serde(default)]
+  columns: HashMap<String, ColumnConfig>
+}
+
+#[cfg(all(feature = "serde", any(feature = "serde_json", feature = "toml")))]
+impl PropertiesConfig {
+  /// Applies configuration to the default builder and returns writer properties.
+  fn into_properties(self) -> Result<WriterProperties> {
+    let mut builder = WriterProperties::builder();
+    if let Some(value) = self.writer_version {
+      builder = builder.set_writer_version(value);
+    }
+    if let Some(value) = self.data_pagesize_limit {
+      builder = builder.set_data_pagesize_limit(value);
+    }
+    if let Some(value) = self.dictionary_pagesize_limit {
+      builder = builder.set_dictionary_pagesize_limit(value);
+    }
+    if let Some(value) = self.write_batch_size {
+      builder = builder.set_write_batch_size(value);
+    }
+    if let Some(value) = self.max_row_group_size {
+      builder = builder.set_max_row_group_size(value);
+    }
+    if let Some(value) = self.created_by {
+      builder = builder.set_created_by(value);
+    }
+    if self.statistics_truncate_length.is_some() {
+      builder = builder.set_statistics_truncate_length(self.statistics_truncate_length);
+    }
+    if let Some(value) = self.sorting_verification {
+      builder = builder.set_sorting_verification(value);
+    }
+    if let Some(value) = self.schema_validation_enabled {
+      builder = builder.set_schema_validation_enabled(value);
+    }
+
+    self.defaults.apply(&mut builder.default_column_properties)?;
+    for (path, config) in self.columns {
+      let parts = path.split('.').map(|part| part.to_owned()).collect();
+      config.apply(builder.get_mut_props(ColumnPath::new(parts)))?;
+    }
+    Ok(builder.build())
+  }
+}
+
+/// Column properties deserialized from a configuration file.
+#[cfg(all(feature = "serde", any(feature = "serde_json", feature = "toml")))]
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ColumnConfig {
+  encoding: Option<Encoding>,
+  compression: Option<Compression>,
+  dictionary_enabled: Option<bool>,
+  adaptive_encoding: Option<bool>,
+  statistics_enabled: Option<EnabledStatistics>,
+  max_statistics_size: Option<usize>,
+  distinct_count_enabled: Option<bool>
+}
+
+#[cfg(all(feature = "serde", any(feature = "serde_json", feature = "toml")))]
+impl ColumnConfig {
+  /// Sets configured values in column properties `props`.
+  fn apply(self, props: &mut ColumnProperties) -> Result<()> {
+    if let Some(value) = self.encoding {
+      if value == Encoding::PLAIN_DICTIONARY || value == Encoding::RLE_DICTIONARY {
+        return Err(general_err!(
+          "Dictionary encoding {} can not be used as fallback encoding, \
+           use dictionary_enabled instead",
+          value
+        ));
+      }
+      props.set_encoding(value);
+    }
+    if let Some(value) = self.compression {
+      props.set_compression(value);
+    }
+    if let Some(value) = self.dictionary_enabled {
+      props.set_dictionary_enabled(value);
+    }
+    if let Some(value) = self.adaptive_encoding {
+      props.set_adaptive_encoding(value);
+    }
+    if let Some(value) = self.statistics_enabled {
+      props.set_statistics_enabled(value);
+    }
+    if let Some(value) = self.max_statistics_size {
+      props.set_max_statistics_size(value);
+    }
+    if let Some(value) = self.distinct_count_enabled {
+      props.set_distinct_count_enabled(value);
+    }
+    Ok(())
+  }
 }
 
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use file::metadata::ApplicationVersion;
 
   #[test]
   fn test_writer_version() {
@@ -482,12 +921,25 @@ mod tests {
     assert_eq!(props.max_row_group_size(), DEFAULT_MAX_ROW_GROUP_SIZE);
     assert_eq!(props.writer_version(), DEFAULT_WRITER_VERSION);
     assert_eq!(props.created_by(), DEFAULT_CREATED_BY);
+    assert_eq!(
+      props.statistics_truncate_length(),
+      DEFAULT_STATISTICS_TRUNCATE_LENGTH
+    );
+    assert_eq!(props.key_value_metadata(), None);
+    assert_eq!(props.sorting_columns(), None);
+    assert_eq!(props.sorting_verification(), DEFAULT_SORTING_VERIFICATION);
+    assert_eq!(props.schema_validation_enabled(), DEFAULT_SCHEMA_VALIDATION_ENABLED);
+    assert!(props.file_encryption_properties().is_none());
     assert_eq!(props.encoding(&ColumnPath::from("col")), DEFAULT_ENCODING);
     assert_eq!(props.compression(&ColumnPath::from("col")), DEFAULT_COMPRESSION);
     assert_eq!(
       props.dictionary_enabled(&ColumnPath::from("col")),
       DEFAULT_DICTIONARY_ENABLED
     );
+    assert_eq!(
+      props.adaptive_encoding(&ColumnPath::from("col")),
+      DEFAULT_ADAPTIVE_ENCODING
+    );
     assert_eq!(
       props.statistics_enabled(&ColumnPath::from("col")),
       DEFAULT_STATISTICS_ENABLED
@@ -496,6 +948,10 @@ mod tests {
       props.max_statistics_size(&ColumnPath::from("col")),
       DEFAULT_MAX_STATISTICS_SIZE
     );
+    assert_eq!(
+      props.distinct_count_enabled(&ColumnPath::from("col")),
+      DEFAULT_DISTINCT_COUNT_ENABLED
+    );
   }
 
   #[test]
@@ -551,18 +1007,28 @@ mod tests {
       .set_write_batch_size(30)
       .set_max_row_group_size(40)
       .set_created_by("default".to_owned())
+      .set_statistics_truncate_length(Some(60))
+      .set_key_value_metadata(Some(vec![KeyValue::new("k".to_owned(), "v".to_owned())]))
+      .set_sorting_columns(Some(vec![SortingColumn::new(0, false, true)]))
+      .set_sorting_verification(SortingVerification::CLEAR)
+      .set_schema_validation_enabled(true)
+      .set_file_encryption_properties(Some(
+        FileEncryptionProperties::builder(vec![0; 16]).build().unwrap()
+      ))
       // global column settings
       .set_encoding(Encoding::DELTA_BINARY_PACKED)
       .set_compression(Compression::GZIP)
       .set_dictionary_enabled(false)
-      .set_statistics_enabled(false)
+      .set_statistics_enabled(EnabledStatistics::NONE)
       .set_max_statistics_size(50)
+      .set_distinct_count_enabled(true)
       // specific column settings
       .set_column_encoding(ColumnPath::from("col"), Encoding::RLE)
       .set_column_compression(ColumnPath::from("col"), Compression::SNAPPY)
       .set_column_dictionary_enabled(ColumnPath::from("col"), true)
-      .set_column_statistics_enabled(ColumnPath::from("col"), true)
+      .set_column_statistics_enabled(ColumnPath::from("col"), EnabledStatistics::CHUNK)
       .set_column_max_statistics_size(ColumnPath::from("col"), 123)
+      .set_column_distinct_count_enabled(ColumnPath::from("col"), false)
       .build();
 
     assert_eq!(props.writer_version(), WriterVersion::PARQUET_2_0);
@@ -571,18 +1037,49 @@ mod tests {
     assert_eq!(props.write_batch_size(), 30);
     assert_eq!(props.max_row_group_size(), 40);
     assert_eq!(props.created_by(), "default");
+    assert_eq!(props.statistics_truncate_length(), Some(60));
+    assert_eq!(
+      props.key_value_metadata(),
+      Some(&vec![KeyValue::new("k".to_owned(), "v".to_owned())])
+    );
+    assert_eq!(props.sorting_columns(), Some(&vec![SortingColumn::new(0, false, true)]));
+    assert_eq!(props.sorting_verification(), SortingVerification::CLEAR);
+    assert!(props.schema_validation_enabled());
+    assert!(props.file_encryption_properties().is_some());
 
     assert_eq!(props.encoding(&ColumnPath::from("a")), Encoding::DELTA_BINARY_PACKED);
     assert_eq!(props.compression(&ColumnPath::from("a")), Compression::GZIP);
     assert_eq!(props.dictionary_enabled(&ColumnPath::from("a")), false);
-    assert_eq!(props.statistics_enabled(&ColumnPath::from("a")), false);
+    assert_eq!(
+      props.statistics_enabled(&ColumnPath::from("a")),
+      EnabledStatistics::NONE
+    );
     assert_eq!(props.max_statistics_size(&ColumnPath::from("a")), 50);
+    assert_eq!(props.distinct_count_enabled(&ColumnPath::from("a")), true);
 
     assert_eq!(props.encoding(&ColumnPath::from("col")), Encoding::RLE);
     assert_eq!(props.compression(&ColumnPath::from("col")), Compression::SNAPPY);
     assert_eq!(props.dictionary_enabled(&ColumnPath::from("col")), true);
-    assert_eq!(props.statistics_enabled(&ColumnPath::from("col")), true);
+    assert_eq!(
+      props.statistics_enabled(&ColumnPath::from("col")),
+      EnabledStatistics::CHUNK
+    );
     assert_eq!(props.max_statistics_size(&ColumnPath::from("col")), 123);
+    assert_eq!(props.distinct_count_enabled(&ColumnPath::from("col")), false);
+  }
+
+  #[test]
+  fn test_writer_properties_application() {
+    let props = WriterProperties::builder().set_application("app", "1.2.3").build();
+    assert_eq!(
+      props.created_by(),
+      format!("app version 1.2.3 (parquet-rs version {})", CRATE_VERSION)
+    );
+
+    let version = ApplicationVersion::parse(props.created_by());
+    assert_eq!(version.application(), "app");
+    assert_eq!(version.version(), Some("1.2.3"));
+    assert_eq!(version.version_number(), (1, 2, 3));
   }
 
   #[test]
@@ -600,4 +1097,124 @@ mod tests {
       DEFAULT_DICTIONARY_ENABLED
     );
   }
+
+  #[test]
+  fn test_writer_properties_writer_version_encodings() {
+    let props = WriterProperties::builder()
+      .set_encoding(Encoding::DELTA_BINARY_PACKED)
+      .set_column_encoding(ColumnPath::from("col"), Encoding::RLE)
+      .build();
+    assert_eq!(props.writer_version(), WriterVersion::PARQUET_1_0);
+    assert_eq!(props.encoding(&ColumnPath::from("a")), Encoding::PLAIN);
+    assert_eq!(props.encoding(&ColumnPath::from("col")), Encoding::RLE);
+
+    for encoding in vec![
+      Encoding::DELTA_BINARY_PACKED,
+      Encoding::DELTA_LENGTH_BYTE_ARRAY,
+      Encoding::DELTA_BYTE_ARRAY
+    ] {
+      assert!(!WriterVersion::PARQUET_1_0.supports_encoding(encoding));
+      assert!(WriterVersion::PARQUET_2_0.supports_encoding(encoding));
+    }
+    assert!(WriterVersion::PARQUET_1_0.supports_encoding(Encoding::PLAIN));
+  }
+
+  #[test]
+  fn test_writer_properties_adaptive_encoding() {
+    let props = WriterProperties::builder()
+      .set_adaptive_encoding(true)
+      .set_column_adaptive_encoding(ColumnPath::from("b"), false)
+      .set_column_encoding(ColumnPath::from("c"), Encoding::DELTA_BINARY_PACKED)
+      .set_column_dictionary_enabled(ColumnPath::from("d"), true)
+      .set_column_compression(ColumnPath::from("e"), Compression::SNAPPY)
+      .build();
+
+    assert!(props.adaptive_encoding(&ColumnPath::from("a")));
+    assert!(!props.adaptive_encoding(&ColumnPath::from("b")));
+    // Encoding settings of the column override adaptive encoding
+    assert!(!props.adaptive_encoding(&ColumnPath::from("c")));
+    assert!(!props.adaptive_encoding(&ColumnPath::from("d")));
+    assert!(props.adaptive_encoding(&ColumnPath::from("e")));
+  }
+
+  #[test]
+  #[cfg(all(feature = "serde", feature = "serde_json"))]
+  fn test_writer_properties_from_json() {
+    let props = WriterProperties::from_json(r#"{
+      "writer_version": "PARQUET_2_0",
+      "max_row_group_size": 1000,
+      "statistics_truncate_length": 16,
+      "defaults": { "compression": "SNAPPY", "statistics_enabled": "CHUNK" },
+      "columns": {
+        "a.b": { "encoding": "DELTA_BINARY_PACKED", "dictionary_enabled": false },
+        "c": { "compression": "ZSTD" }
+      }
+    }"#).unwrap();
+
+    let col = ColumnPath::new(vec!["a".to_string(), "b".to_string()]);
+    assert_eq!(props.writer_version(), WriterVersion::PARQUET_2_0);
+    assert_eq!(props.max_row_group_size(), 1000);
+    assert_eq!(props.statistics_truncate_length(), Some(16));
+    assert_eq!(props.data_pagesize_limit(), DEFAULT_PAGE_SIZE);
+    assert_eq!(props.encoding(&col), Encoding::DELTA_BINARY_PACKED);
+    assert!(!props.dictionary_enabled(&col));
+    assert_eq!(props.compression(&col), Compression::SNAPPY);
+    assert_eq!(props.statistics_enabled(&col), EnabledStatistics::CHUNK);
+    assert_eq!(props.compression(&ColumnPath::from("c")), Compression::ZSTD);
+    assert!(props.dictionary_enabled(&ColumnPath::from("c")));
+    assert_eq!(props.compression(&ColumnPath::from("d")), Compression::SNAPPY);
+
+    let props = WriterProperties::from_json("{}").unwrap();
+    assert_eq!(props.writer_version(), DEFAULT_WRITER_VERSION);
+    assert_eq!(props.compression(&ColumnPath::from("c")), DEFAULT_COMPRESSION);
+  }
+
+  #[test]
+  #[cfg(all(feature = "serde", feature = "serde_json"))]
+  fn test_writer_properties_from_json_errors() {
+    assert!(WriterProperties::from_json(r#"{ "compression": "SNAPPY" }"#).is_err());
+    assert!(
+      WriterProperties::from_json(r#"{ "defaults": { "compression": "SNAP" } }"#)
+        .is_err()
+    );
+    assert_eq!(
+      WriterProperties::from_json(r#"{ "defaults": { "encoding": "RLE_DICTIONARY" } }"#)
+        .unwrap_err(),
+      general_err!(
+        "Dictionary encoding RLE_DICTIONARY can not be used as fallback encoding, \
+         use dictionary_enabled instead"
+      )
+    );
+  }
+
+  #[test]
+  #[cfg(all(feature = "serde", feature = "toml"))]
+  fn test_writer_properties_from_toml() {
+    let props = WriterProperties::from_toml(r#"
+      writer_version = "PARQUET_2_0"
+      created_by = "test"
+      sorting_verification = "ERROR"
+
+      [defaults]
+      compression = "GZIP"
+      adaptive_encoding = true
+
+      [columns."a.b"]
+      encoding = "DELTA_BINARY_PACKED"
+      max_statistics_size = 128
+    "#).unwrap();
+
+    let col = ColumnPath::new(vec!["a".to_string(), "b".to_string()]);
+    assert_eq!(props.writer_version(), WriterVersion::PARQUET_2_0);
+    assert_eq!(props.created_by(), "test");
+    assert_eq!(props.sorting_verification(), SortingVerification::ERROR);
+    assert_eq!(props.compression(&col), Compression::GZIP);
+    assert_eq!(props.encoding(&col), Encoding::DELTA_BINARY_PACKED);
+    assert_eq!(props.max_statistics_size(&col), 128);
+    assert!(props.adaptive_encoding(&ColumnPath::from("c")));
+    assert_eq!(props.max_statistics_size(&ColumnPath::from("c")),
+      DEFAULT_MAX_STATISTICS_SIZE);
+
+    assert!(WriterProperties::from_toml("[defaults]\nunknown = 1").is_err());
+  }
 }
@@ -18,28 +18,70 @@
 //! Contains file reader API and provides methods to access file metadata, row group
 //! readers to read individual column chunks, or access record iterator.
 
+use std::cmp;
 use std::convert::TryFrom;
 use std::fs::File;
 use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
 use std::path::Path;
 use std::rc::Rc;
+use std::time::Instant;
 
 use basic::{ColumnOrder, Compression, Encoding, Type};
 use byteorder::{LittleEndian, ByteOrder};
 use column::page::{Page, PageReader};
 use column::reader::{ColumnReader, ColumnReaderImpl};
 use compression::{create_codec, Codec};
-use errors::{ParquetError, Result};
-use file::{FOOTER_SIZE, PARQUET_MAGIC};
+use crc32fast;
+use data_type::DataType;
+use encodings::decoding::{Decoder, PlainDecoder};
+use encodings::levels::{max_buffer_size, LevelEncoder};
+use encryption::ciphers::{read_module, ColumnCipher, ModuleType};
+use encryption::decrypt::{column_cipher, FileDecryptionProperties, FileDecryptor};
+use encryption::metadata::{
+  binary_from_compact_bytes, AlgorithmMetaData, ColumnCryptoMetaData, FileCryptoMetaData
+};
+use errors::{ErrorContext, ParquetError, Result};
+use file::{FOOTER_SIZE, PARQUET_MAGIC, PARQUET_MAGIC_ENCRYPTED_FOOTER};
+use file::bloom_filter::{read_bloom_filter_with_cipher, Sbbf};
+use file::dictionary_cache::{DictionaryCache, DictionaryCachePtr};
 use file::metadata::*;
+use file::metrics::ReaderMetricsPtr;
+use file::page_index::{
+  read_column_index_with_cipher, read_offset_index_with_cipher, ColumnIndex, OffsetIndex
+};
+use file::page_cache::{get_cached_page_reader, PageCache, PageCachePtr};
+use file::prefetch::{Prefetcher, PrefetcherPtr};
+use file::salvage::salvage_metadata;
 use file::statistics;
 use parquet_format::{ColumnOrder as TColumnOrder, FileMetaData as TFileMetaData};
-use parquet_format::{PageType, PageHeader};
+use parquet_format::{ColumnMetaData, PageType, PageHeader, RowGroup, SchemaElement};
 use record::reader::RowIter;
-use schema::types::{self, SchemaDescriptor, Type as SchemaType};
-use thrift::protocol::TCompactInputProtocol;
+use schema::types::{
+  self, ColumnDescPtr, SchemaDescriptor, Type as SchemaType, TypePtr
+};
+use thrift::protocol::{TCompactInputProtocol, TType};
 use util::io::FileSource;
-use util::memory::ByteBufferPtr;
+use util::thrift::{TolerantInputProtocol, UnknownEnums};
+use util::memory::{BufferPoolPtr, ByteBuffer, ByteBufferPtr, MemTrackerPtr};
+
+// Upper bound of dictionary page header size that older parquet-mr writers did not
+// include in column chunk size, see PARQUET-816
+const MAX_DICT_HEADER_SIZE: i64 = 100;
+
+// Length of signature of plaintext footer: nonce and tag of AES-GCM
+const FOOTER_SIGNATURE_LEN: usize = 28;
+
+// Upper bound of size of encrypted page header, page headers are read from streams of
+// unknown size
+const MAX_ENCRYPTED_PAGE_HEADER_SIZE: usize = 64 * 1024 * 1024;
+
+// Id of `row_groups` field of `FileMetaData`
+const ROW_GROUPS_FIELD_ID: i16 = 4;
+
+const DEFAULT_MAX_METADATA_SIZE: usize = 256 * 1024 * 1024;
+const DEFAULT_MAX_SCHEMA_DEPTH: usize = 128;
+const DEFAULT_MAX_COLUMNS: usize = 1024 * 1024;
+const DEFAULT_MAX_ROW_GROUPS: usize = 1024 * 1024;
 
 // ----------------------------------------------------------------------
 // APIs for file & row group readers
@@ -63,6 +105,11 @@ pub trait FileReader {
   /// Projected schema can be a subset of or equal to the file schema, when it is None,
   /// full file schema is assumed.
   fn get_row_iter(&self, projection: Option<SchemaType>) -> Result<RowIter>;
+
+  /// Get metrics collected by this reader, if enabled.
+  fn metrics(&self) -> Option<ReaderMetricsPtr> {
+    None
+  }
 }
 
 /// Parquet row group reader API. With this, user can get metadata information about the
@@ -80,11 +127,44 @@ pub trait RowGroupReader {
   /// Get value reader for the `i`th column chunk.
   fn get_column_reader(&self, i: usize) -> Result<ColumnReader>;
 
+  /// Get raw bytes of the `i`th column chunk, i.e. all page headers and pages as they
+  /// are stored in the file, without decompression. Can be appended to another file
+  /// with [`RowGroupWriter::append_column_chunk`]
+  /// (../writer/trait.RowGroupWriter.html#method.append_column_chunk).
+  fn get_column_chunk_bytes(&self, _i: usize) -> Result<Vec<u8>> {
+    Err(nyi_err!("Reading raw column chunk bytes is not supported"))
+  }
+
+  /// Get decompressed dictionary page of the `i`th column chunk, or `None` if column
+  /// chunk is not dictionary encoded. Only the dictionary page is read, which allows
+  /// to check whether or not column chunk contains a value before reading data pages.
+  /// Use [`get_typed_dictionary`](fn.get_typed_dictionary.html) to decode values.
+  fn get_dictionary_page(&self, i: usize) -> Result<Option<Page>> {
+    let metadata = self.metadata();
+    let column = metadata.column(i);
+    let is_dictionary_encoded = column.has_dictionary_page() ||
+      column.encodings().iter().any(|encoding| {
+        *encoding == Encoding::PLAIN_DICTIONARY || *encoding == Encoding::RLE_DICTIONARY
+      });
+    if !is_dictionary_encoded {
+      return Ok(None);
+    }
+    match self.get_column_page_reader(i)?.get_next_page()? {
+      Some(page @ Page::DictionaryPage { .. }) => Ok(Some(page)),
+      _ => Ok(None)
+    }
+  }
+
   /// Get iterator of `Row`s from this row group.
   ///
   /// Projected schema can be a subset of or equal to the file schema, when it is None,
   /// full file schema is assumed.
   fn get_row_iter(&self, projection: Option<SchemaType>) -> Result<RowIter>;
+
+  /// Get metrics collected by this reader, if enabled.
+  fn metrics(&self) -> Option<ReaderMetricsPtr> {
+    None
+  }
 }
 
 // ----------------------------------------------------------------------
@@ -136,19 +216,445 @@ impl<'a> TryClone for Cursor<&'a [u8]> {
 pub trait ParquetReader: Read + Seek + Length + TryClone {}
 impl<T: Read + Seek + Length + TryClone> ParquetReader for T {}
 
+/// Limits checked when parsing file metadata, so that corrupt or malicious files, e.g.
+/// with footer claiming gigabytes of metadata or millions of nested groups, result in
+/// error instead of unbounded allocation.
+///
+/// Numbers of elements of lists of metadata are additionally bounded by size of the
+/// metadata, which is always checked. Limits are checked before the parts of metadata
+/// they bound are built, so services can reject pathological files deterministically,
+/// e.g. with limits configured per tenant.
+///
+/// # Example
+///
+/// ```rust
+/// use std::fs::File;
+/// use parquet::file::reader::{MetadataLimits, SerializedFileReader};
+///
+/// let limits = MetadataLimits::new().with_max_schema_depth(4).with_max_columns(10);
+/// let file = File::open("data/alltypes_plain.parquet").unwrap();
+/// let err = SerializedFileReader::new_with_limits(file, limits).err().unwrap();
+/// assert_eq!(
+///   err.to_string(),
+///   "Parquet error: Schema has 11 leaf columns, which exceeds limit 10"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetadataLimits {
+  max_metadata_size: usize,
+  max_schema_depth: usize,
+  max_columns: usize,
+  max_row_groups: usize
+}
+
+impl MetadataLimits {
+  /// Returns default limits: 256 MB of metadata, schema depth of 128, 1048576 leaf
+  /// columns and 1048576 row groups.
+  pub fn new() -> Self {
+    Self {
+      max_metadata_size: DEFAULT_MAX_METADATA_SIZE,
+      max_schema_depth: DEFAULT_MAX_SCHEMA_DEPTH,
+      max_columns: DEFAULT_MAX_COLUMNS,
+      max_row_groups: DEFAULT_MAX_ROW_GROUPS
+    }
+  }
+
+  /// Sets maximum size of serialized file metadata in bytes, checked before metadata
+  /// is read.
+  pub fn with_max_metadata_size(mut self, size: usize) -> Self {
+    self.max_metadata_size = size;
+    self
+  }
+
+  /// Sets maximum depth of schema, i.e. number of nested groups including the root
+  /// message, checked before schema is built.
+  pub fn with_max_schema_depth(mut self, depth: usize) -> Self {
+    self.max_schema_depth = depth;
+    self
+  }
+
+  /// Sets maximum number of leaf columns of schema, checked before schema is built.
+  pub fn with_max_columns(mut self, num_columns: usize) -> Self {
+    self.max_columns = num_columns;
+    self
+  }
+
+  /// Sets maximum number of row groups, checked before row groups are read.
+  pub fn with_max_row_groups(mut self, num_row_groups: usize) -> Self {
+    self.max_row_groups = num_row_groups;
+    self
+  }
+
+  /// Returns maximum size of serialized file metadata in bytes.
+  pub fn max_metadata_size(&self) -> usize {
+    self.max_metadata_size
+  }
+
+  /// Returns maximum depth of schema.
+  pub fn max_schema_depth(&self) -> usize {
+    self.max_schema_depth
+  }
+
+  /// Returns maximum number of leaf columns of schema.
+  pub fn max_columns(&self) -> usize {
+    self.max_columns
+  }
+
+  /// Returns maximum number of row groups.
+  pub fn max_row_groups(&self) -> usize {
+    self.max_row_groups
+  }
+
+  /// Checks depth and number of leaf columns of schema with `elements` in depth-first
+  /// order against the limits, without recursion.
+  fn check_schema(&self, elements: &[SchemaElement]) -> Result<()> {
+    // Numbers of children left to read of the groups being read
+    let mut remaining: Vec<i32> = Vec::new();
+    let mut num_columns = 0;
+    for element in elements {
+      while remaining.last() == Some(&0) {
+        remaining.pop();
+      }
+      if let Some(num_children) = remaining.last_mut() {
+        *num_children -= 1;
+      }
+      let num_children = match element.num_children {
+        Some(num_children) => num_children,
+        None => {
+          num_columns += 1;
+          continue;
+        }
+      };
+      if num_children > 0 {
+        remaining.push(num_children);
+        if remaining.len() > self.max_schema_depth {
+          return Err(general_err!(
+            "Schema depth {} at group '{}' exceeds limit {}",
+            remaining.len(),
+            element.name,
+            self.max_schema_depth
+          ));
+        }
+      }
+    }
+    if num_columns > self.max_columns {
+      return Err(general_err!(
+        "Schema has {} leaf columns, which exceeds limit {}",
+        num_columns,
+        self.max_columns
+      ));
+    }
+    Ok(())
+  }
+}
+
+impl Default for MetadataLimits {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
 /// A serialized implementation for Parquet [`FileReader`].
 pub struct SerializedFileReader<R: ParquetReader> {
   buf: BufReader<R>,
-  metadata: ParquetMetaDataPtr
+  metadata: ParquetMetaDataPtr,
+  metrics: Option<ReaderMetricsPtr>,
+  page_cache: Option<PageCachePtr>,
+  page_mem_tracker: Option<MemTrackerPtr>,
+  page_buffer_pool: Option<BufferPoolPtr<u8>>,
+  dictionary_mem_tracker: Option<MemTrackerPtr>,
+  prefetcher: Option<PrefetcherPtr>,
+  // Decryptor of an encrypted file, if decryption properties are set
+  decryptor: Option<Rc<FileDecryptor>>,
+  lenient: bool
 }
 
 impl<R: ParquetReader> SerializedFileReader<R> {
   /// Creates file reader from a Parquet file.
-  /// Returns error if Parquet file does not exist or is corrupt.
+  /// Returns error if Parquet file does not exist or is corrupt. Files written with
+  /// Parquet modular encryption are read with
+  /// [`new_with_decryption`](#method.new_with_decryption), otherwise files with
+  /// encrypted footer result in error, and only metadata and plaintext columns of
+  /// files with plaintext footer can be read.
   pub fn new(reader: R) -> Result<Self> {
+    Self::new_internal(reader, false, &MetadataLimits::default(), None)
+  }
+
+  /// Creates file reader from a Parquet file, checking its metadata against `limits`
+  /// instead of the default ones, e.g. to read untrusted files with stricter limits.
+  pub fn new_with_limits(reader: R, limits: MetadataLimits) -> Result<Self> {
+    Self::new_internal(reader, false, &limits, None)
+  }
+
+  /// Creates file reader from a Parquet file written with Parquet modular encryption,
+  /// with either encrypted or plaintext footer. Footer, column metadata, pages, page
+  /// indexes and bloom filters are decrypted with keys of `properties`.
+  ///
+  /// Signature of plaintext footer is verified unless disabled in `properties`. Columns
+  /// whose keys are not available can not be read, but their metadata is still
+  /// available in files with plaintext footer.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// use std::fs::File;
+  /// use parquet::encryption::decrypt::FileDecryptionProperties;
+  /// use parquet::file::reader::{FileReader, SerializedFileReader};
+  /// use parquet::schema::types::ColumnPath;
+  ///
+  /// let properties = FileDecryptionProperties::builder()
+  ///   .set_footer_key(b"0123456789012345".to_vec())
+  ///   .set_column_key(ColumnPath::from("double_field"), b"1234567890123450".to_vec())
+  ///   .set_column_key(ColumnPath::from("float_field"), b"1234567890123451".to_vec())
+  ///   .build()
+  ///   .unwrap();
+  /// let file = File::open("uniform_encryption.parquet.encrypted").unwrap();
+  /// let reader = SerializedFileReader::new_with_decryption(file, properties).unwrap();
+  /// for row in reader.get_row_iter(None).unwrap() {
+  ///   println!("{}", row.unwrap());
+  /// }
+  /// ```
+  pub fn new_with_decryption(
+    reader: R,
+    properties: FileDecryptionProperties
+  ) -> Result<Self> {
+    Self::new_internal(reader, false, &MetadataLimits::default(), Some(&properties))
+  }
+
+  /// Creates file reader in lenient mode, which skips corrupt data instead of failing
+  /// the entire read, e.g. to salvage data from partially corrupted files.
+  ///
+  /// - Row groups with invalid metadata or column chunks outside of the file are
+  /// skipped.
+  /// - Pages that fail to decompress or do not match their checksum are replaced with
+  /// pages of nulls for optional non-repeated columns.
+  /// - Otherwise, e.g. when page header is invalid or page is truncated, the rest of the
+  /// column chunk is skipped, so column returns fewer values than expected.
+  ///
+  /// Footer and file schema still must be valid. Skipped data is reported as warnings
+  /// with `tracing` feature enabled and counted in
+  /// [`ReaderMetrics::pages_corrupt`](../metrics/struct.ReaderMetrics.html).
+  pub fn new_lenient(reader: R) -> Result<Self> {
+    Self::new_internal(reader, true, &MetadataLimits::default(), None)
+  }
+
+  /// Creates file reader from a Parquet file whose footer is missing or corrupt, e.g.
+  /// a truncated file, and its `schema`.
+  ///
+  /// Metadata is reconstructed by scanning page headers with
+  /// [`salvage_metadata`](../salvage/fn.salvage_metadata.html), so only complete row
+  /// groups before the first truncated or invalid page are read. Unlike
+  /// [`new_lenient`](#method.new_lenient), this does not need a valid footer.
+  pub fn new_salvaged(mut reader: R, schema: TypePtr) -> Result<Self> {
+    let metadata = salvage_metadata(&mut reader, schema)?;
+    Ok(Self::new_with_metadata(reader, Rc::new(metadata)))
+  }
+
+  /// Creates file reader from a Parquet file and its already parsed `metadata`, e.g.
+  /// metadata cached by a catalog with
+  /// [`ParquetMetaData::to_bytes`](../metadata/struct.ParquetMetaData.html), so that
+  /// repeated reads of the same file do not read and parse the footer again.
+  ///
+  /// Metadata must belong to the file, offsets of column chunks are not checked until
+  /// they are read.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use std::fs::File;
+  /// use std::rc::Rc;
+  /// use parquet::file::metadata::ParquetMetaData;
+  /// use parquet::file::reader::{FileReader, SerializedFileReader};
+  ///
+  /// let path = "data/alltypes_plain.parquet";
+  /// let reader = SerializedFileReader::new(File::open(path).unwrap()).unwrap();
+  /// let cached = reader.metadata().to_bytes().unwrap();
+  ///
+  /// let metadata = Rc::new(ParquetMetaData::from_bytes(&cached).unwrap());
+  /// let file = File::open(path).unwrap();
+  /// let reader = SerializedFileReader::new_with_metadata(file, metadata);
+  /// assert_eq!(reader.get_row_iter(None).unwrap().count(), 8);
+  /// ```
+  pub fn new_with_metadata(reader: R, metadata: ParquetMetaDataPtr) -> Self {
+    Self {
+      buf: BufReader::new(reader),
+      metadata: metadata,
+      metrics: None,
+      page_cache: None,
+      page_mem_tracker: None,
+      page_buffer_pool: None,
+      dictionary_mem_tracker: None,
+      prefetcher: None,
+      decryptor: None,
+      lenient: false
+    }
+  }
+
+  fn new_internal(
+    reader: R,
+    lenient: bool,
+    limits: &MetadataLimits,
+    decryption: Option<&FileDecryptionProperties>
+  ) -> Result<Self> {
     let mut buf = BufReader::new(reader);
-    let metadata = Self::parse_metadata(&mut buf)?;
-    Ok(Self { buf: buf, metadata: Rc::new(metadata) })
+    let (metadata, decryptor) =
+      Self::parse_encrypted_metadata(&mut buf, lenient, limits, decryption)?;
+    Ok(Self {
+      buf: buf,
+      metadata: Rc::new(metadata),
+      metrics: None,
+      page_cache: None,
+      page_mem_tracker: None,
+      page_buffer_pool: None,
+      dictionary_mem_tracker: None,
+      prefetcher: None,
+      decryptor: decryptor,
+      lenient: lenient
+    })
+  }
+
+  /// Enables metrics for this file reader. Metrics are shared with all row group,
+  /// page and column readers created from this reader.
+  pub fn with_metrics(mut self, metrics: ReaderMetricsPtr) -> Self {
+    self.metrics = Some(metrics);
+    self
+  }
+
+  /// Enables caching of pages read by this file reader in `cache`, see
+  /// [`PageCache`](../page_cache/struct.PageCache.html). Cache is shared with all row
+  /// group readers created from this reader.
+  pub fn with_page_cache(mut self, cache: PageCache) -> Self {
+    self.page_cache = Some(Rc::new(cache));
+    self
+  }
+
+  /// Returns page cache of this file reader, if enabled.
+  pub fn page_cache(&self) -> Option<&PageCache> {
+    self.page_cache.as_ref().map(|cache| &**cache)
+  }
+
+  /// Enables streaming reads in a fixed memory envelope: buffers of compressed and
+  /// decompressed pages of all page readers created from this reader are allocated in
+  /// `mem_tracker`, and reading a page that does not fit into the limit of
+  /// `mem_tracker` fails with `ParquetError::ResourceExhausted` error.
+  ///
+  /// Pages are read, decompressed and decoded one at a time, memory of a page is
+  /// released when the page is no longer referenced, so column chunks and row groups
+  /// are never buffered as a whole. Byte array values are slices of their pages, so
+  /// such pages are released once the values are dropped as well. Column reader holds
+  /// at most the current data page and the dictionary page, and briefly the next page
+  /// when moving to it, so limit should allow for a few of the largest pages for each
+  /// column that is read at the same time. Page cache, if enabled, is not used in this
+  /// mode, since it keeps whole column chunks in memory.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use std::fs::File;
+  /// use std::sync::Arc;
+  /// use parquet::file::reader::{FileReader, SerializedFileReader};
+  /// use parquet::memory::MemTracker;
+  ///
+  /// let mem_tracker = Arc::new(MemTracker::with_limit(1024 * 1024));
+  /// let file = File::open("data/alltypes_plain.parquet").unwrap();
+  /// let reader = SerializedFileReader::new(file)
+  ///   .unwrap()
+  ///   .with_page_mem_tracker(mem_tracker.clone());
+  /// assert_eq!(reader.get_row_iter(None).unwrap().count(), 8);
+  /// assert!(mem_tracker.max_memory_usage() > 0);
+  /// assert_eq!(mem_tracker.memory_usage(), 0);
+  /// ```
+  pub fn with_page_mem_tracker(mut self, mem_tracker: MemTrackerPtr) -> Self {
+    self.page_mem_tracker = Some(mem_tracker);
+    self
+  }
+
+  /// Reuses buffers of compressed and decompressed pages from `pool`, so that scans
+  /// do not allocate and free memory for every page. Pool is shared with all page
+  /// readers created from this reader, and can be shared with other readers.
+  ///
+  /// Buffer of a decompressed page is returned to the pool when the page and all values
+  /// that reference it are dropped, buffer of a compressed page is returned right after
+  /// decompression. Statistics of the pool show how many buffers were reused.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use std::fs::File;
+  /// use std::sync::Arc;
+  /// use parquet::file::reader::{FileReader, SerializedFileReader};
+  /// use parquet::memory::BufferPool;
+  ///
+  /// let pool = Arc::new(BufferPool::new(16));
+  /// let file = File::open("data/alltypes_plain.snappy.parquet").unwrap();
+  /// let reader = SerializedFileReader::new(file)
+  ///   .unwrap()
+  ///   .with_page_buffer_pool(pool.clone());
+  /// assert_eq!(reader.get_row_iter(None).unwrap().count(), 2);
+  /// assert_eq!(reader.get_row_iter(None).unwrap().count(), 2);
+  /// assert!(pool.hits() > 0);
+  /// ```
+  pub fn with_page_buffer_pool(mut self, pool: BufferPoolPtr<u8>) -> Self {
+    self.page_buffer_pool = Some(pool);
+    self
+  }
+
+  /// Enables caching of decoded dictionaries in row group readers created from this
+  /// reader, so that dictionaries are decoded once per row group reader, e.g. when
+  /// [`RowFilter`](../../record/filter/struct.RowFilter.html) reads filter columns
+  /// before the projection. Cached dictionaries are accounted in `mem_tracker`, see
+  /// [`DictionaryCache`](../dictionary_cache/struct.DictionaryCache.html).
+  pub fn with_dictionary_cache(mut self, mem_tracker: MemTrackerPtr) -> Self {
+    self.dictionary_mem_tracker = Some(mem_tracker);
+    self
+  }
+
+  /// Enables readahead of column chunks with `prefetcher`, which reads the next column
+  /// chunks in background while the current ones are decoded, see
+  /// [`Prefetcher`](../prefetch/struct.Prefetcher.html). Column chunks of a row group
+  /// are requested when its reader is created, followed by column chunks of the next
+  /// row group as column readers are created.
+  pub fn with_prefetcher(mut self, prefetcher: Prefetcher) -> Self {
+    self.prefetcher = Some(Rc::new(prefetcher));
+    self
+  }
+
+  /// Returns prefetcher of this file reader, if enabled.
+  pub fn prefetcher(&self) -> Option<&Prefetcher> {
+    self.prefetcher.as_ref().map(|prefetcher| &**prefetcher)
+  }
+
+  /// Reads offset index of column `j` of row group `i`, see
+  /// [`read_offset_index`](../page_index/fn.read_offset_index.html). Indexes of
+  /// encrypted columns are decrypted.
+  pub fn read_offset_index(&self, i: usize, j: usize) -> Result<Option<OffsetIndex>> {
+    let row_group = self.metadata.row_group(i);
+    let column = row_group.column(j);
+    let cipher = column_cipher(self.decryptor.as_ref().map(|d| &**d), column, i, j)?;
+    let mut file = self.buf.get_ref().try_clone()?;
+    read_offset_index_with_cipher(&mut file, column, cipher.as_ref())
+  }
+
+  /// Reads column index of column `j` of row group `i`, see
+  /// [`read_column_index`](../page_index/fn.read_column_index.html). Indexes of
+  /// encrypted columns are decrypted.
+  pub fn read_column_index(&self, i: usize, j: usize) -> Result<Option<ColumnIndex>> {
+    let row_group = self.metadata.row_group(i);
+    let column = row_group.column(j);
+    let cipher = column_cipher(self.decryptor.as_ref().map(|d| &**d), column, i, j)?;
+    let mut file = self.buf.get_ref().try_clone()?;
+    read_column_index_with_cipher(&mut file, column, cipher.as_ref())
+  }
+
+  /// Reads bloom filter of column `j` of row group `i`, see
+  /// [`read_bloom_filter`](../bloom_filter/fn.read_bloom_filter.html). Bloom filters
+  /// of encrypted columns are decrypted.
+  pub fn read_bloom_filter(&self, i: usize, j: usize) -> Result<Option<Sbbf>> {
+    let row_group = self.metadata.row_group(i);
+    let column = row_group.column(j);
+    let cipher = column_cipher(self.decryptor.as_ref().map(|d| &**d), column, i, j)?;
+    let mut file = self.buf.get_ref().try_clone()?;
+    read_bloom_filter_with_cipher(&mut file, column, cipher.as_ref())
   }
 
   // Layout of Parquet file
@@ -157,15 +663,41 @@ impl<R: ParquetReader> SerializedFileReader<R> {
   // +---------------------------+---+-----+
   // where A: parquet footer, B: parquet metadata.
   //
-  fn parse_metadata(buf: &mut BufReader<R>) -> Result<ParquetMetaData> {
+  pub(crate) fn parse_metadata(
+    buf: &mut BufReader<R>,
+    lenient: bool,
+    limits: &MetadataLimits
+  ) -> Result<ParquetMetaData> {
+    Self::parse_encrypted_metadata(buf, lenient, limits, None)
+      .map(|(metadata, _)| metadata)
+  }
+
+  /// Parses file metadata, decrypting it with `decryption` properties if the file is
+  /// encrypted. Returns decryptor of the file, if any.
+  //
+  // Layout of the end of a file with encrypted footer
+  // +------+-----------------------+---------------------+---+------+
+  // | .... |  FileCryptoMetaData   |  Encrypted footer   | L | PARE |
+  // +------+-----------------------+---------------------+---+------+
+  // where L is length of crypto metadata and encrypted footer. Plaintext footer of a
+  // file with encrypted columns is followed by its signature of 28 bytes, which is
+  // included in metadata length.
+  fn parse_encrypted_metadata(
+    buf: &mut BufReader<R>,
+    lenient: bool,
+    limits: &MetadataLimits,
+    decryption: Option<&FileDecryptionProperties>
+  ) -> Result<(ParquetMetaData, Option<Rc<FileDecryptor>>)> {
     let file_size = buf.get_ref().len();
+    trace_span!("parse_footer", file_size = file_size);
     if file_size < (FOOTER_SIZE as u64) {
       return Err(general_err!("Invalid Parquet file. Size is smaller than footer"));
     }
     let mut footer_buffer: [u8; FOOTER_SIZE] = [0; FOOTER_SIZE];
     buf.seek(SeekFrom::End(-(FOOTER_SIZE as i64)))?;
     buf.read_exact(&mut footer_buffer)?;
-    if footer_buffer[4..] != PARQUET_MAGIC {
+    let is_footer_encrypted = footer_buffer[4..] == PARQUET_MAGIC_ENCRYPTED_FOOTER;
+    if !is_footer_encrypted && footer_buffer[4..] != PARQUET_MAGIC {
       return Err(general_err!("Invalid Parquet file. Corrupt footer"));
     }
     let metadata_len = LittleEndian::read_i32(&footer_buffer[0..4]) as i64;
@@ -175,6 +707,13 @@ impl<R: ParquetReader> SerializedFileReader<R> {
         metadata_len
       ));
     }
+    if metadata_len as u64 > limits.max_metadata_size() as u64 {
+      return Err(general_err!(
+        "Invalid Parquet file. Metadata length {} exceeds limit {}",
+        metadata_len,
+        limits.max_metadata_size()
+      ));
+    }
     let metadata_start: i64 = file_size as i64 - FOOTER_SIZE as i64 - metadata_len;
     if metadata_start < 0 {
       return Err(general_err!(
@@ -183,18 +722,145 @@ impl<R: ParquetReader> SerializedFileReader<R> {
       ));
     }
     buf.seek(SeekFrom::Start(metadata_start as u64))?;
-    let metadata_buf = buf.take(metadata_len as u64).into_inner();
+    let mut metadata_buf = vec![0; metadata_len as usize];
+    buf.read_exact(&mut metadata_buf)?;
+    let (metadata, decryptor) = if is_footer_encrypted {
+      let properties = decryption.ok_or_else(|| {
+        general_err!(
+          "Parquet file has encrypted footer, decryption properties are required"
+        )
+      })?;
+      let context = ErrorContext::new().with_offset(metadata_start as u64);
+      let (footer, decryptor) = Self::decrypt_footer(&metadata_buf, properties)
+        .map_err(|e| e.with_context(context))?;
+      Self::decode_encrypted_metadata(
+        &footer, Some(metadata_start as u64), lenient, limits, None, Some(decryptor))?
+    } else {
+      Self::decode_encrypted_metadata(
+        &metadata_buf, Some(metadata_start as u64), lenient, limits, decryption, None)?
+    };
+    trace_event!(
+      metadata_len = metadata_len,
+      num_row_groups = metadata.num_row_groups(),
+      "Parsed file metadata"
+    );
+    Ok((metadata, decryptor))
+  }
 
+  /// Reads crypto metadata at the beginning of `buf` and decrypts the encrypted footer
+  /// that follows it, returns serialized file metadata and decryptor of the file.
+  fn decrypt_footer(
+    buf: &[u8],
+    properties: &FileDecryptionProperties
+  ) -> Result<(Vec<u8>, Rc<FileDecryptor>)> {
+    let mut cursor = Cursor::new(buf);
+    let crypto_metadata = {
+      let mut prot = TolerantInputProtocol::for_file_crypto_metadata(
+        TCompactInputProtocol::new(&mut cursor))
+        .with_max_collection_size(buf.len())
+        .with_max_binary_size(buf.len());
+      FileCryptoMetaData::read(&mut prot)
+        .map_err(|e| {
+          general_err!("Could not parse file crypto metadata: {}", e.message())
+        })?
+    };
+    let decryptor = FileDecryptor::new(
+      properties, crypto_metadata.algorithm, crypto_metadata.key_metadata)?;
+    let footer = decryptor.decrypt_footer(&buf[cursor.position() as usize..])?;
+    Ok((footer, Rc::new(decryptor)))
+  }
+
+  /// Decodes file metadata from Thrift bytes `buf`, located at `metadata_start` in the
+  /// file, if known. Start of metadata is added to errors and, in lenient mode, used to
+  /// check bounds of column chunks.
+  pub(crate) fn decode_metadata(
+    buf: &[u8],
+    metadata_start: Option<u64>,
+    lenient: bool,
+    limits: &MetadataLimits
+  ) -> Result<ParquetMetaData> {
+    Self::decode_encrypted_metadata(buf, metadata_start, lenient, limits, None, None)
+      .map(|(metadata, _)| metadata)
+  }
+
+  /// Decodes file metadata as [`decode_metadata`](#method.decode_metadata) and decrypts
+  /// metadata of encrypted columns with `decryptor` of a file with encrypted footer.
+  /// For plaintext footer, decryptor is created from `decryption` properties and
+  /// encryption algorithm stored in the footer, if any, and the footer signature at
+  /// the end of `buf` is verified.
+  fn decode_encrypted_metadata(
+    buf: &[u8],
+    metadata_start: Option<u64>,
+    lenient: bool,
+    limits: &MetadataLimits,
+    decryption: Option<&FileDecryptionProperties>,
+    decryptor: Option<Rc<FileDecryptor>>
+  ) -> Result<(ParquetMetaData, Option<Rc<FileDecryptor>>)> {
     // TODO: row group filtering
-    let mut prot = TCompactInputProtocol::new(metadata_buf);
-    let mut t_file_metadata: TFileMetaData =
+    let row_groups_path: &'static [i16] = &[ROW_GROUPS_FIELD_ID];
+    let mut prot =
+      TolerantInputProtocol::for_file_metadata(TCompactInputProtocol::new(buf))
+        .with_max_collection_size(buf.len())
+        .with_max_binary_size(buf.len())
+        .with_collection_limit(row_groups_path, "row groups", limits.max_row_groups());
+    let t_file_metadata: TFileMetaData =
       TFileMetaData::read_from_in_protocol(&mut prot)
-        .map_err(|e| ParquetError::General(format!("Could not parse metadata: {}", e)))?;
-    let schema = types::from_thrift(&mut t_file_metadata.schema)?;
+        .map_err(|e| {
+          let e = ParquetError::General(format!("Could not parse metadata: {}", e));
+          match metadata_start {
+            Some(start) => e.with_context(ErrorContext::new().with_offset(start)),
+            None => e
+          }
+        })?;
+    limits.check_schema(&t_file_metadata.schema)?;
+    let mut unknown_enums = prot.into_unknown_enums();
+    let decryptor = match (decryptor, decryption) {
+      (Some(decryptor), _) => Some(decryptor),
+      (None, Some(properties)) => {
+        Self::plaintext_footer_decryptor(buf, properties, &unknown_enums)
+          .map_err(|e| match metadata_start {
+            Some(start) => e.with_context(ErrorContext::new().with_offset(start)),
+            None => e
+          })?
+      },
+      (None, None) => None
+    };
+    // Encryption fields of plaintext footer are only valid with the footer signature,
+    // so they are not kept in metadata
+    if unknown_enums.field(&[ENCRYPTION_ALGORITHM_FIELD_ID]).is_some() {
+      unknown_enums.remove_field(&[ENCRYPTION_ALGORITHM_FIELD_ID]);
+      unknown_enums.remove_field(&[FOOTER_SIGNING_KEY_METADATA_FIELD_ID]);
+    }
+    let schema = types::from_thrift_with_unknown_enums(
+      &t_file_metadata.schema, &unknown_enums.scope(&[2]))?;
     let schema_descr = Rc::new(SchemaDescriptor::new(schema.clone()));
+    let writer_version = ApplicationVersion::parse(
+      t_file_metadata.created_by.as_ref().map(|s| s.as_str()).unwrap_or(""));
     let mut row_groups = Vec::new();
-    for rg in t_file_metadata.row_groups {
-      row_groups.push(Rc::new(RowGroupMetaData::from_thrift(schema_descr.clone(), rg)?));
+    for (i, mut rg) in t_file_metadata.row_groups.into_iter().enumerate() {
+      let row_group = decrypt_column_metadata(
+        &mut rg, i, decryptor.as_ref().map(|d| &**d), &mut unknown_enums)
+        .and_then(|_| {
+          discard_incorrect_statistics(&writer_version, &schema_descr, &mut rg);
+          RowGroupMetaData::from_thrift_with_unknown_enums(
+            schema_descr.clone(), rg, &unknown_enums.scope(&[4, i]))
+        })
+        .and_then(|row_group| {
+          if let (true, Some(start)) = (lenient, metadata_start) {
+            Self::check_row_group(&row_group, start)?;
+          }
+          Ok(row_group)
+        });
+      match row_group {
+        Ok(row_group) => row_groups.push(Rc::new(row_group)),
+        Err(e) => {
+          let e = e.with_context(ErrorContext::new().with_row_group(i));
+          if !lenient {
+            return Err(e);
+          }
+          trace_warn!(error = %e, "Skipping corrupt row group");
+        }
+      }
     }
     let column_orders =
       Self::parse_column_orders(t_file_metadata.column_orders, &schema_descr);
@@ -206,8 +872,55 @@ impl<R: ParquetReader> SerializedFileReader<R> {
       schema,
       schema_descr,
       column_orders
-    );
-    Ok(ParquetMetaData::new(file_metadata, row_groups))
+    )
+    .with_key_value_metadata(t_file_metadata.key_value_metadata)
+    .with_unknown_fields(unknown_enums.unknown_fields());
+    Ok((ParquetMetaData::new(file_metadata, row_groups), decryptor))
+  }
+
+  /// Returns decryptor of a file with plaintext footer `buf` and encrypted columns,
+  /// or `None` if columns are not encrypted. Signature of the footer is verified.
+  fn plaintext_footer_decryptor(
+    buf: &[u8],
+    properties: &FileDecryptionProperties,
+    unknown_enums: &UnknownEnums
+  ) -> Result<Option<Rc<FileDecryptor>>> {
+    let algorithm = match unknown_enums.field(&[ENCRYPTION_ALGORITHM_FIELD_ID]) {
+      Some((TType::Struct, data)) => AlgorithmMetaData::from_compact_bytes(data)?,
+      _ => return Ok(None)
+    };
+    let key_metadata_path = [FOOTER_SIGNING_KEY_METADATA_FIELD_ID];
+    let key_metadata = match unknown_enums.field(&key_metadata_path) {
+      Some((TType::String, data)) => Some(binary_from_compact_bytes(data)?),
+      _ => None
+    };
+    let decryptor = FileDecryptor::new(properties, algorithm, key_metadata)?;
+    if buf.len() < FOOTER_SIGNATURE_LEN {
+      return Err(corrupt_err!("Plaintext footer is missing signature"));
+    }
+    let (footer, signature) = buf.split_at(buf.len() - FOOTER_SIGNATURE_LEN);
+    decryptor.verify_footer_signature(footer, signature)?;
+    Ok(Some(Rc::new(decryptor)))
+  }
+
+  /// Checks that all column chunks of a row group are located before file metadata.
+  fn check_row_group(row_group: &RowGroupMetaData, metadata_start: u64) -> Result<()> {
+    for column in row_group.columns() {
+      let start = column.dictionary_page_offset().unwrap_or(column.data_page_offset());
+      let end = start.checked_add(column.compressed_size());
+      match end {
+        Some(end) if start >= 0 && end <= metadata_start as i64 => {},
+        _ => {
+          return Err(general_err!(
+            "Column chunk {} is out of bounds: offset {}, size {}",
+            column.column_path().string(),
+            start,
+            column.compressed_size()
+          ));
+        }
+      }
+    }
+    Ok(())
   }
 
   /// Parses column orders from Thrift definition.
@@ -253,12 +966,39 @@ impl<R: 'static + ParquetReader> FileReader for SerializedFileReader<R> {
     let row_group_metadata = self.metadata.row_group(i);
     // Row groups should be processed sequentially.
     let f = self.buf.get_ref().try_clone()?;
-    Ok(Box::new(SerializedRowGroupReader::new(f, row_group_metadata)))
+    let mut row_group_reader = SerializedRowGroupReader::new(f, row_group_metadata);
+    row_group_reader.index = Some(i);
+    row_group_reader.metrics = self.metrics.clone();
+    row_group_reader.lenient = self.lenient;
+    row_group_reader.page_cache = self.page_cache.clone();
+    row_group_reader.page_mem_tracker = self.page_mem_tracker.clone();
+    row_group_reader.page_buffer_pool = self.page_buffer_pool.clone();
+    row_group_reader.dictionary_cache = self.dictionary_mem_tracker.as_ref()
+      .map(|mem_tracker| Rc::new(DictionaryCache::new(mem_tracker.clone())));
+    row_group_reader.writer_version =
+      Some(self.metadata.file_metadata().writer_version());
+    row_group_reader.decryptor = self.decryptor.clone();
+    if let Some(ref prefetcher) = self.prefetcher {
+      let mut ranges = Vec::new();
+      for j in i..cmp::min(i + 2, self.metadata.num_row_groups()) {
+        for col in self.metadata.row_group(j).columns() {
+          ranges.push(row_group_reader.prefetch_range(col));
+        }
+      }
+      row_group_reader.prefetcher = Some(prefetcher.clone());
+      row_group_reader.prefetch_ranges = ranges;
+      row_group_reader.prefetch_from(0);
+    }
+    Ok(Box::new(row_group_reader))
   }
 
   fn get_row_iter(&self, projection: Option<SchemaType>) -> Result<RowIter> {
     RowIter::from_file(projection, self)
   }
+
+  fn metrics(&self) -> Option<ReaderMetricsPtr> {
+    self.metrics.clone()
+  }
 }
 
 impl TryFrom<File> for SerializedFileReader<File> {
@@ -297,14 +1037,49 @@ impl<'a> TryFrom<&'a str> for SerializedFileReader<File> {
 /// A serialized implementation for Parquet [`RowGroupReader`].
 pub struct SerializedRowGroupReader<R: ParquetReader> {
   buf: BufReader<R>,
-  metadata: RowGroupMetaDataPtr
+  metadata: RowGroupMetaDataPtr,
+  // Index of this row group in a file, if known, used in error context
+  index: Option<usize>,
+  metrics: Option<ReaderMetricsPtr>,
+  // Cache of pages of the file, if enabled
+  page_cache: Option<PageCachePtr>,
+  // Memory tracker of page buffers in streaming mode
+  page_mem_tracker: Option<MemTrackerPtr>,
+  // Pool of page buffers, if enabled
+  page_buffer_pool: Option<BufferPoolPtr<u8>>,
+  // Cache of decoded dictionaries of this row group, if enabled
+  dictionary_cache: Option<DictionaryCachePtr>,
+  // Prefetcher of column chunks, if enabled
+  prefetcher: Option<PrefetcherPtr>,
+  // Ranges of column chunks of this row group followed by the next row group, if any,
+  // to be prefetched in this order, `None` if range is invalid
+  prefetch_ranges: Vec<Option<(u64, usize)>>,
+  lenient: bool,
+  // Application that wrote the file, if known, used to work around issues of writers
+  writer_version: Option<ApplicationVersion>,
+  // Decryptor of an encrypted file, if decryption properties are set
+  decryptor: Option<Rc<FileDecryptor>>
 }
 
 impl<R: 'static + ParquetReader> SerializedRowGroupReader<R> {
   /// Creates new row group reader from a file and row group metadata.
   fn new(file: R, metadata: RowGroupMetaDataPtr) -> Self {
     let buf = BufReader::new(file);
-    Self { buf, metadata }
+    Self {
+      buf,
+      metadata,
+      index: None,
+      metrics: None,
+      page_cache: None,
+      page_mem_tracker: None,
+      page_buffer_pool: None,
+      dictionary_cache: None,
+      prefetcher: None,
+      prefetch_ranges: Vec::new(),
+      lenient: false,
+      writer_version: None,
+      decryptor: None
+    }
   }
 }
 
@@ -317,30 +1092,20 @@ impl<R: 'static + ParquetReader> RowGroupReader for SerializedRowGroupReader<R>
     self.metadata.num_columns()
   }
 
-  // TODO: fix PARQUET-816
   fn get_column_page_reader(&self, i: usize) -> Result<Box<PageReader>> {
-    let col = self.metadata.column(i);
-    let mut col_start = col.data_page_offset();
-    if col.has_dictionary_page() {
-      col_start = col.dictionary_page_offset().unwrap();
+    match (&self.page_cache, self.index) {
+      (Some(cache), Some(index)) if self.page_mem_tracker.is_none() => {
+        get_cached_page_reader(cache, index, i, || self.get_serialized_page_reader(i))
+      },
+      _ => self.get_serialized_page_reader(i)
     }
-    let col_length = col.compressed_size();
-    let file_chunk = FileSource::new(
-      self.buf.get_ref(), col_start as u64, col_length as usize);
-    let page_reader = SerializedPageReader::new(
-      file_chunk,
-      col.num_values(),
-      col.compression(),
-      col.column_descr().physical_type()
-    )?;
-    Ok(Box::new(page_reader))
   }
 
   fn get_column_reader(&self, i: usize) -> Result<ColumnReader> {
     let schema_descr = self.metadata.schema_descr();
     let col_descr = schema_descr.column(i);
     let col_page_reader = self.get_column_page_reader(i)?;
-    let col_reader = match col_descr.physical_type() {
+    let mut col_reader = match col_descr.physical_type() {
       Type::BOOLEAN => ColumnReader::BoolColumnReader(
         ColumnReaderImpl::new(col_descr, col_page_reader)),
       Type::INT32 => ColumnReader::Int32ColumnReader(
@@ -358,12 +1123,192 @@ impl<R: 'static + ParquetReader> RowGroupReader for SerializedRowGroupReader<R>
       Type::FIXED_LEN_BYTE_ARRAY => ColumnReader::FixedLenByteArrayColumnReader(
         ColumnReaderImpl::new(col_descr, col_page_reader)),
     };
+    if let Some(ref metrics) = self.metrics {
+      col_reader = col_reader.with_metrics(metrics.clone());
+    }
+    if let Some(ref cache) = self.dictionary_cache {
+      col_reader = col_reader.with_dictionary_cache(cache.clone(), i);
+    }
     Ok(col_reader)
   }
 
+  fn get_column_chunk_bytes(&self, i: usize) -> Result<Vec<u8>> {
+    let col = self.metadata.column(i);
+    let (col_start, col_length, is_extended) = self.column_chunk_range(col);
+    let mut file_chunk = FileSource::new(
+      self.buf.get_ref(), col_start as u64, col_length as usize);
+    let mut buf = vec![0; col_length as usize];
+    let context = ErrorContext::new()
+      .with_column_path(col.column_path().string())
+      .with_offset(col_start as u64);
+    file_chunk.read_exact(&mut buf).map_err(|e| {
+      ParquetError::from(e).with_context(context.clone())
+    })?;
+    if is_extended {
+      let len = column_chunk_size(&buf, col.num_values())
+        .map_err(|e| e.with_context(context))?;
+      buf.truncate(len);
+    }
+    Ok(buf)
+  }
+
   fn get_row_iter(&self, projection: Option<SchemaType>) -> Result<RowIter> {
     RowIter::from_row_group(projection, self)
   }
+
+  fn metrics(&self) -> Option<ReaderMetricsPtr> {
+    self.metrics.clone()
+  }
+}
+
+impl<R: 'static + ParquetReader> SerializedRowGroupReader<R> {
+  /// Returns page reader of column `i` that reads pages from the file.
+  fn get_serialized_page_reader(&self, i: usize) -> Result<Box<PageReader>> {
+    let col = self.metadata.column(i);
+    let prefetched = match (&self.prefetcher, self.prefetch_range(col)) {
+      (Some(prefetcher), Some((start, length))) => prefetcher.take(start, length),
+      _ => None
+    };
+    if self.prefetcher.is_some() {
+      self.prefetch_from(i + 1);
+    }
+    match prefetched {
+      Some(bytes) => self.new_page_reader(Cursor::new(bytes), i),
+      None => {
+        let (col_start, col_length, _) = self.column_chunk_range(col);
+        let file_chunk = FileSource::new(
+          self.buf.get_ref(), col_start as u64, col_length as usize);
+        self.new_page_reader(file_chunk, i)
+      }
+    }
+  }
+
+  /// Returns page reader of column `i` that reads pages of the column chunk from `buf`.
+  fn new_page_reader<T: 'static + Read>(
+    &self,
+    buf: T,
+    i: usize
+  ) -> Result<Box<PageReader>> {
+    let col = self.metadata.column(i);
+    let (col_start, _, _) = self.column_chunk_range(col);
+    let mut context = ErrorContext::new()
+      .with_column_path(col.column_path().string())
+      .with_offset(col_start as u64);
+    if let Some(index) = self.index {
+      context = context.with_row_group(index);
+    }
+    let cipher = column_cipher(
+      self.decryptor.as_ref().map(|d| &**d), col, self.index.unwrap_or(0), i
+    ).map_err(|e| e.with_context(context.clone()))?;
+    let mut page_reader = SerializedPageReader::new(
+      buf,
+      col.num_values(),
+      col.compression(),
+      col.column_descr().physical_type()
+    ).map_err(|e| e.with_context(context.clone()))?
+      .with_error_context(context);
+    if let Some(ref metrics) = self.metrics {
+      page_reader = page_reader.with_metrics(metrics.clone());
+    }
+    if self.lenient {
+      page_reader = page_reader.with_lenient(col.column_descr_ptr());
+    }
+    if let Some(ref mem_tracker) = self.page_mem_tracker {
+      page_reader = page_reader.with_mem_tracker(mem_tracker.clone());
+    }
+    if let Some(ref pool) = self.page_buffer_pool {
+      page_reader = page_reader.with_buffer_pool(pool.clone());
+    }
+    if let Some(cipher) = cipher {
+      page_reader = page_reader.with_cipher(cipher, col.has_dictionary_page());
+    }
+    Ok(Box::new(page_reader))
+  }
+
+  /// Returns start offset and length of column chunk `col` in the file, and whether or
+  /// not length is extended beyond the size in column chunk metadata.
+  fn column_chunk_range(&self, col: &ColumnChunkMetaData) -> (i64, i64, bool) {
+    let mut col_start = col.data_page_offset();
+    if col.has_dictionary_page() {
+      col_start = col.dictionary_page_offset().unwrap();
+    }
+    let mut col_length = col.compressed_size();
+    // PARQUET-816: size of column chunk of older writers does not include dictionary page
+    // header, so column chunk is extended up to the maximum size of the header
+    let excludes_header = self.writer_version.as_ref()
+      .map(|version| version.excludes_dictionary_page_header())
+      .unwrap_or(false);
+    let is_extended = excludes_header && col.has_dictionary_page();
+    if is_extended {
+      let remaining = self.buf.get_ref().len() as i64 - (col_start + col_length);
+      col_length += cmp::max(0, cmp::min(MAX_DICT_HEADER_SIZE, remaining));
+    }
+    (col_start, col_length, is_extended)
+  }
+
+  /// Returns range of column chunk `col` to prefetch, or `None` if range is invalid.
+  fn prefetch_range(&self, col: &ColumnChunkMetaData) -> Option<(u64, usize)> {
+    let (col_start, col_length, _) = self.column_chunk_range(col);
+    if col_start < 0 || col_length <= 0 {
+      return None;
+    }
+    Some((col_start as u64, col_length as usize))
+  }
+
+  /// Requests prefetch of column chunks starting from range `from`, until the limit of
+  /// the prefetcher is reached.
+  fn prefetch_from(&self, from: usize) {
+    if let Some(ref prefetcher) = self.prefetcher {
+      let ranges = self.prefetch_ranges.iter().skip(from).filter_map(|r| *r);
+      for (start, length) in ranges {
+        if !prefetcher.prefetch(start, length) {
+          break;
+        }
+      }
+    }
+  }
+}
+
+/// Returns empty page buffer, that is allocated in `mem_tracker` and taken from `pool`
+/// if set.
+fn new_page_buffer(
+  mem_tracker: &Option<MemTrackerPtr>,
+  pool: &Option<BufferPoolPtr<u8>>
+) -> ByteBuffer {
+  let mut buffer = ByteBuffer::new();
+  if let Some(ref mem_tracker) = *mem_tracker {
+    buffer = buffer.with_mem_tracker(mem_tracker.clone());
+  }
+  if let Some(ref pool) = *pool {
+    buffer = buffer.with_pool(pool.clone());
+  }
+  buffer
+}
+
+/// Returns size of page headers and pages at the beginning of `data` that contain
+/// `num_values` values.
+fn column_chunk_size(data: &[u8], num_values: i64) -> Result<usize> {
+  let mut cursor = Cursor::new(data);
+  let mut values_read = 0;
+  while values_read < num_values {
+    let page_header = {
+      let mut prot =
+        TolerantInputProtocol::for_page_header(TCompactInputProtocol::new(&mut cursor));
+      PageHeader::read_from_in_protocol(&mut prot)?
+    };
+    check_page_header(&page_header)?;
+    values_read += match page_header.type_ {
+      PageType::DATA_PAGE => page_header.data_page_header.map(|h| h.num_values),
+      PageType::DATA_PAGE_V2 => page_header.data_page_header_v2.map(|h| h.num_values),
+      _ => None
+    }.unwrap_or(0) as i64;
+    let end = cursor.position() + page_header.compressed_page_size as u64;
+    if end > data.len() as u64 {
+      return Err(eof_err!("Column chunk ends in the middle of a page"));
+    }
+    cursor.set_position(end);
+  }
+  Ok(cursor.position() as usize)
 }
 
 /// A serialized implementation for Parquet [`PageReader`].
@@ -382,7 +1327,38 @@ pub struct SerializedPageReader<T: Read> {
   total_num_values: i64,
 
   // Column chunk type.
-  physical_type: Type
+  physical_type: Type,
+
+  // Metrics to update, if enabled.
+  metrics: Option<ReaderMetricsPtr>,
+
+  // Context added to errors, e.g. column path and row group.
+  context: ErrorContext,
+
+  // Ordinal of the current page, including dictionary page.
+  page_ordinal: usize,
+
+  // Offset of the current page relative to the start of the column chunk.
+  page_offset: u64,
+
+  // Column descriptor, set only in lenient mode.
+  lenient: Option<ColumnDescPtr>,
+
+  // Memory tracker of page buffers, if set.
+  mem_tracker: Option<MemTrackerPtr>,
+
+  // Pool of page buffers, if set.
+  pool: Option<BufferPoolPtr<u8>>,
+
+  // Cipher of pages and page headers, set only for encrypted column chunks.
+  cipher: Option<ColumnCipher>,
+
+  // Whether or not the next page header is a dictionary page header, which is only
+  // known from column chunk metadata when the header is encrypted.
+  expect_dictionary_page: bool,
+
+  // Ordinal of the current data page, used in AAD of encrypted modules.
+  data_page_ordinal: usize
 }
 
 impl<T: Read> SerializedPageReader<T> {
@@ -399,23 +1375,247 @@ impl<T: Read> SerializedPageReader<T> {
       total_num_values: total_num_values,
       seen_num_values: 0,
       decompressor: decompressor,
-      physical_type: physical_type
+      physical_type: physical_type,
+      metrics: None,
+      context: ErrorContext::new(),
+      page_ordinal: 0,
+      page_offset: 0,
+      lenient: None,
+      mem_tracker: None,
+      pool: None,
+      cipher: None,
+      expect_dictionary_page: false,
+      data_page_ordinal: 0
     };
     Ok(result)
   }
 
+  /// Enables metrics for this page reader: bytes read, pages read and decompression
+  /// are recorded.
+  pub fn with_metrics(mut self, metrics: ReaderMetricsPtr) -> Self {
+    self.metrics = Some(metrics);
+    self
+  }
+
+  /// Sets context that is added to errors of this page reader, e.g. column path and
+  /// row group index. Page ordinal is always added, byte offset of the page is added
+  /// when the context contains offset of the column chunk.
+  pub fn with_error_context(mut self, context: ErrorContext) -> Self {
+    self.context = context;
+    self
+  }
+
+  /// Enables lenient mode for this page reader, see
+  /// [`SerializedFileReader::new_lenient`](struct.SerializedFileReader.html).
+  /// Descriptor of the column is used to replace corrupt pages with nulls.
+  pub fn with_lenient(mut self, descr: ColumnDescPtr) -> Self {
+    self.lenient = Some(descr);
+    self
+  }
+
+  /// Allocates buffers of pages in `mem_tracker`, reading a page fails with
+  /// `ParquetError::ResourceExhausted` error if its buffers do not fit into the limit.
+  /// Memory is released when the page and all buffers sliced from it are dropped.
+  pub fn with_mem_tracker(mut self, mem_tracker: MemTrackerPtr) -> Self {
+    self.mem_tracker = Some(mem_tracker);
+    self
+  }
+
+  /// Takes buffers of pages from `pool` and returns them to the pool when pages are
+  /// dropped, see
+  /// [`SerializedFileReader::with_page_buffer_pool`](struct.SerializedFileReader.html).
+  pub fn with_buffer_pool(mut self, pool: BufferPoolPtr<u8>) -> Self {
+    self.pool = Some(pool);
+    self
+  }
+
+  /// Decrypts pages and page headers of encrypted column chunk with `cipher`.
+  /// `has_dictionary_page` is `true` if column chunk starts with a dictionary page.
+  pub(crate) fn with_cipher(
+    mut self,
+    cipher: ColumnCipher,
+    has_dictionary_page: bool
+  ) -> Self {
+    self.cipher = Some(cipher);
+    self.expect_dictionary_page = has_dictionary_page;
+    self
+  }
+
+  /// Returns error context of the current page.
+  fn page_context(&self) -> ErrorContext {
+    let mut context = self.context.clone().with_page(self.page_ordinal);
+    if let Some(offset) = self.context.offset() {
+      context = context.with_offset(offset + self.page_offset);
+    }
+    context
+  }
+
   /// Reads Page header from Thrift.
-  fn read_page_header(&mut self) -> Result<PageHeader> {
-    let mut prot = TCompactInputProtocol::new(&mut self.buf);
-    let page_header = PageHeader::read_from_in_protocol(&mut prot)?;
-    Ok(page_header)
+  /// Returns page header, the number of bytes read and enum values of the header that
+  /// are not known to the Thrift definition.
+  fn read_page_header(&mut self) -> Result<(PageHeader, usize, UnknownEnums)> {
+    let start = Instant::now();
+    let mut counting = CountingRead { inner: &mut self.buf, bytes_read: 0 };
+    let (page_header, unknown_enums) = match self.cipher {
+      Some(ref cipher) => {
+        let module = read_module(&mut counting, MAX_ENCRYPTED_PAGE_HEADER_SIZE)?;
+        let module_type = if self.expect_dictionary_page {
+          ModuleType::DICTIONARY_PAGE_HEADER
+        } else {
+          ModuleType::DATA_PAGE_HEADER
+        };
+        let header = cipher.decrypt(module_type, self.data_page_ordinal, &module)?;
+        self.expect_dictionary_page = false;
+        let mut prot = TolerantInputProtocol::for_page_header(
+          TCompactInputProtocol::new(&header[..])
+        );
+        let page_header = PageHeader::read_from_in_protocol(&mut prot)?;
+        (page_header, prot.into_unknown_enums())
+      },
+      None => {
+        let mut prot = TolerantInputProtocol::for_page_header(
+          TCompactInputProtocol::new(&mut counting)
+        );
+        let page_header = PageHeader::read_from_in_protocol(&mut prot)?;
+        (page_header, prot.into_unknown_enums())
+      }
+    };
+    if let Some(ref metrics) = self.metrics {
+      metrics.record_read(counting.bytes_read, start);
+    }
+    Ok((page_header, counting.bytes_read, unknown_enums))
   }
-}
 
-impl<T: Read> PageReader for SerializedPageReader<T> {
-  fn get_next_page(&mut self) -> Result<Option<Page>> {
+  /// Decrypts page buffer of encrypted column chunk, if cipher is set. Data pages are
+  /// decrypted with their ordinal `data_page_ordinal`.
+  fn decrypt_page(
+    &self,
+    page_header: &PageHeader,
+    buffer: ByteBuffer,
+    data_page_ordinal: usize
+  ) -> Result<ByteBuffer> {
+    match self.cipher {
+      Some(ref cipher) => {
+        let module_type = match page_header.type_ {
+          PageType::DICTIONARY_PAGE => ModuleType::DICTIONARY_PAGE,
+          _ => ModuleType::DATA_PAGE
+        };
+        let data = cipher.decrypt(module_type, data_page_ordinal, buffer.data())?;
+        let mut result = new_page_buffer(&self.mem_tracker, &self.pool);
+        result.set_data(data);
+        Ok(result)
+      },
+      None => Ok(buffer)
+    }
+  }
+
+  /// Decompresses page buffer, if decompressor is set. When processing data page v2,
+  /// first `offset` bytes of levels are not compressed.
+  fn decompress_page(
+    &mut self,
+    buffer: ByteBuffer,
+    offset: usize,
+    uncompressed_len: usize,
+    can_decompress: bool
+  ) -> Result<ByteBuffer> {
+    // TODO: page header could be huge because of statistics. We should set a maximum
+    // page header size and abort if that is exceeded.
+    if let Some(decompressor) = self.decompressor.as_mut() {
+      if can_decompress {
+        trace_span!(
+          "decompress_page",
+          compressed_size = buffer.size() - offset,
+          uncompressed_size = uncompressed_len
+        );
+        if let Some(ref mem_tracker) = self.mem_tracker {
+          mem_tracker.check_alloc((offset + uncompressed_len) as i64)?;
+        }
+        let mut decompressed_buffer = match self.pool {
+          Some(ref pool) => pool.get(offset + uncompressed_len),
+          None => Vec::with_capacity(offset + uncompressed_len)
+        };
+        let start = Instant::now();
+        let decompressed_size = if offset == 0 {
+          decompressor.decompress(buffer.data(), &mut decompressed_buffer)?
+        } else {
+          // Some codecs overwrite the output buffer, so values are decompressed into a
+          // separate buffer and appended to the saved offsets
+          let mut values = Vec::with_capacity(uncompressed_len);
+          let size = decompressor.decompress(&buffer.data()[offset..], &mut values)?;
+          decompressed_buffer.extend_from_slice(&buffer.data()[..offset]);
+          decompressed_buffer.extend_from_slice(&values);
+          size
+        };
+        if let Some(ref metrics) = self.metrics {
+          metrics.record_decompression(decompressed_size, start);
+        }
+        if decompressed_size != uncompressed_len {
+          return Err(general_err!(
+            "Actual decompressed size doesn't \
+             match the expected one ({} vs {})",
+            decompressed_size,
+            uncompressed_len
+          ));
+        }
+        let mut result = new_page_buffer(&self.mem_tracker, &self.pool);
+        result.set_data(decompressed_buffer);
+        return Ok(result);
+      }
+    }
+    Ok(buffer)
+  }
+
+  /// Handles error of reading the current page. In strict mode error is returned.
+  ///
+  /// In lenient mode, data page with valid `page_header` is replaced with a page of
+  /// nulls, if column is optional and not repeated. Otherwise the rest of the column
+  /// chunk is skipped.
+  #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+  fn recover(
+    &mut self,
+    err: ParquetError,
+    page_header: Option<&PageHeader>
+  ) -> Result<Option<Page>> {
+    let descr = match self.lenient {
+      Some(ref descr) => descr.clone(),
+      None => return Err(err)
+    };
+    let err = err.with_context(self.page_context());
+    if let Some(ref metrics) = self.metrics {
+      metrics.record_page_corrupt();
+    }
+
+    if let Some(page_header) = page_header {
+      if let Some((page, num_values)) = null_page(&descr, page_header) {
+        trace_warn!(error = %err, "Replacing corrupt page with nulls");
+        self.seen_num_values += num_values as i64;
+        return Ok(Some(page));
+      }
+    }
+    trace_warn!(error = %err, "Skipping the rest of column chunk after corrupt page");
+    self.seen_num_values = self.total_num_values;
+    Ok(None)
+  }
+
+  /// Reads next page, see `PageReader::get_next_page`.
+  fn read_next_page(&mut self) -> Result<Option<Page>> {
     while self.seen_num_values < self.total_num_values {
-      let page_header = self.read_page_header()?;
+      let (page_header, header_len, unknown_enums) = {
+        trace_span!("read_page_header");
+        let res = self.read_page_header()
+          .and_then(|res| check_page_header(&res.0).map(|_| res));
+        match res {
+          Ok(res) => res,
+          Err(e) => return self.recover(e, None)
+        }
+      };
+
+      // Ordinal of data page in AAD of encrypted modules, other pages are not counted
+      let data_page_ordinal = self.data_page_ordinal;
+      match page_header.type_ {
+        PageType::DATA_PAGE | PageType::DATA_PAGE_V2 => self.data_page_ordinal += 1,
+        _ => {}
+      }
 
       // When processing data page v2, depending on enabled compression for the page, we
       // should account for uncompressed data ('offset') of repetition and definition
@@ -436,43 +1636,58 @@ impl<T: Read> PageReader for SerializedPageReader<T> {
       let compressed_len = page_header.compressed_page_size as usize - offset;
       let uncompressed_len = page_header.uncompressed_page_size as usize - offset;
       // We still need to read all bytes from buffered stream
-      let mut buffer = vec![0; offset + compressed_len];
-      self.buf.read_exact(&mut buffer)?;
-
-      // TODO: page header could be huge because of statistics. We should set a maximum
-      // page header size and abort if that is exceeded.
-      if let Some(decompressor) = self.decompressor.as_mut() {
-        if can_decompress {
-          let mut decompressed_buffer = Vec::with_capacity(uncompressed_len);
-          let decompressed_size =
-            decompressor.decompress(&buffer[offset..], &mut decompressed_buffer)?;
-          if decompressed_size != uncompressed_len {
-            return Err(general_err!(
-              "Actual decompressed size doesn't \
-               match the expected one ({} vs {})",
-              decompressed_size,
-              uncompressed_len
-            ));
-          }
-          if offset == 0 {
-            buffer = decompressed_buffer;
-          } else {
-            // Prepend saved offsets to the buffer
-            buffer.truncate(offset);
-            buffer.append(&mut decompressed_buffer);
-          }
+      let mut buffer = new_page_buffer(&self.mem_tracker, &self.pool);
+      buffer.try_resize(offset + compressed_len, 0)?;
+      {
+        trace_span!("read_page", num_bytes = buffer.size());
+        let start = Instant::now();
+        if let Err(e) = self.buf.read_exact(buffer.data_mut()) {
+          return self.recover(e.into(), None);
+        }
+        if let Some(ref metrics) = self.metrics {
+          metrics.record_read(buffer.size(), start);
+          metrics.record_page_read();
         }
       }
 
+      // Checksums are only verified in lenient mode, so corrupt pages can be skipped.
+      // Checksums of encrypted pages are computed over encrypted bytes.
+      let res = if self.lenient.is_some() {
+        check_page_crc(&page_header, buffer.data())
+      } else {
+        Ok(())
+      };
+      let res = res
+        .and_then(|_| self.decrypt_page(&page_header, buffer, data_page_ordinal))
+        .and_then(|buffer| {
+          self.decompress_page(buffer, offset, uncompressed_len, can_decompress)
+        });
+      let mut buffer = match res {
+        Ok(buffer) => buffer,
+        Err(e) => {
+          // Memory limit is not corruption of the page, so it is never recovered from
+          if let ParquetError::ResourceExhausted(_) = *e.without_context() {
+            return Err(e);
+          }
+          let page = self.recover(e, Some(&page_header))?;
+          self.page_ordinal += 1;
+          self.page_offset += (header_len + offset + compressed_len) as u64;
+          return Ok(page);
+        }
+      };
+
+      self.page_ordinal += 1;
+      self.page_offset += (header_len + offset + compressed_len) as u64;
+
       let result = match page_header.type_ {
         PageType::DICTIONARY_PAGE => {
           assert!(page_header.dictionary_page_header.is_some());
           let dict_header = page_header.dictionary_page_header.as_ref().unwrap();
           let is_sorted = dict_header.is_sorted.unwrap_or(false);
           Page::DictionaryPage {
-            buf: ByteBufferPtr::new(buffer),
+            buf: buffer.consume(),
             num_values: dict_header.num_values as u32,
-            encoding: Encoding::from(dict_header.encoding),
+            encoding: unknown_enums.encoding(&[7, 2], dict_header.encoding),
             is_sorted: is_sorted
           }
         },
@@ -481,11 +1696,13 @@ impl<T: Read> PageReader for SerializedPageReader<T> {
           let header = page_header.data_page_header.unwrap();
           self.seen_num_values += header.num_values as i64;
           Page::DataPage {
-            buf: ByteBufferPtr::new(buffer),
+            buf: buffer.consume(),
             num_values: header.num_values as u32,
-            encoding: Encoding::from(header.encoding),
-            def_level_encoding: Encoding::from(header.definition_level_encoding),
-            rep_level_encoding: Encoding::from(header.repetition_level_encoding),
+            encoding: unknown_enums.encoding(&[5, 2], header.encoding),
+            def_level_encoding:
+              unknown_enums.encoding(&[5, 3], header.definition_level_encoding),
+            rep_level_encoding:
+              unknown_enums.encoding(&[5, 4], header.repetition_level_encoding),
             statistics: statistics::from_thrift(self.physical_type, header.statistics)
           }
         },
@@ -495,9 +1712,9 @@ impl<T: Read> PageReader for SerializedPageReader<T> {
           let is_compressed = header.is_compressed.unwrap_or(true);
           self.seen_num_values += header.num_values as i64;
           Page::DataPageV2 {
-            buf: ByteBufferPtr::new(buffer),
+            buf: buffer.consume(),
             num_values: header.num_values as u32,
-            encoding: Encoding::from(header.encoding),
+            encoding: unknown_enums.encoding(&[8, 4], header.encoding),
             num_nulls: header.num_nulls as u32,
             num_rows: header.num_rows as u32,
             def_levels_byte_len: header.definition_levels_byte_length as u32,
@@ -507,24 +1724,261 @@ impl<T: Read> PageReader for SerializedPageReader<T> {
           }
         },
         _ => {
-          // For unknown page type (e.g., INDEX_PAGE), skip and read next.
+          // For unknown page type (e.g., INDEX_PAGE or page types of newer versions of
+          // Parquet, which are read as INDEX_PAGE), skip and read next.
           continue;
         }
       };
       return Ok(Some(result));
     }
 
-    // We are at the end of this column chunk and no more page left. Return None.
-    Ok(None)
+    // We are at the end of this column chunk and no more page left. Return None.
+    Ok(None)
+  }
+}
+
+impl<T: Read> PageReader for SerializedPageReader<T> {
+  fn get_next_page(&mut self) -> Result<Option<Page>> {
+    self.read_next_page().map_err(|e| e.with_context(self.page_context()))
+  }
+}
+
+/// Decrypts metadata of encrypted columns of row group `row_group` with ordinal `i`,
+/// and replaces unknown enum values of their metadata in `unknown_enums` of the file.
+///
+/// Without `decryptor` or key of a column, plaintext metadata of the column is kept, if
+/// any. It is only stored in files with plaintext footer and does not have statistics.
+fn decrypt_column_metadata(
+  row_group: &mut RowGroup,
+  i: usize,
+  decryptor: Option<&FileDecryptor>,
+  unknown_enums: &mut UnknownEnums
+) -> Result<()> {
+  for (j, column) in row_group.columns.iter_mut().enumerate() {
+    // Path of field `field_id` of the column chunk
+    let path = |field_id| [ROW_GROUPS_FIELD_ID as usize, i, 1, j, field_id];
+    let crypto_metadata = match unknown_enums.field(&path(CRYPTO_METADATA_FIELD_ID)) {
+      Some((TType::Struct, data)) => ColumnCryptoMetaData::from_compact_bytes(data)?,
+      _ => continue
+    };
+    let module = match unknown_enums.field(&path(ENCRYPTED_COLUMN_METADATA_FIELD_ID)) {
+      Some((TType::String, data)) => binary_from_compact_bytes(data)?,
+      _ => continue
+    };
+    let cipher = match decryptor.map(|d| d.column_cipher(&crypto_metadata, i, j)) {
+      Some(Ok(cipher)) => cipher,
+      Some(Err(_)) | None if column.meta_data.is_some() => continue,
+      Some(Err(e)) => return Err(e),
+      None => {
+        return Err(general_err!(
+          "Column {} has encrypted metadata, decryption properties are required", j));
+      }
+    };
+    let data = cipher.decrypt(ModuleType::COLUMN_META_DATA, 0, &module)?;
+    let mut prot =
+      TolerantInputProtocol::for_column_metadata(TCompactInputProtocol::new(&data[..]))
+        .with_max_collection_size(data.len())
+        .with_max_binary_size(data.len());
+    let meta_data = ColumnMetaData::read_from_in_protocol(&mut prot)
+      .map_err(|e| general_err!("Could not parse decrypted column metadata: {}", e))?;
+    unknown_enums.remove_scope(&path(3));
+    unknown_enums.extend(&prot.into_unknown_enums().nest(&path(3)));
+    column.meta_data = Some(meta_data);
+  }
+  Ok(())
+}
+
+/// Removes min and max values of column chunk statistics that are known to be incorrect
+/// for the application that wrote the file, see
+/// [`ApplicationVersion::has_correct_statistics`](
+/// ../metadata/struct.ApplicationVersion.html#method.has_correct_statistics).
+fn discard_incorrect_statistics(
+  writer_version: &ApplicationVersion,
+  schema_descr: &SchemaDescriptor,
+  row_group: &mut RowGroup
+) {
+  for (column, descr) in row_group.columns.iter_mut().zip(schema_descr.columns()) {
+    let stats = match column.meta_data.as_mut().and_then(|m| m.statistics.as_mut()) {
+      Some(stats) => stats,
+      None => continue
+    };
+    let min_equals_max = if stats.min_value.is_some() || stats.max_value.is_some() {
+      stats.min_value.is_some() && stats.min_value == stats.max_value
+    } else {
+      stats.min.is_some() && stats.min == stats.max
+    };
+    let sort_order =
+      ColumnOrder::get_sort_order(descr.logical_type(), descr.physical_type());
+    if !writer_version.has_correct_statistics(
+        descr.physical_type(), sort_order, min_equals_max) {
+      trace_event!(column = %descr.path(), "discarding incorrect min/max statistics");
+      stats.min = None;
+      stats.max = None;
+      stats.min_value = None;
+      stats.max_value = None;
+    }
+  }
+}
+
+/// Reads dictionary of the `i`th column chunk of `row_group_reader` and returns decoded
+/// dictionary values, or `None` if column chunk is not dictionary encoded. Returns
+/// error if physical type of the column does not match type `T`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::fs::File;
+/// use parquet::data_type::{ByteArray, ByteArrayType};
+/// use parquet::file::reader::{get_typed_dictionary, FileReader, SerializedFileReader};
+///
+/// let file = File::open("data/alltypes_dictionary.parquet").unwrap();
+/// let reader = SerializedFileReader::new(file).unwrap();
+/// let row_group_reader = reader.get_row_group(0).unwrap();
+/// // Column `string_col`
+/// let dictionary =
+///   get_typed_dictionary::<ByteArrayType>(&*row_group_reader, 9).unwrap().unwrap();
+/// assert!(dictionary.contains(&ByteArray::from("0")));
+/// assert!(!dictionary.contains(&ByteArray::from("2")));
+/// ```
+pub fn get_typed_dictionary<T: DataType>(
+  row_group_reader: &RowGroupReader,
+  i: usize
+) -> Result<Option<Vec<T::T>>> {
+  let metadata = row_group_reader.metadata();
+  let descr = metadata.schema_descr().column(i);
+  if descr.physical_type() != T::get_physical_type() {
+    return Err(general_err!(
+      "Cannot read dictionary of column {} of type {} as {}",
+      descr.path().string(),
+      descr.physical_type(),
+      T::get_physical_type()
+    ));
+  }
+  let page = match row_group_reader.get_dictionary_page(i)? {
+    Some(page) => page,
+    None => return Ok(None)
+  };
+  let num_values = page.num_values() as usize;
+  let mut decoder = PlainDecoder::<T>::new(descr.type_length());
+  decoder.set_data(page.buffer().clone(), num_values)?;
+  let mut values = vec![T::T::default(); num_values];
+  let values_read = decoder.get(&mut values)?;
+  if values_read != num_values {
+    return Err(general_err!(
+      "Dictionary page has {} values, expected {}",
+      values_read,
+      num_values
+    ));
+  }
+  Ok(Some(values))
+}
+
+/// Checks that page sizes in page header are valid.
+pub fn check_page_header(page_header: &PageHeader) -> Result<()> {
+  let mut levels_len = 0;
+  if let Some(ref header_v2) = page_header.data_page_header_v2 {
+    if header_v2.definition_levels_byte_length < 0 ||
+        header_v2.repetition_levels_byte_length < 0 {
+      return Err(general_err!("Invalid page header: negative levels byte length"));
+    }
+    levels_len = header_v2.definition_levels_byte_length as i64 +
+      header_v2.repetition_levels_byte_length as i64;
+  }
+  if (page_header.compressed_page_size as i64) < levels_len ||
+      (page_header.uncompressed_page_size as i64) < levels_len {
+    return Err(general_err!(
+      "Invalid page header: page size {} (uncompressed {}) is invalid",
+      page_header.compressed_page_size,
+      page_header.uncompressed_page_size
+    ));
+  }
+  Ok(())
+}
+
+/// Checks CRC32 checksum of page bytes, if checksum is set in page header.
+pub fn check_page_crc(page_header: &PageHeader, buffer: &[u8]) -> Result<()> {
+  if let Some(crc) = page_header.crc {
+    let actual = crc32fast::hash(buffer) as i32;
+    if actual != crc {
+      return Err(general_err!("Page checksum mismatch: {} != {}", actual, crc));
+    }
+  }
+  Ok(())
+}
+
+/// Creates data page of nulls with the same number of values as data page of
+/// `page_header`. Returns `None` if column is required or repeated, or if page is not
+/// a data page.
+fn null_page(descr: &ColumnDescPtr, page_header: &PageHeader) -> Option<(Page, u32)> {
+  if descr.max_def_level() == 0 || descr.max_rep_level() > 0 {
+    return None;
+  }
+  let num_values = match page_header.type_ {
+    PageType::DATA_PAGE => page_header.data_page_header.as_ref()?.num_values,
+    PageType::DATA_PAGE_V2 => page_header.data_page_header_v2.as_ref()?.num_values,
+    _ => return None
+  };
+  if num_values < 0 {
+    return None;
+  }
+
+  let max_def_level = descr.max_def_level();
+  let num_values = num_values as usize;
+  let size = max_buffer_size(Encoding::RLE, max_def_level, num_values);
+  let mut encoder = LevelEncoder::v1(Encoding::RLE, max_def_level, vec![0; size]);
+  encoder.put(&vec![0; num_values]).ok()?;
+  let page = Page::DataPage {
+    buf: ByteBufferPtr::new(encoder.consume().ok()?),
+    num_values: num_values as u32,
+    encoding: Encoding::PLAIN,
+    def_level_encoding: Encoding::RLE,
+    rep_level_encoding: Encoding::RLE,
+    statistics: None
+  };
+  Some((page, num_values as u32))
+}
+
+/// Read wrapper that counts bytes read, used to measure size of page headers.
+struct CountingRead<'a, T: 'a + Read> {
+  inner: &'a mut T,
+  bytes_read: usize
+}
+
+impl<'a, T: 'a + Read> Read for CountingRead<'a, T> {
+  fn read(&mut self, buf: &mut [u8]) -> ::std::io::Result<usize> {
+    let num_bytes = self.inner.read(buf)?;
+    self.bytes_read += num_bytes;
+    Ok(num_bytes)
   }
 }
 
 
 #[cfg(test)]
 mod tests {
+  use std::error::Error;
+  use std::fs;
+  use std::sync::Arc;
+
   use basic::SortOrder;
-  use parquet_format::TypeDefinedOrder;
+  use column::reader::get_typed_column_reader;
+  use column::writer::ColumnWriter;
+  use data_type::{ByteArray, ByteArrayType, Int32Type, Int64Type};
+  use file::metrics::ReaderMetrics;
+  use file::properties::WriterProperties;
+  use file::writer::{FileWriter, write_to_bytes};
+  use basic::LogicalType;
+  use encryption::ciphers::{footer_aad, AesCipher};
+  use encryption::decrypt::KeyRetriever;
+  use encryption::metadata::EncryptionAlgorithm;
+  use parquet_format::{ColumnChunk, ColumnMetaData, CompressionCodec, ConvertedType};
+  use parquet_format::{DataPageHeader, Encoding as TEncoding, TypeDefinedOrder};
+  use parquet_format::{FieldRepetitionType, SchemaElement, Type as TType};
+  use record::{Row, RowAccessor};
+  use record::filter::RowFilter;
+  use schema::parser::parse_message_type;
   use super::*;
+  use thrift::protocol::{TCompactOutputProtocol, TOutputProtocol};
+  use util::memory::{BufferPool, MemTracker};
   use util::test_common::{get_temp_file, get_test_file, get_test_path};
 
   #[test]
@@ -588,6 +2042,428 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_file_reader_metadata_encrypted() {
+    let test_file =
+      get_temp_file("encrypted-1.parquet", b"PARE\x00\x00\x00\x00\x04\x00\x00\x00PARE");
+    assert_eq!(
+      SerializedFileReader::new(test_file).err().unwrap(),
+      general_err!(
+        "Parquet file has encrypted footer, decryption properties are required"
+      )
+    );
+
+    // Plaintext footer with encryption algorithm, which is an empty struct here, can be
+    // read without decryption properties
+    let schema = vec![
+      SchemaElement::new(
+        None, None, None, "schema".to_owned(), 0, None, None, None, None, None)
+    ];
+    let file_metadata = TFileMetaData::new(1, schema, 0, vec![], None, None, None);
+    let mut footer = Vec::new();
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut footer);
+      file_metadata.write_to_out_protocol(&mut prot).unwrap();
+      prot.flush().unwrap();
+    }
+    footer.pop();
+    footer.extend(&[0x0C, 0x10, 0x00, 0x00]);
+    let mut buf = PARQUET_MAGIC.to_vec();
+    buf.extend(&footer);
+    buf.extend(&[footer.len() as u8, 0, 0, 0]);
+    buf.extend(&PARQUET_MAGIC);
+    let test_file = get_temp_file("encrypted-2.parquet", &buf);
+    let reader = SerializedFileReader::new(test_file).unwrap();
+    assert!(reader.metadata().file_metadata().unknown_fields().is_empty());
+  }
+
+  /// Writes file with required INT32 column `a` of 1000 values, a dictionary page and
+  /// several data pages.
+  fn write_dictionary_file() -> Vec<u8> {
+    let schema =
+      Rc::new(parse_message_type("message schema { REQUIRED INT32 a; }").unwrap());
+    let props = Rc::new(
+      WriterProperties::builder()
+        .set_data_pagesize_limit(256)
+        .set_write_batch_size(64)
+        .build()
+    );
+    write_to_bytes(schema, props, |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      while let Some(mut col_writer) = row_group_writer.next_column()? {
+        if let ColumnWriter::Int32ColumnWriter(ref mut typed) = col_writer {
+          let values = (0..1000).map(|i| i % 50).collect::<Vec<i32>>();
+          typed.write_batch(&values[..], None, None)?;
+        }
+        row_group_writer.close_column(col_writer)?;
+      }
+      writer.close_row_group(row_group_writer)
+    }).unwrap()
+  }
+
+  /// Encrypts pages of the single column chunk of file `data` with footer `key` and
+  /// returns file with encrypted footer, or with signed plaintext footer. AAD of
+  /// modules starts with `aad_prefix`, which is stored in file only if set in
+  /// `algorithm`.
+  fn encrypt_file(
+    data: &[u8],
+    key: &[u8],
+    algorithm: &AlgorithmMetaData,
+    aad_prefix: &[u8],
+    key_metadata: Option<Vec<u8>>,
+    plaintext_footer: bool
+  ) -> Vec<u8> {
+    let metadata_len = LittleEndian::read_i32(&data[data.len() - 8..]) as usize;
+    let metadata = ParquetMetaData::from_bytes(
+      &data[data.len() - 8 - metadata_len..data.len() - 8]).unwrap();
+    let row_group = metadata.row_group(0);
+    let column = row_group.column(0);
+    let start = column.dictionary_page_offset().unwrap_or(column.data_page_offset());
+    let end = start + column.compressed_size();
+
+    let mut file_aad = aad_prefix.to_vec();
+    file_aad.extend(algorithm.aad_file_unique.as_ref().unwrap());
+    let cipher =
+      ColumnCipher::new(key, algorithm.algorithm, &file_aad, 0, 0).unwrap();
+    let mut out =
+      if plaintext_footer { PARQUET_MAGIC } else { PARQUET_MAGIC_ENCRYPTED_FOOTER }
+        .to_vec();
+    let mut cursor = Cursor::new(&data[start as usize..end as usize]);
+    let mut data_page_offset = None;
+    let mut data_page_ordinal = 0;
+    while (cursor.position() as i64) < end - start {
+      let mut header = PageHeader::read_from_in_protocol(
+        &mut TCompactInputProtocol::new(&mut cursor)).unwrap();
+      let body_start = cursor.position() as usize;
+      let body_end = body_start + header.compressed_page_size as usize;
+      cursor.set_position(body_end as u64);
+      let body = &cursor.get_ref()[body_start..body_end];
+      let (page_type, header_type) = match header.type_ {
+        PageType::DICTIONARY_PAGE => {
+          (ModuleType::DICTIONARY_PAGE, ModuleType::DICTIONARY_PAGE_HEADER)
+        },
+        _ => {
+          data_page_offset = data_page_offset.or(Some(out.len() as i64));
+          (ModuleType::DATA_PAGE, ModuleType::DATA_PAGE_HEADER)
+        }
+      };
+      let body = cipher.encrypt(page_type, data_page_ordinal, body).unwrap();
+      header.compressed_page_size = body.len() as i32;
+      header.crc = None;
+      let mut header_buf = vec![];
+      {
+        let mut prot = TCompactOutputProtocol::new(&mut header_buf);
+        header.write_to_out_protocol(&mut prot).unwrap();
+        prot.flush().unwrap();
+      }
+      out.extend(cipher.encrypt(header_type, data_page_ordinal, &header_buf).unwrap());
+      out.extend(body);
+      if page_type == ModuleType::DATA_PAGE {
+        data_page_ordinal += 1;
+      }
+    }
+
+    let encrypted_column = ColumnChunkMetaData::builder(column.column_descr_ptr())
+      .set_encodings(column.encodings().clone())
+      .set_num_values(column.num_values())
+      .set_compression(column.compression())
+      .set_total_compressed_size(out.len() as i64 - 4)
+      .set_total_uncompressed_size(column.uncompressed_size())
+      .set_dictionary_page_offset(column.dictionary_page_offset().map(|_| 4))
+      .set_data_page_offset(data_page_offset.unwrap())
+      .set_crypto_metadata(Some(ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY))
+      .build()
+      .unwrap();
+    let row_group = RowGroupMetaData::builder(metadata.file_metadata().schema_descr_ptr())
+      .set_num_rows(row_group.num_rows())
+      .set_total_byte_size(row_group.total_byte_size())
+      .set_column_metadata(vec![Rc::new(encrypted_column)])
+      .build()
+      .unwrap();
+    let file_metadata = metadata.file_metadata();
+    let mut unknown_fields = UnknownEnums::default();
+    if plaintext_footer {
+      let data = algorithm.to_compact_bytes().unwrap();
+      let path = vec![ENCRYPTION_ALGORITHM_FIELD_ID];
+      unknown_fields.insert_field(path, ::thrift::protocol::TType::Struct, data);
+    }
+    let footer = ParquetMetaData::new(
+      FileMetaData::new(
+        file_metadata.version(),
+        file_metadata.num_rows(),
+        file_metadata.created_by().clone(),
+        file_metadata.schema_descr().root_schema_ptr(),
+        file_metadata.schema_descr_ptr(),
+        None
+      ).with_unknown_fields(unknown_fields),
+      vec![Rc::new(row_group)]
+    ).to_bytes().unwrap();
+
+    let footer_cipher = AesCipher::new(key).unwrap();
+    let module = footer_cipher.encrypt_gcm(&footer_aad(&file_aad), &footer).unwrap();
+    let metadata_start = out.len();
+    if plaintext_footer {
+      // Signature is nonce and tag of the encrypted footer
+      out.extend(&footer);
+      out.extend(&module[4..16]);
+      out.extend(&module[module.len() - 16..]);
+    } else {
+      let crypto_metadata = FileCryptoMetaData {
+        algorithm: algorithm.clone(),
+        key_metadata: key_metadata
+      };
+      {
+        let mut prot = TCompactOutputProtocol::new(&mut out);
+        crypto_metadata.write(&mut prot).unwrap();
+        prot.flush().unwrap();
+      }
+      out.extend(&module);
+    }
+    let len = (out.len() - metadata_start) as i32;
+    let mut len_buf = [0; 4];
+    LittleEndian::write_i32(&mut len_buf, len);
+    out.extend(&len_buf);
+    out.extend(&out[..4].to_vec());
+    out
+  }
+
+  fn aes_gcm_v1(aad_prefix: Option<&[u8]>, supply_aad_prefix: bool) -> AlgorithmMetaData {
+    AlgorithmMetaData {
+      algorithm: EncryptionAlgorithm::AES_GCM_V1,
+      aad_prefix: aad_prefix.map(|prefix| prefix.to_vec()),
+      aad_file_unique: Some(b"unique".to_vec()),
+      supply_aad_prefix: supply_aad_prefix
+    }
+  }
+
+  /// Reads all rows of file `data` with decryption `properties`.
+  fn read_encrypted_rows(
+    file_name: &str,
+    data: &[u8],
+    properties: FileDecryptionProperties
+  ) -> Result<Vec<Row>> {
+    let file = get_temp_file(file_name, data);
+    let reader = SerializedFileReader::new_with_decryption(file, properties)?;
+    let mut rows = vec![];
+    let row_group = reader.get_row_group(0)?;
+    let mut iter = row_group.get_row_iter(None)?;
+    while let Some(row) = iter.next() {
+      rows.push(row?);
+    }
+    Ok(rows)
+  }
+
+  fn footer_key_properties(key: &[u8]) -> FileDecryptionProperties {
+    FileDecryptionProperties::builder().set_footer_key(key.to_vec()).build().unwrap()
+  }
+
+  #[test]
+  fn test_file_reader_encrypted_footer() {
+    let data = write_dictionary_file();
+    let expected: Vec<Row> =
+      SerializedFileReader::new(get_temp_file("encrypted-plain.parquet", &data))
+      .unwrap()
+      .get_row_iter(None)
+      .unwrap()
+      .map(|row| row.unwrap())
+      .collect();
+    let key = b"0123456789012345";
+
+    let mut algorithm = aes_gcm_v1(None, false);
+    for &encryption_algorithm in
+      &[EncryptionAlgorithm::AES_GCM_V1, EncryptionAlgorithm::AES_GCM_CTR_V1]
+    {
+      algorithm.algorithm = encryption_algorithm;
+      let encrypted = encrypt_file(&data, key, &algorithm, b"", None, false);
+      let properties = footer_key_properties(key);
+      let rows =
+        read_encrypted_rows("encrypted-footer.parquet", &encrypted, properties).unwrap();
+      assert_eq!(rows, expected);
+    }
+
+    let encrypted = encrypt_file(&data, key, &aes_gcm_v1(None, false), b"", None, false);
+    let properties = footer_key_properties(b"1234567890123450");
+    assert_eq!(
+      read_encrypted_rows("encrypted-footer.parquet", &encrypted, properties)
+        .err()
+        .unwrap()
+        .without_context(),
+      &general_err!(
+        "Could not decrypt footer: Failed to decrypt module, key is wrong or module is \
+         corrupt"
+      )
+    );
+    let properties = FileDecryptionProperties::builder().build().unwrap();
+    assert_eq!(
+      read_encrypted_rows("encrypted-footer.parquet", &encrypted, properties)
+        .err()
+        .unwrap()
+        .without_context(),
+      &general_err!("Footer key is not set")
+    );
+  }
+
+  #[test]
+  fn test_file_reader_encrypted_corrupt_page() {
+    let data = write_dictionary_file();
+    let key = b"0123456789012345";
+    let mut encrypted =
+      encrypt_file(&data, key, &aes_gcm_v1(None, false), b"", None, false);
+    // Tag of the dictionary page header
+    encrypted[4 + 4 + 12] ^= 1;
+    let file = get_temp_file("encrypted-corrupt-page.parquet", &encrypted);
+    let properties = footer_key_properties(key);
+    let reader = SerializedFileReader::new_with_decryption(file, properties).unwrap();
+    let mut page_reader =
+      reader.get_row_group(0).unwrap().get_column_page_reader(0).unwrap();
+    let err = page_reader.get_next_page().err().unwrap();
+    assert_eq!(err.context().and_then(|context| context.page()), Some(0));
+    assert_eq!(
+      err.without_context(),
+      &general_err!("Failed to decrypt module, key is wrong or module is corrupt")
+    );
+  }
+
+  #[test]
+  fn test_file_reader_encrypted_aad_prefix() {
+    let data = write_dictionary_file();
+    let key = b"0123456789012345";
+    let prefix = b"tester";
+    let encrypted =
+      encrypt_file(&data, key, &aes_gcm_v1(Some(prefix), false), prefix, None, false);
+    let num_rows = |data: &[u8], properties| {
+      read_encrypted_rows("encrypted-aad.parquet", data, properties)
+        .map(|rows| rows.len())
+    };
+
+    let properties = FileDecryptionProperties::builder()
+      .set_footer_key(key.to_vec())
+      .set_aad_prefix(prefix.to_vec())
+      .build()
+      .unwrap();
+    assert_eq!(num_rows(&encrypted, properties).unwrap(), 1000);
+    let properties = FileDecryptionProperties::builder()
+      .set_footer_key(key.to_vec())
+      .set_aad_prefix(b"other".to_vec())
+      .build()
+      .unwrap();
+    assert_eq!(
+      num_rows(&encrypted, properties).err().unwrap().without_context(),
+      &general_err!("AAD prefix does not match the prefix stored in file")
+    );
+
+    // AAD prefix is not stored in file and must be supplied
+    let encrypted =
+      encrypt_file(&data, key, &aes_gcm_v1(None, true), prefix, None, false);
+    assert_eq!(
+      num_rows(&encrypted, footer_key_properties(key)).err().unwrap().without_context(),
+      &general_err!("AAD prefix is not stored in file and must be supplied")
+    );
+    let properties = FileDecryptionProperties::builder()
+      .set_footer_key(key.to_vec())
+      .set_aad_prefix(prefix.to_vec())
+      .build()
+      .unwrap();
+    assert_eq!(num_rows(&encrypted, properties).unwrap(), 1000);
+  }
+
+  /// Key retriever of tests, which returns `key` for key metadata `key_id`.
+  struct TestKeyRetriever {
+    key_id: Vec<u8>,
+    key: Vec<u8>
+  }
+
+  impl KeyRetriever for TestKeyRetriever {
+    fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>> {
+      if key_metadata == &self.key_id[..] {
+        Ok(self.key.clone())
+      } else {
+        Err(general_err!("Unknown key {:?}", key_metadata))
+      }
+    }
+  }
+
+  #[test]
+  fn test_file_reader_encrypted_key_retriever() {
+    let data = write_dictionary_file();
+    let key = b"0123456789012345";
+    let algorithm = aes_gcm_v1(None, false);
+    let encrypted =
+      encrypt_file(&data, key, &algorithm, b"", Some(b"kf".to_vec()), false);
+    let retriever = TestKeyRetriever { key_id: b"kf".to_vec(), key: key.to_vec() };
+    let properties = FileDecryptionProperties::builder()
+      .set_key_retriever(Rc::new(retriever))
+      .build()
+      .unwrap();
+    let rows =
+      read_encrypted_rows("encrypted-retriever.parquet", &encrypted, properties).unwrap();
+    assert_eq!(rows.len(), 1000);
+
+    let retriever = TestKeyRetriever { key_id: b"kc".to_vec(), key: key.to_vec() };
+    let properties = FileDecryptionProperties::builder()
+      .set_key_retriever(Rc::new(retriever))
+      .build()
+      .unwrap();
+    assert_eq!(
+      read_encrypted_rows("encrypted-retriever.parquet", &encrypted, properties)
+        .err()
+        .unwrap()
+        .without_context(),
+      &general_err!("Unknown key [107, 102]")
+    );
+  }
+
+  #[test]
+  fn test_file_reader_encrypted_plaintext_footer() {
+    let data = write_dictionary_file();
+    let key = b"0123456789012345";
+    let encrypted =
+      encrypt_file(&data, key, &aes_gcm_v1(None, false), b"", None, true);
+
+    // Metadata is readable without keys, but encrypted column is not
+    let file = get_temp_file("encrypted-plaintext-footer.parquet", &encrypted);
+    let reader = SerializedFileReader::new(file).unwrap();
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 1000);
+    assert!(reader.metadata().file_metadata().unknown_fields().is_empty());
+    let row_group = reader.metadata().row_group(0);
+    assert_eq!(
+      row_group.column(0).crypto_metadata(),
+      Some(&ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY)
+    );
+    let err = reader.get_row_group(0).unwrap().get_column_reader(0).err().unwrap();
+    assert_eq!(
+      err.without_context(),
+      &general_err!(
+        "Column a is encrypted, decryption properties are required to read it"
+      )
+    );
+
+    let rows = read_encrypted_rows(
+      "encrypted-plaintext-footer.parquet", &encrypted, footer_key_properties(key)
+    ).unwrap();
+    assert_eq!(rows.len(), 1000);
+
+    // Tampered signature
+    let mut tampered = encrypted.clone();
+    let len = tampered.len();
+    tampered[len - 9] ^= 1;
+    assert_eq!(
+      read_encrypted_rows(
+        "encrypted-plaintext-footer.parquet", &tampered, footer_key_properties(key)
+      ).err().unwrap().without_context(),
+      &general_err!("Footer signature verification failed")
+    );
+    let properties = FileDecryptionProperties::builder()
+      .set_footer_key(key.to_vec())
+      .set_footer_signature_verification(false)
+      .build()
+      .unwrap();
+    let rows =
+      read_encrypted_rows("encrypted-plaintext-footer.parquet", &tampered, properties)
+        .unwrap();
+    assert_eq!(rows.len(), 1000);
+  }
+
   #[test]
   fn test_file_reader_column_orders_parse() {
     // Define simple schema, we do not need to provide logical types.
@@ -634,6 +2510,65 @@ mod tests {
     SerializedFileReader::<File>::parse_column_orders(t_column_orders, &schema_descr);
   }
 
+  #[test]
+  fn test_file_reader_new_with_metadata() {
+    let reader =
+      SerializedFileReader::new(get_test_file("alltypes_plain.parquet")).unwrap();
+    let metadata = reader.metadata();
+    let rows: Vec<_> = reader.get_row_iter(None).unwrap().collect();
+
+    // Footer is not read, so the reader works even if the footer is corrupt
+    let mut data = fs::read(get_test_path("alltypes_plain.parquet")).unwrap();
+    let len = data.len();
+    data[len - 4..].copy_from_slice(b"XXXX");
+    let file = get_temp_file("file_reader_new_with_metadata", &data);
+    assert!(SerializedFileReader::new(file.try_clone().unwrap()).is_err());
+    let reader = SerializedFileReader::new_with_metadata(file, metadata.clone());
+    assert!(Rc::ptr_eq(&reader.metadata(), &metadata));
+    assert_eq!(reader.get_row_iter(None).unwrap().collect::<Vec<_>>(), rows);
+  }
+
+  #[test]
+  fn test_row_group_reader_get_dictionary_page() {
+    let reader =
+      SerializedFileReader::new(get_test_file("alltypes_dictionary.parquet")).unwrap();
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    let page = row_group_reader.get_dictionary_page(0).unwrap().unwrap();
+    assert_eq!(page.page_type(), ::basic::PageType::DICTIONARY_PAGE);
+    assert_eq!(page.num_values(), 2);
+    let ids = get_typed_dictionary::<Int32Type>(&*row_group_reader, 0).unwrap();
+    assert_eq!(ids, Some(vec![0, 1]));
+    let strings = get_typed_dictionary::<ByteArrayType>(&*row_group_reader, 9).unwrap();
+    assert_eq!(strings, Some(vec![ByteArray::from("0"), ByteArray::from("1")]));
+
+    // Dictionary page without dictionary page offset in metadata
+    let reader =
+      SerializedFileReader::new(get_test_file("nested_lists.snappy.parquet")).unwrap();
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    let dictionary = get_typed_dictionary::<ByteArrayType>(&*row_group_reader, 0)
+      .unwrap()
+      .unwrap();
+    assert_eq!(dictionary.len(), 6);
+
+    // Column chunks that are not dictionary encoded
+    let reader =
+      SerializedFileReader::new(get_test_file("nonnullable.impala.parquet")).unwrap();
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    assert!(row_group_reader.get_dictionary_page(0).unwrap().is_none());
+    assert_eq!(get_typed_dictionary::<Int64Type>(&*row_group_reader, 0).unwrap(), None);
+  }
+
+  #[test]
+  fn test_get_typed_dictionary_type_mismatch() {
+    let reader =
+      SerializedFileReader::new(get_test_file("alltypes_dictionary.parquet")).unwrap();
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    assert_eq!(
+      get_typed_dictionary::<Int64Type>(&*row_group_reader, 0).err().unwrap(),
+      general_err!("Cannot read dictionary of column id of type INT32 as INT64")
+    );
+  }
+
   #[test]
   fn test_file_reader_try_from() {
     // Valid file path
@@ -689,6 +2624,671 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_page_reader_error_context() {
+    let mut file = get_test_file("alltypes_plain.parquet");
+    let reader = SerializedFileReader::new(file.try_clone().unwrap()).unwrap();
+    let row_group_metadata = reader.metadata().row_group(0);
+    let column = row_group_metadata.column(0);
+    let start = column.dictionary_page_offset().unwrap() as u64;
+
+    // Truncate data page of the column chunk, so dictionary page is read successfully
+    let mut chunk = vec![0; column.compressed_size() as usize - 1];
+    file.seek(SeekFrom::Start(start)).unwrap();
+    file.read_exact(&mut chunk).unwrap();
+    let context = ErrorContext::new()
+      .with_column_path(column.column_path().string())
+      .with_row_group(0)
+      .with_offset(start);
+    let mut page_reader = SerializedPageReader::new(
+      Cursor::new(chunk),
+      column.num_values(),
+      column.compression(),
+      column.column_type()
+    ).unwrap().with_error_context(context);
+
+    assert!(page_reader.get_next_page().unwrap().is_some());
+    let err = page_reader.get_next_page().err().unwrap();
+    let context = err.context().expect("Error context is set");
+    assert_eq!(context.column_path(), Some("id"));
+    assert_eq!(context.row_group(), Some(0));
+    assert_eq!(context.page(), Some(1));
+    assert!(context.offset().unwrap() > start);
+    match err.without_context() {
+      &ParquetError::External(_, _) => {},
+      err => panic!("Expected IO error, found {:?}", err)
+    }
+    assert!(err.source().unwrap().source().is_some());
+  }
+
+  /// Serializes Thrift structure with `write` and replaces bytes according to
+  /// `replacements`, each replaced byte must be unique in the output.
+  fn write_and_replace<F>(write: F, replacements: &[(u8, u8)]) -> Vec<u8>
+    where F: Fn(&mut TOutputProtocol) -> ::thrift::Result<()> {
+    let mut buf = Vec::new();
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut buf);
+      write(&mut prot).unwrap();
+      prot.flush().unwrap();
+    }
+    let original = buf.clone();
+    for &(from, to) in replacements {
+      let positions: Vec<usize> =
+        (0..original.len()).filter(|&i| original[i] == from).collect();
+      assert_eq!(positions.len(), 1, "Byte {} is not unique in {:?}", from, original);
+      buf[positions[0]] = to;
+    }
+    buf
+  }
+
+  #[test]
+  fn test_page_reader_unknown_enums() {
+    // Page of unknown type 7, which is written as DATA_PAGE_V2 (zigzag encoded as 6)
+    let header = PageHeader::new(
+      PageType::DATA_PAGE_V2, 4, 4, None, None, None, None, None);
+    let mut buf =
+      write_and_replace(|prot| header.write_to_out_protocol(prot), &[(6, 14)]);
+    buf.extend_from_slice(&[0; 4]);
+    // Data page with unknown encoding 11, which is written as DELTA_BYTE_ARRAY
+    let data_page_header = DataPageHeader::new(
+      1, TEncoding::DELTA_BYTE_ARRAY, TEncoding::RLE, TEncoding::RLE, None);
+    let header = PageHeader::new(
+      PageType::DATA_PAGE, 4, 4, None, data_page_header, None, None, None);
+    buf.extend(write_and_replace(|prot| header.write_to_out_protocol(prot), &[(14, 22)]));
+    buf.extend_from_slice(&[0; 4]);
+
+    let mut page_reader = SerializedPageReader::new(
+      Cursor::new(buf), 1, Compression::UNCOMPRESSED, Type::INT32).unwrap();
+    let page = page_reader.get_next_page().unwrap().unwrap();
+    assert_eq!(page.page_type(), ::basic::PageType::DATA_PAGE);
+    assert_eq!(page.encoding(), Encoding::Unknown(11));
+    assert_eq!(page.num_values(), 1);
+    assert!(page_reader.get_next_page().unwrap().is_none());
+  }
+
+  #[test]
+  fn test_file_reader_unknown_enums() {
+    let schema = vec![
+      SchemaElement::new(
+        None, None, None, "schema".to_owned(), 1, None, None, None, None, None),
+      SchemaElement::new(
+        TType::INT32, None, FieldRepetitionType::REQUIRED, "a".to_owned(), None,
+        ConvertedType::BSON, None, None, None, None)
+    ];
+    let column_metadata = ColumnMetaData::new(
+      TType::INT32, vec![TEncoding::DELTA_LENGTH_BYTE_ARRAY], vec!["a".to_owned()],
+      CompressionCodec::LZ4, 0, 0, 0, None, 4, None, None, None, None);
+    let column = ColumnChunk::new(None, 4, column_metadata, None, None, None, None);
+    let row_group = RowGroup::new(vec![column], 0, 0, None);
+    let file_metadata =
+      TFileMetaData::new(1, schema, 0, vec![row_group], None, None, None);
+
+    // Replace BSON (20), DELTA_LENGTH_BYTE_ARRAY (6) and LZ4 (5), which are zigzag
+    // encoded, with unknown values 30, 12 and 9
+    let footer = write_and_replace(
+      |prot| file_metadata.write_to_out_protocol(prot),
+      &[(40, 60), (12, 24), (10, 18)]
+    );
+    let mut buf = PARQUET_MAGIC.to_vec();
+    buf.extend(&footer);
+    buf.extend(&[footer.len() as u8, 0, 0, 0]);
+    buf.extend(&PARQUET_MAGIC);
+
+    let file = get_temp_file("file_reader_unknown_enums.parquet", &buf);
+    let reader = SerializedFileReader::new(file).unwrap();
+    let metadata = reader.metadata();
+    assert_eq!(
+      metadata.file_metadata().schema_descr().column(0).logical_type(),
+      LogicalType::Unknown(30)
+    );
+    let row_group = metadata.row_group(0);
+    let column = row_group.column(0);
+    assert_eq!(column.encodings(), &vec![Encoding::Unknown(12)]);
+    assert_eq!(column.compression(), Compression::Unknown(9));
+
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    assert_eq!(
+      row_group_reader.get_column_page_reader(0).err().unwrap().without_context(),
+      &nyi_err!("The codec type Unknown(9) is not supported yet")
+    );
+  }
+
+  #[test]
+  fn test_file_reader_metadata_limits() {
+    let data = &b"PAR1\x00\x00\x00\x00\x00\x00\xff\xff\xff\xff\x04\x00\x00\x00PAR1"[..];
+    let limits = MetadataLimits::new().with_max_metadata_size(3);
+    assert_eq!(
+      SerializedFileReader::new_with_limits(Cursor::new(data), limits).err().unwrap(),
+      general_err!("Invalid Parquet file. Metadata length 4 exceeds limit 3")
+    );
+
+    let limits = MetadataLimits::new().with_max_row_groups(0);
+    let err = SerializedFileReader::new_with_limits(
+      get_test_file("alltypes_plain.parquet"), limits).err().unwrap();
+    assert_eq!(
+      err.without_context(),
+      &general_err!("Could not parse metadata: Number of row groups 1 exceeds limit 0")
+    );
+
+    // Schema of nullable.impala.parquet has groups nested 8 levels deep
+    let limits = MetadataLimits::new().with_max_schema_depth(7);
+    assert_eq!(
+      SerializedFileReader::new_with_limits(
+        get_test_file("nullable.impala.parquet"), limits).err().unwrap(),
+      general_err!("Schema depth 8 at group 'element' exceeds limit 7")
+    );
+    let limits = MetadataLimits::new()
+      .with_max_schema_depth(8)
+      .with_max_row_groups(1);
+    assert!(SerializedFileReader::new_with_limits(
+      get_test_file("nullable.impala.parquet"), limits).is_ok());
+
+    let limits = MetadataLimits::new().with_max_columns(10);
+    assert_eq!(
+      SerializedFileReader::new_with_limits(
+        get_test_file("alltypes_plain.parquet"), limits).err().unwrap(),
+      general_err!("Schema has 11 leaf columns, which exceeds limit 10")
+    );
+    let limits = MetadataLimits::new().with_max_columns(11);
+    assert!(SerializedFileReader::new_with_limits(
+      get_test_file("alltypes_plain.parquet"), limits).is_ok());
+  }
+
+  #[test]
+  fn test_file_reader_error_context() {
+    // Metadata of 4 bytes starts at offset 10
+    let err = SerializedFileReader::new(Cursor::new(
+      &b"PAR1\x00\x00\x00\x00\x00\x00\xff\xff\xff\xff\x04\x00\x00\x00PAR1"[..]
+    )).err().unwrap();
+    assert_eq!(err.context().and_then(|context| context.offset()), Some(10));
+    match err.without_context() {
+      &ParquetError::General(ref message) => {
+        assert!(message.starts_with("Could not parse metadata"))
+      },
+      err => panic!("Expected General error, found {:?}", err)
+    }
+  }
+
+  /// Writes file with optional INT32 columns `a` and `b` of 100 values each, compressed
+  /// with SNAPPY. Returns file bytes and offset of the first page body of column `a`.
+  fn write_snappy_file(file_name: &str) -> (Vec<u8>, usize) {
+    let schema = Rc::new(parse_message_type(
+      "message schema { OPTIONAL INT32 a; OPTIONAL INT32 b; }"
+    ).unwrap());
+    let props = Rc::new(
+      WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_dictionary_enabled(false)
+        .build()
+    );
+    let bytes = write_to_bytes(schema, props, |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      while let Some(mut col_writer) = row_group_writer.next_column()? {
+        if let ColumnWriter::Int32ColumnWriter(ref mut typed) = col_writer {
+          let values = (0..100).collect::<Vec<i32>>();
+          typed.write_batch(&values[..], Some(&vec![1; 100][..]), None)?;
+        }
+        row_group_writer.close_column(col_writer)?;
+      }
+      writer.close_row_group(row_group_writer)
+    }).unwrap();
+
+    let start = {
+      let reader =
+        SerializedFileReader::new(get_temp_file(file_name, &bytes[..])).unwrap();
+      reader.metadata().row_group(0).column(0).data_page_offset() as usize
+    };
+    let mut cursor = Cursor::new(&bytes[start..]);
+    PageHeader::read_from_in_protocol(&mut TCompactInputProtocol::new(&mut cursor))
+      .unwrap();
+    let body_start = start + cursor.position() as usize;
+    (bytes, body_start)
+  }
+
+  /// Writes page header and page bytes into `out`.
+  fn write_page(header: &PageHeader, body: &[u8], out: &mut Vec<u8>) {
+    {
+      let mut protocol = TCompactOutputProtocol::new(&mut *out);
+      header.write_to_out_protocol(&mut protocol).unwrap();
+      protocol.flush().unwrap();
+    }
+    out.extend_from_slice(body);
+  }
+
+  #[test]
+  fn test_file_reader_lenient_corrupt_page() {
+    let (mut bytes, body_start) =
+      write_snappy_file("test_file_reader_lenient_corrupt_page");
+    // Corrupt length of the uncompressed data in SNAPPY stream
+    bytes[body_start] = 1;
+
+    let file = get_temp_file("test_file_reader_lenient_corrupt_page", &bytes[..]);
+    let reader = SerializedFileReader::new(file.try_clone().unwrap()).unwrap();
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    let mut page_reader = row_group_reader.get_column_page_reader(0).unwrap();
+    let err = page_reader.get_next_page().err().unwrap();
+    assert_eq!(err.context().and_then(|context| context.page()), Some(0));
+
+    let metrics = Arc::new(ReaderMetrics::new());
+    let reader = SerializedFileReader::new_lenient(file).unwrap()
+      .with_metrics(metrics.clone());
+    let mut num_rows = 0;
+    for (i, row) in reader.get_row_iter(None).unwrap().enumerate() {
+      let row = row.unwrap();
+      assert!(row.get_int(0).is_err(), "Value of corrupt page should be null");
+      assert_eq!(row.get_int(1).unwrap(), i as i32);
+      num_rows += 1;
+    }
+    assert_eq!(num_rows, 100);
+    assert_eq!(metrics.pages_corrupt(), 1);
+  }
+
+  #[test]
+  fn test_file_reader_lenient_row_groups() {
+    let (bytes, _) = write_snappy_file("test_file_reader_lenient_row_groups");
+    let file = get_temp_file("test_file_reader_lenient_row_groups", &bytes[..]);
+    let reader = SerializedFileReader::new(file).unwrap();
+    let row_group = reader.metadata().row_group(0);
+    assert!(SerializedFileReader::<File>::check_row_group(&row_group, 1000).is_ok());
+    assert_eq!(
+      SerializedFileReader::<File>::check_row_group(&row_group, 10),
+      Err(general_err!("Column chunk a is out of bounds: offset 4, size {}",
+        row_group.column(0).compressed_size()))
+    );
+  }
+
+  #[test]
+  fn test_page_reader_lenient_checksum() {
+    let desc = reader_test_descr();
+    let max_def_level = desc.max_def_level();
+    let mut body = {
+      let mut encoder = LevelEncoder::v1(
+        Encoding::RLE, max_def_level,
+        vec![0; max_buffer_size(Encoding::RLE, max_def_level, 3)]);
+      encoder.put(&[1, 0, 1]).unwrap();
+      encoder.consume().unwrap()
+    };
+    body.extend_from_slice(&[1, 0, 0, 0, 3, 0, 0, 0]);
+    let make_header = |crc: Option<i32>| PageHeader::new(
+      PageType::DATA_PAGE,
+      body.len() as i32,
+      body.len() as i32,
+      crc,
+      DataPageHeader::new(3, TEncoding::PLAIN, TEncoding::RLE, TEncoding::RLE, None),
+      None,
+      None,
+      None
+    );
+
+    // Valid page, page with invalid checksum and page without checksum, followed by
+    // corrupt page header
+    let mut chunk = vec![];
+    let crc = crc32fast::hash(&body[..]) as i32;
+    write_page(&make_header(Some(crc)), &body[..], &mut chunk);
+    write_page(&make_header(Some(crc + 1)), &body[..], &mut chunk);
+    write_page(&make_header(None), &body[..], &mut chunk);
+    chunk.extend_from_slice(&[0xff; 16]);
+
+    let read = |lenient: bool| {
+      let mut page_reader = SerializedPageReader::new(
+        Cursor::new(chunk.clone()), 12, Compression::UNCOMPRESSED, Type::INT32
+      ).unwrap();
+      if lenient {
+        page_reader = page_reader.with_lenient(desc.clone());
+      }
+      let column_reader = ColumnReaderImpl::new(desc.clone(), Box::new(page_reader));
+      let mut column_reader = get_typed_column_reader::<Int32Type>(
+        ColumnReader::Int32ColumnReader(column_reader));
+      let mut values = vec![0; 12];
+      let mut def_levels = vec![0; 12];
+      column_reader.read_batch(12, Some(&mut def_levels[..]), None, &mut values[..])
+        .map(|(num_values, num_levels)| {
+          (values[0..num_values].to_vec(), def_levels[0..num_levels].to_vec())
+        })
+    };
+
+    // Checksums are not verified in strict mode, reading fails at page header
+    assert!(read(false).is_err());
+    assert_eq!(read(true).unwrap(), (
+      vec![1, 3, 1, 3],
+      vec![1, 0, 1, 0, 0, 0, 1, 0, 1]
+    ));
+  }
+
+  fn reader_test_descr() -> ColumnDescPtr {
+    let schema = parse_message_type("message schema { OPTIONAL INT32 a; }").unwrap();
+    SchemaDescriptor::new(Rc::new(schema)).column(0)
+  }
+
+  #[test]
+  fn test_column_chunk_size() {
+    let reader = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap();
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    let row_group_metadata = row_group_reader.metadata();
+    let column = row_group_metadata.column(0);
+    let data = row_group_reader.get_column_chunk_bytes(0).unwrap();
+    assert_eq!(data.len() as i64, column.compressed_size());
+
+    let mut extended = data.clone();
+    extended.extend_from_slice(&[0; 20]);
+    assert_eq!(column_chunk_size(&extended, column.num_values()).unwrap(), data.len());
+    assert_eq!(
+      column_chunk_size(&data[..data.len() - 1], column.num_values()).err().unwrap(),
+      eof_err!("Column chunk ends in the middle of a page")
+    );
+  }
+
+  #[test]
+  fn test_discard_incorrect_statistics() {
+    let schema = parse_message_type("
+      message schema {
+        REQUIRED BYTE_ARRAY a (UTF8);
+        REQUIRED INT32 b;
+      }
+    ").unwrap();
+    let schema_descr = Rc::new(SchemaDescriptor::new(Rc::new(schema)));
+    let row_group = RowGroupMetaData::builder(schema_descr.clone())
+      .set_num_rows(1)
+      .set_column_metadata(vec![
+        Rc::new(ColumnChunkMetaData::builder(schema_descr.column(0))
+          .set_statistics(statistics::Statistics::byte_array(
+            Some(ByteArray::from("a")), Some(ByteArray::from("b")), None, 0, false))
+          .build()
+          .unwrap()),
+        Rc::new(ColumnChunkMetaData::builder(schema_descr.column(1))
+          .set_statistics(statistics::Statistics::int32(Some(1), Some(2), None, 3, false))
+          .build()
+          .unwrap())
+      ])
+      .build()
+      .unwrap()
+      .to_thrift();
+    let get_stats = |row_group: &RowGroup, i: usize| {
+      row_group.columns[i].meta_data.as_ref().unwrap().statistics.clone().unwrap()
+    };
+
+    let mut t_row_group = row_group.clone();
+    let writer_version = ApplicationVersion::parse("parquet-mr version 1.10.0");
+    discard_incorrect_statistics(&writer_version, &schema_descr, &mut t_row_group);
+    assert_eq!(t_row_group, row_group);
+
+    let mut t_row_group = row_group.clone();
+    let writer_version = ApplicationVersion::parse("parquet-mr version 1.7.0");
+    discard_incorrect_statistics(&writer_version, &schema_descr, &mut t_row_group);
+    let stats = get_stats(&t_row_group, 0);
+    assert_eq!(stats.min_value, None);
+    assert_eq!(stats.max_value, None);
+    assert_eq!(get_stats(&t_row_group, 1), get_stats(&row_group, 1));
+
+    let stats = RowGroupMetaData::from_thrift(schema_descr.clone(), t_row_group)
+      .unwrap()
+      .column(0)
+      .statistics()
+      .map(|stats| stats.has_min_max_set());
+    assert_eq!(stats, Some(false));
+  }
+
+  #[test]
+  fn test_file_reader_metrics() {
+    let metrics = Arc::new(ReaderMetrics::new());
+    let reader = SerializedFileReader::new(get_test_file("alltypes_plain.snappy.parquet"))
+      .unwrap()
+      .with_metrics(metrics.clone());
+    assert!(Arc::ptr_eq(&reader.metrics().unwrap(), &metrics));
+
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    assert!(Arc::ptr_eq(&row_group_reader.metrics().unwrap(), &metrics));
+    let row_group_metadata = row_group_reader.metadata();
+    let compressed_size = row_group_metadata.column(0).compressed_size();
+    let mut page_reader = row_group_reader.get_column_page_reader(0).unwrap();
+    let mut num_pages = 0;
+    while let Some(_) = page_reader.get_next_page().unwrap() {
+      num_pages += 1;
+    }
+    assert_eq!(metrics.pages_read(), num_pages);
+    // Page headers are included in the number of bytes read
+    assert_eq!(metrics.bytes_read(), compressed_size as u64);
+    assert!(metrics.bytes_decompressed() > 0);
+    assert_eq!(metrics.pages_decoded(), 0);
+    assert_eq!(metrics.rows_pruned(), 0);
+
+    let iter = reader.get_row_iter(None).unwrap().with_offset(1);
+    assert_eq!(iter.count(), 1);
+    assert_eq!(metrics.rows_pruned(), 1);
+    assert_eq!(metrics.pages_decoded(), row_group_reader.num_columns() as u64);
+  }
+
+  #[test]
+  fn test_file_reader_page_cache() {
+    let metrics = Arc::new(ReaderMetrics::new());
+    let reader = SerializedFileReader::new(get_test_file("alltypes_dictionary.parquet"))
+      .unwrap()
+      .with_metrics(metrics.clone())
+      .with_page_cache(PageCache::with_capacity(1024 * 1024));
+    let rows: Vec<_> = reader.get_row_iter(None).unwrap().collect();
+    let pages_read = metrics.pages_read();
+    let bytes_read = metrics.bytes_read();
+    assert!(pages_read > 0);
+
+    // Second pass reads pages from the cache
+    let cached_rows: Vec<_> = reader.get_row_iter(None).unwrap().collect();
+    assert_eq!(cached_rows, rows);
+    assert_eq!(metrics.pages_read(), pages_read);
+    assert_eq!(metrics.bytes_read(), bytes_read);
+    let cache = reader.page_cache().unwrap();
+    assert_eq!(cache.num_entries(), 11);
+    assert_eq!(cache.hits(), 11);
+    assert_eq!(cache.misses(), 11);
+    assert!(cache.memory_usage() > 0);
+    assert_eq!(cache.mem_tracker().memory_usage(), cache.memory_usage());
+
+    // Column chunks that do not fit into the cache are read from the file
+    let reader = SerializedFileReader::new(get_test_file("alltypes_dictionary.parquet"))
+      .unwrap()
+      .with_page_cache(PageCache::with_capacity(0));
+    assert_eq!(reader.get_row_iter(None).unwrap().count(), 2);
+    assert_eq!(reader.get_row_iter(None).unwrap().count(), 2);
+    assert_eq!(reader.page_cache().unwrap().num_entries(), 0);
+    assert!(SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap()
+      .page_cache()
+      .is_none());
+  }
+
+  #[test]
+  fn test_file_reader_page_mem_tracker() {
+    let schema = Rc::new(parse_message_type(
+      "message schema { REQUIRED INT32 a; }"
+    ).unwrap());
+    let props = Rc::new(
+      WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_dictionary_enabled(false)
+        .set_data_pagesize_limit(1024)
+        .set_write_batch_size(128)
+        .build()
+    );
+    let bytes = write_to_bytes(schema.clone(), props.clone(), |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      while let Some(mut col_writer) = row_group_writer.next_column()? {
+        if let ColumnWriter::Int32ColumnWriter(ref mut typed) = col_writer {
+          let values = (0..10000).collect::<Vec<i32>>();
+          typed.write_batch(&values[..], None, None)?;
+        }
+        row_group_writer.close_column(col_writer)?;
+      }
+      writer.close_row_group(row_group_writer)
+    }).unwrap();
+
+    // Column chunk is read page by page within the limit, and pages are released
+    let mem_tracker = Arc::new(MemTracker::with_limit(16 * 1024));
+    let file = get_temp_file("page_mem_tracker.parquet", &bytes[..]);
+    let reader = SerializedFileReader::new(file)
+      .unwrap()
+      .with_page_cache(PageCache::with_capacity(1024 * 1024))
+      .with_page_mem_tracker(mem_tracker.clone());
+    assert!(reader.metadata().row_group(0).column(0).uncompressed_size() > 16 * 1024);
+    assert_eq!(reader.get_row_iter(None).unwrap().count(), 10000);
+    assert!(mem_tracker.max_memory_usage() > 0);
+    assert_eq!(mem_tracker.memory_usage(), 0);
+    assert_eq!(reader.page_cache().unwrap().num_entries(), 0);
+
+    // Pages that do not fit into the limit are not read
+    let file = get_temp_file("page_mem_tracker.parquet", &bytes[..]);
+    let reader = SerializedFileReader::new(file)
+      .unwrap()
+      .with_page_mem_tracker(Arc::new(MemTracker::with_limit(16)));
+    let mut page_reader =
+      reader.get_row_group(0).unwrap().get_column_page_reader(0).unwrap();
+    match page_reader.get_next_page() {
+      Err(ref err) => match *err.without_context() {
+        ParquetError::ResourceExhausted(_) => {},
+        ref other => panic!("Expected ResourceExhausted error, found {:?}", other)
+      },
+      Ok(_) => panic!("Expected ResourceExhausted error, found a page")
+    }
+
+    // Lenient reader does not recover from memory limit when decompressing a page, that
+    // fits into the limit only while compressed
+    let bytes = write_to_bytes(schema, props, |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      while let Some(mut col_writer) = row_group_writer.next_column()? {
+        if let ColumnWriter::Int32ColumnWriter(ref mut typed) = col_writer {
+          typed.write_batch(&vec![1; 10000], None, None)?;
+        }
+        row_group_writer.close_column(col_writer)?;
+      }
+      writer.close_row_group(row_group_writer)
+    }).unwrap();
+    let file = get_temp_file("page_mem_tracker_lenient.parquet", &bytes[..]);
+    let reader = SerializedFileReader::new_lenient(file)
+      .unwrap()
+      .with_page_mem_tracker(Arc::new(MemTracker::with_limit(512)));
+    let mut page_reader =
+      reader.get_row_group(0).unwrap().get_column_page_reader(0).unwrap();
+    match page_reader.get_next_page() {
+      Err(ref err) => match *err.without_context() {
+        ParquetError::ResourceExhausted(_) => {},
+        ref other => panic!("Expected ResourceExhausted error, found {:?}", other)
+      },
+      Ok(_) => panic!("Expected ResourceExhausted error, found a page")
+    }
+  }
+
+  #[test]
+  fn test_file_reader_page_buffer_pool() {
+    let expected: Vec<_> =
+      SerializedFileReader::new(get_test_file("alltypes_dictionary.parquet"))
+        .unwrap()
+        .get_row_iter(None)
+        .unwrap()
+        .collect();
+
+    let pool = Arc::new(BufferPool::new(64));
+    let mem_tracker = Arc::new(MemTracker::new());
+    let reader = SerializedFileReader::new(get_test_file("alltypes_dictionary.parquet"))
+      .unwrap()
+      .with_page_mem_tracker(mem_tracker.clone())
+      .with_page_buffer_pool(pool.clone());
+    let rows: Vec<_> = reader.get_row_iter(None).unwrap().collect();
+    assert_eq!(rows, expected);
+    drop(rows);
+    let hits = pool.hits();
+    assert!(pool.misses() > 0);
+    assert!(pool.num_buffers() > 0);
+
+    // Second scan reuses buffers of the first one
+    let rows: Vec<_> = reader.get_row_iter(None).unwrap().collect();
+    assert_eq!(rows, expected);
+    drop(rows);
+    assert!(pool.hits() > hits);
+    assert_eq!(mem_tracker.memory_usage(), 0);
+  }
+
+  #[test]
+  fn test_file_reader_prefetcher() {
+    let expected: Vec<_> =
+      SerializedFileReader::new(get_test_file("alltypes_dictionary.parquet"))
+        .unwrap()
+        .get_row_iter(None)
+        .unwrap()
+        .collect();
+
+    // Limit below the size of a column chunk still prefetches one column chunk ahead
+    for &max_bytes in &[1, 1024 * 1024] {
+      let prefetcher =
+        Prefetcher::new(get_test_file("alltypes_dictionary.parquet"), max_bytes);
+      let reader = SerializedFileReader::new(get_test_file("alltypes_dictionary.parquet"))
+        .unwrap()
+        .with_prefetcher(prefetcher);
+      let rows: Vec<_> = reader.get_row_iter(None).unwrap().collect();
+      assert_eq!(rows, expected);
+
+      let num_columns = reader.metadata().file_metadata().schema_descr().num_columns();
+      let prefetcher = reader.prefetcher().unwrap();
+      assert_eq!(prefetcher.hits(), num_columns as u64);
+      assert_eq!(prefetcher.misses(), 0);
+      assert_eq!(prefetcher.pending_bytes(), 0);
+    }
+
+    // Projection skips prefetched column chunks
+    let prefetcher =
+      Prefetcher::new(get_test_file("alltypes_plain.parquet"), 1024 * 1024);
+    let reader = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap()
+      .with_prefetcher(prefetcher);
+    let projection = parse_message_type(
+      "message schema { OPTIONAL INT32 int_col; OPTIONAL BYTE_ARRAY string_col; }"
+    ).unwrap();
+    assert_eq!(reader.get_row_iter(Some(projection)).unwrap().count(), 8);
+    assert_eq!(reader.prefetcher().unwrap().hits(), 2);
+  }
+
+  #[test]
+  fn test_file_reader_dictionary_cache() {
+    let read = |reader: &SerializedFileReader<File>| {
+      let projection = parse_message_type(
+        "message schema { OPTIONAL INT32 int_col; }"
+      ).unwrap();
+      let filter = RowFilter::new(projection, |row| Ok(row.get_int(0)? > 0));
+      let row_group_reader = reader.get_row_group(0).unwrap();
+      let rows: Vec<_> =
+        row_group_reader.get_row_iter(None).unwrap().with_row_filter(filter).collect();
+      rows
+    };
+    let reader =
+      SerializedFileReader::new(get_test_file("alltypes_dictionary.parquet")).unwrap();
+    let expected = read(&reader);
+
+    let mem_tracker = Arc::new(MemTracker::new());
+    let reader = SerializedFileReader::new(get_test_file("alltypes_dictionary.parquet"))
+      .unwrap()
+      .with_dictionary_cache(mem_tracker.clone());
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    assert_eq!(row_group_reader.get_row_iter(None).unwrap().count(), 2);
+    assert!(mem_tracker.memory_usage() > 0);
+    drop(row_group_reader);
+    assert_eq!(mem_tracker.memory_usage(), 0);
+
+    assert_eq!(read(&reader), expected);
+    assert_eq!(mem_tracker.memory_usage(), 0);
+
+    // Dictionaries that do not fit are decoded without caching
+    let reader = SerializedFileReader::new(get_test_file("alltypes_dictionary.parquet"))
+      .unwrap()
+      .with_dictionary_cache(Arc::new(MemTracker::with_limit(0)));
+    assert_eq!(read(&reader), expected);
+  }
+
+  #[test]
+  fn test_file_reader_without_metrics() {
+    let reader =
+      SerializedFileReader::new(get_test_file("alltypes_plain.parquet")).unwrap();
+    assert!(reader.metrics().is_none());
+    assert!(reader.get_row_group(0).unwrap().metrics().is_none());
+  }
+
   #[test]
   fn test_file_reader() {
     let test_file = get_test_file("alltypes_plain.parquet");
@@ -863,4 +3463,52 @@ mod tests {
     }
     assert_eq!(page_count, 2);
   }
+
+  #[test]
+  fn test_file_reader_delta_binary_packed() {
+    // Both files are written by parquet-mr with DELTA_BINARY_PACKED data pages v2
+    let reader = SerializedFileReader::new(
+      get_test_file("test_datapage_v2.snappy.parquet")).unwrap();
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    let mut column_reader = get_typed_column_reader::<Int32Type>(
+      row_group_reader.get_column_reader(1).unwrap());
+    let mut values = vec![0; 8];
+    let (num_values, _) =
+      column_reader.read_batch(8, None, None, &mut values[..]).unwrap();
+    assert_eq!(&values[0..num_values], &[1, 2, 3, 4, 5]);
+
+    // 10000 values span many blocks of 128 values with 4 mini blocks each, decoded
+    // values must match statistics of the column chunk
+    let reader = SerializedFileReader::new(get_test_file("10k-v2.parquet")).unwrap();
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    let metadata = row_group_reader.metadata();
+
+    let mut column_reader = get_typed_column_reader::<Int32Type>(
+      row_group_reader.get_column_reader(1).unwrap());
+    let mut values = vec![0; 10001];
+    let (num_values, _) =
+      column_reader.read_batch(10001, None, None, &mut values[..]).unwrap();
+    assert_eq!(num_values, 10000);
+    match metadata.column(1).statistics() {
+      Some(statistics::Statistics::Int32(stats)) => {
+        assert_eq!(values[0..num_values].iter().min(), Some(stats.min()));
+        assert_eq!(values[0..num_values].iter().max(), Some(stats.max()));
+      },
+      stats => panic!("Unexpected statistics: {:?}", stats)
+    }
+
+    let mut column_reader = get_typed_column_reader::<Int64Type>(
+      row_group_reader.get_column_reader(2).unwrap());
+    let mut values = vec![0; 10001];
+    let (num_values, _) =
+      column_reader.read_batch(10001, None, None, &mut values[..]).unwrap();
+    assert_eq!(num_values, 10000);
+    match metadata.column(2).statistics() {
+      Some(statistics::Statistics::Int64(stats)) => {
+        assert_eq!(values[0..num_values].iter().min(), Some(stats.min()));
+        assert_eq!(values[0..num_values].iter().max(), Some(stats.max()));
+      },
+      stats => panic!("Unexpected statistics: {:?}", stats)
+    }
+  }
 }
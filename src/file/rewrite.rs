@@ -0,0 +1,2032 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains API to rewrite a Parquet file with different compression, encodings or
+//! row group size, and optionally without some of the columns.
+//!
+//! Row groups can be re-chunked to a target number of rows or bytes, which splits
+//! large row groups and merges small ones, e.g. to fix files written with bad
+//! defaults, see [`RewriteOptions::with_row_group_size`] and
+//! [`RewriteOptions::with_row_group_byte_size`].
+//!
+//! Values, definition and repetition levels are copied exactly, while pages are
+//! re-encoded and compressed according to [`WriterProperties`] of
+//! [`RewriteOptions`]. This is useful to reduce storage costs of existing files, e.g.
+//! by switching to a better compression codec.
+//!
+//! When only some of the columns need to change, column chunks that already use the
+//! target codec can be copied as is without decoding, see
+//! [`RewriteOptions::with_copy_column_chunks`].
+//!
+//! Values of columns with sensitive data can be replaced with nulls or hashes while
+//! the rest of the file is copied, see [`RewriteOptions::with_masked_columns`].
+//!
+//! Schema of existing files can be evolved by dropping fields and adding columns with
+//! nulls or a constant value, see [`RewriteOptions::with_dropped_columns`] and
+//! [`RewriteOptions::with_added_columns`].
+//!
+//! Row groups of several files with the same schema can be combined into one file
+//! without rewriting them with [`append_row_groups`].
+//!
+//! Files sorted by the same columns can be merged into one sorted file with
+//! [`merge_sorted_files`].
+//!
+//! Files written without page indexes can be copied with offset and column indexes
+//! built from page headers with [`backfill_page_indexes`], so that readers can skip
+//! pages of legacy data.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use std::rc::Rc;
+//! use parquet::basic::Compression;
+//! use parquet::file::properties::WriterProperties;
+//! use parquet::file::rewrite::{rewrite_file, RewriteOptions};
+//! # use std::fs;
+//! # let path = std::env::temp_dir().join("rewrite_example.parquet");
+//!
+//! let props = WriterProperties::builder().set_compression(Compression::GZIP).build();
+//! let options = RewriteOptions::new(Rc::new(props))
+//!   .with_row_group_size(4)
+//!   .with_dropped_columns(vec!["timestamp_col".to_owned()]);
+//!
+//! let input = File::open("data/alltypes_plain.parquet").unwrap();
+//! let output = File::create(&path).unwrap();
+//! assert_eq!(rewrite_file(input, output, &options).unwrap(), 8);
+//! # fs::remove_file(&path).unwrap();
+//! ```
+
+use std::cmp;
+use std::io::Write;
+use std::rc::Rc;
+
+use data_type::*;
+use column::reader::{get_typed_column_reader, ColumnReaderImpl};
+use column::writer::{ColumnWriter, ColumnWriterImpl};
+use errors::{ParquetError, Result};
+use file::metadata::{ColumnChunkMetaData, RowGroupMetaDataPtr};
+use file::page_index::{BoundaryOrder, ColumnIndex, OffsetIndex, PageLocation};
+use file::page_metadata::{PageMetadata, PageMetadataIterator};
+use file::properties::WriterPropertiesPtr;
+use file::reader::{FileReader, ParquetReader, SerializedFileReader};
+use file::statistics::compare_greater;
+use file::writer::{FileWriter, SerializedFileWriter};
+use record::merge::{declared_sorting_columns, sorting_projection, SortedMergeReader};
+use basic::{Repetition, Type as PhysicalType};
+use schema::types::{ColumnDescPtr, ColumnDescriptor, SchemaDescriptor, Type, TypePtr};
+
+/// Number of values and levels copied at once when rewriting a file.
+const COPY_BATCH_SIZE: usize = 1024;
+
+/// Function that computes hash of a byte array value, see [`ColumnMask::Hash`].
+pub type HashFn = Rc<Fn(&[u8]) -> Vec<u8>>;
+
+/// Masking of values of a column in the new file, see
+/// [`RewriteOptions::with_masked_columns`].
+#[derive(Clone)]
+pub enum ColumnMask {
+  /// Replaces all values with nulls. Only optional columns can be masked with nulls,
+  /// levels of repeated parents are kept, e.g. lists keep their lengths.
+  Null,
+  /// Replaces each value with its hash computed by the function, null values stay
+  /// nulls. Only `BYTE_ARRAY` and `FIXED_LEN_BYTE_ARRAY` columns can be masked with
+  /// hashes; for the latter hash must have the same length as the type. Use a keyed
+  /// or salted cryptographic hash to prevent recovery of original values.
+  Hash(HashFn)
+}
+
+/// Value of each row of a column added to the new file, see
+/// [`RewriteOptions::with_added_columns`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnValue {
+  /// Null in each row, only optional columns can be added with nulls.
+  Null,
+  Bool(bool),
+  Int32(i32),
+  Int64(i64),
+  Int96(Int96),
+  Float(f32),
+  Double(f64),
+  ByteArray(ByteArray),
+  /// Fixed length byte array, length must be the same as length of the type.
+  FixedLenByteArray(ByteArray)
+}
+
+impl ColumnValue {
+  /// Returns physical type of the value, or `None` for null.
+  fn physical_type(&self) -> Option<PhysicalType> {
+    match *self {
+      ColumnValue::Null => None,
+      ColumnValue::Bool(_) => Some(PhysicalType::BOOLEAN),
+      ColumnValue::Int32(_) => Some(PhysicalType::INT32),
+      ColumnValue::Int64(_) => Some(PhysicalType::INT64),
+      ColumnValue::Int96(_) => Some(PhysicalType::INT96),
+      ColumnValue::Float(_) => Some(PhysicalType::FLOAT),
+      ColumnValue::Double(_) => Some(PhysicalType::DOUBLE),
+      ColumnValue::ByteArray(_) => Some(PhysicalType::BYTE_ARRAY),
+      ColumnValue::FixedLenByteArray(_) => Some(PhysicalType::FIXED_LEN_BYTE_ARRAY)
+    }
+  }
+}
+
+/// Options of [`rewrite_file`].
+pub struct RewriteOptions {
+  props: WriterPropertiesPtr,
+  row_group_size: Option<usize>,
+  row_group_byte_size: Option<usize>,
+  dropped_columns: Vec<String>,
+  masked_columns: Vec<(String, ColumnMask)>,
+  added_columns: Vec<(TypePtr, ColumnValue)>,
+  copy_column_chunks: bool
+}
+
+impl RewriteOptions {
+  /// Creates options to rewrite a file with writer properties `props`, which define
+  /// compression, encodings, page sizes and statistics of the new file. Row groups of
+  /// the original file and all columns are kept.
+  pub fn new(props: WriterPropertiesPtr) -> Self {
+    Self {
+      props: props,
+      row_group_size: None,
+      row_group_byte_size: None,
+      dropped_columns: Vec::new(),
+      masked_columns: Vec::new(),
+      added_columns: Vec::new(),
+      copy_column_chunks: false
+    }
+  }
+
+  /// Sets number of rows of each row group of the new file, the last row group can be
+  /// smaller. Row groups of the original file are split or merged as needed.
+  pub fn with_row_group_size(mut self, num_rows: usize) -> Self {
+    self.row_group_size = Some(num_rows);
+    self
+  }
+
+  /// Sets approximate number of bytes of each row group of the new file. Row groups of
+  /// the original file are split or merged as needed, each row group of the new file
+  /// has at least one row.
+  ///
+  /// Size of rows is estimated from uncompressed sizes of column chunks of the original
+  /// file that are kept, assuming that all rows of a row group have the same size, so
+  /// it does not depend on compression of the new file. When row group size is set as
+  /// well, row group ends when either limit is reached.
+  pub fn with_row_group_byte_size(mut self, num_bytes: usize) -> Self {
+    self.row_group_byte_size = Some(num_bytes);
+    self
+  }
+
+  /// Sets fields to drop from the new file. Each field is identified by its path of
+  /// names separated by dots, e.g. `a.b`; dropping a group field drops all of its
+  /// leaf columns. Groups that do not have any fields left are dropped as well.
+  pub fn with_dropped_columns(mut self, columns: Vec<String>) -> Self {
+    self.dropped_columns = columns;
+    self
+  }
+
+  /// Sets columns to mask in the new file, where each column is identified by its path
+  /// of names separated by dots, e.g. `a.b`, and must be a leaf column that is not
+  /// dropped. Masked columns are always re-encoded, so neither values nor statistics
+  /// of the original column chunks are kept in the new file.
+  pub fn with_masked_columns(mut self, columns: Vec<(String, ColumnMask)>) -> Self {
+    self.masked_columns = columns;
+    self
+  }
+
+  /// Sets columns to add to the new file, e.g. to evolve schema of existing files.
+  /// Each column is a primitive field that is not repeated, appended to the root of
+  /// the schema after the fields of the original file, with the same value in each
+  /// row. Name of the field must not be used by fields that are kept, so that a column
+  /// can be replaced by dropping it and adding a new one with the same name.
+  pub fn with_added_columns(mut self, columns: Vec<(TypePtr, ColumnValue)>) -> Self {
+    self.added_columns = columns;
+    self
+  }
+
+  /// Sets whether or not column chunks compressed with the codec that writer
+  /// properties define for the column are copied as is, without decompressing and
+  /// decoding pages. Encodings, page sizes and statistics of such column chunks are
+  /// kept from the original file. Disabled by default.
+  ///
+  /// Only used when row groups of the original file are kept, i.e. neither row group
+  /// size nor row group byte size is set. Masked columns are never copied.
+  pub fn with_copy_column_chunks(mut self, value: bool) -> Self {
+    self.copy_column_chunks = value;
+    self
+  }
+}
+
+/// Reads file from `reader` and writes it to `sink` according to `options`. Returns
+/// number of rows written.
+///
+/// Key-value metadata, column orders and fields of file metadata unknown to this
+/// version of the crate are copied to the new file, see
+/// [`SerializedFileWriter::copy_footer_fields`].
+///
+/// Returns error if a field to drop or a column to mask does not exist in the file
+/// schema, if all fields are dropped, if a column cannot be masked as requested, or
+/// if a column cannot be added with its value.
+pub fn rewrite_file<R, W>(reader: R, sink: W, options: &RewriteOptions) -> Result<i64>
+  where R: 'static + ParquetReader,
+        W: 'static + Write {
+  let reader = SerializedFileReader::new(reader)?;
+  let metadata = reader.metadata();
+  let schema_descr = metadata.file_metadata().schema_descr_ptr();
+
+  let mut found = vec![false; options.dropped_columns.len()];
+  let schema = drop_fields(
+    &schema_descr.root_schema_ptr(),
+    "",
+    &options.dropped_columns,
+    &mut found
+  );
+  if let Some(i) = found.iter().position(|found| !found) {
+    return Err(general_err!(
+      "Column {} is not found in file schema",
+      options.dropped_columns[i]
+    ));
+  }
+  let schema = match schema {
+    Some(ref schema) if !schema.get_fields().is_empty() => schema.clone(),
+    _ => return Err(general_err!("Cannot drop all columns of a file"))
+  };
+  let schema = add_fields(&schema, &options.added_columns)?;
+
+  // Index of each column of the new schema in the original file schema, added columns
+  // are the last ones
+  let new_schema_descr = SchemaDescriptor::new(schema.clone());
+  let num_copied = new_schema_descr.num_columns() - options.added_columns.len();
+  let mut copiers = Vec::with_capacity(num_copied);
+  for descr in &new_schema_descr.columns()[..num_copied] {
+    let index = (0..schema_descr.num_columns())
+      .find(|i| schema_descr.column(*i).path() == descr.path())
+      .expect("Column of the new schema exists in the file schema");
+    copiers.push(ColumnCopier::new(schema_descr.column(index), index));
+  }
+  for (name, mask) in &options.masked_columns {
+    let index = new_schema_descr.columns().iter()
+      .position(|descr| &descr.path().string() == name);
+    match index {
+      Some(index) => copiers[index].set_mask(mask.clone())?,
+      None => return Err(general_err!("Column {} is not found in file schema", name))
+    }
+  }
+  for (i, (_, value)) in options.added_columns.iter().enumerate() {
+    check_added_column(&new_schema_descr.column(num_copied + i), value)?;
+  }
+
+  let columns: Vec<_> = copiers.iter().map(|copier| copier.column_index()).collect();
+  let row_group_sizes = get_row_group_sizes(metadata.row_groups(), &columns, options)?;
+
+  let copy_column_chunks = options.copy_column_chunks &&
+    options.row_group_size.is_none() && options.row_group_byte_size.is_none();
+  let mut writer = SerializedFileWriter::new(sink, schema, options.props.clone())?;
+  writer.copy_footer_fields(&metadata.file_metadata());
+  let mut num_rows = 0;
+  for (i, row_group_size) in row_group_sizes.into_iter().enumerate() {
+    if row_group_size == 0 {
+      continue;
+    }
+    let mut row_group_writer = writer.next_row_group()?;
+    for copier in copiers.iter_mut() {
+      if copy_column_chunks && !copier.is_masked() {
+        let row_group_reader = reader.get_row_group(i)?;
+        let row_group_metadata = row_group_reader.metadata();
+        let column = row_group_metadata.column(copier.column_index());
+        if column.compression() == options.props.compression(column.column_path()) {
+          let data = row_group_reader.get_column_chunk_bytes(copier.column_index())?;
+          row_group_writer.append_column_chunk(&data, column, row_group_size as u64)?;
+          continue;
+        }
+        // Other column chunks of this column could have been copied
+        copier.set_next_row_group(i);
+      }
+
+      let mut column_writer = match row_group_writer.next_column()? {
+        Some(column_writer) => column_writer,
+        None => return Err(general_err!("Missing column writer"))
+      };
+      copier.copy_rows(&reader, &mut column_writer, row_group_size)?;
+      row_group_writer.close_column(column_writer)?;
+    }
+    for (i, (_, value)) in options.added_columns.iter().enumerate() {
+      let is_optional = new_schema_descr.column(num_copied + i).max_def_level() > 0;
+      let mut column_writer = match row_group_writer.next_column()? {
+        Some(column_writer) => column_writer,
+        None => return Err(general_err!("Missing column writer"))
+      };
+      write_column_value(&mut column_writer, value, is_optional, row_group_size)?;
+      row_group_writer.close_column(column_writer)?;
+    }
+    writer.close_row_group(row_group_writer)?;
+    num_rows += row_group_size as i64;
+  }
+  writer.close()?;
+  Ok(num_rows)
+}
+
+/// Returns copy of root type `root` with `added` fields appended after its fields.
+fn add_fields(root: &TypePtr, added: &[(TypePtr, ColumnValue)]) -> Result<TypePtr> {
+  if added.is_empty() {
+    return Ok(root.clone());
+  }
+  let mut fields = root.get_fields().to_vec();
+  for (field, _) in added {
+    let info = field.get_basic_info();
+    if !field.is_primitive() || info.repetition() == Repetition::REPEATED {
+      return Err(general_err!(
+        "Cannot add column {}, column must be primitive and not repeated",
+        field.name()
+      ));
+    }
+    if fields.iter().any(|existing| existing.name() == field.name()) {
+      return Err(general_err!("Column {} already exists in file schema", field.name()));
+    }
+    fields.push(field.clone());
+  }
+  let info = root.get_basic_info();
+  let root = Type::group_type_builder(root.name())
+    .with_logical_type(info.logical_type())
+    .with_fields(&mut fields)
+    .build()?;
+  Ok(Rc::new(root))
+}
+
+/// Returns error if column `descr` cannot be added with `value` in each row.
+fn check_added_column(descr: &ColumnDescriptor, value: &ColumnValue) -> Result<()> {
+  match (value, value.physical_type()) {
+    (_, None) => {
+      if descr.self_type().get_basic_info().repetition() != Repetition::OPTIONAL {
+        return Err(general_err!(
+          "Cannot add column {} with nulls, column is not optional",
+          descr.path().string()
+        ));
+      }
+    },
+    (&ColumnValue::FixedLenByteArray(ref bytes), Some(physical_type))
+        if physical_type == descr.physical_type() => {
+      if bytes.len() != descr.type_length() as usize {
+        return Err(general_err!(
+          "Cannot add column {} of length {} with value of length {}",
+          descr.path().string(),
+          descr.type_length(),
+          bytes.len()
+        ));
+      }
+    },
+    (_, Some(physical_type)) => {
+      if physical_type != descr.physical_type() {
+        return Err(general_err!(
+          "Cannot add column {} of type {} with value of type {}",
+          descr.path().string(),
+          descr.physical_type(),
+          physical_type
+        ));
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Writes `num_rows` rows of added column with `value` in each row into `writer`.
+fn write_column_value(
+  writer: &mut ColumnWriter,
+  value: &ColumnValue,
+  is_optional: bool,
+  num_rows: usize
+) -> Result<()> {
+  macro_rules! write_by_type {
+    ($($value_variant:ident => $writer_variant:ident),*) => {
+      match (writer, value) {
+        $(
+          (
+            &mut ColumnWriter::$writer_variant(ref mut typed_writer),
+            &ColumnValue::$value_variant(ref value)
+          ) => {
+            write_typed_column_value(typed_writer, Some(value), is_optional, num_rows)
+          },
+          (
+            &mut ColumnWriter::$writer_variant(ref mut typed_writer),
+            &ColumnValue::Null
+          ) => {
+            write_typed_column_value(typed_writer, None, is_optional, num_rows)
+          },
+        )*
+        _ => Err(general_err!("Column value and column writer types do not match"))
+      }
+    };
+  }
+  write_by_type!(
+    Bool => BoolColumnWriter,
+    Int32 => Int32ColumnWriter,
+    Int64 => Int64ColumnWriter,
+    Int96 => Int96ColumnWriter,
+    Float => FloatColumnWriter,
+    Double => DoubleColumnWriter,
+    ByteArray => ByteArrayColumnWriter,
+    FixedLenByteArray => FixedLenByteArrayColumnWriter
+  )
+}
+
+/// Writes `num_rows` rows with `value` in each row into `writer`, or nulls if `value`
+/// is `None`. Rows are written in batches, so that memory does not depend on number of
+/// rows. Definition levels are only written for optional columns.
+fn write_typed_column_value<T: DataType>(
+  writer: &mut ColumnWriterImpl<T>,
+  value: Option<&T::T>,
+  is_optional: bool,
+  num_rows: usize
+) -> Result<()> {
+  let batch_size = cmp::min(num_rows, COPY_BATCH_SIZE);
+  let values = match value {
+    Some(value) => vec![value.clone(); batch_size],
+    None => Vec::new()
+  };
+  let def_level = if value.is_some() { 1 } else { 0 };
+  let def_levels = vec![def_level; batch_size];
+
+  let mut rows_left = num_rows;
+  while rows_left > 0 {
+    let n = cmp::min(rows_left, batch_size);
+    let values = if value.is_some() { &values[..n] } else { &values[..] };
+    let def_levels = if is_optional { Some(&def_levels[..n]) } else { None };
+    writer.write_batch(values, def_levels, None)?;
+    rows_left -= n;
+  }
+  Ok(())
+}
+
+/// Returns number of rows of each row group to write, according to row group size and
+/// row group byte size of `options`. Sizes of rows are estimated from uncompressed
+/// sizes of column chunks of `columns` in `row_groups` of the original file.
+fn get_row_group_sizes(
+  row_groups: &[RowGroupMetaDataPtr],
+  columns: &[usize],
+  options: &RewriteOptions
+) -> Result<Vec<usize>> {
+  let max_rows = options.row_group_size;
+  let max_bytes = options.row_group_byte_size;
+  if max_rows == Some(0) {
+    return Err(general_err!("Row group size must be positive"));
+  }
+  if max_bytes == Some(0) {
+    return Err(general_err!("Row group byte size must be positive"));
+  }
+  if max_rows.is_none() && max_bytes.is_none() {
+    return Ok(row_groups.iter().map(|row_group| row_group.num_rows() as usize).collect());
+  }
+
+  let mut sizes = Vec::new();
+  // Number of rows and estimated bytes of the current row group of the new file
+  let mut num_rows = 0;
+  let mut num_bytes = 0.0;
+  for row_group in row_groups {
+    let mut rows_left = row_group.num_rows() as usize;
+    if rows_left == 0 {
+      continue;
+    }
+    let row_group_bytes: i64 =
+      columns.iter().map(|i| row_group.column(*i).uncompressed_size()).sum();
+    let row_bytes = row_group_bytes as f64 / rows_left as f64;
+
+    while rows_left > 0 {
+      let mut n = rows_left;
+      if let Some(max_rows) = max_rows {
+        n = cmp::min(n, max_rows - num_rows);
+      }
+      if let Some(max_bytes) = max_bytes {
+        if row_bytes > 0.0 {
+          let fit = ((max_bytes as f64 - num_bytes) / row_bytes).floor().max(0.0);
+          // Row group has at least one row, even if the row is larger than the limit
+          let min_rows = if num_rows == 0 { 1 } else { 0 };
+          n = cmp::min(n, cmp::max(fit as usize, min_rows));
+        }
+      }
+      num_rows += n;
+      num_bytes += n as f64 * row_bytes;
+      rows_left -= n;
+
+      let is_full = max_rows.map_or(false, |max_rows| num_rows >= max_rows) ||
+        max_bytes.map_or(false, |max_bytes| num_bytes + row_bytes > max_bytes as f64);
+      if is_full {
+        sizes.push(num_rows);
+        num_rows = 0;
+        num_bytes = 0.0;
+      }
+    }
+  }
+  if num_rows > 0 {
+    sizes.push(num_rows);
+  }
+  Ok(sizes)
+}
+
+/// Appends all row groups of file `src` to file writer `dest`, and returns number of
+/// rows appended. Column chunks are copied as is, without decompressing and decoding
+/// pages, only offsets in column chunk metadata are updated, which makes this useful
+/// for compaction of many small files into a larger one.
+///
+/// Schema of `src` must have the same leaf columns as schema of `dest`, i.e. same
+/// paths, types and repetition, otherwise error is returned. Compression codecs and
+/// encodings of column chunks are kept, so files written with different codecs can be
+/// combined. Offset and column indexes of `src` are not copied, fields written in the
+/// footer are copied with [`SerializedFileWriter::copy_footer_fields`].
+///
+/// # Example
+///
+/// ```rust
+/// use std::fs::File;
+/// use std::rc::Rc;
+/// use parquet::file::properties::WriterProperties;
+/// use parquet::file::reader::{FileReader, SerializedFileReader};
+/// use parquet::file::rewrite::append_row_groups;
+/// use parquet::file::writer::{FileWriter, SerializedFileWriter};
+/// # use std::fs;
+/// # let path = std::env::temp_dir().join("append_row_groups_example.parquet");
+///
+/// let paths = ["data/alltypes_plain.parquet", "data/alltypes_plain.snappy.parquet"];
+/// let readers: Vec<_> = paths.iter()
+///   .map(|path| SerializedFileReader::new(File::open(path).unwrap()).unwrap())
+///   .collect();
+/// let schema = readers[0].metadata().file_metadata().schema_descr().root_schema_ptr();
+/// let props = Rc::new(WriterProperties::builder().build());
+///
+/// let mut writer =
+///   SerializedFileWriter::new(File::create(&path).unwrap(), schema, props).unwrap();
+/// for reader in &readers {
+///   append_row_groups(&mut writer, reader).unwrap();
+/// }
+/// writer.close().unwrap();
+///
+/// let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+/// assert_eq!(reader.metadata().file_metadata().num_rows(), 10);
+/// # fs::remove_file(&path).unwrap();
+/// ```
+pub fn append_row_groups<W: 'static + Write>(
+  dest: &mut SerializedFileWriter<W>,
+  src: &FileReader
+) -> Result<i64> {
+  dest.schema_descr().check_compatible(src.metadata().file_metadata().schema_descr())?;
+  dest.copy_footer_fields(&src.metadata().file_metadata());
+
+  let mut num_rows = 0;
+  for i in 0..src.num_row_groups() {
+    let row_group_reader = src.get_row_group(i)?;
+    let row_group_metadata = row_group_reader.metadata();
+    let row_group_size = row_group_metadata.num_rows() as u64;
+    let mut row_group_writer = dest.next_row_group()?;
+    for (j, column) in row_group_metadata.columns().iter().enumerate() {
+      let data = row_group_reader.get_column_chunk_bytes(j)?;
+      row_group_writer.append_column_chunk(&data, column, row_group_size)?;
+    }
+    dest.close_row_group(row_group_writer)?;
+    num_rows += row_group_size as i64;
+  }
+  Ok(num_rows)
+}
+
+/// Merges rows of files `readers`, each sorted by the same sorting columns declared in
+/// metadata of its row groups, into one sorted file written to `sink`, and returns
+/// number of rows written. Useful for compaction of sorted files.
+///
+/// Rows are merged with [`SortedMergeReader`], which reads only sorting columns, and
+/// values and levels of all columns are copied from each file in the merged order
+/// without converting them into rows, so any schema is supported. Files must have the
+/// same leaf columns. Row groups of the new file have `row_group_size` rows, except
+/// for the last one; sorting columns of the new file are declared according to
+/// `props`, see [`WriterPropertiesBuilder::set_sorting_columns`]. Fields written in the
+/// footer are copied with [`SerializedFileWriter::copy_footer_fields`].
+///
+/// [`SortedMergeReader`]: ../../record/merge/struct.SortedMergeReader.html
+/// [`WriterPropertiesBuilder::set_sorting_columns`]:
+/// ../properties/struct.WriterPropertiesBuilder.html#method.set_sorting_columns
+pub fn merge_sorted_files<W: 'static + Write>(
+  readers: Vec<Rc<FileReader>>,
+  sink: W,
+  props: WriterPropertiesPtr,
+  row_group_size: usize
+) -> Result<i64> {
+  if row_group_size == 0 {
+    return Err(general_err!("Row group size must be positive"));
+  }
+  let sorting_columns = declared_sorting_columns(&readers)?;
+  let schema_descr = readers[0].metadata().file_metadata().schema_descr_ptr();
+  let projection = sorting_projection(&schema_descr, &sorting_columns)?;
+  let mut merge_reader = SortedMergeReader::with_sorting_columns(
+    readers.clone(),
+    Some(projection),
+    sorting_columns
+  )?;
+
+  // Copiers of each column of each file, which copy rows of a file sequentially
+  let num_columns = schema_descr.num_columns();
+  let mut copiers: Vec<Vec<_>> = readers.iter()
+    .map(|_| {
+      (0..num_columns).map(|i| ColumnCopier::new(schema_descr.column(i), i)).collect()
+    })
+    .collect();
+
+  let schema = schema_descr.root_schema_ptr();
+  let mut writer = SerializedFileWriter::new(sink, schema, props)?;
+  for reader in &readers {
+    writer.copy_footer_fields(&reader.metadata().file_metadata());
+  }
+  let mut num_rows = 0;
+  loop {
+    // Runs of consecutive rows of the same file in the merged order
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    let mut row_group_rows = 0;
+    while row_group_rows < row_group_size {
+      let source = match merge_reader.next_with_source()? {
+        Some((source, _)) => source,
+        None => break
+      };
+      if runs.last().map_or(false, |run| run.0 == source) {
+        runs.last_mut().expect("Run exists").1 += 1;
+      } else {
+        runs.push((source, 1));
+      }
+      row_group_rows += 1;
+    }
+    if row_group_rows == 0 {
+      break;
+    }
+
+    let mut row_group_writer = writer.next_row_group()?;
+    for i in 0..num_columns {
+      let mut column_writer = match row_group_writer.next_column()? {
+        Some(column_writer) => column_writer,
+        None => return Err(general_err!("Missing column writer"))
+      };
+      for &(source, len) in &runs {
+        copiers[source][i].copy_rows(&*readers[source], &mut column_writer, len)?;
+      }
+      row_group_writer.close_column(column_writer)?;
+    }
+    writer.close_row_group(row_group_writer)?;
+    num_rows += row_group_rows as i64;
+  }
+  writer.close()?;
+  Ok(num_rows)
+}
+
+/// Copies file `reader` into `sink` with page indexes built from page headers, and
+/// returns number of column chunks that have page indexes in the new file. Useful to
+/// enable page skipping on files written without page indexes.
+///
+/// Column chunks are copied as is, without decompressing and decoding pages, only page
+/// headers are read. Offset index is written for each column chunk where first rows of
+/// data pages are known, i.e. except for data pages v1 of repeated columns. Column
+/// index is written in addition when every data page has statistics with min/max
+/// values, or contains only nulls. Existing page indexes are replaced, fields written
+/// in the footer are copied with [`SerializedFileWriter::copy_footer_fields`].
+///
+/// # Example
+///
+/// ```rust
+/// use std::fs::File;
+/// use std::rc::Rc;
+/// use parquet::file::page_index::read_offset_index;
+/// use parquet::file::properties::WriterProperties;
+/// use parquet::file::reader::{FileReader, SerializedFileReader};
+/// use parquet::file::rewrite::backfill_page_indexes;
+/// # use std::fs;
+/// # let path = std::env::temp_dir().join("backfill_page_indexes_example.parquet");
+///
+/// let input = File::open("data/alltypes_plain.parquet").unwrap();
+/// let output = File::create(&path).unwrap();
+/// let props = Rc::new(WriterProperties::builder().build());
+/// assert_eq!(backfill_page_indexes(input, output, props).unwrap(), 11);
+///
+/// let mut file = File::open(&path).unwrap();
+/// let reader = SerializedFileReader::new(file.try_clone().unwrap()).unwrap();
+/// let metadata = reader.metadata();
+/// let offset_index =
+///   read_offset_index(&mut file, metadata.row_group(0).column(0)).unwrap().unwrap();
+/// assert_eq!(offset_index.num_pages(), 1);
+/// # fs::remove_file(&path).unwrap();
+/// ```
+pub fn backfill_page_indexes<R, W>(
+  reader: R,
+  sink: W,
+  props: WriterPropertiesPtr
+) -> Result<usize>
+  where R: 'static + ParquetReader,
+        W: 'static + Write {
+  let file_reader = SerializedFileReader::new(reader.try_clone()?)?;
+  let metadata = file_reader.metadata();
+  let schema = metadata.file_metadata().schema_descr().root_schema_ptr();
+  let mut writer = SerializedFileWriter::new(sink, schema, props)?;
+  writer.copy_footer_fields(&metadata.file_metadata());
+
+  let mut num_indexed = 0;
+  for i in 0..file_reader.num_row_groups() {
+    let row_group_reader = file_reader.get_row_group(i)?;
+    let row_group_metadata = row_group_reader.metadata();
+    let row_group_size = row_group_metadata.num_rows() as u64;
+    let mut row_group_writer = writer.next_row_group()?;
+    let mut pages = Vec::with_capacity(row_group_metadata.num_columns());
+    for (j, column) in row_group_metadata.columns().iter().enumerate() {
+      let data = row_group_reader.get_column_chunk_bytes(j)?;
+      row_group_writer.append_column_chunk(&data, column, row_group_size)?;
+      let column_pages = PageMetadataIterator::new(reader.try_clone()?, column)
+        .collect::<Result<Vec<_>>>()?;
+      pages.push(column_pages);
+    }
+    let new_row_group_metadata = row_group_writer.close()?;
+    writer.close_row_group(row_group_writer)?;
+
+    for (j, column_pages) in pages.iter().enumerate() {
+      let column = new_row_group_metadata.column(j);
+      // Column chunks are copied as is, so pages are shifted by the same distance
+      let shift = column_chunk_start(column) -
+        column_chunk_start(row_group_metadata.column(j));
+      if let Some(offset_index) = offset_index_from_pages(column_pages, shift) {
+        let column_index = column_index_from_pages(column.column_descr(), column_pages);
+        writer.set_page_indexes(i, j, column_index, offset_index)?;
+        num_indexed += 1;
+      }
+    }
+  }
+  writer.close()?;
+  Ok(num_indexed)
+}
+
+/// Returns offset of the first page of column chunk `column`.
+fn column_chunk_start(column: &ColumnChunkMetaData) -> i64 {
+  column.dictionary_page_offset().unwrap_or(column.data_page_offset())
+}
+
+/// Returns offset index of data pages in `pages` with page offsets moved by `shift`,
+/// or `None` if first row of any data page is not known.
+fn offset_index_from_pages(pages: &[PageMetadata], shift: i64) -> Option<OffsetIndex> {
+  let mut page_locations = Vec::new();
+  for page in pages.iter().filter(|page| page.is_data_page()) {
+    let location = page.page_location()?;
+    page_locations.push(PageLocation::new(
+      location.offset() + shift,
+      location.compressed_page_size(),
+      location.first_row_index()
+    ));
+  }
+  Some(OffsetIndex::new(page_locations))
+}
+
+/// Returns column index of data pages in `pages` from statistics in their headers, or
+/// `None` if any data page without statistics or min/max values has non-null values.
+/// Statistics with deprecated min/max fields are not used, since their sort order
+/// could differ from the column order.
+fn column_index_from_pages(
+  descr: &ColumnDescriptor,
+  pages: &[PageMetadata]
+) -> Option<ColumnIndex> {
+  let mut null_pages = Vec::new();
+  let mut page_statistics = Vec::new();
+  for page in pages.iter().filter(|page| page.is_data_page()) {
+    let stats = match page.statistics() {
+      Some(stats) if !stats.is_min_max_deprecated() => stats,
+      _ => return None
+    };
+    let is_null_page = stats.null_count() == page.num_values() as u64;
+    if !is_null_page && !stats.has_min_max_set() {
+      return None;
+    }
+    null_pages.push(is_null_page);
+    page_statistics.push(stats.clone());
+  }
+
+  let values: Vec<_> = page_statistics.iter().zip(null_pages.iter())
+    .filter(|&(_, is_null_page)| !is_null_page)
+    .map(|(stats, _)| (stats.min_bytes(), stats.max_bytes()))
+    .collect();
+  let boundary_order = if values.windows(2).all(|w| is_ordered(descr, w[0], w[1])) {
+    BoundaryOrder::ASCENDING
+  } else if values.windows(2).all(|w| is_ordered(descr, w[1], w[0])) {
+    BoundaryOrder::DESCENDING
+  } else {
+    BoundaryOrder::UNORDERED
+  };
+  ColumnIndex::new(boundary_order, null_pages, page_statistics).ok()
+}
+
+/// Returns `true` if min and max values `a` of a page are not greater than min and max
+/// values `b` of the next page.
+fn is_ordered(descr: &ColumnDescriptor, a: (&[u8], &[u8]), b: (&[u8], &[u8])) -> bool {
+  !compare_greater(descr, a.0, b.0) && !compare_greater(descr, a.1, b.1)
+}
+
+/// Returns copy of `tpe` without fields in `dropped`, or `None` if `tpe` itself
+/// is dropped or does not have any fields left. Sets flags in `found` for each dropped
+/// field that exists in `tpe`.
+fn drop_fields(
+  tpe: &TypePtr,
+  path: &str,
+  dropped: &[String],
+  found: &mut [bool]
+) -> Option<TypePtr> {
+  let mut is_dropped = false;
+  for (i, name) in dropped.iter().enumerate() {
+    if name == path {
+      found[i] = true;
+      is_dropped = true;
+    }
+  }
+  if is_dropped {
+    return None;
+  }
+  if tpe.is_primitive() {
+    return Some(tpe.clone());
+  }
+
+  let num_fields = tpe.get_fields().len();
+  let mut fields = Vec::with_capacity(num_fields);
+  for field in tpe.get_fields() {
+    let field_path = if path.is_empty() {
+      field.name().to_owned()
+    } else {
+      format!("{}.{}", path, field.name())
+    };
+    if let Some(field) = drop_fields(field, &field_path, dropped, found) {
+      fields.push(field);
+    }
+  }
+  if fields.len() == num_fields {
+    return Some(tpe.clone());
+  }
+  // Root message type is kept even without fields, so that error can be reported
+  if fields.is_empty() && !path.is_empty() {
+    return None;
+  }
+
+  let info = tpe.get_basic_info();
+  let mut builder = Type::group_type_builder(tpe.name())
+    .with_logical_type(info.logical_type())
+    .with_fields(&mut fields);
+  if info.has_repetition() {
+    builder = builder.with_repetition(info.repetition());
+  }
+  if info.has_id() {
+    builder = builder.with_id(info.id());
+  }
+  Some(Rc::new(builder.build().expect("Group type is valid")))
+}
+
+// ----------------------------------------------------------------------
+// Copying of column values
+
+/// Column copier for each physical type.
+enum ColumnCopier {
+  Bool(TypedColumnCopier<BoolType>),
+  Int32(TypedColumnCopier<Int32Type>),
+  Int64(TypedColumnCopier<Int64Type>),
+  Int96(TypedColumnCopier<Int96Type>),
+  Float(TypedColumnCopier<FloatType>),
+  Double(TypedColumnCopier<DoubleType>),
+  ByteArray(TypedColumnCopier<ByteArrayType>),
+  FixedLenByteArray(TypedColumnCopier<FixedLenByteArrayType>)
+}
+
+impl ColumnCopier {
+  fn new(descr: ColumnDescPtr, column_index: usize) -> Self {
+    match descr.physical_type() {
+      PhysicalType::BOOLEAN => {
+        ColumnCopier::Bool(TypedColumnCopier::new(descr, column_index))
+      },
+      PhysicalType::INT32 => {
+        ColumnCopier::Int32(TypedColumnCopier::new(descr, column_index))
+      },
+      PhysicalType::INT64 => {
+        ColumnCopier::Int64(TypedColumnCopier::new(descr, column_index))
+      },
+      PhysicalType::INT96 => {
+        ColumnCopier::Int96(TypedColumnCopier::new(descr, column_index))
+      },
+      PhysicalType::FLOAT => {
+        ColumnCopier::Float(TypedColumnCopier::new(descr, column_index))
+      },
+      PhysicalType::DOUBLE => {
+        ColumnCopier::Double(TypedColumnCopier::new(descr, column_index))
+      },
+      PhysicalType::BYTE_ARRAY => {
+        ColumnCopier::ByteArray(TypedColumnCopier::new(descr, column_index))
+      },
+      PhysicalType::FIXED_LEN_BYTE_ARRAY => {
+        ColumnCopier::FixedLenByteArray(TypedColumnCopier::new(descr, column_index))
+      }
+    }
+  }
+
+  /// Returns index of the column in the original file.
+  fn column_index(&self) -> usize {
+    match *self {
+      ColumnCopier::Bool(ref copier) => copier.column_index,
+      ColumnCopier::Int32(ref copier) => copier.column_index,
+      ColumnCopier::Int64(ref copier) => copier.column_index,
+      ColumnCopier::Int96(ref copier) => copier.column_index,
+      ColumnCopier::Float(ref copier) => copier.column_index,
+      ColumnCopier::Double(ref copier) => copier.column_index,
+      ColumnCopier::ByteArray(ref copier) => copier.column_index,
+      ColumnCopier::FixedLenByteArray(ref copier) => copier.column_index
+    }
+  }
+
+  /// Sets mask of the column, returns error if the column cannot be masked with it.
+  fn set_mask(&mut self, mask: ColumnMask) -> Result<()> {
+    match *self {
+      ColumnCopier::Bool(ref mut copier) => copier.set_mask(mask),
+      ColumnCopier::Int32(ref mut copier) => copier.set_mask(mask),
+      ColumnCopier::Int64(ref mut copier) => copier.set_mask(mask),
+      ColumnCopier::Int96(ref mut copier) => copier.set_mask(mask),
+      ColumnCopier::Float(ref mut copier) => copier.set_mask(mask),
+      ColumnCopier::Double(ref mut copier) => copier.set_mask(mask),
+      ColumnCopier::ByteArray(ref mut copier) => copier.set_mask(mask),
+      ColumnCopier::FixedLenByteArray(ref mut copier) => copier.set_mask(mask)
+    }
+  }
+
+  /// Returns `true` if values of the column are masked.
+  fn is_masked(&self) -> bool {
+    match *self {
+      ColumnCopier::Bool(ref copier) => copier.mask.is_some(),
+      ColumnCopier::Int32(ref copier) => copier.mask.is_some(),
+      ColumnCopier::Int64(ref copier) => copier.mask.is_some(),
+      ColumnCopier::Int96(ref copier) => copier.mask.is_some(),
+      ColumnCopier::Float(ref copier) => copier.mask.is_some(),
+      ColumnCopier::Double(ref copier) => copier.mask.is_some(),
+      ColumnCopier::ByteArray(ref copier) => copier.mask.is_some(),
+      ColumnCopier::FixedLenByteArray(ref copier) => copier.mask.is_some()
+    }
+  }
+
+  /// Sets row group of the original file to copy rows from next, buffered values of
+  /// the current row group are discarded.
+  fn set_next_row_group(&mut self, i: usize) {
+    match *self {
+      ColumnCopier::Bool(ref mut copier) => copier.set_next_row_group(i),
+      ColumnCopier::Int32(ref mut copier) => copier.set_next_row_group(i),
+      ColumnCopier::Int64(ref mut copier) => copier.set_next_row_group(i),
+      ColumnCopier::Int96(ref mut copier) => copier.set_next_row_group(i),
+      ColumnCopier::Float(ref mut copier) => copier.set_next_row_group(i),
+      ColumnCopier::Double(ref mut copier) => copier.set_next_row_group(i),
+      ColumnCopier::ByteArray(ref mut copier) => copier.set_next_row_group(i),
+      ColumnCopier::FixedLenByteArray(ref mut copier) => copier.set_next_row_group(i)
+    }
+  }
+
+  /// Copies next `num_rows` rows of the column into `writer`.
+  fn copy_rows(
+    &mut self,
+    reader: &FileReader,
+    writer: &mut ColumnWriter,
+    num_rows: usize
+  ) -> Result<()> {
+    macro_rules! copy_by_type {
+      ($($copier_variant:ident => $writer_variant:ident),*) => {
+        match (self, writer) {
+          $(
+            (
+              &mut ColumnCopier::$copier_variant(ref mut copier),
+              &mut ColumnWriter::$writer_variant(ref mut typed_writer)
+            ) => copier.copy_rows(reader, typed_writer, num_rows),
+          )*
+          _ => Err(general_err!("Column copier and column writer types do not match"))
+        }
+      };
+    }
+
+    copy_by_type!(
+      Bool => BoolColumnWriter,
+      Int32 => Int32ColumnWriter,
+      Int64 => Int64ColumnWriter,
+      Int96 => Int96ColumnWriter,
+      Float => FloatColumnWriter,
+      Double => DoubleColumnWriter,
+      ByteArray => ByteArrayColumnWriter,
+      FixedLenByteArray => FixedLenByteArrayColumnWriter
+    )
+  }
+}
+
+/// Copies values and levels of a column across row groups of the original file.
+///
+/// Levels and values are read in batches and buffered, so that the new row groups can
+/// start and end at any row, including rows of repeated columns spanning batches.
+struct TypedColumnCopier<T: DataType> {
+  descr: ColumnDescPtr,
+  column_index: usize,
+  mask: Option<ColumnMask>,
+  // Index of the next row group of the original file to read
+  next_row_group: usize,
+  // Reader of the current row group, `None` if row group is depleted
+  reader: Option<ColumnReaderImpl<T>>,
+  values: Vec<T::T>,
+  def_levels: Vec<i16>,
+  rep_levels: Vec<i16>,
+  // Number of buffered levels and values, and position of the next ones to copy
+  num_levels: usize,
+  num_values: usize,
+  level_pos: usize,
+  value_pos: usize
+}
+
+impl<T: DataType> TypedColumnCopier<T> where T::T: HashValue {
+  fn new(descr: ColumnDescPtr, column_index: usize) -> Self {
+    Self {
+      descr: descr,
+      column_index: column_index,
+      mask: None,
+      next_row_group: 0,
+      reader: None,
+      values: vec![T::T::default(); COPY_BATCH_SIZE],
+      def_levels: vec![0; COPY_BATCH_SIZE],
+      rep_levels: vec![0; COPY_BATCH_SIZE],
+      num_levels: 0,
+      num_values: 0,
+      level_pos: 0,
+      value_pos: 0
+    }
+  }
+
+  fn set_mask(&mut self, mask: ColumnMask) -> Result<()> {
+    match mask {
+      ColumnMask::Null => {
+        if self.descr.self_type().get_basic_info().repetition() != Repetition::OPTIONAL {
+          return Err(general_err!(
+            "Cannot mask column {} with nulls, column is not optional",
+            self.descr.path().string()
+          ));
+        }
+      },
+      ColumnMask::Hash(_) => match self.descr.physical_type() {
+        PhysicalType::BYTE_ARRAY | PhysicalType::FIXED_LEN_BYTE_ARRAY => {},
+        other => {
+          return Err(general_err!(
+            "Cannot mask column {} of type {} with hashes",
+            self.descr.path().string(),
+            other
+          ));
+        }
+      }
+    }
+    self.mask = Some(mask);
+    Ok(())
+  }
+
+  fn set_next_row_group(&mut self, i: usize) {
+    self.next_row_group = i;
+    self.reader = None;
+    self.num_levels = 0;
+    self.num_values = 0;
+    self.level_pos = 0;
+    self.value_pos = 0;
+  }
+
+  fn copy_rows(
+    &mut self,
+    reader: &FileReader,
+    writer: &mut ColumnWriterImpl<T>,
+    num_rows: usize
+  ) -> Result<()> {
+    let max_def_level = self.descr.max_def_level();
+    let max_rep_level = self.descr.max_rep_level();
+    let mut rows_left = num_rows;
+    loop {
+      if self.level_pos == self.num_levels {
+        // Levels of a repeated column that continue the last row can follow in the
+        // next batch of the current row group
+        if rows_left == 0 && max_rep_level == 0 {
+          break;
+        }
+        if !self.read_batch(reader, rows_left > 0)? {
+          if rows_left > 0 {
+            return Err(general_err!(
+              "Column {} has {} rows less than expected",
+              self.descr.path(),
+              rows_left
+            ));
+          }
+          break;
+        }
+        continue;
+      }
+
+      let start = self.level_pos;
+      let mut end = start;
+      if max_rep_level == 0 {
+        end += ::std::cmp::min(rows_left, self.num_levels - start);
+        rows_left -= end - start;
+      } else {
+        while end < self.num_levels {
+          if self.rep_levels[end] == 0 {
+            if rows_left == 0 {
+              break;
+            }
+            rows_left -= 1;
+          }
+          end += 1;
+        }
+      }
+      if end == start {
+        break;
+      }
+
+      let num_values = if max_def_level > 0 {
+        self.def_levels[start..end].iter()
+          .filter(|level| **level == max_def_level)
+          .count()
+      } else {
+        end - start
+      };
+      let def_levels = if max_def_level > 0 {
+        Some(&self.def_levels[start..end])
+      } else {
+        None
+      };
+      let rep_levels = if max_rep_level > 0 {
+        Some(&self.rep_levels[start..end])
+      } else {
+        None
+      };
+      let values = &self.values[self.value_pos..self.value_pos + num_values];
+      match self.mask {
+        None => {
+          writer.write_batch(values, def_levels, rep_levels)?;
+        },
+        Some(ColumnMask::Null) => {
+          // Optional leaf column always has definition levels
+          let null_levels: Vec<i16> = self.def_levels[start..end].iter()
+            .map(|level| ::std::cmp::min(*level, max_def_level - 1))
+            .collect();
+          writer.write_batch(&[], Some(&null_levels), rep_levels)?;
+        },
+        Some(ColumnMask::Hash(ref hash)) => {
+          let mut hashed_values = Vec::with_capacity(values.len());
+          for value in values {
+            hashed_values.push(value.hash_value(&self.descr, &**hash)?);
+          }
+          writer.write_batch(&hashed_values, def_levels, rep_levels)?;
+        }
+      }
+      self.level_pos = end;
+      self.value_pos += num_values;
+    }
+    Ok(())
+  }
+
+  /// Reads next batch of levels and values. Opens reader of the next row group when
+  /// the current one is depleted, if `next_row_group` is `true`.
+  /// Returns `false` if there is nothing left to read.
+  fn read_batch(&mut self, reader: &FileReader, next_row_group: bool) -> Result<bool> {
+    loop {
+      if self.reader.is_none() {
+        if !next_row_group || self.next_row_group >= reader.num_row_groups() {
+          return Ok(false);
+        }
+        let row_group_reader = reader.get_row_group(self.next_row_group)?;
+        let column_reader = row_group_reader.get_column_reader(self.column_index)?;
+        self.reader = Some(get_typed_column_reader(column_reader));
+        self.next_row_group += 1;
+      }
+
+      let (values_read, levels_read) = self.reader.as_mut().unwrap().read_batch(
+        COPY_BATCH_SIZE,
+        Some(&mut self.def_levels[..]),
+        Some(&mut self.rep_levels[..]),
+        &mut self.values[..]
+      )?;
+      if values_read == 0 && levels_read == 0 {
+        self.reader = None;
+        continue;
+      }
+      // Required columns do not have levels, each value is a separate level
+      self.num_levels = if self.descr.max_def_level() > 0 { levels_read } else {
+        values_read
+      };
+      self.num_values = values_read;
+      self.level_pos = 0;
+      self.value_pos = 0;
+      return Ok(true);
+    }
+  }
+}
+
+/// Values that can be replaced with hashes when masking columns.
+trait HashValue: Sized {
+  /// Returns hash of the value of column `descr` computed by `hash`.
+  fn hash_value(
+    &self,
+    descr: &ColumnDescPtr,
+    hash: &Fn(&[u8]) -> Vec<u8>
+  ) -> Result<Self>;
+}
+
+macro_rules! impl_hash_value_unsupported {
+  ($($ty:ty),*) => {
+    $(
+      impl HashValue for $ty {
+        fn hash_value(
+          &self,
+          descr: &ColumnDescPtr,
+          _hash: &Fn(&[u8]) -> Vec<u8>
+        ) -> Result<Self> {
+          Err(general_err!("Cannot hash values of column {}", descr.path().string()))
+        }
+      }
+    )*
+  };
+}
+
+impl_hash_value_unsupported!(bool, i32, i64, Int96, f32, f64);
+
+impl HashValue for ByteArray {
+  fn hash_value(
+    &self,
+    descr: &ColumnDescPtr,
+    hash: &Fn(&[u8]) -> Vec<u8>
+  ) -> Result<Self> {
+    let value = hash(self.data());
+    if descr.physical_type() == PhysicalType::FIXED_LEN_BYTE_ARRAY &&
+        value.len() != descr.type_length() as usize {
+      return Err(general_err!(
+        "Hash of column {} has length {}, expected {}",
+        descr.path().string(),
+        value.len(),
+        descr.type_length()
+      ));
+    }
+    Ok(ByteArray::from(value))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::fs::File;
+
+  use basic::{ColumnOrder, Compression, Encoding, SortOrder};
+  use column::writer::get_typed_column_writer;
+  use file::metadata::{FileMetaData, KeyValue, SortingColumn};
+  use file::page_index::{read_column_index, read_offset_index};
+  use file::properties::{EnabledStatistics, WriterProperties};
+  use file::writer::write_to_bytes;
+  use record::{Row, RowAccessor};
+  use schema::parser::parse_message_type;
+  use schema::types::ColumnPath;
+  use thrift::protocol::TType;
+  use util::test_common::{get_temp_file, get_temp_path, get_test_file};
+  use util::thrift::UnknownEnums;
+
+  /// Rewrites `input` into temp file `output_name` with `options` and returns reader of
+  /// the new file.
+  fn rewrite(
+    input: File,
+    output_name: &str,
+    options: &RewriteOptions
+  ) -> SerializedFileReader<File> {
+    let output = get_temp_file(output_name, &[]);
+    rewrite_file(input, output, options).unwrap();
+    SerializedFileReader::new(File::open(get_temp_path(output_name)).unwrap()).unwrap()
+  }
+
+  fn read_rows(reader: &FileReader) -> Vec<Row> {
+    reader.get_row_iter(None).unwrap().map(|row| row.unwrap()).collect()
+  }
+
+  fn default_options() -> RewriteOptions {
+    RewriteOptions::new(Rc::new(WriterProperties::builder().build()))
+  }
+
+  #[test]
+  fn test_rewrite_file_compression_and_encoding() {
+    let props = WriterProperties::builder()
+      .set_compression(Compression::GZIP)
+      .set_dictionary_enabled(false)
+      .set_encoding(Encoding::PLAIN)
+      .build();
+    let options = RewriteOptions::new(Rc::new(props));
+    let input = get_test_file("alltypes_plain.snappy.parquet");
+    let reader = rewrite(input, "rewrite_compression", &options);
+
+    let original =
+      SerializedFileReader::new(get_test_file("alltypes_plain.snappy.parquet")).unwrap();
+    assert_eq!(read_rows(&reader), read_rows(&original));
+    let row_group = reader.metadata().row_group(0);
+    for column in row_group.columns() {
+      assert_eq!(column.compression(), Compression::GZIP);
+      assert!(!column.encodings().contains(&Encoding::PLAIN_DICTIONARY));
+    }
+  }
+
+  #[test]
+  fn test_rewrite_file_row_group_size() {
+    for file_name in &["nested_lists.snappy.parquet", "nested_maps.snappy.parquet"] {
+      let original = SerializedFileReader::new(get_test_file(file_name)).unwrap();
+      let rows = read_rows(&original);
+      for row_group_size in &[1, 2, 5] {
+        let options = default_options().with_row_group_size(*row_group_size);
+        let reader = rewrite(get_test_file(file_name), "rewrite_split", &options);
+        let num_row_groups = (rows.len() + row_group_size - 1) / row_group_size;
+        assert_eq!(reader.num_row_groups(), num_row_groups);
+        assert_eq!(read_rows(&reader), rows);
+      }
+    }
+  }
+
+  #[test]
+  fn test_rewrite_file_merge_row_groups() {
+    let options = default_options().with_row_group_size(3);
+    let input = get_test_file("alltypes_plain.parquet");
+    let reader = rewrite(input, "rewrite_merge_split", &options);
+    assert_eq!(reader.num_row_groups(), 3);
+
+    let options = default_options().with_row_group_size(100);
+    let input = File::open(get_temp_path("rewrite_merge_split")).unwrap();
+    let reader = rewrite(input, "rewrite_merge", &options);
+    assert_eq!(reader.num_row_groups(), 1);
+    let original = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap();
+    assert_eq!(read_rows(&reader), read_rows(&original));
+  }
+
+  #[test]
+  fn test_rewrite_file_row_group_byte_size() {
+    let original = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap();
+    let row_group = original.metadata().row_group(0);
+    let row_group_bytes: i64 =
+      row_group.columns().iter().map(|column| column.uncompressed_size()).sum();
+
+    // Splits row group of 8 rows into row groups of 3 rows
+    let row_group_byte_size = row_group_bytes as usize * 3 / 8 + 1;
+    let options = default_options().with_row_group_byte_size(row_group_byte_size);
+    let input = get_test_file("alltypes_plain.parquet");
+    let reader = rewrite(input, "rewrite_byte_size_split", &options);
+    let num_rows: Vec<_> = reader.metadata().row_groups().iter()
+      .map(|row_group| row_group.num_rows())
+      .collect();
+    assert_eq!(num_rows, vec![3, 3, 2]);
+    assert_eq!(read_rows(&reader), read_rows(&original));
+
+    // Row groups have at least one row
+    let options = default_options().with_row_group_byte_size(1);
+    let input = get_test_file("alltypes_plain.parquet");
+    let reader = rewrite(input, "rewrite_byte_size_rows", &options);
+    assert_eq!(reader.num_row_groups(), 8);
+
+    // Row group size limits rows of large row groups
+    let options = default_options()
+      .with_row_group_byte_size(1024 * 1024)
+      .with_row_group_size(5);
+    let input = File::open(get_temp_path("rewrite_byte_size_rows")).unwrap();
+    let reader = rewrite(input, "rewrite_byte_size_limits", &options);
+    assert_eq!(reader.num_row_groups(), 2);
+
+    // Merges small row groups
+    let options = default_options().with_row_group_byte_size(1024 * 1024);
+    let input = File::open(get_temp_path("rewrite_byte_size_rows")).unwrap();
+    let reader = rewrite(input, "rewrite_byte_size_merge", &options);
+    assert_eq!(reader.num_row_groups(), 1);
+    assert_eq!(read_rows(&reader), read_rows(&original));
+  }
+
+  #[test]
+  fn test_rewrite_file_large_records() {
+    // Records of a repeated column span multiple batches of levels
+    let schema = Rc::new(parse_message_type("
+      message schema {
+        REQUIRED INT32 a;
+        OPTIONAL group b (LIST) {
+          REPEATED group list {
+            OPTIONAL INT32 element;
+          }
+        }
+      }
+    ").unwrap());
+    let props = Rc::new(WriterProperties::builder().build());
+    let bytes = write_to_bytes(schema, props, |writer| {
+      for row_group in 0..2 {
+        let rows: Vec<i32> = (0..5).map(|i| row_group * 5 + i).collect();
+        let mut row_group_writer = writer.next_row_group()?;
+
+        let column_writer = row_group_writer.next_column()?.unwrap();
+        let mut typed = get_typed_column_writer::<Int32Type>(column_writer);
+        typed.write_batch(&rows, None, None)?;
+        row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed))?;
+
+        // Row `i` has list of `(i % 4) * 700` elements, every third element is null
+        let (mut values, mut def_levels, mut rep_levels) = (vec![], vec![], vec![]);
+        for row in &rows {
+          let num_elements = (row % 4) * 700;
+          if num_elements == 0 {
+            def_levels.push(1);
+            rep_levels.push(0);
+          }
+          for i in 0..num_elements {
+            rep_levels.push(if i == 0 { 0 } else { 1 });
+            if i % 3 == 0 {
+              def_levels.push(2);
+            } else {
+              def_levels.push(3);
+              values.push(i);
+            }
+          }
+        }
+        let column_writer = row_group_writer.next_column()?.unwrap();
+        let mut typed = get_typed_column_writer::<Int32Type>(column_writer);
+        typed.write_batch(&values, Some(&def_levels), Some(&rep_levels))?;
+        row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed))?;
+        writer.close_row_group(row_group_writer)?;
+      }
+      Ok(())
+    }).unwrap();
+    get_temp_file("rewrite_large_records_input", &bytes);
+    let path = get_temp_path("rewrite_large_records_input");
+    let rows = read_rows(&SerializedFileReader::new(File::open(&path).unwrap()).unwrap());
+    assert_eq!(rows.len(), 10);
+
+    for row_group_size in &[1, 3, 7] {
+      let options = default_options().with_row_group_size(*row_group_size);
+      let reader = rewrite(File::open(&path).unwrap(), "rewrite_large_records", &options);
+      assert_eq!(read_rows(&reader), rows);
+    }
+  }
+
+  #[test]
+  fn test_rewrite_file_copy_column_chunks() {
+    // Columns with the same codec are copied, `id` is re-encoded with a different codec
+    let props = WriterProperties::builder()
+      .set_compression(Compression::SNAPPY)
+      .set_column_compression(ColumnPath::from("id"), Compression::GZIP)
+      .build();
+    let options = RewriteOptions::new(Rc::new(props)).with_copy_column_chunks(true);
+    let input = get_test_file("alltypes_plain.snappy.parquet");
+    let reader = rewrite(input, "rewrite_copy_column_chunks", &options);
+
+    let original =
+      SerializedFileReader::new(get_test_file("alltypes_plain.snappy.parquet")).unwrap();
+    assert_eq!(read_rows(&reader), read_rows(&original));
+    let row_group = reader.metadata().row_group(0);
+    let original_row_group = original.metadata().row_group(0);
+    assert_eq!(row_group.column(0).compression(), Compression::GZIP);
+    for i in 1..row_group.num_columns() {
+      let column = row_group.column(i);
+      let original_column = original_row_group.column(i);
+      assert_eq!(column.compression(), Compression::SNAPPY);
+      assert_eq!(column.encodings(), original_column.encodings());
+      assert_eq!(column.compressed_size(), original_column.compressed_size());
+      assert_eq!(
+        reader.get_row_group(0).unwrap().get_column_chunk_bytes(i).unwrap(),
+        original.get_row_group(0).unwrap().get_column_chunk_bytes(i).unwrap()
+      );
+    }
+  }
+
+  #[test]
+  fn test_rewrite_file_dropped_columns() {
+    let options = default_options()
+      .with_dropped_columns(vec!["id".to_owned(), "string_col".to_owned()]);
+    let input = get_test_file("alltypes_plain.parquet");
+    let reader = rewrite(input, "rewrite_dropped", &options);
+    let schema = reader.metadata().file_metadata().schema_descr_ptr();
+    assert_eq!(schema.num_columns(), 9);
+    assert!(schema.columns().iter().all(|c| c.name() != "id"));
+    assert!(schema.columns().iter().all(|c| c.name() != "string_col"));
+    assert_eq!(reader.get_row_iter(None).unwrap().count(), 8);
+
+    // Groups without fields are dropped
+    let options = default_options()
+      .with_dropped_columns(vec!["a.list.element.list.element.list.element".to_owned()]);
+    let input = get_test_file("nested_lists.snappy.parquet");
+    let reader = rewrite(input, "rewrite_dropped_nested", &options);
+    let schema = reader.metadata().file_metadata().schema_descr_ptr();
+    assert_eq!(schema.root_schema().get_fields().len(), 1);
+    assert_eq!(schema.num_columns(), 1);
+    assert_eq!(schema.column(0).name(), "b");
+    assert_eq!(reader.get_row_iter(None).unwrap().count(), 3);
+  }
+
+  #[test]
+  fn test_rewrite_file_masked_columns() {
+    let reverse: HashFn = Rc::new(|value: &[u8]| value.iter().rev().cloned().collect());
+    let options = default_options()
+      .with_copy_column_chunks(true)
+      .with_masked_columns(vec![
+        ("string_col".to_owned(), ColumnMask::Null),
+        ("date_string_col".to_owned(), ColumnMask::Hash(reverse))
+      ]);
+    let input = get_test_file("alltypes_plain.parquet");
+    let reader = rewrite(input, "rewrite_masked", &options);
+
+    let original =
+      SerializedFileReader::new(get_test_file("alltypes_plain.parquet")).unwrap();
+    let rows = read_rows(&reader);
+    let original_rows = read_rows(&original);
+    assert_eq!(rows.len(), original_rows.len());
+    for (row, original_row) in rows.iter().zip(original_rows.iter()) {
+      assert_eq!(row.get_int(0).unwrap(), original_row.get_int(0).unwrap());
+      let mut expected = original_row.get_bytes(8).unwrap().data().to_vec();
+      expected.reverse();
+      assert_eq!(row.get_bytes(8).unwrap().data(), &expected[..]);
+      assert!(row.get_bytes(9).is_err());
+      assert!(original_row.get_bytes(9).is_ok());
+    }
+    let row_group = reader.metadata().row_group(0);
+    assert_eq!(row_group.column(9).num_values(), 8);
+
+    // Levels of repeated parents are kept
+    let options = default_options().with_masked_columns(vec![
+      ("a.list.element.list.element.list.element".to_owned(), ColumnMask::Null)
+    ]);
+    let input = get_test_file("nested_lists.snappy.parquet");
+    let reader = rewrite(input, "rewrite_masked_nested", &options);
+    let rows: Vec<String> =
+      read_rows(&reader).iter().map(|row| row.to_string()).collect();
+    assert_eq!(
+      rows,
+      vec![
+        "{a: [[[null, null], [null]], [null, [null]]], b: 1}",
+        "{a: [[[null, null], [null, null]], [null, [null]]], b: 1}",
+        "{a: [[[null, null], [null, null], [null]], [null, [null]]], b: 1}"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_rewrite_file_masked_columns_invalid() {
+    let check_error = |file_name: &str, column: &str, mask: ColumnMask, message: &str| {
+      let output = get_temp_file("rewrite_masked_invalid", &[]);
+      let options = default_options()
+        .with_dropped_columns(vec!["bool_col".to_owned()])
+        .with_masked_columns(vec![(column.to_owned(), mask)]);
+      assert_eq!(
+        rewrite_file(get_test_file(file_name), output, &options).err().unwrap(),
+        general_err!(message)
+      );
+    };
+    let hash: HashFn = Rc::new(|value: &[u8]| value.to_vec());
+    check_error(
+      "alltypes_plain.parquet",
+      "missing",
+      ColumnMask::Null,
+      "Column missing is not found in file schema"
+    );
+    check_error(
+      "alltypes_plain.parquet",
+      "bool_col",
+      ColumnMask::Null,
+      "Column bool_col is not found in file schema"
+    );
+    check_error(
+      "alltypes_plain.parquet",
+      "id",
+      ColumnMask::Hash(hash),
+      "Cannot mask column id of type INT32 with hashes"
+    );
+
+    let output = get_temp_file("rewrite_masked_invalid", &[]);
+    let options = default_options()
+      .with_masked_columns(vec![("b".to_owned(), ColumnMask::Null)]);
+    assert_eq!(
+      rewrite_file(get_test_file("nested_lists.snappy.parquet"), output, &options)
+        .err().unwrap(),
+      general_err!("Cannot mask column b with nulls, column is not optional")
+    );
+  }
+
+  #[test]
+  fn test_rewrite_file_added_columns() {
+    let added = |name: &str, physical_type: PhysicalType, repetition: Repetition| {
+      let field = Type::primitive_type_builder(name, physical_type)
+        .with_repetition(repetition)
+        .build()
+        .unwrap();
+      Rc::new(field)
+    };
+    let options = default_options()
+      .with_row_group_size(3)
+      .with_dropped_columns(vec!["string_col".to_owned()])
+      .with_added_columns(vec![
+        (
+          added("version", PhysicalType::INT32, Repetition::REQUIRED),
+          ColumnValue::Int32(2)
+        ),
+        (
+          added("note", PhysicalType::BYTE_ARRAY, Repetition::OPTIONAL),
+          ColumnValue::Null
+        ),
+        (
+          added("string_col", PhysicalType::BYTE_ARRAY, Repetition::OPTIONAL),
+          ColumnValue::ByteArray(ByteArray::from("x"))
+        )
+      ]);
+    let input = get_test_file("alltypes_plain.parquet");
+    let reader = rewrite(input, "rewrite_added", &options);
+    let schema = reader.metadata().file_metadata().schema_descr_ptr();
+    assert_eq!(schema.num_columns(), 13);
+    assert_eq!(schema.column(10).name(), "version");
+    assert_eq!(schema.column(10).max_def_level(), 0);
+    assert_eq!(schema.column(11).name(), "note");
+    assert_eq!(schema.column(12).name(), "string_col");
+    assert_eq!(reader.num_row_groups(), 3);
+
+    let original =
+      SerializedFileReader::new(get_test_file("alltypes_plain.parquet")).unwrap();
+    let rows = read_rows(&reader);
+    let original_rows = read_rows(&original);
+    assert_eq!(rows.len(), 8);
+    for (row, original_row) in rows.iter().zip(original_rows.iter()) {
+      assert_eq!(row.get_int(0).unwrap(), original_row.get_int(0).unwrap());
+      assert_eq!(row.get_int(10).unwrap(), 2);
+      assert!(row.get_bytes(11).is_err());
+      assert_eq!(row.get_bytes(12).unwrap().data(), b"x");
+    }
+  }
+
+  #[test]
+  fn test_rewrite_file_added_columns_invalid() {
+    let check_error = |field: Type, value: ColumnValue, message: &str| {
+      let output = get_temp_file("rewrite_added_invalid", &[]);
+      let options = default_options().with_added_columns(vec![(Rc::new(field), value)]);
+      assert_eq!(
+        rewrite_file(get_test_file("alltypes_plain.parquet"), output, &options)
+          .err().unwrap(),
+        general_err!(message)
+      );
+    };
+    check_error(
+      Type::primitive_type_builder("id", PhysicalType::INT32).build().unwrap(),
+      ColumnValue::Int32(1),
+      "Column id already exists in file schema"
+    );
+    check_error(
+      Type::primitive_type_builder("a", PhysicalType::INT32)
+        .with_repetition(Repetition::REPEATED)
+        .build()
+        .unwrap(),
+      ColumnValue::Int32(1),
+      "Cannot add column a, column must be primitive and not repeated"
+    );
+    check_error(
+      Type::primitive_type_builder("a", PhysicalType::INT32)
+        .with_repetition(Repetition::REQUIRED)
+        .build()
+        .unwrap(),
+      ColumnValue::Null,
+      "Cannot add column a with nulls, column is not optional"
+    );
+    check_error(
+      Type::primitive_type_builder("a", PhysicalType::INT64).build().unwrap(),
+      ColumnValue::Int32(1),
+      "Cannot add column a of type INT64 with value of type INT32"
+    );
+    check_error(
+      Type::primitive_type_builder("a", PhysicalType::FIXED_LEN_BYTE_ARRAY)
+        .with_length(4)
+        .build()
+        .unwrap(),
+      ColumnValue::FixedLenByteArray(ByteArray::from(vec![1, 2])),
+      "Cannot add column a of length 4 with value of length 2"
+    );
+  }
+
+  #[test]
+  fn test_rewrite_file_invalid_options() {
+    let output = get_temp_file("rewrite_invalid", &[]);
+    let options = default_options().with_dropped_columns(vec!["missing".to_owned()]);
+    assert_eq!(
+      rewrite_file(get_test_file("alltypes_plain.parquet"), output, &options)
+        .err().unwrap(),
+      general_err!("Column missing is not found in file schema")
+    );
+
+    let output = get_temp_file("rewrite_invalid", &[]);
+    let options = default_options()
+      .with_dropped_columns(vec!["a".to_owned(), "b".to_owned()]);
+    assert_eq!(
+      rewrite_file(get_test_file("nested_lists.snappy.parquet"), output, &options)
+        .err().unwrap(),
+      general_err!("Cannot drop all columns of a file")
+    );
+
+    let output = get_temp_file("rewrite_invalid", &[]);
+    let options = default_options().with_row_group_size(0);
+    assert_eq!(
+      rewrite_file(get_test_file("alltypes_plain.parquet"), output, &options)
+        .err().unwrap(),
+      general_err!("Row group size must be positive")
+    );
+
+    let output = get_temp_file("rewrite_invalid", &[]);
+    let options = default_options().with_row_group_byte_size(0);
+    assert_eq!(
+      rewrite_file(get_test_file("alltypes_plain.parquet"), output, &options)
+        .err().unwrap(),
+      general_err!("Row group byte size must be positive")
+    );
+  }
+
+  #[test]
+  fn test_rewrite_file_footer_fields() {
+    let schema = Rc::new(parse_message_type("
+      message schema {
+        REQUIRED INT32 a;
+        REQUIRED BYTE_ARRAY b;
+      }
+    ").unwrap());
+    // Metadata of a file, whose footer fields are written to the input file
+    let mut unknown_fields = UnknownEnums::default();
+    unknown_fields.insert_field(vec![9], TType::I32, vec![4]);
+    let source = FileMetaData::new(
+      1,
+      0,
+      None,
+      schema.clone(),
+      Rc::new(SchemaDescriptor::new(schema.clone())),
+      Some(vec![
+        ColumnOrder::TYPE_DEFINED_ORDER(SortOrder::SIGNED),
+        ColumnOrder::TYPE_DEFINED_ORDER(SortOrder::UNSIGNED)
+      ])
+    ).with_unknown_fields(unknown_fields);
+    let props = WriterProperties::builder()
+      .set_key_value_metadata(Some(vec![KeyValue::new("a".to_owned(), "1".to_owned())]))
+      .build();
+    let bytes = write_to_bytes(schema, Rc::new(props), |writer| {
+      writer.copy_footer_fields(&source);
+      let mut row_group_writer = writer.next_row_group()?;
+      let column_writer = row_group_writer.next_column()?.unwrap();
+      let mut typed = get_typed_column_writer::<Int32Type>(column_writer);
+      typed.write_batch(&[1, 2], None, None)?;
+      row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed))?;
+      let column_writer = row_group_writer.next_column()?.unwrap();
+      let mut typed = get_typed_column_writer::<ByteArrayType>(column_writer);
+      typed.write_batch(&[ByteArray::from("a"), ByteArray::from("b")], None, None)?;
+      row_group_writer.close_column(ColumnWriter::ByteArrayColumnWriter(typed))?;
+      writer.close_row_group(row_group_writer)
+    }).unwrap();
+    let input = get_temp_file("rewrite_footer_fields_input", &bytes);
+
+    let props = WriterProperties::builder()
+      .set_key_value_metadata(Some(vec![KeyValue::new("b".to_owned(), "2".to_owned())]))
+      .build();
+    let options = RewriteOptions::new(Rc::new(props))
+      .with_dropped_columns(vec!["a".to_owned()]);
+    let reader = rewrite(input, "rewrite_footer_fields", &options);
+    let file_metadata = reader.metadata().file_metadata();
+    assert_eq!(
+      file_metadata.key_value_metadata(),
+      Some(&vec![
+        KeyValue::new("b".to_owned(), "2".to_owned()),
+        KeyValue::new("a".to_owned(), "1".to_owned())
+      ])
+    );
+    assert_eq!(
+      file_metadata.column_orders(),
+      Some(&vec![ColumnOrder::TYPE_DEFINED_ORDER(SortOrder::UNSIGNED)])
+    );
+    assert_eq!(file_metadata.unknown_fields().field(&[9]), Some((TType::I32, &[4][..])));
+  }
+
+  #[test]
+  fn test_append_row_groups() {
+    let file_names = [
+      "alltypes_plain.parquet",
+      "alltypes_plain.snappy.parquet",
+      "alltypes_dictionary.parquet"
+    ];
+    let readers: Vec<_> = file_names.iter()
+      .map(|file_name| SerializedFileReader::new(get_test_file(file_name)).unwrap())
+      .collect();
+    let schema = readers[0].metadata().file_metadata().schema_descr().root_schema_ptr();
+    let props = Rc::new(WriterProperties::builder().build());
+    let output = get_temp_file("append_row_groups", &[]);
+    let mut writer = SerializedFileWriter::new(output, schema, props).unwrap();
+    let mut num_rows = 0;
+    for reader in &readers {
+      num_rows += append_row_groups(&mut writer, reader).unwrap();
+    }
+    writer.close().unwrap();
+    assert_eq!(num_rows, 12);
+
+    let reader =
+      SerializedFileReader::new(File::open(get_temp_path("append_row_groups")).unwrap())
+        .unwrap();
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 12);
+    assert_eq!(reader.num_row_groups(), 3);
+    let mut expected = Vec::new();
+    for (i, original) in readers.iter().enumerate() {
+      expected.extend(read_rows(original));
+      let row_group = reader.metadata().row_group(i);
+      let original_row_group = original.metadata().row_group(0);
+      assert_eq!(row_group.num_rows(), original_row_group.num_rows());
+      for j in 0..row_group.num_columns() {
+        assert_eq!(
+          row_group.column(j).compression(),
+          original_row_group.column(j).compression()
+        );
+      }
+    }
+    assert_eq!(read_rows(&reader), expected);
+  }
+
+  #[test]
+  fn test_backfill_page_indexes() {
+    // File without page statistics gets offset indexes only
+    let props = Rc::new(WriterProperties::builder().build());
+    let output = get_temp_file("backfill_page_indexes", &[]);
+    let input = get_test_file("alltypes_plain.parquet");
+    assert_eq!(backfill_page_indexes(input, output, props).unwrap(), 11);
+
+    let mut file = File::open(get_temp_path("backfill_page_indexes")).unwrap();
+    let reader = SerializedFileReader::new(file.try_clone().unwrap()).unwrap();
+    let original = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap();
+    assert_eq!(read_rows(&reader), read_rows(&original));
+    for column in reader.metadata().row_group(0).columns() {
+      let offset_index = read_offset_index(&mut file, column).unwrap().unwrap();
+      assert_eq!(offset_index.num_pages(), 1);
+      assert_eq!(offset_index.page_locations()[0].offset(), column.data_page_offset());
+      assert_eq!(offset_index.page_locations()[0].first_row_index(), 0);
+      assert!(read_column_index(&mut file, column).unwrap().is_none());
+    }
+
+    // File with several pages and page statistics per column chunk
+    let props = WriterProperties::builder()
+      .set_dictionary_enabled(false)
+      .set_data_pagesize_limit(1)
+      .set_write_batch_size(3)
+      .set_statistics_enabled(EnabledStatistics::PAGE)
+      .build();
+    let options = RewriteOptions::new(Rc::new(props));
+    let input = get_test_file("alltypes_plain.parquet");
+    let reader = rewrite(input, "backfill_page_indexes_input", &options);
+    let input = File::open(get_temp_path("backfill_page_indexes_input")).unwrap();
+    let output = get_temp_file("backfill_page_indexes_pages", &[]);
+    let props = Rc::new(WriterProperties::builder().build());
+    assert_eq!(backfill_page_indexes(input, output, props).unwrap(), 11);
+
+    let mut file = File::open(get_temp_path("backfill_page_indexes_pages")).unwrap();
+    let backfilled = SerializedFileReader::new(file.try_clone().unwrap()).unwrap();
+    assert_eq!(read_rows(&backfilled), read_rows(&reader));
+    let metadata = backfilled.metadata();
+    let row_group = metadata.row_group(0);
+    let column = row_group.column(0);
+    let offset_index = read_offset_index(&mut file, column).unwrap().unwrap();
+    assert_eq!(offset_index.num_pages(), 3);
+    let first_rows: Vec<_> = offset_index.page_locations().iter()
+      .map(|loc| loc.first_row_index())
+      .collect();
+    assert_eq!(first_rows, vec![0, 3, 6]);
+    let column_index = read_column_index(&mut file, column).unwrap().unwrap();
+    assert_eq!(column_index.num_pages(), 3);
+    assert_eq!(column_index.null_pages(), &[false, false, false]);
+  }
+
+  #[test]
+  fn test_merge_sorted_files() {
+    // Row `id` has list of `id % 3` elements equal to `id`
+    let write_sorted = |name: &str, ids: &[i32]| {
+      let schema = Rc::new(parse_message_type("
+        message schema {
+          REQUIRED INT32 id;
+          OPTIONAL group b (LIST) {
+            REPEATED group list {
+              REQUIRED INT32 element;
+            }
+          }
+        }
+      ").unwrap());
+      let props = WriterProperties::builder()
+        .set_sorting_columns(Some(vec![SortingColumn::new(0, false, false)]))
+        .build();
+      let bytes = write_to_bytes(schema, Rc::new(props), |writer| {
+        let mut row_group_writer = writer.next_row_group()?;
+        let column_writer = row_group_writer.next_column()?.unwrap();
+        let mut typed = get_typed_column_writer::<Int32Type>(column_writer);
+        typed.write_batch(ids, None, None)?;
+        row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed))?;
+
+        let (mut values, mut def_levels, mut rep_levels) = (vec![], vec![], vec![]);
+        for id in ids {
+          if id % 3 == 0 {
+            def_levels.push(1);
+            rep_levels.push(0);
+          }
+          for i in 0..id % 3 {
+            values.push(*id);
+            def_levels.push(2);
+            rep_levels.push(if i == 0 { 0 } else { 1 });
+          }
+        }
+        let column_writer = row_group_writer.next_column()?.unwrap();
+        let mut typed = get_typed_column_writer::<Int32Type>(column_writer);
+        typed.write_batch(&values, Some(&def_levels), Some(&rep_levels))?;
+        row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed))?;
+        writer.close_row_group(row_group_writer)
+      }).unwrap();
+      let reader = SerializedFileReader::new(get_temp_file(name, &bytes)).unwrap();
+      Rc::new(reader) as Rc<FileReader>
+    };
+    let readers = vec![
+      write_sorted("merge_sorted_a", &[1, 4, 5, 8]),
+      write_sorted("merge_sorted_b", &[2, 3, 4, 6, 7])
+    ];
+    let props = WriterProperties::builder()
+      .set_sorting_columns(Some(vec![SortingColumn::new(0, false, false)]))
+      .build();
+    let output = get_temp_file("merge_sorted", &[]);
+    assert_eq!(merge_sorted_files(readers, output, Rc::new(props), 4).unwrap(), 9);
+
+    let path = get_temp_path("merge_sorted");
+    let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+    let metadata = reader.metadata();
+    let sizes: Vec<i64> = metadata.row_groups().iter().map(|rg| rg.num_rows()).collect();
+    assert_eq!(sizes, vec![4, 4, 1]);
+    assert_eq!(
+      metadata.row_group(0).sorting_columns(),
+      Some(&vec![SortingColumn::new(0, false, false)])
+    );
+    let rows: Vec<String> =
+      read_rows(&reader).iter().map(|row| row.to_string()).collect();
+    assert_eq!(
+      rows,
+      vec![
+        "{id: 1, b: [1]}",
+        "{id: 2, b: [2, 2]}",
+        "{id: 3, b: []}",
+        "{id: 4, b: [4]}",
+        "{id: 4, b: [4]}",
+        "{id: 5, b: [5, 5]}",
+        "{id: 6, b: []}",
+        "{id: 7, b: [7]}",
+        "{id: 8, b: [8, 8]}"
+      ]
+    );
+
+    // Files must declare sorting columns
+    let reader: Rc<FileReader> = Rc::new(
+      SerializedFileReader::new(get_test_file("alltypes_plain.parquet")).unwrap()
+    );
+    let output = get_temp_file("merge_sorted_invalid", &[]);
+    let props = Rc::new(WriterProperties::builder().build());
+    assert_eq!(
+      merge_sorted_files(vec![reader], output, props, 10).err().unwrap(),
+      general_err!("Row group 0 of file 0 does not declare sorting columns")
+    );
+  }
+
+  #[test]
+  fn test_append_row_groups_schema_mismatch() {
+    let props = Rc::new(WriterProperties::builder().build());
+    let reader = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap();
+
+    let schema = reader.metadata().file_metadata().schema_descr().root_schema_ptr();
+    let output = get_temp_file("append_row_groups_mismatch", &[]);
+    let mut writer = SerializedFileWriter::new(output, schema, props.clone()).unwrap();
+    let nested_reader =
+      SerializedFileReader::new(get_test_file("nested_lists.snappy.parquet")).unwrap();
+    assert_eq!(
+      append_row_groups(&mut writer, &nested_reader).err().unwrap(),
+      general_err!("Schema with 2 columns does not match schema with 11 columns")
+    );
+
+    let schema = parse_message_type("
+      message schema {
+        REQUIRED INT32 id;
+        OPTIONAL BOOLEAN bool_col;
+        OPTIONAL INT32 tinyint_col;
+        OPTIONAL INT32 smallint_col;
+        OPTIONAL INT32 int_col;
+        OPTIONAL INT64 bigint_col;
+        OPTIONAL FLOAT float_col;
+        OPTIONAL DOUBLE double_col;
+        OPTIONAL BYTE_ARRAY date_string_col;
+        OPTIONAL BYTE_ARRAY string_col;
+        OPTIONAL INT96 timestamp_col;
+      }
+    ").unwrap();
+    let output = get_temp_file("append_row_groups_mismatch", &[]);
+    let mut writer = SerializedFileWriter::new(output, Rc::new(schema), props).unwrap();
+    assert_eq!(
+      append_row_groups(&mut writer, &reader).err().unwrap(),
+      general_err!("Column id does not match column id of schema")
+    );
+    writer.close().unwrap();
+  }
+}
@@ -0,0 +1,655 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains recovery of metadata of Parquet files whose footer is missing or corrupt,
+//! e.g. files truncated by an interrupted upload.
+//!
+//! Column chunks are written one after another after the leading magic bytes and every
+//! page starts with a page header, so [`salvage_metadata`] scans page headers from the
+//! beginning of the file until the trailing magic bytes or the first page that is
+//! truncated or invalid. Metadata of a column chunk that writers store after its pages
+//! is skipped. Schema is only stored in the footer, so it must be provided by the
+//! caller, e.g. from another file of the same dataset.
+//!
+//! Scanned pages are grouped into column chunks in the order of leaf columns of the
+//! schema, so that all column chunks of a row group have the same number of rows. Only
+//! complete row groups are recovered. Number of rows of data pages v1 of repeated
+//! columns is counted by decoding their repetition levels.
+//!
+//! Reconstructed metadata is approximate:
+//! - compression codec of a column chunk is detected by decompressing its first page;
+//! - statistics, page indexes, bloom filters and key-value metadata are not recovered;
+//! - if pages can be grouped in several ways, e.g. when all pages have the same number
+//! of rows, grouping that recovers most pages is used, preferring smaller row groups.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::fs::File;
+//! use std::rc::Rc;
+//!
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//! use parquet::schema::parser::parse_message_type;
+//!
+//! let schema = parse_message_type("message schema { REQUIRED INT64 id; }").unwrap();
+//! let file = File::open("truncated.parquet").unwrap();
+//! let reader = SerializedFileReader::new_salvaged(file, Rc::new(schema)).unwrap();
+//! let metadata = reader.metadata();
+//! println!(
+//!   "Recovered {} rows in {} row groups",
+//!   metadata.file_metadata().num_rows(),
+//!   metadata.num_row_groups()
+//! );
+//! ```
+
+use std::collections::HashMap;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::rc::Rc;
+
+use parquet_format::{ColumnChunk, PageHeader};
+use snap::decompress_len;
+use thrift::protocol::TCompactInputProtocol;
+
+use basic::{Compression, Encoding, PageType};
+use compression::create_codec;
+use encodings::levels::LevelDecoder;
+use errors::{ParquetError, Result};
+use file::PARQUET_MAGIC;
+use file::metadata::{
+  ColumnChunkMetaData, FileMetaData, ParquetMetaData, RowGroupMetaData
+};
+use file::reader::{check_page_crc, check_page_header, Length};
+use schema::types::{ColumnDescPtr, SchemaDescriptor, TypePtr};
+use util::memory::ByteBufferPtr;
+use util::thrift::TolerantInputProtocol;
+
+/// Codecs tried when detecting compression codec of a page. Brotli streams do not have
+/// magic bytes, so it is tried last.
+const DETECTED_CODECS: [Compression; 5] = [
+  Compression::SNAPPY,
+  Compression::GZIP,
+  Compression::ZSTD,
+  Compression::LZ4,
+  Compression::BROTLI
+];
+
+/// Reconstructs metadata of a Parquet file with schema `schema` from its page headers,
+/// see [module documentation](index.html) for details.
+///
+/// Returns metadata of all complete row groups found before the first truncated or
+/// invalid page, which can be used with
+/// [`SerializedFileReader::new_with_metadata`]
+/// (../reader/struct.SerializedFileReader.html#method.new_with_metadata). Returns error
+/// if the file does not start with magic bytes or if schema is not a group.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub fn salvage_metadata<R: Read + Seek + Length>(
+  reader: &mut R,
+  schema: TypePtr
+) -> Result<ParquetMetaData> {
+  if !schema.is_group() {
+    return Err(general_err!("Schema of salvaged file must be a group"));
+  }
+  let schema_descr = Rc::new(SchemaDescriptor::new(schema.clone()));
+  if schema_descr.num_columns() == 0 {
+    return Err(general_err!("Schema of salvaged file has no columns"));
+  }
+  let file_size = reader.len() as i64;
+  let mut scanner = PageScanner {
+    reader: BufReader::new(reader),
+    file_size: file_size,
+    pages: Vec::new(),
+    columns: schema_descr.columns().to_vec(),
+    page_rows: HashMap::new()
+  };
+  scanner.scan_pages()?;
+
+  let mut row_groups = Vec::new();
+  let mut num_rows = 0;
+  for layout in scanner.layout_row_groups() {
+    match scanner.row_group_metadata(&layout, schema_descr.clone()) {
+      Ok(row_group) => {
+        num_rows += row_group.num_rows();
+        row_groups.push(Rc::new(row_group));
+      },
+      // Row groups after a chunk that cannot be decompressed are not recovered either
+      Err(e) => {
+        trace_warn!(error = %e, "Stopping salvage at row group that cannot be recovered");
+        break;
+      }
+    }
+  }
+  let file_metadata = FileMetaData::new(1, num_rows, None, schema, schema_descr, None);
+  Ok(ParquetMetaData::new(file_metadata, row_groups))
+}
+
+/// Page found by scanning page headers.
+struct ScannedPage {
+  page_type: PageType,
+  offset: i64,
+  header_size: i64,
+  compressed_size: i64,
+  uncompressed_size: i64,
+  num_values: i64,
+  // Number of rows of data page v2
+  num_rows: Option<i64>,
+  // Size of levels of data page v2, which are never compressed
+  levels_size: i64,
+  is_compressed: bool,
+  rep_level_encoding: Encoding,
+  encodings: Vec<Encoding>
+}
+
+impl ScannedPage {
+  fn is_data_page(&self) -> bool {
+    self.page_type == PageType::DATA_PAGE || self.page_type == PageType::DATA_PAGE_V2
+  }
+}
+
+/// Pages of a row group, `chunks` are ranges of page indexes of column chunks.
+struct RowGroupLayout {
+  num_rows: i64,
+  chunks: Vec<(usize, usize)>
+}
+
+/// Scans pages of a file and groups them into row groups.
+struct PageScanner<R: Read + Seek> {
+  reader: BufReader<R>,
+  file_size: i64,
+  pages: Vec<ScannedPage>,
+  columns: Vec<ColumnDescPtr>,
+  // Number of rows of data pages v1 of repeated columns, keyed by page index and max
+  // repetition level, `None` if levels cannot be decoded
+  page_rows: HashMap<(usize, i16), Option<i64>>
+}
+
+impl<R: Read + Seek> PageScanner<R> {
+  /// Reads page headers after the leading magic bytes until the trailing magic bytes
+  /// or the first page that cannot be read, skipping metadata of column chunks.
+  #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+  fn scan_pages(&mut self) -> Result<()> {
+    let mut magic = [0; 4];
+    self.reader.seek(SeekFrom::Start(0))?;
+    if self.file_size < 4 || self.reader.read_exact(&mut magic).is_err() ||
+        magic != PARQUET_MAGIC {
+      return Err(general_err!("Invalid Parquet file. Corrupt header"));
+    }
+    let mut offset = 4;
+    while offset < self.file_size {
+      if offset + 4 <= self.file_size && self.read_bytes(offset, 4)? == PARQUET_MAGIC {
+        break;
+      }
+      match self.read_page(offset) {
+        Ok(page) => {
+          offset += page.header_size + page.compressed_size;
+          self.pages.push(page);
+        },
+        Err(e) => match self.read_column_metadata(offset) {
+          Ok(size) => offset += size,
+          Err(_) => {
+            trace_warn!(offset = offset, error = %e, "Stopping scan at invalid page");
+            break;
+          }
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Reads metadata of the column chunk that ends at `offset`, which writers store
+  /// after pages of the column chunk, and returns its size.
+  fn read_column_metadata(&mut self, offset: i64) -> Result<i64> {
+    self.reader.seek(SeekFrom::Start(offset as u64))?;
+    let column_chunk = {
+      let mut prot = TolerantInputProtocol::for_column_chunk(
+        TCompactInputProtocol::new(&mut self.reader));
+      ColumnChunk::read_from_in_protocol(&mut prot)?
+    };
+    let size = self.reader.seek(SeekFrom::Current(0))? as i64 - offset;
+    let metadata = column_chunk.meta_data
+      .ok_or(general_err!("Missing column metadata at offset {}", offset))?;
+    let start = metadata.dictionary_page_offset.unwrap_or(metadata.data_page_offset);
+    if start + metadata.total_compressed_size != offset {
+      return Err(general_err!("Column metadata at offset {} is not expected", offset));
+    }
+    Ok(size)
+  }
+
+  /// Reads page header at `offset` and checks that the page is complete.
+  fn read_page(&mut self, offset: i64) -> Result<ScannedPage> {
+    self.reader.seek(SeekFrom::Start(offset as u64))?;
+    let page_header = {
+      let mut prot = TolerantInputProtocol::for_page_header(
+        TCompactInputProtocol::new(&mut self.reader));
+      PageHeader::read_from_in_protocol(&mut prot)?
+    };
+    check_page_header(&page_header)?;
+    let header_size = self.reader.seek(SeekFrom::Current(0))? as i64 - offset;
+    let compressed_size = page_header.compressed_page_size as i64;
+    if offset + header_size + compressed_size > self.file_size {
+      return Err(eof_err!("Page at offset {} is truncated", offset));
+    }
+
+    let page_type = PageType::from(page_header.type_);
+    let mut page = ScannedPage {
+      page_type: page_type,
+      offset: offset,
+      header_size: header_size,
+      compressed_size: compressed_size,
+      uncompressed_size: page_header.uncompressed_page_size as i64,
+      num_values: 0,
+      num_rows: None,
+      levels_size: 0,
+      is_compressed: true,
+      rep_level_encoding: Encoding::RLE,
+      encodings: Vec::new()
+    };
+    match page_type {
+      PageType::DICTIONARY_PAGE => {
+        let header = page_header.dictionary_page_header.as_ref()
+          .ok_or(general_err!("Missing dictionary page header"))?;
+        page.num_values = header.num_values as i64;
+        page.encodings.push(Encoding::from(header.encoding));
+      },
+      PageType::DATA_PAGE => {
+        let header = page_header.data_page_header.as_ref()
+          .ok_or(general_err!("Missing data page header"))?;
+        page.num_values = header.num_values as i64;
+        page.rep_level_encoding = Encoding::from(header.repetition_level_encoding);
+        page.encodings.push(Encoding::from(header.encoding));
+        page.encodings.push(Encoding::from(header.definition_level_encoding));
+        page.encodings.push(page.rep_level_encoding);
+      },
+      PageType::DATA_PAGE_V2 => {
+        let header = page_header.data_page_header_v2.as_ref()
+          .ok_or(general_err!("Missing data page v2 header"))?;
+        if header.num_rows < 0 {
+          return Err(general_err!("Invalid number of rows: {}", header.num_rows));
+        }
+        page.num_values = header.num_values as i64;
+        page.num_rows = Some(header.num_rows as i64);
+        page.levels_size = header.definition_levels_byte_length as i64 +
+          header.repetition_levels_byte_length as i64;
+        page.is_compressed = header.is_compressed.unwrap_or(true);
+        page.encodings.push(Encoding::from(header.encoding));
+        page.encodings.push(Encoding::RLE);
+      },
+      other => return Err(general_err!("Unexpected page type {}", other))
+    }
+    if page.num_values < 0 {
+      return Err(general_err!("Invalid number of values: {}", page.num_values));
+    }
+    if page_header.crc.is_some() {
+      let buf = self.read_bytes(offset + header_size, compressed_size as usize)?;
+      check_page_crc(&page_header, &buf)?;
+    }
+    Ok(page)
+  }
+
+  /// Reads `len` bytes at `offset`.
+  fn read_bytes(&mut self, offset: i64, len: usize) -> Result<Vec<u8>> {
+    self.reader.seek(SeekFrom::Start(offset as u64))?;
+    let mut buf = vec![0; len];
+    self.reader.read_exact(&mut buf)?;
+    Ok(buf)
+  }
+
+  /// Returns number of rows of data page `i` of a column with `max_rep_level`, or
+  /// `None` if page is not a data page or its rows cannot be counted.
+  fn num_rows(&mut self, i: usize, max_rep_level: i16) -> Option<i64> {
+    {
+      let page = &self.pages[i];
+      if !page.is_data_page() {
+        return None;
+      }
+      if page.num_rows.is_some() {
+        return page.num_rows;
+      }
+      if max_rep_level == 0 {
+        // Every value of a non-repeated column is a row
+        return Some(page.num_values);
+      }
+    }
+    if let Some(&num_rows) = self.page_rows.get(&(i, max_rep_level)) {
+      return num_rows;
+    }
+    let num_rows = self.count_records(i, max_rep_level);
+    self.page_rows.insert((i, max_rep_level), num_rows);
+    num_rows
+  }
+
+  /// Counts records of data page v1 `i` by decoding its repetition levels.
+  fn count_records(&mut self, i: usize, max_rep_level: i16) -> Option<i64> {
+    let (_, data) = self.decompress_page(i).ok()?;
+    let page = &self.pages[i];
+    let num_values = page.num_values as usize;
+    match page.rep_level_encoding {
+      Encoding::RLE => {
+        // Levels are prefixed with their length
+        if data.len() < 4 {
+          return None;
+        }
+        let len = data[0] as usize | (data[1] as usize) << 8 |
+          (data[2] as usize) << 16 | (data[3] as usize) << 24;
+        if len > data.len() - 4 {
+          return None;
+        }
+      },
+      Encoding::BIT_PACKED => {},
+      _ => return None
+    }
+    let mut decoder = LevelDecoder::v1(page.rep_level_encoding, max_rep_level);
+    decoder.set_data(num_values, ByteBufferPtr::new(data));
+    let mut levels = vec![0; num_values];
+    if decoder.get(&mut levels).ok()? != num_values ||
+        levels.iter().any(|&level| level > max_rep_level) {
+      return None;
+    }
+    Some(levels.iter().filter(|&&level| level == 0).count() as i64)
+  }
+
+  /// Reads data of page `i` and decompresses it with the first codec that yields data
+  /// of the uncompressed page size. Levels of data page v2 are kept as they are.
+  /// Returns `None` codec if page is not compressed.
+  fn decompress_page(&mut self, i: usize) -> Result<(Option<Compression>, Vec<u8>)> {
+    let (offset, compressed_size, uncompressed_size, levels_size, is_compressed) = {
+      let page = &self.pages[i];
+      (page.offset + page.header_size, page.compressed_size, page.uncompressed_size,
+        page.levels_size, page.is_compressed)
+    };
+    let buf = self.read_bytes(offset, compressed_size as usize)?;
+    if !is_compressed {
+      return Ok((None, buf));
+    }
+    let levels_size = levels_size as usize;
+    let expected_size = (uncompressed_size - levels_size as i64) as usize;
+    for &codec in DETECTED_CODECS.iter() {
+      if codec == Compression::BROTLI && compressed_size == uncompressed_size {
+        return Ok((Some(Compression::UNCOMPRESSED), buf));
+      }
+      // Length of SNAPPY stream is checked first, so that invalid data is not
+      // decompressed into a buffer of arbitrary size
+      if codec == Compression::SNAPPY &&
+          decompress_len(&buf[levels_size..]).ok() != Some(expected_size) {
+        continue;
+      }
+      let mut decompressor = match create_codec(codec)? {
+        Some(decompressor) => decompressor,
+        None => continue
+      };
+      let mut values = Vec::with_capacity(expected_size);
+      match decompressor.decompress(&buf[levels_size..], &mut values) {
+        Ok(_) if values.len() == expected_size => {
+          let mut decompressed = buf[..levels_size].to_vec();
+          decompressed.extend_from_slice(&values);
+          return Ok((Some(codec), decompressed));
+        },
+        _ => {}
+      }
+    }
+    Err(general_err!("Cannot decompress page at offset {}", self.pages[i].offset))
+  }
+
+  /// Returns end of column chunk of `column` that starts with page `start` and has
+  /// `num_rows` rows, or `None` if pages do not form such column chunk.
+  fn chunk_end(&mut self, start: usize, column: usize, num_rows: i64) -> Option<usize> {
+    let max_rep_level = self.columns[column].max_rep_level();
+    let mut i = start;
+    if i < self.pages.len() && self.pages[i].page_type == PageType::DICTIONARY_PAGE {
+      i += 1;
+    }
+    let mut seen_rows = 0;
+    while seen_rows < num_rows {
+      if i >= self.pages.len() {
+        return None;
+      }
+      seen_rows += self.num_rows(i, max_rep_level)?;
+      i += 1;
+    }
+    if seen_rows == num_rows { Some(i) } else { None }
+  }
+
+  /// Returns possible layouts of the row group that starts with page `start`, ordered
+  /// by number of rows.
+  fn row_group_candidates(&mut self, start: usize) -> Vec<RowGroupLayout> {
+    let max_rep_level = self.columns[0].max_rep_level();
+    let mut candidates = Vec::new();
+    let mut i = start;
+    if i < self.pages.len() && self.pages[i].page_type == PageType::DICTIONARY_PAGE {
+      i += 1;
+    }
+    let mut num_rows = 0;
+    while i < self.pages.len() {
+      match self.num_rows(i, max_rep_level) {
+        Some(rows) => num_rows += rows,
+        None => break
+      }
+      i += 1;
+      if num_rows == 0 {
+        continue;
+      }
+      let mut chunks = vec![(start, i)];
+      for column in 1..self.columns.len() {
+        let chunk_start = chunks[column - 1].1;
+        match self.chunk_end(chunk_start, column, num_rows) {
+          Some(end) => chunks.push((chunk_start, end)),
+          None => break
+        }
+      }
+      if chunks.len() == self.columns.len() {
+        candidates.push(RowGroupLayout { num_rows: num_rows, chunks: chunks });
+      }
+    }
+    candidates
+  }
+
+  /// Groups scanned pages into row groups, so that most pages are recovered.
+  fn layout_row_groups(&mut self) -> Vec<RowGroupLayout> {
+    let num_pages = self.pages.len();
+    // Number of pages recovered from row groups that start with page `i` and the
+    // layout of the first such row group
+    let mut best: Vec<(usize, Option<RowGroupLayout>)> = Vec::with_capacity(num_pages);
+    for _ in 0..num_pages + 1 {
+      best.push((0, None));
+    }
+    for start in (0..num_pages).rev() {
+      for layout in self.row_group_candidates(start) {
+        let end = layout.chunks[layout.chunks.len() - 1].1;
+        let num_recovered = end - start + best[end].0;
+        if num_recovered > best[start].0 {
+          best[start] = (num_recovered, Some(layout));
+        }
+      }
+    }
+
+    let mut layouts = Vec::new();
+    let mut start = 0;
+    while let Some(layout) = best[start].1.take() {
+      start = layout.chunks[layout.chunks.len() - 1].1;
+      layouts.push(layout);
+    }
+    layouts
+  }
+
+  /// Builds metadata of row group with `layout`.
+  fn row_group_metadata(
+    &mut self,
+    layout: &RowGroupLayout,
+    schema_descr: Rc<SchemaDescriptor>
+  ) -> Result<RowGroupMetaData> {
+    let mut columns = Vec::with_capacity(layout.chunks.len());
+    let mut total_byte_size = 0;
+    for (column, &(start, end)) in layout.chunks.iter().enumerate() {
+      let mut compression = None;
+      for i in start..end {
+        compression = self.decompress_page(i)?.0;
+        if compression.is_some() {
+          break;
+        }
+      }
+
+      let pages = &self.pages[start..end];
+      let mut encodings = Vec::new();
+      for page in pages {
+        for encoding in &page.encodings {
+          if !encodings.contains(encoding) {
+            encodings.push(*encoding);
+          }
+        }
+      }
+      let dictionary_page_offset = if pages[0].is_data_page() {
+        None
+      } else {
+        Some(pages[0].offset)
+      };
+      let num_values: i64 = pages.iter()
+        .filter(|page| page.is_data_page())
+        .map(|page| page.num_values)
+        .sum();
+      let data_page_offset = pages.iter()
+        .find(|page| page.is_data_page())
+        .map_or(0, |page| page.offset);
+      let total_compressed_size: i64 =
+        pages.iter().map(|page| page.header_size + page.compressed_size).sum();
+      let total_uncompressed_size: i64 =
+        pages.iter().map(|page| page.header_size + page.uncompressed_size).sum();
+      total_byte_size += total_uncompressed_size;
+
+      let column_metadata = ColumnChunkMetaData::builder(self.columns[column].clone())
+        .set_compression(compression.unwrap_or(Compression::UNCOMPRESSED))
+        .set_encodings(encodings)
+        .set_file_offset(pages[0].offset + total_compressed_size)
+        .set_total_compressed_size(total_compressed_size)
+        .set_total_uncompressed_size(total_uncompressed_size)
+        .set_num_values(num_values)
+        .set_data_page_offset(data_page_offset)
+        .set_dictionary_page_offset(dictionary_page_offset)
+        .build()?;
+      columns.push(Rc::new(column_metadata));
+    }
+    RowGroupMetaData::builder(schema_descr)
+      .set_num_rows(layout.num_rows)
+      .set_total_byte_size(total_byte_size)
+      .set_column_metadata(columns)
+      .build()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use column::writer::ColumnWriter;
+  use file::properties::WriterProperties;
+  use file::reader::{FileReader, SerializedFileReader};
+  use file::writer::{FileWriter, write_to_bytes};
+  use record::RowAccessor;
+  use schema::parser::parse_message_type;
+  use util::test_common::get_temp_file;
+
+  // Writes 3 row groups of 10 rows with a repeated column, returns file bytes and
+  // schema
+  fn write_file() -> (Vec<u8>, TypePtr) {
+    let schema = Rc::new(parse_message_type(
+      "message schema { REQUIRED INT64 id; REPEATED INT32 values; }"
+    ).unwrap());
+    let props = Rc::new(
+      WriterProperties::builder().set_compression(Compression::SNAPPY).build()
+    );
+    let bytes = write_to_bytes(schema.clone(), props, |writer| {
+      for i in 0..3 {
+        let mut row_group_writer = writer.next_row_group()?;
+        while let Some(mut col_writer) = row_group_writer.next_column()? {
+          match col_writer {
+            ColumnWriter::Int64ColumnWriter(ref mut typed) => {
+              let ids = (i * 10..i * 10 + 10).collect::<Vec<i64>>();
+              typed.write_batch(&ids[..], None, None)?;
+            },
+            ColumnWriter::Int32ColumnWriter(ref mut typed) => {
+              let values = (0..20).collect::<Vec<i32>>();
+              let rep_levels = (0..20).map(|v| v % 2).collect::<Vec<i16>>();
+              typed.write_batch(&values[..], Some(&[1; 20][..]), Some(&rep_levels[..]))?;
+            },
+            _ => unreachable!()
+          }
+          row_group_writer.close_column(col_writer)?;
+        }
+        writer.close_row_group(row_group_writer)?;
+      }
+      Ok(())
+    }).unwrap();
+    (bytes, schema)
+  }
+
+  #[test]
+  fn test_salvage_truncated_file() {
+    let (bytes, schema) = write_file();
+    let end = {
+      let reader = SerializedFileReader::new(
+        get_temp_file("salvage_full.parquet", &bytes[..])).unwrap();
+      let row_group = reader.metadata().row_group(2);
+      let column = row_group.column(0);
+      // Cut the file in the middle of the first page header of the last row group
+      column.dictionary_page_offset().unwrap_or(column.data_page_offset()) as usize + 3
+    };
+    let mut file = get_temp_file("salvage_truncated.parquet", &bytes[..end]);
+    assert!(SerializedFileReader::new(file.try_clone().unwrap()).is_err());
+
+    let metadata = salvage_metadata(&mut file, schema.clone()).unwrap();
+    assert_eq!(metadata.num_row_groups(), 2);
+    assert_eq!(metadata.file_metadata().num_rows(), 20);
+    for row_group in metadata.row_groups() {
+      assert_eq!(row_group.num_rows(), 10);
+      assert_eq!(row_group.column(0).num_values(), 10);
+      assert_eq!(row_group.column(1).num_values(), 20);
+      assert_eq!(row_group.column(0).compression(), Compression::SNAPPY);
+    }
+
+    let reader = SerializedFileReader::new_salvaged(file, schema).unwrap();
+    let ids = reader.get_row_iter(None).unwrap()
+      .map(|row| row.unwrap().get_long(0).unwrap())
+      .collect::<Vec<_>>();
+    assert_eq!(ids, (0..20).collect::<Vec<i64>>());
+  }
+
+  #[test]
+  fn test_salvage_complete_file() {
+    let (bytes, schema) = write_file();
+    let mut file = get_temp_file("salvage_complete.parquet", &bytes[..]);
+    let metadata = salvage_metadata(&mut file, schema).unwrap();
+    assert_eq!(metadata.num_row_groups(), 3);
+    assert_eq!(metadata.file_metadata().num_rows(), 30);
+  }
+
+  #[test]
+  fn test_salvage_invalid_file() {
+    let (bytes, schema) = write_file();
+    let mut file = get_temp_file("salvage_invalid.parquet", &bytes[4..]);
+    assert_eq!(
+      salvage_metadata(&mut file, schema).err().unwrap(),
+      general_err!("Invalid Parquet file. Corrupt header")
+    );
+
+    let primitive = parse_message_type("message schema { REQUIRED INT64 id; }")
+      .unwrap()
+      .get_fields()[0]
+      .clone();
+    let mut file = get_temp_file("salvage_primitive.parquet", &bytes[..]);
+    assert_eq!(
+      salvage_metadata(&mut file, primitive).err().unwrap(),
+      general_err!("Schema of salvaged file must be a group")
+    );
+  }
+}
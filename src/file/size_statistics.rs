@@ -0,0 +1,263 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains size statistics of a column chunk or a data page.
+//!
+//! Size statistics consist of histograms of definition and repetition levels, i.e.
+//! number of values per level, and number of bytes of unencoded `BYTE_ARRAY` values.
+//! They allow engines to size buffers for nested or variable length values before
+//! reading any pages.
+//!
+//! Statistics of a column chunk are available with
+//! [`ColumnChunkMetaData::size_statistics`], statistics of each data page are kept in
+//! page indexes, see [`ColumnIndex::repetition_level_histogram`],
+//! [`ColumnIndex::definition_level_histogram`] and
+//! [`OffsetIndex::unencoded_byte_array_data_bytes`]. Column writer computes statistics
+//! of each page and of the column chunk, unless statistics are disabled.
+//!
+//! [`ColumnChunkMetaData::size_statistics`]:
+//! ../metadata/struct.ColumnChunkMetaData.html#method.size_statistics
+//! [`ColumnIndex::repetition_level_histogram`]:
+//! ../page_index/struct.ColumnIndex.html#method.repetition_level_histogram
+//! [`ColumnIndex::definition_level_histogram`]:
+//! ../page_index/struct.ColumnIndex.html#method.definition_level_histogram
+//! [`OffsetIndex::unencoded_byte_array_data_bytes`]:
+//! ../page_index/struct.OffsetIndex.html#method.unencoded_byte_array_data_bytes
+
+use thrift::protocol::{
+  TCompactInputProtocol, TCompactOutputProtocol, TFieldIdentifier, TInputProtocol,
+  TListIdentifier, TOutputProtocol, TStructIdentifier, TType
+};
+
+use errors::{ParquetError, Result};
+
+/// Size statistics of a column chunk or a data page.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SizeStatistics {
+  unencoded_byte_array_data_bytes: Option<i64>,
+  repetition_level_histogram: Option<Vec<i64>>,
+  definition_level_histogram: Option<Vec<i64>>
+}
+
+impl SizeStatistics {
+  /// Creates size statistics.
+  pub fn new(
+    unencoded_byte_array_data_bytes: Option<i64>,
+    repetition_level_histogram: Option<Vec<i64>>,
+    definition_level_histogram: Option<Vec<i64>>
+  ) -> Self {
+    Self {
+      unencoded_byte_array_data_bytes,
+      repetition_level_histogram,
+      definition_level_histogram
+    }
+  }
+
+  /// Returns number of bytes of `BYTE_ARRAY` values, excluding their lengths, before
+  /// encoding, or `None` for other types.
+  pub fn unencoded_byte_array_data_bytes(&self) -> Option<i64> {
+    self.unencoded_byte_array_data_bytes
+  }
+
+  /// Returns number of values for each repetition level, starting with level 0, or
+  /// `None` if not available, e.g. when max repetition level is 0.
+  pub fn repetition_level_histogram(&self) -> Option<&[i64]> {
+    self.repetition_level_histogram.as_ref().map(|v| &v[..])
+  }
+
+  /// Returns number of values for each definition level, starting with level 0, or
+  /// `None` if not available, e.g. when max definition level is 0.
+  pub fn definition_level_histogram(&self) -> Option<&[i64]> {
+    self.definition_level_histogram.as_ref().map(|v| &v[..])
+  }
+
+  /// Adds statistics of `other`, e.g. of the next data page of a column chunk.
+  /// Values that are not available in either statistics are not available in the
+  /// result.
+  pub fn update(&mut self, other: &SizeStatistics) {
+    self.unencoded_byte_array_data_bytes = self.unencoded_byte_array_data_bytes
+      .and_then(|bytes| other.unencoded_byte_array_data_bytes.map(|other| bytes + other));
+    update_histogram(
+      &mut self.repetition_level_histogram, &other.repetition_level_histogram);
+    update_histogram(
+      &mut self.definition_level_histogram, &other.definition_level_histogram);
+  }
+
+  /// Decodes statistics from the value of a `SizeStatistics` field serialized with the
+  /// compact protocol.
+  pub(crate) fn from_compact_bytes(data: &[u8]) -> Result<Self> {
+    let mut prot = TCompactInputProtocol::new(data);
+    let mut result = SizeStatistics::default();
+    prot.read_struct_begin()?;
+    loop {
+      let field = prot.read_field_begin()?;
+      if field.field_type == TType::Stop {
+        break;
+      }
+      match (field.id, field.field_type) {
+        (Some(1), TType::I64) => {
+          result.unencoded_byte_array_data_bytes = Some(prot.read_i64()?);
+        },
+        (Some(2), TType::List) => {
+          result.repetition_level_histogram = Some(read_i64_list(&mut prot)?);
+        },
+        (Some(3), TType::List) => {
+          result.definition_level_histogram = Some(read_i64_list(&mut prot)?);
+        },
+        (_, field_type) => prot.skip(field_type)?
+      }
+      prot.read_field_end()?;
+    }
+    prot.read_struct_end()?;
+    Ok(result)
+  }
+
+  /// Serializes statistics as the value of a `SizeStatistics` field with the compact
+  /// protocol.
+  pub(crate) fn to_compact_bytes(&self) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut buf);
+      prot.write_struct_begin(&TStructIdentifier::new("SizeStatistics"))?;
+      if let Some(bytes) = self.unencoded_byte_array_data_bytes {
+        prot.write_field_begin(&field_identifier(TType::I64, 1))?;
+        prot.write_i64(bytes)?;
+        prot.write_field_end()?;
+      }
+      if let Some(ref histogram) = self.repetition_level_histogram {
+        prot.write_field_begin(&field_identifier(TType::List, 2))?;
+        write_i64_list(&mut prot, histogram)?;
+        prot.write_field_end()?;
+      }
+      if let Some(ref histogram) = self.definition_level_histogram {
+        prot.write_field_begin(&field_identifier(TType::List, 3))?;
+        write_i64_list(&mut prot, histogram)?;
+        prot.write_field_end()?;
+      }
+      prot.write_field_stop()?;
+      prot.write_struct_end()?;
+      prot.flush()?;
+    }
+    Ok(buf)
+  }
+}
+
+/// Returns number of values for each level of `levels`, up to `max_level`. Levels that
+/// are out of range are not counted.
+pub(crate) fn level_histogram(levels: &[i16], max_level: i16) -> Vec<i64> {
+  let mut histogram = vec![0; max_level as usize + 1];
+  for &level in levels {
+    if let Some(count) = histogram.get_mut(level as usize) {
+      *count += 1;
+    }
+  }
+  histogram
+}
+
+/// Adds `other` histogram to `histogram` element-wise, the result is `None` if either
+/// histogram is not available or their lengths differ.
+fn update_histogram(histogram: &mut Option<Vec<i64>>, other: &Option<Vec<i64>>) {
+  let is_compatible = match (histogram.as_ref(), other.as_ref()) {
+    (Some(histogram), Some(other)) => histogram.len() == other.len(),
+    _ => false
+  };
+  if !is_compatible {
+    *histogram = None;
+    return;
+  }
+  if let (Some(histogram), Some(other)) = (histogram.as_mut(), other.as_ref()) {
+    for (count, other) in histogram.iter_mut().zip(other) {
+      *count += *other;
+    }
+  }
+}
+
+/// Reads the value of a `list<i64>` field.
+pub(crate) fn read_i64_list(prot: &mut TInputProtocol) -> Result<Vec<i64>> {
+  let list = prot.read_list_begin()?;
+  if list.size < 0 || list.element_type != TType::I64 {
+    return Err(general_err!(
+      "Expected list of i64 values, found {} values of type {:?}",
+      list.size,
+      list.element_type
+    ));
+  }
+  let mut values = Vec::with_capacity(list.size as usize);
+  for _ in 0..list.size {
+    values.push(prot.read_i64()?);
+  }
+  prot.read_list_end()?;
+  Ok(values)
+}
+
+/// Writes the value of a `list<i64>` field.
+fn write_i64_list(prot: &mut TOutputProtocol, values: &[i64]) -> Result<()> {
+  prot.write_list_begin(&TListIdentifier::new(TType::I64, values.len() as i32))?;
+  for &value in values {
+    prot.write_i64(value)?;
+  }
+  prot.write_list_end()?;
+  Ok(())
+}
+
+fn field_identifier(field_type: TType, id: i16) -> TFieldIdentifier {
+  TFieldIdentifier { name: None, field_type: field_type, id: Some(id) }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_level_histogram() {
+    assert_eq!(level_histogram(&[], 0), vec![0]);
+    assert_eq!(level_histogram(&[0, 2, 2, 1, 2], 2), vec![1, 1, 3]);
+    assert_eq!(level_histogram(&[0, 0], 3), vec![2, 0, 0, 0]);
+  }
+
+  #[test]
+  fn test_size_statistics_update() {
+    let mut stats = SizeStatistics::new(Some(10), Some(vec![2, 1]), Some(vec![1, 2]));
+    stats.update(&SizeStatistics::new(Some(5), Some(vec![1, 0]), Some(vec![0, 1])));
+    assert_eq!(stats, SizeStatistics::new(Some(15), Some(vec![3, 1]), Some(vec![1, 3])));
+
+    stats.update(&SizeStatistics::new(None, Some(vec![1, 1, 1]), Some(vec![1, 1])));
+    assert_eq!(stats, SizeStatistics::new(None, None, Some(vec![2, 4])));
+  }
+
+  #[test]
+  fn test_size_statistics_compact_bytes() {
+    let stats = vec![
+      SizeStatistics::default(),
+      SizeStatistics::new(Some(123), None, None),
+      SizeStatistics::new(None, None, Some(vec![3, 0, 7])),
+      SizeStatistics::new(Some(0), Some(vec![1, 2]), Some(vec![4, 5, 6]))
+    ];
+    for stats in stats {
+      let data = stats.to_compact_bytes().unwrap();
+      assert_eq!(SizeStatistics::from_compact_bytes(&data).unwrap(), stats);
+    }
+
+    // i64 value 2 followed by field stop
+    let data = [0x16, 0x04, 0x00];
+    assert_eq!(
+      SizeStatistics::from_compact_bytes(&data).unwrap(),
+      SizeStatistics::new(Some(2), None, None)
+    );
+    assert!(SizeStatistics::from_compact_bytes(&data[..2]).is_err());
+  }
+}
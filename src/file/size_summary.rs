@@ -0,0 +1,254 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains API to summarize row counts and sizes of column chunks of files, computed
+//! from file metadata only, without reading any pages. Used by `parquet-rowcount`
+//! binary.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//! use parquet::file::size_summary::SizeSummary;
+//!
+//! let mut total = SizeSummary::default();
+//! for name in &["alltypes_plain.parquet", "alltypes_plain.snappy.parquet"] {
+//!   let file = File::open(format!("data/{}", name)).unwrap();
+//!   let reader = SerializedFileReader::new(file).unwrap();
+//!   total.add(&SizeSummary::from_metadata(&reader.metadata()));
+//! }
+//! assert_eq!(total.num_rows(), 10);
+//! assert_eq!(total.num_row_groups(), 2);
+//! assert_eq!(total.columns().len(), 11);
+//! ```
+
+use file::metadata::ParquetMetaData;
+use schema::types::ColumnPath;
+
+/// Row count, number of row groups and total sizes of column chunks in bytes of a
+/// file, or of several files combined with [`add`](#method.add).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SizeSummary {
+  num_rows: i64,
+  num_row_groups: usize,
+  compressed_size: i64,
+  uncompressed_size: i64,
+  columns: Vec<ColumnSizeSummary>
+}
+
+impl SizeSummary {
+  /// Creates summary of a file from its metadata.
+  pub fn from_metadata(metadata: &ParquetMetaData) -> Self {
+    let mut summary = SizeSummary {
+      num_rows: metadata.file_metadata().num_rows(),
+      num_row_groups: metadata.num_row_groups(),
+      ..Default::default()
+    };
+    for row_group in metadata.row_groups() {
+      for column in row_group.columns() {
+        summary.add_column(
+          column.column_path(),
+          column.compressed_size(),
+          column.uncompressed_size()
+        );
+      }
+    }
+    summary
+  }
+
+  /// Adds row count and sizes of `other` to this summary. Sizes of columns are
+  /// aggregated by column path, columns that are not in this summary are appended in
+  /// order of `other`.
+  pub fn add(&mut self, other: &SizeSummary) {
+    self.num_rows += other.num_rows;
+    self.num_row_groups += other.num_row_groups;
+    for column in &other.columns {
+      self.add_column(&column.path, column.compressed_size, column.uncompressed_size);
+    }
+  }
+
+  /// Returns total number of rows.
+  pub fn num_rows(&self) -> i64 {
+    self.num_rows
+  }
+
+  /// Returns total number of row groups.
+  pub fn num_row_groups(&self) -> usize {
+    self.num_row_groups
+  }
+
+  /// Returns total compressed size of all column chunks in bytes.
+  pub fn compressed_size(&self) -> i64 {
+    self.compressed_size
+  }
+
+  /// Returns total uncompressed size of all column chunks in bytes.
+  pub fn uncompressed_size(&self) -> i64 {
+    self.uncompressed_size
+  }
+
+  /// Returns compression ratio of all column chunks, see
+  /// [`compression_ratio`](fn.compression_ratio.html).
+  pub fn compression_ratio(&self) -> Option<f64> {
+    compression_ratio(self.compressed_size, self.uncompressed_size)
+  }
+
+  /// Returns sizes of each column, in order of the first occurrence of the column.
+  pub fn columns(&self) -> &[ColumnSizeSummary] {
+    &self.columns
+  }
+
+  fn add_column(
+    &mut self,
+    path: &ColumnPath,
+    compressed_size: i64,
+    uncompressed_size: i64
+  ) {
+    self.compressed_size += compressed_size;
+    self.uncompressed_size += uncompressed_size;
+    let pos = match self.columns.iter().position(|c| &c.path == path) {
+      Some(pos) => pos,
+      None => {
+        self.columns.push(ColumnSizeSummary {
+          path: path.clone(),
+          compressed_size: 0,
+          uncompressed_size: 0
+        });
+        self.columns.len() - 1
+      }
+    };
+    self.columns[pos].compressed_size += compressed_size;
+    self.columns[pos].uncompressed_size += uncompressed_size;
+  }
+}
+
+/// Total sizes of column chunks of a column in bytes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnSizeSummary {
+  path: ColumnPath,
+  compressed_size: i64,
+  uncompressed_size: i64
+}
+
+impl ColumnSizeSummary {
+  /// Returns path of the column.
+  pub fn path(&self) -> &ColumnPath {
+    &self.path
+  }
+
+  /// Returns total compressed size of column chunks in bytes.
+  pub fn compressed_size(&self) -> i64 {
+    self.compressed_size
+  }
+
+  /// Returns total uncompressed size of column chunks in bytes.
+  pub fn uncompressed_size(&self) -> i64 {
+    self.uncompressed_size
+  }
+
+  /// Returns compression ratio of column chunks, see
+  /// [`compression_ratio`](fn.compression_ratio.html).
+  pub fn compression_ratio(&self) -> Option<f64> {
+    compression_ratio(self.compressed_size, self.uncompressed_size)
+  }
+}
+
+/// Returns compression ratio as uncompressed size to compressed size, or `None` if
+/// compressed size is not positive.
+pub fn compression_ratio(compressed_size: i64, uncompressed_size: i64) -> Option<f64> {
+  if compressed_size > 0 {
+    Some(uncompressed_size as f64 / compressed_size as f64)
+  } else {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use file::reader::{FileReader, SerializedFileReader};
+  use util::test_common::get_test_file;
+
+  fn get_summary(file_name: &str) -> SizeSummary {
+    let reader = SerializedFileReader::new(get_test_file(file_name)).unwrap();
+    SizeSummary::from_metadata(&reader.metadata())
+  }
+
+  #[test]
+  fn test_size_summary_from_metadata() {
+    let summary = get_summary("alltypes_plain.parquet");
+    assert_eq!(summary.num_rows(), 8);
+    assert_eq!(summary.num_row_groups(), 1);
+    assert_eq!(summary.compressed_size(), 671);
+    assert_eq!(summary.uncompressed_size(), 671);
+    assert_eq!(summary.compression_ratio(), Some(1.0));
+    assert_eq!(summary.columns().len(), 11);
+
+    let column = &summary.columns()[0];
+    assert_eq!(column.path(), &ColumnPath::from("id"));
+    assert_eq!(column.compressed_size(), 73);
+    assert_eq!(column.uncompressed_size(), 73);
+    let total: i64 = summary.columns().iter().map(|c| c.compressed_size()).sum();
+    assert_eq!(total, summary.compressed_size());
+  }
+
+  #[test]
+  fn test_size_summary_add() {
+    let mut a = SizeSummary::default();
+    a.num_rows = 10;
+    a.num_row_groups = 1;
+    a.add_column(&ColumnPath::from("x"), 100, 300);
+    a.add_column(&ColumnPath::from("y"), 50, 50);
+
+    let mut b = SizeSummary::default();
+    b.num_rows = 5;
+    b.num_row_groups = 2;
+    b.add_column(&ColumnPath::from("z"), 10, 0);
+    b.add_column(&ColumnPath::from("x"), 100, 100);
+
+    let mut total = SizeSummary::default();
+    total.add(&a);
+    total.add(&b);
+    assert_eq!(total.num_rows(), 15);
+    assert_eq!(total.num_row_groups(), 3);
+    assert_eq!(total.compressed_size(), 260);
+    assert_eq!(total.uncompressed_size(), 450);
+
+    let columns: Vec<(String, i64, i64, Option<f64>)> = total.columns().iter()
+      .map(|c| {
+        (c.path().string(), c.compressed_size(), c.uncompressed_size(),
+          c.compression_ratio())
+      })
+      .collect();
+    assert_eq!(columns, vec![
+      ("x".to_owned(), 200, 400, Some(2.0)),
+      ("y".to_owned(), 50, 50, Some(1.0)),
+      ("z".to_owned(), 10, 0, Some(0.0))
+    ]);
+  }
+
+  #[test]
+  fn test_compression_ratio() {
+    assert_eq!(compression_ratio(100, 250), Some(2.5));
+    assert_eq!(compression_ratio(100, 50), Some(0.5));
+    assert_eq!(compression_ratio(0, 50), None);
+    assert_eq!(compression_ratio(-1, 50), None);
+    assert_eq!(SizeSummary::default().compression_ratio(), None);
+  }
+}
@@ -37,13 +37,16 @@
 //! }
 //! ```
 
-use std::cmp;
+use std::cmp::{self, Ordering};
 use std::fmt;
+use std::iter;
 
-use basic::Type;
+use basic::{ColumnOrder, LogicalType, SortOrder, Type};
 use byteorder::{ByteOrder, LittleEndian};
 use data_type::*;
 use parquet_format::{Statistics as TStatistics};
+use schema::types::ColumnDescriptor;
+use util::float16::f16_to_f32;
 
 // Macro to generate methods create Statistics.
 macro_rules! statistics_new_func {
@@ -72,7 +75,26 @@ macro_rules! statistics_enum_func {
   });
 }
 
+// Macro to adjust min/max values of floating point statistics, so that they can be
+// safely used to skip data: NaN values are ignored by removing both min and max, and
+// zero min and max are widened to -0.0 and +0.0 respectively, since either zero may
+// have been written for both.
+macro_rules! float_min_max {
+  ($min:expr, $max:expr) => ({
+    match ($min, $max) {
+      (Some(min), Some(max)) if !min.is_nan() && !max.is_nan() => (
+        Some(if min == 0.0 { -0.0 } else { min }),
+        Some(if max == 0.0 { 0.0 } else { max })
+      ),
+      _ => (None, None)
+    }
+  });
+}
+
 /// Converts Thrift definition into `Statistics`.
+///
+/// Min and max values of FLOAT and DOUBLE statistics are adjusted to be safe for
+/// skipping data, see [`TypedStatistics::min`](struct.TypedStatistics.html#method.min).
 pub fn from_thrift(
   physical_type: Type,
   thrift_stats: Option<TStatistics>
@@ -147,22 +169,18 @@ pub fn from_thrift(
           Statistics::int96(min, max, distinct_count, null_count, old_format)
         },
         Type::FLOAT => {
-          Statistics::float(
+          let (min, max) = float_min_max!(
             min.map(|data| LittleEndian::read_f32(&data)),
-            max.map(|data| LittleEndian::read_f32(&data)),
-            distinct_count,
-            null_count,
-            old_format
-          )
+            max.map(|data| LittleEndian::read_f32(&data))
+          );
+          Statistics::float(min, max, distinct_count, null_count, old_format)
         },
         Type::DOUBLE => {
-          Statistics::double(
+          let (min, max) = float_min_max!(
             min.map(|data| LittleEndian::read_f64(&data)),
-            max.map(|data| LittleEndian::read_f64(&data)),
-            distinct_count,
-            null_count,
-            old_format
-          )
+            max.map(|data| LittleEndian::read_f64(&data))
+          );
+          Statistics::double(min, max, distinct_count, null_count, old_format)
         },
         Type::BYTE_ARRAY => {
           Statistics::byte_array(
@@ -190,6 +208,84 @@ pub fn from_thrift(
   }
 }
 
+/// Returns `true` if value `a` is greater than value `b`, where both are values of
+/// column `descr` encoded as min and max values of statistics, i.e. PLAIN encoded
+/// without length prefix. Values are compared according to the sort order of the
+/// column, see `ColumnOrder::get_sort_order`.
+///
+/// Returns `false` if sort order of the column is undefined, or either value is NaN.
+pub fn compare_greater(descr: &ColumnDescriptor, a: &[u8], b: &[u8]) -> bool {
+  let sort_order =
+    ColumnOrder::get_sort_order(descr.logical_type(), descr.physical_type());
+  compare_greater_in_order(descr, sort_order, a, b)
+}
+
+/// Returns `true` if value `a` is greater than value `b` of column `descr` in
+/// `sort_order`, which may differ from the sort order of the column for legacy
+/// statistics, see [`compare_greater`](fn.compare_greater.html).
+fn compare_greater_in_order(
+  descr: &ColumnDescriptor,
+  sort_order: SortOrder,
+  a: &[u8],
+  b: &[u8]
+) -> bool {
+  match (descr.physical_type(), sort_order) {
+    (_, SortOrder::UNDEFINED) => false,
+    (Type::INT32, SortOrder::UNSIGNED) => {
+      LittleEndian::read_u32(a) > LittleEndian::read_u32(b)
+    },
+    (Type::INT32, _) => LittleEndian::read_i32(a) > LittleEndian::read_i32(b),
+    (Type::INT64, SortOrder::UNSIGNED) => {
+      LittleEndian::read_u64(a) > LittleEndian::read_u64(b)
+    },
+    (Type::INT64, _) => LittleEndian::read_i64(a) > LittleEndian::read_i64(b),
+    (Type::FLOAT, _) => LittleEndian::read_f32(a) > LittleEndian::read_f32(b),
+    (Type::DOUBLE, _) => LittleEndian::read_f64(a) > LittleEndian::read_f64(b),
+    (Type::FIXED_LEN_BYTE_ARRAY, SortOrder::SIGNED)
+        if descr.logical_type() == LogicalType::FLOAT16 => {
+      f16_to_f32(LittleEndian::read_u16(a)) > f16_to_f32(LittleEndian::read_u16(b))
+    },
+    // Signed comparison of big-endian two's complement values, e.g. DECIMAL
+    (Type::BYTE_ARRAY, SortOrder::SIGNED) |
+    (Type::FIXED_LEN_BYTE_ARRAY, SortOrder::SIGNED) => {
+      compare_signed_bytes(a, b) == Ordering::Greater
+    },
+    // Unsigned byte-wise comparison, including BOOLEAN values
+    _ => a > b
+  }
+}
+
+/// Returns `true` if value of column `descr`, encoded as min or max value of
+/// statistics, is NaN. Such values should not be used as min or max values.
+pub fn is_nan(descr: &ColumnDescriptor, value: &[u8]) -> bool {
+  match descr.physical_type() {
+    Type::FLOAT => LittleEndian::read_f32(value).is_nan(),
+    Type::DOUBLE => LittleEndian::read_f64(value).is_nan(),
+    Type::FIXED_LEN_BYTE_ARRAY if descr.logical_type() == LogicalType::FLOAT16 => {
+      value.len() == 2 && f16_to_f32(LittleEndian::read_u16(value)).is_nan()
+    },
+    _ => false
+  }
+}
+
+/// Compares big-endian two's complement values of possibly different length.
+fn compare_signed_bytes(a: &[u8], b: &[u8]) -> Ordering {
+  let is_negative = |v: &[u8]| v.first().map(|x| x & 0x80 != 0).unwrap_or(false);
+  match (is_negative(a), is_negative(b)) {
+    (true, false) => Ordering::Less,
+    (false, true) => Ordering::Greater,
+    (negative, _) => {
+      // Sign-extend shorter value, then values compare as unsigned bytes
+      let len = cmp::max(a.len(), b.len());
+      let ext = if negative { 0xff } else { 0 };
+      let extend = |v: &[u8]| -> Vec<u8> {
+        iter::repeat(ext).take(len - v.len()).chain(v.iter().cloned()).collect()
+      };
+      extend(a).cmp(&extend(b))
+    }
+  }
+}
+
 // Convert Statistics into Thrift definition.
 pub fn to_thrift(stats: Option<&Statistics>) -> Option<TStatistics> {
   if stats.is_none() {
@@ -226,7 +322,7 @@ pub fn to_thrift(stats: Option<&Statistics>) -> Option<TStatistics> {
 }
 
 /// Statistics for a column chunk and data page.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Statistics {
   Boolean(TypedStatistics<BoolType>),
   Int32(TypedStatistics<Int32Type>),
@@ -288,6 +384,52 @@ impl Statistics {
     statistics_enum_func![self, has_min_max_set]
   }
 
+  /// Returns `true` if min value is the actual min value of the column, `false` if it
+  /// is a lower bound, e.g. when the value was truncated by the writer.
+  pub fn is_min_exact(&self) -> bool {
+    statistics_enum_func![self, is_min_exact]
+  }
+
+  /// Returns `true` if max value is the actual max value of the column, `false` if it
+  /// is an upper bound, e.g. when the value was truncated and incremented by the writer.
+  pub fn is_max_exact(&self) -> bool {
+    statistics_enum_func![self, is_max_exact]
+  }
+
+  /// Sets whether min and max values are exact and returns itself.
+  ///
+  /// Exactness is stored in column chunk metadata of the file footer. Min and max values
+  /// of column chunks without it, e.g. in files of older writers, are read as inexact,
+  /// while statistics of pages are always read as exact.
+  pub fn with_min_max_exact(self, is_min_exact: bool, is_max_exact: bool) -> Self {
+    match self {
+      Statistics::Boolean(typed) => {
+        Statistics::Boolean(typed.with_min_max_exact(is_min_exact, is_max_exact))
+      },
+      Statistics::Int32(typed) => {
+        Statistics::Int32(typed.with_min_max_exact(is_min_exact, is_max_exact))
+      },
+      Statistics::Int64(typed) => {
+        Statistics::Int64(typed.with_min_max_exact(is_min_exact, is_max_exact))
+      },
+      Statistics::Int96(typed) => {
+        Statistics::Int96(typed.with_min_max_exact(is_min_exact, is_max_exact))
+      },
+      Statistics::Float(typed) => {
+        Statistics::Float(typed.with_min_max_exact(is_min_exact, is_max_exact))
+      },
+      Statistics::Double(typed) => {
+        Statistics::Double(typed.with_min_max_exact(is_min_exact, is_max_exact))
+      },
+      Statistics::ByteArray(typed) => {
+        Statistics::ByteArray(typed.with_min_max_exact(is_min_exact, is_max_exact))
+      },
+      Statistics::FixedLenByteArray(typed) => Statistics::FixedLenByteArray(
+        typed.with_min_max_exact(is_min_exact, is_max_exact)
+      )
+    }
+  }
+
   /// Returns slice of bytes that represent min value.
   /// Panics if min value is not set.
   pub fn min_bytes(&self) -> &[u8] {
@@ -300,6 +442,111 @@ impl Statistics {
     statistics_enum_func![self, max_bytes]
   }
 
+  /// Returns min and max values as decimals with `precision` and `scale`, for
+  /// statistics of INT32, INT64, BYTE_ARRAY and FIXED_LEN_BYTE_ARRAY columns with
+  /// DECIMAL logical type.
+  ///
+  /// Returns `None` if min and max values are not set, or statistics are of other
+  /// physical type.
+  pub fn decimal_min_max(
+    &self,
+    precision: i32,
+    scale: i32
+  ) -> Option<(Decimal, Decimal)> {
+    if !self.has_min_max_set() {
+      return None;
+    }
+    match *self {
+      Statistics::Int32(ref typed) => Some((
+        Decimal::from_i32(*typed.min(), precision, scale),
+        Decimal::from_i32(*typed.max(), precision, scale)
+      )),
+      Statistics::Int64(ref typed) => Some((
+        Decimal::from_i64(*typed.min(), precision, scale),
+        Decimal::from_i64(*typed.max(), precision, scale)
+      )),
+      Statistics::ByteArray(ref typed) => Some((
+        Decimal::from_bytes(typed.min().clone(), precision, scale),
+        Decimal::from_bytes(typed.max().clone(), precision, scale)
+      )),
+      Statistics::FixedLenByteArray(ref typed) => Some((
+        Decimal::from_bytes(typed.min().clone(), precision, scale),
+        Decimal::from_bytes(typed.max().clone(), precision, scale)
+      )),
+      _ => None
+    }
+  }
+
+  /// Returns min and max values as `f32` for statistics of FIXED_LEN_BYTE_ARRAY columns
+  /// with FLOAT16 logical type.
+  ///
+  /// Returns `None` if min and max values are not set, are not 2 bytes, or statistics
+  /// are of other physical type. NaN values are returned as is and, as for FLOAT and
+  /// DOUBLE statistics, should be ignored.
+  pub fn float16_min_max(&self) -> Option<(f32, f32)> {
+    if !self.has_min_max_set() {
+      return None;
+    }
+    match *self {
+      Statistics::FixedLenByteArray(ref typed) => {
+        match (typed.min().f16_to_f32(), typed.max().f16_to_f32()) {
+          (Ok(min), Ok(max)) => Some((min, max)),
+          _ => None
+        }
+      },
+      _ => None
+    }
+  }
+
+  /// Returns sort order of min and max values of these statistics for column `descr`
+  /// with `column_order` defined in file metadata.
+  ///
+  /// Legacy writers compared all values as signed, so deprecated `min` and `max` fields,
+  /// as well as statistics of files with undefined column order, are only usable for
+  /// columns with signed sort order, e.g. not for UINT_32 or UTF8 columns. Otherwise
+  /// sort order is undefined, meaning that min and max values can not be used.
+  pub fn sort_order(
+    &self,
+    descr: &ColumnDescriptor,
+    column_order: ColumnOrder
+  ) -> SortOrder {
+    let sort_order =
+      ColumnOrder::get_sort_order(descr.logical_type(), descr.physical_type());
+    let is_legacy =
+      self.is_min_max_deprecated() || column_order == ColumnOrder::UNDEFINED;
+    if is_legacy && sort_order != SortOrder::SIGNED {
+      SortOrder::UNDEFINED
+    } else {
+      sort_order
+    }
+  }
+
+  /// Returns `false` if column chunk or page with these statistics can not contain
+  /// `value`, i.e. `value` is less than min value or greater than max value in the
+  /// [sort order](#method.sort_order) of the statistics. Value is encoded the same way
+  /// as min and max values, see [`compare_greater`](fn.compare_greater.html).
+  ///
+  /// Returns `true` if min and max values are not set, sort order is undefined or
+  /// value is NaN, since data can not be skipped in these cases.
+  /// Inexact min and max values are still bounds of the values, so they can be used.
+  pub fn may_contain(
+    &self,
+    descr: &ColumnDescriptor,
+    column_order: ColumnOrder,
+    value: &[u8]
+  ) -> bool {
+    if !self.has_min_max_set() || is_nan(descr, value) {
+      return true;
+    }
+    match self.sort_order(descr, column_order) {
+      SortOrder::UNDEFINED => true,
+      sort_order => {
+        !compare_greater_in_order(descr, sort_order, self.min_bytes(), value) &&
+          !compare_greater_in_order(descr, sort_order, value, self.max_bytes())
+      }
+    }
+  }
+
   /// Returns physical type associated with statistics.
   pub fn physical_type(&self) -> Type {
     match self {
@@ -337,7 +584,9 @@ pub struct TypedStatistics<T: DataType> {
   // Distinct count could be omitted in some cases
   distinct_count: Option<u64>,
   null_count: u64,
-  is_min_max_deprecated: bool
+  is_min_max_deprecated: bool,
+  is_min_exact: bool,
+  is_max_exact: bool
 }
 
 impl<T: DataType> TypedStatistics<T> {
@@ -354,14 +603,28 @@ impl<T: DataType> TypedStatistics<T> {
       max: max,
       distinct_count: distinct_count,
       null_count: null_count,
-      is_min_max_deprecated: is_min_max_deprecated
+      is_min_max_deprecated: is_min_max_deprecated,
+      is_min_exact: true,
+      is_max_exact: true
     }
   }
 
+  /// Sets whether min and max values are exact and returns itself.
+  /// By default, both values are exact.
+  pub fn with_min_max_exact(mut self, is_min_exact: bool, is_max_exact: bool) -> Self {
+    self.is_min_exact = is_min_exact;
+    self.is_max_exact = is_max_exact;
+    self
+  }
+
   /// Returns min value of the statistics.
   ///
   /// Panics if min value is not set, e.g. all values are `null`.
   /// Use `has_min_max_set` method to check that.
+  ///
+  /// For FLOAT and DOUBLE statistics read from a file, min and max values are not set
+  /// if either is NaN, and zero min and max values are returned as -0.0 and +0.0
+  /// respectively, since the column may contain zeros of both signs.
   pub fn min(&self) -> &T::T {
     self.min.as_ref().unwrap()
   }
@@ -410,6 +673,30 @@ impl<T: DataType> TypedStatistics<T> {
   fn is_min_max_deprecated(&self) -> bool {
     self.is_min_max_deprecated
   }
+
+  /// Returns `true` if min value is exact.
+  fn is_min_exact(&self) -> bool {
+    self.is_min_exact
+  }
+
+  /// Returns `true` if max value is exact.
+  fn is_max_exact(&self) -> bool {
+    self.is_max_exact
+  }
+}
+
+impl<T: DataType> Clone for TypedStatistics<T> {
+  fn clone(&self) -> Self {
+    Self {
+      min: self.min.clone(),
+      max: self.max.clone(),
+      distinct_count: self.distinct_count,
+      null_count: self.null_count,
+      is_min_max_deprecated: self.is_min_max_deprecated,
+      is_min_exact: self.is_min_exact,
+      is_max_exact: self.is_max_exact
+    }
+  }
 }
 
 impl<T: DataType> fmt::Display for TypedStatistics<T> {
@@ -453,7 +740,9 @@ impl<T: DataType> cmp::PartialEq for TypedStatistics<T> {
     self.min == other.min && self.max == other.max &&
       self.distinct_count == other.distinct_count &&
       self.null_count == other.null_count &&
-      self.is_min_max_deprecated == other.is_min_max_deprecated
+      self.is_min_max_deprecated == other.is_min_max_deprecated &&
+      self.is_min_exact == other.is_min_exact &&
+      self.is_max_exact == other.is_max_exact
   }
 }
 
@@ -493,6 +782,10 @@ impl ValueDisplay<FixedLenByteArrayType> for TypedStatistics<FixedLenByteArrayTy
 mod tests {
   use super::*;
 
+  use std::rc::Rc;
+
+  use schema::types::{ColumnPath, Type as SchemaType};
+
   #[test]
   fn test_statistics_min_max_bytes() {
     let stats = Statistics::int32(Some(-123), Some(234), None, 1, false);
@@ -511,6 +804,53 @@ mod tests {
     assert_eq!(stats.max_bytes(), &[3, 4, 5]);
   }
 
+  #[test]
+  fn test_statistics_decimal_min_max() {
+    let stats = Statistics::int32(Some(-123), Some(234), None, 1, false);
+    let (min, max) = stats.decimal_min_max(5, 2).unwrap();
+    assert_eq!(min, Decimal::from_i32(-123, 5, 2));
+    assert_eq!(max.to_i128().unwrap(), 234);
+
+    let stats = Statistics::fixed_len_byte_array(
+      Some(ByteArray::from(vec![0xff, 0x38])),
+      Some(ByteArray::from(vec![0x01, 0x00])),
+      None, 0,
+      false
+    );
+    let (min, max) = stats.decimal_min_max(4, 1).unwrap();
+    assert_eq!(min.to_i128().unwrap(), -200);
+    assert_eq!(max.to_i128().unwrap(), 256);
+    assert_eq!(max.scale(), 1);
+
+    let stats = Statistics::int64(None, None, None, 1, false);
+    assert_eq!(stats.decimal_min_max(5, 2), None);
+    let stats = Statistics::double(Some(1.0), Some(2.0), None, 0, false);
+    assert_eq!(stats.decimal_min_max(5, 2), None);
+  }
+
+  #[test]
+  fn test_statistics_float16_min_max() {
+    let stats = Statistics::fixed_len_byte_array(
+      Some(ByteArray::from_f32_as_f16(-1.5)),
+      Some(ByteArray::from_f32_as_f16(::std::f32::NAN)),
+      None, 0,
+      false
+    );
+    let (min, max) = stats.float16_min_max().unwrap();
+    assert_eq!(min, -1.5);
+    assert!(max.is_nan());
+
+    let stats = Statistics::fixed_len_byte_array(
+      Some(ByteArray::from(vec![1, 2, 3])),
+      Some(ByteArray::from(vec![1, 2, 3])),
+      None, 0,
+      false
+    );
+    assert_eq!(stats.float16_min_max(), None);
+    let stats = Statistics::double(Some(1.0), Some(2.0), None, 0, false);
+    assert_eq!(stats.float16_min_max(), None);
+  }
+
   #[test]
   #[should_panic(expected = "Statistics null count is negative (-10)")]
   fn test_statistics_negative_null_count() {
@@ -683,4 +1023,127 @@ mod tests {
     );
     check_stats(Statistics::fixed_len_byte_array(None, None, None, 7, true));
   }
+
+  #[test]
+  fn test_statistics_from_thrift_float_min_max() {
+    // Helper method to convert float statistics through Thrift.
+    fn float_stats(min: f32, max: f32) -> Statistics {
+      let stats = Statistics::float(Some(min), Some(max), None, 0, false);
+      from_thrift(Type::FLOAT, to_thrift(Some(&stats))).unwrap()
+    }
+
+    let stats = float_stats(0.0, 0.0);
+    assert!(stats.has_min_max_set());
+    if let Statistics::Float(ref typed) = stats {
+      assert!(typed.min().is_sign_negative());
+      assert!(typed.max().is_sign_positive());
+    }
+    let stats = float_stats(-0.0, -0.0);
+    if let Statistics::Float(ref typed) = stats {
+      assert!(typed.min().is_sign_negative());
+      assert!(typed.max().is_sign_positive());
+    }
+    assert!(!float_stats(::std::f32::NAN, 1.0).has_min_max_set());
+    assert!(!float_stats(1.0, ::std::f32::NAN).has_min_max_set());
+    assert_eq!(
+      float_stats(-1.5, 2.5),
+      Statistics::float(Some(-1.5), Some(2.5), None, 0, false)
+    );
+
+    let stats = Statistics::double(Some(::std::f64::NAN), Some(1.0), None, 3, false);
+    let stats = from_thrift(Type::DOUBLE, to_thrift(Some(&stats))).unwrap();
+    assert!(!stats.has_min_max_set());
+    assert_eq!(stats.null_count(), 3);
+  }
+
+  #[test]
+  fn test_statistics_compare_greater() {
+    // Helper method to create column descriptor.
+    fn descr(tpe: Type, logical_type: LogicalType, length: i32) -> ColumnDescriptor {
+      let tpe = SchemaType::primitive_type_builder("a", tpe)
+        .with_logical_type(logical_type)
+        .with_length(length)
+        .with_precision(if logical_type == LogicalType::DECIMAL { 2 } else { -1 })
+        .with_scale(if logical_type == LogicalType::DECIMAL { 0 } else { -1 })
+        .build()
+        .unwrap();
+      ColumnDescriptor::new(Rc::new(tpe), None, 0, 0, ColumnPath::from("a"))
+    }
+
+    let int32 = descr(Type::INT32, LogicalType::NONE, -1);
+    assert!(compare_greater(&int32, 1.as_bytes(), (-1).as_bytes()));
+    assert!(!compare_greater(&int32, (-1).as_bytes(), 1.as_bytes()));
+    let uint32 = descr(Type::INT32, LogicalType::UINT_32, -1);
+    assert!(compare_greater(&uint32, (-1).as_bytes(), 1.as_bytes()));
+    let uint64 = descr(Type::INT64, LogicalType::UINT_64, -1);
+    assert!(compare_greater(&uint64, (-1i64).as_bytes(), 1i64.as_bytes()));
+
+    let float = descr(Type::FLOAT, LogicalType::NONE, -1);
+    assert!(compare_greater(&float, 1.5f32.as_bytes(), (-2.0f32).as_bytes()));
+    assert!(!compare_greater(&float, ::std::f32::NAN.as_bytes(), 1.0f32.as_bytes()));
+    assert!(is_nan(&float, ::std::f32::NAN.as_bytes()));
+    assert!(!is_nan(&float, 1.0f32.as_bytes()));
+    let double = descr(Type::DOUBLE, LogicalType::NONE, -1);
+    assert!(compare_greater(&double, 0.5f64.as_bytes(), (-0.5f64).as_bytes()));
+    assert!(is_nan(&double, ::std::f64::NAN.as_bytes()));
+
+    // FLOAT16 values 1.0 and -2.0
+    let float16 = descr(Type::FIXED_LEN_BYTE_ARRAY, LogicalType::FLOAT16, 2);
+    assert!(compare_greater(&float16, &[0x00, 0x3c], &[0x00, 0xc0]));
+    assert!(!compare_greater(&float16, &[0x00, 0xc0], &[0x00, 0x3c]));
+    assert!(is_nan(&float16, &[0x01, 0x7e]));
+
+    let decimal = descr(Type::FIXED_LEN_BYTE_ARRAY, LogicalType::DECIMAL, 2);
+    assert!(compare_greater(&decimal, &[0x00, 0x01], &[0xff, 0xff]));
+    assert!(compare_greater(&decimal, &[0xff, 0xff], &[0xff, 0xfe]));
+    assert!(!compare_greater(&decimal, &[0x80, 0x00], &[0x7f, 0xff]));
+
+    let utf8 = descr(Type::BYTE_ARRAY, LogicalType::UTF8, -1);
+    assert!(compare_greater(&utf8, b"b", b"abc"));
+    assert!(compare_greater(&utf8, &[0xff], &[0x01]));
+
+    let int96 = descr(Type::INT96, LogicalType::NONE, -1);
+    assert!(!compare_greater(&int96, &[1; 12], &[0; 12]));
+  }
+
+  #[test]
+  fn test_statistics_sort_order() {
+    let tpe = SchemaType::primitive_type_builder("a", Type::INT32)
+      .with_logical_type(LogicalType::UINT_32)
+      .build()
+      .unwrap();
+    let uint32 = ColumnDescriptor::new(Rc::new(tpe), None, 0, 0, ColumnPath::from("a"));
+    let tpe = SchemaType::primitive_type_builder("b", Type::INT32).build().unwrap();
+    let int32 = ColumnDescriptor::new(Rc::new(tpe), None, 0, 0, ColumnPath::from("b"));
+    let type_order = ColumnOrder::TYPE_DEFINED_ORDER(SortOrder::SIGNED);
+
+    // Values from 1 to u32::MAX, where u32::MAX is written as -1
+    let stats = Statistics::int32(Some(1), Some(-1), None, 0, false);
+    assert_eq!(stats.sort_order(&uint32, type_order), SortOrder::UNSIGNED);
+    assert!(stats.may_contain(&uint32, type_order, 1.as_bytes()));
+    assert!(stats.may_contain(&uint32, type_order, 100.as_bytes()));
+    assert!(stats.may_contain(&uint32, type_order, (-2).as_bytes()));
+    assert!(!stats.may_contain(&uint32, type_order, 0.as_bytes()));
+
+    // Legacy statistics are only usable for signed sort order
+    assert_eq!(
+      stats.sort_order(&uint32, ColumnOrder::UNDEFINED),
+      SortOrder::UNDEFINED
+    );
+    assert!(stats.may_contain(&uint32, ColumnOrder::UNDEFINED, 0.as_bytes()));
+    let stats = Statistics::int32(Some(1), Some(-1), None, 0, true);
+    assert_eq!(stats.sort_order(&uint32, type_order), SortOrder::UNDEFINED);
+    assert!(stats.may_contain(&uint32, type_order, 0.as_bytes()));
+
+    let stats = Statistics::int32(Some(-10), Some(10), None, 0, true);
+    assert_eq!(stats.sort_order(&int32, ColumnOrder::UNDEFINED), SortOrder::SIGNED);
+    assert!(stats.may_contain(&int32, ColumnOrder::UNDEFINED, (-10).as_bytes()));
+    assert!(stats.may_contain(&int32, ColumnOrder::UNDEFINED, 0.as_bytes()));
+    assert!(!stats.may_contain(&int32, ColumnOrder::UNDEFINED, 11.as_bytes()));
+    assert!(!stats.may_contain(&int32, type_order, (-11).as_bytes()));
+
+    // Statistics without min and max values
+    let stats = Statistics::int32(None, None, None, 3, false);
+    assert!(stats.may_contain(&int32, type_order, 11.as_bytes()));
+  }
 }
@@ -0,0 +1,242 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains API to work with summary metadata files of datasets, also known as
+//! `_metadata` sidecar files, written by Hive, Spark and Dask.
+//!
+//! Summary metadata file has the same layout as a Parquet file without any data: magic
+//! bytes, followed by file metadata and footer. File metadata contains row groups of
+//! all files of the dataset, where each column chunk records path of the file it
+//! belongs to, see [`ColumnChunkMetaData::file_path`]. This allows to plan reads of a
+//! dataset with a single read of the summary file instead of reading footers of all
+//! files.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//! use parquet::file::summary::{merge_metadata, read_metadata_file, write_metadata_file};
+//! # use std::fs;
+//! # let path = std::env::temp_dir().join("summary_example_metadata");
+//!
+//! let mut files = Vec::new();
+//! for name in &["alltypes_plain.parquet", "alltypes_plain.snappy.parquet"] {
+//!   let file = File::open(format!("data/{}", name)).unwrap();
+//!   let reader = SerializedFileReader::new(file).unwrap();
+//!   files.push((name.to_string(), reader.metadata()));
+//! }
+//! let metadata = merge_metadata(&files).unwrap();
+//! write_metadata_file(File::create(&path).unwrap(), &metadata).unwrap();
+//!
+//! let metadata = read_metadata_file(File::open(&path).unwrap()).unwrap();
+//! assert_eq!(metadata.file_metadata().num_rows(), 10);
+//! let file_paths: Vec<_> = metadata.row_groups().iter()
+//!   .map(|row_group| row_group.file_path().unwrap().as_str())
+//!   .collect();
+//! assert_eq!(file_paths, vec!["alltypes_plain.parquet", "alltypes_plain.snappy.parquet"]);
+//! # fs::remove_file(&path).unwrap();
+//! ```
+//!
+//! [`ColumnChunkMetaData::file_path`]: ../metadata/struct.ColumnChunkMetaData.html
+
+use std::io::Write;
+use std::rc::Rc;
+
+use byteorder::{ByteOrder, LittleEndian};
+use errors::{ParquetError, Result};
+use file::{FOOTER_SIZE, PARQUET_MAGIC};
+use file::metadata::{FileMetaData, ParquetMetaData, ParquetMetaDataPtr, RowGroupMetaData};
+use file::reader::{FileReader, ParquetReader, SerializedFileReader};
+
+/// Combines metadata of files into summary metadata, where `files` are pairs of file
+/// path, usually relative to the directory of the summary file, and metadata of the
+/// file. Row groups are kept in order of `files`, and path of the file is set for
+/// each column chunk.
+///
+/// Schema, version, writer, column orders, key-value metadata and fields unknown to
+/// this version of the crate are taken from metadata of the first file. Returns error if `files` is empty, or if schema of any file does not match
+/// schema of the first file.
+pub fn merge_metadata(files: &[(String, ParquetMetaDataPtr)]) -> Result<ParquetMetaData> {
+  let first = match files.first() {
+    Some((_, metadata)) => metadata.file_metadata(),
+    None => return Err(general_err!("Cannot merge metadata of zero files"))
+  };
+  let schema_descr = first.schema_descr_ptr();
+
+  let mut num_rows = 0;
+  let mut row_groups = Vec::new();
+  for (path, metadata) in files {
+    let file_metadata = metadata.file_metadata();
+    if let Err(e) = schema_descr.check_compatible(file_metadata.schema_descr()) {
+      return Err(general_err!(
+        "Cannot merge metadata of file {}: {}",
+        path,
+        e.message()
+      ));
+    }
+    num_rows += file_metadata.num_rows();
+    for row_group in metadata.row_groups() {
+      let unknown_fields = row_group.to_thrift_unknown_fields()?;
+      let mut row_group = row_group.to_thrift();
+      for column in row_group.columns.iter_mut() {
+        column.file_path = Some(path.clone());
+      }
+      let row_group = RowGroupMetaData::from_thrift_with_unknown_enums(
+        schema_descr.clone(), row_group, &unknown_fields)?;
+      row_groups.push(Rc::new(row_group));
+    }
+  }
+
+  let file_metadata = FileMetaData::new(
+    first.version(),
+    num_rows,
+    first.created_by().clone(),
+    first.schema_descr().root_schema_ptr(),
+    schema_descr,
+    first.column_orders().cloned()
+  )
+  .with_key_value_metadata(first.key_value_metadata().cloned())
+  .with_unknown_fields(first.unknown_fields().clone());
+  Ok(ParquetMetaData::new(file_metadata, row_groups))
+}
+
+/// Writes `metadata` into `sink` as summary metadata file, which can be read back with
+/// [`read_metadata_file`].
+pub fn write_metadata_file<W: Write>(mut sink: W, metadata: &ParquetMetaData) -> Result<()> {
+  let buf = metadata.to_bytes()?;
+  let mut footer_buffer: [u8; FOOTER_SIZE] = [0; FOOTER_SIZE];
+  LittleEndian::write_i32(&mut footer_buffer, buf.len() as i32);
+  footer_buffer[4..].copy_from_slice(&PARQUET_MAGIC);
+
+  sink.write_all(&PARQUET_MAGIC)?;
+  sink.write_all(&buf)?;
+  sink.write_all(&footer_buffer)?;
+  sink.flush()?;
+  Ok(())
+}
+
+/// Reads summary metadata file from `reader`. Path of the file that contains each row
+/// group is available with [`RowGroupMetaData::file_path`].
+///
+/// [`RowGroupMetaData::file_path`]:
+/// ../metadata/struct.RowGroupMetaData.html#method.file_path
+pub fn read_metadata_file<R: 'static + ParquetReader>(
+  reader: R
+) -> Result<ParquetMetaDataPtr> {
+  Ok(SerializedFileReader::new(reader)?.metadata())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::fs::File;
+
+  use util::test_common::{get_temp_file, get_test_file};
+
+  fn get_metadata(file_name: &str) -> ParquetMetaDataPtr {
+    SerializedFileReader::new(get_test_file(file_name)).unwrap().metadata()
+  }
+
+  #[test]
+  fn test_merge_metadata() {
+    let files = vec![
+      ("a/alltypes_plain.parquet".to_owned(), get_metadata("alltypes_plain.parquet")),
+      ("b.parquet".to_owned(), get_metadata("alltypes_dictionary.parquet")),
+      ("c.parquet".to_owned(), get_metadata("alltypes_plain.snappy.parquet"))
+    ];
+    let metadata = merge_metadata(&files).unwrap();
+    let file_metadata = metadata.file_metadata();
+    assert_eq!(file_metadata.num_rows(), 12);
+    assert_eq!(file_metadata.schema_descr().num_columns(), 11);
+    assert_eq!(metadata.num_row_groups(), 3);
+
+    for (i, (path, original)) in files.iter().enumerate() {
+      let row_group = metadata.row_group(i);
+      let original_row_group = original.row_group(0);
+      assert_eq!(row_group.file_path(), Some(path));
+      assert_eq!(row_group.num_rows(), original_row_group.num_rows());
+      for (column, original_column) in
+          row_group.columns().iter().zip(original_row_group.columns()) {
+        assert_eq!(column.file_path(), Some(path));
+        assert_eq!(column.data_page_offset(), original_column.data_page_offset());
+        assert_eq!(column.compression(), original_column.compression());
+      }
+    }
+  }
+
+  #[test]
+  fn test_merge_metadata_invalid() {
+    assert_eq!(
+      merge_metadata(&[]).err().unwrap(),
+      general_err!("Cannot merge metadata of zero files")
+    );
+
+    let files = vec![
+      ("a.parquet".to_owned(), get_metadata("alltypes_plain.parquet")),
+      ("b.parquet".to_owned(), get_metadata("nested_lists.snappy.parquet"))
+    ];
+    assert_eq!(
+      merge_metadata(&files).err().unwrap(),
+      general_err!(
+        "Cannot merge metadata of file b.parquet: \
+         Schema with 2 columns does not match schema with 11 columns"
+      )
+    );
+  }
+
+  #[test]
+  fn test_write_and_read_metadata_file() {
+    let files = vec![
+      ("a.parquet".to_owned(), get_metadata("alltypes_plain.parquet")),
+      ("b.parquet".to_owned(), get_metadata("alltypes_plain.snappy.parquet"))
+    ];
+    let metadata = merge_metadata(&files).unwrap();
+    let mut buf = Vec::new();
+    write_metadata_file(&mut buf, &metadata).unwrap();
+    assert_eq!(&buf[0..4], &PARQUET_MAGIC);
+
+    let file = get_temp_file("summary_metadata", &buf);
+    let result = read_metadata_file(file).unwrap();
+    let file_metadata = result.file_metadata();
+    let expected_file_metadata = metadata.file_metadata();
+    assert_eq!(file_metadata.num_rows(), expected_file_metadata.num_rows());
+    assert_eq!(file_metadata.version(), expected_file_metadata.version());
+    assert_eq!(file_metadata.created_by(), expected_file_metadata.created_by());
+    assert_eq!(file_metadata.schema(), expected_file_metadata.schema());
+    assert_eq!(file_metadata.column_orders(), expected_file_metadata.column_orders());
+    assert_eq!(
+      file_metadata.key_value_metadata(),
+      expected_file_metadata.key_value_metadata()
+    );
+    assert_eq!(result.num_row_groups(), 2);
+    for i in 0..result.num_row_groups() {
+      assert_eq!(result.row_group(i).to_thrift(), metadata.row_group(i).to_thrift());
+    }
+    assert_eq!(result.row_group(1).file_path().unwrap(), "b.parquet");
+  }
+
+  #[test]
+  fn test_read_metadata_file_of_data_file() {
+    // Footer of a regular file is a valid summary of itself without file paths
+    let file: File = get_test_file("alltypes_plain.parquet");
+    let metadata = read_metadata_file(file).unwrap();
+    assert_eq!(metadata.num_row_groups(), 1);
+    assert_eq!(metadata.row_group(0).file_path(), None);
+  }
+}
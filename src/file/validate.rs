@@ -0,0 +1,1216 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains API to validate Parquet files and to rewrite a repaired copy of a file.
+//!
+//! [`validate_file`] walks file footer, page headers of all column chunks, checksums,
+//! statistics and offset indexes, and returns [`ValidationReport`] with all issues
+//! found instead of failing on the first one. Each issue has a location in the file,
+//! see [`ErrorContext`](`::errors::ErrorContext`).
+//!
+//! [`repair_file`] rewrites data that can be read in lenient mode into a new file.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use parquet::file::validate::validate_file;
+//!
+//! let file = File::open("data/alltypes_plain.parquet").unwrap();
+//! let report = validate_file(file);
+//! for issue in report.issues() {
+//!   println!("{}", issue);
+//! }
+//! assert!(report.is_valid());
+//! assert_eq!(report.num_row_groups(), 1);
+//! ```
+
+use std::cmp;
+use std::fmt;
+use std::io::{Read, SeekFrom, Write};
+use std::rc::Rc;
+
+use basic::{ColumnOrder, LogicalType, SortOrder, Type};
+use byteorder::{ByteOrder, LittleEndian};
+use column::reader::{ColumnReader, ColumnReaderImpl};
+use column::writer::{ColumnWriter, ColumnWriterImpl};
+use compression::{create_codec, Codec};
+use data_type::DataType;
+use errors::{ErrorContext, ParquetError, Result};
+use file::{FOOTER_SIZE, PARQUET_MAGIC};
+use file::metadata::{ColumnChunkMetaData, RowGroupMetaData};
+use file::properties::WriterPropertiesPtr;
+use file::reader::{check_page_crc, check_page_header};
+use file::reader::{FileReader, ParquetReader, RowGroupReader, SerializedFileReader};
+use file::statistics::{self, Statistics};
+use file::writer::{FileWriter, SerializedFileWriter};
+use parquet_format::{ColumnChunk, ColumnIndex, FileMetaData as TFileMetaData};
+use parquet_format::{OffsetIndex, PageHeader, PageType, RowGroup};
+use parquet_format::Statistics as TStatistics;
+use schema::types::{self, ColumnDescriptor, SchemaDescPtr, SchemaDescriptor};
+use thrift;
+use thrift::protocol::{TCompactInputProtocol, TInputProtocol};
+use util::io::{FileSource, Position};
+
+// ----------------------------------------------------------------------
+// Validation report
+
+/// Kind of issue found by [`validate_file`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IssueKind {
+  /// Invalid magic bytes, metadata length, file metadata or schema.
+  Footer,
+  /// Invalid row group metadata.
+  RowGroup,
+  /// Column chunk is out of bounds, or its number of values does not match pages.
+  ColumnChunk,
+  /// Page header cannot be parsed, or page sizes or number of values are invalid.
+  PageHeader,
+  /// Page checksum does not match page bytes.
+  Checksum,
+  /// Page cannot be decompressed.
+  Compression,
+  /// Statistics are inconsistent, e.g. min value is larger than max value.
+  Statistics,
+  /// Offset index is out of bounds or does not match pages of column chunk.
+  OffsetIndex,
+  /// Column index is out of bounds or does not match pages of column chunk.
+  ColumnIndex
+}
+
+impl fmt::Display for IssueKind {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// Issue found by [`validate_file`] with location in the file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationIssue {
+  kind: IssueKind,
+  context: ErrorContext,
+  message: String
+}
+
+impl ValidationIssue {
+  /// Returns kind of this issue.
+  pub fn kind(&self) -> IssueKind {
+    self.kind
+  }
+
+  /// Returns location of this issue, e.g. row group, column path and page.
+  pub fn context(&self) -> &ErrorContext {
+    &self.context
+  }
+
+  /// Returns description of this issue.
+  pub fn message(&self) -> &str {
+    &self.message
+  }
+}
+
+impl fmt::Display for ValidationIssue {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}: {} ({})", self.kind, self.message, self.context)
+  }
+}
+
+/// Result of [`validate_file`].
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+  issues: Vec<ValidationIssue>,
+  num_row_groups: usize,
+  num_pages: usize
+}
+
+impl ValidationReport {
+  /// Returns `true` if no issues were found.
+  pub fn is_valid(&self) -> bool {
+    self.issues.is_empty()
+  }
+
+  /// Returns all issues in the order they were found.
+  pub fn issues(&self) -> &[ValidationIssue] {
+    &self.issues
+  }
+
+  /// Returns number of row groups in file metadata.
+  pub fn num_row_groups(&self) -> usize {
+    self.num_row_groups
+  }
+
+  /// Returns number of pages checked, including dictionary pages.
+  pub fn num_pages(&self) -> usize {
+    self.num_pages
+  }
+}
+
+// ----------------------------------------------------------------------
+// Validation
+
+/// Validates Parquet file and returns report with all issues found.
+///
+/// Following is checked:
+/// - magic bytes, metadata length, file metadata and schema;
+/// - row group metadata, total number of rows and bounds of column chunks;
+/// - page headers, page sizes, checksums and that pages can be decompressed;
+/// - that number of values of each column chunk matches its data pages;
+/// - statistics of column chunks and pages: min value is not larger than max value with
+/// respect to the sort order of the column, null count does not exceed number of
+/// values;
+/// - bounds of offset and column indexes, and that offset index matches data pages.
+///
+/// Validation stops only if file metadata cannot be read; issues of a column chunk do
+/// not affect validation of other column chunks.
+pub fn validate_file<R: ParquetReader>(reader: R) -> ValidationReport {
+  let mut validator = Validator {
+    reader: reader,
+    report: ValidationReport::default(),
+    metadata_start: 0
+  };
+  validator.validate();
+  validator.report
+}
+
+/// Walks the file and collects issues.
+struct Validator<R: ParquetReader> {
+  reader: R,
+  report: ValidationReport,
+  // Start of file metadata, column chunks and indexes are located before it
+  metadata_start: u64
+}
+
+/// Location and number of values of a data page.
+struct DataPageInfo {
+  offset: i64,
+  // Size of page including page header
+  size: i64,
+  num_values: i64,
+  // Number of rows, when known, e.g. for data page v2 or non-repeated columns
+  num_rows: Option<i64>
+}
+
+impl<R: ParquetReader> Validator<R> {
+  fn add(&mut self, kind: IssueKind, context: ErrorContext, message: String) {
+    self.report.issues.push(ValidationIssue {
+      kind: kind,
+      context: context,
+      message: message
+    });
+  }
+
+  fn add_error(&mut self, kind: IssueKind, context: ErrorContext, err: ParquetError) {
+    let message = err.message().to_owned();
+    let context = err.with_context(context).context().cloned().unwrap_or_default();
+    self.add(kind, context, message);
+  }
+
+  fn validate(&mut self) {
+    let mut t_file_metadata = match self.read_footer() {
+      Ok(metadata) => metadata,
+      Err(e) => return self.add_error(IssueKind::Footer, ErrorContext::new(), e)
+    };
+    let schema = match types::from_thrift(&mut t_file_metadata.schema) {
+      Ok(schema) => schema,
+      Err(e) => return self.add_error(IssueKind::Footer, ErrorContext::new(), e)
+    };
+    let schema_descr = Rc::new(SchemaDescriptor::new(schema));
+    if let Some(ref orders) = t_file_metadata.column_orders {
+      if orders.len() != schema_descr.num_columns() {
+        self.add(
+          IssueKind::Footer,
+          ErrorContext::new(),
+          format!(
+            "Number of column orders {} does not match number of columns {}",
+            orders.len(),
+            schema_descr.num_columns()
+          )
+        );
+      }
+    }
+
+    self.report.num_row_groups = t_file_metadata.row_groups.len();
+    let mut num_rows = 0;
+    for (i, row_group) in t_file_metadata.row_groups.into_iter().enumerate() {
+      num_rows += row_group.num_rows;
+      self.validate_row_group(i, &schema_descr, row_group);
+    }
+    if num_rows != t_file_metadata.num_rows {
+      self.add(
+        IssueKind::Footer,
+        ErrorContext::new(),
+        format!(
+          "Number of rows {} does not match number of rows in row groups {}",
+          t_file_metadata.num_rows,
+          num_rows
+        )
+      );
+    }
+  }
+
+  // See `SerializedFileReader::parse_metadata` for layout of the footer.
+  fn read_footer(&mut self) -> Result<TFileMetaData> {
+    let file_size = self.reader.len();
+    if file_size < (PARQUET_MAGIC.len() + FOOTER_SIZE) as u64 {
+      return Err(general_err!(
+        "File size {} is smaller than magic bytes and footer",
+        file_size
+      ));
+    }
+    let mut magic = [0; 4];
+    self.reader.seek(SeekFrom::Start(0))?;
+    self.reader.read_exact(&mut magic)?;
+    if magic != PARQUET_MAGIC {
+      self.add(
+        IssueKind::Footer,
+        ErrorContext::new().with_offset(0),
+        "Invalid magic bytes at the start of file".to_owned()
+      );
+    }
+
+    let mut footer = [0; FOOTER_SIZE];
+    self.reader.seek(SeekFrom::End(-(FOOTER_SIZE as i64)))?;
+    self.reader.read_exact(&mut footer)?;
+    if footer[4..] != PARQUET_MAGIC {
+      return Err(general_err!("Invalid magic bytes in footer"));
+    }
+    let metadata_len = LittleEndian::read_i32(&footer[0..4]) as i64;
+    let metadata_start = file_size as i64 - FOOTER_SIZE as i64 - metadata_len;
+    if metadata_len < 0 || metadata_start < PARQUET_MAGIC.len() as i64 {
+      return Err(general_err!("Invalid metadata length {}", metadata_len));
+    }
+    self.metadata_start = metadata_start as u64;
+
+    let mut buf = vec![0; metadata_len as usize];
+    self.reader.seek(SeekFrom::Start(self.metadata_start))?;
+    self.reader.read_exact(&mut buf)?;
+    let mut prot = TCompactInputProtocol::new(&buf[..]);
+    TFileMetaData::read_from_in_protocol(&mut prot).map_err(|e| {
+      general_err!("Could not parse metadata: {}", e)
+        .with_context(ErrorContext::new().with_offset(self.metadata_start))
+    })
+  }
+
+  fn validate_row_group(
+    &mut self,
+    i: usize,
+    schema_descr: &SchemaDescPtr,
+    mut row_group: RowGroup
+  ) {
+    let context = ErrorContext::new().with_row_group(i);
+    if row_group.num_rows < 0 {
+      let message = format!("Number of rows is negative ({})", row_group.num_rows);
+      self.add(IssueKind::RowGroup, context.clone(), message);
+    }
+    if row_group.columns.len() != schema_descr.num_columns() {
+      let message = format!(
+        "Number of column chunks {} does not match number of columns {}",
+        row_group.columns.len(),
+        schema_descr.num_columns()
+      );
+      return self.add(IssueKind::RowGroup, context, message);
+    }
+
+    // Statistics that cannot be decoded are removed before conversion
+    for (j, column) in row_group.columns.iter_mut().enumerate() {
+      let descr = schema_descr.column(j);
+      if let Some(ref mut column_metadata) = column.meta_data {
+        let res = match column_metadata.statistics {
+          Some(ref stats) => check_thrift_statistics(stats, &descr),
+          None => Ok(())
+        };
+        if let Err(e) = res {
+          let context = context.clone().with_column_path(descr.path().string());
+          self.add_error(IssueKind::Statistics, context, e);
+          column_metadata.statistics = None;
+        }
+      }
+    }
+
+    let t_columns = row_group.columns.clone();
+    let metadata = match RowGroupMetaData::from_thrift(schema_descr.clone(), row_group) {
+      Ok(metadata) => metadata,
+      Err(e) => return self.add_error(IssueKind::RowGroup, context, e)
+    };
+    for (column, t_column) in metadata.columns().iter().zip(t_columns.iter()) {
+      let context = context.clone().with_column_path(column.column_path().string());
+      self.validate_column_chunk(context, metadata.num_rows(), column, t_column);
+    }
+  }
+
+  fn validate_column_chunk(
+    &mut self,
+    context: ErrorContext,
+    num_rows: i64,
+    column: &ColumnChunkMetaData,
+    t_column: &ColumnChunk
+  ) {
+    let start = column.dictionary_page_offset().unwrap_or(column.data_page_offset());
+    if !self.is_in_bounds(start, column.compressed_size()) {
+      let message = format!(
+        "Column chunk is out of bounds: offset {}, size {}",
+        start,
+        column.compressed_size()
+      );
+      return self.add(IssueKind::ColumnChunk, context, message);
+    }
+    let context = context.with_offset(start as u64);
+    if let Some(offset) = column.dictionary_page_offset() {
+      if offset >= column.data_page_offset() {
+        let message = format!(
+          "Dictionary page offset {} is not before data page offset {}",
+          offset,
+          column.data_page_offset()
+        );
+        self.add(IssueKind::ColumnChunk, context.clone(), message);
+      }
+    }
+    if let Some(stats) = column.statistics() {
+      let res = check_statistics(stats, column.num_values(), column.column_descr());
+      if let Err(e) = res {
+        self.add_error(IssueKind::Statistics, context.clone(), e);
+      }
+    }
+
+    let pages = self.validate_pages(&context, column, start as u64);
+    if let Some(ref pages) = pages {
+      let num_values: i64 = pages.iter().map(|page| page.num_values).sum();
+      if num_values != column.num_values() {
+        let message = format!(
+          "Number of values {} does not match number of values in pages {}",
+          column.num_values(),
+          num_values
+        );
+        self.add(IssueKind::ColumnChunk, context.clone(), message);
+      }
+    }
+
+    if let (Some(offset), Some(length)) =
+        (t_column.offset_index_offset, t_column.offset_index_length) {
+      let res = self.read_index(offset, length as i64, OffsetIndex::read_from_in_protocol)
+        .and_then(|index| match pages {
+          Some(ref pages) => check_offset_index(&index, pages, num_rows),
+          None => Ok(())
+        });
+      if let Err(e) = res {
+        self.add_error(IssueKind::OffsetIndex, context.clone(), e);
+      }
+    }
+    if let (Some(offset), Some(length)) =
+        (t_column.column_index_offset, t_column.column_index_length) {
+      let res = self.read_index(offset, length as i64, ColumnIndex::read_from_in_protocol)
+        .and_then(|index| check_column_index(&index, pages.as_ref().map(|p| p.len())));
+      if let Err(e) = res {
+        self.add_error(IssueKind::ColumnIndex, context, e);
+      }
+    }
+  }
+
+  /// Walks all pages of a column chunk. Returns data pages, or `None` if pages could
+  /// not be walked until the end of column chunk.
+  fn validate_pages(
+    &mut self,
+    context: &ErrorContext,
+    column: &ColumnChunkMetaData,
+    start: u64
+  ) -> Option<Vec<DataPageInfo>> {
+    let mut codec = match create_codec(column.compression()) {
+      Ok(codec) => codec,
+      Err(e) => {
+        self.add_error(IssueKind::ColumnChunk, context.clone(), e);
+        return None;
+      }
+    };
+    let descr = column.column_descr();
+    let end = start + column.compressed_size() as u64;
+    let mut source =
+      FileSource::new(&self.reader, start, column.compressed_size() as usize);
+    let mut pages = Vec::new();
+    let mut ordinal = 0;
+
+    while source.pos() < end {
+      let page_start = source.pos();
+      let context = context.clone().with_page(ordinal).with_offset(page_start);
+      let res = {
+        let mut prot = TCompactInputProtocol::new(&mut source);
+        PageHeader::read_from_in_protocol(&mut prot).map_err(ParquetError::from)
+      };
+      let res = res.and_then(|header| check_page_header(&header).map(|_| header));
+      let header = match res {
+        Ok(header) => header,
+        Err(e) => {
+          self.add_error(IssueKind::PageHeader, context, e);
+          return None;
+        }
+      };
+      let header_len = source.pos() - page_start;
+      let page_len = header.compressed_page_size as u64;
+      if source.pos() + page_len > end {
+        let message = format!("Page of {} bytes exceeds column chunk", page_len);
+        self.add(IssueKind::PageHeader, context, message);
+        return None;
+      }
+      let mut buffer = vec![0; page_len as usize];
+      if let Err(e) = source.read_exact(&mut buffer) {
+        self.add_error(IssueKind::PageHeader, context, e.into());
+        return None;
+      }
+      self.report.num_pages += 1;
+
+      if let Err(e) = check_page_crc(&header, &buffer[..]) {
+        self.add_error(IssueKind::Checksum, context.clone(), e);
+      }
+      if let Err(e) = check_decompression(&mut codec, &header, &buffer[..]) {
+        self.add_error(IssueKind::Compression, context.clone(), e);
+      }
+      match header.type_ {
+        PageType::DICTIONARY_PAGE if ordinal > 0 => {
+          let message = "Dictionary page is not the first page".to_owned();
+          self.add(IssueKind::PageHeader, context, message);
+        },
+        PageType::DATA_PAGE | PageType::DATA_PAGE_V2 => {
+          let (num_values, num_rows) = self.validate_data_page(&context, &header, descr)?;
+          pages.push(DataPageInfo {
+            offset: page_start as i64,
+            size: (header_len + page_len) as i64,
+            num_values: num_values,
+            num_rows: num_rows
+          });
+        },
+        _ => {}
+      }
+      ordinal += 1;
+    }
+    Some(pages)
+  }
+
+  /// Checks data page header and page statistics.
+  /// Returns number of values and number of rows, if known, or `None` if data page
+  /// header is invalid.
+  fn validate_data_page(
+    &mut self,
+    context: &ErrorContext,
+    header: &PageHeader,
+    descr: &ColumnDescriptor
+  ) -> Option<(i64, Option<i64>)> {
+    let (num_values, num_rows, num_nulls, stats) = match (
+      &header.data_page_header,
+      &header.data_page_header_v2
+    ) {
+      (Some(h), _) if header.type_ == PageType::DATA_PAGE => {
+        (h.num_values, None, None, h.statistics.as_ref())
+      },
+      (_, Some(h)) if header.type_ == PageType::DATA_PAGE_V2 => {
+        (h.num_values, Some(h.num_rows), Some(h.num_nulls), h.statistics.as_ref())
+      },
+      _ => {
+        let message = format!("Missing header of data page {:?}", header.type_);
+        self.add(IssueKind::PageHeader, context.clone(), message);
+        return None;
+      }
+    };
+    if num_values < 0 || num_rows.map(|n| n < 0).unwrap_or(false) {
+      let message = "Number of values or rows is negative".to_owned();
+      self.add(IssueKind::PageHeader, context.clone(), message);
+      return None;
+    }
+    if let Some(num_nulls) = num_nulls {
+      if num_nulls < 0 || num_nulls > num_values {
+        let message =
+          format!("Number of nulls {} is invalid for {} values", num_nulls, num_values);
+        self.add(IssueKind::Statistics, context.clone(), message);
+      }
+    }
+    if let Some(stats) = stats {
+      let res = check_thrift_statistics(stats, descr).and_then(|_| {
+        let stats = statistics::from_thrift(descr.physical_type(), Some(stats.clone()));
+        check_statistics(&stats.unwrap(), num_values as i64, descr)
+      });
+      if let Err(e) = res {
+        self.add_error(IssueKind::Statistics, context.clone(), e);
+      }
+    }
+
+    let num_rows = match num_rows {
+      Some(num_rows) => Some(num_rows as i64),
+      None if descr.max_rep_level() == 0 => Some(num_values as i64),
+      None => None
+    };
+    Some((num_values as i64, num_rows))
+  }
+
+  /// Reads index of a column chunk located at `offset`.
+  fn read_index<T, F>(&mut self, offset: i64, length: i64, read: F) -> Result<T>
+    where F: FnOnce(&mut TInputProtocol) -> thrift::Result<T> {
+    if !self.is_in_bounds(offset, length) {
+      return Err(general_err!(
+        "Index is out of bounds: offset {}, size {}",
+        offset,
+        length
+      ));
+    }
+    let mut buf = vec![0; length as usize];
+    self.reader.seek(SeekFrom::Start(offset as u64))?;
+    self.reader.read_exact(&mut buf)?;
+    let mut prot = TCompactInputProtocol::new(&buf[..]);
+    read(&mut prot).map_err(|e| {
+      general_err!("Could not parse index: {}", e)
+        .with_context(ErrorContext::new().with_offset(offset as u64))
+    })
+  }
+
+  /// Returns `true` if byte range is located between magic bytes and file metadata.
+  fn is_in_bounds(&self, offset: i64, length: i64) -> bool {
+    match offset.checked_add(length) {
+      Some(end) => {
+        offset >= PARQUET_MAGIC.len() as i64 && length >= 0 &&
+          end <= self.metadata_start as i64
+      },
+      None => false
+    }
+  }
+}
+
+/// Checks that min and max values of Thrift statistics match size of the physical
+/// type and null count is not negative, so statistics can be converted.
+fn check_thrift_statistics(stats: &TStatistics, descr: &ColumnDescriptor) -> Result<()> {
+  if let Some(null_count) = stats.null_count {
+    if null_count < 0 {
+      return Err(general_err!("Null count is negative ({})", null_count));
+    }
+  }
+  let size = match descr.physical_type() {
+    Type::BOOLEAN => 1,
+    Type::INT32 | Type::FLOAT => 4,
+    Type::INT64 | Type::DOUBLE => 8,
+    Type::INT96 => 12,
+    Type::FIXED_LEN_BYTE_ARRAY => descr.type_length() as usize,
+    Type::BYTE_ARRAY => return Ok(())
+  };
+  // Same as in `statistics::from_thrift`, deprecated fields are only used when new
+  // fields are not set
+  let (min, max) = if stats.min_value.is_none() && stats.max_value.is_none() {
+    (&stats.min, &stats.max)
+  } else {
+    (&stats.min_value, &stats.max_value)
+  };
+  for value in [min, max].iter() {
+    if let Some(ref value) = **value {
+      if value.len() != size {
+        return Err(general_err!(
+          "Min or max value has {} bytes, expected {} bytes",
+          value.len(),
+          size
+        ));
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Checks that null count does not exceed `num_values` and min value is not larger
+/// than max value.
+///
+/// Deprecated min and max values were written with signed comparison, otherwise sort
+/// order of the column is used. Signed order of byte arrays, e.g. decimals, and INT96
+/// values are not checked.
+fn check_statistics(
+  stats: &Statistics,
+  num_values: i64,
+  descr: &ColumnDescriptor
+) -> Result<()> {
+  if stats.null_count() > cmp::max(num_values, 0) as u64 {
+    return Err(general_err!(
+      "Null count {} exceeds number of values {}",
+      stats.null_count(),
+      num_values
+    ));
+  }
+  if !stats.has_min_max_set() {
+    return Ok(());
+  }
+  let sort_order = if stats.is_min_max_deprecated() {
+    SortOrder::SIGNED
+  } else {
+    ColumnOrder::get_sort_order(descr.logical_type(), descr.physical_type())
+  };
+  let is_ordered = match (stats, sort_order) {
+    (_, SortOrder::UNDEFINED) => true,
+    (Statistics::Boolean(typed), _) => typed.min() <= typed.max(),
+    (Statistics::Int32(typed), SortOrder::UNSIGNED) => {
+      (*typed.min() as u32) <= (*typed.max() as u32)
+    },
+    (Statistics::Int32(typed), _) => typed.min() <= typed.max(),
+    (Statistics::Int64(typed), SortOrder::UNSIGNED) => {
+      (*typed.min() as u64) <= (*typed.max() as u64)
+    },
+    (Statistics::Int64(typed), _) => typed.min() <= typed.max(),
+    // NaN values should be ignored
+    (Statistics::Float(typed), _) => {
+      typed.min().is_nan() || typed.max().is_nan() || typed.min() <= typed.max()
+    },
+    (Statistics::Double(typed), _) => {
+      typed.min().is_nan() || typed.max().is_nan() || typed.min() <= typed.max()
+    },
+    // FLOAT16 values are compared as floats, NaN values should be ignored
+    (Statistics::FixedLenByteArray(_), _)
+        if descr.logical_type() == LogicalType::FLOAT16 => {
+      match stats.float16_min_max() {
+        Some((min, max)) => min.is_nan() || max.is_nan() || min <= max,
+        // Values of invalid length can not be compared
+        None => true
+      }
+    },
+    (Statistics::ByteArray(_), SortOrder::UNSIGNED) |
+    (Statistics::FixedLenByteArray(_), SortOrder::UNSIGNED) => {
+      stats.min_bytes() <= stats.max_bytes()
+    },
+    _ => true
+  };
+  if !is_ordered {
+    return Err(general_err!("Min value is larger than max value"));
+  }
+  Ok(())
+}
+
+/// Checks that page can be decompressed into `uncompressed_page_size` bytes.
+fn check_decompression(
+  codec: &mut Option<Box<Codec>>,
+  header: &PageHeader,
+  buffer: &[u8]
+) -> Result<()> {
+  let codec = match *codec {
+    Some(ref mut codec) => codec,
+    None => return Ok(())
+  };
+  // Levels of data page v2 are not compressed
+  let mut offset = 0;
+  if let Some(ref header_v2) = header.data_page_header_v2 {
+    if !header_v2.is_compressed.unwrap_or(true) {
+      return Ok(());
+    }
+    offset = (header_v2.definition_levels_byte_length +
+      header_v2.repetition_levels_byte_length) as usize;
+  }
+  let uncompressed_len = header.uncompressed_page_size as usize - offset;
+  let mut decompressed = Vec::new();
+  let decompressed_len = codec.decompress(&buffer[offset..], &mut decompressed)?;
+  if decompressed_len != uncompressed_len {
+    return Err(general_err!(
+      "Actual decompressed size doesn't match the expected one ({} vs {})",
+      decompressed_len,
+      uncompressed_len
+    ));
+  }
+  Ok(())
+}
+
+/// Checks that offset index has locations of all data pages and valid first row
+/// indexes for row group of `num_rows` rows.
+fn check_offset_index(
+  index: &OffsetIndex,
+  pages: &[DataPageInfo],
+  num_rows: i64
+) -> Result<()> {
+  let locations = &index.page_locations;
+  if locations.len() != pages.len() {
+    return Err(general_err!(
+      "Offset index has {} pages, column chunk has {} data pages",
+      locations.len(),
+      pages.len()
+    ));
+  }
+  // First row index of the current page, if it is known
+  let mut first_row_index = Some(0);
+  for (i, (location, page)) in locations.iter().zip(pages).enumerate() {
+    if location.offset != page.offset ||
+        location.compressed_page_size as i64 != page.size {
+      return Err(general_err!(
+        "Page location {} does not match page: offset {}, size {}, expected offset {}, \
+         size {}",
+        i,
+        location.offset,
+        location.compressed_page_size,
+        page.offset,
+        page.size
+      ));
+    }
+    let row_index = location.first_row_index;
+    let is_valid = row_index >= 0 && (row_index < num_rows || num_rows == 0) &&
+      (i > 0 || row_index == 0) &&
+      (i == 0 || row_index > locations[i - 1].first_row_index) &&
+      first_row_index.map(|expected| expected == row_index).unwrap_or(true);
+    if !is_valid {
+      return Err(general_err!(
+        "Page location {} has invalid first row index {}",
+        i,
+        row_index
+      ));
+    }
+    first_row_index = first_row_index.and_then(|index| page.num_rows.map(|n| index + n));
+  }
+  Ok(())
+}
+
+/// Checks that all lists of column index have the same length as number of data pages,
+/// if known.
+fn check_column_index(index: &ColumnIndex, num_pages: Option<usize>) -> Result<()> {
+  let num_pages = num_pages.unwrap_or(index.null_pages.len());
+  let null_counts_len = index.null_counts.as_ref().map(|counts| counts.len());
+  if index.null_pages.len() != num_pages || index.min_values.len() != num_pages ||
+      index.max_values.len() != num_pages ||
+      null_counts_len.map(|len| len != num_pages).unwrap_or(false) {
+    return Err(general_err!(
+      "Column index does not match number of data pages {}",
+      num_pages
+    ));
+  }
+  Ok(())
+}
+
+// ----------------------------------------------------------------------
+// Repair
+
+/// Number of values and levels copied at once when repairing a file.
+const COPY_BATCH_SIZE: usize = 1024;
+
+/// Writes a repaired copy of a file to `sink` and returns number of rows written.
+///
+/// File is read in lenient mode, see
+/// [`SerializedFileReader::new_lenient`](../reader/struct.SerializedFileReader.html):
+/// corrupt pages of optional non-repeated columns are replaced with nulls, row groups
+/// with invalid metadata, or columns that cannot be fully read, are dropped. Footer and
+/// file schema must be valid.
+///
+/// Values are re-encoded with `props`, encodings and compression of the original file
+/// are not preserved.
+pub fn repair_file<R, W>(reader: R, sink: W, props: WriterPropertiesPtr) -> Result<i64>
+  where R: 'static + ParquetReader,
+        W: 'static + Write {
+  let reader = SerializedFileReader::new_lenient(reader)?;
+  let schema = reader.metadata().file_metadata().schema_descr().root_schema_ptr();
+  let mut writer = SerializedFileWriter::new(sink, schema, props)?;
+  let mut num_rows = 0;
+  for i in 0..reader.num_row_groups() {
+    let row_group_reader = reader.get_row_group(i)?;
+    if !is_row_group_readable(row_group_reader.as_ref()) {
+      trace_warn!(row_group = i, "Dropping row group that cannot be fully read");
+      continue;
+    }
+    let mut row_group_writer = writer.next_row_group()?;
+    for j in 0..row_group_reader.num_columns() {
+      let mut column_writer = match row_group_writer.next_column()? {
+        Some(column_writer) => column_writer,
+        None => return Err(general_err!("Missing column writer for column {}", j))
+      };
+      copy_column(
+        row_group_reader.get_column_reader(j)?,
+        Some(&mut column_writer),
+        row_group_reader.metadata().column(j).column_descr()
+      )?;
+      row_group_writer.close_column(column_writer)?;
+    }
+    writer.close_row_group(row_group_writer)?;
+    num_rows += row_group_reader.metadata().num_rows();
+  }
+  writer.close()?;
+  Ok(num_rows)
+}
+
+/// Returns `true` if all columns of a row group can be read and have the number of
+/// rows of row group metadata.
+fn is_row_group_readable(row_group_reader: &RowGroupReader) -> bool {
+  let metadata = row_group_reader.metadata();
+  (0..row_group_reader.num_columns()).all(|i| {
+    let res = row_group_reader.get_column_reader(i)
+      .and_then(|reader| copy_column(reader, None, metadata.column(i).column_descr()));
+    match res {
+      Ok(num_rows) => num_rows as i64 == metadata.num_rows(),
+      Err(_) => false
+    }
+  })
+}
+
+/// Reads all values and levels of a column and writes them to `writer`, if set.
+/// Returns number of rows read.
+fn copy_column(
+  reader: ColumnReader,
+  writer: Option<&mut ColumnWriter>,
+  descr: &ColumnDescriptor
+) -> Result<usize> {
+  macro_rules! copy_by_type {
+    ($($reader_variant:ident => $writer_variant:ident),*) => {
+      match (reader, writer) {
+        $(
+          (ColumnReader::$reader_variant(typed), None) => {
+            copy_typed_column(typed, None, descr)
+          },
+          (
+            ColumnReader::$reader_variant(typed),
+            Some(&mut ColumnWriter::$writer_variant(ref mut typed_writer))
+          ) => copy_typed_column(typed, Some(typed_writer), descr),
+        )*
+        _ => Err(general_err!("Column reader and column writer types do not match"))
+      }
+    };
+  }
+
+  copy_by_type!(
+    BoolColumnReader => BoolColumnWriter,
+    Int32ColumnReader => Int32ColumnWriter,
+    Int64ColumnReader => Int64ColumnWriter,
+    Int96ColumnReader => Int96ColumnWriter,
+    FloatColumnReader => FloatColumnWriter,
+    DoubleColumnReader => DoubleColumnWriter,
+    ByteArrayColumnReader => ByteArrayColumnWriter,
+    FixedLenByteArrayColumnReader => FixedLenByteArrayColumnWriter
+  )
+}
+
+fn copy_typed_column<T: DataType>(
+  mut reader: ColumnReaderImpl<T>,
+  mut writer: Option<&mut ColumnWriterImpl<T>>,
+  descr: &ColumnDescriptor
+) -> Result<usize> {
+  let mut values = vec![T::T::default(); COPY_BATCH_SIZE];
+  let mut def_levels = vec![0; COPY_BATCH_SIZE];
+  let mut rep_levels = vec![0; COPY_BATCH_SIZE];
+  let mut num_rows = 0;
+  loop {
+    let (values_read, levels_read) = reader.read_batch(
+      COPY_BATCH_SIZE,
+      Some(&mut def_levels[..]),
+      Some(&mut rep_levels[..]),
+      &mut values[..]
+    )?;
+    if values_read == 0 && levels_read == 0 {
+      break;
+    }
+    let def_levels = if descr.max_def_level() > 0 {
+      Some(&def_levels[..levels_read])
+    } else {
+      None
+    };
+    let rep_levels = if descr.max_rep_level() > 0 {
+      Some(&rep_levels[..levels_read])
+    } else {
+      None
+    };
+    num_rows += match rep_levels {
+      Some(levels) => levels.iter().filter(|level| **level == 0).count(),
+      None => cmp::max(values_read, levels_read)
+    };
+    if let Some(ref mut writer) = writer {
+      writer.write_batch(&values[..values_read], def_levels, rep_levels)?;
+    }
+  }
+  Ok(num_rows)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::env;
+  use std::{f32, f64};
+  use std::fs::{self, File};
+  use std::io::Cursor;
+
+  use basic::Compression;
+  use data_type::ByteArray;
+  use file::properties::WriterProperties;
+  use file::writer::write_to_bytes;
+  use parquet_format::PageLocation;
+  use record::RowAccessor;
+  use schema::parser::parse_message_type;
+  use util::test_common::{get_temp_file, get_test_file, get_test_path};
+
+  #[test]
+  fn test_validate_test_files() {
+    for file_name in &[
+      "alltypes_plain.parquet",
+      "alltypes_plain.snappy.parquet",
+      "alltypes_dictionary.parquet",
+      "nested_lists.snappy.parquet",
+      "nested_maps.snappy.parquet",
+      "nonnullable.impala.parquet",
+      "nullable.impala.parquet",
+      "nulls.snappy.parquet",
+      "test_datapage_v2.snappy.parquet",
+      "10k-v2.parquet"
+    ] {
+      let report = validate_file(get_test_file(file_name));
+      assert!(report.is_valid(), "{}: {:?}", file_name, report.issues());
+      assert!(report.num_row_groups() > 0);
+      assert!(report.num_pages() > 0);
+    }
+  }
+
+  #[test]
+  fn test_validate_num_rows() {
+    let report = validate_file(get_test_file("repeated_no_annotation.parquet"));
+    assert_eq!(report.issues(), &[ValidationIssue {
+      kind: IssueKind::Footer,
+      context: ErrorContext::new(),
+      message: "Number of rows 0 does not match number of rows in row groups 6".to_owned()
+    }]);
+  }
+
+  #[test]
+  fn test_validate_footer() {
+    let report = validate_file(get_temp_file("test_validate_small_file", &[1, 2, 3]));
+    assert_eq!(report.issues().len(), 1);
+    assert_eq!(report.issues()[0].kind(), IssueKind::Footer);
+    assert_eq!(
+      report.issues()[0].message(),
+      "File size 3 is smaller than magic bytes and footer"
+    );
+
+    let bytes = fs::read(get_test_path("alltypes_plain.parquet")).unwrap();
+    let len = bytes.len();
+    let mut corrupt = bytes.clone();
+    corrupt[len - 1] = b'X';
+    let report = validate_file(get_temp_file("test_validate_footer_magic", &corrupt));
+    assert_eq!(report.issues().len(), 1);
+    assert_eq!(report.issues()[0].message(), "Invalid magic bytes in footer");
+
+    // Corrupt magic bytes at the start and the last byte of file metadata
+    let mut corrupt = bytes.clone();
+    corrupt[0] = b'X';
+    corrupt[len - 9] = 0xFF;
+    let report = validate_file(get_temp_file("test_validate_footer_metadata", &corrupt));
+    let issues = report.issues();
+    assert_eq!(issues.len(), 2, "{:?}", issues);
+    assert_eq!(issues[0].message(), "Invalid magic bytes at the start of file");
+    assert_eq!(issues[1].kind(), IssueKind::Footer);
+    assert!(issues[1].message().starts_with("Could not parse metadata"));
+    assert!(issues[1].context().offset().is_some());
+  }
+
+  #[test]
+  fn test_validate_corrupt_pages() {
+    let data = vec![vec![1, 2, 3], vec![4, 5]];
+    let (mut bytes, page_offsets) = write_test_file("test_validate_pages", &data);
+    let report = validate_file(get_temp_file("test_validate_pages", &bytes));
+    assert!(report.is_valid(), "{:?}", report.issues());
+    assert_eq!(report.num_row_groups(), 2);
+    assert_eq!(report.num_pages(), 4);
+
+    // Corrupt compressed body of column `b` in the first row group and page header of
+    // column `a` in the second row group
+    let body_offset = page_body_offset(&bytes, page_offsets[0][1]);
+    bytes[body_offset] = 1;
+    bytes[page_offsets[1][0]] = 0xFF;
+    let report = validate_file(get_temp_file("test_validate_pages", &bytes));
+    let issues = report.issues();
+    assert_eq!(issues.len(), 2, "{:?}", issues);
+
+    assert_eq!(issues[0].kind(), IssueKind::Compression);
+    assert_eq!(issues[0].context().row_group(), Some(0));
+    assert_eq!(issues[0].context().column_path(), Some("b"));
+    assert_eq!(issues[0].context().page(), Some(0));
+    assert_eq!(issues[1].kind(), IssueKind::PageHeader);
+    assert_eq!(issues[1].context().row_group(), Some(1));
+    assert_eq!(issues[1].context().column_path(), Some("a"));
+    assert_eq!(issues[1].context().offset(), Some(page_offsets[1][0] as u64));
+    assert_eq!(
+      issues[1].to_string(),
+      format!("PageHeader: {} ({})", issues[1].message(), issues[1].context())
+    );
+  }
+
+  #[test]
+  fn test_check_statistics() {
+    let schema = parse_message_type("
+      message schema {
+        REQUIRED INT32 a;
+        REQUIRED INT32 b (UINT_32);
+        REQUIRED BYTE_ARRAY c (UTF8);
+        REQUIRED DOUBLE d;
+        REQUIRED FIXED_LEN_BYTE_ARRAY (2) e (FLOAT16);
+      }
+    ").unwrap();
+    let descr = SchemaDescriptor::new(Rc::new(schema));
+    let (a, b) = (descr.column(0), descr.column(1));
+    let (c, d) = (descr.column(2), descr.column(3));
+    let e = descr.column(4);
+
+    let stats = Statistics::int32(Some(1), Some(5), None, 0, false);
+    assert!(check_statistics(&stats, 10, &a).is_ok());
+    let stats = Statistics::int32(Some(5), Some(1), None, 0, false);
+    assert_eq!(
+      check_statistics(&stats, 10, &a),
+      Err(general_err!("Min value is larger than max value"))
+    );
+    let stats = Statistics::int32(None, None, None, 11, false);
+    assert_eq!(
+      check_statistics(&stats, 10, &a),
+      Err(general_err!("Null count 11 exceeds number of values 10"))
+    );
+
+    // Unsigned sort order, deprecated min/max values are compared as signed values
+    let stats = Statistics::int32(Some(1), Some(-1), None, 0, false);
+    assert!(check_statistics(&stats, 10, &b).is_ok());
+    let stats = Statistics::int32(Some(-1), Some(1), None, 0, false);
+    assert!(check_statistics(&stats, 10, &b).is_err());
+    let stats = Statistics::int32(Some(-1), Some(1), None, 0, true);
+    assert!(check_statistics(&stats, 10, &b).is_ok());
+
+    let stats = Statistics::byte_array(
+      Some(ByteArray::from("b")), Some(ByteArray::from("a")), None, 0, false);
+    assert!(check_statistics(&stats, 10, &c).is_err());
+    let stats = Statistics::double(Some(f64::NAN), Some(1.0), None, 0, false);
+    assert!(check_statistics(&stats, 10, &d).is_ok());
+
+    // FLOAT16 values are compared as floats, not bytes
+    let float16 = |min: f32, max: f32| Statistics::fixed_len_byte_array(
+      Some(ByteArray::from_f32_as_f16(min)),
+      Some(ByteArray::from_f32_as_f16(max)),
+      None, 0, false);
+    assert!(check_statistics(&float16(-1.0, 1.0), 10, &e).is_ok());
+    assert!(check_statistics(&float16(2.0, 1.0), 10, &e).is_err());
+    assert!(check_statistics(&float16(f32::NAN, 1.0), 10, &e).is_ok());
+
+    let stats = TStatistics::new(None, None, -1, None, None, None);
+    assert_eq!(
+      check_thrift_statistics(&stats, &a),
+      Err(general_err!("Null count is negative (-1)"))
+    );
+    let stats = TStatistics::new(None, None, None, None, vec![1], vec![1, 0, 0, 0]);
+    assert_eq!(
+      check_thrift_statistics(&stats, &a),
+      Err(general_err!("Min or max value has 1 bytes, expected 4 bytes"))
+    );
+    assert!(check_thrift_statistics(&stats, &c).is_ok());
+  }
+
+  #[test]
+  fn test_check_offset_index() {
+    let pages = vec![
+      DataPageInfo { offset: 4, size: 20, num_values: 10, num_rows: Some(10) },
+      DataPageInfo { offset: 24, size: 30, num_values: 5, num_rows: None }
+    ];
+    let index = |locations: &[(i64, i32, i64)]| OffsetIndex::new(
+      locations.iter().map(|l| PageLocation::new(l.0, l.1, l.2)).collect()
+    );
+
+    assert!(check_offset_index(&index(&[(4, 20, 0), (24, 30, 10)]), &pages, 15).is_ok());
+    assert_eq!(
+      check_offset_index(&index(&[(4, 20, 0)]), &pages, 15),
+      Err(general_err!("Offset index has 1 pages, column chunk has 2 data pages"))
+    );
+    assert_eq!(
+      check_offset_index(&index(&[(4, 20, 0), (25, 30, 10)]), &pages, 15),
+      Err(general_err!(
+        "Page location 1 does not match page: offset 25, size 30, expected offset 24, \
+         size 30"
+      ))
+    );
+    assert_eq!(
+      check_offset_index(&index(&[(4, 20, 0), (24, 30, 9)]), &pages, 15),
+      Err(general_err!("Page location 1 has invalid first row index 9"))
+    );
+    assert_eq!(
+      check_offset_index(&index(&[(4, 20, 0), (24, 30, 15)]), &pages, 15),
+      Err(general_err!("Page location 1 has invalid first row index 15"))
+    );
+  }
+
+  #[test]
+  fn test_repair_file() {
+    let data = vec![vec![1, 2, 3], vec![4, 5]];
+    let (mut bytes, page_offsets) = write_test_file("test_repair_file", &data);
+    // Page of optional column `b` in the first row group is replaced with nulls, the
+    // second row group is dropped, because required column `a` is corrupt
+    let body_offset = page_body_offset(&bytes, page_offsets[0][1]);
+    bytes[body_offset] = 1;
+    let body_offset = page_body_offset(&bytes, page_offsets[1][0]);
+    bytes[body_offset] = 1;
+    let file = get_temp_file("test_repair_file", &bytes);
+
+    let repaired = get_temp_file("test_repair_file_repaired", &[]);
+    let props = Rc::new(WriterProperties::builder().build());
+    assert_eq!(repair_file(file, repaired, props).unwrap(), 3);
+
+    let mut path = env::current_dir().unwrap();
+    path.push("target");
+    path.push("debug");
+    path.push("testdata");
+    path.push("test_repair_file_repaired");
+    let report = validate_file(File::open(&path).unwrap());
+    assert!(report.is_valid(), "{:?}", report.issues());
+
+    let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+    assert_eq!(reader.num_row_groups(), 1);
+    let mut num_rows = 0;
+    for (i, row) in reader.get_row_iter(None).unwrap().enumerate() {
+      let row = row.unwrap();
+      assert_eq!(row.get_int(0).unwrap(), data[0][i]);
+      assert!(row.get_int(1).is_err(), "Value of corrupt page should be null");
+      num_rows += 1;
+    }
+    assert_eq!(num_rows, 3);
+  }
+
+  /// Writes file with required INT32 column `a` and optional INT32 column `b`,
+  /// compressed with SNAPPY, each vector is a separate row group. Returns file bytes and
+  /// data page offsets of each column chunk.
+  fn write_test_file(file_name: &str, data: &[Vec<i32>]) -> (Vec<u8>, Vec<Vec<usize>>) {
+    let schema = Rc::new(parse_message_type(
+      "message schema { REQUIRED INT32 a; OPTIONAL INT32 b; }"
+    ).unwrap());
+    let props = Rc::new(
+      WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_dictionary_enabled(false)
+        .build()
+    );
+    let bytes = write_to_bytes(schema, props, |writer| {
+      for values in data {
+        let mut row_group_writer = writer.next_row_group()?;
+        while let Some(mut col_writer) = row_group_writer.next_column()? {
+          if let ColumnWriter::Int32ColumnWriter(ref mut typed) = col_writer {
+            let def_levels = vec![1; values.len()];
+            typed.write_batch(&values[..], Some(&def_levels[..]), None)?;
+          }
+          row_group_writer.close_column(col_writer)?;
+        }
+        writer.close_row_group(row_group_writer)?;
+      }
+      Ok(())
+    }).unwrap();
+
+    let reader = SerializedFileReader::new(get_temp_file(file_name, &bytes)).unwrap();
+    let page_offsets = reader.metadata().row_groups().iter()
+      .map(|row_group| {
+        row_group.columns().iter()
+          .map(|column| column.data_page_offset() as usize)
+          .collect()
+      })
+      .collect();
+    (bytes, page_offsets)
+  }
+
+  /// Returns offset of page body of a page that starts at `page_offset`.
+  fn page_body_offset(bytes: &[u8], page_offset: usize) -> usize {
+    let mut cursor = Cursor::new(&bytes[page_offset..]);
+    PageHeader::read_from_in_protocol(&mut TCompactInputProtocol::new(&mut cursor))
+      .unwrap();
+    page_offset + cursor.position() as usize
+  }
+}
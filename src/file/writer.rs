@@ -18,23 +18,34 @@
 //! Contains file writer API, and provides methods to write row groups and columns by
 //! using row group writers and column writers respectively.
 
-use std::fs::File;
-use std::io::{Seek, SeekFrom, Write};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::io::{self, Write};
 use std::rc::Rc;
 
-use basic::PageType;
+use basic::{ColumnOrder, PageType};
 use byteorder::{LittleEndian, ByteOrder};
 use column::page::{CompressedPage, Page, PageWriteSpec, PageWriter};
 use column::writer::{ColumnWriter, get_column_writer};
+use encryption::ciphers::{ColumnCipher, ModuleType};
+use encryption::encrypt::FileEncryptor;
+use encryption::metadata::{binary_to_compact_bytes, ColumnCryptoMetaData};
 use errors::{ParquetError, Result};
-use file::{FOOTER_SIZE, PARQUET_MAGIC};
+use file::{FOOTER_SIZE, PARQUET_MAGIC, PARQUET_MAGIC_ENCRYPTED_FOOTER};
+use file::bloom_filter::Sbbf;
 use file::metadata::*;
-use file::properties::WriterPropertiesPtr;
+use file::page_index::{ColumnIndex, OffsetIndex};
+#[cfg(feature = "rayon")]
+use file::properties::WriterProperties;
+use file::properties::{SortingVerification, WriterPropertiesPtr};
 use file::statistics::{to_thrift as statistics_to_thrift};
 use parquet_format as parquet;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use schema::types::{self, SchemaDescriptor, SchemaDescPtr, TypePtr};
-use thrift::protocol::{TCompactOutputProtocol, TOutputProtocol};
-use util::io::{FileSink, Position};
+use thrift::protocol::{TCompactOutputProtocol, TOutputProtocol, TType};
+use util::io::{Position, TrackedWrite};
+use util::thrift::{TolerantOutputProtocol, UnknownEnums};
 
 // ----------------------------------------------------------------------
 // APIs for file & row group writers
@@ -105,6 +116,45 @@ pub trait RowGroupWriter {
   /// Can be called multiple times. In subsequent calls will result in no-op and return
   /// already created row group metadata.
   fn close(&mut self) -> Result<RowGroupMetaDataPtr>;
+
+  /// Appends already encoded and compressed column chunk as the next column, without
+  /// decoding its pages. Can be used instead of `next_column` and `close_column`, e.g. to
+  /// copy column chunks between files when codec and encodings are unchanged.
+  ///
+  /// `data` contains page headers and pages of the column chunk, e.g. as returned by
+  /// [`RowGroupReader::get_column_chunk_bytes`]
+  /// (../reader/trait.RowGroupReader.html#method.get_column_chunk_bytes), `metadata` is
+  /// the column chunk metadata of the original file and `num_rows` is the number of rows
+  /// of its row group. Page offsets of `metadata` are updated for the new file, page
+  /// indexes are not copied.
+  ///
+  /// Returns `Err` if column type or path of `metadata` does not match the next column.
+  /// Returns `Err` by default, if implementation does not support raw column chunks.
+  fn append_column_chunk(
+    &mut self,
+    _data: &[u8],
+    _metadata: &ColumnChunkMetaData,
+    _num_rows: u64
+  ) -> Result<()> {
+    Err(nyi_err!("Appending raw column chunks is not supported"))
+  }
+
+  /// Writes all remaining columns of this row group in parallel on the global `rayon`
+  /// thread pool.
+  ///
+  /// Function `f` is called with the index of a column and its column writer, and
+  /// should write all values of that column; column writer is closed afterwards.
+  /// No other columns can be requested after calling this method.
+  ///
+  /// Available with `rayon` feature enabled. Returns `Err` by default, if
+  /// implementation does not support parallel writes.
+  #[cfg(feature = "rayon")]
+  fn write_columns_parallel(
+    &mut self,
+    _f: &(Fn(usize, &mut ColumnWriter) -> Result<()> + Sync + Send)
+  ) -> Result<()> {
+    Err(nyi_err!("Parallel column writes are not supported"))
+  }
 }
 
 // ----------------------------------------------------------------------
@@ -112,80 +162,369 @@ pub trait RowGroupWriter {
 
 /// A serialized implementation for Parquet [`FileWriter`].
 /// See documentation on file writer for more information.
-pub struct SerializedFileWriter {
-  file: File,
+///
+/// Writer accepts any `Write` sink, e.g. `File`, `Vec<u8>`, socket or pipe; byte offsets
+/// that end up in metadata are tracked by the writer itself, therefore `Seek` is not
+/// required.
+pub struct SerializedFileWriter<W: Write> {
+  buf: TrackedWrite<W>,
   schema: TypePtr,
   descr: SchemaDescPtr,
   props: WriterPropertiesPtr,
   total_num_rows: u64,
   row_groups: Vec<RowGroupMetaDataPtr>,
+  key_value_metadata: Option<Vec<KeyValue>>,
+  column_orders: Option<Vec<ColumnOrder>>,
+  unknown_fields: UnknownEnums,
+  // Page indexes of column chunks by row group and column index, written before file
+  // metadata
+  page_indexes: Vec<(usize, usize, Option<ColumnIndex>, OffsetIndex)>,
+  // Bloom filters of column chunks by row group and column index, written before page
+  // indexes
+  bloom_filters: Vec<(usize, usize, Sbbf)>,
+  encryptor: Option<Rc<FileEncryptor>>,
+  // Whether fields of metadata of any file have been copied
+  footer_copied: bool,
   previous_writer_closed: bool,
   is_closed: bool
 }
 
-impl SerializedFileWriter {
-  /// Creates new file writer.
+impl<W: 'static + Write> SerializedFileWriter<W> {
+  /// Creates new file writer, the file is encrypted if encryption properties are set
+  /// in `properties`.
+  /// Returns error if schema validation is enabled in `properties` and schema is
+  /// invalid, or if an encrypted column is not in schema, in which case nothing is
+  /// written to `buf`.
   pub fn new(
-    mut file: File,
+    buf: W,
     schema: TypePtr,
     properties: WriterPropertiesPtr
   ) -> Result<Self> {
-    Self::start_file(&mut file)?;
+    if properties.schema_validation_enabled() {
+      types::validate_schema(&schema)?;
+    }
+    let descr = Rc::new(SchemaDescriptor::new(schema.clone()));
+    let encryptor = match properties.file_encryption_properties() {
+      Some(encryption) => Some(Rc::new(FileEncryptor::new(encryption, &descr)?)),
+      None => None
+    };
+    let mut buf = TrackedWrite::new(buf);
+    Self::start_file(&mut buf, encryptor.as_ref().map(|e| &**e))?;
     Ok(Self {
-      file: file,
-      schema: schema.clone(),
-      descr: Rc::new(SchemaDescriptor::new(schema)),
+      buf: buf,
+      schema: schema,
+      descr: descr,
+      key_value_metadata: properties.key_value_metadata().cloned(),
       props: properties,
       total_num_rows: 0,
       row_groups: Vec::new(),
+      column_orders: None,
+      unknown_fields: UnknownEnums::default(),
+      page_indexes: Vec::new(),
+      bloom_filters: Vec::new(),
+      encryptor: encryptor,
+      footer_copied: false,
       previous_writer_closed: true,
       is_closed: false
     })
   }
 
-  /// Writes magic bytes at the beginning of the file.
-  fn start_file(file: &mut File) -> Result<()> {
-    file.write(&PARQUET_MAGIC)?;
+  /// Returns the underlying sink, all buffered data is flushed.
+  /// Returns error if file writer has not been closed yet.
+  pub fn into_inner(self) -> Result<W> {
+    if !self.is_closed {
+      return Err(general_err!("File writer is not closed"));
+    }
+    Ok(self.buf.into_inner()?)
+  }
+
+  /// Returns descriptor of the schema of the file being written.
+  pub fn schema_descr(&self) -> &SchemaDescriptor {
+    &self.descr
+  }
+
+  /// Copies fields of `file_metadata` of another file that are written in the footer,
+  /// e.g. when the file is rewritten or its row groups are appended, so that they are
+  /// carried through unchanged:
+  /// - key-value metadata, entries with keys that are already set, e.g. in writer
+  /// properties, are not copied;
+  /// - column orders of columns with the same path, which are only written if all
+  /// copied files have the same column orders, since statistics of copied column
+  /// chunks follow column orders of their files;
+  /// - fields unknown to this version of the crate, fields that are already set are
+  /// not copied.
+  pub fn copy_footer_fields(&mut self, file_metadata: &FileMetaData) {
+    if let Some(key_values) = file_metadata.key_value_metadata() {
+      let existing = self.key_value_metadata.get_or_insert_with(Vec::new);
+      for key_value in key_values {
+        if !existing.iter().any(|kv| kv.key == key_value.key) {
+          existing.push(key_value.clone());
+        }
+      }
+    }
+
+    let schema_descr = file_metadata.schema_descr();
+    let column_orders: Vec<_> = self.descr.columns().iter()
+      .map(|descr| {
+        (0..schema_descr.num_columns())
+          .find(|i| schema_descr.column(*i).path() == descr.path())
+          .map(|i| file_metadata.column_order(i))
+          .unwrap_or(ColumnOrder::UNDEFINED)
+      })
+      .collect();
+    if !self.footer_copied {
+      self.column_orders = Some(column_orders);
+    } else if self.column_orders.as_ref() != Some(&column_orders) {
+      self.column_orders = None;
+    }
+
+    self.unknown_fields.extend(file_metadata.unknown_fields());
+    self.footer_copied = true;
+  }
+
+  /// Sets application-specific key-value metadata to write in the footer of the file,
+  /// replacing the value of an entry with the same key, e.g. one set in writer
+  /// properties. Can be called at any time before the file writer is closed, e.g. with
+  /// values computed while rows are written.
+  ///
+  /// Returns error if file writer has been closed.
+  pub fn set_key_value(&mut self, key_value: KeyValue) -> Result<()> {
+    if self.is_closed {
+      return Err(general_err!("File writer is closed"));
+    }
+    let key_values = self.key_value_metadata.get_or_insert_with(Vec::new);
+    match key_values.iter().position(|kv| kv.key == key_value.key) {
+      Some(pos) => key_values[pos] = key_value,
+      None => key_values.push(key_value)
+    }
+    Ok(())
+  }
+
+  /// Sets offset index and, optionally, column index of column `column` of row group
+  /// `row_group`, e.g. indexes built from page headers of a file written without them.
+  /// Indexes are written before file metadata when the file is closed, page offsets in
+  /// `offset_index` must be offsets in this file.
+  ///
+  /// Returns error if row group has not been closed yet, or column does not exist.
+  pub fn set_page_indexes(
+    &mut self,
+    row_group: usize,
+    column: usize,
+    column_index: Option<ColumnIndex>,
+    offset_index: OffsetIndex
+  ) -> Result<()> {
+    self.assert_closed()?;
+    if row_group >= self.row_groups.len() || column >= self.descr.num_columns() {
+      return Err(general_err!(
+        "Column chunk {} of row group {} is not written", column, row_group));
+    }
+    self.page_indexes.retain(|index| index.0 != row_group || index.1 != column);
+    self.page_indexes.push((row_group, column, column_index, offset_index));
+    Ok(())
+  }
+
+  /// Sets bloom filter of column `column` of row group `row_group`, which is written
+  /// before page indexes when the file is closed. Bloom filter of an encrypted column
+  /// is encrypted with the column key.
+  ///
+  /// Returns error if row group has not been closed yet, or column does not exist.
+  pub fn set_bloom_filter(
+    &mut self,
+    row_group: usize,
+    column: usize,
+    bloom_filter: Sbbf
+  ) -> Result<()> {
+    self.assert_closed()?;
+    if row_group >= self.row_groups.len() || column >= self.descr.num_columns() {
+      return Err(general_err!(
+        "Column chunk {} of row group {} is not written", column, row_group));
+    }
+    self.bloom_filters.retain(|filter| filter.0 != row_group || filter.1 != column);
+    self.bloom_filters.push((row_group, column, bloom_filter));
+    Ok(())
+  }
+
+  /// Writes magic bytes at the beginning of the file, which differ for files with
+  /// encrypted footer.
+  fn start_file(
+    buf: &mut TrackedWrite<W>,
+    encryptor: Option<&FileEncryptor>
+  ) -> Result<()> {
+    match encryptor {
+      Some(encryptor) if encryptor.is_footer_encrypted() => {
+        buf.write_all(&PARQUET_MAGIC_ENCRYPTED_FOOTER)?
+      },
+      _ => buf.write_all(&PARQUET_MAGIC)?
+    }
     Ok(())
   }
 
+  /// Returns cipher of column `column` of row group `row_group`, or `None` if column is
+  /// not encrypted.
+  fn column_cipher(
+    &self,
+    row_group: usize,
+    column: usize
+  ) -> Result<Option<ColumnCipher>> {
+    match self.encryptor {
+      Some(ref encryptor) => {
+        encryptor.column_cipher(self.descr.column(column).path(), row_group, column)
+      },
+      None => Ok(None)
+    }
+  }
+
   /// Finalises active row group writer, otherwise no-op.
   fn finalise_row_group_writer(
     &mut self,
     mut row_group_writer: Box<RowGroupWriter>
   ) -> Result<()> {
     let row_group_metadata = row_group_writer.close()?;
+    self.total_num_rows += row_group_metadata.num_rows() as u64;
     self.row_groups.push(row_group_metadata);
     Ok(())
   }
 
+  /// Writes bloom filters of column chunks, and sets their locations in
+  /// `unknown_enums` of file metadata.
+  fn write_bloom_filters(&mut self, unknown_enums: &mut UnknownEnums) -> Result<()> {
+    self.bloom_filters.sort_by_key(|filter| (filter.0, filter.1));
+    for &(row_group, column, ref bloom_filter) in &self.bloom_filters {
+      let cipher = self.column_cipher(row_group, column)?;
+      let start_pos = self.buf.pos();
+      bloom_filter.write_with_cipher(&mut self.buf, cipher.as_ref())?;
+      let length = (self.buf.pos() - start_pos) as i32;
+      // Path of field `field_id` of metadata of the column chunk
+      let path = |field_id| vec![4, row_group, 1, column, 3, field_id];
+      let offset = compact_bytes(|prot| prot.write_i64(start_pos as i64))?;
+      unknown_enums.remove_field(&path(BLOOM_FILTER_OFFSET_FIELD_ID));
+      unknown_enums.insert_field(path(BLOOM_FILTER_OFFSET_FIELD_ID), TType::I64, offset);
+      let length = compact_bytes(|prot| prot.write_i32(length))?;
+      unknown_enums.remove_field(&path(BLOOM_FILTER_LENGTH_FIELD_ID));
+      unknown_enums.insert_field(path(BLOOM_FILTER_LENGTH_FIELD_ID), TType::I32, length);
+    }
+    Ok(())
+  }
+
+  /// Writes column indexes followed by offset indexes of column chunks, and sets their
+  /// locations in `row_groups`. Page indexes of encrypted columns are encrypted.
+  fn write_page_indexes(&mut self, row_groups: &mut [parquet::RowGroup]) -> Result<()> {
+    self.page_indexes.sort_by_key(|index| (index.0, index.1));
+    for &(row_group, column, ref column_index, _) in &self.page_indexes {
+      if let Some(ref column_index) = *column_index {
+        let mut data = Vec::new();
+        {
+          let mut protocol = TCompactOutputProtocol::new(&mut data);
+          column_index.to_thrift().write_to_out_protocol(&mut protocol)?;
+          protocol.flush()?;
+        }
+        let cipher = self.column_cipher(row_group, column)?;
+        let data = encrypt_module(data, cipher.as_ref(), ModuleType::COLUMN_INDEX)?;
+        let column_chunk = &mut row_groups[row_group].columns[column];
+        column_chunk.column_index_offset = Some(self.buf.pos() as i64);
+        column_chunk.column_index_length = Some(data.len() as i32);
+        self.buf.write_all(&data)?;
+      }
+    }
+    for &(row_group, column, _, ref offset_index) in &self.page_indexes {
+      let mut data = Vec::new();
+      {
+        let mut protocol = TCompactOutputProtocol::new(&mut data);
+        offset_index.to_thrift().write_to_out_protocol(&mut protocol)?;
+        protocol.flush()?;
+      }
+      let cipher = self.column_cipher(row_group, column)?;
+      let data = encrypt_module(data, cipher.as_ref(), ModuleType::OFFSET_INDEX)?;
+      let column_chunk = &mut row_groups[row_group].columns[column];
+      column_chunk.offset_index_offset = Some(self.buf.pos() as i64);
+      column_chunk.offset_index_length = Some(data.len() as i32);
+      self.buf.write_all(&data)?;
+    }
+    Ok(())
+  }
+
   /// Assembles and writes metadata at the end of the file.
+  //
+  // Encrypted footer is preceded by crypto metadata, see `parse_encrypted_metadata` of
+  // the file reader, plaintext footer of an encrypted file is followed by its
+  // signature.
   fn write_metadata(&mut self) -> Result<()> {
+    let mut row_groups: Vec<_> =
+      self.row_groups.as_slice().into_iter().map(|v| v.to_thrift()).collect();
+    let mut unknown_enums =
+      types::to_thrift_unknown_enums(self.schema.as_ref()).nest(&[2]);
+    unknown_enums.extend(&row_groups_to_thrift_unknown_fields(&self.row_groups)?);
+    unknown_enums.extend(&self.unknown_fields);
+    self.write_bloom_filters(&mut unknown_enums)?;
+    self.write_page_indexes(&mut row_groups)?;
+    if let Some(ref encryptor) = self.encryptor {
+      encrypt_column_metadata(
+        encryptor, &self.descr, &mut row_groups, &mut unknown_enums)?;
+      if !encryptor.is_footer_encrypted() {
+        let algorithm = encryptor.algorithm().to_compact_bytes()?;
+        unknown_enums.remove_field(&[ENCRYPTION_ALGORITHM_FIELD_ID]);
+        unknown_enums.remove_field(&[FOOTER_SIGNING_KEY_METADATA_FIELD_ID]);
+        unknown_enums.insert_field(
+          vec![ENCRYPTION_ALGORITHM_FIELD_ID], TType::Struct, algorithm);
+        if let Some(key_metadata) = encryptor.footer_key_metadata() {
+          unknown_enums.insert_field(
+            vec![FOOTER_SIGNING_KEY_METADATA_FIELD_ID],
+            TType::String,
+            binary_to_compact_bytes(key_metadata)?
+          );
+        }
+      }
+    }
+
     let file_metadata = parquet::FileMetaData {
       version: self.props.writer_version().as_num(),
       schema: types::to_thrift(self.schema.as_ref())?,
       num_rows: self.total_num_rows as i64,
-      row_groups: self.row_groups.as_slice().into_iter().map(|v| v.to_thrift()).collect(),
-      key_value_metadata: None,
+      row_groups: row_groups,
+      key_value_metadata: self.key_value_metadata.clone(),
       created_by: Some(self.props.created_by().to_owned()),
-      column_orders: None
+      column_orders: self.column_orders.as_ref()
+        .and_then(|orders| column_orders_to_thrift(orders))
     };
-
-    // Write file metadata
-    let start_pos = self.file.seek(SeekFrom::Current(0))?;
+    let mut metadata = Vec::new();
     {
-      let mut protocol = TCompactOutputProtocol::new(&mut self.file);
+      let mut protocol = TolerantOutputProtocol::for_file_metadata(
+        TCompactOutputProtocol::new(&mut metadata), unknown_enums);
       file_metadata.write_to_out_protocol(&mut protocol)?;
       protocol.flush()?;
     }
-    let end_pos = self.file.seek(SeekFrom::Current(0))?;
+
+    // Write file metadata
+    let start_pos = self.buf.pos();
+    let magic = match self.encryptor {
+      Some(ref encryptor) if encryptor.is_footer_encrypted() => {
+        {
+          let mut protocol = TCompactOutputProtocol::new(&mut self.buf);
+          encryptor.file_crypto_metadata().write(&mut protocol)?;
+          protocol.flush()?;
+        }
+        self.buf.write_all(&encryptor.encrypt_footer(&metadata)?)?;
+        PARQUET_MAGIC_ENCRYPTED_FOOTER
+      },
+      Some(ref encryptor) => {
+        self.buf.write_all(&metadata)?;
+        self.buf.write_all(&encryptor.sign_footer(&metadata)?)?;
+        PARQUET_MAGIC
+      },
+      None => {
+        self.buf.write_all(&metadata)?;
+        PARQUET_MAGIC
+      }
+    };
+    let end_pos = self.buf.pos();
 
     // Write footer
     let mut footer_buffer: [u8; FOOTER_SIZE] = [0; FOOTER_SIZE];
     let metadata_len = (end_pos - start_pos) as i32;
     LittleEndian::write_i32(&mut footer_buffer, metadata_len);
-    (&mut footer_buffer[4..]).write(&PARQUET_MAGIC)?;
-    self.file.write(&footer_buffer)?;
+    (&mut footer_buffer[4..]).write(&magic)?;
+    self.buf.write_all(&footer_buffer)?;
+    self.buf.flush()?;
     Ok(())
   }
 
@@ -208,7 +547,7 @@ impl SerializedFileWriter {
   }
 }
 
-impl FileWriter for SerializedFileWriter {
+impl<W: 'static + Write> FileWriter for SerializedFileWriter<W> {
   #[inline]
   fn next_row_group(&mut self) -> Result<Box<RowGroupWriter>> {
     self.assert_closed()?;
@@ -216,8 +555,8 @@ impl FileWriter for SerializedFileWriter {
     let row_group_writer = SerializedRowGroupWriter::new(
       self.descr.clone(),
       self.props.clone(),
-      &self.file
-    );
+      self.buf.clone()
+    ).with_encryptor(self.encryptor.clone(), self.row_groups.len());
     self.previous_writer_closed = false;
     Ok(Box::new(row_group_writer))
   }
@@ -243,54 +582,102 @@ impl FileWriter for SerializedFileWriter {
 /// A serialized implementation for Parquet [`RowGroupWriter`].
 /// Coordinates writing of a row group with column writers.
 /// See documentation on row group writer for more information.
-pub struct SerializedRowGroupWriter {
+pub struct SerializedRowGroupWriter<W: Write> {
   descr: SchemaDescPtr,
   props: WriterPropertiesPtr,
-  file: File,
+  buf: TrackedWrite<W>,
   total_rows_written: Option<u64>,
   total_bytes_written: u64,
   column_index: usize,
   previous_writer_closed: bool,
   row_group_metadata: Option<RowGroupMetaDataPtr>,
-  column_chunks: Vec<ColumnChunkMetaDataPtr>
+  column_chunks: Vec<ColumnChunkMetaDataPtr>,
+  // Position in sorting columns of the current column writer, if its rows are verified
+  sorting_column_position: Option<usize>,
+  // Orders of consecutive rows for each verified sorting column, see
+  // `ColumnWriterImpl::take_row_orders`
+  row_orders: Vec<Option<Vec<Ordering>>>,
+  encryptor: Option<Rc<FileEncryptor>>,
+  // Ordinal of this row group in the file, part of AAD of encrypted modules
+  ordinal: usize
 }
 
-impl SerializedRowGroupWriter {
+impl<W: 'static + Write> SerializedRowGroupWriter<W> {
   pub fn new(
     schema_descr: SchemaDescPtr,
     properties: WriterPropertiesPtr,
-    file: &File
+    buf: TrackedWrite<W>
   ) -> Self {
     let num_columns = schema_descr.num_columns();
+    let num_sorting_columns = properties.sorting_columns().map_or(0, |c| c.len());
     Self {
       descr: schema_descr,
       props: properties,
-      file: file.try_clone().unwrap(),
+      buf: buf,
       total_rows_written: None,
       total_bytes_written: 0,
       column_index: 0,
       previous_writer_closed: true,
       row_group_metadata: None,
-      column_chunks: Vec::with_capacity(num_columns)
+      column_chunks: Vec::with_capacity(num_columns),
+      sorting_column_position: None,
+      row_orders: vec![None; num_sorting_columns],
+      encryptor: None,
+      ordinal: 0
+    }
+  }
+
+  /// Sets `encryptor` of the file and `ordinal` of this row group in the file, which
+  /// are used to encrypt column chunks.
+  pub(crate) fn with_encryptor(
+    mut self,
+    encryptor: Option<Rc<FileEncryptor>>,
+    ordinal: usize
+  ) -> Self {
+    self.encryptor = encryptor;
+    self.ordinal = ordinal;
+    self
+  }
+
+  /// Returns crypto metadata of the column at `column_index`, or `None` if column is
+  /// not encrypted.
+  fn column_crypto_metadata(&self, column_index: usize) -> Option<ColumnCryptoMetaData> {
+    self.encryptor.as_ref().and_then(|encryptor| {
+      encryptor.column_crypto_metadata(self.descr.column(column_index).path())
+    })
+  }
+
+  /// Returns cipher of the column at `column_index`, or `None` if column is not
+  /// encrypted.
+  fn column_cipher(&self, column_index: usize) -> Result<Option<ColumnCipher>> {
+    match self.encryptor {
+      Some(ref encryptor) => {
+        let column = self.descr.column(column_index);
+        encryptor.column_cipher(column.path(), self.ordinal, column_index)
+      },
+      None => Ok(None)
     }
   }
 
   /// Checks and finalises current column writer.
-  fn finalise_column_writer(&mut self, writer: ColumnWriter) -> Result<()> {
-    let (bytes_written, rows_written, metadata) = match writer {
-      ColumnWriter::BoolColumnWriter(typed) => typed.close()?,
-      ColumnWriter::Int32ColumnWriter(typed) => typed.close()?,
-      ColumnWriter::Int64ColumnWriter(typed) => typed.close()?,
-      ColumnWriter::Int96ColumnWriter(typed) => typed.close()?,
-      ColumnWriter::FloatColumnWriter(typed) => typed.close()?,
-      ColumnWriter::DoubleColumnWriter(typed) => typed.close()?,
-      ColumnWriter::ByteArrayColumnWriter(typed) => typed.close()?,
-      ColumnWriter::FixedLenByteArrayColumnWriter(typed) => typed.close()?
-    };
+  fn finalise_column_writer(&mut self, mut writer: ColumnWriter) -> Result<()> {
+    if let Some(position) = self.sorting_column_position.take() {
+      self.row_orders[position] = take_row_orders(&mut writer);
+    }
+    let (bytes_written, rows_written, metadata) = close_column_writer(writer)?;
+    self.update_column_metrics(bytes_written, rows_written, metadata)
+  }
 
-    // Update row group writer metrics
+  /// Updates row group writer metrics with closed column chunk.
+  fn update_column_metrics(
+    &mut self,
+    bytes_written: u64,
+    rows_written: u64,
+    metadata: ColumnChunkMetaData
+  ) -> Result<()> {
     self.total_bytes_written += bytes_written;
-    self.column_chunks.push(Rc::new(metadata));
+    let crypto_metadata = self.column_crypto_metadata(self.column_chunks.len());
+    self.column_chunks.push(Rc::new(metadata.with_crypto_metadata(crypto_metadata)));
     if let Some(rows) = self.total_rows_written {
       if rows != rows_written {
         return Err(general_err!(
@@ -306,6 +693,53 @@ impl SerializedRowGroupWriter {
     Ok(())
   }
 
+  /// Returns position in sorting columns of the column at `column_index`, if rows of
+  /// this column should be verified.
+  fn verified_sorting_column(&self, column_index: usize) -> Option<usize> {
+    if self.props.sorting_verification() == SortingVerification::NONE {
+      return None;
+    }
+    self.props.sorting_columns().and_then(|sorting_columns| {
+      sorting_columns.iter().position(|c| c.column_idx as usize == column_index)
+    })
+  }
+
+  /// Returns sorting columns to declare in row group metadata, verifying that rows are
+  /// sorted by the leading sorting columns with tracked row orders.
+  fn verified_sorting_columns(&self) -> Result<Option<Vec<SortingColumn>>> {
+    let sorting_columns = match self.props.sorting_columns() {
+      Some(sorting_columns) => sorting_columns.clone(),
+      None => return Ok(None)
+    };
+    let verification = self.props.sorting_verification();
+    if verification == SortingVerification::NONE {
+      return Ok(Some(sorting_columns));
+    }
+
+    let row_orders = self.row_orders.iter()
+      .take_while(|orders| orders.is_some())
+      .map(|orders| orders.as_ref().unwrap())
+      .collect::<Vec<_>>();
+    let num_orders = row_orders.iter().map(|orders| orders.len()).min().unwrap_or(0);
+    for i in 0..num_orders {
+      let order = row_orders.iter()
+        .map(|orders| orders[i])
+        .find(|order| *order != Ordering::Equal)
+        .unwrap_or(Ordering::Equal);
+      if order == Ordering::Greater {
+        if verification == SortingVerification::ERROR {
+          return Err(general_err!(
+            "Rows {} and {} of row group are not sorted by sorting columns",
+            i,
+            i + 1
+          ));
+        }
+        return Ok(None);
+      }
+    }
+    Ok(Some(sorting_columns))
+  }
+
   #[inline]
   fn assert_closed(&self) -> Result<()> {
     if self.row_group_metadata.is_some() {
@@ -325,7 +759,46 @@ impl SerializedRowGroupWriter {
   }
 }
 
-impl RowGroupWriter for SerializedRowGroupWriter {
+impl<W: 'static + Write> SerializedRowGroupWriter<W> {
+  /// Appends encoded column chunk `data` into the sink, updates offsets in column chunk
+  /// metadata, which are relative to the start of `data`, and row group writer metrics.
+  /// `unknown_fields` are fields of column chunk metadata not in the Thrift definition.
+  fn append_column_chunk_data(
+    &mut self,
+    data: &[u8],
+    mut column_chunk: parquet::ColumnChunk,
+    unknown_fields: &UnknownEnums,
+    rows_written: u64
+  ) -> Result<()> {
+    let base_offset = self.buf.pos() as i64;
+    self.buf.write_all(data)?;
+
+    column_chunk.file_offset += base_offset;
+    if let Some(ref mut meta_data) = column_chunk.meta_data {
+      meta_data.data_page_offset += base_offset;
+      meta_data.index_page_offset = meta_data.index_page_offset.map(|v| v + base_offset);
+      meta_data.dictionary_page_offset =
+        meta_data.dictionary_page_offset.map(|v| v + base_offset);
+    }
+
+    // Column chunk metadata is written after pages, same as in `SerializedPageWriter`
+    if self.column_crypto_metadata(self.column_index).is_none() {
+      let mut protocol = TCompactOutputProtocol::new(&mut self.buf);
+      column_chunk.write_to_out_protocol(&mut protocol)?;
+      protocol.flush()?;
+    }
+
+    let metadata = ColumnChunkMetaData::from_thrift_with_unknown_enums(
+      self.descr.column(self.column_index),
+      column_chunk,
+      unknown_fields
+    )?;
+    self.column_index += 1;
+    self.update_column_metrics(data.len() as u64, rows_written, metadata)
+  }
+}
+
+impl<W: 'static + Write> RowGroupWriter for SerializedRowGroupWriter<W> {
   #[inline]
   fn next_column(&mut self) -> Result<Option<ColumnWriter>> {
     self.assert_closed()?;
@@ -334,13 +807,20 @@ impl RowGroupWriter for SerializedRowGroupWriter {
     if self.column_index >= self.descr.num_columns() {
       return Ok(None);
     }
-    let sink = FileSink::new(&self.file);
-    let page_writer = Box::new(SerializedPageWriter::new(sink));
-    let column_writer = get_column_writer(
+    let mut page_writer = SerializedPageWriter::new(self.buf.clone());
+    if let Some(cipher) = self.column_cipher(self.column_index)? {
+      page_writer = page_writer.with_cipher(cipher);
+    }
+    let mut column_writer = get_column_writer(
       self.descr.column(self.column_index),
       self.props.clone(),
-      page_writer
+      Box::new(page_writer)
     );
+    self.sorting_column_position = self.verified_sorting_column(self.column_index);
+    if let Some(position) = self.sorting_column_position {
+      let sorting_column = self.props.sorting_columns().unwrap()[position].clone();
+      set_sorting_column(&mut column_writer, sorting_column);
+    }
     self.column_index += 1;
     self.previous_writer_closed = false;
 
@@ -354,16 +834,71 @@ impl RowGroupWriter for SerializedRowGroupWriter {
     res
   }
 
+  fn append_column_chunk(
+    &mut self,
+    data: &[u8],
+    metadata: &ColumnChunkMetaData,
+    num_rows: u64
+  ) -> Result<()> {
+    self.assert_closed()?;
+    self.assert_previous_writer_closed()?;
+
+    if self.column_index >= self.descr.num_columns() {
+      return Err(general_err!("All columns of row group are already written"));
+    }
+    let descr = self.descr.column(self.column_index);
+    if descr.physical_type() != metadata.column_type() ||
+        descr.path() != metadata.column_path() {
+      return Err(general_err!(
+        "Column chunk {} of type {} does not match column {} of type {}",
+        metadata.column_path(),
+        metadata.column_type(),
+        descr.path(),
+        descr.physical_type()
+      ));
+    }
+    // Pages of encrypted column chunks are bound to their file and position in it
+    if metadata.crypto_metadata().is_some() ||
+        self.column_crypto_metadata(self.column_index).is_some() {
+      return Err(general_err!(
+        "Encrypted column chunk {} cannot be appended", metadata.column_path().string()));
+    }
+
+    // Make offsets relative to the start of column chunk
+    let start = metadata.dictionary_page_offset().unwrap_or(metadata.data_page_offset());
+    let mut column_chunk = metadata.to_thrift();
+    column_chunk.file_path = None;
+    column_chunk.file_offset = data.len() as i64;
+    column_chunk.offset_index_offset = None;
+    column_chunk.offset_index_length = None;
+    column_chunk.column_index_offset = None;
+    column_chunk.column_index_length = None;
+    if let Some(ref mut meta_data) = column_chunk.meta_data {
+      meta_data.total_compressed_size = data.len() as i64;
+      meta_data.data_page_offset -= start;
+      meta_data.index_page_offset = meta_data.index_page_offset.map(|v| v - start);
+      meta_data.dictionary_page_offset =
+        meta_data.dictionary_page_offset.map(|v| v - start);
+    }
+    // Bloom filter is stored outside of column chunk and is not appended
+    let mut unknown_fields = metadata.to_thrift_unknown_fields()?;
+    unknown_fields.remove_field(&[3, BLOOM_FILTER_OFFSET_FIELD_ID]);
+    unknown_fields.remove_field(&[3, BLOOM_FILTER_LENGTH_FIELD_ID]);
+    self.append_column_chunk_data(data, column_chunk, &unknown_fields, num_rows)
+  }
+
   #[inline]
   fn close(&mut self) -> Result<RowGroupMetaDataPtr> {
     if self.row_group_metadata.is_none() {
       self.assert_previous_writer_closed()?;
 
+      let sorting_columns = self.verified_sorting_columns()?;
       let row_group_metadata =
         RowGroupMetaData::builder(self.descr.clone())
           .set_column_metadata(self.column_chunks.clone())
           .set_total_byte_size(self.total_bytes_written as i64)
           .set_num_rows(self.total_rows_written.unwrap_or(0) as i64)
+          .set_sorting_columns(sorting_columns)
           .build()?;
 
       self.row_group_metadata = Some(Rc::new(row_group_metadata));
@@ -372,6 +907,203 @@ impl RowGroupWriter for SerializedRowGroupWriter {
     let metadata = self.row_group_metadata.as_ref().unwrap().clone();
     Ok(metadata)
   }
+
+  /// Column chunks are encoded and compressed in memory, and appended to the file in
+  /// schema order once all of them are finished.
+  #[cfg(feature = "rayon")]
+  fn write_columns_parallel(
+    &mut self,
+    f: &(Fn(usize, &mut ColumnWriter) -> Result<()> + Sync + Send)
+  ) -> Result<()> {
+    self.assert_closed()?;
+    self.assert_previous_writer_closed()?;
+
+    let schema = types::to_thrift(self.descr.root_schema())?;
+    let props = self.props.as_ref().clone();
+    let ciphers = (self.column_index..self.descr.num_columns())
+      .map(|i| self.column_cipher(i))
+      .collect::<Result<Vec<_>>>()?;
+    let chunks = (self.column_index..self.descr.num_columns()).into_par_iter()
+      .zip(ciphers)
+      .map(|(i, cipher)| encode_column_chunk(&schema[..], &props, i, cipher, f))
+      .collect::<Result<Vec<EncodedColumnChunk>>>()?;
+
+    for chunk in chunks {
+      self.append_column_chunk_data(
+        &chunk.data[..], chunk.metadata, &chunk.unknown_fields, chunk.rows_written)?;
+    }
+    Ok(())
+  }
+}
+
+/// Closes column writer and returns total bytes written, total rows written and column
+/// chunk metadata.
+fn close_column_writer(writer: ColumnWriter) -> Result<(u64, u64, ColumnChunkMetaData)> {
+  match writer {
+    ColumnWriter::BoolColumnWriter(typed) => typed.close(),
+    ColumnWriter::Int32ColumnWriter(typed) => typed.close(),
+    ColumnWriter::Int64ColumnWriter(typed) => typed.close(),
+    ColumnWriter::Int96ColumnWriter(typed) => typed.close(),
+    ColumnWriter::FloatColumnWriter(typed) => typed.close(),
+    ColumnWriter::DoubleColumnWriter(typed) => typed.close(),
+    ColumnWriter::ByteArrayColumnWriter(typed) => typed.close(),
+    ColumnWriter::FixedLenByteArrayColumnWriter(typed) => typed.close()
+  }
+}
+
+/// Enables tracking of row orders of column writer according to `sorting_column`.
+fn set_sorting_column(writer: &mut ColumnWriter, sorting_column: SortingColumn) {
+  match *writer {
+    ColumnWriter::BoolColumnWriter(ref mut typed) =>
+      typed.set_sorting_column(sorting_column),
+    ColumnWriter::Int32ColumnWriter(ref mut typed) =>
+      typed.set_sorting_column(sorting_column),
+    ColumnWriter::Int64ColumnWriter(ref mut typed) =>
+      typed.set_sorting_column(sorting_column),
+    ColumnWriter::Int96ColumnWriter(ref mut typed) =>
+      typed.set_sorting_column(sorting_column),
+    ColumnWriter::FloatColumnWriter(ref mut typed) =>
+      typed.set_sorting_column(sorting_column),
+    ColumnWriter::DoubleColumnWriter(ref mut typed) =>
+      typed.set_sorting_column(sorting_column),
+    ColumnWriter::ByteArrayColumnWriter(ref mut typed) =>
+      typed.set_sorting_column(sorting_column),
+    ColumnWriter::FixedLenByteArrayColumnWriter(ref mut typed) =>
+      typed.set_sorting_column(sorting_column)
+  }
+}
+
+/// Returns tracked row orders of column writer, if any.
+fn take_row_orders(writer: &mut ColumnWriter) -> Option<Vec<Ordering>> {
+  match *writer {
+    ColumnWriter::BoolColumnWriter(ref mut typed) => typed.take_row_orders(),
+    ColumnWriter::Int32ColumnWriter(ref mut typed) => typed.take_row_orders(),
+    ColumnWriter::Int64ColumnWriter(ref mut typed) => typed.take_row_orders(),
+    ColumnWriter::Int96ColumnWriter(ref mut typed) => typed.take_row_orders(),
+    ColumnWriter::FloatColumnWriter(ref mut typed) => typed.take_row_orders(),
+    ColumnWriter::DoubleColumnWriter(ref mut typed) => typed.take_row_orders(),
+    ColumnWriter::ByteArrayColumnWriter(ref mut typed) => typed.take_row_orders(),
+    ColumnWriter::FixedLenByteArrayColumnWriter(ref mut typed) => typed.take_row_orders()
+  }
+}
+
+/// Column chunk pages encoded in memory, offsets in metadata are relative to the start
+/// of `data`.
+#[cfg(feature = "rayon")]
+struct EncodedColumnChunk {
+  data: Vec<u8>,
+  metadata: parquet::ColumnChunk,
+  unknown_fields: UnknownEnums,
+  rows_written: u64
+}
+
+/// Encodes `i`th column of the schema in memory, pages are encrypted with `cipher` if
+/// set. Schema and properties are passed in a thread-safe form and reassembled, since
+/// schema descriptors are reference counted and cannot be shared between threads.
+#[cfg(feature = "rayon")]
+fn encode_column_chunk(
+  schema: &[parquet::SchemaElement],
+  props: &WriterProperties,
+  i: usize,
+  cipher: Option<ColumnCipher>,
+  f: &(Fn(usize, &mut ColumnWriter) -> Result<()> + Sync + Send)
+) -> Result<EncodedColumnChunk> {
+  let descr = SchemaDescriptor::new(types::from_thrift(schema)?);
+  let sink = TrackedWrite::new(Vec::new());
+  let mut page_writer = SerializedPageWriter::new(sink.clone());
+  if let Some(cipher) = cipher {
+    page_writer = page_writer.with_cipher(cipher);
+  }
+  let mut column_writer =
+    get_column_writer(descr.column(i), Rc::new(props.clone()), Box::new(page_writer));
+
+  f(i, &mut column_writer)?;
+  let (bytes_written, rows_written, metadata) = close_column_writer(column_writer)?;
+
+  // Page writer also appends column chunk metadata, which is discarded here and written
+  // with updated offsets later.
+  let mut data = sink.into_inner()?;
+  data.truncate(bytes_written as usize);
+  Ok(EncodedColumnChunk {
+    data: data,
+    metadata: metadata.to_thrift(),
+    unknown_fields: metadata.to_thrift_unknown_fields()?,
+    rows_written: rows_written
+  })
+}
+
+/// Encrypts serialized `data` of a module with `cipher`, if set, otherwise returns
+/// `data` unchanged.
+fn encrypt_module(
+  data: Vec<u8>,
+  cipher: Option<&ColumnCipher>,
+  module_type: ModuleType
+) -> Result<Vec<u8>> {
+  match cipher {
+    Some(cipher) => cipher.encrypt(module_type, 0, &data),
+    None => Ok(data)
+  }
+}
+
+/// Encrypts metadata of encrypted columns of `row_groups`, and stores it in
+/// `encrypted_column_metadata` field of column chunks in `unknown_enums`. Plaintext
+/// metadata is removed in files with encrypted footer, and is stripped of statistics in
+/// files with plaintext footer. Metadata of columns encrypted with the footer key is
+/// not encrypted separately in files with encrypted footer.
+fn encrypt_column_metadata(
+  encryptor: &FileEncryptor,
+  descr: &SchemaDescriptor,
+  row_groups: &mut [parquet::RowGroup],
+  unknown_enums: &mut UnknownEnums
+) -> Result<()> {
+  let is_footer_encrypted = encryptor.is_footer_encrypted();
+  for (i, row_group) in row_groups.iter_mut().enumerate() {
+    for (j, column) in row_group.columns.iter_mut().enumerate() {
+      let descr = descr.column(j);
+      let path = descr.path();
+      match encryptor.column_crypto_metadata(path) {
+        Some(ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY) if is_footer_encrypted => {
+          continue
+        },
+        Some(_) => {},
+        None => continue
+      }
+      let cipher = match encryptor.column_cipher(path, i, j)? {
+        Some(cipher) => cipher,
+        None => continue
+      };
+      // Path of field `field_id` of the column chunk
+      let field_path = |field_id| vec![4, i, 1, j, field_id];
+      let mut data = Vec::new();
+      if let Some(ref meta_data) = column.meta_data {
+        let mut protocol = TolerantOutputProtocol::for_column_metadata(
+          TCompactOutputProtocol::new(&mut data), unknown_enums.scope(&field_path(3)));
+        meta_data.write_to_out_protocol(&mut protocol)?;
+        protocol.flush()?;
+      }
+      let module = cipher.encrypt(ModuleType::COLUMN_META_DATA, 0, &data)?;
+      unknown_enums.insert_field(
+        field_path(ENCRYPTED_COLUMN_METADATA_FIELD_ID),
+        TType::String,
+        binary_to_compact_bytes(&module)?
+      );
+
+      if is_footer_encrypted {
+        column.meta_data = None;
+        unknown_enums.remove_scope(&field_path(3));
+      } else if let Some(ref mut meta_data) = column.meta_data {
+        // Statistics would reveal values of the column to readers without its key
+        meta_data.statistics = None;
+        meta_data.encoding_stats = None;
+        let mut meta_data_path = field_path(3);
+        meta_data_path.push(13);
+        unknown_enums.remove_scope(&meta_data_path);
+        meta_data_path[5] = SIZE_STATISTICS_FIELD_ID;
+        unknown_enums.remove_field(&meta_data_path);
+      }
+    }
+  }
+  Ok(())
 }
 
 /// A serialized implementation for Parquet [`PageWriter`].
@@ -379,26 +1111,54 @@ impl RowGroupWriter for SerializedRowGroupWriter {
 ///
 /// `SerializedPageWriter` should not be used after calling `close()`.
 pub struct SerializedPageWriter<T: Write + Position> {
-  sink: T
+  sink: T,
+  cipher: Option<ColumnCipher>,
+  // Ordinal of the next data page in the column chunk, part of AAD of encrypted pages
+  data_page_ordinal: usize
 }
 
 impl<T: Write + Position> SerializedPageWriter<T> {
   /// Creates new page writer.
   pub fn new(sink: T) -> Self {
-    Self { sink: sink }
+    Self { sink: sink, cipher: None, data_page_ordinal: 0 }
+  }
+
+  /// Encrypts pages and page headers of encrypted column chunk with `cipher`. Column
+  /// chunk metadata is then only written in file metadata.
+  pub(crate) fn with_cipher(mut self, cipher: ColumnCipher) -> Self {
+    self.cipher = Some(cipher);
+    self
+  }
+
+  /// Encrypts `data` of a page or page header with the cipher of the column chunk,
+  /// returns `None` if column chunk is not encrypted.
+  fn encrypt(&self, module_type: ModuleType, data: &[u8]) -> Result<Option<Vec<u8>>> {
+    match self.cipher {
+      Some(ref cipher) => {
+        cipher.encrypt(module_type, self.data_page_ordinal, data).map(Some)
+      },
+      None => Ok(None)
+    }
   }
 
-  /// Serializes page header into Thrift.
+  /// Serializes page header into Thrift, header is encrypted as a module of
+  /// `module_type` if column chunk is encrypted.
   /// Returns number of bytes that have been written into the sink.
   #[inline]
-  fn serialize_page_header(&mut self, header: parquet::PageHeader) -> Result<usize> {
-    let start_pos = self.sink.pos();
+  fn serialize_page_header(
+    &mut self,
+    header: parquet::PageHeader,
+    module_type: ModuleType
+  ) -> Result<usize> {
+    let mut data = Vec::new();
     {
-      let mut protocol = TCompactOutputProtocol::new(&mut self.sink);
+      let mut protocol = TCompactOutputProtocol::new(&mut data);
       header.write_to_out_protocol(&mut protocol)?;
       protocol.flush()?;
     }
-    Ok((self.sink.pos() - start_pos) as usize)
+    let data = self.encrypt(module_type, &data)?.unwrap_or(data);
+    self.sink.write_all(&data)?;
+    Ok(data.len())
   }
 
   /// Serializes column chunk into Thrift.
@@ -419,6 +1179,17 @@ impl<T: Write + Position> PageWriter for SerializedPageWriter<T> {
     let num_values = page.num_values();
     let encoding = page.encoding();
     let page_type = page.page_type();
+    let is_data_page =
+      page_type == PageType::DATA_PAGE || page_type == PageType::DATA_PAGE_V2;
+    let (module_type, header_module_type) = if is_data_page {
+      (ModuleType::DATA_PAGE, ModuleType::DATA_PAGE_HEADER)
+    } else {
+      (ModuleType::DICTIONARY_PAGE, ModuleType::DICTIONARY_PAGE_HEADER)
+    };
+    // Size of encrypted page is the size of its module
+    let module = self.encrypt(module_type, page.data())?;
+    let data = module.as_ref().map_or(page.data(), |module| &module[..]);
+    let compressed_size = if module.is_some() { data.len() } else { compressed_size };
 
     let mut page_header = parquet::PageHeader {
       type_: page_type.into(),
@@ -481,8 +1252,8 @@ impl<T: Write + Position> PageWriter for SerializedPageWriter<T> {
 
     let start_pos = self.sink.pos();
 
-    let header_size = self.serialize_page_header(page_header)?;
-    self.sink.write_all(page.data())?;
+    let header_size = self.serialize_page_header(page_header, header_module_type)?;
+    self.sink.write_all(data)?;
 
     let mut spec = PageWriteSpec::new();
     spec.page_type = page_type;
@@ -491,14 +1262,19 @@ impl<T: Write + Position> PageWriter for SerializedPageWriter<T> {
     spec.offset = start_pos;
     spec.bytes_written = self.sink.pos() - start_pos;
     // Number of values is incremented for data pages only
-    if page_type == PageType::DATA_PAGE || page_type == PageType::DATA_PAGE_V2 {
+    if is_data_page {
       spec.num_values = num_values;
+      self.data_page_ordinal += 1;
     }
 
     Ok(spec)
   }
 
   fn write_metadata(&mut self, metadata: &ColumnChunkMetaData) -> Result<()> {
+    // Plaintext metadata would reveal statistics of encrypted column
+    if self.cipher.is_some() {
+      return Ok(());
+    }
     self.serialize_column_chunk(metadata.to_thrift())
   }
 
@@ -508,20 +1284,109 @@ impl<T: Write + Position> PageWriter for SerializedPageWriter<T> {
   }
 }
 
+// ----------------------------------------------------------------------
+// In-memory writers
+
+/// In-memory sink for file writer.
+///
+/// Clones share the same buffer, so a clone can be passed into
+/// `SerializedFileWriter`, while the original is used to access written bytes.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryWriteableCursor {
+  buffer: Rc<RefCell<Vec<u8>>>
+}
+
+impl InMemoryWriteableCursor {
+  /// Creates new empty in-memory sink.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Returns copy of the bytes written so far.
+  pub fn data(&self) -> Vec<u8> {
+    self.buffer.borrow().clone()
+  }
+
+  /// Returns number of bytes written so far.
+  pub fn len(&self) -> usize {
+    self.buffer.borrow().len()
+  }
+
+  /// Consumes the sink and returns written bytes, or `None` if buffer is still shared
+  /// with other clones.
+  pub fn into_inner(self) -> Option<Vec<u8>> {
+    Rc::try_unwrap(self.buffer).ok().map(|buf| buf.into_inner())
+  }
+}
+
+impl Write for InMemoryWriteableCursor {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.buffer.borrow_mut().write(buf)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+/// Writes a complete Parquet file into memory and returns its bytes.
+///
+/// Function `f` is called with a file writer to write row groups, file writer is closed
+/// afterwards, so `f` must not close it.
+///
+/// # Example
+///
+/// ```rust
+/// use std::rc::Rc;
+/// use parquet::file::properties::WriterProperties;
+/// use parquet::file::writer::{FileWriter, RowGroupWriter, write_to_bytes};
+/// use parquet::schema::parser::parse_message_type;
+///
+/// let schema = Rc::new(parse_message_type("message schema { REQUIRED INT32 a; }").unwrap());
+/// let props = Rc::new(WriterProperties::builder().build());
+/// let bytes = write_to_bytes(schema, props, |writer| {
+///   let mut row_group_writer = writer.next_row_group()?;
+///   while let Some(col_writer) = row_group_writer.next_column()? {
+///     // ... write values to a column writer
+///     row_group_writer.close_column(col_writer)?;
+///   }
+///   writer.close_row_group(row_group_writer)
+/// }).unwrap();
+/// assert_eq!(&bytes[0..4], b"PAR1");
+/// ```
+pub fn write_to_bytes<F>(
+  schema: TypePtr,
+  properties: WriterPropertiesPtr,
+  f: F
+) -> Result<Vec<u8>>
+  where F: FnOnce(&mut SerializedFileWriter<Vec<u8>>) -> Result<()> {
+  let mut writer = SerializedFileWriter::new(Vec::new(), schema, properties)?;
+  f(&mut writer)?;
+  writer.close()?;
+  writer.into_inner()
+}
+
 
 #[cfg(test)]
 mod tests {
   use std::error::Error;
+  use std::fs::File;
   use std::io::Cursor;
 
   use super::*;
-  use basic::{Compression, Encoding, Repetition, Type};
+  use basic::{Compression, Encoding, LogicalType, Repetition, SortOrder, Type};
   use column::page::PageReader;
   use compression::{Codec, create_codec};
+  use encryption::decrypt::FileDecryptionProperties;
+  use encryption::encrypt::FileEncryptionProperties;
+  use encryption::metadata::EncryptionAlgorithm;
   use file::properties::WriterProperties;
   use file::reader::{FileReader, SerializedFileReader, SerializedPageReader};
+  use file::page_index::{BoundaryOrder, PageLocation};
   use file::statistics::{Statistics, from_thrift, to_thrift};
   use record::RowAccessor;
+  use schema::parser::parse_message_type;
+  use schema::types::ColumnPath;
   use util::memory::ByteBufferPtr;
   use util::test_common::get_temp_file;
 
@@ -624,6 +1489,31 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_file_writer_schema_validation() {
+    let schema = Rc::new(
+      parse_message_type("
+        message schema {
+          REQUIRED INT32 a = 1;
+          OPTIONAL INT32 b = 1;
+        }
+      ").unwrap()
+    );
+
+    // Invalid schema is written, unless validation is enabled
+    let props = Rc::new(WriterProperties::builder().build());
+    assert!(SerializedFileWriter::new(Vec::new(), schema.clone(), props).is_ok());
+
+    let props = Rc::new(WriterProperties::builder()
+      .set_schema_validation_enabled(true)
+      .build());
+    let res = SerializedFileWriter::new(Vec::new(), schema, props);
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(err.description(), "Invalid schema: fields a and b have the same id 1");
+    }
+  }
+
   #[test]
   fn test_file_writer_empty_file() {
     let file = get_temp_file("test_file_writer_write_empty_file", &[]);
@@ -646,6 +1536,103 @@ mod tests {
     assert_eq!(reader.get_row_iter(None).unwrap().count(), 0);
   }
 
+  #[test]
+  fn test_file_writer_copy_footer_fields() {
+    let schema = Rc::new(
+      types::Type::group_type_builder("schema")
+        .with_fields(&mut vec![
+          Rc::new(types::Type::primitive_type_builder("col1", Type::INT32)
+            .build().unwrap())
+        ])
+        .build()
+        .unwrap()
+    );
+    let schema_descr = Rc::new(SchemaDescriptor::new(schema.clone()));
+    let key_value = |key: &str, value: &str| {
+      KeyValue::new(key.to_owned(), value.to_owned())
+    };
+    let first = FileMetaData::new(
+      1,
+      0,
+      None,
+      schema.clone(),
+      schema_descr.clone(),
+      Some(vec![ColumnOrder::TYPE_DEFINED_ORDER(SortOrder::SIGNED)])
+    ).with_key_value_metadata(Some(vec![key_value("a", "1"), key_value("b", "1")]));
+    let second = FileMetaData::new(1, 0, None, schema.clone(), schema_descr, None)
+      .with_key_value_metadata(Some(vec![key_value("b", "2"), key_value("c", "2")]));
+
+    // Key-value metadata of writer properties and of earlier files takes precedence,
+    // column orders are kept only if they are the same in all files
+    let column_orders = vec![ColumnOrder::TYPE_DEFINED_ORDER(SortOrder::SIGNED)];
+    let cases = vec![
+      (vec![&first], vec![("a", "0"), ("b", "1")], Some(column_orders.clone())),
+      (vec![&first, &first], vec![("a", "0"), ("b", "1")], Some(column_orders)),
+      (vec![&first, &second], vec![("a", "0"), ("b", "1"), ("c", "2")], None)
+    ];
+    for (files, expected_key_values, expected_column_orders) in cases {
+      let file = get_temp_file("test_file_writer_copy_footer_fields", &[]);
+      let props = WriterProperties::builder()
+        .set_key_value_metadata(Some(vec![key_value("a", "0")]))
+        .build();
+      let mut writer = SerializedFileWriter::new(
+        file.try_clone().unwrap(), schema.clone(), Rc::new(props)).unwrap();
+      for file_metadata in &files {
+        writer.copy_footer_fields(file_metadata);
+      }
+      writer.close().unwrap();
+
+      let reader = SerializedFileReader::new(file).unwrap();
+      let file_metadata = reader.metadata().file_metadata();
+      let expected_key_values: Vec<_> = expected_key_values.iter()
+        .map(|&(key, value)| key_value(key, value))
+        .collect();
+      assert_eq!(file_metadata.key_value_metadata(), Some(&expected_key_values));
+      assert_eq!(file_metadata.column_orders(), expected_column_orders.as_ref());
+    }
+  }
+
+  #[test]
+  fn test_file_writer_set_key_value() {
+    let file = get_temp_file("test_file_writer_set_key_value", &[]);
+    let schema = Rc::new(
+      types::Type::group_type_builder("schema")
+        .with_fields(&mut vec![
+          Rc::new(types::Type::primitive_type_builder("col1", Type::INT32)
+            .with_repetition(Repetition::REQUIRED)
+            .build().unwrap())
+        ])
+        .build().unwrap()
+    );
+    let key_value = |key: &str, value: &str| {
+      KeyValue::new(key.to_owned(), value.to_owned())
+    };
+    let props = WriterProperties::builder()
+      .set_application("app", "1.0.0")
+      .set_key_value_metadata(Some(vec![key_value("a", "0"), key_value("b", "0")]))
+      .build();
+    let mut writer = SerializedFileWriter::new(
+      file.try_clone().unwrap(), schema, Rc::new(props)).unwrap();
+    writer.set_key_value(key_value("b", "1")).unwrap();
+    writer.set_key_value(key_value("c", "1")).unwrap();
+    writer.close().unwrap();
+    let res = writer.set_key_value(key_value("d", "1"));
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(err.description(), "File writer is closed");
+    }
+
+    let reader = SerializedFileReader::new(file).unwrap();
+    let file_metadata = reader.metadata().file_metadata();
+    assert_eq!(
+      file_metadata.key_value_metadata(),
+      Some(&vec![key_value("a", "0"), key_value("b", "1"), key_value("c", "1")])
+    );
+    let writer_version = file_metadata.writer_version();
+    assert_eq!(writer_version.application(), "app");
+    assert_eq!(writer_version.version(), Some("1.0.0"));
+  }
+
   #[test]
   fn test_file_writer_empty_row_groups() {
     let file = get_temp_file("test_file_writer_write_empty_row_groups", &[]);
@@ -680,6 +1667,386 @@ mod tests {
     ]);
   }
 
+  #[test]
+  fn test_file_writer_non_seekable_sink() {
+    let schema = Rc::new(
+      types::Type::group_type_builder("schema")
+        .with_fields(&mut vec![
+          Rc::new(types::Type::primitive_type_builder("col1", Type::INT32)
+            .with_repetition(Repetition::REQUIRED)
+            .build().unwrap())
+        ])
+        .build()
+        .unwrap()
+    );
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = SerializedFileWriter::new(Vec::new(), schema, props).unwrap();
+    for _ in 0..2 {
+      let mut row_group_writer = writer.next_row_group().unwrap();
+      let mut col_writer = row_group_writer.next_column().unwrap().unwrap();
+      if let ColumnWriter::Int32ColumnWriter(ref mut typed) = col_writer {
+        typed.write_batch(&[1, 2, 3], None, None).unwrap();
+      }
+      row_group_writer.close_column(col_writer).unwrap();
+      writer.close_row_group(row_group_writer).unwrap();
+    }
+    writer.close().unwrap();
+
+    let bytes = writer.into_inner().unwrap();
+    let file = get_temp_file("test_file_writer_non_seekable_sink", &bytes[..]);
+    let reader = SerializedFileReader::new(file).unwrap();
+    assert_eq!(reader.num_row_groups(), 2);
+    let res = reader.get_row_iter(None).unwrap()
+      .map(|row| row.unwrap().get_int(0).unwrap())
+      .collect::<Vec<i32>>();
+    assert_eq!(res, vec![1, 2, 3, 1, 2, 3]);
+  }
+
+  #[test]
+  fn test_file_writer_into_inner_before_close() {
+    let schema = Rc::new(types::Type::group_type_builder("schema").build().unwrap());
+    let props = Rc::new(WriterProperties::builder().build());
+    let writer = SerializedFileWriter::new(Vec::new(), schema, props).unwrap();
+    let res = writer.into_inner();
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(err, general_err!("File writer is not closed"));
+    }
+  }
+
+  #[test]
+  fn test_in_memory_writeable_cursor() {
+    let cursor = InMemoryWriteableCursor::new();
+    let mut cloned = cursor.clone();
+    cloned.write_all(&[1, 2, 3]).unwrap();
+    assert_eq!(cursor.len(), 3);
+    assert_eq!(cursor.data(), vec![1, 2, 3]);
+
+    assert_eq!(cursor.clone().into_inner(), None);
+    drop(cloned);
+    assert_eq!(cursor.into_inner(), Some(vec![1, 2, 3]));
+  }
+
+  #[test]
+  fn test_file_writer_in_memory_cursor() {
+    let schema = Rc::new(types::Type::group_type_builder("schema").build().unwrap());
+    let props = Rc::new(WriterProperties::builder().build());
+    let cursor = InMemoryWriteableCursor::new();
+    let mut writer = SerializedFileWriter::new(cursor.clone(), schema.clone(), props.clone())
+      .unwrap();
+    writer.close().unwrap();
+
+    let bytes = write_to_bytes(schema, props, |_| Ok(())).unwrap();
+    assert_eq!(cursor.data(), bytes);
+    assert_eq!(&bytes[0..4], &PARQUET_MAGIC);
+    assert_eq!(&bytes[bytes.len() - 4..], &PARQUET_MAGIC);
+  }
+
+  #[test]
+  fn test_write_to_bytes_roundtrip() {
+    let schema = Rc::new(
+      types::Type::group_type_builder("schema")
+        .with_fields(&mut vec![
+          Rc::new(types::Type::primitive_type_builder("col1", Type::INT32)
+            .with_repetition(Repetition::REQUIRED)
+            .build().unwrap())
+        ])
+        .build()
+        .unwrap()
+    );
+    let props = Rc::new(WriterProperties::builder().build());
+    let bytes = write_to_bytes(schema, props, |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      let mut col_writer = row_group_writer.next_column()?.unwrap();
+      if let ColumnWriter::Int32ColumnWriter(ref mut typed) = col_writer {
+        typed.write_batch(&[4, 5, 6], None, None)?;
+      }
+      row_group_writer.close_column(col_writer)?;
+      writer.close_row_group(row_group_writer)
+    }).unwrap();
+
+    let file = get_temp_file("test_write_to_bytes_roundtrip", &bytes[..]);
+    let reader = SerializedFileReader::new(file).unwrap();
+    let res = reader.get_row_iter(None).unwrap()
+      .map(|row| row.unwrap().get_int(0).unwrap())
+      .collect::<Vec<i32>>();
+    assert_eq!(res, vec![4, 5, 6]);
+  }
+
+  #[test]
+  fn test_write_uuid_float16_roundtrip() {
+    use data_type::ByteArray;
+    use schema::parser::parse_message_type;
+
+    let schema = Rc::new(parse_message_type("
+      message schema {
+        REQUIRED FIXED_LEN_BYTE_ARRAY (16) id (UUID);
+        REQUIRED FIXED_LEN_BYTE_ARRAY (2) score (FLOAT16);
+      }
+    ").unwrap());
+    let props = Rc::new(WriterProperties::builder().build());
+    let bytes = write_to_bytes(schema.clone(), props, |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      let columns = vec![
+        vec![ByteArray::from((0..16).collect::<Vec<u8>>())],
+        vec![ByteArray::from_f32_as_f16(0.25)]
+      ];
+      for values in columns {
+        let mut col_writer = row_group_writer.next_column()?.unwrap();
+        if let ColumnWriter::FixedLenByteArrayColumnWriter(ref mut typed) = col_writer {
+          typed.write_batch(&values[..], None, None)?;
+        }
+        row_group_writer.close_column(col_writer)?;
+      }
+      writer.close_row_group(row_group_writer)
+    }).unwrap();
+
+    let file = get_temp_file("test_write_uuid_float16_roundtrip", &bytes[..]);
+    let reader = SerializedFileReader::new(file).unwrap();
+    let metadata = reader.metadata();
+    assert_eq!(metadata.file_metadata().schema(), schema.as_ref());
+    let file_metadata = metadata.file_metadata();
+    let descr = file_metadata.schema_descr();
+    assert_eq!(descr.column(0).logical_type(), LogicalType::UUID);
+    assert_eq!(descr.column(1).logical_type(), LogicalType::FLOAT16);
+    let res = reader.get_row_iter(None).unwrap()
+      .map(|row| row.unwrap())
+      .map(|row| (row.get_string(0).unwrap().clone(), row.get_float(1).unwrap()))
+      .collect::<Vec<(String, f32)>>();
+    assert_eq!(res, vec![("00010203-0405-0607-0809-0a0b0c0d0e0f".to_owned(), 0.25)]);
+  }
+
+  #[test]
+  fn test_row_group_writer_append_column_chunk() {
+    use schema::parser::parse_message_type;
+
+    let schema =
+      Rc::new(parse_message_type("message schema { REQUIRED INT32 a; }").unwrap());
+    let props = Rc::new(WriterProperties::builder().build());
+    let bytes = write_to_bytes(schema.clone(), props.clone(), |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      let mut col_writer = row_group_writer.next_column()?.unwrap();
+      if let ColumnWriter::Int32ColumnWriter(ref mut typed) = col_writer {
+        typed.write_batch(&[1, 2, 3], None, None)?;
+      }
+      row_group_writer.close_column(col_writer)?;
+      writer.close_row_group(row_group_writer)
+    }).unwrap();
+    let file = get_temp_file("test_row_group_writer_append_column_chunk", &bytes[..]);
+    let reader = SerializedFileReader::new(file).unwrap();
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    let row_group_metadata = row_group_reader.metadata();
+    let data = row_group_reader.get_column_chunk_bytes(0).unwrap();
+
+    // Column chunk is appended to two row groups, offsets are shifted in the second one
+    let bytes = write_to_bytes(schema.clone(), props.clone(), |writer| {
+      for _ in 0..2 {
+        let mut row_group_writer = writer.next_row_group()?;
+        row_group_writer.append_column_chunk(&data, row_group_metadata.column(0), 3)?;
+        writer.close_row_group(row_group_writer)?;
+      }
+      Ok(())
+    }).unwrap();
+    let file = get_temp_file("test_row_group_writer_append_chunk_copy", &bytes[..]);
+    let reader = SerializedFileReader::new(file).unwrap();
+    assert_eq!(reader.num_row_groups(), 2);
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 6);
+    let res = reader.get_row_iter(None).unwrap()
+      .map(|row| row.unwrap().get_int(0).unwrap())
+      .collect::<Vec<i32>>();
+    assert_eq!(res, vec![1, 2, 3, 1, 2, 3]);
+
+    // Column chunk does not match the next column
+    let other_schema =
+      Rc::new(parse_message_type("message schema { REQUIRED INT64 a; }").unwrap());
+    let res = write_to_bytes(other_schema, props, |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      row_group_writer.append_column_chunk(&data, row_group_metadata.column(0), 3)
+    });
+    assert_eq!(
+      res.err().unwrap(),
+      general_err!(
+        "Column chunk \"a\" of type INT32 does not match column \"a\" of type INT64"
+      )
+    );
+  }
+
+  #[test]
+  fn test_file_writer_size_statistics() {
+    use data_type::ByteArray;
+    use file::size_statistics::SizeStatistics;
+    use schema::parser::parse_message_type;
+
+    let schema =
+      Rc::new(parse_message_type("message schema { OPTIONAL BYTE_ARRAY a; }").unwrap());
+    let props = Rc::new(WriterProperties::builder().build());
+    let bytes = write_to_bytes(schema.clone(), props.clone(), |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      let mut col_writer = row_group_writer.next_column()?.unwrap();
+      if let ColumnWriter::ByteArrayColumnWriter(ref mut typed) = col_writer {
+        let values = vec![ByteArray::from("ab"), ByteArray::from("c")];
+        typed.write_batch(&values, Some(&[1, 0, 1]), None)?;
+      }
+      row_group_writer.close_column(col_writer)?;
+      writer.close_row_group(row_group_writer)
+    }).unwrap();
+    let file = get_temp_file("test_file_writer_size_statistics", &bytes[..]);
+    let reader = SerializedFileReader::new(file).unwrap();
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    let row_group_metadata = row_group_reader.metadata();
+    let expected = SizeStatistics::new(Some(3), None, Some(vec![1, 2]));
+    assert_eq!(row_group_metadata.column(0).size_statistics(), Some(&expected));
+
+    // Size statistics are kept when column chunk is appended
+    let data = row_group_reader.get_column_chunk_bytes(0).unwrap();
+    let bytes = write_to_bytes(schema, props, |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      row_group_writer.append_column_chunk(&data, row_group_metadata.column(0), 3)?;
+      writer.close_row_group(row_group_writer)
+    }).unwrap();
+    let file = get_temp_file("test_file_writer_size_statistics_copy", &bytes[..]);
+    let reader = SerializedFileReader::new(file).unwrap();
+    let row_group_metadata = reader.metadata().row_group(0);
+    assert_eq!(row_group_metadata.column(0).size_statistics(), Some(&expected));
+  }
+
+  #[cfg(feature = "rayon")]
+  #[test]
+  fn test_row_group_writer_write_columns_parallel() {
+    use data_type::ByteArray;
+    use schema::parser::parse_message_type;
+
+    let schema = Rc::new(parse_message_type("
+      message schema {
+        REQUIRED INT32 a;
+        OPTIONAL INT64 b;
+        REQUIRED BYTE_ARRAY c;
+        REQUIRED INT32 d;
+      }
+    ").unwrap());
+    let write_column = |i: usize, col_writer: &mut ColumnWriter| -> Result<()> {
+      match *col_writer {
+        ColumnWriter::Int32ColumnWriter(ref mut typed) => {
+          typed.write_batch(&[i as i32, 2, 3, 4], None, None)?;
+        },
+        ColumnWriter::Int64ColumnWriter(ref mut typed) => {
+          typed.write_batch(&[10, 30], Some(&[1, 0, 1, 0]), None)?;
+        },
+        ColumnWriter::ByteArrayColumnWriter(ref mut typed) => {
+          let values = vec!["a", "bb", "a", "ccc"].into_iter()
+            .map(|v| ByteArray::from(v))
+            .collect::<Vec<ByteArray>>();
+          typed.write_batch(&values[..], None, None)?;
+        },
+        _ => unimplemented!()
+      }
+      Ok(())
+    };
+
+    let props = Rc::new(
+      WriterProperties::builder().set_compression(Compression::SNAPPY).build());
+    for num_sequential in 0..3 {
+      let sequential = write_to_bytes(schema.clone(), props.clone(), |writer| {
+        for _ in 0..2 {
+          let mut row_group_writer = writer.next_row_group()?;
+          let mut i = 0;
+          while let Some(mut col_writer) = row_group_writer.next_column()? {
+            write_column(i, &mut col_writer)?;
+            row_group_writer.close_column(col_writer)?;
+            i += 1;
+          }
+          writer.close_row_group(row_group_writer)?;
+        }
+        Ok(())
+      }).unwrap();
+
+      let parallel = write_to_bytes(schema.clone(), props.clone(), |writer| {
+        for _ in 0..2 {
+          let mut row_group_writer = writer.next_row_group()?;
+          for i in 0..num_sequential {
+            let mut col_writer = row_group_writer.next_column()?.unwrap();
+            write_column(i, &mut col_writer)?;
+            row_group_writer.close_column(col_writer)?;
+          }
+          row_group_writer.write_columns_parallel(&write_column)?;
+          writer.close_row_group(row_group_writer)?;
+        }
+        Ok(())
+      }).unwrap();
+
+      assert_eq!(parallel, sequential);
+
+      let file = get_temp_file("test_row_group_writer_write_columns_parallel", &parallel);
+      let reader = SerializedFileReader::new(file).unwrap();
+      assert_eq!(reader.num_row_groups(), 2);
+      assert_eq!(reader.get_row_iter(None).unwrap().count(), 8);
+    }
+  }
+
+  #[test]
+  fn test_row_group_writer_sorting_verification() {
+    fn write_sorted(
+      b: &[i32],
+      def_levels: &[i16],
+      verification: SortingVerification
+    ) -> Result<Option<Vec<SortingColumn>>> {
+      let schema = Rc::new(parse_message_type(
+        "message schema { REQUIRED INT32 a; OPTIONAL INT32 b; }"
+      ).unwrap());
+      let props = Rc::new(WriterProperties::builder()
+        .set_sorting_columns(Some(vec![
+          SortingColumn::new(0, false, false),
+          SortingColumn::new(1, true, true)
+        ]))
+        .set_sorting_verification(verification)
+        .build());
+      let bytes = write_to_bytes(schema, props, |writer| {
+        let mut row_group_writer = writer.next_row_group()?;
+        let mut col_writer = row_group_writer.next_column()?.unwrap();
+        if let ColumnWriter::Int32ColumnWriter(ref mut typed) = col_writer {
+          typed.write_batch(&[1, 1, 2], None, None)?;
+        }
+        row_group_writer.close_column(col_writer)?;
+        let mut col_writer = row_group_writer.next_column()?.unwrap();
+        if let ColumnWriter::Int32ColumnWriter(ref mut typed) = col_writer {
+          typed.write_batch(b, Some(def_levels), None)?;
+        }
+        row_group_writer.close_column(col_writer)?;
+        writer.close_row_group(row_group_writer)
+      })?;
+
+      let file = get_temp_file("test_row_group_writer_sorting_verification", &bytes);
+      let reader = SerializedFileReader::new(file).unwrap();
+      Ok(reader.metadata().row_group(0).sorting_columns().cloned())
+    }
+
+    let sorting_columns = vec![
+      SortingColumn::new(0, false, false),
+      SortingColumn::new(1, true, true)
+    ];
+
+    // Sorted rows: (1, null), (1, 5), (2, 7)
+    for verification in vec![
+      SortingVerification::NONE,
+      SortingVerification::ERROR,
+      SortingVerification::CLEAR
+    ] {
+      let res = write_sorted(&[5, 7], &[0, 1, 1], verification).unwrap();
+      assert_eq!(res, Some(sorting_columns.clone()));
+    }
+
+    // Unsorted rows: (1, 5), (1, 7), (2, null)
+    let res = write_sorted(&[5, 7], &[1, 1, 0], SortingVerification::NONE).unwrap();
+    assert_eq!(res, Some(sorting_columns.clone()));
+    let res = write_sorted(&[5, 7], &[1, 1, 0], SortingVerification::ERROR);
+    assert_eq!(
+      res.unwrap_err().description(),
+      "Rows 0 and 1 of row group are not sorted by sorting columns"
+    );
+    let res = write_sorted(&[5, 7], &[1, 1, 0], SortingVerification::CLEAR).unwrap();
+    assert_eq!(res, None);
+  }
+
   #[test]
   fn test_page_writer_data_pages() {
     let pages = vec![
@@ -880,6 +2247,305 @@ mod tests {
 
   /// File write-read roundtrip.
   /// `data` consists of arrays of values for each row group.
+  /// Writes two row groups of columns `a` and `b` of `ENCRYPTED_SCHEMA` into file
+  /// `name`, with page indexes of column `a` and bloom filter of column `b`, which are
+  /// encrypted if columns are encrypted by `encryption`.
+  fn write_encrypted_file(
+    name: &str,
+    encryption: Option<FileEncryptionProperties>
+  ) -> File {
+    use data_type::ByteArray;
+
+    let schema = Rc::new(parse_message_type(ENCRYPTED_SCHEMA).unwrap());
+    let props = Rc::new(
+      WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_file_encryption_properties(encryption)
+        .build()
+    );
+    let file = get_temp_file(name, &[]);
+    let mut writer = SerializedFileWriter::new(file.try_clone().unwrap(), schema, props)
+      .unwrap();
+    for i in 0..2 {
+      let mut row_group_writer = writer.next_row_group().unwrap();
+      while let Some(mut col_writer) = row_group_writer.next_column().unwrap() {
+        match col_writer {
+          ColumnWriter::Int32ColumnWriter(ref mut typed) => {
+            typed.write_batch(&[i, 2, 3], None, None).unwrap();
+          },
+          ColumnWriter::ByteArrayColumnWriter(ref mut typed) => {
+            let values = vec![ByteArray::from("x"), ByteArray::from("y")];
+            typed.write_batch(&values, Some(&[1, 0, 1]), None).unwrap();
+          },
+          _ => unimplemented!()
+        }
+        row_group_writer.close_column(col_writer).unwrap();
+      }
+      writer.close_row_group(row_group_writer).unwrap();
+
+      let stats = Statistics::int32(Some(i), Some(3), None, 0, false);
+      let column_index =
+        ColumnIndex::new(BoundaryOrder::UNORDERED, vec![false], vec![stats]).unwrap();
+      let offset_index = OffsetIndex::new(vec![PageLocation::new(4, 10, 0)]);
+      writer.set_page_indexes(i as usize, 0, Some(column_index), offset_index).unwrap();
+      let mut bloom_filter = Sbbf::new(32);
+      bloom_filter.insert(&ByteArray::from("x"));
+      writer.set_bloom_filter(i as usize, 1, bloom_filter).unwrap();
+    }
+    writer.close().unwrap();
+    file
+  }
+
+  const ENCRYPTED_SCHEMA: &str =
+    "message schema { REQUIRED INT32 a; OPTIONAL BYTE_ARRAY b (UTF8); }";
+
+  const FOOTER_KEY: &[u8] = b"0123456789012345";
+
+  const COLUMN_KEY: &[u8] = b"1234567890123450";
+
+  /// Returns rows of `reader` as strings.
+  fn read_rows(reader: &SerializedFileReader<File>) -> Result<Vec<String>> {
+    reader.get_row_iter(None)?.map(|row| row.map(|row| row.to_string())).collect()
+  }
+
+  /// Checks rows, page indexes and bloom filters of `reader` against those of file
+  /// written without encryption.
+  fn assert_encrypted_file(reader: &SerializedFileReader<File>) {
+    let expected = write_encrypted_file("test_file_writer_encryption_expected", None);
+    let expected = SerializedFileReader::new(expected).unwrap();
+    assert_eq!(read_rows(reader).unwrap(), read_rows(&expected).unwrap());
+    assert_eq!(reader.num_row_groups(), 2);
+    for i in 0..2 {
+      let row_group = reader.metadata().row_group(i);
+      let expected_row_group = expected.metadata().row_group(i);
+      for j in 0..2 {
+        assert_eq!(
+          row_group.column(j).statistics(),
+          expected_row_group.column(j).statistics()
+        );
+      }
+      assert_eq!(
+        reader.read_column_index(i, 0).unwrap(),
+        expected.read_column_index(i, 0).unwrap()
+      );
+      assert_eq!(
+        reader.read_offset_index(i, 0).unwrap(),
+        expected.read_offset_index(i, 0).unwrap()
+      );
+      let bloom_filter = reader.read_bloom_filter(i, 1).unwrap().unwrap();
+      assert_eq!(Some(bloom_filter), expected.read_bloom_filter(i, 1).unwrap());
+    }
+  }
+
+  #[test]
+  fn test_file_writer_bloom_filter() {
+    use data_type::ByteArray;
+
+    let file = write_encrypted_file("test_file_writer_bloom_filter", None);
+    let reader = SerializedFileReader::new(file).unwrap();
+    let bloom_filter = reader.read_bloom_filter(1, 1).unwrap().unwrap();
+    assert!(bloom_filter.check(&ByteArray::from("x")));
+    assert!(reader.read_bloom_filter(1, 0).unwrap().is_none());
+  }
+
+  #[test]
+  fn test_file_writer_uniform_encryption() {
+    for &algorithm in &[
+      EncryptionAlgorithm::AES_GCM_V1,
+      EncryptionAlgorithm::AES_GCM_CTR_V1
+    ] {
+      let encryption = FileEncryptionProperties::builder(FOOTER_KEY.to_vec())
+        .set_algorithm(algorithm)
+        .build()
+        .unwrap();
+      let name = "test_file_writer_uniform_encryption";
+      let file = write_encrypted_file(name, Some(encryption));
+      assert!(SerializedFileReader::new(file.try_clone().unwrap()).is_err());
+
+      let decryption = FileDecryptionProperties::builder()
+        .set_footer_key(FOOTER_KEY.to_vec())
+        .build()
+        .unwrap();
+      let reader = SerializedFileReader::new_with_decryption(file, decryption).unwrap();
+      assert_encrypted_file(&reader);
+
+      let decryption = FileDecryptionProperties::builder()
+        .set_footer_key(COLUMN_KEY.to_vec())
+        .build()
+        .unwrap();
+      let file = write_encrypted_file("test_file_writer_uniform_encryption", None);
+      assert!(SerializedFileReader::new_with_decryption(file, decryption).is_ok());
+    }
+  }
+
+  #[test]
+  fn test_file_writer_column_encryption() {
+    let encryption = FileEncryptionProperties::builder(FOOTER_KEY.to_vec())
+      .set_column_key(ColumnPath::from("b"), COLUMN_KEY.to_vec())
+      .build()
+      .unwrap();
+    let name = "test_file_writer_column_encryption";
+    let file = write_encrypted_file(name, Some(encryption));
+
+    let decryption = FileDecryptionProperties::builder()
+      .set_footer_key(FOOTER_KEY.to_vec())
+      .set_column_key(ColumnPath::from("b"), COLUMN_KEY.to_vec())
+      .build()
+      .unwrap();
+    let reader =
+      SerializedFileReader::new_with_decryption(file.try_clone().unwrap(), decryption)
+        .unwrap();
+    assert_encrypted_file(&reader);
+
+    // Metadata of column `b` is only available with its key
+    let decryption = FileDecryptionProperties::builder()
+      .set_footer_key(FOOTER_KEY.to_vec())
+      .build()
+      .unwrap();
+    let res = SerializedFileReader::new_with_decryption(file, decryption);
+    assert_eq!(
+      res.err().unwrap().to_string(),
+      "Parquet error: Key of encrypted column b is not set (row group: 0)"
+    );
+  }
+
+  #[test]
+  fn test_file_writer_plaintext_footer() {
+    let encryption = FileEncryptionProperties::builder(FOOTER_KEY.to_vec())
+      .set_column_key_with_metadata(
+        ColumnPath::from("b"), COLUMN_KEY.to_vec(), b"key".to_vec())
+      .set_plaintext_footer(true)
+      .build()
+      .unwrap();
+    let name = "test_file_writer_plaintext_footer";
+    let file = write_encrypted_file(name, Some(encryption));
+
+    let decryption = FileDecryptionProperties::builder()
+      .set_footer_key(FOOTER_KEY.to_vec())
+      .set_column_key(ColumnPath::from("b"), COLUMN_KEY.to_vec())
+      .build()
+      .unwrap();
+    let reader =
+      SerializedFileReader::new_with_decryption(file.try_clone().unwrap(), decryption)
+        .unwrap();
+    assert_encrypted_file(&reader);
+
+    // Plaintext column is readable without keys, statistics of encrypted column are
+    // not revealed
+    let reader = SerializedFileReader::new(file).unwrap();
+    let row_group = reader.metadata().row_group(0);
+    assert!(row_group.column(0).statistics().is_some());
+    assert!(row_group.column(1).statistics().is_none());
+    assert!(row_group.column(1).encoding_stats().is_none());
+    assert!(reader.read_column_index(0, 0).unwrap().is_some());
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    let mut page_reader = row_group_reader.get_column_page_reader(0).unwrap();
+    assert!(page_reader.get_next_page().unwrap().is_some());
+    assert!(row_group_reader.get_column_page_reader(1).is_err());
+    assert!(reader.read_bloom_filter(0, 1).is_err());
+  }
+
+  #[test]
+  fn test_file_writer_encryption_aad_prefix() {
+    let encryption = FileEncryptionProperties::builder(FOOTER_KEY.to_vec())
+      .set_aad_prefix(b"tester".to_vec())
+      .set_store_aad_prefix(false)
+      .build()
+      .unwrap();
+    let name = "test_file_writer_encryption_aad_prefix";
+    let file = write_encrypted_file(name, Some(encryption));
+
+    let decryption = FileDecryptionProperties::builder()
+      .set_footer_key(FOOTER_KEY.to_vec())
+      .build()
+      .unwrap();
+    assert!(
+      SerializedFileReader::new_with_decryption(file.try_clone().unwrap(), decryption)
+        .is_err()
+    );
+
+    let decryption = FileDecryptionProperties::builder()
+      .set_footer_key(FOOTER_KEY.to_vec())
+      .set_aad_prefix(b"tester".to_vec())
+      .build()
+      .unwrap();
+    let reader = SerializedFileReader::new_with_decryption(file, decryption).unwrap();
+    assert_encrypted_file(&reader);
+  }
+
+  #[test]
+  fn test_row_group_writer_append_encrypted_column_chunk() {
+    let file = write_encrypted_file("test_row_group_writer_append_encrypted", None);
+    let reader = SerializedFileReader::new(file).unwrap();
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    let data = row_group_reader.get_column_chunk_bytes(0).unwrap();
+    let row_group_metadata = row_group_reader.metadata();
+
+    let schema = Rc::new(parse_message_type(ENCRYPTED_SCHEMA).unwrap());
+    let encryption = FileEncryptionProperties::builder(FOOTER_KEY.to_vec())
+      .build()
+      .unwrap();
+    let props = Rc::new(
+      WriterProperties::builder()
+        .set_file_encryption_properties(Some(encryption))
+        .build()
+    );
+    let res = write_to_bytes(schema, props, |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      row_group_writer.append_column_chunk(&data, row_group_metadata.column(0), 3)?;
+      writer.close_row_group(row_group_writer)
+    });
+    assert_eq!(
+      res.err().unwrap().to_string(),
+      "Parquet error: Encrypted column chunk a cannot be appended"
+    );
+  }
+
+  #[cfg(feature = "rayon")]
+  #[test]
+  fn test_row_group_writer_write_columns_parallel_encrypted() {
+    use data_type::ByteArray;
+
+    let schema = Rc::new(parse_message_type(ENCRYPTED_SCHEMA).unwrap());
+    let encryption = FileEncryptionProperties::builder(FOOTER_KEY.to_vec())
+      .set_column_key(ColumnPath::from("a"), COLUMN_KEY.to_vec())
+      .build()
+      .unwrap();
+    let props = Rc::new(
+      WriterProperties::builder()
+        .set_file_encryption_properties(Some(encryption))
+        .build()
+    );
+    let bytes = write_to_bytes(schema, props, |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      row_group_writer.write_columns_parallel(&|_, col_writer: &mut ColumnWriter| {
+        match *col_writer {
+          ColumnWriter::Int32ColumnWriter(ref mut typed) => {
+            typed.write_batch(&[1, 2, 3], None, None)?;
+          },
+          ColumnWriter::ByteArrayColumnWriter(ref mut typed) => {
+            let values = vec![ByteArray::from("x"), ByteArray::from("y")];
+            typed.write_batch(&values, Some(&[1, 0, 1]), None)?;
+          },
+          _ => unimplemented!()
+        }
+        Ok(())
+      })?;
+      writer.close_row_group(row_group_writer)
+    }).unwrap();
+
+    let name = "test_row_group_writer_write_columns_parallel_encrypted";
+    let file = get_temp_file(name, &bytes);
+    let decryption = FileDecryptionProperties::builder()
+      .set_footer_key(FOOTER_KEY.to_vec())
+      .set_column_key(ColumnPath::from("a"), COLUMN_KEY.to_vec())
+      .build()
+      .unwrap();
+    let reader = SerializedFileReader::new_with_decryption(file, decryption).unwrap();
+    let rows = read_rows(&reader).unwrap();
+    assert_eq!(rows, vec!["{a: 1, b: \"x\"}", "{a: 2, b: null}", "{a: 3, b: \"y\"}"]);
+  }
+
   fn test_file_roundtrip(file: File, data: Vec<Vec<i32>>) {
     let schema = Rc::new(
       types::Type::group_type_builder("schema")
@@ -920,11 +2586,22 @@ mod tests {
 
     let reader = SerializedFileReader::new(file).unwrap();
     assert_eq!(reader.num_row_groups(), data.len());
+    let num_rows = data.iter().map(|values| values.len() as i64).sum::<i64>();
+    assert_eq!(reader.metadata().file_metadata().num_rows(), num_rows);
     for i in 0..reader.num_row_groups() {
       let row_group_reader = reader.get_row_group(i).unwrap();
       let iter = row_group_reader.get_row_iter(None).unwrap();
-      let res = iter.map(|elem| elem.get_int(0).unwrap()).collect::<Vec<i32>>();
+      let res = iter.map(|elem| elem.unwrap().get_int(0).unwrap()).collect::<Vec<i32>>();
       assert_eq!(res, data[i]);
+
+      // Page encoding stats are written for each column chunk
+      let row_group_metadata = row_group_reader.metadata();
+      let num_pages = row_group_metadata.column(0).encoding_stats()
+        .expect("Page encoding stats are written")
+        .iter()
+        .map(|stats| stats.count)
+        .sum::<i32>();
+      assert!(num_pages > 0);
     }
   }
 }
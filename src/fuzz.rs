@@ -0,0 +1,271 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Entry points for fuzz testing of parsing and decoding, used by targets in `fuzz`
+//! directory. Each entry point takes arbitrary bytes, and must return an error instead
+//! of panicking on malformed input.
+//!
+//! This module is not part of the public API.
+
+use std::io::{BufReader, Cursor};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use basic::{Compression, Encoding, Repetition, Type};
+use column::reader::{get_column_reader, ColumnReader, ColumnReaderImpl};
+use data_type::*;
+use encodings::decoding::{get_decoder, Decoder, DecoderFactory};
+use errors::{ParquetError, Result};
+use file::metadata::ParquetMetaData;
+use file::reader::{MetadataLimits, SerializedFileReader, SerializedPageReader};
+use schema::types::{ColumnDescPtr, ColumnDescriptor, ColumnPath, Type as SchemaType};
+use util::memory::{ByteBufferPtr, MemTracker};
+
+/// Batch size of values decoded by entry points.
+const BATCH_SIZE: usize = 1024;
+
+/// Max number of values decoded by entry points.
+const MAX_NUM_VALUES: usize = 1024 * 1024;
+
+/// Max memory in bytes allocated by decoders of entry points.
+const MEMORY_LIMIT: i64 = 64 * 1024 * 1024;
+
+const PHYSICAL_TYPES: [Type; 8] = [
+  Type::BOOLEAN,
+  Type::INT32,
+  Type::INT64,
+  Type::INT96,
+  Type::FLOAT,
+  Type::DOUBLE,
+  Type::BYTE_ARRAY,
+  Type::FIXED_LEN_BYTE_ARRAY
+];
+
+const ENCODINGS: [Encoding; 7] = [
+  Encoding::PLAIN,
+  Encoding::PLAIN_DICTIONARY,
+  Encoding::RLE,
+  Encoding::DELTA_BINARY_PACKED,
+  Encoding::DELTA_LENGTH_BYTE_ARRAY,
+  Encoding::DELTA_BYTE_ARRAY,
+  Encoding::RLE_DICTIONARY
+];
+
+const COMPRESSIONS: [Compression; 6] = [
+  Compression::UNCOMPRESSED,
+  Compression::SNAPPY,
+  Compression::GZIP,
+  Compression::BROTLI,
+  Compression::LZ4,
+  Compression::ZSTD
+];
+
+/// Parses `data` as the end of a Parquet file: file metadata, followed by 4 bytes of
+/// length of metadata and magic bytes.
+pub fn parse_footer(data: &[u8]) -> Result<ParquetMetaData> {
+  let mut buf = BufReader::new(Cursor::new(data));
+  SerializedFileReader::parse_metadata(&mut buf, false, &MetadataLimits::default())
+}
+
+/// Reads `data` as a column chunk with pages and returns number of read values.
+///
+/// The first 4 bytes of `data` select physical type, length of fixed length byte
+/// arrays, max definition and repetition levels, and compression codec. The rest of
+/// `data` are pages of the column chunk including page headers.
+pub fn read_column_chunk(data: &[u8]) -> Result<usize> {
+  if data.len() < 4 {
+    return Err(eof_err!("Not enough bytes to read parameters"));
+  }
+  let physical_type = PHYSICAL_TYPES[data[0] as usize % PHYSICAL_TYPES.len()];
+  let type_length = (data[1] % 32) as i32 + 1;
+  let max_def_level = (data[2] & 0x3) as i16;
+  let max_rep_level = ((data[2] >> 2) & 0x3) as i16;
+  let compression = COMPRESSIONS[data[3] as usize % COMPRESSIONS.len()];
+  let descr = column_descr(physical_type, type_length, max_def_level, max_rep_level)?;
+
+  let page_reader = SerializedPageReader::new(
+    Cursor::new(data[4..].to_vec()),
+    MAX_NUM_VALUES as i64,
+    compression,
+    physical_type
+  )?;
+  match get_column_reader(descr, Box::new(page_reader)) {
+    ColumnReader::BoolColumnReader(r) => read_values(r),
+    ColumnReader::Int32ColumnReader(r) => read_values(r),
+    ColumnReader::Int64ColumnReader(r) => read_values(r),
+    ColumnReader::Int96ColumnReader(r) => read_values(r),
+    ColumnReader::FloatColumnReader(r) => read_values(r),
+    ColumnReader::DoubleColumnReader(r) => read_values(r),
+    ColumnReader::ByteArrayColumnReader(r) => read_values(r),
+    ColumnReader::FixedLenByteArrayColumnReader(r) => read_values(r)
+  }
+}
+
+/// Decodes `data` as encoded values and returns number of decoded values.
+///
+/// The first 4 bytes of `data` select physical type, length of fixed length byte
+/// arrays, encoding and number of values. For dictionary encodings the rest of `data`
+/// starts with 2 bytes of the number of dictionary values, followed by 4 bytes of the
+/// length of the dictionary, plain encoded dictionary and indices. Otherwise, the rest
+/// of `data` are encoded values.
+pub fn decode_values(data: &[u8]) -> Result<usize> {
+  if data.len() < 4 {
+    return Err(eof_err!("Not enough bytes to read parameters"));
+  }
+  let physical_type = PHYSICAL_TYPES[data[0] as usize % PHYSICAL_TYPES.len()];
+  let type_length = (data[1] % 32) as i32 + 1;
+  let encoding = ENCODINGS[data[2] as usize % ENCODINGS.len()];
+  let num_values = (data[3] as usize) << 4;
+  let descr = column_descr(physical_type, type_length, 0, 0)?;
+  let data = ByteBufferPtr::new(data[4..].to_vec());
+  match physical_type {
+    Type::BOOLEAN => decode::<BoolType>(descr, encoding, data, num_values),
+    Type::INT32 => decode::<Int32Type>(descr, encoding, data, num_values),
+    Type::INT64 => decode::<Int64Type>(descr, encoding, data, num_values),
+    Type::INT96 => decode::<Int96Type>(descr, encoding, data, num_values),
+    Type::FLOAT => decode::<FloatType>(descr, encoding, data, num_values),
+    Type::DOUBLE => decode::<DoubleType>(descr, encoding, data, num_values),
+    Type::BYTE_ARRAY => decode::<ByteArrayType>(descr, encoding, data, num_values),
+    Type::FIXED_LEN_BYTE_ARRAY => {
+      decode::<FixedLenByteArrayType>(descr, encoding, data, num_values)
+    }
+  }
+}
+
+fn column_descr(
+  physical_type: Type,
+  type_length: i32,
+  max_def_level: i16,
+  max_rep_level: i16
+) -> Result<ColumnDescPtr> {
+  let repetition = if max_rep_level > 0 {
+    Repetition::REPEATED
+  } else if max_def_level > 0 {
+    Repetition::OPTIONAL
+  } else {
+    Repetition::REQUIRED
+  };
+  let tpe = SchemaType::primitive_type_builder("col", physical_type)
+    .with_repetition(repetition)
+    .with_length(type_length)
+    .build()?;
+  Ok(Rc::new(ColumnDescriptor::new(
+    Rc::new(tpe), None, max_def_level, max_rep_level, ColumnPath::from("col"))))
+}
+
+fn read_values<T: DataType>(reader: ColumnReaderImpl<T>) -> Result<usize> {
+  let mut reader =
+    reader.with_mem_tracker(Arc::new(MemTracker::with_limit(MEMORY_LIMIT)));
+  let mut def_levels = vec![0; BATCH_SIZE];
+  let mut rep_levels = vec![0; BATCH_SIZE];
+  let mut values = vec![T::T::default(); BATCH_SIZE];
+  let mut num_levels = 0;
+  while num_levels < MAX_NUM_VALUES {
+    let (_, levels_read) = reader.read_batch(
+      BATCH_SIZE, Some(&mut def_levels), Some(&mut rep_levels), &mut values)?;
+    if levels_read == 0 {
+      break;
+    }
+    num_levels += levels_read;
+  }
+  Ok(num_levels)
+}
+
+fn decode<T: DataType>(
+  descr: ColumnDescPtr,
+  encoding: Encoding,
+  data: ByteBufferPtr,
+  num_values: usize
+) -> Result<usize> {
+  let mem_tracker = Arc::new(MemTracker::with_limit(MEMORY_LIMIT));
+  let mut decoder: Box<Decoder<T>> = match encoding {
+    Encoding::PLAIN_DICTIONARY | Encoding::RLE_DICTIONARY => {
+      if data.len() < 6 {
+        return Err(eof_err!("Not enough bytes to read dictionary"));
+      }
+      let num_dict_values = ((data[0] as usize) << 8) | data[1] as usize;
+      let dict_len = ((data[2] as usize) << 8 | data[3] as usize) << 16
+        | (data[4] as usize) << 8 | data[5] as usize;
+      if data.len() < 6 + dict_len {
+        return Err(eof_err!("Not enough bytes to read dictionary"));
+      }
+      let mut decoder = DecoderFactory::<T>::new(descr, mem_tracker)
+        .get_dict_decoder(Encoding::PLAIN, data.range(6, dict_len), num_dict_values)?;
+      decoder.set_data(data.start_from(6 + dict_len), num_values)?;
+      Box::new(decoder)
+    },
+    _ => {
+      let mut decoder = get_decoder::<T>(descr, encoding, mem_tracker)?;
+      decoder.set_data(data, num_values)?;
+      decoder
+    }
+  };
+  let mut values = vec![T::T::default(); BATCH_SIZE];
+  let mut num_decoded = 0;
+  loop {
+    let num_read = decoder.get(&mut values)?;
+    if num_read == 0 {
+      break;
+    }
+    num_decoded += num_read;
+  }
+  Ok(num_decoded)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::io::Read;
+
+  use util::test_common::get_test_file;
+
+  #[test]
+  fn test_parse_footer() {
+    let mut data = Vec::new();
+    get_test_file("alltypes_plain.parquet").read_to_end(&mut data).unwrap();
+    let metadata = parse_footer(&data).unwrap();
+    assert_eq!(metadata.file_metadata().num_rows(), 8);
+
+    assert!(parse_footer(&[]).is_err());
+    assert!(parse_footer(&[0xff; 64]).is_err());
+  }
+
+  #[test]
+  fn test_read_column_chunk_malformed() {
+    assert!(read_column_chunk(&[]).is_err());
+    assert!(read_column_chunk(&[1, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]).is_err());
+  }
+
+  #[test]
+  fn test_decode_values_malformed() {
+    assert!(decode_values(&[1, 0, 0]).is_err());
+    // INT32, PLAIN, 16 values, but only 4 bytes of data
+    assert!(decode_values(&[1, 0, 0, 1, 0, 0, 0, 0]).is_err());
+    // INT32, PLAIN_DICTIONARY, truncated dictionary
+    assert!(decode_values(&[1, 0, 1, 1, 0, 1, 0, 0, 0, 8]).is_err());
+    // BOOLEAN, RLE, RLE run without the value
+    assert!(decode_values(&[0, 0, 2, 1, 1, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    // INT32, DELTA_BINARY_PACKED, 0 values per mini block
+    assert!(
+      decode_values(&[1, 0, 3, 1, 4, 8, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    // INT32, DELTA_BINARY_PACKED, block size of 2^40
+    assert!(decode_values(
+      &[1, 0, 3, 1, 0x80, 0x80, 0x80, 0x80, 0x80, 0x20, 1, 2, 0, 0, 1, 0xff, 0xff]
+    ).is_err());
+  }
+}
@@ -72,7 +72,7 @@
 //! let mut iter = reader.get_row_iter(None).unwrap();
 //! while let Some(record) = iter.next() {
 //!   // See record API for different field accessors
-//!   println!("{}", record);
+//!   println!("{}", record.unwrap());
 //! }
 //! ```
 //!
@@ -128,9 +128,8 @@
 #![allow(dead_code)]
 #![allow(non_camel_case_types)]
 
-#[macro_use]
-extern crate quick_error;
 extern crate byteorder;
+extern crate crc32fast;
 extern crate thrift;
 extern crate arena;
 extern crate snap;
@@ -141,6 +140,19 @@ extern crate chrono;
 extern crate lz4;
 extern crate num_bigint;
 extern crate zstd;
+extern crate aes;
+extern crate aes_gcm;
+extern crate ctr;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde_json")]
+extern crate serde_json;
+#[cfg(feature = "toml")]
+extern crate toml;
 
 #[cfg(test)]
 extern crate rand;
@@ -154,6 +166,7 @@ pub mod data_type;
 pub use util::memory;
 pub use encodings::encoding;
 pub use encodings::decoding;
+pub use util::bench_util;
 
 #[macro_use]
 mod util;
@@ -163,3 +176,8 @@ pub mod column;
 pub mod record;
 pub mod schema;
 pub mod file;
+pub mod encryption;
+#[doc(hidden)]
+pub mod fuzz;
+#[cfg(all(test, feature = "serde_json"))]
+mod conformance;
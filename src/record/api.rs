@@ -19,12 +19,17 @@
 
 use std::fmt;
 
-use basic::{LogicalType, Type as PhysicalType};
-use chrono::{Local, TimeZone};
-use data_type::{ByteArray, Decimal, Int96};
+use basic::{LogicalType, TimeUnit, Type as PhysicalType};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use data_type::{
+  date_from_days, ByteArray, Decimal, Int96, Interval, Time, Timestamp
+};
 use errors::{ParquetError, Result};
 use num_bigint::{BigInt, Sign};
 use schema::types::ColumnDescPtr;
+#[cfg(feature = "serde_json")]
+use serde_json;
+use util::utf8;
 
 /// Macro as a shortcut to generate 'not yet implemented' panic error.
 macro_rules! nyi {
@@ -49,6 +54,14 @@ impl Row {
   pub fn len(&self) -> usize {
     self.fields.len()
   }
+
+  /// Converts row into a JSON object, see [`Field::to_json`](enum.Field.html) for
+  /// representation of values.
+  pub fn to_json(&self) -> String {
+    let mut json = String::new();
+    write_json_row(self, &mut json);
+    json
+  }
 }
 
 /// Trait for type-safe convenient access to fields within a Row.
@@ -60,13 +73,23 @@ pub trait RowAccessor {
   fn get_long(&self, i: usize) -> Result<i64>;
   fn get_float(&self, i: usize) -> Result<f32>;
   fn get_double(&self, i: usize) -> Result<f64>;
-  fn get_timestamp(&self, i: usize) -> Result<u64>;
+  fn get_date(&self, i: usize) -> Result<u32>;
+  fn get_time(&self, i: usize) -> Result<Time>;
+  fn get_timestamp(&self, i: usize) -> Result<Timestamp>;
+  fn get_interval(&self, i: usize) -> Result<Interval>;
   fn get_decimal(&self, i: usize) -> Result<&Decimal>;
   fn get_string(&self, i: usize) -> Result<&String>;
   fn get_bytes(&self, i: usize) -> Result<&ByteArray>;
+  #[cfg(feature = "serde_json")]
+  fn get_json(&self, i: usize) -> Result<&serde_json::Value>;
   fn get_group(&self, i: usize) -> Result<&Row>;
   fn get_list(&self, i: usize) -> Result<&List>;
   fn get_map(&self, i: usize) -> Result<&Map>;
+
+  /// Returns DATE value as a date, see [`get_date`](#tymethod.get_date).
+  fn get_naive_date(&self, i: usize) -> Result<NaiveDate> {
+    convert_date(self.get_date(i)?)
+  }
 }
 
 /// Macro to generate type-safe get_xxx methods for primitive types,
@@ -105,10 +128,15 @@ impl RowAccessor for Row {
   row_primitive_accessor!(get_long, Long, i64);
   row_primitive_accessor!(get_float, Float, f32);
   row_primitive_accessor!(get_double, Double, f64);
-  row_primitive_accessor!(get_timestamp, Timestamp, u64);
+  row_primitive_accessor!(get_date, Date, u32);
+  row_primitive_accessor!(get_time, Time, Time);
+  row_primitive_accessor!(get_timestamp, Timestamp, Timestamp);
+  row_primitive_accessor!(get_interval, Interval, Interval);
   row_complex_accessor!(get_decimal, Decimal, Decimal);
   row_complex_accessor!(get_string, Str, String);
   row_complex_accessor!(get_bytes, Bytes, ByteArray);
+  #[cfg(feature = "serde_json")]
+  row_complex_accessor!(get_json, Json, serde_json::Value);
   row_complex_accessor!(get_group, Group, Row);
   row_complex_accessor!(get_list, ListInternal, List);
   row_complex_accessor!(get_map, MapInternal, Map);
@@ -120,6 +148,18 @@ pub fn make_row(fields: Vec<(String, Field)>) -> Row {
   Row { fields: fields }
 }
 
+/// Returns value of `i`th field of `row`.
+#[inline]
+pub fn row_field(row: &Row, i: usize) -> &Field {
+  &row.fields[i].1
+}
+
+/// Consumes `row` and returns its list of fields.
+#[inline]
+pub fn into_row_fields(row: Row) -> Vec<(String, Field)> {
+  row.fields
+}
+
 impl fmt::Display for Row {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     write!(f, "{{")?;
@@ -154,6 +194,12 @@ pub fn make_list(elements: Vec<Field>) -> List {
   List { elements: elements }
 }
 
+/// Returns elements of `list`.
+#[inline]
+pub fn list_elements(list: &List) -> &[Field] {
+  &list.elements
+}
+
 
 /// Trait for type-safe access of an index for a `List`.
 /// Note that the get_XXX methods do not do bound checking.
@@ -165,13 +211,23 @@ pub trait ListAccessor {
   fn get_long(&self, i: usize) -> Result<i64>;
   fn get_float(&self, i: usize) -> Result<f32>;
   fn get_double(&self, i: usize) -> Result<f64>;
-  fn get_timestamp(&self, i: usize) -> Result<u64>;
+  fn get_date(&self, i: usize) -> Result<u32>;
+  fn get_time(&self, i: usize) -> Result<Time>;
+  fn get_timestamp(&self, i: usize) -> Result<Timestamp>;
+  fn get_interval(&self, i: usize) -> Result<Interval>;
   fn get_decimal(&self, i: usize) -> Result<&Decimal>;
   fn get_string(&self, i: usize) -> Result<&String>;
   fn get_bytes(&self, i: usize) -> Result<&ByteArray>;
+  #[cfg(feature = "serde_json")]
+  fn get_json(&self, i: usize) -> Result<&serde_json::Value>;
   fn get_group(&self, i: usize) -> Result<&Row>;
   fn get_list(&self, i: usize) -> Result<&List>;
   fn get_map(&self, i: usize) -> Result<&Map>;
+
+  /// Returns DATE value as a date, see [`get_date`](#tymethod.get_date).
+  fn get_naive_date(&self, i: usize) -> Result<NaiveDate> {
+    convert_date(self.get_date(i)?)
+  }
 }
 
 
@@ -215,10 +271,15 @@ impl ListAccessor for List {
   list_primitive_accessor!(get_long, Long, i64);
   list_primitive_accessor!(get_float, Float, f32);
   list_primitive_accessor!(get_double, Double, f64);
-  list_primitive_accessor!(get_timestamp, Timestamp, u64);
+  list_primitive_accessor!(get_date, Date, u32);
+  list_primitive_accessor!(get_time, Time, Time);
+  list_primitive_accessor!(get_timestamp, Timestamp, Timestamp);
+  list_primitive_accessor!(get_interval, Interval, Interval);
   list_complex_accessor!(get_decimal, Decimal, Decimal);
   list_complex_accessor!(get_string, Str, String);
   list_complex_accessor!(get_bytes, Bytes, ByteArray);
+  #[cfg(feature = "serde_json")]
+  list_complex_accessor!(get_json, Json, serde_json::Value);
   list_complex_accessor!(get_group, Group, Row);
   list_complex_accessor!(get_list, ListInternal, List);
   list_complex_accessor!(get_map, MapInternal, Map);
@@ -244,6 +305,12 @@ pub fn make_map(entries: Vec<(Field, Field)>) -> Map {
   Map { entries: entries }
 }
 
+/// Returns key-value pairs of `map`.
+#[inline]
+pub fn map_entries(map: &Map) -> &[(Field, Field)] {
+  &map.entries
+}
+
 /// Trait for type-safe access of an index for a `Map`
 pub trait MapAccessor {
   fn get_keys<'a>(&'a self) -> Box<ListAccessor + 'a>;
@@ -278,10 +345,15 @@ impl<'a> ListAccessor for MapList<'a> {
   map_list_primitive_accessor!(get_long, Long, i64);
   map_list_primitive_accessor!(get_float, Float, f32);
   map_list_primitive_accessor!(get_double, Double, f64);
-  map_list_primitive_accessor!(get_timestamp, Timestamp, u64);
+  map_list_primitive_accessor!(get_date, Date, u32);
+  map_list_primitive_accessor!(get_time, Time, Time);
+  map_list_primitive_accessor!(get_timestamp, Timestamp, Timestamp);
+  map_list_primitive_accessor!(get_interval, Interval, Interval);
   list_complex_accessor!(get_decimal, Decimal, Decimal);
   list_complex_accessor!(get_string, Str, String);
   list_complex_accessor!(get_bytes, Bytes, ByteArray);
+  #[cfg(feature = "serde_json")]
+  list_complex_accessor!(get_json, Json, serde_json::Value);
   list_complex_accessor!(get_group, Group, Row);
   list_complex_accessor!(get_list, ListInternal, List);
   list_complex_accessor!(get_map, MapInternal, Map);
@@ -299,6 +371,23 @@ impl MapAccessor for Map {
   }
 }
 
+/// Validation of UTF-8 strings, i.e. BYTE_ARRAY values with UTF8, ENUM or JSON logical
+/// type, when they are converted into [`Field::Str`](enum.Field.html#variant.Str).
+/// JSON values are always validated when they are parsed with `serde_json` feature.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Utf8Validation {
+  /// Invalid UTF-8 values are rejected, conversion returns an error.
+  Strict,
+  /// Invalid UTF-8 sequences are replaced with U+FFFD replacement character.
+  Lossy
+}
+
+impl Default for Utf8Validation {
+  fn default() -> Self {
+    Utf8Validation::Strict
+  }
+}
+
 /// API to represent a single field in a `Row`.
 #[derive(Clone, Debug, PartialEq)]
 pub enum Field {
@@ -326,11 +415,21 @@ pub enum Field {
   Str(String),
   /// General binary value.
   Bytes(ByteArray),
+  /// JSON document, only with `serde_json` feature, otherwise JSON values are
+  /// represented as `Str`.
+  #[cfg(feature = "serde_json")]
+  Json(serde_json::Value),
   /// Date without a time of day, stores the number of days from the
   /// Unix epoch, 1 January 1970.
   Date(u32),
-  /// Milliseconds from the Unix epoch, 1 January 1970.
-  Timestamp(u64),
+  /// Time of day since midnight in milliseconds, microseconds or nanoseconds, either
+  /// adjusted to UTC or local.
+  Time(Time),
+  /// Date and time since the Unix epoch, 1 January 1970, in milliseconds, microseconds
+  /// or nanoseconds, either adjusted to UTC or local.
+  Timestamp(Timestamp),
+  /// Duration of time in months, days and milliseconds.
+  Interval(Interval),
 
   // ----------------------------------------------------------------------
   // Complex types
@@ -358,9 +457,13 @@ impl Field {
       Field::Double(_) => "Double",
       Field::Decimal(_) => "Decimal",
       Field::Date(_) => "Date",
+      Field::Time(_) => "Time",
       Field::Str(_) => "Str",
       Field::Bytes(_) => "Bytes",
+      #[cfg(feature = "serde_json")]
+      Field::Json(_) => "Json",
       Field::Timestamp(_) => "Timestamp",
+      Field::Interval(_) => "Interval",
       Field::Group(_) => "Group",
       Field::ListInternal(_) => "ListInternal",
       Field::MapInternal(_) => "MapInternal",
@@ -386,6 +489,9 @@ impl Field {
   /// Converts Parquet INT32 type with logical type into `i32` value.
   #[inline]
   pub fn convert_int32(descr: &ColumnDescPtr, value: i32) -> Self {
+    if let Some((unit, is_adjusted_to_utc)) = descr.logical_type().time_unit() {
+      return Field::Time(Time::new(value as i64, unit, is_adjusted_to_utc));
+    }
     match descr.logical_type() {
       LogicalType::INT_8 => Field::Byte(value as i8),
       LogicalType::INT_16 => Field::Short(value as i16),
@@ -405,6 +511,12 @@ impl Field {
   /// Converts Parquet INT64 type with logical type into `i64` value.
   #[inline]
   pub fn convert_int64(descr: &ColumnDescPtr, value: i64) -> Self {
+    if let Some((unit, is_adjusted_to_utc)) = descr.logical_type().time_unit() {
+      return Field::Time(Time::new(value, unit, is_adjusted_to_utc));
+    }
+    if let Some((unit, is_adjusted_to_utc)) = descr.logical_type().timestamp_unit() {
+      return Field::Timestamp(Timestamp::new(value, unit, is_adjusted_to_utc));
+    }
     match descr.logical_type() {
       LogicalType::INT_64 | LogicalType::NONE => Field::Long(value),
       LogicalType::DECIMAL => {
@@ -422,16 +534,15 @@ impl Field {
   /// `Timestamp` value.
   #[inline]
   pub fn convert_int96(_descr: &ColumnDescPtr, value: Int96) -> Self {
-    const JULIAN_TO_UNIX_EPOCH_DAYS: u64 = 2_440_588;
-    const MILLI_SECONDS_IN_A_DAY: u64 = 86_400_000;
-    const NANO_SECONDS_IN_A_DAY: u64 = MILLI_SECONDS_IN_A_DAY * 1_000_000;
+    const JULIAN_TO_UNIX_EPOCH_DAYS: i64 = 2_440_588;
+    const MILLI_SECONDS_IN_A_DAY: i64 = 86_400_000;
+    const NANO_SECONDS_IN_A_DAY: i64 = MILLI_SECONDS_IN_A_DAY * 1_000_000;
 
-    let days_since_epoch = value.data()[2] as u64 - JULIAN_TO_UNIX_EPOCH_DAYS;
-    let nanoseconds: u64 = ((value.data()[1] as u64) << 32) + value.data()[0] as u64;
+    let days_since_epoch = value.data()[2] as i64 - JULIAN_TO_UNIX_EPOCH_DAYS;
+    let nanoseconds = ((value.data()[1] as i64) << 32) + value.data()[0] as i64;
     let nanos = days_since_epoch * NANO_SECONDS_IN_A_DAY + nanoseconds;
-    let millis = nanos / 1_000_000;
 
-    Field::Timestamp(millis)
+    Field::Timestamp(Timestamp::new(nanos, TimeUnit::NANOS, true))
   }
 
   /// Converts Parquet FLOAT type with logical type into `f32` value.
@@ -447,15 +558,36 @@ impl Field {
   }
 
   /// Converts Parquet BYTE_ARRAY type with logical type into either UTF8 string or
-  /// array of bytes.
+  /// array of bytes. Strings are validated with default
+  /// [`Utf8Validation`](enum.Utf8Validation.html).
   #[inline]
-  pub fn convert_byte_array(descr: &ColumnDescPtr, value: ByteArray) -> Self {
-    match descr.physical_type() {
+  pub fn convert_byte_array(descr: &ColumnDescPtr, value: ByteArray) -> Result<Self> {
+    Self::convert_byte_array_with_validation(descr, value, Utf8Validation::default())
+  }
+
+  /// Converts Parquet BYTE_ARRAY type with logical type into either UTF8 string or
+  /// array of bytes, strings are validated according to `validation`.
+  /// Returns error if value is invalid for its logical type, e.g. invalid UTF-8 string.
+  pub fn convert_byte_array_with_validation(
+    descr: &ColumnDescPtr,
+    value: ByteArray,
+    validation: Utf8Validation
+  ) -> Result<Self> {
+    let field = match descr.physical_type() {
       PhysicalType::BYTE_ARRAY => {
         match descr.logical_type() {
-          LogicalType::UTF8 | LogicalType::ENUM | LogicalType::JSON => {
-            let value = unsafe { String::from_utf8_unchecked(value.data().to_vec()) };
-            Field::Str(value)
+          LogicalType::UTF8 | LogicalType::ENUM => {
+            Field::Str(convert_utf8(descr, value.data(), validation)?)
+          },
+          #[cfg(not(feature = "serde_json"))]
+          LogicalType::JSON => Field::Str(convert_utf8(descr, value.data(), validation)?),
+          #[cfg(feature = "serde_json")]
+          LogicalType::JSON => match serde_json::from_slice(value.data()) {
+            Ok(v) => Field::Json(v),
+            Err(e) => {
+              return Err(general_err!(
+                "Invalid JSON value in column {}: {}", descr.path(), e))
+            }
           },
           LogicalType::BSON | LogicalType::NONE => Field::Bytes(value),
           LogicalType::DECIMAL => {
@@ -477,6 +609,21 @@ impl Field {
               descr.type_scale()
             ))
           },
+          LogicalType::UUID => Field::Str(convert_uuid_to_string(value.data())),
+          LogicalType::FLOAT16 => match value.f16_to_f32() {
+            Ok(v) => Field::Float(v),
+            Err(e) => {
+              return Err(general_err!(
+                "Invalid FLOAT16 value in column {}: {}", descr.path(), e))
+            }
+          },
+          LogicalType::INTERVAL => match Interval::from_bytes(value.data()) {
+            Ok(v) => Field::Interval(v),
+            Err(e) => {
+              return Err(general_err!(
+                "Invalid INTERVAL value in column {}: {}", descr.path(), e))
+            }
+          },
           LogicalType::NONE => {
             Field::Bytes(value)
           },
@@ -484,6 +631,109 @@ impl Field {
         }
       },
       _ => nyi!(descr, value)
+    };
+    Ok(field)
+  }
+}
+
+impl Field {
+  /// Converts field into a JSON value.
+  ///
+  /// Groups are converted into objects, lists into arrays and maps into objects, where
+  /// keys are strings or their display representations. Binary values are arrays of
+  /// bytes, decimals are numbers, dates, times and timestamps are strings in the same
+  /// format as their display representations, JSON documents are embedded as they are.
+  /// NaN and infinite values are converted into `null`.
+  pub fn to_json(&self) -> String {
+    let mut json = String::new();
+    write_json_field(self, &mut json);
+    json
+  }
+}
+
+/// Appends escaped JSON string of `value` to `json`.
+fn write_json_string(value: &str, json: &mut String) {
+  json.push('"');
+  for c in value.chars() {
+    match c {
+      '"' => json.push_str("\\\""),
+      '\\' => json.push_str("\\\\"),
+      '\n' => json.push_str("\\n"),
+      '\r' => json.push_str("\\r"),
+      '\t' => json.push_str("\\t"),
+      '\u{8}' => json.push_str("\\b"),
+      '\u{c}' => json.push_str("\\f"),
+      c if c < ' ' => json.push_str(&format!("\\u{:04x}", c as u32)),
+      c => json.push(c)
+    }
+  }
+  json.push('"');
+}
+
+/// Appends JSON object of `row` to `json`.
+fn write_json_row(row: &Row, json: &mut String) {
+  json.push('{');
+  for (i, &(ref key, ref value)) in row.fields.iter().enumerate() {
+    if i > 0 {
+      json.push(',');
+    }
+    write_json_string(key, json);
+    json.push(':');
+    write_json_field(value, json);
+  }
+  json.push('}');
+}
+
+/// Appends JSON value of `field` to `json`.
+fn write_json_field(field: &Field, json: &mut String) {
+  match *field {
+    Field::Null => json.push_str("null"),
+    Field::Bool(value) => json.push_str(&value.to_string()),
+    Field::Byte(value) => json.push_str(&value.to_string()),
+    Field::Short(value) => json.push_str(&value.to_string()),
+    Field::Int(value) => json.push_str(&value.to_string()),
+    Field::Long(value) => json.push_str(&value.to_string()),
+    Field::Float(value) if value.is_finite() => json.push_str(&format!("{:?}", value)),
+    Field::Double(value) if value.is_finite() => json.push_str(&format!("{:?}", value)),
+    Field::Float(_) | Field::Double(_) => json.push_str("null"),
+    Field::Decimal(ref value) => json.push_str(&convert_decimal_to_string(value)),
+    Field::Str(ref value) => write_json_string(value, json),
+    Field::Bytes(ref value) => {
+      json.push_str(&format!("{:?}", value.data()).replace(" ", ""))
+    },
+    #[cfg(feature = "serde_json")]
+    Field::Json(ref value) => json.push_str(&value.to_string()),
+    Field::Date(value) => write_json_string(&convert_date_to_string(value), json),
+    Field::Time(value) => write_json_string(&value.to_string(), json),
+    Field::Timestamp(value) => {
+      write_json_string(&convert_timestamp_to_string(value), json)
+    },
+    Field::Interval(value) => write_json_string(&value.to_string(), json),
+    Field::Group(ref row) => write_json_row(row, json),
+    Field::ListInternal(ref list) => {
+      json.push('[');
+      for (i, element) in list.elements.iter().enumerate() {
+        if i > 0 {
+          json.push(',');
+        }
+        write_json_field(element, json);
+      }
+      json.push(']');
+    },
+    Field::MapInternal(ref map) => {
+      json.push('{');
+      for (i, &(ref key, ref value)) in map.entries.iter().enumerate() {
+        if i > 0 {
+          json.push(',');
+        }
+        match *key {
+          Field::Str(ref key) => write_json_string(key, json),
+          ref key => write_json_string(&key.to_string(), json)
+        }
+        json.push(':');
+        write_json_field(value, json);
+      }
+      json.push('}');
     }
   }
 }
@@ -514,8 +764,12 @@ impl fmt::Display for Field {
       Field::Decimal(ref value) => write!(f, "{}", convert_decimal_to_string(value)),
       Field::Str(ref value) => write!(f, "\"{}\"", value),
       Field::Bytes(ref value) => write!(f, "{:?}", value.data()),
+      #[cfg(feature = "serde_json")]
+      Field::Json(ref value) => write!(f, "{}", value),
       Field::Date(value) => write!(f, "{}", convert_date_to_string(value)),
+      Field::Time(value) => write!(f, "{}", value),
       Field::Timestamp(value) => write!(f, "{}", convert_timestamp_to_string(value)),
+      Field::Interval(value) => write!(f, "{}", value),
       Field::Group(ref fields) => write!(f, "{}", fields),
       Field::ListInternal(ref list) => {
         let elems = &list.elements;
@@ -545,6 +799,47 @@ impl fmt::Display for Field {
   }
 }
 
+/// Converts UTF-8 bytes of a value of column `descr` into string.
+#[inline]
+fn convert_utf8(
+  descr: &ColumnDescPtr,
+  bytes: &[u8],
+  validation: Utf8Validation
+) -> Result<String> {
+  match validation {
+    Utf8Validation::Strict => match utf8::from_utf8(bytes) {
+      Ok(value) => Ok(value.to_owned()),
+      Err(e) => Err(general_err!("Invalid UTF-8 value in column {}: {}", descr.path(), e))
+    },
+    Utf8Validation::Lossy => Ok(String::from_utf8_lossy(bytes).into_owned())
+  }
+}
+
+/// Helper method to convert Parquet UUID into a string.
+/// Input `value` is 16 bytes in big-endian byte order, UUID is displayed in the
+/// canonical 8-4-4-4-12 form of lowercase hex digits.
+#[inline]
+fn convert_uuid_to_string(value: &[u8]) -> String {
+  let hex: Vec<String> = value.iter().map(|b| format!("{:02x}", b)).collect();
+  format!(
+    "{}-{}-{}-{}-{}",
+    hex[0..4].concat(),
+    hex[4..6].concat(),
+    hex[6..8].concat(),
+    hex[8..10].concat(),
+    hex[10..16].concat()
+  )
+}
+
+/// Helper method to convert Parquet date into a `NaiveDate`.
+/// Input `value` is a number of days since the epoch, values that do not fit into
+/// `NaiveDate` result in error.
+#[inline]
+fn convert_date(value: u32) -> Result<NaiveDate> {
+  date_from_days(value as i32)
+    .ok_or_else(|| general_err!("Date {} is out of range", value as i32))
+}
+
 /// Helper method to convert Parquet date into a string.
 /// Input `value` is a number of days since the epoch in UTC.
 /// Date is displayed in local timezone.
@@ -556,12 +851,19 @@ fn convert_date_to_string(value: u32) -> String {
 }
 
 /// Helper method to convert Parquet timestamp into a string.
-/// Input `value` is a number of milliseconds since the epoch in UTC.
-/// Datetime is displayed in local timezone.
+/// Timestamps adjusted to UTC are displayed in local timezone, other timestamps are
+/// displayed as is, without timezone. Fractional seconds are only displayed if they are
+/// not zero, with 3, 6 or 9 digits.
 #[inline]
-fn convert_timestamp_to_string(value: u64) -> String {
-  let dt = Local.timestamp((value / 1000) as i64, 0);
-  format!("{}", dt.format("%Y-%m-%d %H:%M:%S %:z"))
+fn convert_timestamp_to_string(value: Timestamp) -> String {
+  let (seconds, nanos) = value.seconds_and_nanos();
+  if value.is_adjusted_to_utc() {
+    let dt = Local.timestamp(seconds, nanos);
+    format!("{}", dt.format("%Y-%m-%d %H:%M:%S%.f %:z"))
+  } else {
+    let dt = NaiveDateTime::from_timestamp(seconds, nanos);
+    format!("{}", dt.format("%Y-%m-%d %H:%M:%S%.f"))
+  }
 }
 
 /// Helper method to convert Parquet decimal into a string.
@@ -662,6 +964,10 @@ mod tests {
     let descr = make_column_descr![PhysicalType::INT32, LogicalType::DECIMAL, 0, 8, 2];
     let row = Field::convert_int32(&descr, 444);
     assert_eq!(row, Field::Decimal(Decimal::from_i32(444, 8, 2)));
+
+    let descr = make_column_descr![PhysicalType::INT32, LogicalType::TIME_MILLIS];
+    let row = Field::convert_int32(&descr, 555);
+    assert_eq!(row, Field::Time(Time::new(555, TimeUnit::MILLIS, true)));
   }
 
   #[test]
@@ -677,6 +983,24 @@ mod tests {
     let descr = make_column_descr![PhysicalType::INT64, LogicalType::DECIMAL, 0, 8, 2];
     let row = Field::convert_int64(&descr, 3333);
     assert_eq!(row, Field::Decimal(Decimal::from_i64(3333, 8, 2)));
+
+    let descr = make_column_descr![PhysicalType::INT64, LogicalType::TIMESTAMP_MILLIS];
+    let row = Field::convert_int64(&descr, 4444);
+    assert_eq!(row, Field::Timestamp(Timestamp::new(4444, TimeUnit::MILLIS, true)));
+
+    let logical_type = LogicalType::timestamp(TimeUnit::NANOS, false);
+    let descr = make_column_descr![PhysicalType::INT64, logical_type];
+    let row = Field::convert_int64(&descr, 5555);
+    assert_eq!(row, Field::Timestamp(Timestamp::new(5555, TimeUnit::NANOS, false)));
+
+    let descr = make_column_descr![PhysicalType::INT64, LogicalType::TIME_MICROS];
+    let row = Field::convert_int64(&descr, 6666);
+    assert_eq!(row, Field::Time(Time::new(6666, TimeUnit::MICROS, true)));
+
+    let logical_type = LogicalType::time(TimeUnit::NANOS, false);
+    let descr = make_column_descr![PhysicalType::INT64, logical_type];
+    let row = Field::convert_int64(&descr, 7777);
+    assert_eq!(row, Field::Time(Time::new(7777, TimeUnit::NANOS, false)));
   }
 
   #[test]
@@ -686,11 +1010,16 @@ mod tests {
 
     let value = Int96::from(vec![0, 0, 2454923]);
     let row = Field::convert_int96(&descr, value);
-    assert_eq!(row, Field::Timestamp(1238544000000));
+    let ts = Timestamp::new(1238544000000000000, TimeUnit::NANOS, true);
+    assert_eq!(row, Field::Timestamp(ts));
 
     let value = Int96::from(vec![4165425152, 13, 2454923]);
     let row = Field::convert_int96(&descr, value);
-    assert_eq!(row, Field::Timestamp(1238544060000));
+    let ts = Timestamp::new(1238544060000000000, TimeUnit::NANOS, true);
+    assert_eq!(row, Field::Timestamp(ts));
+    if let Field::Timestamp(ts) = row {
+      assert_eq!(ts.millis(), 1238544060000);
+    }
   }
 
   #[test]
@@ -714,53 +1043,141 @@ mod tests {
     // UTF8
     let descr = make_column_descr![PhysicalType::BYTE_ARRAY, LogicalType::UTF8];
     let value = ByteArray::from(vec![b'A', b'B', b'C', b'D']);
-    let row = Field::convert_byte_array(&descr, value);
+    let row = Field::convert_byte_array(&descr, value).unwrap();
     assert_eq!(row, Field::Str("ABCD".to_string()));
 
     // ENUM
     let descr = make_column_descr![PhysicalType::BYTE_ARRAY, LogicalType::ENUM];
     let value = ByteArray::from(vec![b'1', b'2', b'3']);
-    let row = Field::convert_byte_array(&descr, value);
+    let row = Field::convert_byte_array(&descr, value).unwrap();
     assert_eq!(row, Field::Str("123".to_string()));
 
-    // JSON
-    let descr = make_column_descr![PhysicalType::BYTE_ARRAY, LogicalType::JSON];
-    let value = ByteArray::from(vec![b'{', b'"', b'a', b'"', b':', b'1', b'}']);
-    let row = Field::convert_byte_array(&descr, value);
-    assert_eq!(row, Field::Str("{\"a\":1}".to_string()));
-
     // NONE
     let descr = make_column_descr![PhysicalType::BYTE_ARRAY, LogicalType::NONE];
     let value = ByteArray::from(vec![1, 2, 3, 4, 5]);
-    let row = Field::convert_byte_array(&descr, value.clone());
+    let row = Field::convert_byte_array(&descr, value.clone()).unwrap();
     assert_eq!(row, Field::Bytes(value));
 
     // BSON
     let descr = make_column_descr![PhysicalType::BYTE_ARRAY, LogicalType::BSON];
     let value = ByteArray::from(vec![1, 2, 3, 4, 5]);
-    let row = Field::convert_byte_array(&descr, value.clone());
+    let row = Field::convert_byte_array(&descr, value.clone()).unwrap();
     assert_eq!(row, Field::Bytes(value));
 
     // DECIMAL
     let descr = make_column_descr![
       PhysicalType::BYTE_ARRAY, LogicalType::DECIMAL, 0, 8, 2];
     let value = ByteArray::from(vec![207, 200]);
-    let row = Field::convert_byte_array(&descr, value.clone());
+    let row = Field::convert_byte_array(&descr, value.clone()).unwrap();
     assert_eq!(row, Field::Decimal(Decimal::from_bytes(value, 8, 2)));
 
     // DECIMAL (FIXED_LEN_BYTE_ARRAY)
     let descr = make_column_descr![
       PhysicalType::FIXED_LEN_BYTE_ARRAY, LogicalType::DECIMAL, 8, 17, 5];
     let value = ByteArray::from(vec![0, 0, 0, 0, 0, 4, 147, 224]);
-    let row = Field::convert_byte_array(&descr, value.clone());
+    let row = Field::convert_byte_array(&descr, value.clone()).unwrap();
     assert_eq!(row, Field::Decimal(Decimal::from_bytes(value, 17, 5)));
 
     // NONE (FIXED_LEN_BYTE_ARRAY)
     let descr = make_column_descr![
       PhysicalType::FIXED_LEN_BYTE_ARRAY, LogicalType::NONE, 6, 0, 0];
     let value = ByteArray::from(vec![1, 2, 3, 4, 5, 6]);
-    let row = Field::convert_byte_array(&descr, value.clone());
+    let row = Field::convert_byte_array(&descr, value.clone()).unwrap();
     assert_eq!(row, Field::Bytes(value));
+
+    // UUID (FIXED_LEN_BYTE_ARRAY)
+    let descr = make_column_descr![
+      PhysicalType::FIXED_LEN_BYTE_ARRAY, LogicalType::UUID, 16, 0, 0];
+    let value = ByteArray::from(vec![
+      0x12, 0x3e, 0x45, 0x67, 0xe8, 0x9b, 0x12, 0xd3,
+      0xa4, 0x56, 0x42, 0x66, 0x14, 0x17, 0x40, 0x00
+    ]);
+    let row = Field::convert_byte_array(&descr, value).unwrap();
+    assert_eq!(row, Field::Str("123e4567-e89b-12d3-a456-426614174000".to_string()));
+
+    // FLOAT16 (FIXED_LEN_BYTE_ARRAY)
+    let descr = make_column_descr![
+      PhysicalType::FIXED_LEN_BYTE_ARRAY, LogicalType::FLOAT16, 2, 0, 0];
+    let value = ByteArray::from(vec![0x00, 0xbe]);
+    let row = Field::convert_byte_array(&descr, value).unwrap();
+    assert_eq!(row, Field::Float(-1.5));
+
+    // INTERVAL (FIXED_LEN_BYTE_ARRAY)
+    let descr = make_column_descr![
+      PhysicalType::FIXED_LEN_BYTE_ARRAY, LogicalType::INTERVAL, 12, 0, 0];
+    let value = Interval::new(14, 3, 60000).to_bytes();
+    let row = Field::convert_byte_array(&descr, value).unwrap();
+    assert_eq!(row, Field::Interval(Interval::new(14, 3, 60000)));
+  }
+
+  #[cfg(not(feature = "serde_json"))]
+  #[test]
+  fn test_row_convert_byte_array_json() {
+    let descr = make_column_descr![PhysicalType::BYTE_ARRAY, LogicalType::JSON];
+    let value = ByteArray::from(vec![b'{', b'"', b'a', b'"', b':', b'1', b'}']);
+    let row = Field::convert_byte_array(&descr, value).unwrap();
+    assert_eq!(row, Field::Str("{\"a\":1}".to_string()));
+  }
+
+  #[cfg(feature = "serde_json")]
+  #[test]
+  fn test_row_convert_byte_array_json() {
+    let descr = make_column_descr![PhysicalType::BYTE_ARRAY, LogicalType::JSON];
+    let value = ByteArray::from("{\"a\": [1, \"b\"]}");
+    let row = Field::convert_byte_array(&descr, value).unwrap();
+    let expected: serde_json::Value = serde_json::from_str("{\"a\":[1,\"b\"]}").unwrap();
+    assert_eq!(row, Field::Json(expected.clone()));
+    assert_eq!(row.to_json(), "{\"a\":[1,\"b\"]}");
+    assert_eq!(row.to_string(), "{\"a\":[1,\"b\"]}");
+
+    let record = make_row(vec![("doc".to_string(), row)]);
+    assert_eq!(record.get_json(0).unwrap(), &expected);
+    assert_eq!(
+      record.get_string(0).unwrap_err(),
+      general_err!("Cannot access Json as Str")
+    );
+    let list = make_list(vec![Field::Json(expected.clone())]);
+    assert_eq!(list.get_json(0).unwrap(), &expected);
+  }
+
+  #[cfg(feature = "serde_json")]
+  #[test]
+  fn test_row_convert_byte_array_invalid_json() {
+    let descr = make_column_descr![PhysicalType::BYTE_ARRAY, LogicalType::JSON];
+    assert_eq!(
+      Field::convert_byte_array(&descr, ByteArray::from("{\"a\":")).unwrap_err(),
+      general_err!(
+        "Invalid JSON value in column \"col\": EOF while parsing a value at line 1 \
+         column 5"
+      )
+    );
+  }
+
+  #[test]
+  fn test_row_convert_byte_array_utf8_validation() {
+    let descr = make_column_descr![PhysicalType::BYTE_ARRAY, LogicalType::UTF8];
+    let value = ByteArray::from(vec![b'A', 0xff, b'B']);
+
+    let row = Field::convert_byte_array_with_validation(
+      &descr, value.clone(), Utf8Validation::Lossy).unwrap();
+    assert_eq!(row, Field::Str("A\u{FFFD}B".to_string()));
+
+    let value = ByteArray::from("\u{e9}t\u{e9}");
+    let row = Field::convert_byte_array(&descr, value).unwrap();
+    assert_eq!(row, Field::Str("\u{e9}t\u{e9}".to_string()));
+  }
+
+  #[test]
+  fn test_row_convert_byte_array_utf8_strict() {
+    let descr = make_column_descr![PhysicalType::BYTE_ARRAY, LogicalType::UTF8];
+    let value = ByteArray::from(vec![b'A', 0xff, b'B']);
+    assert_eq!(
+      Field::convert_byte_array(&descr, value).unwrap_err(),
+      general_err!(
+        "Invalid UTF-8 value in column \"col\": invalid utf-8 sequence of 1 bytes from \
+         index 1"
+      )
+    );
   }
 
   #[test]
@@ -785,7 +1202,8 @@ mod tests {
     fn check_datetime_conversion(y: u32, m: u32, d: u32, h: u32, mi: u32, s: u32) {
       let datetime = chrono::NaiveDate::from_ymd(y as i32, m, d).and_hms(h, mi, s);
       let dt = Local.from_utc_datetime(&datetime);
-      let res = convert_timestamp_to_string(dt.timestamp_millis() as u64);
+      let ts = Timestamp::new(dt.timestamp_millis(), TimeUnit::MILLIS, true);
+      let res = convert_timestamp_to_string(ts);
       let exp = format!("{}", dt.format("%Y-%m-%d %H:%M:%S %:z"));
       assert_eq!(res, exp);
     }
@@ -797,6 +1215,29 @@ mod tests {
     check_datetime_conversion(2014, 11, 28, 21, 15, 12);
   }
 
+  #[test]
+  fn test_convert_timestamp_units_to_string() {
+    // Local timestamps are displayed as is, regardless of the local timezone
+    let ts = Timestamp::new(1262391174000, TimeUnit::MILLIS, false);
+    assert_eq!(convert_timestamp_to_string(ts), "2010-01-02 00:12:54");
+    let ts = Timestamp::new(1262391174120, TimeUnit::MILLIS, false);
+    assert_eq!(convert_timestamp_to_string(ts), "2010-01-02 00:12:54.120");
+    let ts = Timestamp::new(1262391174000123, TimeUnit::MICROS, false);
+    assert_eq!(convert_timestamp_to_string(ts), "2010-01-02 00:12:54.000123");
+    let ts = Timestamp::new(1262391174000000001, TimeUnit::NANOS, false);
+    assert_eq!(convert_timestamp_to_string(ts), "2010-01-02 00:12:54.000000001");
+    let ts = Timestamp::new(-1, TimeUnit::MICROS, false);
+    assert_eq!(convert_timestamp_to_string(ts), "1969-12-31 23:59:59.999999");
+
+    // Timestamps adjusted to UTC are displayed in the local timezone
+    let ts = Timestamp::new(1262391174000123456, TimeUnit::NANOS, true);
+    let dt = Local.timestamp(1262391174, 123456);
+    assert_eq!(
+      convert_timestamp_to_string(ts),
+      format!("{}", dt.format("%Y-%m-%d %H:%M:%S.000123456 %:z"))
+    );
+  }
+
   #[test]
   fn test_convert_float_to_string() {
     assert_eq!(format!("{}", Field::Float(1.0)), "1.0");
@@ -844,6 +1285,41 @@ mod tests {
     check_decimal(vec![207, 200], 10, 8, "-0.00012344");
   }
 
+  #[test]
+  fn test_row_to_json() {
+    assert_eq!(Field::Null.to_json(), "null");
+    assert_eq!(Field::Bool(true).to_json(), "true");
+    assert_eq!(Field::Int(3).to_json(), "3");
+    assert_eq!(Field::Long(-4).to_json(), "-4");
+    assert_eq!(Field::Float(5.0).to_json(), "5.0");
+    assert_eq!(Field::Double(6.1234).to_json(), "6.1234");
+    assert_eq!(Field::Double(::std::f64::NAN).to_json(), "null");
+    assert_eq!(Field::Float(::std::f32::INFINITY).to_json(), "null");
+    assert_eq!(Field::Decimal(Decimal::from_i32(-4, 8, 2)).to_json(), "-0.04");
+    assert_eq!(
+      Field::Str("a\"b\\c\n\u{1}".to_string()).to_json(),
+      "\"a\\\"b\\\\c\\n\\u0001\""
+    );
+    assert_eq!(Field::Bytes(ByteArray::from(vec![1, 2, 3])).to_json(), "[1,2,3]");
+    assert_eq!(
+      Field::Date(14611).to_json(),
+      format!("\"{}\"", convert_date_to_string(14611))
+    );
+    assert_eq!(Field::Interval(Interval::new(1, 0, 1500)).to_json(), "\"P1M0DT1.500S\"");
+    let time = Time::new(3_723_004, TimeUnit::MILLIS, false);
+    assert_eq!(Field::Time(time).to_json(), "\"01:02:03.004\"");
+
+    let row = make_row(vec![
+      ("x".to_string(), Field::Null),
+      ("y".to_string(), Field::ListInternal(make_list(vec![Field::Int(1), Field::Null]))),
+      ("z".to_string(), Field::MapInternal(make_map(vec![
+        (Field::Str("a".to_string()), Field::Int(1)),
+        (Field::Int(2), Field::Group(make_row(vec![("b".to_string(), Field::Int(3))])))
+      ])))
+    ]);
+    assert_eq!(row.to_json(), r#"{"x":null,"y":[1,null],"z":{"a":1,"2":{"b":3}}}"#);
+  }
+
   #[test]
   fn test_row_display() {
     // Primitive types
@@ -861,10 +1337,11 @@ mod tests {
     assert_eq!(format!("{}", Field::Str("abc".to_string())), "\"abc\"");
     assert_eq!(format!("{}", Field::Bytes(ByteArray::from(vec![1, 2, 3]))), "[1, 2, 3]");
     assert_eq!(format!("{}", Field::Date(14611)), convert_date_to_string(14611));
-    assert_eq!(
-      format!("{}", Field::Timestamp(1262391174000)),
-      convert_timestamp_to_string(1262391174000)
-    );
+    let ts = Timestamp::new(1262391174000, TimeUnit::MILLIS, true);
+    assert_eq!(format!("{}", Field::Timestamp(ts)), convert_timestamp_to_string(ts));
+    assert_eq!(format!("{}", Field::Interval(Interval::new(1, 2, 3))), "P1M2DT0.003S");
+    let time = Time::new(3_723_000_005, TimeUnit::MICROS, true);
+    assert_eq!(format!("{}", Field::Time(time)), "01:02:03.000005");
     assert_eq!(
       format!("{}", Field::Decimal(Decimal::from_i32(4, 8, 2))),
       convert_decimal_to_string(&Decimal::from_i32(4, 8, 2))
@@ -914,7 +1391,10 @@ mod tests {
     assert!(Field::Double(6.1234).is_primitive());
     assert!(Field::Str("abc".to_string()).is_primitive());
     assert!(Field::Bytes(ByteArray::from(vec![1, 2, 3])).is_primitive());
-    assert!(Field::Timestamp(12345678).is_primitive());
+    let ts = Timestamp::new(12345678, TimeUnit::MILLIS, true);
+    assert!(Field::Timestamp(ts).is_primitive());
+    assert!(Field::Interval(Interval::new(1, 2, 3)).is_primitive());
+    assert!(Field::Time(Time::new(1, TimeUnit::MILLIS, true)).is_primitive());
     assert!(Field::Decimal(Decimal::from_i32(4, 8, 2)).is_primitive());
 
     // complex types
@@ -953,7 +1433,10 @@ mod tests {
       ("h".to_string(), Field::Double(8.1)),
       ("i".to_string(), Field::Str("abc".to_string())),
       ("j".to_string(), Field::Bytes(ByteArray::from(vec![1, 2, 3, 4, 5]))),
-      ("k".to_string(), Field::Decimal(Decimal::from_i32(4, 7, 2)))
+      ("k".to_string(), Field::Decimal(Decimal::from_i32(4, 7, 2))),
+      ("l".to_string(), Field::Interval(Interval::new(1, 2, 3))),
+      ("m".to_string(), Field::Date(14611)),
+      ("n".to_string(), Field::Date(::std::i32::MAX as u32))
     ]);
 
     assert_eq!(false, row.get_bool(1).unwrap());
@@ -966,6 +1449,14 @@ mod tests {
     assert_eq!("abc", row.get_string(8).unwrap());
     assert_eq!(5, row.get_bytes(9).unwrap().len());
     assert_eq!(7, row.get_decimal(10).unwrap().precision());
+    assert_eq!(2, row.get_interval(11).unwrap().days());
+    assert_eq!(14611, row.get_date(12).unwrap());
+    assert_eq!(
+      chrono::NaiveDate::from_ymd(2010, 1, 2),
+      row.get_naive_date(12).unwrap()
+    );
+    assert!(row.get_naive_date(13).is_err());
+    assert!(row.get_naive_date(4).is_err());
   }
 
   #[test]
@@ -1067,6 +1558,13 @@ mod tests {
     let list = make_list(vec![Field::Double(3.1415)]);
     assert_eq!(3.1415, list.get_double(0).unwrap());
 
+    let list = make_list(vec![Field::Date(0), Field::Date(-1i32 as u32)]);
+    assert_eq!(0, list.get_date(0).unwrap());
+    assert_eq!(
+      chrono::NaiveDate::from_ymd(1969, 12, 31),
+      list.get_naive_date(1).unwrap()
+    );
+
     let list = make_list(vec![Field::Str("abc".to_string())]);
     assert_eq!(&"abc".to_string(), list.get_string(0).unwrap());
 
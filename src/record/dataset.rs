@@ -0,0 +1,646 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains API to read Parquet files of a directory as one dataset.
+//!
+//! Files are found by scanning the root directory recursively in order of paths. Files
+//! and directories with names starting with `_` or `.` are skipped, e.g. `_SUCCESS`
+//! markers or checksum files, and names of files can be restricted to a pattern, see
+//! [`DatasetReaderBuilder::with_file_pattern`].
+//!
+//! Schema of the dataset merges top-level fields of all files by name, in order of
+//! their first occurrence. A field that exists in several files must have the same
+//! type in each of them; a field that is missing in some files must be optional and is
+//! read as null from those files.
+//!
+//! With hive-style partitioning, directories named `key=value` between the root and a
+//! file define partition values of the file, e.g. `year=2018/month=10/data.parquet`.
+//! Partition values are added to each row as optional UTF8 columns after the fields of
+//! the files, see [`DatasetReaderBuilder::with_hive_partitioning`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use parquet::record::RowAccessor;
+//! use parquet::record::dataset::DatasetReader;
+//! # use std::fs;
+//! # let root = std::env::temp_dir().join("dataset_example");
+//! # for year in &["2017", "2018"] {
+//! #   let dir = root.join(format!("year={}", year));
+//! #   fs::create_dir_all(&dir).unwrap();
+//! #   fs::copy("data/alltypes_plain.parquet", dir.join("part-0.parquet")).unwrap();
+//! # }
+//!
+//! // Directory contains files `year=2017/part-0.parquet` and `year=2018/part-0.parquet`
+//! let dataset = DatasetReader::builder(&root)
+//!   .with_hive_partitioning(true)
+//!   .build()
+//!   .unwrap();
+//! assert_eq!(dataset.files().len(), 2);
+//! assert_eq!(dataset.num_rows(), 16);
+//!
+//! let rows: Vec<_> = dataset.get_row_iter().map(|row| row.unwrap()).collect();
+//! assert_eq!(rows[0].get_string(11).unwrap(), "2017");
+//! assert_eq!(rows[15].get_string(11).unwrap(), "2018");
+//! # fs::remove_dir_all(&root).unwrap();
+//! ```
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use basic::{LogicalType, Repetition, Type as PhysicalType};
+use errors::{ParquetError, Result};
+use file::reader::{FileReader, SerializedFileReader};
+use record::api::{into_row_fields, make_row, Field, Row};
+use record::reader::{ReaderIter, TreeBuilder};
+use schema::types::{Type, TypePtr};
+
+/// Directory name of partitions with null values, as written by Hive and Spark.
+const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// Builder of [`DatasetReader`].
+pub struct DatasetReaderBuilder {
+  root: PathBuf,
+  file_pattern: Option<String>,
+  hive_partitioning: bool
+}
+
+impl DatasetReaderBuilder {
+  /// Sets pattern that names of data files must match, e.g. `*.parquet`. Pattern can
+  /// contain wildcards `*`, that matches any sequence of characters, and `?`, that
+  /// matches any single character. By default all files are read.
+  pub fn with_file_pattern(mut self, pattern: &str) -> Self {
+    self.file_pattern = Some(pattern.to_owned());
+    self
+  }
+
+  /// Sets whether or not partition values are parsed from `key=value` directory names
+  /// and added to each row. Directory names are unescaped from `%XX` sequences, and
+  /// `__HIVE_DEFAULT_PARTITION__` value is read as null. All files must have the same
+  /// partition keys in the same order. Disabled by default.
+  pub fn with_hive_partitioning(mut self, value: bool) -> Self {
+    self.hive_partitioning = value;
+    self
+  }
+
+  /// Scans root directory and reads metadata of each file to build the dataset.
+  ///
+  /// Returns error if no files are found, if schemas of the files cannot be merged, or
+  /// if partition keys of the files differ or clash with fields of the files.
+  pub fn build(self) -> Result<DatasetReader> {
+    let mut paths = Vec::new();
+    find_files(&self.root, self.file_pattern.as_ref().map(|p| &p[..]), &mut paths)?;
+    if paths.is_empty() {
+      return Err(general_err!("No files found in dataset {}", self.root.display()));
+    }
+
+    let mut root_name = None;
+    let mut fields: Vec<TypePtr> = Vec::new();
+    let mut file_schemas = Vec::with_capacity(paths.len());
+    let mut files = Vec::with_capacity(paths.len());
+    for path in paths {
+      let reader = SerializedFileReader::new(File::open(&path)?)?;
+      let metadata = reader.metadata();
+      let file_schema = metadata.file_metadata().schema_descr().root_schema_ptr();
+      if root_name.is_none() {
+        root_name = Some(file_schema.name().to_owned());
+      }
+      for field in file_schema.get_fields() {
+        match fields.iter().position(|existing| existing.name() == field.name()) {
+          Some(i) if fields[i] != *field => {
+            return Err(general_err!(
+              "Field {} of file {} does not match the field of other files",
+              field.name(),
+              path.display()
+            ));
+          },
+          Some(_) => {},
+          None => fields.push(field.clone())
+        }
+      }
+      let partition_values = if self.hive_partitioning {
+        parse_partition_values(&self.root, &path)?
+      } else {
+        Vec::new()
+      };
+      files.push(DatasetFile {
+        path: path,
+        num_rows: metadata.file_metadata().num_rows(),
+        partition_values: partition_values,
+        field_indexes: Vec::with_capacity(fields.len())
+      });
+      file_schemas.push(file_schema);
+    }
+
+    let partition_keys: Vec<String> =
+      files[0].partition_values.iter().map(|&(ref key, _)| key.clone()).collect();
+    for (file, file_schema) in files.iter_mut().zip(file_schemas.iter()) {
+      let keys = file.partition_values.iter().map(|&(ref key, _)| key);
+      if !keys.eq(partition_keys.iter()) {
+        return Err(general_err!(
+          "Partition keys of file {} do not match partition keys of other files",
+          file.path.display()
+        ));
+      }
+      for field in &fields {
+        let index =
+          file_schema.get_fields().iter().position(|f| f.name() == field.name());
+        if index.is_none() &&
+            field.get_basic_info().repetition() == Repetition::REQUIRED {
+          return Err(general_err!(
+            "Required field {} is missing in file {}",
+            field.name(),
+            file.path.display()
+          ));
+        }
+        file.field_indexes.push(index);
+      }
+    }
+
+    for key in &partition_keys {
+      if fields.iter().any(|field| field.name() == key) {
+        return Err(general_err!("Partition key {} is also a field of the files", key));
+      }
+      let field = Type::primitive_type_builder(key, PhysicalType::BYTE_ARRAY)
+        .with_repetition(Repetition::OPTIONAL)
+        .with_logical_type(LogicalType::UTF8)
+        .build()?;
+      fields.push(Rc::new(field));
+    }
+    let schema = Type::group_type_builder(&root_name.expect("Dataset has files"))
+      .with_fields(&mut fields)
+      .build()?;
+
+    Ok(DatasetReader {
+      schema: Rc::new(schema),
+      files: files
+    })
+  }
+}
+
+/// Data file of a dataset.
+pub struct DatasetFile {
+  path: PathBuf,
+  num_rows: i64,
+  partition_values: Vec<(String, Option<String>)>,
+  // Index of each field of the dataset schema in the file schema, excluding partitions
+  field_indexes: Vec<Option<usize>>
+}
+
+impl DatasetFile {
+  /// Returns path of the file.
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Returns number of rows of the file.
+  pub fn num_rows(&self) -> i64 {
+    self.num_rows
+  }
+
+  /// Returns partition keys and values of the file, empty unless hive-style
+  /// partitioning is enabled. Null values are `None`.
+  pub fn partition_values(&self) -> &[(String, Option<String>)] {
+    &self.partition_values
+  }
+
+  /// Converts `row` of the file into row of the dataset with `schema`.
+  fn dataset_row(&self, schema: &Type, row: Row) -> Row {
+    let mut values: Vec<_> = into_row_fields(row).into_iter().map(Some).collect();
+    let mut fields = Vec::with_capacity(schema.get_fields().len());
+    for (field, index) in schema.get_fields().iter().zip(self.field_indexes.iter()) {
+      let value = match *index {
+        Some(i) => values[i].take().map(|(_, value)| value).unwrap_or(Field::Null),
+        None => Field::Null
+      };
+      fields.push((field.name().to_owned(), value));
+    }
+    for &(ref key, ref value) in &self.partition_values {
+      let value = value.clone().map(Field::Str).unwrap_or(Field::Null);
+      fields.push((key.clone(), value));
+    }
+    make_row(fields)
+  }
+}
+
+/// Reader of Parquet files of a directory as one dataset, see module documentation
+/// for more details.
+pub struct DatasetReader {
+  schema: TypePtr,
+  files: Vec<DatasetFile>
+}
+
+impl DatasetReader {
+  /// Creates builder of a dataset with files in directory `root`.
+  pub fn builder<P: AsRef<Path>>(root: P) -> DatasetReaderBuilder {
+    DatasetReaderBuilder {
+      root: root.as_ref().to_path_buf(),
+      file_pattern: None,
+      hive_partitioning: false
+    }
+  }
+
+  /// Returns merged schema of the dataset, including partition columns.
+  pub fn schema(&self) -> &Type {
+    &self.schema
+  }
+
+  /// Returns data files of the dataset in the order they are read.
+  pub fn files(&self) -> &[DatasetFile] {
+    &self.files
+  }
+
+  /// Returns total number of rows of all files.
+  pub fn num_rows(&self) -> i64 {
+    self.files.iter().map(|file| file.num_rows).sum()
+  }
+
+  /// Returns iterator of rows of all files with the dataset schema. Files are opened
+  /// one at a time, when their rows are read.
+  pub fn get_row_iter(&self) -> DatasetRowIter<'_> {
+    DatasetRowIter {
+      dataset: self,
+      tree_builder: TreeBuilder::new(),
+      reader: None,
+      current_file: 0,
+      next_file: 0,
+      next_row_group: 0,
+      row_iter: None,
+      done: false
+    }
+  }
+}
+
+/// Iterator of rows of a dataset, see [`DatasetReader::get_row_iter`]. Iteration stops
+/// after the first error, e.g. when a file cannot be opened or a row cannot be read.
+pub struct DatasetRowIter<'a> {
+  dataset: &'a DatasetReader,
+  tree_builder: TreeBuilder,
+  reader: Option<SerializedFileReader<File>>,
+  current_file: usize,
+  next_file: usize,
+  next_row_group: usize,
+  row_iter: Option<ReaderIter>,
+  done: bool
+}
+
+impl<'a> DatasetRowIter<'a> {
+  /// Moves to the next row group, opening the next file if needed. Returns `false` if
+  /// there are no row groups left.
+  fn advance(&mut self) -> Result<bool> {
+    self.row_iter = None;
+    loop {
+      if let Some(ref reader) = self.reader {
+        if self.next_row_group < reader.num_row_groups() {
+          let row_group_reader = reader.get_row_group(self.next_row_group)?;
+          let descr = reader.metadata().file_metadata().schema_descr_ptr();
+          self.row_iter = Some(self.tree_builder.as_iter(descr, &*row_group_reader));
+          self.next_row_group += 1;
+          return Ok(true);
+        }
+      }
+      if self.next_file >= self.dataset.files.len() {
+        self.reader = None;
+        return Ok(false);
+      }
+      let file = File::open(&self.dataset.files[self.next_file].path)?;
+      self.reader = Some(SerializedFileReader::new(file)?);
+      self.current_file = self.next_file;
+      self.next_file += 1;
+      self.next_row_group = 0;
+    }
+  }
+}
+
+impl<'a> Iterator for DatasetRowIter<'a> {
+  type Item = Result<Row>;
+
+  fn next(&mut self) -> Option<Result<Row>> {
+    while !self.done {
+      if let Some(ref mut iter) = self.row_iter {
+        match iter.next() {
+          Some(Ok(row)) => {
+            let file = &self.dataset.files[self.current_file];
+            return Some(Ok(file.dataset_row(&self.dataset.schema, row)));
+          },
+          Some(Err(e)) => {
+            self.done = true;
+            return Some(Err(e));
+          },
+          None => {}
+        }
+      }
+      match self.advance() {
+        Ok(true) => {},
+        Ok(false) => self.done = true,
+        Err(e) => {
+          self.done = true;
+          return Some(Err(e));
+        }
+      }
+    }
+    None
+  }
+}
+
+/// Appends paths of data files in directory `dir` and its subdirectories to `files`,
+/// sorted by path. Names of data files must match `pattern`, if it is set.
+fn find_files(dir: &Path, pattern: Option<&str>, files: &mut Vec<PathBuf>) -> Result<()> {
+  let mut paths = Vec::new();
+  for entry in fs::read_dir(dir)? {
+    paths.push(entry?.path());
+  }
+  paths.sort();
+  for path in paths {
+    let is_hidden = match path.file_name().and_then(|name| name.to_str()) {
+      Some(name) => name.starts_with('_') || name.starts_with('.'),
+      None => true
+    };
+    if is_hidden {
+      continue;
+    }
+    if path.is_dir() {
+      find_files(&path, pattern, files)?;
+    } else if pattern.map_or(true, |pattern| matches_pattern(pattern, &path)) {
+      files.push(path);
+    }
+  }
+  Ok(())
+}
+
+/// Returns `true` if file name of `path` matches `pattern` with `*` and `?` wildcards.
+fn matches_pattern(pattern: &str, path: &Path) -> bool {
+  fn matches(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.first(), name.first()) {
+      (None, None) => true,
+      (Some(&'*'), _) => {
+        matches(&pattern[1..], name) || !name.is_empty() && matches(pattern, &name[1..])
+      },
+      (Some(&'?'), Some(_)) => matches(&pattern[1..], &name[1..]),
+      (Some(p), Some(n)) if p == n => matches(&pattern[1..], &name[1..]),
+      _ => false
+    }
+  }
+  let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+  let pattern: Vec<char> = pattern.chars().collect();
+  let name: Vec<char> = name.chars().collect();
+  matches(&pattern, &name)
+}
+
+/// Returns partition keys and values parsed from `key=value` names of directories
+/// between `root` and file `path`. Other directories are ignored.
+fn parse_partition_values(
+  root: &Path,
+  path: &Path
+) -> Result<Vec<(String, Option<String>)>> {
+  let relative = match path.strip_prefix(root) {
+    Ok(relative) => relative,
+    Err(_) => return Err(general_err!("File {} is not in dataset", path.display()))
+  };
+  let mut values = Vec::new();
+  if let Some(dir) = relative.parent() {
+    for component in dir.components() {
+      let name = component.as_os_str().to_string_lossy();
+      if let Some(pos) = name.find('=') {
+        let key = unescape_path_name(&name[..pos]);
+        let value = &name[pos + 1..];
+        let value = if value == HIVE_DEFAULT_PARTITION {
+          None
+        } else {
+          Some(unescape_path_name(value))
+        };
+        values.push((key, value));
+      }
+    }
+  }
+  Ok(values)
+}
+
+/// Returns `name` with `%XX` escape sequences of hive partition directories replaced by
+/// the characters they encode.
+fn unescape_path_name(name: &str) -> String {
+  let bytes = name.as_bytes();
+  let mut result = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    if bytes[i] == b'%' && i + 2 < bytes.len() &&
+        (bytes[i + 1] as char).is_digit(16) && (bytes[i + 2] as char).is_digit(16) {
+      let hex = [bytes[i + 1], bytes[i + 2]];
+      let hex = ::std::str::from_utf8(&hex).expect("Hex digits are valid UTF-8");
+      result.push(u8::from_str_radix(hex, 16).expect("Hex digits are valid number"));
+      i += 3;
+    } else {
+      result.push(bytes[i]);
+      i += 1;
+    }
+  }
+  String::from_utf8_lossy(&result).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::io::Write;
+
+  use column::writer::{get_typed_column_writer, ColumnWriter};
+  use data_type::Int32Type;
+  use file::properties::WriterProperties;
+  use file::writer::{write_to_bytes, FileWriter};
+  use record::RowAccessor;
+  use schema::parser::parse_message_type;
+  use util::test_common::{get_temp_path, get_test_path};
+
+  /// Returns empty directory `name` in "target/debug/testdata".
+  fn get_temp_dir(name: &str) -> PathBuf {
+    let path = get_temp_path(name);
+    if path.exists() {
+      fs::remove_dir_all(&path).unwrap();
+    }
+    fs::create_dir_all(&path).unwrap();
+    path
+  }
+
+  /// Writes file `path` with INT32 columns of `message_type`, where each column has
+  /// values `ids`.
+  fn write_file(path: &Path, message_type: &str, ids: &[i32]) {
+    let schema = Rc::new(parse_message_type(message_type).unwrap());
+    let repetitions = schema
+      .get_fields()
+      .iter()
+      .map(|field| field.get_basic_info().repetition())
+      .collect::<Vec<_>>();
+    let props = Rc::new(WriterProperties::builder().build());
+    let bytes = write_to_bytes(schema, props, |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      for repetition in &repetitions {
+        let column_writer = row_group_writer.next_column()?.unwrap();
+        let mut typed = get_typed_column_writer::<Int32Type>(column_writer);
+        let def_levels = vec![1; ids.len()];
+        let def_levels = match *repetition {
+          Repetition::REQUIRED => None,
+          _ => Some(&def_levels[..])
+        };
+        typed.write_batch(ids, def_levels, None)?;
+        row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed))?;
+      }
+      writer.close_row_group(row_group_writer)
+    }).unwrap();
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    File::create(path).unwrap().write_all(&bytes).unwrap();
+  }
+
+  #[test]
+  fn test_dataset_reader_merged_schema() {
+    let root = get_temp_dir("dataset_merged_schema");
+    write_file(
+      &root.join("year=2018/part-0.parquet"),
+      "message schema { REQUIRED INT32 id; OPTIONAL INT32 a; }",
+      &[1, 2]
+    );
+    write_file(
+      &root.join("year=2019/part-0.parquet"),
+      "message schema { REQUIRED INT32 id; OPTIONAL INT32 b; }",
+      &[3]
+    );
+    write_file(
+      &root.join("year=__HIVE_DEFAULT_PARTITION__/part-0.parquet"),
+      "message schema { REQUIRED INT32 id; }",
+      &[4]
+    );
+    File::create(root.join("_SUCCESS")).unwrap();
+    File::create(root.join("year=2018/.part-0.parquet.crc")).unwrap();
+
+    let dataset = DatasetReader::builder(&root).with_hive_partitioning(true).build();
+    let dataset = dataset.unwrap();
+    let names: Vec<_> = dataset.schema().get_fields().iter().map(|f| f.name()).collect();
+    assert_eq!(names, vec!["id", "a", "b", "year"]);
+    assert_eq!(dataset.files().len(), 3);
+    assert_eq!(dataset.num_rows(), 4);
+    assert_eq!(
+      dataset.files()[0].partition_values(),
+      &[("year".to_owned(), Some("2018".to_owned()))]
+    );
+    assert_eq!(dataset.files()[2].partition_values(), &[("year".to_owned(), None)]);
+
+    let rows: Vec<Row> = dataset.get_row_iter().map(|row| row.unwrap()).collect();
+    let rows: Vec<String> = rows.iter().map(|row| row.to_string()).collect();
+    assert_eq!(
+      rows,
+      vec![
+        "{id: 1, a: 1, b: null, year: \"2018\"}",
+        "{id: 2, a: 2, b: null, year: \"2018\"}",
+        "{id: 3, a: null, b: 3, year: \"2019\"}",
+        "{id: 4, a: null, b: null, year: null}"
+      ]
+    );
+
+    // Without partitioning only fields of the files are read
+    let dataset = DatasetReader::builder(&root).build().unwrap();
+    assert_eq!(dataset.schema().get_fields().len(), 3);
+    let row = dataset.get_row_iter().next().unwrap().unwrap();
+    assert_eq!(row.len(), 3);
+    assert_eq!(row.get_int(0).unwrap(), 1);
+  }
+
+  #[test]
+  fn test_dataset_reader_file_pattern() {
+    let root = get_temp_dir("dataset_file_pattern");
+    fs::copy(get_test_path("alltypes_plain.parquet"), root.join("a.parquet")).unwrap();
+    fs::copy(get_test_path("alltypes_plain.parquet"), root.join("b.parquet")).unwrap();
+    File::create(root.join("notes.txt")).unwrap();
+
+    let dataset = DatasetReader::builder(&root).with_file_pattern("?.parq*").build();
+    let dataset = dataset.unwrap();
+    assert_eq!(dataset.files().len(), 2);
+    assert_eq!(dataset.files()[0].path(), root.join("a.parquet").as_path());
+    assert_eq!(dataset.get_row_iter().count(), 16);
+
+    // Text file is not a Parquet file
+    let result = DatasetReader::builder(&root).build();
+    assert!(result.is_err());
+
+    let result = DatasetReader::builder(&root).with_file_pattern("*.csv").build();
+    assert_eq!(
+      result.err().unwrap(),
+      general_err!("No files found in dataset {}", root.display())
+    );
+  }
+
+  #[test]
+  fn test_dataset_reader_invalid() {
+    let root = get_temp_dir("dataset_type_mismatch");
+    write_file(&root.join("a.parquet"), "message schema { REQUIRED INT32 id; }", &[1]);
+    write_file(&root.join("b.parquet"), "message schema { OPTIONAL INT32 id; }", &[1]);
+    assert_eq!(
+      DatasetReader::builder(&root).build().err().unwrap(),
+      general_err!(
+        "Field id of file {} does not match the field of other files",
+        root.join("b.parquet").display()
+      )
+    );
+
+    let root = get_temp_dir("dataset_missing_required");
+    write_file(&root.join("a.parquet"), "message schema { REQUIRED INT32 id; }", &[1]);
+    write_file(&root.join("b.parquet"), "message schema { REQUIRED INT32 x; }", &[1]);
+    assert_eq!(
+      DatasetReader::builder(&root).build().err().unwrap(),
+      general_err!(
+        "Required field x is missing in file {}",
+        root.join("a.parquet").display()
+      )
+    );
+
+    let root = get_temp_dir("dataset_partition_keys");
+    let message_type = "message schema { REQUIRED INT32 id; }";
+    write_file(&root.join("a=1/part-0.parquet"), message_type, &[1]);
+    write_file(&root.join("b=1/part-0.parquet"), message_type, &[1]);
+    assert_eq!(
+      DatasetReader::builder(&root).with_hive_partitioning(true).build().err().unwrap(),
+      general_err!(
+        "Partition keys of file {} do not match partition keys of other files",
+        root.join("b=1/part-0.parquet").display()
+      )
+    );
+
+    let root = get_temp_dir("dataset_partition_clash");
+    write_file(&root.join("id=1/part-0.parquet"), message_type, &[1]);
+    assert_eq!(
+      DatasetReader::builder(&root).with_hive_partitioning(true).build().err().unwrap(),
+      general_err!("Partition key id is also a field of the files")
+    );
+  }
+
+  #[test]
+  fn test_parse_partition_values() {
+    let values = parse_partition_values(
+      Path::new("/data"),
+      Path::new("/data/dt=2018-10-01/city=San%20Jose/other/part-0.parquet")
+    ).unwrap();
+    assert_eq!(
+      values,
+      vec![
+        ("dt".to_owned(), Some("2018-10-01".to_owned())),
+        ("city".to_owned(), Some("San Jose".to_owned()))
+      ]
+    );
+    assert_eq!(unescape_path_name("a%3Db%2"), "a=b%2");
+    assert!(matches_pattern("part-*.parquet", Path::new("/data/part-0.parquet")));
+    assert!(!matches_pattern("part-*.parquet", Path::new("/data/part-0.parquet.crc")));
+  }
+}
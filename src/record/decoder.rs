@@ -0,0 +1,258 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains untyped decoding of leaf column values.
+//!
+//! [`ColumnValueDecoder`](struct.ColumnValueDecoder.html) reads a single leaf column
+//! of any physical type and yields each value as a [`Field`](../enum.Field.html)
+//! together with its definition and repetition levels. It is meant for tools, such as
+//! dumpers and converters, that only know the schema at runtime and therefore cannot
+//! use typed column readers.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::fs::File;
+//!
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//! use parquet::record::decoder::ColumnValueDecoder;
+//!
+//! let file = File::open("data.parquet").unwrap();
+//! let reader = SerializedFileReader::new(file).unwrap();
+//! let row_group = reader.get_row_group(0).unwrap();
+//! for i in 0..row_group.num_columns() {
+//!   let decoder = ColumnValueDecoder::from_row_group(&*row_group, i).unwrap();
+//!   for value in decoder {
+//!     println!("{}", value.unwrap().value());
+//!   }
+//! }
+//! ```
+
+use column::reader::ColumnReader;
+use errors::{ParquetError, Result};
+use file::reader::RowGroupReader;
+use record::api::{Field, Utf8Validation};
+use record::triplet::TripletIter;
+use schema::types::ColumnDescPtr;
+
+/// Default number of levels and values buffered by the decoder.
+const DEFAULT_BATCH_SIZE: usize = 1024;
+
+/// Single decoded value of a leaf column with its levels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnValue {
+  value: Field,
+  def_level: i16,
+  rep_level: i16
+}
+
+impl ColumnValue {
+  /// Returns decoded value, `Field::Null` if the value is null.
+  pub fn value(&self) -> &Field {
+    &self.value
+  }
+
+  /// Consumes this value and returns decoded field.
+  pub fn into_value(self) -> Field {
+    self.value
+  }
+
+  /// Returns definition level of the value.
+  pub fn def_level(&self) -> i16 {
+    self.def_level
+  }
+
+  /// Returns repetition level of the value.
+  pub fn rep_level(&self) -> i16 {
+    self.rep_level
+  }
+
+  /// Returns true, if value is null.
+  pub fn is_null(&self) -> bool {
+    self.value == Field::Null
+  }
+}
+
+/// Type-erased decoder of a leaf column.
+/// Iterates over all values of the column, including nulls, converting them into fields
+/// according to the physical and logical types of the column descriptor.
+pub struct ColumnValueDecoder {
+  triplets: TripletIter,
+  descr: ColumnDescPtr,
+  done: bool
+}
+
+impl ColumnValueDecoder {
+  /// Creates new decoder for column reader of any physical type.
+  pub fn new(descr: ColumnDescPtr, reader: ColumnReader, batch_size: usize) -> Self {
+    let triplets = TripletIter::new(descr.clone(), reader, batch_size);
+    Self { triplets, descr, done: false }
+  }
+
+  /// Creates new decoder for `i`th column of the row group with default batch size.
+  pub fn from_row_group(row_group_reader: &RowGroupReader, i: usize) -> Result<Self> {
+    if i >= row_group_reader.num_columns() {
+      return Err(general_err!(
+        "Column index {} is out of bound, row group has {} columns",
+        i,
+        row_group_reader.num_columns()
+      ));
+    }
+    let descr = row_group_reader.metadata().column(i).column_descr_ptr();
+    let reader = row_group_reader.get_column_reader(i)?;
+    Ok(Self::new(descr, reader, DEFAULT_BATCH_SIZE))
+  }
+
+  /// Sets validation of UTF-8 strings converted from BYTE_ARRAY values.
+  pub fn with_utf8_validation(mut self, validation: Utf8Validation) -> Self {
+    self.triplets = self.triplets.with_utf8_validation(validation);
+    self
+  }
+
+  /// Returns column descriptor of the decoded column.
+  pub fn column_descr(&self) -> &ColumnDescPtr {
+    &self.descr
+  }
+
+  /// Reads next value of the column.
+  /// Returns `None` when all values have been read.
+  pub fn read_value(&mut self) -> Result<Option<ColumnValue>> {
+    if self.done {
+      return Ok(None);
+    }
+    match self.triplets.read_next() {
+      Ok(true) => {},
+      Ok(false) => {
+        self.done = true;
+        return Ok(None);
+      },
+      Err(e) => {
+        self.done = true;
+        return Err(e);
+      }
+    }
+    let value = if self.triplets.is_null() {
+      Field::Null
+    } else {
+      self.triplets.current_value()?
+    };
+    Ok(Some(ColumnValue {
+      value,
+      def_level: self.triplets.current_def_level(),
+      rep_level: self.triplets.current_rep_level()
+    }))
+  }
+}
+
+impl Iterator for ColumnValueDecoder {
+  type Item = Result<ColumnValue>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    match self.read_value() {
+      Ok(Some(value)) => Some(Ok(value)),
+      Ok(None) => None,
+      Err(e) => Some(Err(e))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use file::reader::{FileReader, SerializedFileReader};
+  use util::test_common::get_test_file;
+
+  fn decode_column(file_name: &str, i: usize, batch_size: usize) -> Vec<ColumnValue> {
+    let file_reader = SerializedFileReader::new(get_test_file(file_name)).unwrap();
+    let row_group_reader = file_reader.get_row_group(0).unwrap();
+    let descr = row_group_reader.metadata().column(i).column_descr_ptr();
+    let reader = row_group_reader.get_column_reader(i).unwrap();
+    ColumnValueDecoder::new(descr, reader, batch_size)
+      .collect::<Result<Vec<_>>>()
+      .unwrap()
+  }
+
+  #[test]
+  fn test_column_value_decoder_optional_column() {
+    // nested_struct.A column
+    let expected = vec![
+      (Field::Int(1), 2, 0),
+      (Field::Null, 1, 0),
+      (Field::Null, 1, 0),
+      (Field::Null, 1, 0),
+      (Field::Null, 1, 0),
+      (Field::Null, 0, 0),
+      (Field::Int(7), 2, 0)
+    ];
+    let file_reader =
+      SerializedFileReader::new(get_test_file("nullable.impala.parquet")).unwrap();
+    let file_metadata = file_reader.metadata().file_metadata();
+    let schema = file_metadata.schema_descr();
+    let index = (0..schema.num_columns())
+      .find(|&i| schema.column(i).path().string() == "nested_struct.A")
+      .unwrap();
+
+    for batch_size in vec![1, 2, 3, 128] {
+      let values = decode_column("nullable.impala.parquet", index, batch_size)
+        .into_iter()
+        .map(|v| (v.value().clone(), v.def_level(), v.rep_level()))
+        .collect::<Vec<_>>();
+      assert_eq!(values, expected);
+    }
+    let nulls = decode_column("nullable.impala.parquet", index, 2)
+      .iter()
+      .filter(|v| v.is_null())
+      .count();
+    assert_eq!(nulls, 5);
+  }
+
+  #[test]
+  fn test_column_value_decoder_all_columns() {
+    let file_reader =
+      SerializedFileReader::new(get_test_file("alltypes_plain.parquet")).unwrap();
+    let row_group_reader = file_reader.get_row_group(0).unwrap();
+    for i in 0..row_group_reader.num_columns() {
+      let decoder = ColumnValueDecoder::from_row_group(&*row_group_reader, i).unwrap();
+      assert_eq!(decoder.column_descr().path(),
+        row_group_reader.metadata().column(i).column_path());
+      let values = decoder.collect::<Result<Vec<_>>>().unwrap();
+      assert_eq!(values.len(), 8);
+      assert!(values.iter().all(|v| !v.is_null() && v.rep_level() == 0));
+    }
+
+    let id = ColumnValueDecoder::from_row_group(&*row_group_reader, 0)
+      .unwrap()
+      .map(|v| v.unwrap().into_value())
+      .collect::<Vec<_>>();
+    assert_eq!(id, vec![
+      Field::Int(4), Field::Int(5), Field::Int(6), Field::Int(7),
+      Field::Int(2), Field::Int(3), Field::Int(0), Field::Int(1)
+    ]);
+  }
+
+  #[test]
+  fn test_column_value_decoder_invalid_column() {
+    let file_reader =
+      SerializedFileReader::new(get_test_file("alltypes_plain.parquet")).unwrap();
+    let row_group_reader = file_reader.get_row_group(0).unwrap();
+    let res = ColumnValueDecoder::from_row_group(&*row_group_reader, 11);
+    assert_eq!(
+      res.err().unwrap(),
+      general_err!("Column index 11 is out of bound, row group has 11 columns")
+    );
+  }
+}
@@ -0,0 +1,144 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains row-level predicate pushdown for the record reader.
+//!
+//! [`RowFilter`] evaluates a predicate on a projection that usually contains only a few
+//! columns of the file. Result is a [`RowSelection`](`::column::selection::RowSelection`)
+//! of matching rows, which is then used to read the rest of the projected columns, so
+//! values of non-matching rows are not assembled and, where possible, not decoded.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//! use parquet::record::RowAccessor;
+//! use parquet::record::filter::RowFilter;
+//! use parquet::schema::parser::parse_message_type;
+//!
+//! let file = File::open("data/alltypes_plain.parquet").unwrap();
+//! let reader = SerializedFileReader::new(file).unwrap();
+//!
+//! let projection = parse_message_type("message schema { OPTIONAL INT32 id; }").unwrap();
+//! let filter = RowFilter::new(projection, |row| Ok(row.get_int(0)? % 2 == 0));
+//! let iter = reader.get_row_iter(None).unwrap().with_row_filter(filter);
+//! assert_eq!(iter.count(), 4);
+//! ```
+
+use std::rc::Rc;
+
+use column::selection::{RowSelection, RowSelector};
+use errors::{ParquetError, Result};
+use file::reader::RowGroupReader;
+use record::api::Row;
+use record::reader::TreeBuilder;
+use schema::types::{SchemaDescPtr, SchemaDescriptor, Type};
+
+/// Predicate on rows of a projection, see module documentation for more details.
+pub struct RowFilter {
+  descr: SchemaDescPtr,
+  predicate: Box<Fn(&Row) -> Result<bool>>
+}
+
+impl RowFilter {
+  /// Creates new filter that evaluates `predicate` on rows of `projection`.
+  /// Projection must be a part of the file schema.
+  pub fn new<F>(projection: Type, predicate: F) -> Self
+    where F: 'static + Fn(&Row) -> Result<bool> {
+    Self {
+      descr: Rc::new(SchemaDescriptor::new(Rc::new(projection))),
+      predicate: Box::new(predicate)
+    }
+  }
+
+  /// Returns projection used to evaluate predicate.
+  pub fn projection(&self) -> &Type {
+    self.descr.root_schema()
+  }
+
+  /// Evaluates predicate for all rows of a row group.
+  /// Returns selection of rows that match predicate.
+  pub fn evaluate(&self, row_group_reader: &RowGroupReader) -> Result<RowSelection> {
+    let metadata = row_group_reader.metadata();
+    if !metadata.schema_descr().root_schema().check_contains(self.projection()) {
+      return Err(general_err!("Root schema does not contain projection"));
+    }
+
+    let iter = TreeBuilder::new().as_iter(self.descr.clone(), row_group_reader);
+    let mut selectors = Vec::new();
+    for row in iter {
+      if (self.predicate)(&row?)? {
+        selectors.push(RowSelector::select(1));
+      } else {
+        selectors.push(RowSelector::skip(1));
+      }
+    }
+    Ok(RowSelection::from_selectors(selectors))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use file::reader::{FileReader, SerializedFileReader};
+  use record::RowAccessor;
+  use schema::parser::parse_message_type;
+  use util::test_common::get_test_file;
+
+  fn id_projection() -> Type {
+    parse_message_type("message schema { OPTIONAL INT32 id; }").unwrap()
+  }
+
+  #[test]
+  fn test_row_filter_evaluate() {
+    let file_reader =
+      SerializedFileReader::new(get_test_file("alltypes_plain.parquet")).unwrap();
+    let row_group_reader = file_reader.get_row_group(0).unwrap();
+
+    // Values of `id` column: 4, 5, 6, 7, 2, 3, 0, 1
+    let filter = RowFilter::new(id_projection(), |row| Ok(row.get_int(0)? >= 4));
+    let selection = filter.evaluate(&*row_group_reader).unwrap();
+    assert_eq!(selection.to_ranges(), vec![0..4]);
+    assert_eq!(selection.total_row_count(), 8);
+
+    let filter = RowFilter::new(id_projection(), |row| Ok(row.get_int(0)? % 3 == 0));
+    let selection = filter.evaluate(&*row_group_reader).unwrap();
+    assert_eq!(selection.to_ranges(), vec![2..3, 5..7]);
+  }
+
+  #[test]
+  fn test_row_filter_evaluate_error() {
+    let file_reader =
+      SerializedFileReader::new(get_test_file("alltypes_plain.parquet")).unwrap();
+    let row_group_reader = file_reader.get_row_group(0).unwrap();
+
+    let filter = RowFilter::new(id_projection(), |_| Err(general_err!("Test error")));
+    assert_eq!(
+      filter.evaluate(&*row_group_reader),
+      Err(general_err!("Test error"))
+    );
+
+    let projection = parse_message_type("message schema { OPTIONAL INT32 b; }").unwrap();
+    let filter = RowFilter::new(projection, |_| Ok(true));
+    assert_eq!(
+      filter.evaluate(&*row_group_reader),
+      Err(general_err!("Root schema does not contain projection"))
+    );
+  }
+}
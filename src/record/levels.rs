@@ -0,0 +1,518 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains computation of definition and repetition levels of leaf columns from
+//! nested records, i.e. record shredding, which is the counterpart of record assembly
+//! in [`reader`](../reader/index.html).
+//!
+//! [`LevelBuilder`](struct.LevelBuilder.html) collects levels and non-null values of a
+//! single leaf column and validates them against max levels of the column, so that it
+//! can be used by writers of any nested representation.
+//! [`shred_rows`](fn.shred_rows.html) shreds rows of the record API with level builders,
+//! interpreting `LIST` and `MAP` groups in the same way as the record reader.
+
+use basic::{LogicalType, Repetition};
+use errors::{ParquetError, Result};
+use record::api::{list_elements, map_entries, row_field, Field, Row};
+use record::reader::Reader;
+use schema::types::{ColumnDescPtr, SchemaDescriptor, Type};
+
+/// Builder of definition and repetition levels and non-null values of a leaf column.
+///
+/// Every slot of the column is either a non-null value, which is defined at the max
+/// definition level, or a null or an empty list at a lower definition level. Levels are
+/// validated against max levels of the column when they are added.
+pub struct LevelBuilder<V> {
+  descr: ColumnDescPtr,
+  def_levels: Vec<i16>,
+  rep_levels: Vec<i16>,
+  values: Vec<V>
+}
+
+impl<V> LevelBuilder<V> {
+  /// Creates new level builder for column `descr`.
+  pub fn new(descr: ColumnDescPtr) -> Self {
+    Self { descr: descr, def_levels: vec![], rep_levels: vec![], values: vec![] }
+  }
+
+  /// Returns column descriptor of this level builder.
+  pub fn column_descr(&self) -> &ColumnDescPtr {
+    &self.descr
+  }
+
+  /// Appends non-null `value` at repetition level `rep_level`.
+  pub fn push_value(&mut self, value: V, rep_level: i16) -> Result<()> {
+    let max_def_level = self.descr.max_def_level();
+    self.push_levels(max_def_level, rep_level)?;
+    self.values.push(value);
+    Ok(())
+  }
+
+  /// Appends null value or empty list at definition level `def_level` and repetition
+  /// level `rep_level`. Definition level must be less than the max definition level.
+  pub fn push_null(&mut self, def_level: i16, rep_level: i16) -> Result<()> {
+    if def_level < 0 || def_level >= self.descr.max_def_level() {
+      return Err(general_err!(
+        "Invalid definition level {} of null value in column {}, max definition \
+         level: {}",
+        def_level,
+        self.descr.path(),
+        self.descr.max_def_level()
+      ));
+    }
+    self.push_levels(def_level, rep_level)
+  }
+
+  /// Validates `rep_level` and appends levels of a slot.
+  fn push_levels(&mut self, def_level: i16, rep_level: i16) -> Result<()> {
+    if rep_level < 0 || rep_level > self.descr.max_rep_level() {
+      return Err(general_err!(
+        "Invalid repetition level {} in column {}, max repetition level: {}",
+        rep_level,
+        self.descr.path(),
+        self.descr.max_rep_level()
+      ));
+    }
+    if rep_level > 0 && self.def_levels.is_empty() {
+      return Err(general_err!(
+        "First value of column {} must start a record, found repetition level {}",
+        self.descr.path(),
+        rep_level
+      ));
+    }
+    self.def_levels.push(def_level);
+    self.rep_levels.push(rep_level);
+    Ok(())
+  }
+
+  /// Returns number of slots, i.e. number of levels.
+  pub fn num_levels(&self) -> usize {
+    self.def_levels.len()
+  }
+
+  /// Returns number of records, i.e. number of slots at repetition level 0.
+  pub fn num_records(&self) -> usize {
+    self.rep_levels.iter().filter(|level| **level == 0).count()
+  }
+
+  /// Returns definition levels, or `None` if the column is required and levels are not
+  /// written.
+  pub fn def_levels(&self) -> Option<&[i16]> {
+    if self.descr.max_def_level() > 0 { Some(&self.def_levels) } else { None }
+  }
+
+  /// Returns repetition levels, or `None` if the column is not repeated and levels are
+  /// not written.
+  pub fn rep_levels(&self) -> Option<&[i16]> {
+    if self.descr.max_rep_level() > 0 { Some(&self.rep_levels) } else { None }
+  }
+
+  /// Returns non-null values.
+  pub fn values(&self) -> &[V] {
+    &self.values
+  }
+
+  /// Removes all levels and values, keeping the column descriptor.
+  pub fn clear(&mut self) {
+    self.def_levels.clear();
+    self.rep_levels.clear();
+    self.values.clear();
+  }
+}
+
+/// Shreds `rows` with `schema` into levels and non-null leaf values of every column of
+/// the schema, in the order of columns.
+///
+/// Fields of rows are matched to fields of the schema by position. Lists are expected
+/// for `LIST` groups and repeated fields, maps are expected for `MAP` groups.
+pub fn shred_rows(
+  schema: &SchemaDescriptor,
+  rows: &[Row]
+) -> Result<Vec<LevelBuilder<Field>>> {
+  let mut columns = schema
+    .columns()
+    .iter()
+    .map(|descr| LevelBuilder::new(descr.clone()))
+    .collect::<Vec<_>>();
+  for row in rows {
+    shred_row(schema, row, &mut columns)?;
+  }
+  Ok(columns)
+}
+
+/// Shreds a single `row` with `schema`, appending levels and values to `columns`, which
+/// must contain a level builder for every column of the schema.
+pub fn shred_row(
+  schema: &SchemaDescriptor,
+  row: &Row,
+  columns: &mut [LevelBuilder<Field>]
+) -> Result<()> {
+  if columns.len() != schema.num_columns() {
+    return Err(general_err!(
+      "Expected {} columns, found {}",
+      schema.num_columns(),
+      columns.len()
+    ));
+  }
+  shred_group(schema.root_schema(), row, 0, 0, 0, columns)
+}
+
+/// Shreds `value` of field `tpe` according to its repetition. `def_level` is the
+/// definition level of the parent, `rep_level` is the repetition level of the first
+/// slot and `depth` is the repetition level of the parent. `columns` are level builders
+/// of leaves of the field.
+fn shred_field(
+  tpe: &Type,
+  value: &Field,
+  def_level: i16,
+  rep_level: i16,
+  depth: i16,
+  columns: &mut [LevelBuilder<Field>]
+) -> Result<()> {
+  match (tpe.get_basic_info().repetition(), value) {
+    (Repetition::REQUIRED, &Field::Null) => {
+      Err(general_err!("Required field {} is null", tpe.name()))
+    },
+    (Repetition::REQUIRED, _) => {
+      shred_value(tpe, value, def_level, rep_level, depth, columns)
+    },
+    (Repetition::OPTIONAL, &Field::Null) => push_nulls(columns, def_level, rep_level),
+    (Repetition::OPTIONAL, _) => {
+      shred_value(tpe, value, def_level + 1, rep_level, depth, columns)
+    },
+    // Repeated field without `LIST` or `MAP` parent is a list of required elements
+    (Repetition::REPEATED, &Field::ListInternal(ref list)) => {
+      let elements = list_elements(list);
+      if elements.is_empty() {
+        return push_nulls(columns, def_level, rep_level);
+      }
+      for (i, element) in elements.iter().enumerate() {
+        let rep_level = if i == 0 { rep_level } else { depth + 1 };
+        shred_value(tpe, element, def_level + 1, rep_level, depth + 1, columns)?;
+      }
+      Ok(())
+    },
+    (Repetition::REPEATED, _) => {
+      Err(general_err!("Repeated field {} is not a list", tpe.name()))
+    }
+  }
+}
+
+/// Shreds non-null `value` of field `tpe` at definition level `def_level`, which
+/// includes the field itself.
+fn shred_value(
+  tpe: &Type,
+  value: &Field,
+  def_level: i16,
+  rep_level: i16,
+  depth: i16,
+  columns: &mut [LevelBuilder<Field>]
+) -> Result<()> {
+  if tpe.is_primitive() {
+    return columns[0].push_value(value.clone(), rep_level);
+  }
+  match (tpe.get_basic_info().logical_type(), value) {
+    (LogicalType::LIST, &Field::ListInternal(ref list)) => {
+      let elements = list_elements(list);
+      if elements.is_empty() {
+        return push_nulls(columns, def_level, rep_level);
+      }
+      let repeated_type = &tpe.get_fields()[0];
+      let is_element_type = Reader::is_element_type(repeated_type);
+      for (i, element) in elements.iter().enumerate() {
+        let rep_level = if i == 0 { rep_level } else { depth + 1 };
+        let def_level = def_level + 1;
+        if is_element_type {
+          // Backward compatible list, the repeated field is the element
+          shred_value(repeated_type, element, def_level, rep_level, depth + 1, columns)?;
+        } else {
+          let element_type = &repeated_type.get_fields()[0];
+          shred_field(element_type, element, def_level, rep_level, depth + 1, columns)?;
+        }
+      }
+      Ok(())
+    },
+    (LogicalType::MAP, &Field::MapInternal(ref map)) |
+    (LogicalType::MAP_KEY_VALUE, &Field::MapInternal(ref map)) => {
+      let entries = map_entries(map);
+      if entries.is_empty() {
+        return push_nulls(columns, def_level, rep_level);
+      }
+      let key_value_type = &tpe.get_fields()[0];
+      let key_type = &key_value_type.get_fields()[0];
+      let value_type = &key_value_type.get_fields()[1];
+      let (key_columns, value_columns) = columns.split_at_mut(num_leaves(key_type));
+      for (i, &(ref key, ref value)) in entries.iter().enumerate() {
+        let rep_level = if i == 0 { rep_level } else { depth + 1 };
+        let def_level = def_level + 1;
+        shred_field(key_type, key, def_level, rep_level, depth + 1, key_columns)?;
+        shred_field(value_type, value, def_level, rep_level, depth + 1, value_columns)?;
+      }
+      Ok(())
+    },
+    (LogicalType::LIST, _) => Err(general_err!("Field {} is not a list", tpe.name())),
+    (LogicalType::MAP, _) | (LogicalType::MAP_KEY_VALUE, _) => {
+      Err(general_err!("Field {} is not a map", tpe.name()))
+    },
+    (_, &Field::Group(ref row)) => {
+      shred_group(tpe, row, def_level, rep_level, depth, columns)
+    },
+    _ => Err(general_err!("Field {} is not a group", tpe.name()))
+  }
+}
+
+/// Shreds fields of `row` with fields of group `tpe`.
+fn shred_group(
+  tpe: &Type,
+  row: &Row,
+  def_level: i16,
+  rep_level: i16,
+  depth: i16,
+  columns: &mut [LevelBuilder<Field>]
+) -> Result<()> {
+  let fields = tpe.get_fields();
+  if row.len() != fields.len() {
+    return Err(general_err!(
+      "Group {} has {} fields, found {}",
+      tpe.name(),
+      fields.len(),
+      row.len()
+    ));
+  }
+  let mut offset = 0;
+  for (i, field) in fields.iter().enumerate() {
+    let num_columns = num_leaves(field);
+    let field_columns = &mut columns[offset..offset + num_columns];
+    shred_field(field, row_field(row, i), def_level, rep_level, depth, field_columns)?;
+    offset += num_columns;
+  }
+  Ok(())
+}
+
+/// Appends null slot at `def_level` and `rep_level` to every column of `columns`.
+fn push_nulls(
+  columns: &mut [LevelBuilder<Field>],
+  def_level: i16,
+  rep_level: i16
+) -> Result<()> {
+  for column in columns.iter_mut() {
+    column.push_null(def_level, rep_level)?;
+  }
+  Ok(())
+}
+
+/// Returns number of leaf columns of `tpe`.
+fn num_leaves(tpe: &Type) -> usize {
+  if tpe.is_primitive() {
+    1
+  } else {
+    tpe.get_fields().iter().map(|field| num_leaves(field)).sum()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::rc::Rc;
+
+  use record::api::{make_list, make_map, make_row};
+  use schema::parser::parse_message_type;
+
+  fn schema_descr(message_type: &str) -> SchemaDescriptor {
+    SchemaDescriptor::new(Rc::new(parse_message_type(message_type).unwrap()))
+  }
+
+  fn str_field(value: &str) -> Field {
+    Field::Str(value.to_owned())
+  }
+
+  fn list(elements: Vec<Field>) -> Field {
+    Field::ListInternal(make_list(elements))
+  }
+
+  fn map(entries: Vec<(Field, Field)>) -> Field {
+    Field::MapInternal(make_map(entries))
+  }
+
+  #[test]
+  fn test_shred_rows() {
+    let schema = schema_descr("
+      message schema {
+        REQUIRED INT64 id;
+        OPTIONAL group tags (LIST) {
+          REPEATED group list {
+            OPTIONAL BYTE_ARRAY element (UTF8);
+          }
+        }
+        OPTIONAL group props (MAP) {
+          REPEATED group key_value {
+            REQUIRED BYTE_ARRAY key (UTF8);
+            OPTIONAL INT32 value;
+          }
+        }
+        REPEATED INT32 scores;
+        OPTIONAL group info {
+          OPTIONAL INT32 a;
+        }
+      }
+    ");
+    let rows = vec![
+      make_row(vec![
+        ("id".to_owned(), Field::Long(1)),
+        ("tags".to_owned(), list(vec![str_field("a"), Field::Null])),
+        ("props".to_owned(), map(vec![(str_field("x"), Field::Int(1))])),
+        ("scores".to_owned(), list(vec![Field::Int(1), Field::Int(2)])),
+        ("info".to_owned(), Field::Group(make_row(vec![("a".to_owned(), Field::Null)])))
+      ]),
+      make_row(vec![
+        ("id".to_owned(), Field::Long(2)),
+        ("tags".to_owned(), Field::Null),
+        ("props".to_owned(), map(vec![])),
+        ("scores".to_owned(), list(vec![])),
+        ("info".to_owned(), Field::Null)
+      ])
+    ];
+
+    let columns = shred_rows(&schema, &rows).unwrap();
+    assert_eq!(columns.len(), 6);
+
+    assert_eq!(columns[0].values(), &[Field::Long(1), Field::Long(2)]);
+    assert_eq!(columns[0].def_levels(), None);
+    assert_eq!(columns[0].rep_levels(), None);
+
+    assert_eq!(columns[1].values(), &[str_field("a")]);
+    assert_eq!(columns[1].def_levels(), Some(&[3, 2, 0][..]));
+    assert_eq!(columns[1].rep_levels(), Some(&[0, 1, 0][..]));
+    assert_eq!(columns[1].num_levels(), 3);
+    assert_eq!(columns[1].num_records(), 2);
+
+    assert_eq!(columns[2].values(), &[str_field("x")]);
+    assert_eq!(columns[2].def_levels(), Some(&[2, 1][..]));
+    assert_eq!(columns[2].rep_levels(), Some(&[0, 0][..]));
+    assert_eq!(columns[3].values(), &[Field::Int(1)]);
+    assert_eq!(columns[3].def_levels(), Some(&[3, 1][..]));
+
+    assert_eq!(columns[4].values(), &[Field::Int(1), Field::Int(2)]);
+    assert_eq!(columns[4].def_levels(), Some(&[1, 1, 0][..]));
+    assert_eq!(columns[4].rep_levels(), Some(&[0, 1, 0][..]));
+
+    assert!(columns[5].values().is_empty());
+    assert_eq!(columns[5].def_levels(), Some(&[1, 0][..]));
+    assert_eq!(columns[5].rep_levels(), None);
+  }
+
+  #[test]
+  fn test_shred_rows_nested_lists() {
+    // List of backward compatible lists of required elements
+    let schema = schema_descr("
+      message schema {
+        REQUIRED group matrix (LIST) {
+          REPEATED group list {
+            REQUIRED group element (LIST) {
+              REPEATED INT32 element;
+            }
+          }
+        }
+      }
+    ");
+    let matrix = list(vec![
+      list(vec![Field::Int(1), Field::Int(2)]),
+      list(vec![]),
+      list(vec![Field::Int(3)])
+    ]);
+    let row = make_row(vec![("matrix".to_owned(), matrix)]);
+
+    let columns = shred_rows(&schema, &[row]).unwrap();
+    assert_eq!(columns[0].values(), &[Field::Int(1), Field::Int(2), Field::Int(3)]);
+    assert_eq!(columns[0].def_levels(), Some(&[2, 2, 1, 2][..]));
+    assert_eq!(columns[0].rep_levels(), Some(&[0, 2, 1, 1][..]));
+  }
+
+  #[test]
+  fn test_shred_rows_errors() {
+    let schema = schema_descr("
+      message schema {
+        REQUIRED INT32 a;
+        REPEATED INT32 b;
+      }
+    ");
+    let check_error = |fields: Vec<(&str, Field)>, message: &str| {
+      let row = make_row(
+        fields.into_iter().map(|(name, field)| (name.to_owned(), field)).collect()
+      );
+      assert_eq!(shred_rows(&schema, &[row]).err().unwrap(), general_err!(message));
+    };
+    check_error(
+      vec![("a", Field::Null), ("b", list(vec![]))],
+      "Required field a is null"
+    );
+    check_error(
+      vec![("a", Field::Int(1)), ("b", Field::Int(2))],
+      "Repeated field b is not a list"
+    );
+    check_error(vec![("a", Field::Int(1))], "Group schema has 2 fields, found 1");
+
+    let mut columns = vec![LevelBuilder::new(schema.column(0))];
+    let row =
+      make_row(vec![("a".to_owned(), Field::Int(1)), ("b".to_owned(), list(vec![]))]);
+    assert_eq!(
+      shred_row(&schema, &row, &mut columns).unwrap_err(),
+      general_err!("Expected 2 columns, found 1")
+    );
+  }
+
+  #[test]
+  fn test_level_builder_validation() {
+    let schema = schema_descr("
+      message schema {
+        OPTIONAL group a {
+          REPEATED INT32 b;
+        }
+      }
+    ");
+    let mut builder = LevelBuilder::new(schema.column(0));
+    assert_eq!(
+      builder.push_value(1, 1).unwrap_err(),
+      general_err!(
+        "First value of column \"a.b\" must start a record, found repetition level 1"
+      )
+    );
+    builder.push_value(1, 0).unwrap();
+    builder.push_null(1, 0).unwrap();
+    assert_eq!(
+      builder.push_null(2, 0).unwrap_err(),
+      general_err!(
+        "Invalid definition level 2 of null value in column \"a.b\", max definition \
+         level: 2"
+      )
+    );
+    assert_eq!(
+      builder.push_value(3, 2).unwrap_err(),
+      general_err!(
+        "Invalid repetition level 2 in column \"a.b\", max repetition level: 1"
+      )
+    );
+    assert_eq!(builder.values(), &[1]);
+    assert_eq!(builder.def_levels(), Some(&[2, 1][..]));
+    assert_eq!(builder.rep_levels(), Some(&[0, 0][..]));
+
+    builder.clear();
+    assert_eq!(builder.num_levels(), 0);
+    assert!(builder.values().is_empty());
+  }
+}
@@ -0,0 +1,549 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains k-way merge of rows of several files sorted by the same columns.
+//!
+//! [`SortedMergeReader`] reads rows of each file in order and returns the smallest
+//! row among the next rows of all files, so the result is sorted when each file is
+//! sorted, e.g. to compact many small sorted files into one. Rows that compare as
+//! equal are returned in order of files. Sorting columns are usually the ones declared
+//! in metadata of row groups, see [`declared_sorting_columns`].
+//!
+//! Only top-level primitive columns can be sorting columns. Values are compared
+//! according to their logical type, strings and binary values are compared byte-wise,
+//! and `NaN` values are equal to any other value.
+//!
+//! Rows can be written into a new file without converting them back into values with
+//! [`merge_sorted_files`](`::file::rewrite::merge_sorted_files`), which copies columns
+//! of each file in the merged order.
+
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use basic::LogicalType;
+use data_type::Decimal;
+use errors::{ParquetError, Result};
+use file::metadata::SortingColumn;
+use file::reader::FileReader;
+use record::api::{row_field, Field, Row};
+use record::reader::{ReaderIter, TreeBuilder};
+use schema::types::{SchemaDescPtr, SchemaDescriptor, Type, TypePtr};
+
+/// Returns sorting columns declared in metadata of all row groups of `readers`.
+/// Returns error if a row group does not declare sorting columns, or if sorting columns
+/// of row groups differ.
+pub fn declared_sorting_columns(
+  readers: &[Rc<FileReader>]
+) -> Result<Vec<SortingColumn>> {
+  let metadata = readers.iter().map(|reader| reader.metadata()).collect::<Vec<_>>();
+  let mut result: Option<&Vec<SortingColumn>> = None;
+  for (i, metadata) in metadata.iter().enumerate() {
+    for (j, row_group) in metadata.row_groups().iter().enumerate() {
+      let sorting_columns = match row_group.sorting_columns() {
+        Some(sorting_columns) => sorting_columns,
+        None => {
+          return Err(general_err!(
+            "Row group {} of file {} does not declare sorting columns",
+            j,
+            i
+          ));
+        }
+      };
+      if result.map_or(false, |result| result != sorting_columns) {
+        return Err(general_err!(
+          "Sorting columns of row group {} of file {} differ from other row groups",
+          j,
+          i
+        ));
+      }
+      result = Some(sorting_columns);
+    }
+  }
+  match result {
+    Some(result) => Ok(result.clone()),
+    None => Err(general_err!("Files do not have any row groups with sorting columns"))
+  }
+}
+
+/// Returns projection of `schema` with top-level fields of `sorting_columns`, so that
+/// only values of sorting columns are read to merge rows. Returns error if a sorting
+/// column does not exist or is not supported.
+pub fn sorting_projection(
+  schema: &SchemaDescriptor,
+  sorting_columns: &[SortingColumn]
+) -> Result<Type> {
+  let mut fields = Vec::with_capacity(sorting_columns.len());
+  for field in sorting_fields(schema, sorting_columns)? {
+    if !fields.iter().any(|existing: &TypePtr| existing.name() == field.name()) {
+      fields.push(field);
+    }
+  }
+  Type::group_type_builder(schema.name()).with_fields(&mut fields).build()
+}
+
+/// Returns top-level field of each sorting column, see [`sorting_projection`].
+fn sorting_fields(
+  schema: &SchemaDescriptor,
+  sorting_columns: &[SortingColumn]
+) -> Result<Vec<TypePtr>> {
+  let mut fields = Vec::with_capacity(sorting_columns.len());
+  for sorting_column in sorting_columns {
+    let i = sorting_column.column_idx;
+    if i < 0 || i as usize >= schema.num_columns() {
+      return Err(general_err!("Invalid sorting column index {}", i));
+    }
+    let descr = schema.column(i as usize);
+    let root = schema.get_column_root(i as usize);
+    if !root.is_primitive() {
+      return Err(general_err!(
+        "Cannot merge rows by column {}, column is not a top-level primitive field",
+        descr.path().string()
+      ));
+    }
+    if descr.logical_type() == LogicalType::INTERVAL {
+      return Err(general_err!(
+        "Cannot merge rows by column {} of INTERVAL type",
+        descr.path().string()
+      ));
+    }
+    let field = schema.root_schema().get_fields().iter()
+      .find(|field| field.name() == root.name())
+      .expect("Column root is a field of root schema");
+    fields.push(field.clone());
+  }
+  Ok(fields)
+}
+
+/// Sorting column resolved to a field of merged rows.
+struct SortKey {
+  field_index: usize,
+  descending: bool,
+  nulls_first: bool
+}
+
+/// Next rows of a file.
+struct MergeSource {
+  next_row_group: usize,
+  row_iter: Option<ReaderIter>,
+  // Next row of the file to merge, `None` if file is depleted
+  head: Option<Row>
+}
+
+/// Iterator of rows of several sorted files in the merged order, see module
+/// documentation for more details.
+pub struct SortedMergeReader {
+  readers: Vec<Rc<FileReader>>,
+  descr: SchemaDescPtr,
+  sorting_columns: Vec<SortingColumn>,
+  sort_keys: Vec<SortKey>,
+  tree_builder: TreeBuilder,
+  sources: Vec<MergeSource>,
+  is_started: bool
+}
+
+impl SortedMergeReader {
+  /// Creates iterator of rows of `readers` merged by sorting columns declared in their
+  /// metadata, see [`declared_sorting_columns`]. Rows contain fields of `projection`,
+  /// or all fields of the files if it is `None`.
+  pub fn new(readers: Vec<Rc<FileReader>>, projection: Option<Type>) -> Result<Self> {
+    let sorting_columns = declared_sorting_columns(&readers)?;
+    Self::with_sorting_columns(readers, projection, sorting_columns)
+  }
+
+  /// Creates iterator of rows of `readers` merged by `sorting_columns`, which are
+  /// leaf column indexes of the file schema in order of priority. Rows contain fields
+  /// of `projection`, or all fields of the files if it is `None`; fields of sorting
+  /// columns must be a part of the projection.
+  ///
+  /// Returns error if there are no readers, if schemas of the files differ, or if a
+  /// sorting column is not supported.
+  pub fn with_sorting_columns(
+    readers: Vec<Rc<FileReader>>,
+    projection: Option<Type>,
+    sorting_columns: Vec<SortingColumn>
+  ) -> Result<Self> {
+    let schema_descr = match readers.first() {
+      Some(reader) => reader.metadata().file_metadata().schema_descr_ptr(),
+      None => return Err(general_err!("At least one file is required to merge rows"))
+    };
+    for reader in &readers[1..] {
+      schema_descr.check_compatible(reader.metadata().file_metadata().schema_descr())?;
+    }
+    let descr = match projection {
+      Some(projection) => {
+        if !schema_descr.root_schema().check_contains(&projection) {
+          return Err(general_err!("Root schema does not contain projection"));
+        }
+        Rc::new(SchemaDescriptor::new(Rc::new(projection)))
+      },
+      None => schema_descr.clone()
+    };
+
+    let fields = sorting_fields(&schema_descr, &sorting_columns)?;
+    let mut sort_keys = Vec::with_capacity(fields.len());
+    for (field, sorting_column) in fields.iter().zip(sorting_columns.iter()) {
+      let field_index = descr.root_schema().get_fields().iter()
+        .position(|projected| projected.name() == field.name());
+      let field_index = match field_index {
+        Some(field_index) => field_index,
+        None => {
+          return Err(general_err!(
+            "Field {} of sorting column is not in projection",
+            field.name()
+          ));
+        }
+      };
+      sort_keys.push(SortKey {
+        field_index: field_index,
+        descending: sorting_column.descending,
+        nulls_first: sorting_column.nulls_first
+      });
+    }
+
+    let sources = readers.iter()
+      .map(|_| MergeSource { next_row_group: 0, row_iter: None, head: None })
+      .collect();
+    Ok(Self {
+      readers: readers,
+      descr: descr,
+      sorting_columns: sorting_columns,
+      sort_keys: sort_keys,
+      tree_builder: TreeBuilder::new(),
+      sources: sources,
+      is_started: false
+    })
+  }
+
+  /// Returns sorting columns that rows are merged by.
+  pub fn sorting_columns(&self) -> &[SortingColumn] {
+    &self.sorting_columns
+  }
+
+  /// Returns next row in the merged order together with index of the file it comes
+  /// from, or `None` if rows of all files have been returned.
+  pub fn next_with_source(&mut self) -> Result<Option<(usize, Row)>> {
+    if !self.is_started {
+      for i in 0..self.sources.len() {
+        self.sources[i].head = self.read_row(i)?;
+      }
+      self.is_started = true;
+    }
+
+    let mut min: Option<usize> = None;
+    for i in 0..self.sources.len() {
+      if let Some(ref row) = self.sources[i].head {
+        let is_less = match min {
+          Some(j) => {
+            let min_row = self.sources[j].head.as_ref().expect("Row is buffered");
+            compare_rows(&self.sort_keys, row, min_row) == Ordering::Less
+          },
+          None => true
+        };
+        if is_less {
+          min = Some(i);
+        }
+      }
+    }
+    let i = match min {
+      Some(i) => i,
+      None => return Ok(None)
+    };
+    let row = self.sources[i].head.take().expect("Row is buffered");
+    self.sources[i].head = self.read_row(i)?;
+    Ok(Some((i, row)))
+  }
+
+  /// Returns next row of file `i`, or `None` if file is depleted.
+  fn read_row(&mut self, i: usize) -> Result<Option<Row>> {
+    let reader = &self.readers[i];
+    let source = &mut self.sources[i];
+    loop {
+      if let Some(ref mut iter) = source.row_iter {
+        if let Some(row) = iter.next() {
+          return row.map(Some);
+        }
+      }
+      if source.next_row_group >= reader.num_row_groups() {
+        source.row_iter = None;
+        return Ok(None);
+      }
+      let row_group_reader = reader.get_row_group(source.next_row_group)?;
+      source.next_row_group += 1;
+      let iter = self.tree_builder.as_iter(self.descr.clone(), &*row_group_reader);
+      source.row_iter = Some(iter);
+    }
+  }
+}
+
+impl Iterator for SortedMergeReader {
+  type Item = Result<Row>;
+
+  fn next(&mut self) -> Option<Result<Row>> {
+    match self.next_with_source() {
+      Ok(Some((_, row))) => Some(Ok(row)),
+      Ok(None) => None,
+      Err(e) => Some(Err(e))
+    }
+  }
+}
+
+/// Compares rows `a` and `b` by `sort_keys`.
+fn compare_rows(sort_keys: &[SortKey], a: &Row, b: &Row) -> Ordering {
+  for key in sort_keys {
+    let a = row_field(a, key.field_index);
+    let b = row_field(b, key.field_index);
+    let ordering = match (a, b) {
+      (&Field::Null, &Field::Null) => Ordering::Equal,
+      (&Field::Null, _) if key.nulls_first => Ordering::Less,
+      (&Field::Null, _) => Ordering::Greater,
+      (_, &Field::Null) if key.nulls_first => Ordering::Greater,
+      (_, &Field::Null) => Ordering::Less,
+      _ if key.descending => compare_values(a, b).reverse(),
+      _ => compare_values(a, b)
+    };
+    if ordering != Ordering::Equal {
+      return ordering;
+    }
+  }
+  Ordering::Equal
+}
+
+/// Compares non-null values `a` and `b` of the same column.
+fn compare_values(a: &Field, b: &Field) -> Ordering {
+  match (a, b) {
+    (&Field::Bool(a), &Field::Bool(b)) => a.cmp(&b),
+    (&Field::Byte(a), &Field::Byte(b)) => a.cmp(&b),
+    (&Field::Short(a), &Field::Short(b)) => a.cmp(&b),
+    (&Field::Int(a), &Field::Int(b)) => a.cmp(&b),
+    (&Field::Long(a), &Field::Long(b)) => a.cmp(&b),
+    (&Field::Date(a), &Field::Date(b)) => (a as i32).cmp(&(b as i32)),
+    (&Field::Time(a), &Field::Time(b)) => a.cmp(&b),
+    (&Field::Timestamp(a), &Field::Timestamp(b)) => a.cmp(&b),
+    (&Field::Float(a), &Field::Float(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+    (&Field::Double(a), &Field::Double(b)) => {
+      a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+    },
+    (&Field::Str(ref a), &Field::Str(ref b)) => a.cmp(b),
+    (&Field::Bytes(ref a), &Field::Bytes(ref b)) => a.data().cmp(b.data()),
+    (&Field::Decimal(ref a), &Field::Decimal(ref b)) => compare_decimals(a, b),
+    _ => Ordering::Equal
+  }
+}
+
+/// Compares unscaled values of decimals `a` and `b` with the same scale, which are
+/// big-endian two's complement numbers of any length.
+fn compare_decimals(a: &Decimal, b: &Decimal) -> Ordering {
+  let (a, b) = (a.data(), b.data());
+  let is_negative = |data: &[u8]| data.first().map_or(false, |byte| byte & 0x80 != 0);
+  match (is_negative(a), is_negative(b)) {
+    (true, false) => return Ordering::Less,
+    (false, true) => return Ordering::Greater,
+    _ => {}
+  }
+  // Values have the same sign, so they compare byte-wise after sign extension
+  let sign_byte = if is_negative(a) { 0xff } else { 0 };
+  let len = a.len().max(b.len());
+  let byte = |data: &[u8], i: usize| {
+    if i < len - data.len() { sign_byte } else { data[i - (len - data.len())] }
+  };
+  (0..len).map(|i| byte(a, i).cmp(&byte(b, i)))
+    .find(|ordering| *ordering != Ordering::Equal)
+    .unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use column::writer::{get_typed_column_writer, ColumnWriter};
+  use data_type::{ByteArray, Int32Type, Int64Type};
+  use file::properties::WriterProperties;
+  use file::reader::SerializedFileReader;
+  use file::writer::{write_to_bytes, FileWriter};
+  use record::RowAccessor;
+  use schema::parser::parse_message_type;
+  use util::test_common::{get_temp_file, get_test_file};
+
+  /// Returns reader of temp file `name` with rows `(id, value)` in one row group,
+  /// declaring that rows are sorted by `id`. Values are nulls when `None`.
+  fn sorted_file(name: &str, ids: &[i32], values: &[Option<i64>]) -> Rc<FileReader> {
+    let schema = Rc::new(parse_message_type("
+      message schema {
+        REQUIRED INT32 id;
+        OPTIONAL INT64 value;
+      }
+    ").unwrap());
+    let props = WriterProperties::builder()
+      .set_sorting_columns(Some(vec![SortingColumn::new(0, false, false)]))
+      .build();
+    let bytes = write_to_bytes(schema, Rc::new(props), |writer| {
+      let mut row_group_writer = writer.next_row_group()?;
+      let column_writer = row_group_writer.next_column()?.unwrap();
+      let mut typed = get_typed_column_writer::<Int32Type>(column_writer);
+      typed.write_batch(ids, None, None)?;
+      row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed))?;
+
+      let def_levels: Vec<i16> =
+        values.iter().map(|v| if v.is_some() { 1 } else { 0 }).collect();
+      let non_null: Vec<i64> = values.iter().filter_map(|v| *v).collect();
+      let column_writer = row_group_writer.next_column()?.unwrap();
+      let mut typed = get_typed_column_writer::<Int64Type>(column_writer);
+      typed.write_batch(&non_null, Some(&def_levels), None)?;
+      row_group_writer.close_column(ColumnWriter::Int64ColumnWriter(typed))?;
+      writer.close_row_group(row_group_writer)
+    }).unwrap();
+    Rc::new(SerializedFileReader::new(get_temp_file(name, &bytes)).unwrap())
+  }
+
+  #[test]
+  fn test_sorted_merge_reader() {
+    let readers = vec![
+      sorted_file("merge_a", &[1, 4, 4, 9], &[Some(10), Some(40), None, Some(90)]),
+      sorted_file("merge_b", &[2, 3, 4], &[Some(20), Some(30), Some(41)]),
+      sorted_file("merge_c", &[], &[])
+    ];
+    let mut merge_reader = SortedMergeReader::new(readers, None).unwrap();
+    assert_eq!(merge_reader.sorting_columns(), &[SortingColumn::new(0, false, false)]);
+
+    let mut rows = Vec::new();
+    while let Some((source, row)) = merge_reader.next_with_source().unwrap() {
+      rows.push((source, row.get_int(0).unwrap(), row.get_long(1).ok()));
+    }
+    // Equal rows are returned in order of files
+    assert_eq!(
+      rows,
+      vec![
+        (0, 1, Some(10)),
+        (1, 2, Some(20)),
+        (1, 3, Some(30)),
+        (0, 4, Some(40)),
+        (0, 4, None),
+        (1, 4, Some(41)),
+        (0, 9, Some(90))
+      ]
+    );
+  }
+
+  #[test]
+  fn test_sorted_merge_reader_sorting_columns() {
+    // Files sorted by `value` descending with nulls first, then by `id`
+    let readers = vec![
+      sorted_file("merge_sorting_a", &[5, 1, 2], &[None, Some(3), Some(1)]),
+      sorted_file("merge_sorting_b", &[3, 4, 6], &[None, Some(2), Some(2)])
+    ];
+    let sorting_columns =
+      vec![SortingColumn::new(1, true, true), SortingColumn::new(0, false, false)];
+    let projection = parse_message_type("
+      message schema {
+        OPTIONAL INT64 value;
+        REQUIRED INT32 id;
+      }
+    ").unwrap();
+    let merge_reader =
+      SortedMergeReader::with_sorting_columns(readers, Some(projection), sorting_columns)
+        .unwrap();
+    let ids: Vec<i32> =
+      merge_reader.map(|row| row.unwrap().get_int(1).unwrap()).collect();
+    assert_eq!(ids, vec![3, 5, 1, 4, 6, 2]);
+  }
+
+  #[test]
+  fn test_sorted_merge_reader_invalid() {
+    let check_error = |result: Result<SortedMergeReader>, message: &str| {
+      match result {
+        Ok(_) => panic!("Expected error: {}", message),
+        Err(e) => assert_eq!(e, general_err!(message))
+      }
+    };
+
+    let reader: Rc<FileReader> = Rc::new(
+      SerializedFileReader::new(get_test_file("alltypes_plain.parquet")).unwrap()
+    );
+    check_error(
+      SortedMergeReader::new(vec![reader.clone()], None),
+      "Row group 0 of file 0 does not declare sorting columns"
+    );
+    check_error(
+      SortedMergeReader::with_sorting_columns(vec![], None, vec![]),
+      "At least one file is required to merge rows"
+    );
+    check_error(
+      SortedMergeReader::with_sorting_columns(
+        vec![reader.clone()],
+        None,
+        vec![SortingColumn::new(11, false, false)]
+      ),
+      "Invalid sorting column index 11"
+    );
+    check_error(
+      SortedMergeReader::with_sorting_columns(
+        vec![reader.clone(), sorted_file("merge_invalid", &[1], &[None])],
+        None,
+        vec![SortingColumn::new(0, false, false)]
+      ),
+      "Schema with 2 columns does not match schema with 11 columns"
+    );
+    let projection = parse_message_type("message schema { OPTIONAL INT32 int_col; }");
+    check_error(
+      SortedMergeReader::with_sorting_columns(
+        vec![reader.clone()],
+        Some(projection.unwrap()),
+        vec![SortingColumn::new(0, false, false)]
+      ),
+      "Field id of sorting column is not in projection"
+    );
+
+    let reader: Rc<FileReader> = Rc::new(
+      SerializedFileReader::new(get_test_file("nested_lists.snappy.parquet")).unwrap()
+    );
+    check_error(
+      SortedMergeReader::with_sorting_columns(
+        vec![reader],
+        None,
+        vec![SortingColumn::new(0, false, false)]
+      ),
+      "Cannot merge rows by column a.list.element.list.element.list.element, column is \
+       not a top-level primitive field"
+    );
+  }
+
+  #[test]
+  fn test_compare_values() {
+    assert_eq!(compare_values(&Field::Int(-1), &Field::Int(1)), Ordering::Less);
+    assert_eq!(
+      compare_values(&Field::Str("b".to_owned()), &Field::Str("ab".to_owned())),
+      Ordering::Greater
+    );
+    assert_eq!(
+      compare_values(&Field::Double(::std::f64::NAN), &Field::Double(1.0)),
+      Ordering::Equal
+    );
+    let bytes = |data: Vec<u8>| Field::Bytes(ByteArray::from(data));
+    assert_eq!(compare_values(&bytes(vec![1]), &bytes(vec![255])), Ordering::Less);
+
+    let decimal = |data: Vec<u8>| Decimal::from_bytes(ByteArray::from(data), 10, 2);
+    // -1 < 1 < 256, values have different lengths
+    assert_eq!(compare_decimals(&decimal(vec![255]), &decimal(vec![1])), Ordering::Less);
+    let (one, large) = (decimal(vec![0, 1]), decimal(vec![1, 0]));
+    assert_eq!(compare_decimals(&one, &large), Ordering::Less);
+    assert_eq!(compare_decimals(&decimal(vec![1]), &one), Ordering::Equal);
+    assert_eq!(
+      compare_decimals(&decimal(vec![255, 254]), &decimal(vec![255])),
+      Ordering::Less
+    );
+  }
+}
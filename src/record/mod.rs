@@ -15,10 +15,18 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! Contains record-based API for reading Parquet files.
+//! Contains record-based API for reading Parquet files and shredding records into
+//! levels of columns for writing.
 
+pub mod dataset;
+pub mod decoder;
+pub mod filter;
+pub mod levels;
+pub mod merge;
 pub mod reader;
 mod api;
 mod triplet;
 
-pub use self::api::{Row, RowAccessor, List, ListAccessor, Map, MapAccessor};
+pub use self::api::{
+  Field, Row, RowAccessor, List, ListAccessor, Map, MapAccessor, Utf8Validation
+};
@@ -18,15 +18,21 @@
 //! Contains implementation of record assembly and converting Parquet types into
 //! [`Row`](`::record::api::Row`)s.
 
-use std::collections::HashMap;
+use std::cmp;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
+use std::mem;
 use std::rc::Rc;
 
-use basic::{LogicalType, Repetition};
+use basic::{LogicalType, Repetition, Type as PhysicalType};
+use column::selection::{RowSelection, RowSelector};
+use data_type::{ByteArray, Int96};
 use errors::{ParquetError, Result};
+use file::metadata::ColumnChunkMetaData;
 use file::reader::{FileReader, RowGroupReader};
 use schema::types::{ColumnPath, SchemaDescriptor, SchemaDescPtr, Type, TypePtr};
-use record::api::{Row, Field, make_row, make_list, make_map};
+use record::api::{Row, Field, Utf8Validation, make_row, make_list, make_map};
+use record::filter::RowFilter;
 use record::triplet::TripletIter;
 
 /// Default batch size for a reader
@@ -37,14 +43,20 @@ const DEFAULT_BATCH_SIZE: usize = 1024;
 /// accessing a records iterator [`RowIter`].
 pub struct TreeBuilder {
   // Batch size (>= 1) for triplet iterators
-  batch_size: usize
+  batch_size: usize,
+  // Target memory size in bytes of a batch of each triplet iterator
+  batch_memory_limit: Option<usize>,
+  // Validation of UTF-8 strings
+  utf8_validation: Utf8Validation
 }
 
 impl TreeBuilder {
   /// Creates new tree builder with default parameters.
   pub fn new() -> Self {
     Self {
-      batch_size: DEFAULT_BATCH_SIZE
+      batch_size: DEFAULT_BATCH_SIZE,
+      batch_memory_limit: None,
+      utf8_validation: Utf8Validation::default()
     }
   }
 
@@ -54,6 +66,23 @@ impl TreeBuilder {
     self
   }
 
+  /// Sets target memory size in bytes of a batch buffered for each column.
+  ///
+  /// Batch size of each column is computed from the estimated size of its values and
+  /// levels, so that wide columns, e.g. large binary values, are read in smaller
+  /// batches. Batch size is at most the batch size of this builder, and at least 1.
+  pub fn with_batch_memory_limit(mut self, limit: usize) -> Self {
+    self.batch_memory_limit = Some(limit);
+    self
+  }
+
+  /// Sets validation of UTF-8 strings, i.e. values of BYTE_ARRAY columns with UTF8,
+  /// ENUM or JSON logical type. Default is `Utf8Validation::Strict`.
+  pub fn with_utf8_validation(mut self, validation: Utf8Validation) -> Self {
+    self.utf8_validation = validation;
+    self
+  }
+
   /// Creates new root reader for provided schema and row group.
   pub fn build(
     &self,
@@ -96,6 +125,68 @@ impl TreeBuilder {
     ReaderIter::new(self.build(descr, row_group_reader), num_records)
   }
 
+  /// Creates iterator of `Row`s from schema descriptor and row group, that starts at
+  /// record `offset`. Leading records are skipped in column readers without being
+  /// assembled, whole pages are skipped when possible.
+  pub fn as_iter_with_offset(
+    &self,
+    descr: SchemaDescPtr,
+    row_group_reader: &RowGroupReader,
+    offset: usize
+  ) -> Result<ReaderIter> {
+    let num_records = row_group_reader.metadata().num_rows() as usize;
+    let offset = cmp::min(offset, num_records);
+    let selection = RowSelection::from_selectors(vec![
+      RowSelector::skip(offset),
+      RowSelector::select(num_records - offset)
+    ]);
+    self.as_iter_with_selection(descr, row_group_reader, &selection)
+  }
+
+  /// Creates iterator of `Row`s from schema descriptor and row group, that returns only
+  /// records selected by `selection`. Skipped records are not assembled, and are not
+  /// decoded where possible.
+  pub fn as_iter_with_selection(
+    &self,
+    descr: SchemaDescPtr,
+    row_group_reader: &RowGroupReader,
+    selection: &RowSelection
+  ) -> Result<ReaderIter> {
+    ReaderIter::with_selection(self.build(descr, row_group_reader), selection)
+  }
+
+  /// Returns batch size for a column, applying batch memory limit if it is set.
+  fn column_batch_size(&self, col_meta: &ColumnChunkMetaData) -> usize {
+    let limit = match self.batch_memory_limit {
+      Some(limit) => limit,
+      None => return self.batch_size
+    };
+    let descr = col_meta.column_descr();
+    let value_size = match descr.physical_type() {
+      PhysicalType::BOOLEAN => mem::size_of::<bool>(),
+      PhysicalType::INT32 | PhysicalType::FLOAT => 4,
+      PhysicalType::INT64 | PhysicalType::DOUBLE => 8,
+      PhysicalType::INT96 => mem::size_of::<Int96>(),
+      PhysicalType::FIXED_LEN_BYTE_ARRAY => {
+        mem::size_of::<ByteArray>() + cmp::max(descr.type_length(), 0) as usize
+      },
+      PhysicalType::BYTE_ARRAY => {
+        // Size of values is estimated as average uncompressed size of the column chunk
+        let num_values = cmp::max(col_meta.num_values(), 1);
+        let data_size = cmp::max(col_meta.uncompressed_size(), 0) / num_values;
+        mem::size_of::<ByteArray>() + data_size as usize
+      }
+    };
+    let mut row_size = value_size;
+    if descr.max_def_level() > 0 {
+      row_size += mem::size_of::<i16>();
+    }
+    if descr.max_rep_level() > 0 {
+      row_size += mem::size_of::<i16>();
+    }
+    cmp::max(cmp::min(limit / row_size, self.batch_size), 1)
+  }
+
   /// Builds tree of readers for the current schema recursively.
   fn reader_tree(
     &self,
@@ -124,9 +215,12 @@ impl TreeBuilder {
     let reader = if field.is_primitive() {
       let col_path = ColumnPath::new(path.to_vec());
       let orig_index = *paths.get(&col_path).unwrap();
-      let col_descr = row_group_reader.metadata().column(orig_index).column_descr_ptr();
+      let row_group_metadata = row_group_reader.metadata();
+      let col_meta = row_group_metadata.column(orig_index);
+      let batch_size = self.column_batch_size(col_meta);
       let col_reader = row_group_reader.get_column_reader(orig_index).unwrap();
-      let column = TripletIter::new(col_descr, col_reader, self.batch_size);
+      let column = TripletIter::new(col_meta.column_descr_ptr(), col_reader, batch_size)
+        .with_utf8_validation(self.utf8_validation);
       Reader::PrimitiveReader(field, column)
     } else {
       match field.get_basic_info().logical_type() {
@@ -271,7 +365,7 @@ impl Reader {
   /// This method is copied from Spark Parquet reader and is based on the reference:
   /// https://github.com/apache/parquet-format/blob/master/LogicalTypes.md
   ///   #backward-compatibility-rules
-  fn is_element_type(repeated_type: &Type) -> bool {
+  pub(crate) fn is_element_type(repeated_type: &Type) -> bool {
     // For legacy 2-level list types with primitive element type, e.g.:
     //
     //    // ARRAY<INT> (nullable list, non-null elements)
@@ -319,14 +413,14 @@ impl Reader {
   /// Automatically advances all necessary readers.
   /// This must be called on the root level reader (i.e., for Message type).
   /// Otherwise, it will panic.
-  fn read(&mut self) -> Row {
+  fn read(&mut self) -> Result<Row> {
     match *self {
       Reader::GroupReader(_, _, ref mut readers) => {
         let mut fields = Vec::new();
         for reader in readers {
-          fields.push((String::from(reader.field_name()), reader.read_field()));
+          fields.push((String::from(reader.field_name()), reader.read_field()?));
         }
-        make_row(fields)
+        Ok(make_row(fields))
       },
       _ => panic!("Cannot call read() on {}", self)
     }
@@ -334,19 +428,19 @@ impl Reader {
 
   /// Reads current record as `Field` from the reader tree.
   /// Automatically advances all necessary readers.
-  fn read_field(&mut self) -> Field {
+  fn read_field(&mut self) -> Result<Field> {
     match *self {
       Reader::PrimitiveReader(_, ref mut column) => {
-        let value = column.current_value();
-        column.read_next().unwrap();
-        value
+        let value = column.current_value()?;
+        column.read_next()?;
+        Ok(value)
       },
       Reader::OptionReader(def_level, ref mut reader) => {
         if reader.current_def_level() > def_level {
           reader.read_field()
         } else {
-          reader.advance_columns();
-          Field::Null
+          reader.advance_columns()?;
+          Ok(Field::Null)
         }
       },
       Reader::GroupReader(_, def_level, ref mut readers) => {
@@ -354,22 +448,22 @@ impl Reader {
         for reader in readers {
           if reader.repetition() != Repetition::OPTIONAL ||
               reader.current_def_level() > def_level {
-            fields.push((String::from(reader.field_name()), reader.read_field()));
+            fields.push((String::from(reader.field_name()), reader.read_field()?));
           } else {
-            reader.advance_columns();
+            reader.advance_columns()?;
             fields.push((String::from(reader.field_name()), Field::Null));
           }
         }
         let row = make_row(fields);
-        Field::Group(row)
+        Ok(Field::Group(row))
       },
       Reader::RepeatedReader(_, def_level, rep_level, ref mut reader) => {
         let mut elements = Vec::new();
         loop {
           if reader.current_def_level() > def_level {
-            elements.push(reader.read_field());
+            elements.push(reader.read_field()?);
           } else {
-            reader.advance_columns();
+            reader.advance_columns()?;
             // If the current definition level is equal to the definition level of this
             // repeated type, then the result is an empty list and the repetition level
             // will always be <= rl.
@@ -382,7 +476,7 @@ impl Reader {
             break;
           }
         }
-        Field::ListInternal(make_list(elements))
+        Ok(Field::ListInternal(make_list(elements)))
       },
       Reader::KeyValueReader(_, def_level, rep_level,
           ref mut keys, ref mut values) => {
@@ -390,10 +484,10 @@ impl Reader {
         let mut pairs = Vec::new();
         loop {
           if keys.current_def_level() > def_level {
-            pairs.push((keys.read_field(), values.read_field()));
+            pairs.push((keys.read_field()?, values.read_field()?));
           } else {
-            keys.advance_columns();
-            values.advance_columns();
+            keys.advance_columns()?;
+            values.advance_columns()?;
             // If the current definition level is equal to the definition level of this
             // repeated type, then the result is an empty list and the repetition level
             // will always be <= rl.
@@ -407,7 +501,7 @@ impl Reader {
           }
         }
 
-        Field::MapInternal(make_map(pairs))
+        Ok(Field::MapInternal(make_map(pairs)))
       }
     }
   }
@@ -490,28 +584,54 @@ impl Reader {
     }
   }
 
+  /// Skips next `num_records` records in all leaf columns of the current reader.
+  /// Must be called either before leaf columns are advanced, or after a record has been
+  /// read, so that all leaf columns are at the start of the next record.
+  /// Returns number of records skipped.
+  fn skip_records(&mut self, num_records: usize) -> Result<usize> {
+    match *self {
+      Reader::PrimitiveReader(_, ref mut column) => column.skip_records(num_records),
+      Reader::OptionReader(_, ref mut reader) => reader.skip_records(num_records),
+      Reader::GroupReader(_, _, ref mut readers) => {
+        let mut num_skipped = num_records;
+        for reader in readers {
+          num_skipped = cmp::min(num_skipped, reader.skip_records(num_records)?);
+        }
+        Ok(num_skipped)
+      },
+      Reader::RepeatedReader(_, _, _, ref mut reader) => {
+        reader.skip_records(num_records)
+      },
+      Reader::KeyValueReader(_, _, _, ref mut keys, ref mut values) => {
+        let num_skipped = keys.skip_records(num_records)?;
+        Ok(cmp::min(num_skipped, values.skip_records(num_records)?))
+      }
+    }
+  }
+
   /// Advances leaf columns for the current reader.
-  fn advance_columns(&mut self) {
+  fn advance_columns(&mut self) -> Result<()> {
     match *self {
       Reader::PrimitiveReader(_, ref mut column) => {
-        column.read_next().unwrap();
+        column.read_next()?;
       },
       Reader::OptionReader(_, ref mut reader) => {
-        reader.advance_columns();
+        reader.advance_columns()?;
       },
       Reader::GroupReader(_, _, ref mut readers) => {
         for reader in readers {
-          reader.advance_columns();
+          reader.advance_columns()?;
         }
       },
       Reader::RepeatedReader(_, _, _, ref mut reader) => {
-        reader.advance_columns();
+        reader.advance_columns()?;
       },
       Reader::KeyValueReader(_, _, _, ref mut keys, ref mut values) => {
-        keys.advance_columns();
-        values.advance_columns();
+        keys.advance_columns()?;
+        values.advance_columns()?;
       }
     }
+    Ok(())
   }
 }
 
@@ -536,13 +656,42 @@ impl fmt::Display for Reader {
 /// Iterator of [`Row`](`::record::api::Row`)s.
 /// It is used either for a single row group to iterate over data in that row group, or
 /// an entire file with auto buffering of all row groups.
+///
+/// Errors, e.g. of a corrupt page or an invalid UTF-8 value, are returned as items and
+/// iteration stops after the first error.
+///
+/// Use [`with_offset`](#method.with_offset) and [`with_limit`](#method.with_limit) to
+/// read only a range of rows, e.g. for previews or pagination. Row groups before the
+/// offset are skipped using metadata and are never opened, leading rows within a row
+/// group are skipped without being assembled, and no row groups are opened once the
+/// limit is reached.
+///
+/// # Example
+///
+/// ```rust
+/// use std::fs::File;
+/// use parquet::file::reader::{FileReader, SerializedFileReader};
+///
+/// let file = File::open("data/alltypes_plain.parquet").unwrap();
+/// let reader = SerializedFileReader::new(file).unwrap();
+/// let iter = reader.get_row_iter(None).unwrap().with_offset(2).with_limit(3);
+/// assert_eq!(iter.count(), 3);
+/// ```
 pub struct RowIter<'a> {
   descr: SchemaDescPtr,
   tree_builder: TreeBuilder,
   file_reader: Option<&'a FileReader>,
+  row_group_reader: Option<&'a RowGroupReader>,
   current_row_group: usize,
   num_row_groups: usize,
-  row_iter: Option<ReaderIter>
+  row_iter: Option<ReaderIter>,
+  // number of leading rows left to skip
+  offset: usize,
+  // number of rows left to return, if limit is set
+  limit: Option<usize>,
+  filter: Option<RowFilter>,
+  // whether an error has been returned
+  done: bool
 }
 
 impl<'a> RowIter<'a> {
@@ -556,30 +705,103 @@ impl<'a> RowIter<'a> {
       descr: descr,
       tree_builder: Self::tree_builder(),
       file_reader: Some(reader),
+      row_group_reader: None,
       current_row_group: 0,
       num_row_groups: num_row_groups,
-      row_iter: None
+      row_iter: None,
+      offset: 0,
+      limit: None,
+      filter: None,
+      done: false
     })
   }
 
   /// Creates iterator of [`Row`](`::record::api::Row`)s for a specific row group.
   pub fn from_row_group(proj: Option<Type>, reader: &'a RowGroupReader) -> Result<Self> {
     let descr = Self::get_proj_descr(proj, reader.metadata().schema_descr_ptr())?;
-    let tree_builder = Self::tree_builder();
-    let row_iter = tree_builder.as_iter(descr.clone(), reader);
 
     // For row group we need to set `current_row_group` >= `num_row_groups`, because we
-    // only have one row group and can't buffer more.
+    // only have one row group and can't buffer more. Reader tree is built on the first
+    // call to `next`, so that offset can be applied.
     Ok(Self {
       descr: descr,
-      tree_builder: tree_builder,
+      tree_builder: Self::tree_builder(),
       file_reader: None,
+      row_group_reader: Some(reader),
       current_row_group: 0,
       num_row_groups: 0,
-      row_iter: Some(row_iter)
+      row_iter: None,
+      offset: 0,
+      limit: None,
+      filter: None,
+      done: false
     })
   }
 
+  /// Sets number of leading rows to skip.
+  pub fn with_offset(mut self, offset: usize) -> Self {
+    self.offset = offset;
+    self
+  }
+
+  /// Sets maximum number of rows to return.
+  pub fn with_limit(mut self, limit: usize) -> Self {
+    self.limit = Some(limit);
+    self
+  }
+
+  /// Sets target memory size in bytes of a batch buffered for each column, see
+  /// [`TreeBuilder::with_batch_memory_limit`].
+  ///
+  /// [`TreeBuilder::with_batch_memory_limit`]:
+  /// struct.TreeBuilder.html#method.with_batch_memory_limit
+  pub fn with_batch_memory_limit(mut self, limit: usize) -> Self {
+    self.tree_builder = self.tree_builder.with_batch_memory_limit(limit);
+    self
+  }
+
+  /// Sets validation of UTF-8 strings, see [`TreeBuilder::with_utf8_validation`].
+  ///
+  /// [`TreeBuilder::with_utf8_validation`]:
+  /// struct.TreeBuilder.html#method.with_utf8_validation
+  pub fn with_utf8_validation(mut self, validation: Utf8Validation) -> Self {
+    self.tree_builder = self.tree_builder.with_utf8_validation(validation);
+    self
+  }
+
+  /// Sets filter to return only rows that match its predicate.
+  ///
+  /// Predicate is evaluated on the filter projection for each row group first, and only
+  /// matching rows are read for the projection of this iterator. Offset and limit are
+  /// applied to matching rows. Errors returned by predicate are returned by the
+  /// iterator.
+  pub fn with_row_filter(mut self, filter: RowFilter) -> Self {
+    self.filter = Some(filter);
+    self
+  }
+
+  /// Creates iterator for a row group, applying filter and remaining offset.
+  fn row_group_iter(&mut self, row_group_reader: &RowGroupReader) -> Result<ReaderIter> {
+    let selection = match self.filter {
+      Some(ref filter) => filter.evaluate(row_group_reader)?,
+      None => {
+        RowSelection::select_all(row_group_reader.metadata().num_rows() as usize)
+      }
+    };
+    let offset = cmp::min(self.offset, selection.row_count());
+    self.offset -= offset;
+    let mut selection = selection.offset(offset);
+    if let Some(metrics) = row_group_reader.metrics() {
+      let num_rows = row_group_reader.metadata().num_rows() as usize;
+      metrics.record_rows_pruned(num_rows - selection.row_count());
+    }
+    if let Some(limit) = self.limit {
+      selection = selection.limit(limit);
+    }
+    self.tree_builder
+      .as_iter_with_selection(self.descr.clone(), row_group_reader, &selection)
+  }
+
   /// Returns common tree builder, so the same settings are applied to both iterators
   /// from file reader and row group.
   #[inline]
@@ -608,60 +830,140 @@ impl<'a> RowIter<'a> {
       }
     }
   }
-}
 
-impl<'a> Iterator for RowIter<'a> {
-  type Item = Row;
+  /// Returns next row, moving to the next row group if needed, or `None` if there are
+  /// no rows left.
+  fn read_row(&mut self) -> Result<Option<Row>> {
+    if let Some(row_group_reader) = self.row_group_reader.take() {
+      self.row_iter = Some(self.row_group_iter(row_group_reader)?);
+    }
 
-  fn next(&mut self) -> Option<Row> {
     let mut row = None;
     if let Some(ref mut iter) = self.row_iter {
-      row = iter.next();
+      row = iter.read_row()?;
     }
 
     while row.is_none() && self.current_row_group < self.num_row_groups {
-      // We do not expect any failures when accessing a row group, and file reader
-      // must be set for selecting next row group.
-      let row_group_reader = &*self.file_reader
-        .as_ref()
-        .expect("File reader is required to advance row group")
-        .get_row_group(self.current_row_group).unwrap();
+      // File reader must be set for selecting next row group.
+      let file_reader = self.file_reader
+        .expect("File reader is required to advance row group");
+
+      // Skip entire row groups before offset using metadata, number of matching rows
+      // is not known in advance when filter is set
+      let num_rows =
+        file_reader.metadata().row_group(self.current_row_group).num_rows() as usize;
+      if self.filter.is_none() && self.offset >= num_rows {
+        if let Some(metrics) = file_reader.metrics() {
+          metrics.record_rows_pruned(num_rows);
+        }
+        self.offset -= num_rows;
+        self.current_row_group += 1;
+        continue;
+      }
+
+      let row_group_reader = &*file_reader.get_row_group(self.current_row_group)?;
       self.current_row_group += 1;
-      let mut iter = self.tree_builder.as_iter(self.descr.clone(), row_group_reader);
-      row = iter.next();
+      let mut iter = self.row_group_iter(row_group_reader)?;
+      row = iter.read_row()?;
       self.row_iter = Some(iter);
     }
+    Ok(row)
+  }
+}
+
+impl<'a> Iterator for RowIter<'a> {
+  type Item = Result<Row>;
 
-    row
+  fn next(&mut self) -> Option<Result<Row>> {
+    if self.done || self.limit == Some(0) {
+      return None;
+    }
+    match self.read_row() {
+      Ok(Some(row)) => {
+        if let Some(ref mut limit) = self.limit {
+          *limit -= 1;
+        }
+        Some(Ok(row))
+      },
+      Ok(None) => None,
+      Err(e) => {
+        self.done = true;
+        Some(Err(e))
+      }
+    }
   }
 }
 
-/// Internal iterator of [`Row`](`::record::api::Row`)s for a reader.
+/// Internal iterator of [`Row`](`::record::api::Row`)s for a reader. Iteration stops
+/// after the first error.
 pub struct ReaderIter {
   root_reader: Reader,
-  records_left: usize
+  records_left: usize,
+  // remaining runs of selection, after the current one
+  selectors: VecDeque<RowSelector>,
+  // whether leaf columns have been advanced to the first record
+  is_started: bool,
+  // whether an error has been returned
+  done: bool
 }
 
 impl ReaderIter {
-  fn new(mut root_reader: Reader, num_records: usize) -> Self {
-    // Prepare root reader by advancing all column vectors
-    root_reader.advance_columns();
+  fn new(root_reader: Reader, num_records: usize) -> Self {
     Self {
       root_reader: root_reader,
-      records_left: num_records
+      records_left: num_records,
+      selectors: VecDeque::new(),
+      is_started: false,
+      done: false
     }
   }
+
+  fn with_selection(mut root_reader: Reader, selection: &RowSelection) -> Result<Self> {
+    let mut selectors = VecDeque::from(selection.selectors().to_vec());
+    // Leading records are skipped before any values are buffered
+    if selectors.front().map(|s| s.skip).unwrap_or(false) {
+      let selector = selectors.pop_front().unwrap();
+      root_reader.skip_records(selector.row_count)?;
+    }
+    let mut iter = Self::new(root_reader, 0);
+    iter.selectors = selectors;
+    Ok(iter)
+  }
+
+  /// Returns next selected row, or `None` if there are no rows left.
+  fn read_row(&mut self) -> Result<Option<Row>> {
+    if !self.is_started {
+      // Prepare root reader by advancing all column vectors
+      self.root_reader.advance_columns()?;
+      self.is_started = true;
+    }
+    while self.records_left == 0 {
+      match self.selectors.pop_front() {
+        Some(ref selector) if selector.skip => {
+          self.root_reader.skip_records(selector.row_count)?;
+        },
+        Some(selector) => self.records_left = selector.row_count,
+        None => return Ok(None)
+      }
+    }
+    self.records_left -= 1;
+    self.root_reader.read().map(Some)
+  }
 }
 
 impl Iterator for ReaderIter {
-  type Item = Row;
+  type Item = Result<Row>;
 
-  fn next(&mut self) -> Option<Row> {
-    if self.records_left > 0 {
-      self.records_left -= 1;
-      Some(self.root_reader.read())
-    } else {
-      None
+  fn next(&mut self) -> Option<Result<Row>> {
+    if self.done {
+      return None;
+    }
+    match self.read_row() {
+      Ok(row) => row.map(Ok),
+      Err(e) => {
+        self.done = true;
+        Some(Err(e))
+      }
     }
   }
 }
@@ -670,11 +972,17 @@ impl Iterator for ReaderIter {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use std::fs::File;
+
+  use column::writer::ColumnWriter;
   use errors::{ParquetError, Result};
+  use file::properties::WriterProperties;
   use file::reader::{FileReader, SerializedFileReader};
+  use file::writer::{FileWriter, write_to_bytes};
+  use record::RowAccessor;
   use record::api::{Row, Field};
   use schema::parser::parse_message_type;
-  use util::test_common::get_test_file;
+  use util::test_common::{get_temp_file, get_test_file};
 
   // Convenient macros to assemble row, list, map, and group.
 
@@ -1370,11 +1678,248 @@ mod tests {
     assert_eq!(rows, expected_rows);
   }
 
+  #[test]
+  fn test_file_reader_rows_offset_limit() {
+    let file_names = vec![
+      "alltypes_plain.parquet",
+      "nulls.snappy.parquet",
+      "nested_lists.snappy.parquet",
+      "nested_maps.snappy.parquet",
+      "nonnullable.impala.parquet",
+      "nullable.impala.parquet",
+      "repeated_no_annotation.parquet"
+    ];
+    for file_name in file_names {
+      let rows = test_file_reader_rows(file_name, None).unwrap();
+      for offset in 0..rows.len() + 2 {
+        for limit in 0..rows.len() + 2 {
+          let expected =
+            rows.iter().skip(offset).take(limit).cloned().collect::<Vec<_>>();
+
+          let file_reader = SerializedFileReader::new(get_test_file(file_name)).unwrap();
+          let res = file_reader.get_row_iter(None).unwrap()
+            .with_offset(offset)
+            .with_limit(limit)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+          assert_eq!(res, expected, "file: {}, offset: {}, limit: {}",
+            file_name, offset, limit);
+
+          let row_group_reader = file_reader.get_row_group(0).unwrap();
+          let res = row_group_reader.get_row_iter(None).unwrap()
+            .with_offset(offset)
+            .with_limit(limit)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+          assert_eq!(res, expected, "file: {}, offset: {}, limit: {}",
+            file_name, offset, limit);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_tree_builder_batch_memory_limit() {
+    let file_reader =
+      SerializedFileReader::new(get_test_file("alltypes_plain.parquet")).unwrap();
+    let row_group_reader = file_reader.get_row_group(0).unwrap();
+    let metadata = row_group_reader.metadata();
+
+    let builder = TreeBuilder::new();
+    assert_eq!(builder.column_batch_size(metadata.column(0)), DEFAULT_BATCH_SIZE);
+
+    // Optional INT32 column has 4 bytes of value and 2 bytes of definition level
+    let builder = TreeBuilder::new().with_batch_memory_limit(60);
+    assert_eq!(builder.column_batch_size(metadata.column(0)), 10);
+    // Optional BYTE_ARRAY column is wider than INT32 column
+    assert!(builder.column_batch_size(metadata.column(9)) < 10);
+
+    let builder = TreeBuilder::new().with_batch_size(4).with_batch_memory_limit(1 << 20);
+    assert_eq!(builder.column_batch_size(metadata.column(0)), 4);
+    let builder = TreeBuilder::new().with_batch_memory_limit(0);
+    assert_eq!(builder.column_batch_size(metadata.column(9)), 1);
+  }
+
+  #[test]
+  fn test_file_reader_rows_batch_memory_limit() {
+    let file_names = vec![
+      "alltypes_plain.parquet",
+      "nested_lists.snappy.parquet",
+      "nested_maps.snappy.parquet",
+      "nullable.impala.parquet"
+    ];
+    for file_name in file_names {
+      let expected = test_file_reader_rows(file_name, None).unwrap();
+      for limit in vec![0, 10, 100, 1 << 20] {
+        let file_reader = SerializedFileReader::new(get_test_file(file_name)).unwrap();
+        let rows = file_reader.get_row_iter(None).unwrap()
+          .with_batch_memory_limit(limit)
+          .collect::<Result<Vec<_>>>()
+          .unwrap();
+        assert_eq!(rows, expected, "file: {}, limit: {}", file_name, limit);
+      }
+    }
+  }
+
+  #[test]
+  fn test_file_reader_rows_offset_limit_row_groups() {
+    // Values 0..20 are written into 4 row groups with 5 rows each
+    let file = write_int32_row_groups("test_file_reader_rows_offset_limit_row_groups");
+    let file_reader = SerializedFileReader::new(file).unwrap();
+    assert_eq!(file_reader.num_row_groups(), 4);
+
+    let read = |offset: usize, limit: Option<usize>| -> Vec<i32> {
+      let mut iter = file_reader.get_row_iter(None).unwrap().with_offset(offset);
+      if let Some(limit) = limit {
+        iter = iter.with_limit(limit);
+      }
+      iter.map(|row| row.unwrap().get_int(0).unwrap()).collect()
+    };
+
+    assert_eq!(read(0, None), (0..20).collect::<Vec<_>>());
+    assert_eq!(read(5, Some(5)), (5..10).collect::<Vec<_>>());
+    assert_eq!(read(7, Some(10)), (7..17).collect::<Vec<_>>());
+    assert_eq!(read(18, None), vec![18, 19]);
+    assert_eq!(read(20, None), Vec::<i32>::new());
+    assert_eq!(read(3, Some(0)), Vec::<i32>::new());
+  }
+
+  #[test]
+  fn test_file_reader_rows_row_filter() {
+    let rows = test_file_reader_rows("nullable.impala.parquet", None).unwrap();
+    for modulus in 1..5 {
+      for residue in 0..modulus {
+        let matches = move |row: &Row| -> Result<bool> {
+          Ok(row.get_long(0)? % modulus == residue)
+        };
+        let expected = rows.iter()
+          .filter(|row| matches(row).unwrap())
+          .cloned()
+          .collect::<Vec<_>>();
+
+        let file = get_test_file("nullable.impala.parquet");
+        let file_reader = SerializedFileReader::new(file).unwrap();
+        let projection = parse_message_type(
+          "message org.apache.impala.ComplexTypesTbl { OPTIONAL INT64 id; }").unwrap();
+        let res = file_reader.get_row_iter(None).unwrap()
+          .with_row_filter(RowFilter::new(projection, matches))
+          .collect::<Result<Vec<_>>>()
+          .unwrap();
+        assert_eq!(res, expected, "modulus: {}, residue: {}", modulus, residue);
+
+        let projection = parse_message_type(
+          "message org.apache.impala.ComplexTypesTbl { OPTIONAL INT64 id; }").unwrap();
+        let row_group_reader = file_reader.get_row_group(0).unwrap();
+        let res = row_group_reader.get_row_iter(None).unwrap()
+          .with_row_filter(RowFilter::new(projection, matches))
+          .with_offset(1)
+          .with_limit(2)
+          .collect::<Result<Vec<_>>>()
+          .unwrap();
+        assert_eq!(res, expected.iter().skip(1).take(2).cloned().collect::<Vec<_>>(),
+          "modulus: {}, residue: {}", modulus, residue);
+      }
+    }
+  }
+
+  #[test]
+  fn test_file_reader_rows_row_filter_row_groups() {
+    // Values 0..20 are written into 4 row groups with 5 rows each
+    let file = write_int32_row_groups("test_file_reader_rows_row_filter_row_groups");
+    let file_reader = SerializedFileReader::new(file).unwrap();
+
+    let read = |offset: usize, limit: usize| -> Vec<i32> {
+      let projection =
+        parse_message_type("message schema { REQUIRED INT32 a; }").unwrap();
+      let filter = RowFilter::new(projection, |row| {
+        let value = row.get_int(0)?;
+        Ok(value % 3 == 0 || (value >= 5 && value < 10))
+      });
+      file_reader.get_row_iter(None).unwrap()
+        .with_row_filter(filter)
+        .with_offset(offset)
+        .with_limit(limit)
+        .map(|row| row.unwrap().get_int(0).unwrap())
+        .collect()
+    };
+
+    assert_eq!(read(0, 20), vec![0, 3, 5, 6, 7, 8, 9, 12, 15, 18]);
+    assert_eq!(read(2, 4), vec![5, 6, 7, 8]);
+    assert_eq!(read(7, 20), vec![12, 15, 18]);
+    assert_eq!(read(10, 20), Vec::<i32>::new());
+  }
+
+  #[test]
+  fn test_file_reader_rows_utf8_validation() {
+    let schema = Rc::new(
+      parse_message_type("message schema { REQUIRED BYTE_ARRAY a (UTF8); }").unwrap()
+    );
+    let props = Rc::new(WriterProperties::builder().build());
+    let bytes = write_to_bytes(schema, props, |writer| {
+      let values = vec![
+        ByteArray::from("abc"),
+        ByteArray::from(vec![b'a', 0xff]),
+        ByteArray::from("def")
+      ];
+      let mut row_group_writer = writer.next_row_group()?;
+      let mut col_writer = row_group_writer.next_column()?.unwrap();
+      if let ColumnWriter::ByteArrayColumnWriter(ref mut typed) = col_writer {
+        typed.write_batch(&values[..], None, None)?;
+      }
+      row_group_writer.close_column(col_writer)?;
+      writer.close_row_group(row_group_writer)
+    }).unwrap();
+    let file = get_temp_file("test_file_reader_rows_utf8_validation", &bytes);
+    let file_reader = SerializedFileReader::new(file).unwrap();
+
+    let values: Vec<String> = file_reader.get_row_iter(None).unwrap()
+      .with_utf8_validation(Utf8Validation::Lossy)
+      .map(|row| row.unwrap().get_string(0).unwrap().clone())
+      .collect();
+    assert_eq!(values, vec!["abc", "a\u{FFFD}", "def"]);
+
+    // Invalid values are rejected by default, iteration stops after the error
+    let res = file_reader.get_row_iter(None).unwrap().collect::<Vec<_>>();
+    assert_eq!(res.len(), 2);
+    assert_eq!(res[0].as_ref().unwrap().get_string(0).unwrap(), "abc");
+    match res[1] {
+      Err(ref e) => assert_eq!(
+        e.to_string(),
+        "Parquet error: Invalid UTF-8 value in column \"a\": \
+          invalid utf-8 sequence of 1 bytes from index 1"
+      ),
+      Ok(_) => panic!("Expected error for invalid UTF-8 value")
+    }
+  }
+
+  /// Writes file with one required INT32 column and 4 row groups with 5 rows each,
+  /// values are 0..20.
+  fn write_int32_row_groups(file_name: &str) -> File {
+    let schema = Rc::new(
+      parse_message_type("message schema { REQUIRED INT32 a; }").unwrap()
+    );
+    let props = Rc::new(WriterProperties::builder().build());
+    let bytes = write_to_bytes(schema, props, |writer| {
+      for i in 0..4 {
+        let values = (i * 5..i * 5 + 5).collect::<Vec<i32>>();
+        let mut row_group_writer = writer.next_row_group()?;
+        let mut col_writer = row_group_writer.next_column()?.unwrap();
+        if let ColumnWriter::Int32ColumnWriter(ref mut typed) = col_writer {
+          typed.write_batch(&values[..], None, None)?;
+        }
+        row_group_writer.close_column(col_writer)?;
+        writer.close_row_group(row_group_writer)?;
+      }
+      Ok(())
+    }).unwrap();
+    get_temp_file(file_name, &bytes)
+  }
+
   fn test_file_reader_rows(file_name: &str, schema: Option<Type>) -> Result<Vec<Row>> {
     let file = get_test_file(file_name);
     let file_reader: Box<FileReader> = Box::new(SerializedFileReader::new(file)?);
     let iter = file_reader.get_row_iter(schema)?;
-    Ok(iter.collect())
+    iter.collect()
   }
 
   fn test_row_group_rows(file_name: &str, schema: Option<Type>) -> Result<Vec<Row>> {
@@ -1383,6 +1928,6 @@ mod tests {
     // Check the first row group only, because files will contain only single row group
     let row_group_reader = file_reader.get_row_group(0).unwrap();
     let iter = row_group_reader.get_row_iter(schema)?;
-    Ok(iter.collect())
+    iter.collect()
   }
 }
@@ -19,7 +19,7 @@ use basic::{Type as PhysicalType};
 use column::reader::{get_typed_column_reader, ColumnReader, ColumnReaderImpl};
 use data_type::*;
 use errors::{ParquetError, Result};
-use record::api::Field;
+use record::api::{Field, Utf8Validation};
 use schema::types::ColumnDescPtr;
 
 /// Macro to generate simple functions that cover all types of triplet iterator.
@@ -92,6 +92,14 @@ impl TripletIter {
     }
   }
 
+  /// Sets validation of UTF-8 strings converted from BYTE_ARRAY values.
+  pub fn with_utf8_validation(mut self, validation: Utf8Validation) -> Self {
+    if let TripletIter::ByteArrayTripletIter(ref mut typed) = self {
+      typed.utf8_validation = validation;
+    }
+    self
+  }
+
   /// Invokes underlying typed triplet iterator to buffer current value.
   /// Should be called once - either before `is_null` or `current_value`.
   #[inline]
@@ -99,6 +107,29 @@ impl TripletIter {
     triplet_enum_func!(self, read_next, ref, mut)
   }
 
+  /// Skips next `num_records` records. Returns number of records skipped.
+  ///
+  /// If no triplets are buffered, records are skipped in the underlying column reader
+  /// without decoding them, and `read_next` should be called afterwards. Otherwise,
+  /// the current triplet must start a record, buffered triplets are skipped first and
+  /// iterator is positioned at the first triplet of the next record.
+  pub fn skip_records(&mut self, num_records: usize) -> Result<usize> {
+    match *self {
+      TripletIter::BoolTripletIter(ref mut typed) => typed.skip_records(num_records),
+      TripletIter::Int32TripletIter(ref mut typed) => typed.skip_records(num_records),
+      TripletIter::Int64TripletIter(ref mut typed) => typed.skip_records(num_records),
+      TripletIter::Int96TripletIter(ref mut typed) => typed.skip_records(num_records),
+      TripletIter::FloatTripletIter(ref mut typed) => typed.skip_records(num_records),
+      TripletIter::DoubleTripletIter(ref mut typed) => typed.skip_records(num_records),
+      TripletIter::ByteArrayTripletIter(ref mut typed) => {
+        typed.skip_records(num_records)
+      },
+      TripletIter::FixedLenByteArrayTripletIter(ref mut typed) => {
+        typed.skip_records(num_records)
+      }
+    }
+  }
+
   /// Provides check on values/levels left without invoking the underlying typed triplet
   /// iterator.
   /// Returns true if more values/levels exist, false otherwise.
@@ -141,9 +172,10 @@ impl TripletIter {
   }
 
   /// Updates non-null value for current row.
-  pub fn current_value(&self) -> Field {
+  /// Returns error if value cannot be converted into `Field`, e.g. invalid UTF-8 string.
+  pub fn current_value(&self) -> Result<Field> {
     assert!(!self.is_null(), "Value is null");
-    match *self {
+    let field = match *self {
       TripletIter::BoolTripletIter(ref typed) => {
         Field::convert_bool(typed.column_descr(), *typed.current_value())
       },
@@ -163,12 +195,17 @@ impl TripletIter {
         Field::convert_double(typed.column_descr(), *typed.current_value())
       },
       TripletIter::ByteArrayTripletIter(ref typed) => {
-        Field::convert_byte_array(typed.column_descr(), typed.current_value().clone())
+        Field::convert_byte_array_with_validation(
+          typed.column_descr(),
+          typed.current_value().clone(),
+          typed.utf8_validation
+        )?
       },
       TripletIter::FixedLenByteArrayTripletIter(ref typed) => {
-        Field::convert_byte_array(typed.column_descr(), typed.current_value().clone())
+        Field::convert_byte_array(typed.column_descr(), typed.current_value().clone())?
       }
-    }
+    };
+    Ok(field)
   }
 }
 
@@ -190,7 +227,9 @@ pub struct TypedTripletIter<T: DataType> {
   // how many triplets are left before we need to buffer
   triplets_left: usize,
   // helper flag to quickly check if we have more values/levels to read
-  has_next: bool
+  has_next: bool,
+  // validation of UTF-8 strings, only used for BYTE_ARRAY values
+  utf8_validation: Utf8Validation
 }
 
 impl<T: DataType> TypedTripletIter<T> {
@@ -220,7 +259,8 @@ impl<T: DataType> TypedTripletIter<T> {
       rep_levels: rep_levels,
       curr_triplet_index: 0,
       triplets_left: 0,
-      has_next: false
+      has_next: false,
+      utf8_validation: Utf8Validation::default()
     }
   }
 
@@ -282,6 +322,31 @@ impl<T: DataType> TypedTripletIter<T> {
     self.has_next
   }
 
+  /// Skips next `num_records` records, see `TripletIter::skip_records`.
+  fn skip_records(&mut self, num_records: usize) -> Result<usize> {
+    if !self.has_next {
+      return self.reader.skip_records(num_records);
+    }
+
+    let mut records_skipped = 0;
+    while self.has_next {
+      // Every triplet starts a record, if column is not repeated
+      if self.max_rep_level == 0 || self.current_rep_level() == 0 {
+        if records_skipped == num_records {
+          break;
+        }
+        records_skipped += 1;
+      }
+      // Skip the rest of the records in column reader once buffer is exhausted
+      if self.curr_triplet_index + 1 >= self.triplets_left &&
+          records_skipped < num_records {
+        records_skipped += self.reader.skip_records(num_records - records_skipped)?;
+      }
+      self.read_next()?;
+    }
+    Ok(records_skipped)
+  }
+
   /// Advances to the next triplet.
   /// Returns true, if there are more records to read, false there are no records left.
   fn read_next(&mut self) -> Result<bool> {
@@ -449,6 +514,88 @@ mod tests {
       &values, &def_levels, &rep_levels);
   }
 
+  #[test]
+  fn test_triplet_skip_records() {
+    let file_reader =
+      SerializedFileReader::new(get_test_file("nulls.snappy.parquet")).unwrap();
+    let row_group_reader = file_reader.get_row_group(0).unwrap();
+    let descr = row_group_reader.metadata().column(0).column_descr_ptr();
+
+    let reader = row_group_reader.get_column_reader(0).unwrap();
+    let mut iter = TripletIter::new(descr.clone(), reader, 2);
+    assert_eq!(iter.skip_records(3).unwrap(), 3);
+    let mut num_levels = 0;
+    while let Ok(true) = iter.read_next() {
+      assert!(iter.is_null());
+      num_levels += 1;
+    }
+    assert_eq!(num_levels, 5);
+
+    // Skip records after triplets are buffered
+    let reader = row_group_reader.get_column_reader(0).unwrap();
+    let mut iter = TripletIter::new(descr, reader, 2);
+    assert!(iter.read_next().unwrap());
+    assert_eq!(iter.skip_records(4).unwrap(), 4);
+    let mut num_levels = 0;
+    while iter.has_next() {
+      num_levels += 1;
+      iter.read_next().unwrap();
+    }
+    assert_eq!(num_levels, 4);
+  }
+
+  #[test]
+  fn test_triplet_skip_records_repeated() {
+    // Compare skipping records of a nested list column against reading all triplets
+    let file_reader =
+      SerializedFileReader::new(get_test_file("nested_lists.snappy.parquet")).unwrap();
+    let row_group_reader = file_reader.get_row_group(0).unwrap();
+    let descr = row_group_reader.metadata().column(0).column_descr_ptr();
+
+    let read_all = |iter: &mut TripletIter| {
+      let mut res = vec![];
+      while iter.has_next() {
+        res.push((iter.current_def_level(), iter.current_rep_level()));
+        iter.read_next().unwrap();
+      }
+      res
+    };
+
+    let reader = row_group_reader.get_column_reader(0).unwrap();
+    let mut iter = TripletIter::new(descr.clone(), reader, 128);
+    iter.read_next().unwrap();
+    let levels = read_all(&mut iter);
+    let record_starts = levels.iter().enumerate()
+      .filter(|&(_, &(_, rep))| rep == 0)
+      .map(|(i, _)| i)
+      .collect::<Vec<_>>();
+
+    for batch_size in 1..10 {
+      for num_skipped in 0..record_starts.len() + 2 {
+        for &buffered in &[false, true] {
+          let reader = row_group_reader.get_column_reader(0).unwrap();
+          let mut iter = TripletIter::new(descr.clone(), reader, batch_size);
+          if buffered {
+            iter.read_next().unwrap();
+          }
+          let skipped = iter.skip_records(num_skipped).unwrap();
+          if !buffered {
+            iter.read_next().unwrap();
+          }
+          assert_eq!(skipped, ::std::cmp::min(num_skipped, record_starts.len()));
+          let expected = if skipped < record_starts.len() {
+            &levels[record_starts[skipped]..]
+          } else {
+            &levels[levels.len()..]
+          };
+          assert_eq!(&read_all(&mut iter)[..], expected,
+            "batch size: {}, skipped: {}, buffered: {}",
+            batch_size, num_skipped, buffered);
+        }
+      }
+    }
+  }
+
   // Check triplet iterator across different batch sizes
   fn test_triplet_iter(
     file_name: &str,
@@ -515,7 +662,7 @@ mod tests {
     while let Ok(true) = iter.read_next() {
       assert!(iter.has_next());
       if !iter.is_null() {
-        values.push(iter.current_value());
+        values.push(iter.current_value().unwrap());
       }
       def_levels.push(iter.current_def_level());
       rep_levels.push(iter.current_rep_level());
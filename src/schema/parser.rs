@@ -44,7 +44,7 @@
 
 use std::rc::Rc;
 
-use basic::{LogicalType, Repetition, Type as PhysicalType};
+use basic::{LogicalType, Repetition, TimeUnit, Type as PhysicalType};
 use errors::{ParquetError, Result};
 use schema::types::{Type, TypePtr};
 
@@ -263,7 +263,18 @@ impl<'a> Parser<'a> {
     let (logical_type, precision, scale) = if let Some("(") = self.tokenizer.next() {
       let tpe = self.tokenizer.next()
         .ok_or(general_err!("Expected logical type, found None"))
-        .and_then(|v| v.to_uppercase().parse::<LogicalType>())?;
+        .map(|v| v.to_uppercase())?;
+      let tpe = match tpe.as_str() {
+        "TIME" => {
+          let (unit, is_adjusted_to_utc) = self.parse_time_unit(&tpe)?;
+          LogicalType::time(unit, is_adjusted_to_utc)
+        },
+        "TIMESTAMP" => {
+          let (unit, is_adjusted_to_utc) = self.parse_time_unit(&tpe)?;
+          LogicalType::timestamp(unit, is_adjusted_to_utc)
+        },
+        _ => tpe.parse::<LogicalType>()?
+      };
 
       // Parse precision and scale for decimals
       let mut precision: i32 = -1;
@@ -322,6 +333,23 @@ impl<'a> Parser<'a> {
     }
     Ok(builder.build()?)
   }
+
+  // Parses unit and UTC adjustment of TIME or TIMESTAMP logical type, e.g.
+  // (NANOS, false).
+  fn parse_time_unit(&mut self, logical_type: &str) -> Result<(TimeUnit, bool)> {
+    assert_token(self.tokenizer.next(), "(")?;
+    let unit = self.tokenizer.next()
+      .ok_or(general_err!("Expected unit, found None"))
+      .and_then(|v| v.to_uppercase().parse::<TimeUnit>())?;
+    assert_token(self.tokenizer.next(), ",")?;
+    let is_adjusted_to_utc = self.tokenizer.next()
+      .ok_or(general_err!("Expected UTC adjustment, found None"))
+      .and_then(|v| v.to_lowercase().parse::<bool>().map_err(|_| {
+        general_err!("Failed to parse UTC adjustment for {} type", logical_type)
+      }))?;
+    assert_token(self.tokenizer.next(), ")")?;
+    Ok((unit, is_adjusted_to_utc))
+  }
 }
 
 
@@ -531,6 +559,69 @@ mod tests {
     assert!(result.is_ok());
   }
 
+  #[test]
+  fn test_parse_message_type_timestamp() {
+    let schema = "
+    message root {
+      required int64 f1 (TIMESTAMP(MILLIS, true));
+      required int64 f2 (timestamp(micros,false));
+      optional int64 f3 (TIMESTAMP(NANOS,true));
+    }
+    ";
+    let mut iter = Tokenizer::from_str(schema);
+    let message = Parser { tokenizer: &mut iter }.parse_message_type().unwrap();
+    let fields = message.get_fields();
+    assert_eq!(fields[0].get_basic_info().logical_type(), LogicalType::TIMESTAMP_MILLIS);
+    assert_eq!(
+      fields[1].get_basic_info().logical_type(),
+      LogicalType::timestamp(TimeUnit::MICROS, false)
+    );
+    assert_eq!(
+      fields[2].get_basic_info().logical_type(),
+      LogicalType::timestamp(TimeUnit::NANOS, true)
+    );
+
+    // Invalid unit, missing UTC adjustment and invalid UTC adjustment
+    let invalid = ["TIMESTAMP(SECONDS,true)", "TIMESTAMP(NANOS)", "TIMESTAMP(NANOS,1)"];
+    for logical_type in &invalid {
+      let schema = format!("message root {{ required int64 f1 ({}); }}", logical_type);
+      let mut iter = Tokenizer::from_str(&schema);
+      let result = Parser { tokenizer: &mut iter }.parse_message_type();
+      assert!(result.is_err());
+    }
+  }
+
+  #[test]
+  fn test_parse_message_type_time() {
+    let schema = "
+    message root {
+      required int32 f1 (TIME(MILLIS, true));
+      required int32 f2 (time(millis,false));
+      optional int64 f3 (TIME(NANOS,true));
+    }
+    ";
+    let mut iter = Tokenizer::from_str(schema);
+    let message = Parser { tokenizer: &mut iter }.parse_message_type().unwrap();
+    let fields = message.get_fields();
+    assert_eq!(fields[0].get_basic_info().logical_type(), LogicalType::TIME_MILLIS);
+    assert_eq!(
+      fields[1].get_basic_info().logical_type(),
+      LogicalType::time(TimeUnit::MILLIS, false)
+    );
+    assert_eq!(
+      fields[2].get_basic_info().logical_type(),
+      LogicalType::time(TimeUnit::NANOS, true)
+    );
+
+    let schema = "message root { required int64 f1 (TIME(NANOS,yes)); }";
+    let mut iter = Tokenizer::from_str(schema);
+    let result = Parser { tokenizer: &mut iter }.parse_message_type();
+    assert_eq!(
+      result.unwrap_err(),
+      general_err!("Failed to parse UTC adjustment for TIME type")
+    );
+  }
+
   #[test]
   fn test_parse_message_type_compare_1() {
     let schema = "
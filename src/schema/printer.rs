@@ -146,6 +146,16 @@ fn print_column_chunk_metadata(out: &mut io::Write, cc_metadata: &ColumnChunkMet
     Some(stats) => stats.to_string()
   };
   writeln!(out, "statistics: {}", statistics_str);
+  let encoding_stats_str = match cc_metadata.encoding_stats() {
+    None => "N/A".to_owned(),
+    Some(encoding_stats) => {
+      let stats_strs: Vec<_> = encoding_stats.iter()
+        .map(|s| format!("{}/{}: {}", s.page_type, s.encoding, s.count))
+        .collect();
+      stats_strs.join(", ")
+    }
+  };
+  writeln!(out, "page encoding stats: {}", encoding_stats_str);
   writeln!(out, "");
 }
 
@@ -254,7 +264,7 @@ mod tests {
   use std::rc::Rc;
 
   use super::*;
-  use basic::{Repetition, Type as PhysicalType};
+  use basic::{Repetition, TimeUnit, Type as PhysicalType};
   use schema::parser::parse_message_type;
   use schema::types::Type;
 
@@ -440,4 +450,35 @@ mod tests {
 
     assert_print_parse_message(message);
   }
+
+  #[test]
+  fn test_print_and_parse_timestamp() {
+    let mut fields = vec![];
+    for &(unit, is_adjusted_to_utc) in &[
+      (TimeUnit::MILLIS, true),
+      (TimeUnit::MICROS, false),
+      (TimeUnit::NANOS, true),
+      (TimeUnit::NANOS, false)
+    ] {
+      let name = format!("{}_{}", unit, is_adjusted_to_utc);
+      let field = Type::primitive_type_builder(&name, PhysicalType::INT64)
+        .with_repetition(Repetition::REQUIRED)
+        .with_logical_type(LogicalType::timestamp(unit, is_adjusted_to_utc))
+        .build().unwrap();
+      fields.push(Rc::new(field));
+    }
+
+    let mut s = String::new();
+    {
+      let mut p = Printer::new(&mut s);
+      p.print(&fields[3]);
+    }
+    assert_eq!(&mut s, "REQUIRED INT64 NANOS_false (TIMESTAMP(NANOS,false));");
+
+    let message = Type::group_type_builder("schema")
+      .with_fields(&mut fields)
+      .build().unwrap();
+
+    assert_print_parse_message(message);
+  }
 }
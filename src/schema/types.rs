@@ -22,9 +22,13 @@ use std::convert::From;
 use std::fmt;
 use std::rc::Rc;
 
-use basic::{LogicalType, Repetition, Type as PhysicalType};
+use basic::{LogicalType, Repetition, TimeUnit, Type as PhysicalType};
 use errors::{ParquetError, Result};
-use parquet_format::SchemaElement;
+use parquet_format::{self as parquet, SchemaElement};
+use util::thrift::{
+  logical_type_placeholder, logical_type_to_member_id, time_unit_to_member_id,
+  time_unit_to_thrift, UnknownEnums
+};
 
 // ----------------------------------------------------------------------
 // Parquet Type definitions
@@ -331,16 +335,43 @@ impl<'a> PrimitiveTypeBuilder<'a> {
           return Err(general_err!("{} can only annotate INT64", self.logical_type));
         }
       }
+      LogicalType::TIMESTAMP { .. } => {
+        if self.physical_type != PhysicalType::INT64 {
+          return Err(general_err!("TIMESTAMP can only annotate INT64"));
+        }
+      }
+      LogicalType::TIME { unit: TimeUnit::MILLIS, .. } => {
+        if self.physical_type != PhysicalType::INT32 {
+          return Err(general_err!("{} can only annotate INT32", self.logical_type));
+        }
+      }
+      LogicalType::TIME { .. } => {
+        if self.physical_type != PhysicalType::INT64 {
+          return Err(general_err!("{} can only annotate INT64", self.logical_type));
+        }
+      }
       LogicalType::INTERVAL => {
         if self.physical_type != PhysicalType::FIXED_LEN_BYTE_ARRAY || self.length != 12 {
           return Err(general_err!("INTERVAL can only annotate FIXED_LEN_BYTE_ARRAY(12)"));
         }
       }
+      LogicalType::UUID => {
+        if self.physical_type != PhysicalType::FIXED_LEN_BYTE_ARRAY || self.length != 16 {
+          return Err(general_err!("UUID can only annotate FIXED_LEN_BYTE_ARRAY(16)"));
+        }
+      }
+      LogicalType::FLOAT16 => {
+        if self.physical_type != PhysicalType::FIXED_LEN_BYTE_ARRAY || self.length != 2 {
+          return Err(general_err!("FLOAT16 can only annotate FIXED_LEN_BYTE_ARRAY(2)"));
+        }
+      }
       LogicalType::ENUM => {
         if self.physical_type != PhysicalType::BYTE_ARRAY {
           return Err(general_err!("ENUM can only annotate BYTE_ARRAY fields"));
         }
       }
+      // Converted and logical types of newer versions of Parquet are not validated
+      LogicalType::Unknown(_) | LogicalType::Other(_) => {},
       _ => {
         return Err(general_err!(
           "{} cannot be applied to a primitive type",
@@ -611,6 +642,24 @@ impl ColumnDescriptor {
     self.primitive_type.get_basic_info().logical_type()
   }
 
+  /// Returns `true` if values of this column are UTF-8 strings, i.e. values with UTF8,
+  /// ENUM or JSON logical type.
+  pub fn is_string(&self) -> bool {
+    match self.logical_type() {
+      LogicalType::UTF8 | LogicalType::ENUM | LogicalType::JSON => true,
+      _ => false
+    }
+  }
+
+  /// Returns `true` if values of this column are embedded documents, i.e. values with
+  /// JSON or BSON logical type.
+  pub fn is_document(&self) -> bool {
+    match self.logical_type() {
+      LogicalType::JSON | LogicalType::BSON => true,
+      _ => false
+    }
+  }
+
   /// Returns physical type for this column.
   /// Note that it will panic if called on a non-primitive type.
   pub fn physical_type(&self) -> PhysicalType {
@@ -734,10 +783,42 @@ impl SchemaDescriptor {
     self.schema.as_ref()
   }
 
+  /// Returns reference counted pointer to schema, e.g. to create a file writer with the
+  /// same schema.
+  pub fn root_schema_ptr(&self) -> TypePtr {
+    self.schema.clone()
+  }
+
   /// Returns schema name.
   pub fn name(&self) -> &str {
     self.schema.name()
   }
+
+  /// Returns error if leaf columns of `other` are different from leaf columns of this
+  /// schema, i.e. data of one schema cannot be read with the other one. Columns are
+  /// compared by path, type, repetition and max levels; schema names are ignored.
+  pub fn check_compatible(&self, other: &SchemaDescriptor) -> Result<()> {
+    if self.num_columns() != other.num_columns() {
+      return Err(general_err!(
+        "Schema with {} columns does not match schema with {} columns",
+        other.num_columns(),
+        self.num_columns()
+      ));
+    }
+    for (column, other_column) in self.leaves.iter().zip(other.leaves.iter()) {
+      if column.path() != other_column.path() ||
+          column.self_type() != other_column.self_type() ||
+          column.max_def_level() != other_column.max_def_level() ||
+          column.max_rep_level() != other_column.max_rep_level() {
+        return Err(general_err!(
+          "Column {} does not match column {} of schema",
+          other_column.path().string(),
+          column.path().string()
+        ));
+      }
+    }
+    Ok(())
+  }
 }
 
 fn build_tree(
@@ -792,12 +873,229 @@ fn build_tree(
   }
 }
 
+/// Validates `schema` before it is written, returns error with the path of the first
+/// invalid field, if any. The following is checked:
+/// - root is a group type that is not repeated;
+/// - `FIXED_LEN_BYTE_ARRAY` fields have positive length;
+/// - precision and scale of `DECIMAL` fields fit their physical type;
+/// - `MAP` groups contain a single repeated group with a required `key` field;
+/// - field ids are non-negative and unique.
+pub fn validate_schema(schema: &Type) -> Result<()> {
+  if !schema.is_group() {
+    return Err(general_err!("Invalid schema: root {} is not a group", schema.name()));
+  }
+  let basic_info = schema.get_basic_info();
+  if basic_info.has_repetition() && basic_info.repetition() == Repetition::REPEATED {
+    return Err(general_err!("Invalid schema: root {} is repeated", schema.name()));
+  }
+  let mut ids = HashMap::new();
+  let mut path = Vec::new();
+  for field in schema.get_fields() {
+    validate_field(field, &mut path, &mut ids)?;
+  }
+  Ok(())
+}
+
+/// Validates `tp` and its fields, see [`validate_schema`](fn.validate_schema.html).
+/// `path` contains names of parents of the field and `ids` paths of fields by field id.
+fn validate_field(
+  tp: &Type,
+  path: &mut Vec<String>,
+  ids: &mut HashMap<i32, String>
+) -> Result<()> {
+  path.push(tp.name().to_owned());
+  let name = path.join(".");
+  let basic_info = tp.get_basic_info();
+
+  if basic_info.has_id() {
+    let id = basic_info.id();
+    if id < 0 {
+      return Err(general_err!("Invalid schema: field {} has negative id {}", name, id));
+    }
+    if let Some(other) = ids.insert(id, name.clone()) {
+      return Err(general_err!(
+        "Invalid schema: fields {} and {} have the same id {}", other, name, id));
+    }
+  }
+
+  match *tp {
+    Type::PrimitiveType { physical_type, type_length, scale, precision, .. } => {
+      if physical_type == PhysicalType::FIXED_LEN_BYTE_ARRAY && type_length <= 0 {
+        return Err(general_err!(
+          "Invalid schema: FIXED_LEN_BYTE_ARRAY field {} has invalid length {}",
+          name,
+          type_length
+        ));
+      }
+      if basic_info.logical_type() == LogicalType::DECIMAL {
+        let max_precision = match physical_type {
+          PhysicalType::INT32 => 9,
+          PhysicalType::INT64 => 18,
+          PhysicalType::FIXED_LEN_BYTE_ARRAY => {
+            (2f64.powi(8 * type_length - 1) - 1f64).log10().floor() as i32
+          },
+          _ => i32::max_value()
+        };
+        if precision < 1 || precision > max_precision || scale < 0 || scale >= precision {
+          return Err(general_err!(
+            "Invalid schema: DECIMAL field {} of type {} has invalid precision {} and \
+              scale {}",
+            name,
+            physical_type,
+            precision,
+            scale
+          ));
+        }
+      }
+    },
+    Type::GroupType { ref fields, .. } => {
+      if basic_info.logical_type() == LogicalType::MAP {
+        let has_key = fields.len() == 1 && fields[0].is_group() &&
+          fields[0].get_basic_info().repetition() == Repetition::REPEATED &&
+          fields[0].get_fields().first().map_or(false, |key| {
+            key.name() == "key" &&
+              key.get_basic_info().repetition() == Repetition::REQUIRED
+          });
+        if !has_key {
+          return Err(general_err!(
+            "Invalid schema: MAP field {} must contain a single repeated group with \
+              a required key field",
+            name
+          ));
+        }
+      }
+      for field in fields {
+        validate_field(field, path, ids)?;
+      }
+    }
+  }
+
+  path.pop();
+  Ok(())
+}
+
+/// Projection of a schema by field ids, see
+/// [`project_by_field_ids`](fn.project_by_field_ids.html).
+#[derive(Debug, PartialEq)]
+pub struct FieldIdProjection {
+  schema: Type,
+  fields: Vec<(i32, Option<ColumnPath>)>
+}
+
+impl FieldIdProjection {
+  /// Returns projected schema, which can be used as projection to read rows.
+  pub fn schema(&self) -> &Type {
+    &self.schema
+  }
+
+  /// Returns projected schema, consuming the projection.
+  pub fn into_schema(self) -> Type {
+    self.schema
+  }
+
+  /// Returns path of the field with id `id` in the schema, or `None` if the id is not
+  /// projected or there is no field with this id, e.g. the field has been added to the
+  /// table schema after the file was written.
+  pub fn field_path(&self, id: i32) -> Option<&ColumnPath> {
+    self.fields.iter()
+      .find(|&&(field_id, _)| field_id == id)
+      .and_then(|&(_, ref path)| path.as_ref())
+  }
+
+  /// Returns projected field ids that are not found in the schema, in order of
+  /// projected ids.
+  pub fn missing_ids(&self) -> Vec<i32> {
+    self.fields.iter()
+      .filter(|&&(_, ref path)| path.is_none())
+      .map(|&(id, _)| id)
+      .collect()
+  }
+}
+
+/// Returns projection of `schema` with fields whose ids are in `ids`, e.g. to read
+/// columns of a file by ids of a table schema, which are stable when fields are
+/// renamed. Projected group fields contain all of their fields, parents of projected
+/// nested fields contain only projected fields. Fields keep their order in `schema`.
+///
+/// Ids without a field in `schema` are reported by
+/// [`FieldIdProjection::missing_ids`](struct.FieldIdProjection.html#method.missing_ids).
+/// Returns error if several fields of `schema` have the same projected id.
+pub fn project_by_field_ids(schema: &Type, ids: &[i32]) -> Result<FieldIdProjection> {
+  let mut paths = HashMap::new();
+  let mut fields = Vec::new();
+  for field in schema.get_fields() {
+    let mut path = Vec::new();
+    if let Some(field) = project_field(field, ids, &mut path, &mut paths)? {
+      fields.push(field);
+    }
+  }
+  let schema = Type::group_type_builder(schema.name()).with_fields(&mut fields).build()?;
+  let fields = ids.iter().map(|id| (*id, paths.get(id).cloned())).collect();
+  Ok(FieldIdProjection { schema: schema, fields: fields })
+}
+
+/// Returns projection of `tp` by field ids, or `None` if neither the field nor any of
+/// its fields are projected, see [`project_by_field_ids`](fn.project_by_field_ids.html).
+/// `paths` contains paths of projected fields by field id.
+fn project_field(
+  tp: &TypePtr,
+  ids: &[i32],
+  path: &mut Vec<String>,
+  paths: &mut HashMap<i32, ColumnPath>
+) -> Result<Option<TypePtr>> {
+  path.push(tp.name().to_owned());
+  let basic_info = tp.get_basic_info();
+  let is_projected = basic_info.has_id() && ids.contains(&basic_info.id());
+  if is_projected {
+    let id = basic_info.id();
+    if let Some(other) = paths.insert(id, ColumnPath::new(path.clone())) {
+      return Err(general_err!(
+        "Fields {} and {} have the same id {}", other.string(), path.join("."), id));
+    }
+  }
+
+  let mut fields = Vec::new();
+  if tp.is_group() {
+    for field in tp.get_fields() {
+      if let Some(field) = project_field(field, ids, path, paths)? {
+        fields.push(field);
+      }
+    }
+  }
+  path.pop();
+
+  if is_projected {
+    Ok(Some(tp.clone()))
+  } else if fields.is_empty() {
+    Ok(None)
+  } else {
+    let mut builder = Type::group_type_builder(tp.name())
+      .with_repetition(basic_info.repetition())
+      .with_logical_type(basic_info.logical_type())
+      .with_fields(&mut fields);
+    if basic_info.has_id() {
+      builder = builder.with_id(basic_info.id());
+    }
+    Ok(Some(Rc::new(builder.build()?)))
+  }
+}
+
 /// Method to convert from Thrift.
 pub fn from_thrift(elements: &[SchemaElement]) -> Result<TypePtr> {
+  from_thrift_with_unknown_enums(elements, &UnknownEnums::default())
+}
+
+/// Method to convert from Thrift, where `unknown_enums` contains enum values of schema
+/// elements that are not known to the Thrift definition, with paths relative to
+/// `elements`.
+pub fn from_thrift_with_unknown_enums(
+  elements: &[SchemaElement],
+  unknown_enums: &UnknownEnums
+) -> Result<TypePtr> {
   let mut index = 0;
   let mut schema_nodes = Vec::new();
   while index < elements.len() {
-    let t = from_thrift_helper(elements, index)?;
+    let t = from_thrift_helper(elements, index, unknown_enums)?;
     index = t.0;
     schema_nodes.push(t.1);
   }
@@ -817,7 +1115,8 @@ pub fn from_thrift(elements: &[SchemaElement]) -> Result<TypePtr> {
 /// The second result is the result Type.
 fn from_thrift_helper(
   elements: &[SchemaElement],
-  index: usize
+  index: usize,
+  unknown_enums: &UnknownEnums
 ) -> Result<(usize, TypePtr)> {
   if index > elements.len() {
     return Err(general_err!(
@@ -825,7 +1124,13 @@ fn from_thrift_helper(
       index, elements.len()
     ));
   }
-  let logical_type = LogicalType::from(elements[index].converted_type);
+  // Logical types unknown to `parquet_format` and logical types of the union take
+  // precedence, since they are not always written with a converted type
+  let logical_type = unknown_enums.logical_type_member(&[index, 10])
+    .or_else(|| logical_type_from_thrift(&elements[index], index, unknown_enums))
+    .unwrap_or_else(|| {
+      unknown_enums.logical_type(&[index, 6], elements[index].converted_type)
+    });
   let field_id = elements[index].field_id;
   match elements[index].num_children {
     None => {
@@ -856,7 +1161,8 @@ fn from_thrift_helper(
       let mut fields = vec![];
       let mut next_index = index + 1;
       for _ in 0..n {
-        let child_result = from_thrift_helper(elements, next_index as usize)?;
+        let child_result =
+          from_thrift_helper(elements, next_index as usize, unknown_enums)?;
         next_index = child_result.0;
         fields.push(child_result.1);
       }
@@ -875,6 +1181,29 @@ fn from_thrift_helper(
   }
 }
 
+/// Returns TIME, TIMESTAMP, ENUM, JSON or BSON logical type of `element` at `index`, if
+/// any. Times and timestamps with an unknown unit are ignored.
+fn logical_type_from_thrift(
+  element: &SchemaElement,
+  index: usize,
+  unknown_enums: &UnknownEnums
+) -> Option<LogicalType> {
+  match element.logical_type {
+    Some(parquet::LogicalType::TIME(ref time)) => {
+      unknown_enums.time_unit(&[index, 10, 7, 2], &time.unit)
+        .map(|unit| LogicalType::time(unit, time.is_adjusted_to_u_t_c))
+    },
+    Some(parquet::LogicalType::TIMESTAMP(ref timestamp)) => {
+      unknown_enums.time_unit(&[index, 10, 8, 2], &timestamp.unit)
+        .map(|unit| LogicalType::timestamp(unit, timestamp.is_adjusted_to_u_t_c))
+    },
+    Some(parquet::LogicalType::ENUM(_)) => Some(LogicalType::ENUM),
+    Some(parquet::LogicalType::JSON(_)) => Some(LogicalType::JSON),
+    Some(parquet::LogicalType::BSON(_)) => Some(LogicalType::BSON),
+    _ => None
+  }
+}
+
 /// Method to convert to Thrift.
 pub fn to_thrift(schema: &Type) -> Result<Vec<SchemaElement>> {
   if !schema.is_group() {
@@ -885,6 +1214,61 @@ pub fn to_thrift(schema: &Type) -> Result<Vec<SchemaElement>> {
   Ok(elements)
 }
 
+/// Returns field ids and fields of members of the logical type union of elements
+/// returned by [`to_thrift`](fn.to_thrift.html) that are unknown to `parquet_format`,
+/// with paths relative to the elements. Elements with such logical types are converted
+/// with a placeholder, which should be replaced when writing with
+/// [`TolerantOutputProtocol`](`::util::thrift::TolerantOutputProtocol`).
+pub fn to_thrift_unknown_enums(schema: &Type) -> UnknownEnums {
+  let mut unknown_enums = UnknownEnums::default();
+  let mut stack = vec![schema];
+  let mut index = 0;
+  while let Some(tpe) = stack.pop() {
+    let logical_type = tpe.get_basic_info().logical_type();
+    if let Some(id) = logical_type_to_member_id(logical_type) {
+      unknown_enums.insert(vec![index, 10], id as i32);
+    }
+    if let LogicalType::Other(raw) = logical_type {
+      if !raw.data().is_empty() {
+        unknown_enums.insert_member(vec![index, 10], raw.data().to_vec());
+      }
+    }
+    // Units are kept at the unit field of TIME and TIMESTAMP members respectively
+    let time_unit = match logical_type {
+      LogicalType::TIME { unit, .. } => Some((7, unit)),
+      LogicalType::TIMESTAMP { unit, .. } => Some((8, unit)),
+      _ => None
+    };
+    if let Some((member_id, unit)) = time_unit {
+      if let Some(id) = time_unit_to_member_id(unit) {
+        unknown_enums.insert(vec![index, 10, member_id, 2], id as i32);
+      }
+    }
+    if let Type::GroupType { ref fields, .. } = *tpe {
+      stack.extend(fields.iter().rev().map(|field| field.as_ref()));
+    }
+    index += 1;
+  }
+  unknown_enums
+}
+
+/// Returns Thrift logical type for `logical_type`, only logical types without converted
+/// type, times and timestamps, which need the UTC adjustment, are written.
+fn logical_type_to_thrift(logical_type: LogicalType) -> Option<parquet::LogicalType> {
+  match logical_type {
+    LogicalType::TIME { unit, is_adjusted_to_utc } => {
+      let time = parquet::TimeType::new(is_adjusted_to_utc, time_unit_to_thrift(unit));
+      Some(parquet::LogicalType::TIME(time))
+    },
+    LogicalType::TIMESTAMP { unit, is_adjusted_to_utc } => {
+      let timestamp =
+        parquet::TimestampType::new(is_adjusted_to_utc, time_unit_to_thrift(unit));
+      Some(parquet::LogicalType::TIMESTAMP(timestamp))
+    },
+    _ => logical_type_to_member_id(logical_type).map(|_| logical_type_placeholder())
+  }
+}
+
 /// Constructs list of `SchemaElement` from the schema using depth-first traversal.
 /// Here we assume that schema is always valid and starts with group type.
 fn to_thrift_helper(schema: &Type, elements: &mut Vec<SchemaElement>) {
@@ -906,7 +1290,7 @@ fn to_thrift_helper(schema: &Type, elements: &mut Vec<SchemaElement>) {
         scale: if scale >= 0 { Some(scale) } else { None },
         precision: if precision >= 0 { Some(precision) } else { None },
         field_id: if basic_info.has_id() { Some(basic_info.id()) } else { None },
-        logical_type: None
+        logical_type: logical_type_to_thrift(basic_info.logical_type())
       };
 
       elements.push(element);
@@ -928,7 +1312,7 @@ fn to_thrift_helper(schema: &Type, elements: &mut Vec<SchemaElement>) {
         scale: None,
         precision: None,
         field_id: if basic_info.has_id() { Some(basic_info.id()) } else { None },
-        logical_type: None
+        logical_type: logical_type_to_thrift(basic_info.logical_type())
       };
 
       elements.push(element);
@@ -947,6 +1331,7 @@ mod tests {
   use super::*;
   use std::error::Error;
   use schema::parser::parse_message_type;
+  use basic::RawLogicalType;
 
   #[test]
   fn test_primitive_type() {
@@ -1103,6 +1488,33 @@ mod tests {
       assert_eq!(e.description(), "TIME_MICROS can only annotate INT64");
     }
 
+    result = Type::primitive_type_builder("foo", PhysicalType::INT96)
+      .with_repetition(Repetition::REQUIRED)
+      .with_logical_type(LogicalType::timestamp(TimeUnit::NANOS, true))
+      .build();
+    assert!(result.is_err());
+    if let Err(e) = result {
+      assert_eq!(e.description(), "TIMESTAMP can only annotate INT64");
+    }
+
+    result = Type::primitive_type_builder("foo", PhysicalType::INT64)
+      .with_repetition(Repetition::REQUIRED)
+      .with_logical_type(LogicalType::time(TimeUnit::MILLIS, false))
+      .build();
+    assert!(result.is_err());
+    if let Err(e) = result {
+      assert_eq!(e.description(), "TIME(MILLIS,false) can only annotate INT32");
+    }
+
+    result = Type::primitive_type_builder("foo", PhysicalType::INT32)
+      .with_repetition(Repetition::REQUIRED)
+      .with_logical_type(LogicalType::time(TimeUnit::NANOS, true))
+      .build();
+    assert!(result.is_err());
+    if let Err(e) = result {
+      assert_eq!(e.description(), "TIME(NANOS,true) can only annotate INT64");
+    }
+
     result = Type::primitive_type_builder("foo", PhysicalType::BYTE_ARRAY)
       .with_repetition(Repetition::REQUIRED)
       .with_logical_type(LogicalType::INTERVAL)
@@ -1122,6 +1534,26 @@ mod tests {
       assert_eq!(e.description(), "INTERVAL can only annotate FIXED_LEN_BYTE_ARRAY(12)");
     }
 
+    result = Type::primitive_type_builder("foo", PhysicalType::FIXED_LEN_BYTE_ARRAY)
+      .with_repetition(Repetition::REQUIRED)
+      .with_logical_type(LogicalType::UUID)
+      .with_length(12)
+      .build();
+    assert!(result.is_err());
+    if let Err(e) = result {
+      assert_eq!(e.description(), "UUID can only annotate FIXED_LEN_BYTE_ARRAY(16)");
+    }
+
+    result = Type::primitive_type_builder("foo", PhysicalType::FIXED_LEN_BYTE_ARRAY)
+      .with_repetition(Repetition::REQUIRED)
+      .with_logical_type(LogicalType::FLOAT16)
+      .with_length(4)
+      .build();
+    assert!(result.is_err());
+    if let Err(e) = result {
+      assert_eq!(e.description(), "FLOAT16 can only annotate FIXED_LEN_BYTE_ARRAY(2)");
+    }
+
     result = Type::primitive_type_builder("foo", PhysicalType::INT32)
       .with_repetition(Repetition::REQUIRED)
       .with_logical_type(LogicalType::ENUM)
@@ -1220,6 +1652,19 @@ mod tests {
     assert_eq!(descr.type_precision(), -1);
     assert_eq!(descr.type_scale(), -1);
     assert_eq!(descr.root_type(), root_tp_rc.as_ref());
+    assert!(descr.is_string());
+    assert!(!descr.is_document());
+
+    for &(logical_type, is_string) in
+      &[(LogicalType::ENUM, true), (LogicalType::JSON, true), (LogicalType::BSON, false)]
+    {
+      let tp = Type::primitive_type_builder("doc", PhysicalType::BYTE_ARRAY)
+        .with_logical_type(logical_type)
+        .build()?;
+      let descr = ColumnDescriptor::new(Rc::new(tp), None, 0, 0, ColumnPath::from("doc"));
+      assert_eq!(descr.is_string(), is_string);
+      assert_eq!(descr.is_document(), logical_type != LogicalType::ENUM);
+    }
 
     Ok(())
   }
@@ -1317,6 +1762,185 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_schema_descriptor_check_compatible() {
+    let descr = |message_type: &str| {
+      SchemaDescriptor::new(Rc::new(parse_message_type(message_type).unwrap()))
+    };
+    let schema = descr("message schema { REQUIRED INT32 a; OPTIONAL BYTE_ARRAY b; }");
+
+    let other = descr("message other { REQUIRED INT32 a; OPTIONAL BYTE_ARRAY b; }");
+    assert!(schema.check_compatible(&other).is_ok());
+
+    let other = descr("message schema { REQUIRED INT32 a; }");
+    assert_eq!(
+      schema.check_compatible(&other).unwrap_err(),
+      general_err!("Schema with 1 columns does not match schema with 2 columns")
+    );
+
+    let other = descr("message schema { REQUIRED INT32 a; OPTIONAL BYTE_ARRAY c; }");
+    assert_eq!(
+      schema.check_compatible(&other).unwrap_err(),
+      general_err!("Column c does not match column b of schema")
+    );
+
+    let other = descr("message schema { REQUIRED INT32 a; REQUIRED BYTE_ARRAY b; }");
+    assert_eq!(
+      schema.check_compatible(&other).unwrap_err(),
+      general_err!("Column b does not match column b of schema")
+    );
+
+    let other =
+      descr("message schema { REQUIRED INT32 a; OPTIONAL BYTE_ARRAY b (UTF8); }");
+    assert!(schema.check_compatible(&other).is_err());
+  }
+
+  #[test]
+  fn test_validate_schema() {
+    let validate = |message_type: &str| {
+      validate_schema(&parse_message_type(message_type).unwrap())
+    };
+
+    assert!(validate("
+      message schema {
+        REQUIRED INT32 a = 1;
+        OPTIONAL FIXED_LEN_BYTE_ARRAY (16) b (DECIMAL(20, 2)) = 2;
+        OPTIONAL group m (MAP) = 3 {
+          REPEATED group key_value {
+            REQUIRED BYTE_ARRAY key (UTF8);
+            OPTIONAL INT32 value;
+          }
+        }
+      }
+    ").is_ok());
+
+    assert_eq!(
+      validate("
+        message schema {
+          REQUIRED INT32 a = 1;
+          OPTIONAL group b { REQUIRED INT32 c = 1; }
+        }
+      ").unwrap_err(),
+      general_err!("Invalid schema: fields a and b.c have the same id 1")
+    );
+    assert_eq!(
+      validate("message schema { REQUIRED INT32 a = -1; }").unwrap_err(),
+      general_err!("Invalid schema: field a has negative id -1")
+    );
+    assert_eq!(
+      validate("
+        message schema {
+          OPTIONAL group m (MAP) {
+            REPEATED group key_value { OPTIONAL INT32 value; }
+          }
+        }
+      ").unwrap_err(),
+      general_err!(
+        "Invalid schema: MAP field m must contain a single repeated group with a \
+          required key field")
+    );
+
+    let primitive = |physical_type, logical_type, type_length, precision| {
+      Rc::new(Type::PrimitiveType {
+        basic_info: BasicTypeInfo {
+          name: "a".to_owned(),
+          repetition: Some(Repetition::REQUIRED),
+          logical_type: logical_type,
+          id: None
+        },
+        physical_type: physical_type,
+        type_length: type_length,
+        scale: 0,
+        precision: precision
+      })
+    };
+    let schema = |field| {
+      Type::group_type_builder("schema")
+        .with_fields(&mut vec![field])
+        .build()
+        .unwrap()
+    };
+    let field = primitive(PhysicalType::FIXED_LEN_BYTE_ARRAY, LogicalType::NONE, -1, 0);
+    assert_eq!(
+      validate_schema(&schema(field)).unwrap_err(),
+      general_err!("Invalid schema: FIXED_LEN_BYTE_ARRAY field a has invalid length -1")
+    );
+    let field = primitive(PhysicalType::INT64, LogicalType::DECIMAL, 0, 19);
+    assert_eq!(
+      validate_schema(&schema(field)).unwrap_err(),
+      general_err!(
+        "Invalid schema: DECIMAL field a of type INT64 has invalid precision 19 and \
+          scale 0")
+    );
+
+    let root = Type::group_type_builder("schema")
+      .with_repetition(Repetition::REPEATED)
+      .build()
+      .unwrap();
+    assert_eq!(
+      validate_schema(&root).unwrap_err(),
+      general_err!("Invalid schema: root schema is repeated")
+    );
+  }
+
+  #[test]
+  fn test_project_by_field_ids() {
+    let schema = parse_message_type("
+      message schema {
+        REQUIRED INT32 a = 1;
+        OPTIONAL group b = 2 {
+          OPTIONAL INT32 c = 3;
+          OPTIONAL INT32 d = 4;
+        }
+        OPTIONAL group e (LIST) = 5 {
+          REPEATED group list {
+            OPTIONAL INT32 element = 6;
+          }
+        }
+      }
+    ").unwrap();
+
+    let projection = project_by_field_ids(&schema, &[5, 10, 4, 6]).unwrap();
+    let expected = parse_message_type("
+      message schema {
+        OPTIONAL group b = 2 {
+          OPTIONAL INT32 d = 4;
+        }
+        OPTIONAL group e (LIST) = 5 {
+          REPEATED group list {
+            OPTIONAL INT32 element = 6;
+          }
+        }
+      }
+    ").unwrap();
+    assert_eq!(projection.schema(), &expected);
+    assert!(schema.check_contains(projection.schema()));
+    assert_eq!(projection.missing_ids(), vec![10]);
+    assert_eq!(
+      projection.field_path(4),
+      Some(&ColumnPath::new(vec!["b".to_owned(), "d".to_owned()]))
+    );
+    assert_eq!(
+      projection.field_path(6),
+      Some(&ColumnPath::new(
+        vec!["e".to_owned(), "list".to_owned(), "element".to_owned()]))
+    );
+    assert_eq!(projection.field_path(1), None);
+    assert_eq!(projection.field_path(10), None);
+
+    let projection = project_by_field_ids(&schema, &[]).unwrap();
+    assert_eq!(projection.into_schema().get_fields().len(), 0);
+
+    let schema = parse_message_type("
+      message schema { REQUIRED INT32 a = 1; OPTIONAL group b { OPTIONAL INT32 c = 1; } }
+    ").unwrap();
+    assert!(project_by_field_ids(&schema, &[2]).is_ok());
+    assert_eq!(
+      project_by_field_ids(&schema, &[1]).unwrap_err(),
+      general_err!("Fields a and b.c have the same id 1")
+    );
+  }
+
   #[test]
   fn test_schema_build_tree_def_rep_levels() {
     let message_type = "
@@ -1586,4 +2210,197 @@ mod tests {
     let result_schema = from_thrift(&thrift_schema).unwrap();
     assert_eq!(result_schema, Rc::new(expected_schema));
   }
+
+  #[test]
+  fn test_schema_type_thrift_conversion_logical_type_only() {
+    let message_type = "
+    message logical_types {
+      REQUIRED FIXED_LEN_BYTE_ARRAY (16) id (UUID);
+      OPTIONAL group nested {
+        OPTIONAL BYTE_ARRAY name (UTF8);
+        REPEATED FIXED_LEN_BYTE_ARRAY (16) refs (UUID);
+        OPTIONAL FIXED_LEN_BYTE_ARRAY (2) score (FLOAT16);
+      }
+    }
+    ";
+    let expected_schema = parse_message_type(message_type).unwrap();
+    let thrift_schema = to_thrift(&expected_schema).unwrap();
+    assert_eq!(thrift_schema[1].converted_type, None);
+    assert!(thrift_schema[1].logical_type.is_some());
+    assert!(thrift_schema[3].logical_type.is_none());
+
+    let unknown_enums = to_thrift_unknown_enums(&expected_schema);
+    assert_eq!(unknown_enums.get(&[1, 10]), Some(14));
+    assert_eq!(unknown_enums.get(&[4, 10]), Some(14));
+    assert_eq!(unknown_enums.get(&[3, 10]), None);
+    assert_eq!(unknown_enums.get(&[5, 10]), Some(15));
+
+    let result_schema =
+      from_thrift_with_unknown_enums(&thrift_schema, &unknown_enums).unwrap();
+    assert_eq!(result_schema, Rc::new(expected_schema));
+  }
+
+  #[test]
+  fn test_schema_type_thrift_conversion_other_logical_type() {
+    let field = Type::primitive_type_builder("a", PhysicalType::BYTE_ARRAY)
+      .with_logical_type(LogicalType::Other(RawLogicalType::new(16, &[21, 4])))
+      .build()
+      .unwrap();
+    let group = Type::group_type_builder("b")
+      .with_repetition(Repetition::OPTIONAL)
+      .with_logical_type(LogicalType::Other(RawLogicalType::new(17, &[])))
+      .with_fields(&mut vec![Rc::new(field)])
+      .build()
+      .unwrap();
+    let expected_schema = Type::group_type_builder("schema")
+      .with_fields(&mut vec![Rc::new(group)])
+      .build()
+      .unwrap();
+
+    let thrift_schema = to_thrift(&expected_schema).unwrap();
+    assert_eq!(thrift_schema[1].converted_type, None);
+    assert!(thrift_schema[1].logical_type.is_some());
+    assert!(thrift_schema[2].logical_type.is_some());
+
+    let unknown_enums = to_thrift_unknown_enums(&expected_schema);
+    assert_eq!(unknown_enums.get(&[1, 10]), Some(17));
+    assert_eq!(unknown_enums.member(&[1, 10]), None);
+    assert_eq!(unknown_enums.get(&[2, 10]), Some(16));
+    assert_eq!(unknown_enums.member(&[2, 10]), Some(&[21, 4][..]));
+
+    let result_schema =
+      from_thrift_with_unknown_enums(&thrift_schema, &unknown_enums).unwrap();
+    assert_eq!(result_schema, Rc::new(expected_schema));
+  }
+
+  #[test]
+  fn test_schema_type_thrift_conversion_timestamp() {
+    let message_type = "
+    message timestamps {
+      REQUIRED INT64 millis (TIMESTAMP_MILLIS);
+      REQUIRED INT64 micros (TIMESTAMP(MICROS,true));
+      REQUIRED INT64 local_millis (TIMESTAMP(MILLIS,false));
+      REQUIRED INT64 nanos (TIMESTAMP(NANOS,true));
+      OPTIONAL INT64 local_nanos (TIMESTAMP(NANOS,false));
+    }
+    ";
+    let expected_schema = parse_message_type(message_type).unwrap();
+    let fields = expected_schema.get_fields();
+    assert_eq!(fields[1].get_basic_info().logical_type(), LogicalType::TIMESTAMP_MICROS);
+    assert_eq!(
+      fields[2].get_basic_info().logical_type(),
+      LogicalType::TIMESTAMP { unit: TimeUnit::MILLIS, is_adjusted_to_utc: false }
+    );
+
+    let thrift_schema = to_thrift(&expected_schema).unwrap();
+    assert_eq!(
+      thrift_schema[1].converted_type,
+      Some(parquet::ConvertedType::TIMESTAMP_MILLIS)
+    );
+    assert!(thrift_schema[1].logical_type.is_none());
+    assert_eq!(thrift_schema[3].converted_type, None);
+    assert_eq!(
+      thrift_schema[3].logical_type,
+      Some(parquet::LogicalType::TIMESTAMP(parquet::TimestampType::new(
+        false,
+        parquet::TimeUnit::MILLIS(parquet::MilliSeconds::new())
+      )))
+    );
+    assert_eq!(thrift_schema[4].converted_type, None);
+    assert!(thrift_schema[4].logical_type.is_some());
+
+    let unknown_enums = to_thrift_unknown_enums(&expected_schema);
+    assert_eq!(unknown_enums.get(&[3, 10, 8, 2]), None);
+    assert_eq!(unknown_enums.get(&[4, 10, 8, 2]), Some(3));
+    assert_eq!(unknown_enums.get(&[5, 10, 8, 2]), Some(3));
+
+    let result_schema =
+      from_thrift_with_unknown_enums(&thrift_schema, &unknown_enums).unwrap();
+    assert_eq!(result_schema.as_ref(), &expected_schema);
+
+    // Without the unknown unit, NANOS is read as the placeholder unit
+    let result_schema = from_thrift(&thrift_schema).unwrap();
+    assert_eq!(
+      result_schema.get_fields()[3].get_basic_info().logical_type(),
+      LogicalType::TIMESTAMP_MILLIS
+    );
+
+    // Timestamps written with both converted and logical type
+    let mut thrift_schema = to_thrift(&expected_schema).unwrap();
+    let unit = parquet::TimeUnit::MILLIS(parquet::MilliSeconds::new());
+    thrift_schema[1].logical_type =
+      Some(parquet::LogicalType::TIMESTAMP(parquet::TimestampType::new(true, unit)));
+    let result_schema = from_thrift(&thrift_schema).unwrap();
+    assert_eq!(
+      result_schema.get_fields()[0].get_basic_info().logical_type(),
+      LogicalType::TIMESTAMP_MILLIS
+    );
+  }
+
+  #[test]
+  fn test_schema_type_thrift_conversion_time() {
+    let message_type = "
+    message times {
+      REQUIRED INT32 millis (TIME_MILLIS);
+      REQUIRED INT32 local_millis (TIME(MILLIS,false));
+      REQUIRED INT64 micros (TIME(MICROS,true));
+      OPTIONAL INT64 nanos (TIME(NANOS,true));
+      REQUIRED INT64 ts_nanos (TIMESTAMP(NANOS,true));
+    }
+    ";
+    let expected_schema = parse_message_type(message_type).unwrap();
+    let fields = expected_schema.get_fields();
+    assert_eq!(fields[2].get_basic_info().logical_type(), LogicalType::TIME_MICROS);
+
+    let thrift_schema = to_thrift(&expected_schema).unwrap();
+    assert_eq!(
+      thrift_schema[1].converted_type,
+      Some(parquet::ConvertedType::TIME_MILLIS)
+    );
+    assert!(thrift_schema[1].logical_type.is_none());
+    assert_eq!(thrift_schema[2].converted_type, None);
+    assert_eq!(
+      thrift_schema[2].logical_type,
+      Some(parquet::LogicalType::TIME(parquet::TimeType::new(
+        false,
+        parquet::TimeUnit::MILLIS(parquet::MilliSeconds::new())
+      )))
+    );
+
+    let unknown_enums = to_thrift_unknown_enums(&expected_schema);
+    assert_eq!(unknown_enums.get(&[4, 10, 7, 2]), Some(3));
+    assert_eq!(unknown_enums.get(&[4, 10, 8, 2]), None);
+    assert_eq!(unknown_enums.get(&[5, 10, 8, 2]), Some(3));
+
+    let result_schema =
+      from_thrift_with_unknown_enums(&thrift_schema, &unknown_enums).unwrap();
+    assert_eq!(result_schema, Rc::new(expected_schema));
+  }
+
+  #[test]
+  fn test_schema_type_thrift_conversion_documents() {
+    let message_type = "
+    message documents {
+      REQUIRED BYTE_ARRAY kind (ENUM);
+      OPTIONAL BYTE_ARRAY json (JSON);
+      OPTIONAL BYTE_ARRAY bson (BSON);
+    }
+    ";
+    let expected_schema = parse_message_type(message_type).unwrap();
+    let mut thrift_schema = to_thrift(&expected_schema).unwrap();
+    assert_eq!(thrift_schema[2].converted_type, Some(parquet::ConvertedType::JSON));
+
+    // Annotations are recognized when written only as logical type
+    let logical_types = vec![
+      parquet::LogicalType::ENUM(parquet::EnumType::new()),
+      parquet::LogicalType::JSON(parquet::JsonType::new()),
+      parquet::LogicalType::BSON(parquet::BsonType::new()),
+    ];
+    for (element, logical_type) in thrift_schema[1..].iter_mut().zip(logical_types) {
+      element.converted_type = None;
+      element.logical_type = Some(logical_type);
+    }
+    let result_schema = from_thrift(&thrift_schema).unwrap();
+    assert_eq!(result_schema, Rc::new(expected_schema));
+  }
 }
@@ -0,0 +1,438 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains generators of pages for each encoding and type, and a driver to measure
+//! decoding throughput of pages. This is used by benchmarks of this crate, and can be
+//! used to benchmark decoding in downstream projects.
+//!
+//! Values are generated with a seeded pseudo-random generator, so that the same pages
+//! are generated for the same arguments.
+//!
+//! # Example
+//!
+//! ```rust
+//! use parquet::basic::{Encoding, Type};
+//! use parquet::bench_util::{gen_pages, measure_decoding, column_descr};
+//! use parquet::data_type::Int32Type;
+//!
+//! let descr = column_descr(Type::INT32, 0);
+//! let encoding = Encoding::RLE_DICTIONARY;
+//! let pages = gen_pages::<Int32Type>(descr.clone(), encoding, 1024, 100, 42).unwrap();
+//! let measurement = measure_decoding::<Int32Type>(descr, &pages, 128, 10).unwrap();
+//! assert_eq!(measurement.num_values(), 10 * 1024);
+//! println!("{} values/s", measurement.values_per_second());
+//! ```
+
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use basic::{Encoding, Type};
+use column::page::Page;
+use data_type::*;
+use encodings::decoding::{get_decoder, Decoder, DecoderFactory, DictDecoder};
+use encodings::encoding::{get_encoder, DictEncoder, Encoder};
+use errors::{ParquetError, Result};
+use schema::types::{ColumnDescPtr, ColumnDescriptor, ColumnPath, Type as SchemaType};
+use util::memory::{ByteBufferPtr, MemTracker, MemTrackerPtr};
+
+/// Data type, values of which can be generated for benchmarks.
+pub trait ValueGenerator: DataType {
+  /// Returns value for `index`, different indexes map to different values where the
+  /// type allows it. `type_length` is the length of fixed length byte array values.
+  fn gen_value(index: u64, type_length: i32) -> Self::T;
+}
+
+impl ValueGenerator for BoolType {
+  fn gen_value(index: u64, _: i32) -> bool {
+    index % 2 == 1
+  }
+}
+
+impl ValueGenerator for Int32Type {
+  fn gen_value(index: u64, _: i32) -> i32 {
+    index as i32
+  }
+}
+
+impl ValueGenerator for Int64Type {
+  fn gen_value(index: u64, _: i32) -> i64 {
+    index as i64
+  }
+}
+
+impl ValueGenerator for Int96Type {
+  fn gen_value(index: u64, _: i32) -> Int96 {
+    let mut value = Int96::new();
+    value.set_data(index as u32, (index >> 32) as u32, 0);
+    value
+  }
+}
+
+impl ValueGenerator for FloatType {
+  fn gen_value(index: u64, _: i32) -> f32 {
+    index as f32 * 0.5
+  }
+}
+
+impl ValueGenerator for DoubleType {
+  fn gen_value(index: u64, _: i32) -> f64 {
+    index as f64 * 0.5
+  }
+}
+
+impl ValueGenerator for ByteArrayType {
+  fn gen_value(index: u64, _: i32) -> ByteArray {
+    // Values have different lengths and share prefixes, similar to identifiers
+    let padding = "x".repeat((mix(index) % 16) as usize);
+    ByteArray::from(format!("value_{}{}", index, padding).into_bytes())
+  }
+}
+
+impl ValueGenerator for FixedLenByteArrayType {
+  fn gen_value(index: u64, type_length: i32) -> ByteArray {
+    let mut value = Vec::with_capacity(type_length as usize);
+    let mut state = mix(index);
+    for i in 0..type_length {
+      if i % 8 == 0 && i > 0 {
+        state = mix(state);
+      }
+      value.push((state >> ((i % 8) * 8)) as u8);
+    }
+    ByteArray::from(value)
+  }
+}
+
+/// Returns descriptor of a required column of `physical_type`, with `type_length` for
+/// fixed length byte arrays.
+pub fn column_descr(physical_type: Type, type_length: i32) -> ColumnDescPtr {
+  let tpe = SchemaType::primitive_type_builder("col", physical_type)
+    .with_length(type_length)
+    .build()
+    .unwrap();
+  Rc::new(ColumnDescriptor::new(Rc::new(tpe), None, 0, 0, ColumnPath::from("col")))
+}
+
+/// Returns encodings of data pages that can be generated for `physical_type`.
+pub fn supported_encodings(physical_type: Type) -> Vec<Encoding> {
+  let mut encodings = vec![Encoding::PLAIN];
+  match physical_type {
+    Type::BOOLEAN => encodings.push(Encoding::RLE),
+    Type::INT32 | Type::INT64 => encodings.push(Encoding::DELTA_BINARY_PACKED),
+    Type::BYTE_ARRAY => {
+      encodings.push(Encoding::DELTA_LENGTH_BYTE_ARRAY);
+      encodings.push(Encoding::DELTA_BYTE_ARRAY);
+    },
+    Type::FIXED_LEN_BYTE_ARRAY => encodings.push(Encoding::DELTA_BYTE_ARRAY),
+    _ => {}
+  }
+  if physical_type != Type::BOOLEAN {
+    encodings.push(Encoding::RLE_DICTIONARY);
+  }
+  encodings
+}
+
+/// Generates `num_values` values, that are chosen from `num_distinct` distinct values
+/// with pseudo-random generator initialized with `seed`.
+pub fn gen_values<T: ValueGenerator>(
+  num_values: usize,
+  num_distinct: usize,
+  type_length: i32,
+  seed: u64
+) -> Vec<T::T> {
+  let num_distinct = ::std::cmp::max(num_distinct, 1) as u64;
+  let mut state = seed;
+  let mut values = Vec::with_capacity(num_values);
+  for _ in 0..num_values {
+    state = mix(state);
+    values.push(T::gen_value(state % num_distinct, type_length));
+  }
+  values
+}
+
+/// Generates pages of `num_values` values of column `descr` encoded with `encoding`,
+/// see [`gen_values`](fn.gen_values.html) for `num_distinct` and `seed`.
+///
+/// For `PLAIN_DICTIONARY` and `RLE_DICTIONARY` encodings a dictionary page is returned
+/// before the data page. Data pages are uncompressed and have no levels, so that only
+/// decoding of values is measured.
+pub fn gen_pages<T: ValueGenerator>(
+  descr: ColumnDescPtr,
+  encoding: Encoding,
+  num_values: usize,
+  num_distinct: usize,
+  seed: u64
+) -> Result<Vec<Page>> {
+  let values = gen_values::<T>(num_values, num_distinct, descr.type_length(), seed);
+  let mem_tracker = Arc::new(MemTracker::new());
+  let mut pages = Vec::new();
+  let buf = match encoding {
+    Encoding::PLAIN_DICTIONARY | Encoding::RLE_DICTIONARY => {
+      let mut encoder = DictEncoder::<T>::new(descr, mem_tracker);
+      encoder.put(&values)?;
+      pages.push(Page::DictionaryPage {
+        buf: encoder.write_dict()?,
+        num_values: encoder.num_entries() as u32,
+        encoding: Encoding::PLAIN,
+        is_sorted: false
+      });
+      encoder.flush_buffer()?
+    },
+    _ => {
+      let mut encoder = get_encoder::<T>(descr, encoding, mem_tracker)?;
+      encoder.put(&values)?;
+      encoder.flush_buffer()?
+    }
+  };
+  pages.push(Page::DataPage {
+    buf: buf,
+    num_values: num_values as u32,
+    encoding: encoding,
+    def_level_encoding: Encoding::RLE,
+    rep_level_encoding: Encoding::RLE,
+    statistics: None
+  });
+  Ok(pages)
+}
+
+/// Decodes values of `pages` of column `descr` in batches of `batch_size` values,
+/// returns number of decoded values.
+///
+/// Column must be required and not repeated, e.g. pages generated with
+/// [`gen_pages`](fn.gen_pages.html).
+pub fn decode_pages<T: DataType>(
+  descr: ColumnDescPtr,
+  pages: &[Page],
+  batch_size: usize
+) -> Result<usize> {
+  if descr.max_def_level() > 0 || descr.max_rep_level() > 0 {
+    return Err(general_err!(
+      "Cannot decode pages of column {} with levels",
+      descr.path().string()
+    ));
+  }
+  if batch_size == 0 {
+    return Err(general_err!("Batch size must be positive"));
+  }
+  let mem_tracker = Arc::new(MemTracker::new());
+  let mut values = vec![T::T::default(); batch_size];
+  let mut dict_decoder: Option<DictDecoder<T>> = None;
+  let mut num_decoded = 0;
+  for page in pages {
+    let (buf, num_values, encoding) = match *page {
+      Page::DictionaryPage { ref buf, num_values, encoding, .. } => {
+        let factory = DecoderFactory::new(descr.clone(), mem_tracker.clone());
+        dict_decoder =
+          Some(factory.get_dict_decoder(encoding, buf.clone(), num_values as usize)?);
+        continue;
+      },
+      Page::DataPage { ref buf, num_values, encoding, .. } => {
+        (buf.clone(), num_values, encoding)
+      },
+      Page::DataPageV2 {
+        ref buf, num_values, encoding, def_levels_byte_len, rep_levels_byte_len, ..
+      } => {
+        let levels_len = (def_levels_byte_len + rep_levels_byte_len) as usize;
+        (buf.start_from(levels_len), num_values, encoding)
+      }
+    };
+    num_decoded +=
+      decode_values(&descr, &mem_tracker, &mut dict_decoder, buf, num_values, encoding,
+        &mut values)?;
+  }
+  Ok(num_decoded)
+}
+
+/// Decodes all values of a data page, returns number of decoded values.
+fn decode_values<T: DataType>(
+  descr: &ColumnDescPtr,
+  mem_tracker: &MemTrackerPtr,
+  dict_decoder: &mut Option<DictDecoder<T>>,
+  buf: ByteBufferPtr,
+  num_values: u32,
+  encoding: Encoding,
+  values: &mut [T::T]
+) -> Result<usize> {
+  let mut decoder: Box<Decoder<T>>;
+  let decoder: &mut Decoder<T> = match encoding {
+    Encoding::PLAIN_DICTIONARY | Encoding::RLE_DICTIONARY => {
+      match *dict_decoder {
+        Some(ref mut dict_decoder) => dict_decoder,
+        None => return Err(general_err!("Missing dictionary page"))
+      }
+    },
+    _ => {
+      decoder = get_decoder::<T>(descr.clone(), encoding, mem_tracker.clone())?;
+      &mut *decoder
+    }
+  };
+  decoder.set_data(buf, num_values as usize)?;
+  let mut num_decoded = 0;
+  loop {
+    let num_read = decoder.get(values)?;
+    num_decoded += num_read;
+    if num_read < values.len() || num_decoded == num_values as usize {
+      break;
+    }
+  }
+  Ok(num_decoded)
+}
+
+/// Result of measuring decoding throughput with
+/// [`measure_decoding`](fn.measure_decoding.html).
+#[derive(Clone, Debug)]
+pub struct DecodeMeasurement {
+  num_values: usize,
+  num_bytes: usize,
+  elapsed: Duration
+}
+
+impl DecodeMeasurement {
+  /// Returns total number of decoded values.
+  pub fn num_values(&self) -> usize {
+    self.num_values
+  }
+
+  /// Returns total size in bytes of decoded pages.
+  pub fn num_bytes(&self) -> usize {
+    self.num_bytes
+  }
+
+  /// Returns total time of decoding.
+  pub fn elapsed(&self) -> Duration {
+    self.elapsed
+  }
+
+  /// Returns number of decoded values per second.
+  pub fn values_per_second(&self) -> f64 {
+    self.num_values as f64 / self.elapsed_secs()
+  }
+
+  /// Returns number of bytes of encoded pages decoded per second.
+  pub fn bytes_per_second(&self) -> f64 {
+    self.num_bytes as f64 / self.elapsed_secs()
+  }
+
+  fn elapsed_secs(&self) -> f64 {
+    let secs =
+      self.elapsed.as_secs() as f64 + self.elapsed.subsec_nanos() as f64 * 1e-9;
+    // Avoid division by zero for measurements below timer resolution
+    secs.max(1e-9)
+  }
+}
+
+/// Decodes `pages` `num_iterations` times with [`decode_pages`](fn.decode_pages.html)
+/// and measures decoding throughput.
+pub fn measure_decoding<T: DataType>(
+  descr: ColumnDescPtr,
+  pages: &[Page],
+  batch_size: usize,
+  num_iterations: usize
+) -> Result<DecodeMeasurement> {
+  let page_bytes: usize = pages.iter().map(|page| page.buffer().len()).sum();
+  let mut num_values = 0;
+  let start = Instant::now();
+  for _ in 0..num_iterations {
+    num_values += decode_pages::<T>(descr.clone(), pages, batch_size)?;
+  }
+  Ok(DecodeMeasurement {
+    num_values: num_values,
+    num_bytes: page_bytes * num_iterations,
+    elapsed: start.elapsed()
+  })
+}
+
+/// Mixes bits of `value`, used as a step of pseudo-random generator (splitmix64).
+fn mix(value: u64) -> u64 {
+  let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+  z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+  z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+  z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn check_decoding<T: ValueGenerator>(physical_type: Type, type_length: i32) {
+    let descr = column_descr(physical_type, type_length);
+    for encoding in supported_encodings(physical_type) {
+      let pages = gen_pages::<T>(descr.clone(), encoding, 1000, 20, 7).unwrap();
+      for batch_size in vec![1, 7, 128, 2000] {
+        assert_eq!(
+          decode_pages::<T>(descr.clone(), &pages, batch_size).unwrap(),
+          1000,
+          "type: {}, encoding: {}, batch size: {}",
+          physical_type,
+          encoding,
+          batch_size
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn test_gen_and_decode_pages() {
+    check_decoding::<BoolType>(Type::BOOLEAN, 0);
+    check_decoding::<Int32Type>(Type::INT32, 0);
+    check_decoding::<Int64Type>(Type::INT64, 0);
+    check_decoding::<Int96Type>(Type::INT96, 0);
+    check_decoding::<FloatType>(Type::FLOAT, 0);
+    check_decoding::<DoubleType>(Type::DOUBLE, 0);
+    check_decoding::<ByteArrayType>(Type::BYTE_ARRAY, 0);
+    check_decoding::<FixedLenByteArrayType>(Type::FIXED_LEN_BYTE_ARRAY, 12);
+  }
+
+  #[test]
+  fn test_gen_values() {
+    let values = gen_values::<ByteArrayType>(100, 5, 0, 1);
+    assert_eq!(values, gen_values::<ByteArrayType>(100, 5, 0, 1));
+    let mut distinct = values.clone();
+    distinct.sort_by(|a, b| a.data().cmp(b.data()));
+    distinct.dedup();
+    assert_eq!(distinct.len(), 5);
+
+    let values = gen_values::<FixedLenByteArrayType>(10, 10, 20, 1);
+    assert!(values.iter().all(|value| value.len() == 20));
+  }
+
+  #[test]
+  fn test_decode_pages_invalid() {
+    let descr = column_descr(Type::INT32, 0);
+    let pages = gen_pages::<Int32Type>(descr.clone(), Encoding::RLE_DICTIONARY, 10, 2, 1)
+      .unwrap();
+    assert_eq!(
+      decode_pages::<Int32Type>(descr.clone(), &pages[1..], 10).err().unwrap(),
+      general_err!("Missing dictionary page")
+    );
+    assert_eq!(
+      decode_pages::<Int32Type>(descr, &pages, 0).err().unwrap(),
+      general_err!("Batch size must be positive")
+    );
+  }
+
+  #[test]
+  fn test_measure_decoding() {
+    let descr = column_descr(Type::DOUBLE, 0);
+    let pages = gen_pages::<DoubleType>(descr.clone(), Encoding::PLAIN, 100, 100, 3)
+      .unwrap();
+    let measurement = measure_decoding::<DoubleType>(descr, &pages, 16, 3).unwrap();
+    assert_eq!(measurement.num_values(), 300);
+    assert_eq!(measurement.num_bytes(), 3 * 800);
+    assert!(measurement.bytes_per_second() > 0.0);
+  }
+}
@@ -113,6 +113,12 @@ pub fn trailing_bits(v: u64, num_bits: usize) -> u64 {
   (v << n) >> n
 }
 
+/// Returns `true` if bit `i` of `bits` is set, bits are in least-significant bit order.
+#[inline]
+pub fn get_array_bit(bits: &[u8], i: usize) -> bool {
+  bits[i / 8] & (1 << (i % 8)) != 0
+}
+
 #[inline]
 pub fn set_array_bit(bits: &mut [u8], i: usize) {
   bits[i / 8] |= 1 << (i % 8);
@@ -363,9 +369,14 @@ impl BitWriter {
 /// MAX_VLQ_BYTE_LEN = 5 for i32, and MAX_VLQ_BYTE_LEN = 10 for i64
 pub const MAX_VLQ_BYTE_LEN: usize = 10;
 
-pub struct BitReader {
+/// Reader of bit/byte stream from buffer `B`, which is a [`ByteBufferPtr`] by default.
+/// Borrowed data can be read without copying it into a buffer, e.g. with
+/// `BitReader::new(&data[..])`.
+///
+/// [`ByteBufferPtr`]: ../memory/struct.ByteBufferPtr.html
+pub struct BitReader<B: AsRef<[u8]> = ByteBufferPtr> {
   // The byte buffer to read from, passed in by client
-  buffer: ByteBufferPtr,
+  buffer: B,
 
   // Bytes are memcpy'd from `buffer` and values are read from this variable.
   // This is faster than reading values byte by byte directly from `buffer`
@@ -389,9 +400,9 @@ pub struct BitReader {
 
 /// Utility class to read bit/byte stream. This class can read bits or bytes that are
 /// either byte aligned or not.
-impl BitReader {
-  pub fn new(buffer: ByteBufferPtr) -> Self {
-    let total_bytes = buffer.len();
+impl<B: AsRef<[u8]>> BitReader<B> {
+  pub fn new(buffer: B) -> Self {
+    let total_bytes = buffer.as_ref().len();
     let num_bytes = cmp::min(8, total_bytes);
     let buffered_values = read_num_bytes!(u64, num_bytes, buffer.as_ref());
     BitReader {
@@ -404,9 +415,9 @@ impl BitReader {
   }
 
   #[inline]
-  pub fn reset(&mut self, buffer: ByteBufferPtr) {
+  pub fn reset(&mut self, buffer: B) {
     self.buffer = buffer;
-    self.total_bytes = self.buffer.len();
+    self.total_bytes = self.buffer.as_ref().len();
     let num_bytes = cmp::min(8, self.total_bytes);
     self.buffered_values = read_num_bytes!(u64, num_bytes, self.buffer.as_ref());
     self.byte_offset = 0;
@@ -419,6 +430,12 @@ impl BitReader {
     self.byte_offset + ceil(self.bit_offset as i64, 8) as usize
   }
 
+  /// Gets the number of bytes left to read, a partially read byte is not counted
+  #[inline]
+  pub fn bytes_left(&self) -> usize {
+    self.total_bytes.saturating_sub(self.get_byte_offset())
+  }
+
   /// Reads a value of type `T` and of size `num_bits`.
   ///
   /// Returns `None` if there's not enough data available. `Some` otherwise.
@@ -452,9 +469,13 @@ impl BitReader {
     Some(result)
   }
 
+  /// Reads a batch of values of type `T` and of size `num_bits` into `batch`.
+  ///
+  /// Returns number of values read, which is less than length of `batch` if there's
+  /// not enough data available.
   #[inline]
   pub fn get_batch<T: Default>(&mut self, batch: &mut [T], num_bits: usize) -> usize {
-    assert!(num_bits <= 32);
+    assert!(num_bits <= 64);
     assert!(num_bits <= size_of::<T>() * 8);
 
     let mut values_to_read = batch.len();
@@ -466,6 +487,15 @@ impl BitReader {
 
     let mut i = 0;
 
+    // Values wider than 32 bits can not be unpacked with `unpack32`
+    if num_bits > 32 {
+      while i < values_to_read {
+        batch[i] = self.get_value(num_bits).expect("expected to have more data");
+        i += 1;
+      }
+      return values_to_read;
+    }
+
     // First align bit offset to byte offset
     if self.bit_offset != 0 {
       while i < values_to_read && self.bit_offset != 0 {
@@ -475,7 +505,7 @@ impl BitReader {
     }
 
     unsafe {
-      let in_buf = &self.buffer.data()[self.byte_offset..];
+      let in_buf = &self.buffer.as_ref()[self.byte_offset..];
       let mut in_ptr = in_buf as *const [u8] as *const u8 as *const u32;
       if size_of::<T>() == 4 {
         while values_to_read - i >= 32 {
@@ -494,6 +524,8 @@ impl BitReader {
             // We need to copy from smaller size to bigger size to avoid overwritting
             // other memory regions.
             if size_of::<T>() > size_of::<u32>() {
+              // Only the lower 4 bytes are copied, the rest must be zero
+              batch[i] = T::default();
               ::std::ptr::copy_nonoverlapping(
                 out_buf[n..].as_ptr() as *const u32,
                 &mut batch[i] as *mut T as *mut u32,
@@ -539,10 +571,7 @@ impl BitReader {
 
     // Advance byte_offset to next unread byte and read num_bytes
     self.byte_offset += bytes_read;
-    let v = read_num_bytes!(
-      T, num_bytes,
-      self.buffer.start_from(self.byte_offset).as_ref()
-    );
+    let v = read_num_bytes!(T, num_bytes, &self.buffer.as_ref()[self.byte_offset..]);
     self.byte_offset += num_bytes;
 
     // Reset buffered_values
@@ -554,7 +583,8 @@ impl BitReader {
   /// Reads a VLQ encoded (in little endian order) int from the stream.
   /// The encoded int must start at the beginning of a byte.
   ///
-  /// Returns `None` if there's not enough bytes in the stream. `Some` otherwise.
+  /// Returns `None` if there's not enough bytes in the stream or the int is longer than
+  /// `MAX_VLQ_BYTE_LEN` bytes. `Some` otherwise.
   #[inline]
   pub fn get_vlq_int(&mut self) -> Option<i64> {
     let mut shift = 0;
@@ -562,14 +592,12 @@ impl BitReader {
     while let Some(byte) = self.get_aligned::<u8>(1) {
       v |= ((byte & 0x7F) as i64) << shift;
       shift += 7;
-      assert!(
-        shift <= MAX_VLQ_BYTE_LEN * 7,
-        "Num of bytes exceed MAX_VLQ_BYTE_LEN ({})",
-        MAX_VLQ_BYTE_LEN
-      );
       if byte & 0x80 == 0 {
         return Some(v);
       }
+      if shift >= MAX_VLQ_BYTE_LEN * 7 {
+        return None;
+      }
     }
     None
   }
@@ -595,7 +623,7 @@ impl BitReader {
   fn reload_buffer_values(&mut self) {
     let bytes_to_read = cmp::min(self.total_bytes - self.byte_offset, 8);
     self.buffered_values = read_num_bytes!(
-      u64, bytes_to_read, self.buffer.start_from(self.byte_offset).as_ref()
+      u64, bytes_to_read, &self.buffer.as_ref()[self.byte_offset..]
     );
   }
 }
@@ -607,6 +635,13 @@ impl From<Vec<u8>> for BitReader {
   }
 }
 
+impl<'a> From<&'a [u8]> for BitReader<&'a [u8]> {
+  #[inline]
+  fn from(buffer: &'a [u8]) -> Self {
+    BitReader::new(buffer)
+  }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -680,6 +715,31 @@ mod tests {
     assert_eq!(bit_reader.get_aligned::<i32>(3), None);
   }
 
+  #[test]
+  fn test_bit_reader_borrowed_slice() {
+    // 01110101 11001011, followed by 32 values of 3 bits
+    let mut writer = BitWriter::new(14);
+    writer.put_aligned(0xCB75u16, 2);
+    for i in 0..32 {
+      writer.put_value(i % 8, 3);
+    }
+    let buffer = writer.consume();
+
+    let mut bit_reader = BitReader::new(&buffer[..2]);
+    assert_eq!(bit_reader.get_value::<i32>(3), Some(5));
+    assert_eq!(bit_reader.get_aligned::<i32>(1), Some(203));
+    assert_eq!(bit_reader.get_value::<i32>(1), None);
+
+    bit_reader.reset(&buffer[2..]);
+    let mut batch = vec![0; 32];
+    assert_eq!(bit_reader.get_batch::<i32>(&mut batch, 3), 32);
+    assert_eq!(batch, (0..32).map(|i| i % 8).collect::<Vec<i32>>());
+    assert_eq!(bit_reader.get_byte_offset(), 12);
+
+    let mut bit_reader = BitReader::from(&buffer[..]);
+    assert_eq!(bit_reader.get_aligned::<u16>(2), Some(0xCB75));
+  }
+
   #[test]
   fn test_bit_reader_get_vlq_int() {
     // 10001001 00000001 11110010 10110101 00000110
@@ -687,6 +747,10 @@ mod tests {
     let mut bit_reader = BitReader::from(buffer);
     assert_eq!(bit_reader.get_vlq_int(), Some(137));
     assert_eq!(bit_reader.get_vlq_int(), Some(105202));
+
+    // Int longer than MAX_VLQ_BYTE_LEN bytes
+    let mut bit_reader = BitReader::from(vec![0xFF; 12]);
+    assert_eq!(bit_reader.get_vlq_int(), None);
   }
 
   #[test]
@@ -716,6 +780,10 @@ mod tests {
     assert_eq!(buffer, vec![16, 12, 0]);
     unset_array_bit(&mut buffer[..], 10);
     assert_eq!(buffer, vec![16, 8, 0]);
+    assert!(get_array_bit(&buffer[..], 4));
+    assert!(get_array_bit(&buffer[..], 11));
+    assert!(!get_array_bit(&buffer[..], 10));
+    assert!(!get_array_bit(&buffer[..], 23));
   }
 
   #[test]
@@ -916,6 +984,29 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_get_batch_wide_values() {
+    for &num_bits in &[20, 32, 33, 48, 63, 64] {
+      let total = 70;
+      let mut writer = BitWriter::new(8 * total);
+      let values: Vec<u64> = random_numbers::<u64>(total)
+        .iter()
+        .map(|v| trailing_bits(*v, num_bits))
+        .collect();
+      for &v in &values {
+        assert!(writer.put_value(v, num_bits));
+      }
+
+      let mut reader = BitReader::from(writer.consume());
+      // Upper bytes of values read with `unpack32` must not be kept
+      let mut batch = vec![-1i64; total];
+      assert_eq!(reader.get_batch::<i64>(&mut batch, num_bits), total);
+      for i in 0..total {
+        assert_eq!(batch[i] as u64, values[i], "num_bits = {}, index = {}", num_bits, i);
+      }
+    }
+  }
+
   #[test]
   fn test_put_aligned_roundtrip() {
     test_put_aligned_rand_numbers::<u8>(4, 3);
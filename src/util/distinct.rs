@@ -0,0 +1,153 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Counting of distinct values, used to populate `distinct_count` of statistics.
+//!
+//! Values are counted exactly until their number exceeds a threshold, after that number
+//! of distinct values is estimated with HyperLogLog, using a fixed amount of memory.
+
+use std::collections::HashSet;
+
+use data_type::AsBytes;
+use util::hash_util::hash64;
+
+/// Max number of distinct values that are counted exactly.
+const EXACT_THRESHOLD: usize = 1024;
+
+/// Number of bits of hash value used to select HyperLogLog register.
+const PRECISION: u32 = 14;
+
+/// Number of HyperLogLog registers.
+const NUM_REGISTERS: usize = 1 << PRECISION;
+
+/// Counter of distinct values.
+pub struct DistinctCounter {
+  // Set of values, when number of values is below threshold
+  values: Option<HashSet<Vec<u8>>>,
+  // HyperLogLog registers, when number of values exceeded threshold
+  registers: Vec<u8>
+}
+
+impl DistinctCounter {
+  /// Creates new counter without values.
+  pub fn new() -> Self {
+    Self { values: Some(HashSet::new()), registers: vec![] }
+  }
+
+  /// Adds value to the counter.
+  pub fn insert<T: AsBytes>(&mut self, value: &T) {
+    let is_above_threshold = match self.values {
+      Some(ref mut values) => {
+        if !values.contains(value.as_bytes()) {
+          values.insert(value.as_bytes().to_vec());
+        }
+        values.len() > EXACT_THRESHOLD
+      },
+      None => {
+        self.insert_hash(hash64(value, 0));
+        false
+      }
+    };
+    if is_above_threshold {
+      self.registers = vec![0; NUM_REGISTERS];
+      if let Some(values) = self.values.take() {
+        for value in values {
+          self.insert_hash(hash64(&value, 0));
+        }
+      }
+    }
+  }
+
+  /// Returns `true` if number of distinct values is exact, `false` if it is an
+  /// estimate.
+  pub fn is_exact(&self) -> bool {
+    self.values.is_some()
+  }
+
+  /// Returns number of distinct values, see [`is_exact`](#method.is_exact).
+  pub fn count(&self) -> u64 {
+    if let Some(ref values) = self.values {
+      return values.len() as u64;
+    }
+    let m = NUM_REGISTERS as f64;
+    let alpha = 0.7213 / (1.0 + 1.079 / m);
+    let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+    let estimate = alpha * m * m / sum;
+    let num_zeros = self.registers.iter().filter(|&&r| r == 0).count();
+    // Linear counting is more accurate for small cardinalities. Large range correction
+    // is not required for 64-bit hash values.
+    let estimate = if estimate <= 2.5 * m && num_zeros > 0 {
+      m * (m / num_zeros as f64).ln()
+    } else {
+      estimate
+    };
+    estimate.round() as u64
+  }
+
+  /// Updates register of HyperLogLog with hash value of a value.
+  fn insert_hash(&mut self, hash: u64) {
+    let index = (hash >> (64 - PRECISION)) as usize;
+    // Position of the first 1-bit in the remaining bits, guard bit bounds the position
+    let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() + 1;
+    if self.registers[index] < rank as u8 {
+      self.registers[index] = rank as u8;
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_distinct_counter_exact() {
+    let mut counter = DistinctCounter::new();
+    assert_eq!(counter.count(), 0);
+    for i in 0..100 {
+      counter.insert(&(i % 10));
+    }
+    assert!(counter.is_exact());
+    assert_eq!(counter.count(), 10);
+
+    for i in 0..EXACT_THRESHOLD as i32 {
+      counter.insert(&i);
+    }
+    assert!(counter.is_exact());
+    assert_eq!(counter.count(), EXACT_THRESHOLD as u64);
+  }
+
+  #[test]
+  fn test_distinct_counter_estimate() {
+    let mut counter = DistinctCounter::new();
+    for i in 0..100_000i64 {
+      counter.insert(&(i % 50_000));
+    }
+    assert!(!counter.is_exact());
+    let count = counter.count() as f64;
+    assert!((count - 50_000.0).abs() < 50_000.0 * 0.05, "Estimate {}", count);
+
+    // Number of values just above threshold is estimated using linear counting
+    let mut counter = DistinctCounter::new();
+    for i in 0..(EXACT_THRESHOLD as i32 + 1) {
+      counter.insert(&i);
+    }
+    assert!(!counter.is_exact());
+    let count = counter.count() as f64;
+    assert!((count - EXACT_THRESHOLD as f64).abs() < 50.0, "Estimate {}", count);
+  }
+}
@@ -0,0 +1,135 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Conversions between IEEE 754 half-precision floats, stored as FLOAT16 logical type,
+//! and `f32`. Half-precision values are represented by their bits as `u16`.
+
+/// Converts bits of a half-precision float into `f32`. Conversion is exact.
+pub fn f16_to_f32(bits: u16) -> f32 {
+  let sign = ((bits & 0x8000) as u32) << 16;
+  let exp = ((bits >> 10) & 0x1f) as u32;
+  let mant = (bits & 0x3ff) as u32;
+  match exp {
+    // Zero or subnormal value, i.e. mantissa * 2^-24
+    0 => {
+      let value = mant as f32 * 2f32.powi(-24);
+      if sign == 0 { value } else { -value }
+    },
+    // Infinity or NaN, NaN payload is preserved
+    0x1f => f32::from_bits(sign | 0x7f80_0000 | (mant << 13)),
+    _ => f32::from_bits(sign | ((exp + 127 - 15) << 23) | (mant << 13))
+  }
+}
+
+/// Converts `f32` into bits of a half-precision float, rounding to the nearest value
+/// with ties to even. Values that are too large are converted into infinity.
+pub fn f32_to_f16(value: f32) -> u16 {
+  let bits = value.to_bits();
+  let sign = ((bits >> 16) & 0x8000) as u16;
+  let exp = ((bits >> 23) & 0xff) as i32;
+  let mant = bits & 0x7f_ffff;
+
+  if exp == 0xff {
+    // Infinity or NaN, NaN is kept quiet
+    return if mant == 0 { sign | 0x7c00 } else { sign | 0x7e00 | (mant >> 13) as u16 };
+  }
+
+  let half_exp = exp - 127 + 15;
+  if half_exp >= 0x1f {
+    return sign | 0x7c00;
+  }
+  if half_exp <= 0 {
+    // Subnormal value or zero
+    if half_exp < -10 {
+      return sign;
+    }
+    let mant = mant | 0x80_0000;
+    let shift = (14 - half_exp) as u32;
+    return sign | round_shift(mant, shift) as u16;
+  }
+  // Carry of rounding goes into exponent, which results in infinity on overflow
+  sign | round_shift(((half_exp as u32) << 23) | mant, 13) as u16
+}
+
+/// Shifts `value` right by `shift` bits, rounding to the nearest value with ties to even.
+#[inline]
+fn round_shift(value: u32, shift: u32) -> u32 {
+  let result = value >> shift;
+  let rem = value & ((1 << shift) - 1);
+  let half = 1 << (shift - 1);
+  if rem > half || (rem == half && result & 1 == 1) {
+    result + 1
+  } else {
+    result
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_f16_to_f32() {
+    assert_eq!(f16_to_f32(0x0000), 0.0);
+    assert!(f16_to_f32(0x8000).is_sign_negative());
+    assert_eq!(f16_to_f32(0x3c00), 1.0);
+    assert_eq!(f16_to_f32(0xc000), -2.0);
+    assert_eq!(f16_to_f32(0x3555), 0.333251953125);
+    assert_eq!(f16_to_f32(0x7bff), 65504.0);
+    assert_eq!(f16_to_f32(0x0001), 2f32.powi(-24));
+    assert_eq!(f16_to_f32(0x0400), 2f32.powi(-14));
+    assert_eq!(f16_to_f32(0x7c00), ::std::f32::INFINITY);
+    assert_eq!(f16_to_f32(0xfc00), ::std::f32::NEG_INFINITY);
+    assert!(f16_to_f32(0x7e00).is_nan());
+  }
+
+  #[test]
+  fn test_f32_to_f16() {
+    assert_eq!(f32_to_f16(0.0), 0x0000);
+    assert_eq!(f32_to_f16(-0.0), 0x8000);
+    assert_eq!(f32_to_f16(1.0), 0x3c00);
+    assert_eq!(f32_to_f16(-2.0), 0xc000);
+    assert_eq!(f32_to_f16(65504.0), 0x7bff);
+    assert_eq!(f32_to_f16(2f32.powi(-24)), 0x0001);
+    assert_eq!(f32_to_f16(2f32.powi(-14)), 0x0400);
+    assert_eq!(f32_to_f16(::std::f32::INFINITY), 0x7c00);
+    assert!(f16_to_f32(f32_to_f16(::std::f32::NAN)).is_nan());
+
+    // Rounding
+    assert_eq!(f32_to_f16(1.0 / 3.0), 0x3555);
+    assert_eq!(f32_to_f16(65520.0), 0x7c00);
+    assert_eq!(f32_to_f16(1.0 + 2f32.powi(-11)), 0x3c00);
+    assert_eq!(f32_to_f16(1.0 + 3.0 * 2f32.powi(-11)), 0x3c02);
+    assert_eq!(f32_to_f16(2f32.powi(-25)), 0x0000);
+    assert_eq!(f32_to_f16(3.0 * 2f32.powi(-26)), 0x0001);
+    assert_eq!(f32_to_f16(1e-10), 0x0000);
+  }
+
+  #[test]
+  fn test_f16_roundtrip() {
+    for bits in 0..=0xffffu32 {
+      let bits = bits as u16;
+      let value = f16_to_f32(bits);
+      if value.is_nan() {
+        assert!(f16_to_f32(f32_to_f16(value)).is_nan());
+      } else {
+        assert_eq!(f32_to_f16(value), bits);
+      }
+    }
+  }
+}
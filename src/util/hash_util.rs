@@ -15,6 +15,8 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use byteorder::{ByteOrder, LittleEndian};
+
 use data_type::AsBytes;
 
 /// Computes hash value for `data`, with a seed value `seed`.
@@ -30,6 +32,88 @@ pub fn hash<T: AsBytes>(data: &T, seed: u32) -> u32 {
   }
 }
 
+/// Computes 64-bit hash value for `data`, with a seed value `seed`.
+/// Unlike [`hash`](fn.hash.html), result does not depend on the platform.
+pub fn hash64<T: AsBytes>(data: &T, seed: u64) -> u64 {
+  murmur_hash2_64a(data, seed)
+}
+
+/// Computes 64-bit xxHash (XXH64) value for `data`, with a seed value `seed`.
+/// This is the hash function of bloom filters in Parquet files.
+pub fn xxhash64<T: AsBytes>(data: &T, seed: u64) -> u64 {
+  xxhash64_bytes(data.as_bytes(), seed)
+}
+
+const XXH_PRIME_1: u64 = 11400714785074694791;
+const XXH_PRIME_2: u64 = 14029467366897019727;
+const XXH_PRIME_3: u64 = 1609587929392839161;
+const XXH_PRIME_4: u64 = 9650029242287828579;
+const XXH_PRIME_5: u64 = 2870177450012600261;
+
+/// Rust implementation of XXH64
+fn xxhash64_bytes(data: &[u8], seed: u64) -> u64 {
+  let len = data.len();
+  let mut offset = 0;
+
+  let mut h = if len >= 32 {
+    let mut v = [
+      seed.wrapping_add(XXH_PRIME_1).wrapping_add(XXH_PRIME_2),
+      seed.wrapping_add(XXH_PRIME_2),
+      seed,
+      seed.wrapping_sub(XXH_PRIME_1)
+    ];
+    while offset + 32 <= len {
+      for (i, acc) in v.iter_mut().enumerate() {
+        *acc = xxhash64_round(*acc, LittleEndian::read_u64(&data[offset + i * 8..]));
+      }
+      offset += 32;
+    }
+    let mut h = v[0].rotate_left(1)
+      .wrapping_add(v[1].rotate_left(7))
+      .wrapping_add(v[2].rotate_left(12))
+      .wrapping_add(v[3].rotate_left(18));
+    for acc in &v {
+      h = (h ^ xxhash64_round(0, *acc))
+        .wrapping_mul(XXH_PRIME_1)
+        .wrapping_add(XXH_PRIME_4);
+    }
+    h
+  } else {
+    seed.wrapping_add(XXH_PRIME_5)
+  };
+  h = h.wrapping_add(len as u64);
+
+  while offset + 8 <= len {
+    h ^= xxhash64_round(0, LittleEndian::read_u64(&data[offset..]));
+    h = h.rotate_left(27).wrapping_mul(XXH_PRIME_1).wrapping_add(XXH_PRIME_4);
+    offset += 8;
+  }
+  if offset + 4 <= len {
+    h ^= (LittleEndian::read_u32(&data[offset..]) as u64).wrapping_mul(XXH_PRIME_1);
+    h = h.rotate_left(23).wrapping_mul(XXH_PRIME_2).wrapping_add(XXH_PRIME_3);
+    offset += 4;
+  }
+  while offset < len {
+    h ^= (data[offset] as u64).wrapping_mul(XXH_PRIME_5);
+    h = h.rotate_left(11).wrapping_mul(XXH_PRIME_1);
+    offset += 1;
+  }
+
+  h ^= h >> 33;
+  h = h.wrapping_mul(XXH_PRIME_2);
+  h ^= h >> 29;
+  h = h.wrapping_mul(XXH_PRIME_3);
+  h ^= h >> 32;
+  h
+}
+
+#[inline]
+fn xxhash64_round(acc: u64, input: u64) -> u64 {
+  acc.wrapping_add(input.wrapping_mul(XXH_PRIME_2))
+    .rotate_left(31)
+    .wrapping_mul(XXH_PRIME_1)
+}
+
 const MURMUR_PRIME: u64 = 0xc6a4a7935bd1e995;
 const MURMUR_R: i32 = 47;
 
@@ -130,6 +214,17 @@ mod tests {
     assert_eq!(result, 2392198230801491746);
   }
 
+  #[test]
+  fn test_xxhash64() {
+    assert_eq!(xxhash64(&"", 0), 0xef46db3751d8e999);
+    assert_eq!(xxhash64(&"a", 0), 0xd24ec4f1a98c6e5b);
+    assert_eq!(xxhash64(&"abc", 0), 0x44bc2cf5ad770999);
+    assert_eq!(
+      xxhash64(&"Nobody inspects the spammish repetition", 0),
+      0xfbcea83c8a378bf1
+    );
+  }
+
   #[test]
   #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
   fn test_crc32() {
@@ -16,9 +16,11 @@
 // under the License.
 
 use file::reader::ParquetReader;
+use std::cell::{Cell, RefCell};
 use std::cmp;
 use std::fs::File;
 use std::io::*;
+use std::rc::Rc;
 use std::sync::Mutex;
 
 // ----------------------------------------------------------------------
@@ -121,6 +123,67 @@ impl Position for FileSink {
   }
 }
 
+/// Struct that represents any `Write` output stream with position tracking.
+/// Used as a sink in file writer, does not require the underlying stream to support
+/// seek operations, so pipes, sockets and compressing writers can be used as well.
+///
+/// Position is the number of bytes written through this sink. Clones share the same
+/// underlying stream and position, which allows row group and page writers to append
+/// to the same output.
+pub struct TrackedWrite<W: Write> {
+  buf: Rc<RefCell<BufWriter<W>>>,
+  bytes_written: Rc<Cell<u64>>
+}
+
+impl<W: Write> TrackedWrite<W> {
+  /// Creates new tracked sink.
+  /// Position starts at 0, regardless of the state of the underlying stream.
+  pub fn new(inner: W) -> Self {
+    Self {
+      buf: Rc::new(RefCell::new(BufWriter::new(inner))),
+      bytes_written: Rc::new(Cell::new(0))
+    }
+  }
+
+  /// Returns total number of bytes written so far.
+  pub fn bytes_written(&self) -> u64 {
+    self.bytes_written.get()
+  }
+
+  /// Flushes buffered data and returns the underlying stream.
+  /// Returns error if there are other clones of this sink still alive.
+  pub fn into_inner(self) -> Result<W> {
+    match Rc::try_unwrap(self.buf) {
+      Ok(buf) => buf.into_inner().into_inner().map_err(|err| err.into()),
+      Err(_) => Err(Error::new(ErrorKind::Other, "Sink is shared with other writers"))
+    }
+  }
+}
+
+impl<W: Write> Clone for TrackedWrite<W> {
+  fn clone(&self) -> Self {
+    Self { buf: self.buf.clone(), bytes_written: self.bytes_written.clone() }
+  }
+}
+
+impl<W: Write> Write for TrackedWrite<W> {
+  fn write(&mut self, buf: &[u8]) -> Result<usize> {
+    let num_bytes = self.buf.borrow_mut().write(buf)?;
+    self.bytes_written.set(self.bytes_written.get() + num_bytes as u64);
+    Ok(num_bytes)
+  }
+
+  fn flush(&mut self) -> Result<()> {
+    self.buf.borrow_mut().flush()
+  }
+}
+
+impl<W: Write> Position for TrackedWrite<W> {
+  fn pos(&self) -> u64 {
+    self.bytes_written()
+  }
+}
+
 // Position implementation for Cursor to use in various tests.
 impl<'a> Position for Cursor<&'a mut Vec<u8>> {
   fn pos(&self) -> u64 {
@@ -216,4 +279,23 @@ mod tests {
 
     assert_eq!(res, vec![b'a', b'b', b'c', b'd', b'e', b'f', b'g']);
   }
+
+  #[test]
+  fn test_io_tracked_write() {
+    let mut sink = TrackedWrite::new(Vec::new());
+    assert_eq!(sink.pos(), 0);
+
+    sink.write_all(&[1, 2, 3]).unwrap();
+    assert_eq!(sink.pos(), 3);
+
+    // Clones share position and underlying stream
+    let mut cloned = sink.clone();
+    cloned.write_all(&[4, 5]).unwrap();
+    assert_eq!(sink.pos(), 5);
+    assert_eq!(cloned.bytes_written(), 5);
+
+    assert!(sink.clone().into_inner().is_err());
+    drop(cloned);
+    assert_eq!(sink.into_inner().unwrap(), vec![1, 2, 3, 4, 5]);
+  }
 }
@@ -17,27 +17,60 @@
 
 //! Utility methods and structs for working with memory.
 
-use std::cell::Cell;
 use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
 use std::io::{Result as IoResult, Write};
 use std::mem;
 use std::ops::{Index, IndexMut};
-use std::rc::{Rc, Weak};
+use std::sync::{Arc, Mutex, Weak};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+
+use errors::{ParquetError, Result};
 
 // ----------------------------------------------------------------------
 // Memory Tracker classes
 
-/// Reference counted pointer for [`MemTracker`].
-pub type MemTrackerPtr = Rc<MemTracker>;
+/// Atomically reference counted pointer for [`MemTracker`].
+/// Memory tracker can be shared between readers and decoders, including across
+/// threads.
+pub type MemTrackerPtr = Arc<MemTracker>;
 /// Non-owning reference for [`MemTracker`].
 pub type WeakMemTrackerPtr = Weak<MemTracker>;
 
+/// Listener of allocations tracked by [`MemTracker`].
+///
+/// Can be used to make buffers participate in memory accounting of a host system,
+/// e.g. a memory manager of a query engine. Memory itself is allocated with the global
+/// allocator, listener is notified about allocated and released bytes and can reject
+/// fallible allocations.
+pub trait AllocationListener: Send + Sync {
+  /// Called when `num_bytes` have been allocated (positive value) or released
+  /// (negative value).
+  fn alloc(&self, num_bytes: i64);
+
+  /// Called before `num_bytes` are allocated by fallible allocation, e.g.
+  /// [`MemTracker::try_alloc`]. Returning error rejects the allocation, otherwise
+  /// bytes are considered allocated.
+  fn try_alloc(&self, num_bytes: i64) -> Result<()> {
+    self.alloc(num_bytes);
+    Ok(())
+  }
+}
+
 /// Struct to track memory usage information.
-#[derive(Debug)]
+///
+/// Memory tracker can optionally have a limit. Fallible allocations, e.g.
+/// [`try_alloc`](#method.try_alloc) or [`Buffer::try_resize`], return
+/// `ParquetError::ResourceExhausted` error instead of exceeding the limit.
+///
+/// All allocations are also reported to [`AllocationListener`], if set.
 pub struct MemTracker {
-  // In the tuple, the first element is the current memory allocated (in bytes),
-  // and the second element is the maximum memory allocated so far (in bytes).
-  memory_usage: Cell<(i64, i64)>
+  // Current memory allocated (in bytes).
+  current_memory_usage: AtomicI64,
+  // Maximum memory allocated so far (in bytes).
+  max_memory_usage: AtomicI64,
+  // Maximum number of bytes allowed to be allocated, if set.
+  limit: Option<i64>,
+  listener: Option<Arc<AllocationListener>>
 }
 
 impl MemTracker {
@@ -45,29 +78,268 @@ impl MemTracker {
   #[inline]
   pub fn new() -> MemTracker {
     MemTracker {
-      memory_usage: Cell::new((0, 0))
+      current_memory_usage: AtomicI64::new(0),
+      max_memory_usage: AtomicI64::new(0),
+      limit: None,
+      listener: None
+    }
+  }
+
+  /// Creates new memory tracker that allows at most `limit` bytes to be allocated.
+  #[inline]
+  pub fn with_limit(limit: i64) -> MemTracker {
+    MemTracker {
+      current_memory_usage: AtomicI64::new(0),
+      max_memory_usage: AtomicI64::new(0),
+      limit: Some(limit),
+      listener: None
     }
   }
 
+  /// Adds [`AllocationListener`] for this memory tracker.
+  #[inline]
+  pub fn with_listener(mut self, listener: Arc<AllocationListener>) -> Self {
+    self.listener = Some(listener);
+    self
+  }
+
+  /// Returns memory limit in bytes, if set.
+  pub fn limit(&self) -> Option<i64> {
+    self.limit
+  }
+
   /// Returns the current memory consumption, in bytes.
   pub fn memory_usage(&self) -> i64 {
-    self.memory_usage.get().0
+    self.current_memory_usage.load(Ordering::Acquire)
   }
 
   /// Returns the maximum memory consumption so far, in bytes.
   pub fn max_memory_usage(&self) -> i64 {
-    self.memory_usage.get().1
+    self.max_memory_usage.load(Ordering::Acquire)
   }
 
   /// Adds `num_bytes` to the memory consumption tracked by this memory tracker.
   #[inline]
   pub fn alloc(&self, num_bytes: i64) {
-    let (current, mut maximum) = self.memory_usage.get();
-    let new_current = current + num_bytes;
-    if new_current > maximum {
-      maximum = new_current
+    let new_current =
+      self.current_memory_usage.fetch_add(num_bytes, Ordering::AcqRel) + num_bytes;
+    self.max_memory_usage.fetch_max(new_current, Ordering::AcqRel);
+    if let Some(ref listener) = self.listener {
+      listener.alloc(num_bytes);
+    }
+  }
+
+  /// Checks if `num_bytes` can be allocated without exceeding the limit.
+  /// Memory consumption is not updated.
+  #[inline]
+  pub fn check_alloc(&self, num_bytes: i64) -> Result<()> {
+    self.check_limit(self.memory_usage(), num_bytes)
+  }
+
+  /// Adds `num_bytes` to the memory consumption tracked by this memory tracker.
+  /// Returns error and does not update memory consumption, if allocation would exceed
+  /// the limit.
+  ///
+  /// Check and update are performed atomically, so concurrent allocations never
+  /// exceed the limit.
+  #[inline]
+  pub fn try_alloc(&self, num_bytes: i64) -> Result<()> {
+    let mut current = self.memory_usage();
+    loop {
+      self.check_limit(current, num_bytes)?;
+      match self.current_memory_usage.compare_exchange_weak(
+        current, current + num_bytes, Ordering::AcqRel, Ordering::Acquire) {
+        Ok(_) => break,
+        Err(actual) => current = actual
+      }
+    }
+    if let Some(ref listener) = self.listener {
+      if let Err(e) = listener.try_alloc(num_bytes) {
+        self.current_memory_usage.fetch_sub(num_bytes, Ordering::AcqRel);
+        return Err(e);
+      }
+    }
+    self.max_memory_usage.fetch_max(current + num_bytes, Ordering::AcqRel);
+    Ok(())
+  }
+
+  #[inline]
+  fn check_limit(&self, current: i64, num_bytes: i64) -> Result<()> {
+    match self.limit {
+      Some(limit) if num_bytes > 0 && current + num_bytes > limit => {
+        Err(resource_exhausted_err!(
+          "Failed to allocate {} bytes: memory usage {} bytes, limit {} bytes",
+          num_bytes,
+          current,
+          limit
+        ))
+      },
+      _ => Ok(())
+    }
+  }
+}
+
+impl Debug for MemTracker {
+  fn fmt(&self, f: &mut Formatter) -> FmtResult {
+    f.debug_struct("MemTracker")
+      .field("memory_usage", &self.memory_usage())
+      .field("max_memory_usage", &self.max_memory_usage())
+      .field("limit", &self.limit)
+      .field("has_listener", &self.listener.is_some())
+      .finish()
+  }
+}
+
+// ----------------------------------------------------------------------
+// Buffer Pool classes
+
+/// Atomically reference counted pointer for [`BufferPool`].
+pub type BufferPoolPtr<T> = Arc<BufferPool<T>>;
+
+/// Pool of reusable vectors, e.g. buffers of pages, so that long scans do not allocate
+/// and free memory for every page.
+///
+/// [`Buffer`] and [`BufferPtr`] with a pool take their data from the pool, and return
+/// it to the pool when dropped. Pool keeps at most `max_buffers` idle buffers,
+/// replacing the smallest one when full. Idle buffers can be accounted in a memory
+/// tracker, buffers that do not fit into its limit are released instead of pooled.
+pub struct BufferPool<T> {
+  buffers: Mutex<Vec<Vec<T>>>,
+  max_buffers: usize,
+  mem_tracker: Option<MemTrackerPtr>,
+  hits: AtomicU64,
+  misses: AtomicU64
+}
+
+impl<T> BufferPool<T> {
+  /// Creates new buffer pool that keeps at most `max_buffers` idle buffers.
+  pub fn new(max_buffers: usize) -> Self {
+    Self {
+      buffers: Mutex::new(Vec::new()),
+      max_buffers: max_buffers,
+      mem_tracker: None,
+      hits: AtomicU64::new(0),
+      misses: AtomicU64::new(0)
+    }
+  }
+
+  /// Accounts memory of idle buffers in `mem_tracker`.
+  pub fn with_mem_tracker(mut self, mem_tracker: MemTrackerPtr) -> Self {
+    self.mem_tracker = Some(mem_tracker);
+    self
+  }
+
+  /// Returns empty vector with at least `capacity` capacity, reusing an idle buffer if
+  /// there is one that is large enough.
+  pub fn get(&self, capacity: usize) -> Vec<T> {
+    let buffer = {
+      let mut buffers = self.buffers.lock().unwrap();
+      let pos = buffers.iter().position(|buffer| buffer.capacity() >= capacity);
+      pos.map(|pos| buffers.swap_remove(pos))
+    };
+    match buffer {
+      Some(buffer) => {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        self.track(-(self.num_bytes(&buffer) as i64));
+        buffer
+      },
+      None => {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        Vec::with_capacity(capacity)
+      }
+    }
+  }
+
+  /// Returns `buffer` to the pool. Buffer is released if pool is full and all idle
+  /// buffers are larger, or buffer does not fit into limit of the memory tracker.
+  pub fn put(&self, mut buffer: Vec<T>) {
+    let num_bytes = self.num_bytes(&buffer);
+    if num_bytes == 0 {
+      return;
+    }
+    if let Some(ref mc) = self.mem_tracker {
+      if mc.try_alloc(num_bytes as i64).is_err() {
+        return;
+      }
+    }
+    buffer.clear();
+    let evicted = {
+      let mut buffers = self.buffers.lock().unwrap();
+      if buffers.len() < self.max_buffers {
+        buffers.push(buffer);
+        None
+      } else {
+        let smallest = (0..buffers.len()).min_by_key(|&i| buffers[i].capacity());
+        match smallest {
+          Some(i) if buffers[i].capacity() < buffer.capacity() => {
+            Some(mem::replace(&mut buffers[i], buffer))
+          },
+          _ => Some(buffer)
+        }
+      }
+    };
+    if let Some(evicted) = evicted {
+      self.track(-(self.num_bytes(&evicted) as i64));
     }
-    self.memory_usage.set((new_current, maximum));
+  }
+
+  /// Returns number of buffers returned by [`get`](#method.get) that were reused.
+  pub fn hits(&self) -> u64 {
+    self.hits.load(Ordering::Relaxed)
+  }
+
+  /// Returns number of buffers returned by [`get`](#method.get) that were allocated.
+  pub fn misses(&self) -> u64 {
+    self.misses.load(Ordering::Relaxed)
+  }
+
+  /// Returns number of idle buffers in the pool.
+  pub fn num_buffers(&self) -> usize {
+    self.buffers.lock().unwrap().len()
+  }
+
+  /// Returns memory of idle buffers in the pool, in bytes.
+  pub fn memory_usage(&self) -> usize {
+    let buffers = self.buffers.lock().unwrap();
+    buffers.iter().map(|buffer| self.num_bytes(buffer)).sum()
+  }
+
+  /// Releases all idle buffers.
+  pub fn clear(&self) {
+    let buffers = mem::replace(&mut *self.buffers.lock().unwrap(), Vec::new());
+    for buffer in buffers {
+      self.track(-(self.num_bytes(&buffer) as i64));
+    }
+  }
+
+  #[inline]
+  fn num_bytes(&self, buffer: &Vec<T>) -> usize {
+    buffer.capacity() * mem::size_of::<T>()
+  }
+
+  #[inline]
+  fn track(&self, num_bytes: i64) {
+    if let Some(ref mc) = self.mem_tracker {
+      mc.alloc(num_bytes);
+    }
+  }
+}
+
+impl<T> Drop for BufferPool<T> {
+  fn drop(&mut self) {
+    self.clear();
+  }
+}
+
+impl<T> Debug for BufferPool<T> {
+  fn fmt(&self, f: &mut Formatter) -> FmtResult {
+    f.debug_struct("BufferPool")
+      .field("num_buffers", &self.num_buffers())
+      .field("memory_usage", &self.memory_usage())
+      .field("max_buffers", &self.max_buffers)
+      .field("hits", &self.hits())
+      .field("misses", &self.misses())
+      .finish()
   }
 }
 
@@ -90,6 +362,7 @@ pub type ByteBufferPtr = BufferPtr<u8>;
 pub struct Buffer<T: Clone> {
   data: Vec<T>,
   mem_tracker: Option<MemTrackerPtr>,
+  pool: Option<BufferPoolPtr<T>>,
   type_length: usize
 }
 
@@ -99,6 +372,7 @@ impl<T: Clone> Buffer<T> {
     Buffer {
       data: vec![],
       mem_tracker: None,
+      pool: None,
       type_length: ::std::mem::size_of::<T>()
     }
   }
@@ -111,12 +385,27 @@ impl<T: Clone> Buffer<T> {
     self
   }
 
+  /// Adds [`BufferPool`] for this buffer. Empty buffer takes its data from the pool
+  /// when resized with [`try_resize`](#method.try_resize), and data is returned to the
+  /// pool when buffer is dropped.
+  #[inline]
+  pub fn with_pool(mut self, pool: BufferPoolPtr<T>) -> Self {
+    self.pool = Some(pool);
+    self
+  }
+
   /// Returns slice of data in this buffer.
   #[inline]
   pub fn data(&self) -> &[T] {
     self.data.as_slice()
   }
 
+  /// Returns mutable slice of data in this buffer.
+  #[inline]
+  pub fn data_mut(&mut self) -> &mut [T] {
+    self.data.as_mut_slice()
+  }
+
   /// Sets data for this buffer.
   #[inline]
   pub fn set_data(&mut self, new_data: Vec<T>) {
@@ -127,6 +416,19 @@ impl<T: Clone> Buffer<T> {
     self.data = new_data;
   }
 
+  /// Sets data for this buffer.
+  /// Returns error if memory tracker does not allow to allocate extra capacity of
+  /// `new_data`, and buffer is left unchanged.
+  #[inline]
+  pub fn try_set_data(&mut self, new_data: Vec<T>) -> Result<()> {
+    if let Some(ref mc) = self.mem_tracker {
+      let capacity_diff = new_data.capacity() as i64 - self.data.capacity() as i64;
+      mc.try_alloc(capacity_diff * self.type_length as i64)?;
+    }
+    self.data = new_data;
+    Ok(())
+  }
+
   /// Resizes underlying data in place to a new length `new_size`.
   ///
   /// If `new_size` is less than current length, data is truncated, otherwise, it is
@@ -143,6 +445,45 @@ impl<T: Clone> Buffer<T> {
     }
   }
 
+  /// Resizes underlying data in place to a new length `new_size`, same as
+  /// [`resize`](#method.resize).
+  ///
+  /// Returns error if memory tracker does not allow to allocate additional capacity,
+  /// and buffer is left unchanged. Capacity is reserved exactly, so tracked memory
+  /// never exceeds the limit.
+  #[inline]
+  pub fn try_resize(&mut self, new_size: usize, init_value: T) -> Result<()> {
+    if self.data.capacity() == 0 && new_size > 0 {
+      if let Some(ref pool) = self.pool {
+        let data = pool.get(new_size);
+        if let Some(ref mc) = self.mem_tracker {
+          if let Err(e) = mc.try_alloc((data.capacity() * self.type_length) as i64) {
+            pool.put(data);
+            return Err(e);
+          }
+        }
+        self.data = data;
+      }
+    }
+    let old_capacity = self.data.capacity();
+    if new_size > old_capacity {
+      // Account for requested capacity first, then for any extra capacity reserved by
+      // the vector
+      let requested_diff = ((new_size - old_capacity) * self.type_length) as i64;
+      if let Some(ref mc) = self.mem_tracker {
+        mc.try_alloc(requested_diff)?;
+      }
+      let additional = new_size - self.data.len();
+      self.data.reserve_exact(additional);
+      if let Some(ref mc) = self.mem_tracker {
+        let capacity_diff = self.data.capacity() - old_capacity;
+        mc.alloc((capacity_diff * self.type_length) as i64 - requested_diff);
+      }
+    }
+    self.data.resize(new_size, init_value);
+    Ok(())
+  }
+
   /// Clears underlying data.
   #[inline]
   pub fn clear(&mut self) {
@@ -173,6 +514,9 @@ impl<T: Clone> Buffer<T> {
     if let Some(ref mc) = self.mem_tracker {
       result = result.with_mem_tracker(mc.clone());
     }
+    if let Some(ref pool) = self.pool {
+      result = result.with_pool(pool.clone());
+    }
     result
   }
 
@@ -254,6 +598,9 @@ impl<T: Clone> Drop for Buffer<T> {
     if let Some(ref mc) = self.mem_tracker {
       mc.alloc(-((self.data.capacity() * self.type_length) as i64));
     }
+    if let Some(ref pool) = self.pool {
+      pool.put(mem::replace(&mut self.data, vec![]));
+    }
   }
 }
 
@@ -263,13 +610,23 @@ impl<T: Clone> Drop for Buffer<T> {
 /// An representation of a slice on a reference-counting and read-only byte array.
 /// Sub-slices can be further created from this. The byte array will be released
 /// when all slices are dropped.
+///
+/// Buffer is `Send` and `Sync` if `T` is, slices can be shared between threads.
 #[derive(Clone, Debug)]
 pub struct BufferPtr<T> {
-  data: Rc<Vec<T>>,
+  data: Arc<BufferData<T>>,
   start: usize,
-  len: usize,
-  // TODO: will this create too many references? rethink about this.
-  mem_tracker: Option<MemTrackerPtr>
+  len: usize
+}
+
+// Shared data of `BufferPtr` slices.
+// Tracked memory is released and data is returned to the pool, if set, when the last
+// slice is dropped.
+#[derive(Debug)]
+struct BufferData<T> {
+  data: Vec<T>,
+  mem_tracker: Option<MemTrackerPtr>,
+  pool: Option<BufferPoolPtr<T>>
 }
 
 impl<T> BufferPtr<T> {
@@ -277,16 +634,15 @@ impl<T> BufferPtr<T> {
   pub fn new(v: Vec<T>) -> Self {
     let len = v.len();
     Self {
-      data: Rc::new(v),
+      data: Arc::new(BufferData { data: v, mem_tracker: None, pool: None }),
       start: 0,
-      len: len,
-      mem_tracker: None
+      len: len
     }
   }
 
   /// Returns slice of data in this buffer.
   pub fn data(&self) -> &[T] {
-    &self.data[self.start..self.start + self.len]
+    &self.data.data[self.start..self.start + self.len]
   }
 
   /// Updates this buffer with new `start` position and length `len`.
@@ -300,9 +656,25 @@ impl<T> BufferPtr<T> {
     self
   }
 
-  /// Adds memory tracker to this buffer.
+  /// Adds memory tracker to this buffer and all its slices.
+  ///
+  /// Memory tracker is only added if this buffer is the only reference to the data,
+  /// e.g. right after the buffer is created.
   pub fn with_mem_tracker(mut self, mc: MemTrackerPtr) -> Self {
-    self.mem_tracker = Some(mc);
+    if let Some(data) = Arc::get_mut(&mut self.data) {
+      data.mem_tracker = Some(mc);
+    }
+    self
+  }
+
+  /// Adds [`BufferPool`] to this buffer, data is returned to the pool when this buffer
+  /// and all its slices are dropped.
+  ///
+  /// Pool is only added if this buffer is the only reference to the data.
+  pub fn with_pool(mut self, pool: BufferPoolPtr<T>) -> Self {
+    if let Some(data) = Arc::get_mut(&mut self.data) {
+      data.pool = Some(pool);
+    }
     self
   }
 
@@ -318,7 +690,7 @@ impl<T> BufferPtr<T> {
 
   /// Returns `true` if this buffer has memory tracker, `false` otherwise.
   pub fn is_mem_tracked(&self) -> bool {
-    self.mem_tracker.is_some()
+    self.data.mem_tracker.is_some()
   }
 
   /// Returns a shallow copy of the buffer.
@@ -327,8 +699,7 @@ impl<T> BufferPtr<T> {
     BufferPtr {
       data: self.data.clone(),
       start: self.start,
-      len: self.len,
-      mem_tracker: self.mem_tracker.as_ref().map(|p| p.clone())
+      len: self.len
     }
   }
 
@@ -338,8 +709,7 @@ impl<T> BufferPtr<T> {
     BufferPtr {
       data: self.data.clone(),
       start: self.start + start,
-      len: self.len - start,
-      mem_tracker: self.mem_tracker.as_ref().map(|p| p.clone())
+      len: self.len - start
     }
   }
 
@@ -349,8 +719,7 @@ impl<T> BufferPtr<T> {
     BufferPtr {
       data: self.data.clone(),
       start: self.start + start,
-      len: len,
-      mem_tracker: self.mem_tracker.as_ref().map(|p| p.clone())
+      len: len
     }
   }
 }
@@ -359,29 +728,30 @@ impl<T: Sized> Index<usize> for BufferPtr<T> {
   type Output = T;
   fn index(&self, index: usize) -> &T {
     assert!(index < self.len);
-    &self.data[self.start + index]
+    &self.data.data[self.start + index]
   }
 }
 
 impl<T: Debug> Display for BufferPtr<T> {
   fn fmt(&self, f: &mut Formatter) -> FmtResult {
-    write!(f, "{:?}", self.data)
+    write!(f, "{:?}", self.data.data)
   }
 }
 
-impl<T> Drop for BufferPtr<T> {
+impl<T> Drop for BufferData<T> {
   fn drop(&mut self) {
-    if self.is_mem_tracked() &&
-      Rc::strong_count(&self.data) == 1 && Rc::weak_count(&self.data) == 0 {
-      let mc = self.mem_tracker.as_ref().unwrap();
-      mc.alloc(-(self.data.capacity() as i64));
+    if let Some(ref mc) = self.mem_tracker {
+      mc.alloc(-((self.data.capacity() * mem::size_of::<T>()) as i64));
+    }
+    if let Some(ref pool) = self.pool {
+      pool.put(mem::replace(&mut self.data, vec![]));
     }
   }
 }
 
 impl AsRef<[u8]> for BufferPtr<u8> {
   fn as_ref(&self) -> &[u8] {
-    &self.data[self.start..self.start + self.len]
+    &self.data.data[self.start..self.start + self.len]
   }
 }
 
@@ -390,9 +760,11 @@ impl AsRef<[u8]> for BufferPtr<u8> {
 mod tests {
   use super::*;
 
+  use std::thread;
+
   #[test]
   fn test_byte_buffer_mem_tracker() {
-    let mem_tracker = Rc::new(MemTracker::new());
+    let mem_tracker = Arc::new(MemTracker::new());
 
     let mut buffer = ByteBuffer::new()
       .with_mem_tracker(mem_tracker.clone());
@@ -425,7 +797,7 @@ mod tests {
 
   #[test]
   fn test_byte_ptr_mem_tracker() {
-    let mem_tracker = Rc::new(MemTracker::new());
+    let mem_tracker = Arc::new(MemTracker::new());
 
     let mut buffer = ByteBuffer::new()
       .with_mem_tracker(mem_tracker.clone());
@@ -450,6 +822,213 @@ mod tests {
     assert_eq!(mem_tracker.memory_usage(), buffer.capacity() as i64);
   }
 
+  #[test]
+  fn test_buffer_pool() {
+    let mem_tracker = Arc::new(MemTracker::with_limit(100));
+    let pool = BufferPool::<u8>::new(2).with_mem_tracker(mem_tracker.clone());
+
+    let buffer = pool.get(10);
+    assert!(buffer.is_empty());
+    assert!(buffer.capacity() >= 10);
+    assert_eq!((pool.hits(), pool.misses()), (0, 1));
+    pool.put(vec![1; 10]);
+    assert_eq!(pool.num_buffers(), 1);
+    assert_eq!(pool.memory_usage() as i64, mem_tracker.memory_usage());
+
+    // Idle buffer is reused if it is large enough
+    let reused = pool.get(5);
+    assert!(reused.is_empty());
+    assert_eq!(reused.capacity(), 10);
+    assert_eq!((pool.hits(), pool.misses()), (1, 1));
+    assert_eq!(pool.num_buffers(), 0);
+    assert_eq!(mem_tracker.memory_usage(), 0);
+    assert!(pool.get(20).capacity() >= 20);
+    assert_eq!((pool.hits(), pool.misses()), (1, 2));
+
+    // Smallest buffer is replaced when pool is full
+    pool.put(reused);
+    pool.put(Vec::with_capacity(30));
+    pool.put(Vec::with_capacity(40));
+    assert_eq!(pool.num_buffers(), 2);
+    assert_eq!(pool.memory_usage(), 70);
+    pool.put(Vec::with_capacity(5));
+    assert_eq!(pool.memory_usage(), 70);
+    assert_eq!(mem_tracker.memory_usage(), 70);
+
+    // Buffers that do not fit into the limit of memory tracker are released
+    pool.put(Vec::with_capacity(50));
+    assert_eq!(pool.memory_usage(), 70);
+
+    pool.clear();
+    assert_eq!(pool.num_buffers(), 0);
+    assert_eq!(mem_tracker.memory_usage(), 0);
+  }
+
+  #[test]
+  fn test_byte_buffer_pool() {
+    let pool = Arc::new(BufferPool::new(4));
+    {
+      let mut buffer = ByteBuffer::new().with_pool(pool.clone());
+      buffer.try_resize(60, 0).unwrap();
+      assert_eq!(pool.misses(), 1);
+      let buf_ptr = buffer.consume();
+      let _ = buf_ptr.start_from(20);
+      assert_eq!(pool.num_buffers(), 0);
+    }
+    // Data is returned to the pool when the last slice is dropped
+    assert_eq!(pool.num_buffers(), 1);
+
+    let mem_tracker = Arc::new(MemTracker::new());
+    let mut buffer = ByteBuffer::new()
+      .with_mem_tracker(mem_tracker.clone())
+      .with_pool(pool.clone());
+    buffer.try_resize(30, 1).unwrap();
+    assert_eq!(buffer.data(), &[1; 30][..]);
+    assert_eq!(buffer.capacity(), 60);
+    assert_eq!(mem_tracker.memory_usage(), 60);
+    assert_eq!((pool.hits(), pool.num_buffers()), (1, 0));
+    drop(buffer);
+    assert_eq!(mem_tracker.memory_usage(), 0);
+    assert_eq!(pool.num_buffers(), 1);
+  }
+
+  #[test]
+  fn test_mem_tracker_limit() {
+    let mem_tracker = MemTracker::with_limit(100);
+    assert_eq!(mem_tracker.limit(), Some(100));
+    assert!(mem_tracker.try_alloc(60).is_ok());
+    assert_eq!(
+      mem_tracker.try_alloc(50),
+      Err(ParquetError::ResourceExhausted(
+        "Failed to allocate 50 bytes: memory usage 60 bytes, limit 100 bytes".to_owned()))
+    );
+    assert_eq!(mem_tracker.memory_usage(), 60);
+
+    // Deallocations are always allowed
+    assert!(mem_tracker.try_alloc(-60).is_ok());
+    assert!(mem_tracker.try_alloc(100).is_ok());
+    assert_eq!(mem_tracker.max_memory_usage(), 100);
+
+    assert_eq!(MemTracker::new().limit(), None);
+    assert!(MemTracker::new().try_alloc(::std::i64::MAX).is_ok());
+  }
+
+  #[test]
+  fn test_mem_tracker_shared_between_threads() {
+    let mem_tracker = Arc::new(MemTracker::with_limit(1000));
+    let handles = (0..4).map(|_| {
+      let mem_tracker = mem_tracker.clone();
+      thread::spawn(move || {
+        let mut num_allocated = 0;
+        for _ in 0..100 {
+          if mem_tracker.try_alloc(10).is_ok() {
+            num_allocated += 1;
+          }
+        }
+        num_allocated
+      })
+    }).collect::<Vec<_>>();
+
+    let total: i64 = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    assert_eq!(total, 100);
+    assert_eq!(mem_tracker.memory_usage(), 1000);
+    assert_eq!(mem_tracker.max_memory_usage(), 1000);
+  }
+
+  struct TestListener {
+    allocated: AtomicI64,
+    limit: i64
+  }
+
+  impl AllocationListener for TestListener {
+    fn alloc(&self, num_bytes: i64) {
+      self.allocated.fetch_add(num_bytes, Ordering::SeqCst);
+    }
+
+    fn try_alloc(&self, num_bytes: i64) -> Result<()> {
+      if self.allocated.load(Ordering::SeqCst) + num_bytes > self.limit {
+        return Err(general_err!("Host memory limit exceeded"));
+      }
+      self.alloc(num_bytes);
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_mem_tracker_listener() {
+    let listener = Arc::new(TestListener { allocated: AtomicI64::new(0), limit: 64 });
+    let mem_tracker = Arc::new(MemTracker::new().with_listener(listener.clone()));
+    {
+      let mut buffer = Buffer::<u8>::new().with_mem_tracker(mem_tracker.clone());
+      buffer.try_resize(32, 0).unwrap();
+      assert_eq!(listener.allocated.load(Ordering::SeqCst), 32);
+
+      // Rejected by listener, tracker is not updated
+      assert_eq!(
+        buffer.try_resize(128, 0),
+        Err(general_err!("Host memory limit exceeded"))
+      );
+      assert_eq!(mem_tracker.memory_usage(), 32);
+      assert_eq!(listener.allocated.load(Ordering::SeqCst), 32);
+
+      // Infallible allocations are always reported
+      buffer.resize(100, 0);
+      assert_eq!(listener.allocated.load(Ordering::SeqCst), mem_tracker.memory_usage());
+    }
+    assert_eq!(mem_tracker.memory_usage(), 0);
+    assert_eq!(listener.allocated.load(Ordering::SeqCst), 0);
+  }
+
+  #[test]
+  fn test_byte_buffer_mem_tracker_limit() {
+    let mem_tracker = Arc::new(MemTracker::with_limit(16));
+    {
+      let mut buffer = Buffer::<i32>::new().with_mem_tracker(mem_tracker.clone());
+      assert!(buffer.try_resize(4, 0).is_ok());
+      assert_eq!(mem_tracker.memory_usage(), 16);
+
+      assert!(buffer.try_resize(5, 0).is_err());
+      assert_eq!(buffer.size(), 4);
+      assert_eq!(mem_tracker.memory_usage(), 16);
+
+      // Shrinking does not allocate
+      assert!(buffer.try_resize(2, 0).is_ok());
+      assert!(buffer.try_set_data(vec![1; 32]).is_err());
+      assert_eq!(buffer.size(), 2);
+      assert!(buffer.try_set_data(vec![1; 3]).is_ok());
+      assert_eq!(mem_tracker.memory_usage(), 12);
+    }
+    assert_eq!(mem_tracker.memory_usage(), 0);
+  }
+
+  #[test]
+  fn test_memory_types_are_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<MemTracker>();
+    assert_send_sync::<MemTrackerPtr>();
+    assert_send_sync::<ByteBuffer>();
+    assert_send_sync::<ByteBufferPtr>();
+    assert_send_sync::<BufferPool<u8>>();
+  }
+
+  #[test]
+  fn test_byte_ptr_shared_between_threads() {
+    let mem_tracker = Arc::new(MemTracker::new());
+    let mut buffer = ByteBuffer::new().with_mem_tracker(mem_tracker.clone());
+    buffer.set_data((0..64).collect());
+    let buf_ptr = buffer.consume();
+
+    let handles = (0..4).map(|i| {
+      let slice = buf_ptr.range(i * 16, 16);
+      thread::spawn(move || slice.data().iter().map(|v| *v as usize).sum::<usize>())
+    }).collect::<Vec<_>>();
+    drop(buf_ptr);
+
+    let total: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    assert_eq!(total, (0..64).sum::<usize>());
+    assert_eq!(mem_tracker.memory_usage(), 0);
+  }
+
   #[test]
   fn test_byte_buffer() {
     let mut buffer = ByteBuffer::new();
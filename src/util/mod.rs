@@ -15,12 +15,20 @@
 // specific language governing permissions and limitations
 // under the License.
 
+#[macro_use]
+mod trace;
+
 pub mod io;
 pub mod memory;
 #[macro_use]
 pub mod bit_util;
 pub mod hash_util;
+pub mod bench_util;
+pub mod float16;
+pub mod utf8;
+pub mod distinct;
 mod bit_packing;
+pub mod thrift;
 
 #[cfg(test)]
 pub mod test_common;
@@ -162,15 +162,21 @@ pub fn get_test_file(file_name: &str) -> fs::File {
   file.unwrap()
 }
 
-/// Returns file handle for a temp file in 'target' directory with a provided content
-pub fn get_temp_file(file_name: &str, content: &[u8]) -> fs::File {
-  // build tmp path to a file in "target/debug/testdata"
+/// Returns path to a temp file in "target/debug/testdata", creates the directory if
+/// it does not exist
+pub fn get_temp_path(file_name: &str) -> path::PathBuf {
   let mut path_buf = env::current_dir().unwrap();
   path_buf.push("target");
   path_buf.push("debug");
   path_buf.push("testdata");
   fs::create_dir_all(&path_buf).unwrap();
   path_buf.push(file_name);
+  path_buf
+}
+
+/// Returns file handle for a temp file in 'target' directory with a provided content
+pub fn get_temp_file(file_name: &str, content: &[u8]) -> fs::File {
+  let path_buf = get_temp_path(file_name);
 
   // write file content
   let mut tmp_file = fs::File::create(path_buf.as_path()).unwrap();
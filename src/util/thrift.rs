@@ -0,0 +1,1789 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains Thrift input protocol that tolerates enum values unknown to
+//! `parquet_format`, so that files written by newer versions of Parquet remain at least
+//! partially readable.
+//!
+//! Generated Thrift code fails to deserialize a structure when any enum field has a value
+//! that is not in the Thrift definition, e.g. a new encoding. [`TolerantInputProtocol`]
+//! replaces such values with a placeholder and records them in [`UnknownEnums`], which
+//! is then used when converting Thrift structures into `basic` types, resulting in
+//! `Unknown(i32)` variants, e.g. [`Encoding::Unknown`](`::basic::Encoding::Unknown`).
+//!
+//! Members of Thrift unions that are not in the Thrift definition, e.g. the UUID logical
+//! type, are handled the same way: their field ids and fields, serialized with the
+//! compact protocol, are recorded and they are read as a placeholder member.
+//! [`TolerantOutputProtocol`] does the reverse and writes such members verbatim in
+//! place of the placeholder, so that they are not lost when metadata is written again.
+//!
+//! Fields of the root structure that are not in the Thrift definition, e.g. fields added
+//! to `FileMetaData` by newer versions of Parquet format, are skipped by generated code.
+//! Their values are recorded the same way and written after known fields. Some fields of
+//! nested structures are kept the same way, e.g. size statistics of column chunks.
+//!
+//! Corrupt input must not exhaust stack or memory, so [`TolerantInputProtocol`] limits
+//! nesting depth and, when configured, numbers of elements of collections and lengths
+//! of binary values, which generated code would otherwise allocate as read from input.
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use basic::{Compression, Encoding, LogicalType, PageType, RawLogicalType, TimeUnit};
+use parquet_format as parquet;
+use thrift;
+use thrift::protocol::{
+  TCompactOutputProtocol, TFieldIdentifier, TInputProtocol, TListIdentifier,
+  TMapIdentifier, TMessageIdentifier, TOutputProtocol, TSetIdentifier, TStructIdentifier,
+  TType
+};
+
+/// Enum fields of `FileMetaData` that can have unknown values, identified by field ids.
+const FILE_METADATA_ENUMS: &[EnumField] = &[
+  // schema.converted_type
+  EnumField { path: &[2, 6], kind: EnumKind::ConvertedType },
+  // row_groups.columns.meta_data.encodings
+  EnumField { path: &[4, 1, 3, 2], kind: EnumKind::Encoding },
+  // row_groups.columns.meta_data.codec
+  EnumField { path: &[4, 1, 3, 4], kind: EnumKind::CompressionCodec },
+  // row_groups.columns.meta_data.encoding_stats.page_type
+  EnumField { path: &[4, 1, 3, 13, 1], kind: EnumKind::PageType },
+  // row_groups.columns.meta_data.encoding_stats.encoding
+  EnumField { path: &[4, 1, 3, 13, 2], kind: EnumKind::Encoding }
+];
+
+/// Enum fields of `PageHeader` that can have unknown values, identified by field ids.
+const PAGE_HEADER_ENUMS: &[EnumField] = &[
+  // type
+  EnumField { path: &[1], kind: EnumKind::PageType },
+  // data_page_header.encoding, definition_level_encoding and repetition_level_encoding
+  EnumField { path: &[5, 2], kind: EnumKind::Encoding },
+  EnumField { path: &[5, 3], kind: EnumKind::Encoding },
+  EnumField { path: &[5, 4], kind: EnumKind::Encoding },
+  // dictionary_page_header.encoding
+  EnumField { path: &[7, 2], kind: EnumKind::Encoding },
+  // data_page_header_v2.encoding
+  EnumField { path: &[8, 4], kind: EnumKind::Encoding }
+];
+
+/// Enum fields of `ColumnChunk` that can have unknown values, identified by field ids.
+const COLUMN_CHUNK_ENUMS: &[EnumField] = &[
+  // meta_data.encodings
+  EnumField { path: &[3, 2], kind: EnumKind::Encoding },
+  // meta_data.codec
+  EnumField { path: &[3, 4], kind: EnumKind::CompressionCodec },
+  // meta_data.encoding_stats.page_type and encoding
+  EnumField { path: &[3, 13, 1], kind: EnumKind::PageType },
+  EnumField { path: &[3, 13, 2], kind: EnumKind::Encoding }
+];
+
+/// Enum fields of `ColumnMetaData` that can have unknown values, identified by field ids.
+const COLUMN_METADATA_ENUMS: &[EnumField] = &[
+  // encodings
+  EnumField { path: &[2], kind: EnumKind::Encoding },
+  // codec
+  EnumField { path: &[4], kind: EnumKind::CompressionCodec },
+  // encoding_stats.page_type and encoding
+  EnumField { path: &[13, 1], kind: EnumKind::PageType },
+  EnumField { path: &[13, 2], kind: EnumKind::Encoding }
+];
+
+/// Ids of fields of `FileMetaData` in `parquet_format`.
+const FILE_METADATA_FIELD_IDS: &[i16] = &[1, 2, 3, 4, 5, 6, 7];
+
+/// Ids of fields of `PageHeader` in `parquet_format`.
+const PAGE_HEADER_FIELD_IDS: &[i16] = &[1, 2, 3, 4, 5, 6, 7, 8];
+
+/// Ids of fields of `ColumnChunk` in `parquet_format`.
+const COLUMN_CHUNK_FIELD_IDS: &[i16] = &[1, 2, 3, 4, 5, 6, 7];
+
+/// Ids of fields of `ColumnMetaData` in `parquet_format`.
+const COLUMN_METADATA_FIELD_IDS: &[i16] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+
+/// Ids of fields of `FileCryptoMetaData`, which is not in `parquet_format`.
+const FILE_CRYPTO_METADATA_FIELD_IDS: &[i16] = &[1, 2];
+
+/// Fields of nested structures of `FileMetaData` that are not in `parquet_format` and
+/// are kept as unknown fields, identified by field ids.
+const FILE_METADATA_EXTENSION_FIELDS: &[&[i16]] = &[
+  // row_groups.columns.meta_data.bloom_filter_offset
+  &[4, 1, 3, 14],
+  // row_groups.columns.meta_data.bloom_filter_length
+  &[4, 1, 3, 15],
+  // row_groups.columns.meta_data.size_statistics
+  &[4, 1, 3, 16],
+  // row_groups.columns.meta_data.statistics.is_max_value_exact
+  &[4, 1, 3, 12, 7],
+  // row_groups.columns.meta_data.statistics.is_min_value_exact
+  &[4, 1, 3, 12, 8],
+  // row_groups.columns.crypto_metadata
+  &[4, 1, 8],
+  // row_groups.columns.encrypted_column_metadata
+  &[4, 1, 9]
+];
+
+/// Fields of nested structures of `ColumnMetaData` that are not in `parquet_format`
+/// and are kept as unknown fields, identified by field ids.
+const COLUMN_METADATA_EXTENSION_FIELDS: &[&[i16]] = &[
+  // statistics.is_max_value_exact
+  &[12, 7],
+  // statistics.is_min_value_exact
+  &[12, 8]
+];
+
+/// Ids of fields of `OffsetIndex` in `parquet_format`, sizes of unencoded `BYTE_ARRAY`
+/// values of pages are kept as unknown field.
+const OFFSET_INDEX_FIELD_IDS: &[i16] = &[1];
+
+/// Ids of fields of `ColumnIndex` in `parquet_format`, repetition and definition level
+/// histograms are kept as unknown fields.
+const COLUMN_INDEX_FIELD_IDS: &[i16] = &[1, 2, 3, 4, 5];
+
+/// Union fields of `FileMetaData` that can have unknown members, identified by field ids.
+const FILE_METADATA_UNIONS: &[UnionField] = &[
+  // schema.logical_type, unknown members are read as STRING
+  UnionField { path: &[2, 10], known_ids: &[1, 2, 3, 4, 5, 6, 7, 8, 10, 11, 12, 13] },
+  // schema.logical_type.TIME.unit and TIMESTAMP.unit, unknown members are read as MILLIS
+  UnionField { path: &[2, 10, 7, 2], known_ids: &[1, 2] },
+  UnionField { path: &[2, 10, 8, 2], known_ids: &[1, 2] }
+];
+
+/// Default maximum nesting depth of structures and collections, which is much deeper
+/// than any Thrift structure of `parquet_format`.
+const DEFAULT_MAX_DEPTH: usize = 64;
+
+/// Maximum length of binary values of page headers, i.e. of statistics of pages.
+const PAGE_HEADER_MAX_BINARY_SIZE: usize = 16 * 1024 * 1024;
+
+/// Field id of the placeholder member of a union, all members of unions in
+/// `FILE_METADATA_UNIONS` with this id are empty structures.
+const UNION_PLACEHOLDER_ID: i16 = 1;
+
+/// Field id of the UUID member of the `LogicalType` union.
+const LOGICAL_TYPE_UUID_ID: i16 = 14;
+
+/// Field id of the FLOAT16 member of the `LogicalType` union.
+const LOGICAL_TYPE_FLOAT16_ID: i16 = 15;
+
+/// Field id of the NANOS member of the `TimeUnit` union.
+const TIME_UNIT_NANOS_ID: i16 = 3;
+
+/// Thrift enums of `parquet_format` that are allowed to have unknown values.
+#[derive(Clone, Copy, Debug)]
+enum EnumKind {
+  ConvertedType,
+  Encoding,
+  CompressionCodec,
+  PageType
+}
+
+impl EnumKind {
+  /// Returns `true` if `value` is defined in `parquet_format`.
+  fn is_known(&self, value: i32) -> bool {
+    match *self {
+      EnumKind::ConvertedType => (0..=21).contains(&value),
+      EnumKind::Encoding => value == 0 || (2..=8).contains(&value),
+      EnumKind::CompressionCodec => (0..=6).contains(&value),
+      EnumKind::PageType => (0..=3).contains(&value)
+    }
+  }
+
+  /// Returns value that replaces unknown value during deserialization.
+  /// Pages of unknown type are replaced with index pages, which are skipped by readers.
+  fn placeholder(&self) -> i32 {
+    match *self {
+      EnumKind::ConvertedType => parquet::ConvertedType::UTF8 as i32,
+      EnumKind::Encoding => parquet::Encoding::PLAIN as i32,
+      EnumKind::CompressionCodec => parquet::CompressionCodec::UNCOMPRESSED as i32,
+      EnumKind::PageType => parquet::PageType::INDEX_PAGE as i32
+    }
+  }
+}
+
+/// Enum field, identified by field ids from the root structure.
+struct EnumField {
+  path: &'static [i16],
+  kind: EnumKind
+}
+
+/// Union field, identified by field ids from the root structure.
+struct UnionField {
+  path: &'static [i16],
+  known_ids: &'static [i16]
+}
+
+/// Returns logical type for member of the `LogicalType` union with field id `id`, if the
+/// member is not in `parquet_format`, but is supported. Other members are represented
+/// as [`LogicalType::Other`](`::basic::LogicalType::Other`).
+pub fn logical_type_from_member_id(id: i16) -> Option<LogicalType> {
+  match id {
+    LOGICAL_TYPE_UUID_ID => Some(LogicalType::UUID),
+    LOGICAL_TYPE_FLOAT16_ID => Some(LogicalType::FLOAT16),
+    _ => None
+  }
+}
+
+/// Returns field id of the member of the `LogicalType` union for `logical_type`, if the
+/// member is not in `parquet_format`. Such logical types are written as the placeholder
+/// member, see [`logical_type_placeholder`](fn.logical_type_placeholder.html).
+pub fn logical_type_to_member_id(logical_type: LogicalType) -> Option<i16> {
+  match logical_type {
+    LogicalType::UUID => Some(LOGICAL_TYPE_UUID_ID),
+    LogicalType::FLOAT16 => Some(LOGICAL_TYPE_FLOAT16_ID),
+    LogicalType::Other(raw) => Some(raw.id()),
+    _ => None
+  }
+}
+
+/// Returns placeholder member of the `LogicalType` union, which is replaced with a
+/// member unknown to `parquet_format` by [`TolerantOutputProtocol`].
+pub fn logical_type_placeholder() -> parquet::LogicalType {
+  parquet::LogicalType::STRING(parquet::StringType::new())
+}
+
+/// Returns field id of the member of the `TimeUnit` union for `unit`, if the member is
+/// not in `parquet_format`.
+pub fn time_unit_to_member_id(unit: TimeUnit) -> Option<i16> {
+  match unit {
+    TimeUnit::NANOS => Some(TIME_UNIT_NANOS_ID),
+    _ => None
+  }
+}
+
+/// Converts `unit` into Thrift time unit, units that are not in `parquet_format` are
+/// converted into the placeholder member MILLIS, see
+/// [`time_unit_to_member_id`](fn.time_unit_to_member_id.html).
+pub fn time_unit_to_thrift(unit: TimeUnit) -> parquet::TimeUnit {
+  match unit {
+    TimeUnit::MICROS => parquet::TimeUnit::MICROS(parquet::MicroSeconds::new()),
+    _ => parquet::TimeUnit::MILLIS(parquet::MilliSeconds::new())
+  }
+}
+
+/// Structure or collection that is being read or written.
+enum Frame {
+  // Id of the field that is being read
+  Struct(Option<i16>),
+  // Number of elements read so far, including the current one
+  Collection(usize)
+}
+
+// ----------------------------------------------------------------------
+// Unknown enum values
+
+/// Enum values that are not defined in `parquet_format`, found while reading a Thrift
+/// structure. Field ids of union members that are not defined in `parquet_format` are
+/// kept as values at the path of the union, together with their serialized fields.
+/// Values of unknown fields of the root structure and of known extension fields of
+/// nested structures are kept at the path of the field.
+///
+/// Each value is identified by a path from the root structure, that consists of field
+/// ids and, for fields of list type, indices of list elements. For example, path of the
+/// second encoding of the first column chunk of the third row group in `FileMetaData` is
+/// `[4, 2, 1, 0, 3, 2, 1]`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UnknownEnums {
+  values: Vec<(Vec<usize>, i32)>,
+  // Fields of unknown union members serialized with the compact protocol
+  members: Vec<(Vec<usize>, Vec<u8>)>,
+  // Types and values of unknown fields serialized with the compact protocol
+  fields: Vec<(Vec<usize>, TType, Vec<u8>)>
+}
+
+impl UnknownEnums {
+  /// Returns `true` if there are no unknown enum values.
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty() && self.members.is_empty() && self.fields.is_empty()
+  }
+
+  /// Returns unknown value at `path`, if any.
+  pub fn get(&self, path: &[usize]) -> Option<i32> {
+    self.values.iter().find(|(p, _)| p.as_slice() == path).map(|&(_, value)| value)
+  }
+
+  /// Adds unknown value at `path`.
+  pub fn insert(&mut self, path: Vec<usize>, value: i32) {
+    self.values.push((path, value));
+  }
+
+  /// Returns fields of unknown union member at `path` serialized with the compact
+  /// protocol, if any.
+  pub fn member(&self, path: &[usize]) -> Option<&[u8]> {
+    self.members.iter().find(|(p, _)| p.as_slice() == path).map(|(_, data)| &data[..])
+  }
+
+  /// Adds fields of unknown union member at `path` serialized with the compact
+  /// protocol. Field id of the member is added with [`insert`](#method.insert).
+  pub fn insert_member(&mut self, path: Vec<usize>, data: Vec<u8>) {
+    self.members.push((path, data));
+  }
+
+  /// Returns type and value of unknown field at `path`, with the value serialized with
+  /// the compact protocol, if any.
+  pub fn field(&self, path: &[usize]) -> Option<(TType, &[u8])> {
+    self.fields.iter()
+      .find(|(p, _, _)| p.as_slice() == path)
+      .map(|(_, field_type, data)| (*field_type, &data[..]))
+  }
+
+  /// Adds unknown field at `path` of type `field_type`, with value `data` serialized
+  /// with the compact protocol.
+  pub fn insert_field(&mut self, path: Vec<usize>, field_type: TType, data: Vec<u8>) {
+    self.fields.push((path, field_type, data));
+  }
+
+  /// Removes unknown field at `path`, if any.
+  pub fn remove_field(&mut self, path: &[usize]) {
+    self.fields.retain(|(p, _, _)| p.as_slice() != path);
+  }
+
+  /// Returns unknown fields of the root structure only, without unknown enum values,
+  /// union members and fields of nested structures.
+  pub fn unknown_fields(&self) -> UnknownEnums {
+    let fields = self.fields.iter().filter(|(path, _, _)| path.len() == 1).cloned();
+    UnknownEnums { values: vec![], members: vec![], fields: fields.collect() }
+  }
+
+  /// Adds unknown values, union members and fields of `other`, except ones at paths
+  /// that already have a value of the same kind.
+  pub fn extend(&mut self, other: &UnknownEnums) {
+    for (path, value) in &other.values {
+      if self.get(path).is_none() {
+        self.values.push((path.clone(), *value));
+      }
+    }
+    for (path, data) in &other.members {
+      if self.member(path).is_none() {
+        self.members.push((path.clone(), data.clone()));
+      }
+    }
+    for (path, field_type, data) in &other.fields {
+      if self.field(path).is_none() {
+        self.fields.push((path.clone(), *field_type, data.clone()));
+      }
+    }
+  }
+
+  /// Returns unknown values of a nested structure or list at `prefix`, with paths
+  /// relative to the nested structure.
+  pub fn scope(&self, prefix: &[usize]) -> UnknownEnums {
+    let values = self.values.iter()
+      .filter(|(path, _)| path.starts_with(prefix))
+      .map(|(path, value)| (path[prefix.len()..].to_vec(), *value))
+      .collect();
+    let members = self.members.iter()
+      .filter(|(path, _)| path.starts_with(prefix))
+      .map(|(path, data)| (path[prefix.len()..].to_vec(), data.clone()))
+      .collect();
+    let fields = self.fields.iter()
+      .filter(|(path, _, _)| path.starts_with(prefix))
+      .map(|(path, field_type, data)| {
+        (path[prefix.len()..].to_vec(), *field_type, data.clone())
+      })
+      .collect();
+    UnknownEnums { values: values, members: members, fields: fields }
+  }
+
+  /// Removes unknown values, union members and fields of a nested structure or list
+  /// at `prefix`, e.g. before replacing the structure.
+  pub fn remove_scope(&mut self, prefix: &[usize]) {
+    self.values.retain(|(path, _)| !path.starts_with(prefix));
+    self.members.retain(|(path, _)| !path.starts_with(prefix));
+    self.fields.retain(|(path, _, _)| !path.starts_with(prefix));
+  }
+
+  /// Returns unknown values with `prefix` prepended to their paths, i.e. the inverse of
+  /// [`scope`](#method.scope).
+  pub fn nest(&self, prefix: &[usize]) -> UnknownEnums {
+    let values = self.values.iter()
+      .map(|(path, value)| (prefix.iter().chain(path).cloned().collect(), *value))
+      .collect();
+    let members = self.members.iter()
+      .map(|(path, data)| (prefix.iter().chain(path).cloned().collect(), data.clone()))
+      .collect();
+    let fields = self.fields.iter()
+      .map(|(path, field_type, data)| {
+        (prefix.iter().chain(path).cloned().collect(), *field_type, data.clone())
+      })
+      .collect();
+    UnknownEnums { values: values, members: members, fields: fields }
+  }
+
+  /// Converts Thrift encoding at `path`, returns `Encoding::Unknown` if the value is
+  /// unknown.
+  pub fn encoding(&self, path: &[usize], value: parquet::Encoding) -> Encoding {
+    self.get(path).map(Encoding::Unknown).unwrap_or_else(|| Encoding::from(value))
+  }
+
+  /// Converts Thrift compression codec at `path`, returns `Compression::Unknown` if the
+  /// value is unknown.
+  pub fn compression(
+    &self,
+    path: &[usize],
+    value: parquet::CompressionCodec
+  ) -> Compression {
+    self.get(path).map(Compression::Unknown).unwrap_or_else(|| Compression::from(value))
+  }
+
+  /// Converts Thrift page type at `path`, returns `PageType::Unknown` if the value is
+  /// unknown.
+  pub fn page_type(&self, path: &[usize], value: parquet::PageType) -> PageType {
+    self.get(path).map(PageType::Unknown).unwrap_or_else(|| PageType::from(value))
+  }
+
+  /// Converts Thrift converted type at `path`, returns `LogicalType::Unknown` if the
+  /// value is unknown.
+  pub fn logical_type(
+    &self,
+    path: &[usize],
+    value: Option<parquet::ConvertedType>
+  ) -> LogicalType {
+    self.get(path).map(LogicalType::Unknown).unwrap_or_else(|| LogicalType::from(value))
+  }
+
+  /// Returns logical type for unknown member of the `LogicalType` union at `path`, if
+  /// any. Members that are not supported are returned as `LogicalType::Other`.
+  pub fn logical_type_member(&self, path: &[usize]) -> Option<LogicalType> {
+    let id = self.get(path)? as i16;
+    logical_type_from_member_id(id).or_else(|| {
+      let data = self.member(path).unwrap_or(&[]);
+      Some(LogicalType::Other(RawLogicalType::new(id, data)))
+    })
+  }
+
+  /// Converts Thrift time unit at `path`, returns `None` if the unit is an unknown
+  /// member of the `TimeUnit` union other than NANOS.
+  pub fn time_unit(&self, path: &[usize], value: &parquet::TimeUnit) -> Option<TimeUnit> {
+    match self.get(path) {
+      Some(id) if id == TIME_UNIT_NANOS_ID as i32 => Some(TimeUnit::NANOS),
+      Some(_) => None,
+      None => match *value {
+        parquet::TimeUnit::MILLIS(_) => Some(TimeUnit::MILLIS),
+        parquet::TimeUnit::MICROS(_) => Some(TimeUnit::MICROS)
+      }
+    }
+  }
+}
+
+/// Records start of a value, which is a new element when in a collection.
+fn begin_value(stack: &mut Vec<Frame>) {
+  if let Some(&mut Frame::Collection(ref mut num_elements)) = stack.last_mut() {
+    *num_elements += 1;
+  }
+}
+
+/// Returns ids of fields that are being read or written, from the root structure.
+fn field_ids<'a>(stack: &'a [Frame]) -> impl Iterator<Item = i16> + Clone + 'a {
+  stack.iter().filter_map(|frame| match *frame {
+    Frame::Struct(id) => id,
+    Frame::Collection(_) => None
+  })
+}
+
+/// Returns path of the value that is being read or written.
+fn path(stack: &[Frame]) -> Vec<usize> {
+  stack.iter().filter_map(|frame| match *frame {
+    Frame::Struct(id) => id.map(|id| id as usize),
+    Frame::Collection(num_elements) => Some(num_elements - 1)
+  }).collect()
+}
+
+/// Returns union field whose member is being read or written, if any.
+fn union_field<'a>(unions: &'a [UnionField], stack: &[Frame]) -> Option<&'a UnionField> {
+  match stack.split_last() {
+    Some((&Frame::Struct(_), parents)) => {
+      unions.iter().find(|union| field_ids(parents).eq(union.path.iter().cloned()))
+    },
+    _ => None
+  }
+}
+
+/// Buffer shared between a protocol that writes to it and its owner.
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.0.borrow_mut().write(buf)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+/// Value unknown to `parquet_format` that is serialized as it is read.
+enum CaptureTarget {
+  // Fields of unknown union member at path, captured until the field stop
+  Member(Vec<usize>),
+  // Value of unknown field at path, captured until the field end
+  Field(Vec<usize>, TType)
+}
+
+/// Capture of a value unknown to `parquet_format`.
+struct Capture {
+  // Depth of the stack when reading fields of the member or the field itself
+  depth: usize,
+  target: CaptureTarget,
+  buffer: SharedBuffer,
+  prot: TCompactOutputProtocol<SharedBuffer>
+}
+
+// ----------------------------------------------------------------------
+// Tolerant input protocol
+
+/// Thrift input protocol that replaces unknown enum values of known fields with a
+/// placeholder, see module documentation for more details.
+pub struct TolerantInputProtocol<P: TInputProtocol> {
+  inner: P,
+  enum_fields: &'static [EnumField],
+  union_fields: &'static [UnionField],
+  known_root_field_ids: &'static [i16],
+  extension_fields: &'static [&'static [i16]],
+  stack: Vec<Frame>,
+  unknown_enums: UnknownEnums,
+  root_field_ids: Vec<i16>,
+  // Path of unknown union member, whose structure is about to be read
+  pending_member: Option<Vec<usize>>,
+  capture: Option<Capture>,
+  max_collection_size: Option<usize>,
+  // Limits of numbers of elements of collection fields, identified by field ids
+  collection_limits: Vec<(&'static [i16], &'static str, usize)>,
+  max_binary_size: Option<usize>,
+  max_depth: usize
+}
+
+impl<P: TInputProtocol> TolerantInputProtocol<P> {
+  /// Creates protocol to read `FileMetaData` from `inner` protocol.
+  pub fn for_file_metadata(inner: P) -> Self {
+    Self::new(
+      inner,
+      FILE_METADATA_ENUMS,
+      FILE_METADATA_UNIONS,
+      FILE_METADATA_FIELD_IDS,
+      FILE_METADATA_EXTENSION_FIELDS
+    )
+  }
+
+  /// Creates protocol to read `PageHeader` from `inner` protocol. Page headers are
+  /// read from streams of unknown size, so length of their binary values is limited
+  /// to 16 MB.
+  pub fn for_page_header(inner: P) -> Self {
+    Self::new(inner, PAGE_HEADER_ENUMS, &[], PAGE_HEADER_FIELD_IDS, &[])
+      .with_max_binary_size(PAGE_HEADER_MAX_BINARY_SIZE)
+  }
+
+  /// Creates protocol to read `ColumnChunk` from `inner` protocol, e.g. metadata that
+  /// writers store after pages of a column chunk. Length of binary values is limited
+  /// as for page headers.
+  pub fn for_column_chunk(inner: P) -> Self {
+    Self::new(inner, COLUMN_CHUNK_ENUMS, &[], COLUMN_CHUNK_FIELD_IDS, &[])
+      .with_max_binary_size(PAGE_HEADER_MAX_BINARY_SIZE)
+  }
+
+  /// Creates protocol to read `ColumnMetaData` from `inner` protocol, e.g. decrypted
+  /// metadata of an encrypted column.
+  pub fn for_column_metadata(inner: P) -> Self {
+    Self::new(
+      inner,
+      COLUMN_METADATA_ENUMS,
+      &[],
+      COLUMN_METADATA_FIELD_IDS,
+      COLUMN_METADATA_EXTENSION_FIELDS
+    )
+  }
+
+  /// Creates protocol to read `FileCryptoMetaData` of a file with encrypted footer
+  /// from `inner` protocol.
+  pub fn for_file_crypto_metadata(inner: P) -> Self {
+    Self::new(inner, &[], &[], FILE_CRYPTO_METADATA_FIELD_IDS, &[])
+  }
+
+  /// Creates protocol to read `OffsetIndex` from `inner` protocol.
+  pub fn for_offset_index(inner: P) -> Self {
+    Self::new(inner, &[], &[], OFFSET_INDEX_FIELD_IDS, &[])
+  }
+
+  /// Creates protocol to read `ColumnIndex` from `inner` protocol.
+  pub fn for_column_index(inner: P) -> Self {
+    Self::new(inner, &[], &[], COLUMN_INDEX_FIELD_IDS, &[])
+  }
+
+  fn new(
+    inner: P,
+    enum_fields: &'static [EnumField],
+    union_fields: &'static [UnionField],
+    known_root_field_ids: &'static [i16],
+    extension_fields: &'static [&'static [i16]]
+  ) -> Self {
+    Self {
+      inner: inner,
+      enum_fields: enum_fields,
+      union_fields: union_fields,
+      known_root_field_ids: known_root_field_ids,
+      extension_fields: extension_fields,
+      stack: Vec::new(),
+      unknown_enums: UnknownEnums::default(),
+      root_field_ids: Vec::new(),
+      pending_member: None,
+      capture: None,
+      max_collection_size: None,
+      collection_limits: Vec::new(),
+      max_binary_size: None,
+      max_depth: DEFAULT_MAX_DEPTH
+    }
+  }
+
+  /// Sets maximum number of elements of any list, set or map. Generated code allocates
+  /// lists with the number of elements read from input, so this bounds allocations
+  /// when input is corrupt, e.g. with size of the serialized structure, because each
+  /// element takes at least one byte in the compact protocol.
+  pub fn with_max_collection_size(mut self, max_size: usize) -> Self {
+    self.max_collection_size = Some(max_size);
+    self
+  }
+
+  /// Sets maximum number of elements of collection field at `path` of field ids from
+  /// the root structure, `name` of elements is used in error message.
+  pub fn with_collection_limit(
+    mut self,
+    path: &'static [i16],
+    name: &'static str,
+    limit: usize
+  ) -> Self {
+    self.collection_limits.push((path, name, limit));
+    self
+  }
+
+  /// Sets maximum length of binary and string values, which is checked before the
+  /// value is allocated. Length is then read as a varint of the compact protocol, so
+  /// `inner` protocol must be a compact protocol.
+  pub fn with_max_binary_size(mut self, max_size: usize) -> Self {
+    self.max_binary_size = Some(max_size);
+    self
+  }
+
+  /// Sets maximum nesting depth of structures and collections, including the root
+  /// structure, 64 by default. Values of unknown fields are skipped recursively, so
+  /// this bounds recursion when input is corrupt.
+  pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+    self.max_depth = max_depth;
+    self
+  }
+
+  /// Returns unknown enum values found so far.
+  pub fn unknown_enums(&self) -> &UnknownEnums {
+    &self.unknown_enums
+  }
+
+  /// Returns ids of fields of the root structure read so far, including fields that
+  /// are not in the Thrift definition and are skipped, e.g. fields added by newer
+  /// versions of Parquet format.
+  pub fn root_field_ids(&self) -> &[i16] {
+    &self.root_field_ids
+  }
+
+  /// Consumes protocol and returns unknown enum values.
+  pub fn into_unknown_enums(self) -> UnknownEnums {
+    self.unknown_enums
+  }
+
+  /// Records start of a value, which is a new element when reading a collection.
+  fn begin_value(&mut self) {
+    begin_value(&mut self.stack);
+  }
+
+  /// Returns kind of enum field that is being read, if any.
+  fn enum_kind(&self) -> Option<EnumKind> {
+    let field_ids = field_ids(&self.stack);
+    self.enum_fields.iter()
+      .find(|field| field_ids.clone().eq(field.path.iter().cloned()))
+      .map(|field| field.kind)
+  }
+
+  /// Returns `true` if field that is being read is a field of a nested structure that
+  /// is not in `parquet_format`, but is kept as unknown field.
+  fn is_extension_field(&self) -> bool {
+    let field_ids = field_ids(&self.stack);
+    self.extension_fields.iter().any(|path| field_ids.clone().eq(path.iter().cloned()))
+  }
+
+  /// Returns path of the value that is being read.
+  fn path(&self) -> Vec<usize> {
+    path(&self.stack)
+  }
+
+  /// Starts reading a structure or collection with `frame`, checks nesting depth.
+  fn push_frame(&mut self, frame: Frame) -> thrift::Result<()> {
+    self.begin_value();
+    self.stack.push(frame);
+    if self.stack.len() > self.max_depth {
+      return Err(thrift::Error::User(format!(
+        "Nesting depth of Thrift structures exceeds limit {}", self.max_depth).into()));
+    }
+    Ok(())
+  }
+
+  /// Reads binary value, checking its length before allocation if limit is set.
+  fn read_binary(&mut self) -> thrift::Result<Vec<u8>> {
+    let max_size = match self.max_binary_size {
+      Some(max_size) => max_size,
+      None => return self.inner.read_bytes()
+    };
+    // Length is an unsigned varint of at most 5 bytes
+    let mut len: u64 = 0;
+    let mut shift = 0;
+    loop {
+      let byte = self.inner.read_byte()?;
+      len |= ((byte & 0x7F) as u64) << shift;
+      if byte & 0x80 == 0 {
+        break;
+      }
+      shift += 7;
+      if shift > 28 {
+        return Err(thrift::Error::User("Invalid length of binary value".into()));
+      }
+    }
+    if len > max_size as u64 {
+      return Err(thrift::Error::User(format!(
+        "Length of binary value {} exceeds limit {}", len, max_size).into()));
+    }
+    let mut value = Vec::with_capacity(len as usize);
+    for _ in 0..len {
+      value.push(self.inner.read_byte()?);
+    }
+    Ok(value)
+  }
+
+  /// Checks number of elements of collection that is being read against limits.
+  fn check_collection_size(&self, size: i32) -> thrift::Result<()> {
+    if size < 0 {
+      return Err(thrift::Error::User(
+        format!("Invalid number of collection elements: {}", size).into()));
+    }
+    let size = size as usize;
+    if let Some(max_size) = self.max_collection_size {
+      if size > max_size {
+        return Err(thrift::Error::User(format!(
+          "Number of collection elements {} exceeds limit {}", size, max_size).into()));
+      }
+    }
+    let field_ids = field_ids(&self.stack);
+    for &(path, name, limit) in &self.collection_limits {
+      if size > limit && field_ids.clone().eq(path.iter().cloned()) {
+        return Err(thrift::Error::User(
+          format!("Number of {} {} exceeds limit {}", name, size, limit).into()));
+      }
+    }
+    Ok(())
+  }
+
+  /// Starts capture of `target`, whose values are read at `depth` of the stack.
+  fn start_capture(&mut self, depth: usize, target: CaptureTarget) {
+    let buffer = SharedBuffer::default();
+    self.capture = Some(Capture {
+      depth: depth,
+      target: target,
+      buffer: buffer.clone(),
+      prot: TCompactOutputProtocol::new(buffer)
+    });
+  }
+
+  /// Finishes capture, if any, and records the captured value.
+  fn finish_capture(&mut self) {
+    if let Some(capture) = self.capture.take() {
+      let data = capture.buffer.0.borrow().clone();
+      match capture.target {
+        CaptureTarget::Member(path) => {
+          if !data.is_empty() {
+            self.unknown_enums.insert_member(path, data);
+          }
+        },
+        CaptureTarget::Field(path, field_type) => {
+          self.unknown_enums.insert_field(path, field_type, data);
+        }
+      }
+    }
+  }
+
+  /// Returns `true` if capture ends with the field stop or the field end, depending on
+  /// `is_stop`, at the current depth.
+  fn is_capture_end(&self, is_stop: bool) -> bool {
+    match self.capture {
+      Some(Capture { depth, target: CaptureTarget::Member(_), .. }) => {
+        is_stop && depth == self.stack.len()
+      },
+      Some(Capture { depth, target: CaptureTarget::Field(..), .. }) => {
+        !is_stop && depth == self.stack.len()
+      },
+      None => false
+    }
+  }
+
+  /// Writes value that has been read with `write`, if a value unknown to
+  /// `parquet_format` is being read.
+  fn write_captured<F>(&mut self, write: F) -> thrift::Result<()>
+    where F: FnOnce(&mut TOutputProtocol) -> thrift::Result<()> {
+    match self.capture {
+      Some(ref mut capture) => write(&mut capture.prot),
+      None => Ok(())
+    }
+  }
+}
+
+impl<P: TInputProtocol> TInputProtocol for TolerantInputProtocol<P> {
+  fn read_message_begin(&mut self) -> thrift::Result<TMessageIdentifier> {
+    self.inner.read_message_begin()
+  }
+
+  fn read_message_end(&mut self) -> thrift::Result<()> {
+    self.inner.read_message_end()
+  }
+
+  fn read_struct_begin(&mut self) -> thrift::Result<Option<TStructIdentifier>> {
+    self.push_frame(Frame::Struct(None))?;
+    let identifier = self.inner.read_struct_begin()?;
+    match self.pending_member.take() {
+      Some(path) => {
+        // Fields of the member are written without the enclosing structure
+        let depth = self.stack.len();
+        self.start_capture(depth, CaptureTarget::Member(path));
+      },
+      None => {
+        let identifier = TStructIdentifier::new("");
+        self.write_captured(|prot| prot.write_struct_begin(&identifier))?
+      }
+    }
+    Ok(identifier)
+  }
+
+  fn read_struct_end(&mut self) -> thrift::Result<()> {
+    self.stack.pop();
+    self.write_captured(|prot| prot.write_struct_end())?;
+    self.inner.read_struct_end()
+  }
+
+  fn read_field_begin(&mut self) -> thrift::Result<TFieldIdentifier> {
+    let mut field = self.inner.read_field_begin()?;
+    if field.field_type == TType::Stop && self.is_capture_end(true) {
+      self.finish_capture();
+    } else if field.field_type == TType::Stop {
+      self.write_captured(|prot| prot.write_field_stop())?;
+    } else {
+      self.write_captured(|prot| prot.write_field_begin(&field))?;
+    }
+
+    let union = union_field(self.union_fields, &self.stack);
+    if let (Some(union), Some(id)) = (union, field.id) {
+      // Only structures can be replaced with the placeholder member, which is an empty
+      // structure, fields of the member are kept separately
+      if !union.known_ids.contains(&id) && field.field_type == TType::Struct {
+        let path = self.path();
+        self.unknown_enums.values.push((path.clone(), id as i32));
+        self.pending_member = Some(path);
+        field.id = Some(UNION_PLACEHOLDER_ID);
+      }
+    }
+    if let Some(&mut Frame::Struct(ref mut id)) = self.stack.last_mut() {
+      *id = field.id;
+    }
+    if let (1, Some(id)) = (self.stack.len(), field.id) {
+      self.root_field_ids.push(id);
+      // Value of the field is skipped by generated code, which reads it with this
+      // protocol, so it is captured as any other value
+      if !self.known_root_field_ids.contains(&id) {
+        self.start_capture(1, CaptureTarget::Field(vec![id as usize], field.field_type));
+      }
+    } else if field.id.is_some() && self.capture.is_none() && self.is_extension_field() {
+      let depth = self.stack.len();
+      let path = self.path();
+      self.start_capture(depth, CaptureTarget::Field(path, field.field_type));
+    }
+    Ok(field)
+  }
+
+  fn read_field_end(&mut self) -> thrift::Result<()> {
+    if self.is_capture_end(false) {
+      self.finish_capture();
+    } else {
+      self.write_captured(|prot| prot.write_field_end())?;
+    }
+    self.inner.read_field_end()
+  }
+
+  fn read_bool(&mut self) -> thrift::Result<bool> {
+    self.begin_value();
+    let value = self.inner.read_bool()?;
+    self.write_captured(|prot| prot.write_bool(value))?;
+    Ok(value)
+  }
+
+  fn read_bytes(&mut self) -> thrift::Result<Vec<u8>> {
+    self.begin_value();
+    let value = self.read_binary()?;
+    self.write_captured(|prot| prot.write_bytes(&value))?;
+    Ok(value)
+  }
+
+  fn read_i8(&mut self) -> thrift::Result<i8> {
+    self.begin_value();
+    let value = self.inner.read_i8()?;
+    self.write_captured(|prot| prot.write_i8(value))?;
+    Ok(value)
+  }
+
+  fn read_i16(&mut self) -> thrift::Result<i16> {
+    self.begin_value();
+    let value = self.inner.read_i16()?;
+    self.write_captured(|prot| prot.write_i16(value))?;
+    Ok(value)
+  }
+
+  fn read_i32(&mut self) -> thrift::Result<i32> {
+    self.begin_value();
+    let value = self.inner.read_i32()?;
+    self.write_captured(|prot| prot.write_i32(value))?;
+    match self.enum_kind() {
+      Some(kind) if !kind.is_known(value) => {
+        let path = self.path();
+        self.unknown_enums.values.push((path, value));
+        Ok(kind.placeholder())
+      },
+      _ => Ok(value)
+    }
+  }
+
+  fn read_i64(&mut self) -> thrift::Result<i64> {
+    self.begin_value();
+    let value = self.inner.read_i64()?;
+    self.write_captured(|prot| prot.write_i64(value))?;
+    Ok(value)
+  }
+
+  fn read_double(&mut self) -> thrift::Result<f64> {
+    self.begin_value();
+    let value = self.inner.read_double()?;
+    self.write_captured(|prot| prot.write_double(value))?;
+    Ok(value)
+  }
+
+  fn read_string(&mut self) -> thrift::Result<String> {
+    self.begin_value();
+    // Captured values are skipped by generated code, which reads binary values as
+    // strings, so they are kept as is, e.g. encrypted column metadata
+    if self.capture.is_some() {
+      let value = self.read_binary()?;
+      self.write_captured(|prot| prot.write_bytes(&value))?;
+      return Ok(String::new());
+    }
+    let value = match self.max_binary_size {
+      Some(_) => String::from_utf8(self.read_binary()?)?,
+      None => self.inner.read_string()?
+    };
+    Ok(value)
+  }
+
+  fn read_list_begin(&mut self) -> thrift::Result<TListIdentifier> {
+    self.push_frame(Frame::Collection(0))?;
+    let identifier = self.inner.read_list_begin()?;
+    self.check_collection_size(identifier.size)?;
+    self.write_captured(|prot| prot.write_list_begin(&identifier))?;
+    Ok(identifier)
+  }
+
+  fn read_list_end(&mut self) -> thrift::Result<()> {
+    self.stack.pop();
+    self.write_captured(|prot| prot.write_list_end())?;
+    self.inner.read_list_end()
+  }
+
+  fn read_set_begin(&mut self) -> thrift::Result<TSetIdentifier> {
+    self.push_frame(Frame::Collection(0))?;
+    let identifier = self.inner.read_set_begin()?;
+    self.check_collection_size(identifier.size)?;
+    self.write_captured(|prot| prot.write_set_begin(&identifier))?;
+    Ok(identifier)
+  }
+
+  fn read_set_end(&mut self) -> thrift::Result<()> {
+    self.stack.pop();
+    self.write_captured(|prot| prot.write_set_end())?;
+    self.inner.read_set_end()
+  }
+
+  fn read_map_begin(&mut self) -> thrift::Result<TMapIdentifier> {
+    self.push_frame(Frame::Collection(0))?;
+    let identifier = self.inner.read_map_begin()?;
+    self.check_collection_size(identifier.size)?;
+    self.write_captured(|prot| prot.write_map_begin(&identifier))?;
+    Ok(identifier)
+  }
+
+  fn read_map_end(&mut self) -> thrift::Result<()> {
+    self.stack.pop();
+    self.write_captured(|prot| prot.write_map_end())?;
+    self.inner.read_map_end()
+  }
+
+  fn read_byte(&mut self) -> thrift::Result<u8> {
+    self.inner.read_byte()
+  }
+}
+
+// ----------------------------------------------------------------------
+// Tolerant output protocol
+
+/// Thrift output protocol that writes members of unions unknown to `parquet_format` in
+/// place of the placeholder member and unknown fields after known fields, see module
+/// documentation for more details.
+pub struct TolerantOutputProtocol<P: TOutputProtocol> {
+  inner: P,
+  union_fields: &'static [UnionField],
+  stack: Vec<Frame>,
+  // Numbers of elements of collections in the stack
+  collection_sizes: Vec<usize>,
+  unknown_enums: UnknownEnums,
+  // Fields of unknown union member, whose structure is about to be written
+  pending_member: Option<Vec<u8>>
+}
+
+impl<P: TOutputProtocol> TolerantOutputProtocol<P> {
+  /// Creates protocol to write `FileMetaData` to `inner` protocol, where
+  /// `unknown_enums` contains field ids of unknown union members, e.g. returned by
+  /// [`to_thrift_unknown_enums`](`::schema::types::to_thrift_unknown_enums`) for the
+  /// schema, and unknown fields to write.
+  pub fn for_file_metadata(inner: P, unknown_enums: UnknownEnums) -> Self {
+    Self::new(inner, FILE_METADATA_UNIONS, unknown_enums)
+  }
+
+  /// Creates protocol to write `ColumnMetaData` to `inner` protocol, e.g. metadata of
+  /// an encrypted column, where `unknown_enums` contains unknown fields to write.
+  pub fn for_column_metadata(inner: P, unknown_enums: UnknownEnums) -> Self {
+    Self::new(inner, &[], unknown_enums)
+  }
+
+  fn new(
+    inner: P,
+    union_fields: &'static [UnionField],
+    unknown_enums: UnknownEnums
+  ) -> Self {
+    Self {
+      inner: inner,
+      union_fields: union_fields,
+      stack: Vec::new(),
+      collection_sizes: Vec::new(),
+      unknown_enums: unknown_enums,
+      pending_member: None
+    }
+  }
+
+  /// Starts writing a collection of `size` elements. Empty collections are not tracked,
+  /// since they have no elements.
+  fn begin_collection(&mut self, size: i32) {
+    begin_value(&mut self.stack);
+    if size > 0 {
+      self.stack.push(Frame::Collection(0));
+      self.collection_sizes.push(size as usize);
+    }
+  }
+
+  /// Ends writing the current collection, if all of its elements have been written.
+  /// Generated code of `parquet_format` ends a list after each of its elements, so the
+  /// end of a collection is only known from its size.
+  fn end_collection(&mut self) {
+    let is_complete = match (self.stack.last(), self.collection_sizes.last()) {
+      (Some(&Frame::Collection(num_elements)), Some(&size)) => num_elements >= size,
+      _ => false
+    };
+    if is_complete {
+      self.stack.pop();
+      self.collection_sizes.pop();
+    }
+  }
+}
+
+impl<P: TOutputProtocol> TOutputProtocol for TolerantOutputProtocol<P> {
+  fn write_message_begin(
+    &mut self,
+    identifier: &TMessageIdentifier
+  ) -> thrift::Result<()> {
+    self.inner.write_message_begin(identifier)
+  }
+
+  fn write_message_end(&mut self) -> thrift::Result<()> {
+    self.inner.write_message_end()
+  }
+
+  fn write_struct_begin(&mut self, identifier: &TStructIdentifier) -> thrift::Result<()> {
+    begin_value(&mut self.stack);
+    self.stack.push(Frame::Struct(None));
+    self.inner.write_struct_begin(identifier)?;
+    // Fields of the member precede the field stop of the empty placeholder structure
+    if let Some(data) = self.pending_member.take() {
+      for &byte in &data {
+        self.inner.write_byte(byte)?;
+      }
+    }
+    Ok(())
+  }
+
+  fn write_struct_end(&mut self) -> thrift::Result<()> {
+    self.stack.pop();
+    self.inner.write_struct_end()
+  }
+
+  fn write_field_begin(&mut self, identifier: &TFieldIdentifier) -> thrift::Result<()> {
+    let mut field = identifier.clone();
+    if union_field(self.union_fields, &self.stack).is_some() &&
+        field.id == Some(UNION_PLACEHOLDER_ID) {
+      let path = path(&self.stack);
+      if let Some(id) = self.unknown_enums.get(&path) {
+        field.id = Some(id as i16);
+        self.pending_member = self.unknown_enums.member(&path).map(|data| data.to_vec());
+      }
+    }
+    if let Some(&mut Frame::Struct(ref mut id)) = self.stack.last_mut() {
+      *id = identifier.id;
+    }
+    self.inner.write_field_begin(&field)
+  }
+
+  fn write_field_end(&mut self) -> thrift::Result<()> {
+    self.inner.write_field_end()
+  }
+
+  fn write_field_stop(&mut self) -> thrift::Result<()> {
+    let parent = path(&self.stack[..self.stack.len().saturating_sub(1)]);
+    let fields: Vec<_> = self.unknown_enums.fields.iter()
+      .filter(|(path, _, _)| path.len() == parent.len() + 1 && path.starts_with(&parent))
+      .cloned()
+      .collect();
+    for (path, field_type, data) in fields {
+      let field = TFieldIdentifier {
+        name: None,
+        field_type: field_type,
+        id: Some(path[parent.len()] as i16)
+      };
+      self.inner.write_field_begin(&field)?;
+      if field_type == TType::Bool {
+        // Boolean values are part of the field header in the compact protocol
+        self.inner.write_bool(data.first() == Some(&1))?;
+      } else {
+        for &byte in &data {
+          self.inner.write_byte(byte)?;
+        }
+      }
+      self.inner.write_field_end()?;
+    }
+    self.inner.write_field_stop()
+  }
+
+  fn write_bool(&mut self, b: bool) -> thrift::Result<()> {
+    begin_value(&mut self.stack);
+    self.inner.write_bool(b)
+  }
+
+  fn write_bytes(&mut self, b: &[u8]) -> thrift::Result<()> {
+    begin_value(&mut self.stack);
+    self.inner.write_bytes(b)
+  }
+
+  fn write_i8(&mut self, i: i8) -> thrift::Result<()> {
+    begin_value(&mut self.stack);
+    self.inner.write_i8(i)
+  }
+
+  fn write_i16(&mut self, i: i16) -> thrift::Result<()> {
+    begin_value(&mut self.stack);
+    self.inner.write_i16(i)
+  }
+
+  fn write_i32(&mut self, i: i32) -> thrift::Result<()> {
+    begin_value(&mut self.stack);
+    self.inner.write_i32(i)
+  }
+
+  fn write_i64(&mut self, i: i64) -> thrift::Result<()> {
+    begin_value(&mut self.stack);
+    self.inner.write_i64(i)
+  }
+
+  fn write_double(&mut self, d: f64) -> thrift::Result<()> {
+    begin_value(&mut self.stack);
+    self.inner.write_double(d)
+  }
+
+  fn write_string(&mut self, s: &str) -> thrift::Result<()> {
+    begin_value(&mut self.stack);
+    self.inner.write_string(s)
+  }
+
+  fn write_list_begin(&mut self, identifier: &TListIdentifier) -> thrift::Result<()> {
+    self.begin_collection(identifier.size);
+    self.inner.write_list_begin(identifier)
+  }
+
+  fn write_list_end(&mut self) -> thrift::Result<()> {
+    self.end_collection();
+    self.inner.write_list_end()
+  }
+
+  fn write_set_begin(&mut self, identifier: &TSetIdentifier) -> thrift::Result<()> {
+    self.begin_collection(identifier.size);
+    self.inner.write_set_begin(identifier)
+  }
+
+  fn write_set_end(&mut self) -> thrift::Result<()> {
+    self.end_collection();
+    self.inner.write_set_end()
+  }
+
+  fn write_map_begin(&mut self, identifier: &TMapIdentifier) -> thrift::Result<()> {
+    // Keys and values are both elements
+    self.begin_collection(identifier.size * 2);
+    self.inner.write_map_begin(identifier)
+  }
+
+  fn write_map_end(&mut self) -> thrift::Result<()> {
+    self.end_collection();
+    self.inner.write_map_end()
+  }
+
+  fn flush(&mut self) -> thrift::Result<()> {
+    self.inner.flush()
+  }
+
+  fn write_byte(&mut self, b: u8) -> thrift::Result<()> {
+    self.inner.write_byte(b)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use parquet_format::{ColumnChunk, ColumnMetaData, FileMetaData, PageHeader, RowGroup};
+  use parquet_format::{DataPageHeader, SchemaElement};
+  use thrift::protocol::{TCompactInputProtocol, TCompactOutputProtocol, TOutputProtocol};
+
+  /// Serializes Thrift structure with `write` and replaces all i32 values equal to
+  /// `from` with `to` in the output, i.e. `from` should be unique in the output.
+  fn write_and_replace<F>(write: F, from: i32, to: i32) -> Vec<u8>
+    where F: Fn(&mut TOutputProtocol) -> thrift::Result<()> {
+    let mut buf = Vec::new();
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut buf);
+      write(&mut prot).unwrap();
+      prot.flush().unwrap();
+    }
+    let zigzag = |v: i32| vec![((v << 1) ^ (v >> 31)) as u8];
+    let (from, to) = (zigzag(from), zigzag(to));
+    let pos = buf.windows(1).rposition(|w| w == &from[..]).unwrap();
+    buf.splice(pos..pos + 1, to);
+    buf
+  }
+
+  fn test_column_chunk() -> ColumnChunk {
+    let meta_data = ColumnMetaData::new(
+      parquet::Type::INT32,
+      vec![parquet::Encoding::RLE, parquet::Encoding::DELTA_BYTE_ARRAY],
+      vec!["a".to_owned()],
+      parquet::CompressionCodec::SNAPPY,
+      10,
+      100,
+      100,
+      None,
+      4,
+      None,
+      None,
+      None,
+      None
+    );
+    ColumnChunk::new(None, 4, meta_data, None, None, None, None)
+  }
+
+  #[test]
+  fn test_unknown_enums() {
+    let unknown = UnknownEnums {
+      values: vec![(vec![4, 0, 1, 1, 3, 2, 1], 10), (vec![4, 1, 1, 0, 3, 4], 9)],
+      members: vec![(vec![4, 0, 1, 1, 3, 2, 1], vec![21, 4])],
+      fields: vec![(vec![9], TType::I32, vec![4])]
+    };
+    assert!(!unknown.is_empty());
+    assert_eq!(unknown.get(&[4, 0, 1, 1, 3, 2, 1]), Some(10));
+    assert_eq!(unknown.get(&[4, 0, 1, 1, 3, 2]), None);
+
+    assert_eq!(unknown.member(&[4, 0, 1, 1, 3, 2, 1]), Some(&[21, 4][..]));
+    assert_eq!(unknown.member(&[4, 1, 1, 0, 3, 4]), None);
+
+    let scoped = unknown.scope(&[4, 0, 1, 1, 3]);
+    assert_eq!(scoped.get(&[2, 1]), Some(10));
+    assert_eq!(scoped.member(&[2, 1]), Some(&[21, 4][..]));
+    assert_eq!(scoped.encoding(&[2, 1], parquet::Encoding::PLAIN), Encoding::Unknown(10));
+    assert_eq!(scoped.encoding(&[2, 0], parquet::Encoding::RLE), Encoding::RLE);
+    assert!(unknown.scope(&[4, 2]).is_empty());
+
+    let scoped = unknown.scope(&[4, 1, 1, 0, 3]);
+    assert_eq!(
+      scoped.compression(&[4], parquet::CompressionCodec::UNCOMPRESSED),
+      Compression::Unknown(9)
+    );
+    assert_eq!(
+      scoped.page_type(&[1], parquet::PageType::DATA_PAGE),
+      PageType::DATA_PAGE
+    );
+    assert_eq!(
+      scoped.logical_type(&[1], Some(parquet::ConvertedType::UTF8)),
+      LogicalType::UTF8
+    );
+
+    let nested = scoped.nest(&[4, 1, 1, 0, 3]);
+    assert_eq!(nested, unknown.scope(&[4, 1]).nest(&[4, 1]));
+
+    assert_eq!(unknown.field(&[9]), Some((TType::I32, &[4][..])));
+    assert_eq!(unknown.field(&[8]), None);
+    let fields = unknown.unknown_fields();
+    assert_eq!(fields.get(&[4, 0, 1, 1, 3, 2, 1]), None);
+    assert_eq!(fields.field(&[9]), Some((TType::I32, &[4][..])));
+
+    let mut extended = UnknownEnums::default();
+    extended.insert(vec![4, 0, 1, 1, 3, 2, 1], 11);
+    extended.insert_field(vec![9], TType::I64, vec![2]);
+    extended.extend(&unknown);
+    assert_eq!(extended.get(&[4, 0, 1, 1, 3, 2, 1]), Some(11));
+    assert_eq!(extended.get(&[4, 1, 1, 0, 3, 4]), Some(9));
+    assert_eq!(extended.member(&[4, 0, 1, 1, 3, 2, 1]), Some(&[21, 4][..]));
+    assert_eq!(extended.field(&[9]), Some((TType::I64, &[2][..])));
+
+    extended.remove_scope(&[4, 0]);
+    assert_eq!(extended.get(&[4, 0, 1, 1, 3, 2, 1]), None);
+    assert_eq!(extended.member(&[4, 0, 1, 1, 3, 2, 1]), None);
+    assert_eq!(extended.get(&[4, 1, 1, 0, 3, 4]), Some(9));
+    assert_eq!(extended.field(&[9]), Some((TType::I64, &[2][..])));
+  }
+
+  fn test_file_metadata_with_logical_type(id: i32) -> (FileMetaData, UnknownEnums) {
+    let mut element = SchemaElement::new(
+      parquet::Type::FIXED_LEN_BYTE_ARRAY,
+      16,
+      parquet::FieldRepetitionType::REQUIRED,
+      "a".to_owned(),
+      None,
+      None,
+      None,
+      None,
+      None,
+      None
+    );
+    element.logical_type = Some(logical_type_placeholder());
+    let root = SchemaElement::new(
+      None, None, None, "schema".to_owned(), 1, None, None, None, None, None);
+    let file_metadata =
+      FileMetaData::new(1, vec![root, element], 0, vec![], None, None, None);
+    let mut unknown_enums = UnknownEnums::default();
+    unknown_enums.insert(vec![2, 1, 10], id);
+    (file_metadata, unknown_enums)
+  }
+
+  #[test]
+  fn test_write_read_file_metadata_unknown_union_member() {
+    for &id in &[14, 20] {
+      let (file_metadata, unknown_enums) = test_file_metadata_with_logical_type(id);
+      let mut buf = Vec::new();
+      {
+        let mut prot = TolerantOutputProtocol::for_file_metadata(
+          TCompactOutputProtocol::new(&mut buf), unknown_enums.clone());
+        file_metadata.write_to_out_protocol(&mut prot).unwrap();
+        prot.flush().unwrap();
+      }
+
+      let mut prot = TolerantInputProtocol::for_file_metadata(
+        TCompactInputProtocol::new(&buf[..]));
+      let res = FileMetaData::read_from_in_protocol(&mut prot).unwrap();
+      assert_eq!(res, file_metadata);
+      assert_eq!(prot.unknown_enums(), &unknown_enums);
+    }
+  }
+
+  #[test]
+  fn test_write_read_file_metadata_unknown_union_member_fields() {
+    let (file_metadata, mut unknown_enums) = test_file_metadata_with_logical_type(16);
+    // Field 1 of type i32 with value 2 and field 2 of structure type with field 1 of
+    // type string with value "a"
+    let data = vec![0x15, 0x04, 0x1C, 0x18, 0x01, 0x61, 0x00];
+    unknown_enums.insert_member(vec![2, 1, 10], data.clone());
+    let mut buf = Vec::new();
+    {
+      let mut prot = TolerantOutputProtocol::for_file_metadata(
+        TCompactOutputProtocol::new(&mut buf), unknown_enums.clone());
+      file_metadata.write_to_out_protocol(&mut prot).unwrap();
+      prot.flush().unwrap();
+    }
+
+    let mut prot = TolerantInputProtocol::for_file_metadata(
+      TCompactInputProtocol::new(&buf[..]));
+    let res = FileMetaData::read_from_in_protocol(&mut prot).unwrap();
+    assert_eq!(res, file_metadata);
+    let res_unknown_enums = prot.into_unknown_enums();
+    assert_eq!(res_unknown_enums, unknown_enums);
+    assert_eq!(
+      res_unknown_enums.logical_type_member(&[2, 1, 10]),
+      Some(LogicalType::Other(RawLogicalType::new(16, &data)))
+    );
+
+    let mut res_buf = Vec::new();
+    {
+      let mut prot = TolerantOutputProtocol::for_file_metadata(
+        TCompactOutputProtocol::new(&mut res_buf), res_unknown_enums);
+      res.write_to_out_protocol(&mut prot).unwrap();
+      prot.flush().unwrap();
+    }
+    assert_eq!(res_buf, buf);
+  }
+
+  #[test]
+  fn test_logical_type_member() {
+    let (_, unknown_enums) = test_file_metadata_with_logical_type(14);
+    let scoped = unknown_enums.scope(&[2]);
+    assert_eq!(scoped.logical_type_member(&[1, 10]), Some(LogicalType::UUID));
+    assert_eq!(scoped.logical_type_member(&[0, 10]), None);
+
+    let (_, mut unknown_enums) = test_file_metadata_with_logical_type(20);
+    assert_eq!(
+      unknown_enums.logical_type_member(&[2, 1, 10]),
+      Some(LogicalType::Other(RawLogicalType::new(20, &[])))
+    );
+    unknown_enums.insert_member(vec![2, 1, 10], vec![21, 4]);
+    assert_eq!(
+      unknown_enums.logical_type_member(&[2, 1, 10]),
+      Some(LogicalType::Other(RawLogicalType::new(20, &[21, 4])))
+    );
+    assert_eq!(
+      logical_type_to_member_id(LogicalType::Other(RawLogicalType::new(20, &[]))),
+      Some(20)
+    );
+
+    assert_eq!(logical_type_to_member_id(LogicalType::UUID), Some(14));
+    assert_eq!(logical_type_to_member_id(LogicalType::FLOAT16), Some(15));
+    assert_eq!(logical_type_from_member_id(15), Some(LogicalType::FLOAT16));
+    assert_eq!(logical_type_to_member_id(LogicalType::UTF8), None);
+  }
+
+  #[test]
+  fn test_write_read_file_metadata_timestamp_nanos() {
+    let (mut file_metadata, _) = test_file_metadata_with_logical_type(0);
+    file_metadata.schema[1].type_ = Some(parquet::Type::INT64);
+    file_metadata.schema[1].type_length = None;
+    file_metadata.schema[1].logical_type = Some(parquet::LogicalType::TIMESTAMP(
+      parquet::TimestampType::new(false, time_unit_to_thrift(TimeUnit::NANOS))
+    ));
+    let mut unknown_enums = UnknownEnums::default();
+    unknown_enums.insert(vec![2, 1, 10, 8, 2], 3);
+    let mut buf = Vec::new();
+    {
+      let mut prot = TolerantOutputProtocol::for_file_metadata(
+        TCompactOutputProtocol::new(&mut buf), unknown_enums.clone());
+      file_metadata.write_to_out_protocol(&mut prot).unwrap();
+      prot.flush().unwrap();
+    }
+
+    let mut prot = TolerantInputProtocol::for_file_metadata(
+      TCompactInputProtocol::new(&buf[..]));
+    let res = FileMetaData::read_from_in_protocol(&mut prot).unwrap();
+    assert_eq!(res, file_metadata);
+    assert_eq!(prot.unknown_enums(), &unknown_enums);
+
+    let scoped = prot.unknown_enums().scope(&[2]);
+    let unit = parquet::TimeUnit::MILLIS(parquet::MilliSeconds::new());
+    assert_eq!(scoped.time_unit(&[1, 10, 8, 2], &unit), Some(TimeUnit::NANOS));
+    assert_eq!(scoped.time_unit(&[0, 10, 8, 2], &unit), Some(TimeUnit::MILLIS));
+    let mut unknown_unit = UnknownEnums::default();
+    unknown_unit.insert(vec![1, 10, 8, 2], 4);
+    assert_eq!(unknown_unit.time_unit(&[1, 10, 8, 2], &unit), None);
+
+    assert_eq!(time_unit_to_member_id(TimeUnit::NANOS), Some(3));
+    assert_eq!(time_unit_to_member_id(TimeUnit::MICROS), None);
+  }
+
+  #[test]
+  fn test_read_file_metadata_unknown_encoding() {
+    let row_group = RowGroup::new(vec![test_column_chunk()], 100, 10, None);
+    let file_metadata =
+      FileMetaData::new(1, vec![], 10, vec![row_group], None, None, None);
+    // DELTA_BYTE_ARRAY is the only value 7 in the file metadata
+    let buf = write_and_replace(|prot| file_metadata.write_to_out_protocol(prot), 7, 10);
+
+    let res = FileMetaData::read_from_in_protocol(
+      &mut TCompactInputProtocol::new(&buf[..]));
+    assert!(res.is_err());
+
+    let mut prot = TolerantInputProtocol::for_file_metadata(
+      TCompactInputProtocol::new(&buf[..]));
+    let res = FileMetaData::read_from_in_protocol(&mut prot).unwrap();
+    let encodings = &res.row_groups[0].columns[0].meta_data.as_ref().unwrap().encodings;
+    assert_eq!(encodings, &vec![parquet::Encoding::RLE, parquet::Encoding::PLAIN]);
+    assert_eq!(
+      prot.into_unknown_enums(),
+      UnknownEnums {
+        values: vec![(vec![4, 0, 1, 0, 3, 2, 1], 10)],
+        members: vec![],
+        fields: vec![]
+      }
+    );
+  }
+
+  #[test]
+  fn test_read_file_metadata_unknown_codec() {
+    let row_group = RowGroup::new(vec![test_column_chunk()], 100, 10, None);
+    let file_metadata =
+      FileMetaData::new(1, vec![], 10, vec![row_group], None, None, None);
+    // SNAPPY is the only value 1 in the file metadata after version and path
+    let buf = write_and_replace(|prot| file_metadata.write_to_out_protocol(prot), 1, 20);
+
+    let mut prot = TolerantInputProtocol::for_file_metadata(
+      TCompactInputProtocol::new(&buf[..]));
+    let res = FileMetaData::read_from_in_protocol(&mut prot).unwrap();
+    assert_eq!(
+      res.row_groups[0].columns[0].meta_data.as_ref().unwrap().codec,
+      parquet::CompressionCodec::UNCOMPRESSED
+    );
+    assert_eq!(prot.unknown_enums().get(&[4, 0, 1, 0, 3, 4]), Some(20));
+  }
+
+  #[test]
+  fn test_read_column_metadata_unknown_values() {
+    let meta_data = test_column_chunk().meta_data.unwrap();
+    // DELTA_BYTE_ARRAY is the only value 7 in the column metadata
+    let buf = write_and_replace(|prot| meta_data.write_to_out_protocol(prot), 7, 10);
+
+    let mut prot =
+      TolerantInputProtocol::for_column_metadata(TCompactInputProtocol::new(&buf[..]));
+    let res = ColumnMetaData::read_from_in_protocol(&mut prot).unwrap();
+    assert_eq!(res.encodings, vec![parquet::Encoding::RLE, parquet::Encoding::PLAIN]);
+    assert_eq!(prot.unknown_enums().get(&[2, 1]), Some(10));
+  }
+
+  #[test]
+  fn test_read_file_metadata_unknown_field() {
+    let row_group = RowGroup::new(vec![test_column_chunk()], 100, 10, None);
+    let file_metadata =
+      FileMetaData::new(1, vec![], 10, vec![row_group], None, None, None);
+    let mut buf = Vec::new();
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut buf);
+      file_metadata.write_to_out_protocol(&mut prot).unwrap();
+      prot.flush().unwrap();
+    }
+    // Replace stop field with empty struct field 8 followed by stop field
+    assert_eq!(buf.pop(), Some(0));
+    buf.extend_from_slice(&[0x0C, 0x10, 0x00, 0x00]);
+
+    let mut prot = TolerantInputProtocol::for_file_metadata(
+      TCompactInputProtocol::new(&buf[..]));
+    let res = FileMetaData::read_from_in_protocol(&mut prot).unwrap();
+    assert_eq!(res.num_rows, 10);
+    assert_eq!(prot.root_field_ids(), &[1, 2, 3, 4, 8]);
+    assert_eq!(prot.unknown_enums().field(&[8]), Some((TType::Struct, &[0][..])));
+  }
+
+  #[test]
+  fn test_read_file_metadata_collection_limits() {
+    let row_group = RowGroup::new(vec![test_column_chunk()], 100, 10, None);
+    let file_metadata = FileMetaData::new(
+      1, vec![], 10, vec![row_group.clone(), row_group], None, None, None);
+    let mut buf = Vec::new();
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut buf);
+      file_metadata.write_to_out_protocol(&mut prot).unwrap();
+      prot.flush().unwrap();
+    }
+    let mut prot = TolerantInputProtocol::for_file_metadata(
+      TCompactInputProtocol::new(&buf[..]))
+      .with_max_collection_size(buf.len())
+      .with_collection_limit(&[4], "row groups", 2);
+    assert!(FileMetaData::read_from_in_protocol(&mut prot).is_ok());
+
+    let mut prot = TolerantInputProtocol::for_file_metadata(
+      TCompactInputProtocol::new(&buf[..]))
+      .with_collection_limit(&[4], "row groups", 1);
+    let err = FileMetaData::read_from_in_protocol(&mut prot).unwrap_err();
+    assert_eq!(err.to_string(), "Number of row groups 2 exceeds limit 1");
+
+    // Version followed by schema list of 2^31 - 1 structures
+    let buf = [0x15, 0x02, 0x19, 0xFC, 0xFF, 0xFF, 0xFF, 0xFF, 0x07];
+    let mut prot = TolerantInputProtocol::for_file_metadata(
+      TCompactInputProtocol::new(&buf[..]))
+      .with_max_collection_size(buf.len());
+    let err = FileMetaData::read_from_in_protocol(&mut prot).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Number of collection elements 2147483647 exceeds limit 9"
+    );
+  }
+
+  #[test]
+  fn test_read_file_metadata_binary_limit() {
+    // Version followed by schema list with element, whose name has 2^32 - 1 bytes
+    let buf = [0x15, 0x02, 0x19, 0x1C, 0x48, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+    let mut prot = TolerantInputProtocol::for_file_metadata(
+      TCompactInputProtocol::new(&buf[..]))
+      .with_max_binary_size(buf.len());
+    let err = FileMetaData::read_from_in_protocol(&mut prot).unwrap_err();
+    assert_eq!(err.to_string(), "Length of binary value 4294967295 exceeds limit 10");
+
+    let buf = [0x15, 0x02, 0x19, 0x1C, 0x48, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+    let mut prot = TolerantInputProtocol::for_file_metadata(
+      TCompactInputProtocol::new(&buf[..]))
+      .with_max_binary_size(buf.len());
+    let err = FileMetaData::read_from_in_protocol(&mut prot).unwrap_err();
+    assert_eq!(err.to_string(), "Invalid length of binary value");
+
+    let row_group = RowGroup::new(vec![test_column_chunk()], 100, 10, None);
+    let file_metadata =
+      FileMetaData::new(1, vec![], 10, vec![row_group], None, Some("a".to_owned()), None);
+    let mut buf = Vec::new();
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut buf);
+      file_metadata.write_to_out_protocol(&mut prot).unwrap();
+      prot.flush().unwrap();
+    }
+    let mut prot = TolerantInputProtocol::for_file_metadata(
+      TCompactInputProtocol::new(&buf[..]))
+      .with_max_binary_size(buf.len());
+    assert_eq!(FileMetaData::read_from_in_protocol(&mut prot).unwrap(), file_metadata);
+  }
+
+  #[test]
+  fn test_read_file_metadata_depth_limit() {
+    // Unknown field 8 of nested lists, the innermost list is empty
+    let mut buf = vec![0x15, 0x02, 0x79];
+    for _ in 0..20 {
+      buf.push(0x19);
+    }
+    buf.extend_from_slice(&[0x09, 0x00]);
+
+    let mut prot = TolerantInputProtocol::for_file_metadata(
+      TCompactInputProtocol::new(&buf[..]))
+      .with_max_depth(10);
+    let err = FileMetaData::read_from_in_protocol(&mut prot).unwrap_err();
+    assert_eq!(err.to_string(), "Nesting depth of Thrift structures exceeds limit 10");
+  }
+
+  #[test]
+  fn test_write_read_file_metadata_unknown_fields() {
+    let row_group = RowGroup::new(vec![test_column_chunk()], 100, 10, None);
+    let file_metadata =
+      FileMetaData::new(1, vec![], 10, vec![row_group], None, None, None);
+    let mut buf = Vec::new();
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut buf);
+      file_metadata.write_to_out_protocol(&mut prot).unwrap();
+      prot.flush().unwrap();
+    }
+    // Replace stop field with true boolean field 8, structure field 9 with i32 field 1
+    // with value 2, list field 10 with 2 strings "a" and "b", binary field 11 that is
+    // not valid UTF-8, and stop field
+    assert_eq!(buf.pop(), Some(0));
+    buf.extend_from_slice(&[0x41, 0x1C, 0x15, 0x04, 0x00]);
+    buf.extend_from_slice(&[0x19, 0x28, 0x01, 0x61, 0x01, 0x62]);
+    buf.extend_from_slice(&[0x18, 0x01, 0xFF, 0x00]);
+
+    let mut prot = TolerantInputProtocol::for_file_metadata(
+      TCompactInputProtocol::new(&buf[..]));
+    let res = FileMetaData::read_from_in_protocol(&mut prot).unwrap();
+    assert_eq!(res, file_metadata);
+    let unknown_enums = prot.into_unknown_enums();
+    assert_eq!(unknown_enums.field(&[8]), Some((TType::Bool, &[1][..])));
+    assert_eq!(unknown_enums.field(&[9]), Some((TType::Struct, &[0x15, 0x04, 0x00][..])));
+    assert_eq!(
+      unknown_enums.field(&[10]),
+      Some((TType::List, &[0x28, 0x01, 0x61, 0x01, 0x62][..]))
+    );
+    assert_eq!(unknown_enums.field(&[11]), Some((TType::String, &[0x01, 0xFF][..])));
+
+    let mut res_buf = Vec::new();
+    {
+      let mut prot = TolerantOutputProtocol::for_file_metadata(
+        TCompactOutputProtocol::new(&mut res_buf), unknown_enums);
+      res.write_to_out_protocol(&mut prot).unwrap();
+      prot.flush().unwrap();
+    }
+    assert_eq!(res_buf, buf);
+  }
+
+  #[test]
+  fn test_write_read_file_metadata_extension_fields() {
+    let row_group = RowGroup::new(vec![test_column_chunk()], 100, 10, None);
+    let file_metadata =
+      FileMetaData::new(1, vec![], 10, vec![row_group], None, None, None);
+    // Size statistics of the column chunk, structure with i64 field 1 with value 2
+    let mut unknown_enums = UnknownEnums::default();
+    let path = vec![4, 0, 1, 0, 3, 16];
+    unknown_enums.insert_field(path, TType::Struct, vec![0x16, 0x04, 0x00]);
+
+    let mut buf = Vec::new();
+    {
+      let mut prot = TolerantOutputProtocol::for_file_metadata(
+        TCompactOutputProtocol::new(&mut buf), unknown_enums.clone());
+      file_metadata.write_to_out_protocol(&mut prot).unwrap();
+      prot.flush().unwrap();
+    }
+
+    let mut prot = TolerantInputProtocol::for_file_metadata(
+      TCompactInputProtocol::new(&buf[..]));
+    let res = FileMetaData::read_from_in_protocol(&mut prot).unwrap();
+    assert_eq!(res, file_metadata);
+    let res_unknown_enums = prot.into_unknown_enums();
+    assert_eq!(res_unknown_enums, unknown_enums);
+    assert!(res_unknown_enums.unknown_fields().is_empty());
+  }
+
+  #[test]
+  fn test_read_page_header_unknown_values() {
+    let data_page_header = DataPageHeader::new(
+      10,
+      parquet::Encoding::DELTA_BYTE_ARRAY,
+      parquet::Encoding::RLE,
+      parquet::Encoding::RLE,
+      None
+    );
+    let page_header = PageHeader::new(
+      parquet::PageType::DATA_PAGE, 100, 100, None, data_page_header, None, None, None);
+
+    let buf = write_and_replace(|prot| page_header.write_to_out_protocol(prot), 7, 11);
+    let mut prot = TolerantInputProtocol::for_page_header(
+      TCompactInputProtocol::new(&buf[..]));
+    let res = PageHeader::read_from_in_protocol(&mut prot).unwrap();
+    assert_eq!(res.type_, parquet::PageType::DATA_PAGE);
+    assert_eq!(res.data_page_header.unwrap().encoding, parquet::Encoding::PLAIN);
+    assert_eq!(
+      prot.into_unknown_enums(),
+      UnknownEnums { values: vec![(vec![5, 2], 11)], members: vec![], fields: vec![] }
+    );
+
+    let page_header = PageHeader::new(
+      parquet::PageType::DATA_PAGE_V2, 100, 100, None, None, None, None, None);
+    let buf = write_and_replace(|prot| page_header.write_to_out_protocol(prot), 3, 7);
+    let mut prot = TolerantInputProtocol::for_page_header(
+      TCompactInputProtocol::new(&buf[..]));
+    let res = PageHeader::read_from_in_protocol(&mut prot).unwrap();
+    assert_eq!(res.type_, parquet::PageType::INDEX_PAGE);
+    assert_eq!(prot.unknown_enums().page_type(&[1], res.type_), PageType::Unknown(7));
+  }
+}
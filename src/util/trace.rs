@@ -0,0 +1,137 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Internal macros for `tracing` instrumentation, available with `tracing` feature
+//! enabled. Without the feature macros expand to nothing, so arguments are not
+//! evaluated.
+//!
+//! Spans: `parse_footer` (reading and parsing file metadata), `read_page_header` and
+//! `read_page` (reading page header and page bytes from the source),
+//! `decompress_page`, `decode_page` (setting up decoders for a new data page or
+//! dictionary), `decode_values` and `compress_page`.
+
+/// Enters a debug span until the end of the current block.
+#[cfg(feature = "tracing")]
+macro_rules! trace_span {
+  ($($args:tt)*) => {
+    let _span_guard = ::tracing::debug_span!($($args)*).entered();
+  };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_span {
+  ($($args:tt)*) => {};
+}
+
+/// Enters a trace span until the end of the current block, used for frequently called
+/// methods, e.g. decoding values of a batch.
+#[cfg(feature = "tracing")]
+macro_rules! trace_fine_span {
+  ($($args:tt)*) => {
+    let _span_guard = ::tracing::trace_span!($($args)*).entered();
+  };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_fine_span {
+  ($($args:tt)*) => {};
+}
+
+/// Emits a debug event.
+#[cfg(feature = "tracing")]
+macro_rules! trace_event {
+  ($($args:tt)*) => {
+    ::tracing::debug!($($args)*);
+  };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_event {
+  ($($args:tt)*) => {};
+}
+
+/// Emits a warning event, e.g. when corrupt data is skipped.
+#[cfg(feature = "tracing")]
+macro_rules! trace_warn {
+  ($($args:tt)*) => {
+    ::tracing::warn!($($args)*);
+  };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_warn {
+  ($($args:tt)*) => {};
+}
+
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use tracing::{Event, Id, Metadata, Subscriber};
+  use tracing::span::{Attributes, Record};
+  use tracing::subscriber;
+
+  use file::reader::{FileReader, SerializedFileReader};
+  use util::test_common::get_test_file;
+
+  /// Subscriber that records names of created spans.
+  struct SpanNames {
+    names: Arc<Mutex<Vec<String>>>
+  }
+
+  impl Subscriber for SpanNames {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+      true
+    }
+
+    fn new_span(&self, span: &Attributes) -> Id {
+      let mut names = self.names.lock().unwrap();
+      names.push(span.metadata().name().to_owned());
+      Id::from_u64(names.len() as u64)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event) {}
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+  }
+
+  #[test]
+  fn test_trace_spans() {
+    let names = Arc::new(Mutex::new(Vec::new()));
+    let subscriber = SpanNames { names: names.clone() };
+    subscriber::with_default(subscriber, || {
+      let file = get_test_file("alltypes_plain.snappy.parquet");
+      let reader = SerializedFileReader::new(file).unwrap();
+      assert_eq!(reader.get_row_iter(None).unwrap().count(), 2);
+    });
+
+    let names = names.lock().unwrap();
+    for name in &[
+      "parse_footer", "read_page_header", "read_page", "decompress_page", "decode_page",
+      "decode_values"
+    ] {
+      assert!(names.iter().any(|n| n == name), "Span {} is not recorded", name);
+    }
+  }
+}
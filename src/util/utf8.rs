@@ -0,0 +1,293 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Validation of UTF-8 strings.
+//!
+//! When SSSE3 instructions are available, values are validated 16 bytes at a time with
+//! the lookup algorithm from "Validating UTF-8 In Less Than One Instruction Per Byte"
+//! by John Keiser and Daniel Lemire, and blocks of ASCII characters are skipped. Short
+//! values, platforms without SSSE3 and invalid values, which need position of the error,
+//! are handled by `std::str::from_utf8`.
+
+use std::str;
+
+/// Minimum length of a value validated with SIMD instructions.
+const SIMD_MIN_LEN: usize = 32;
+
+/// Converts `bytes` into a string slice, same as `std::str::from_utf8`.
+pub fn from_utf8(bytes: &[u8]) -> Result<&str, str::Utf8Error> {
+  #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+  {
+    if bytes.len() >= SIMD_MIN_LEN && is_x86_feature_detected!("ssse3") {
+      if unsafe { is_valid_ssse3(bytes) } {
+        return Ok(unsafe { str::from_utf8_unchecked(bytes) });
+      }
+    }
+  }
+  str::from_utf8(bytes)
+}
+
+// Error bits of the lookup tables, a byte pair is invalid if the same bit is set for
+// the high and low nibbles of the first byte and the high nibble of the second byte.
+// `TWO_CONTS` is also set for the second and third bytes of 3- and 4-byte sequences,
+// which is checked separately.
+const TOO_SHORT: u8 = 1 << 0;
+const TOO_LONG: u8 = 1 << 1;
+const OVERLONG_3: u8 = 1 << 2;
+const TOO_LARGE: u8 = 1 << 3;
+const SURROGATE: u8 = 1 << 4;
+const OVERLONG_2: u8 = 1 << 5;
+const TOO_LARGE_1000: u8 = 1 << 6;
+const OVERLONG_4: u8 = 1 << 6;
+const TWO_CONTS: u8 = 1 << 7;
+const CARRY: u8 = TOO_SHORT | TOO_LONG | TWO_CONTS;
+
+/// Errors by the high nibble of the first byte.
+const BYTE_1_HIGH: [u8; 16] = [
+  // 0___: ASCII
+  TOO_LONG, TOO_LONG, TOO_LONG, TOO_LONG,
+  TOO_LONG, TOO_LONG, TOO_LONG, TOO_LONG,
+  // 10__: continuation
+  TWO_CONTS, TWO_CONTS, TWO_CONTS, TWO_CONTS,
+  // 1100: 2-byte lead
+  TOO_SHORT | OVERLONG_2,
+  // 1101: 2-byte lead
+  TOO_SHORT,
+  // 1110: 3-byte lead
+  TOO_SHORT | OVERLONG_3 | SURROGATE,
+  // 1111: 4-byte lead
+  TOO_SHORT | TOO_LARGE | TOO_LARGE_1000 | OVERLONG_4
+];
+
+/// Errors by the low nibble of the first byte.
+const BYTE_1_LOW: [u8; 16] = [
+  // 0000
+  CARRY | OVERLONG_3 | OVERLONG_2 | OVERLONG_4,
+  // 0001
+  CARRY | OVERLONG_2,
+  // 001_
+  CARRY,
+  CARRY,
+  // 0100
+  CARRY | TOO_LARGE,
+  // 0101
+  CARRY | TOO_LARGE | TOO_LARGE_1000,
+  // 011_
+  CARRY | TOO_LARGE | TOO_LARGE_1000,
+  CARRY | TOO_LARGE | TOO_LARGE_1000,
+  // 1___
+  CARRY | TOO_LARGE | TOO_LARGE_1000,
+  CARRY | TOO_LARGE | TOO_LARGE_1000,
+  CARRY | TOO_LARGE | TOO_LARGE_1000,
+  CARRY | TOO_LARGE | TOO_LARGE_1000,
+  CARRY | TOO_LARGE | TOO_LARGE_1000,
+  // 1101
+  CARRY | TOO_LARGE | TOO_LARGE_1000 | SURROGATE,
+  CARRY | TOO_LARGE | TOO_LARGE_1000,
+  CARRY | TOO_LARGE | TOO_LARGE_1000
+];
+
+/// Errors by the high nibble of the second byte.
+const BYTE_2_HIGH: [u8; 16] = [
+  // 0___: ASCII
+  TOO_SHORT, TOO_SHORT, TOO_SHORT, TOO_SHORT,
+  TOO_SHORT, TOO_SHORT, TOO_SHORT, TOO_SHORT,
+  // 1000
+  TOO_LONG | OVERLONG_2 | TWO_CONTS | OVERLONG_3 | TOO_LARGE_1000 | OVERLONG_4,
+  // 1001
+  TOO_LONG | OVERLONG_2 | TWO_CONTS | OVERLONG_3 | TOO_LARGE,
+  // 101_
+  TOO_LONG | OVERLONG_2 | TWO_CONTS | SURROGATE | TOO_LARGE,
+  TOO_LONG | OVERLONG_2 | TWO_CONTS | SURROGATE | TOO_LARGE,
+  // 11__: lead
+  TOO_SHORT, TOO_SHORT, TOO_SHORT, TOO_SHORT
+];
+
+/// Maximum values of the last 3 bytes of a block that do not start a sequence, which
+/// continues in the next block.
+const MAX_COMPLETE: [u8; 16] = [
+  0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+  0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xEF, 0xDF, 0xBF
+];
+
+/// Returns `true` if `bytes` is valid UTF-8.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "ssse3")]
+unsafe fn is_valid_ssse3(bytes: &[u8]) -> bool {
+  #[cfg(target_arch = "x86")]
+  use std::arch::x86::*;
+  #[cfg(target_arch = "x86_64")]
+  use std::arch::x86_64::*;
+
+  let byte_1_high = _mm_loadu_si128(BYTE_1_HIGH.as_ptr() as *const __m128i);
+  let byte_1_low = _mm_loadu_si128(BYTE_1_LOW.as_ptr() as *const __m128i);
+  let byte_2_high = _mm_loadu_si128(BYTE_2_HIGH.as_ptr() as *const __m128i);
+  let max_complete = _mm_loadu_si128(MAX_COMPLETE.as_ptr() as *const __m128i);
+  let low_nibble = _mm_set1_epi8(0x0F);
+
+  let mut error = _mm_setzero_si128();
+  let mut prev_input = _mm_setzero_si128();
+  let mut prev_incomplete = _mm_setzero_si128();
+
+  // The last block is padded with zeros, i.e. ASCII characters
+  let mut last_block = [0u8; 16];
+  let num_blocks = (bytes.len() + 15) / 16;
+  for i in 0..num_blocks {
+    let block = if (i + 1) * 16 <= bytes.len() {
+      &bytes[i * 16..]
+    } else {
+      let remainder = &bytes[i * 16..];
+      last_block[..remainder.len()].copy_from_slice(remainder);
+      &last_block[..]
+    };
+    let input = _mm_loadu_si128(block.as_ptr() as *const __m128i);
+
+    if _mm_movemask_epi8(input) == 0 {
+      // ASCII block can only be invalid if the previous block ends with a lead byte
+      error = _mm_or_si128(error, prev_incomplete);
+    } else {
+      // Byte pairs are checked by looking up errors for both bytes
+      let prev1 = _mm_alignr_epi8(input, prev_input, 15);
+      let prev1_high = _mm_and_si128(_mm_srli_epi16(prev1, 4), low_nibble);
+      let prev1_low = _mm_and_si128(prev1, low_nibble);
+      let input_high = _mm_and_si128(_mm_srli_epi16(input, 4), low_nibble);
+      let special_cases = _mm_and_si128(
+        _mm_and_si128(
+          _mm_shuffle_epi8(byte_1_high, prev1_high),
+          _mm_shuffle_epi8(byte_1_low, prev1_low)
+        ),
+        _mm_shuffle_epi8(byte_2_high, input_high)
+      );
+
+      // Continuation bytes must follow 3- and 4-byte leads exactly
+      let prev2 = _mm_alignr_epi8(input, prev_input, 14);
+      let prev3 = _mm_alignr_epi8(input, prev_input, 13);
+      let is_third_byte = _mm_subs_epu8(prev2, _mm_set1_epi8((0xE0u8 - 0x80) as i8));
+      let is_fourth_byte = _mm_subs_epu8(prev3, _mm_set1_epi8((0xF0u8 - 0x80) as i8));
+      let must_be_continuation = _mm_and_si128(
+        _mm_or_si128(is_third_byte, is_fourth_byte),
+        _mm_set1_epi8(0x80u8 as i8)
+      );
+      error = _mm_or_si128(error, _mm_xor_si128(must_be_continuation, special_cases));
+    }
+
+    prev_incomplete = _mm_subs_epu8(input, max_complete);
+    prev_input = input;
+  }
+  error = _mm_or_si128(error, prev_incomplete);
+
+  _mm_movemask_epi8(_mm_cmpeq_epi8(error, _mm_setzero_si128())) == 0xFFFF
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rand::{thread_rng, Rng};
+
+  /// Checks validation of `bytes` against `std::str::from_utf8`.
+  fn check(bytes: &[u8]) {
+    let expected = str::from_utf8(bytes);
+    assert_eq!(from_utf8(bytes), expected, "{:?}", bytes);
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+      if is_x86_feature_detected!("ssse3") {
+        assert_eq!(unsafe { is_valid_ssse3(bytes) }, expected.is_ok(), "{:?}", bytes);
+      }
+    }
+  }
+
+  /// Checks `sequence` placed in ASCII text at every offset around block boundaries and
+  /// at the end of data.
+  fn check_at_offsets(sequence: &[u8]) {
+    let len = 40;
+    for offset in (12..21).chain(28..len - sequence.len() + 1) {
+      let mut bytes = vec![b'a'; len];
+      bytes[offset..offset + sequence.len()].copy_from_slice(sequence);
+      check(&bytes);
+      // Sequence after a multi-byte character
+      bytes[offset - 3..offset].copy_from_slice("\u{20ac}".as_bytes());
+      check(&bytes);
+    }
+  }
+
+  /// Interesting values of bytes: boundaries of ASCII, continuation and lead bytes.
+  const EDGE_BYTES: [u8; 18] = [
+    0x00, 0x7F, 0x80, 0x8F, 0x90, 0x9F, 0xA0, 0xBF, 0xC0, 0xC1, 0xC2, 0xDF, 0xE0, 0xED,
+    0xEF, 0xF0, 0xF4, 0xF5
+  ];
+
+  #[test]
+  fn test_from_utf8_valid() {
+    check(b"");
+    check(b"abc");
+    check("abcdefghijklmnopqrstuvwxyz0123456789".as_bytes());
+    check("\u{e9}t\u{e9} \u{20ac}100 \u{1f600} \u{10ffff}\u{7ff}\u{800}\u{ffff}"
+      .repeat(3).as_bytes());
+  }
+
+  #[test]
+  fn test_from_utf8_invalid() {
+    // Truncated sequence at the end of a block and at the end of data
+    let mut bytes = vec![b'a'; 48];
+    bytes[15] = 0xE2;
+    check(&bytes);
+    bytes[47] = 0xF0;
+    check(&bytes);
+    let result = from_utf8(&bytes);
+    assert_eq!(result.unwrap_err().valid_up_to(), 15);
+  }
+
+  #[test]
+  fn test_from_utf8_two_bytes() {
+    for b0 in 0x80..0x100 {
+      for b1 in 0..0x100 {
+        check_at_offsets(&[b0 as u8, b1 as u8]);
+      }
+    }
+  }
+
+  #[test]
+  fn test_from_utf8_edge_sequences() {
+    for &b0 in &EDGE_BYTES[2..] {
+      for &b1 in &EDGE_BYTES {
+        for &b2 in &EDGE_BYTES {
+          check_at_offsets(&[b0, b1, b2]);
+          for &b3 in &EDGE_BYTES {
+            check_at_offsets(&[b0, b1, b2, b3]);
+          }
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_from_utf8_random() {
+    let mut rng = thread_rng();
+    let chars = ['a', '\u{e9}', '\u{20ac}', '\u{1f600}', '\u{7ff}', '\u{10000}'];
+    for _ in 0..2000 {
+      let len = rng.gen_range(0, 100);
+      let value: String = (0..len).map(|_| chars[rng.gen_range(0, chars.len())]).collect();
+      let mut bytes = value.into_bytes();
+      check(&bytes);
+      if !bytes.is_empty() {
+        let i = rng.gen_range(0, bytes.len());
+        bytes[i] = rng.gen();
+        check(&bytes);
+      }
+    }
+  }
+}